@@ -64,6 +64,7 @@ impl From<ExternalDataId> for DataId {
 
 pub const NAME_DELIMITER: char = ':';
 pub const NAME_BRACE: char = '`';
+pub const VERSION_DELIMITER: char = '@';
 pub const SYSTEM_NAMESPACE: &str = "_";
 pub const SYSTEM_PROVIDER: &str = "_";
 
@@ -79,6 +80,10 @@ pub const SYSTEM_PROVIDER: &str = "_";
 /// * `namespace:dataset` -> `NamedData { namespace: Some("namespace"), provider: None, name: "dataset" }`
 /// * `namespace:provider:dataset` -> `NamedData { namespace: Some("namespace"), provider: Some("provider"), name: "dataset" }`
 ///
+/// The name may carry a version suffix, separated by an `@`, that pins the data to a specific version.
+///
+/// * `dataset@3` -> `NamedData { namespace: None, provider: None, name: "dataset@3" }`
+///
 #[derive(Debug, Clone, Hash, Eq, PartialEq)]
 pub struct NamedData {
     pub namespace: Option<String>,
@@ -130,6 +135,14 @@ impl NamedData {
             name: name.into(),
         }
     }
+
+    /// Splits the optional version suffix (`name@version`) off the name.
+    pub fn name_and_version(&self) -> (&str, Option<&str>) {
+        match self.name.rsplit_once(VERSION_DELIMITER) {
+            Some((name, version)) => (name, Some(version)),
+            None => (&self.name, None),
+        }
+    }
 }
 
 impl std::fmt::Display for NamedData {
@@ -156,10 +169,11 @@ impl Serialize for NamedData {
     where
         S: serde::Serializer,
     {
-        let name = if self
-            .name
-            .contains(|c| c == NAME_DELIMITER || c == NAME_BRACE || is_invalid_name_char(c))
-        {
+        let name = if self.name.contains(|c| {
+            c == NAME_DELIMITER
+                || c == NAME_BRACE
+                || (c != VERSION_DELIMITER && is_invalid_name_char(c))
+        }) {
             brace_name(&self.name)
         } else {
             self.name.clone()
@@ -237,7 +251,7 @@ impl NamedDataDeserializeVisitor {
                     NAME_BRACE => {
                         mode = Mode::BraceStart { num_braces: 1 };
                     }
-                    c if !is_allowed_name_char(c) => {
+                    c if c != VERSION_DELIMITER && !is_allowed_name_char(c) => {
                         return Err(E::custom(format!(
                             "character '{c}' is not allowed in a dataset name"
                         )));
@@ -425,6 +439,30 @@ mod tests {
         assert_eq!(serde_json::to_value(&named_data).unwrap(), json);
     }
 
+    #[test]
+    fn test_ser_de_dataset_name_version() {
+        let json = serde_json::json!("foo:bar@3");
+
+        let named_data: NamedData = serde_json::from_value(json.clone()).unwrap();
+
+        assert_eq!(
+            named_data,
+            NamedData {
+                namespace: Some("foo".to_string()),
+                provider: None,
+                name: "bar@3".to_string()
+            }
+        );
+        assert_eq!(named_data.name_and_version(), ("bar", Some("3")));
+
+        assert_eq!(serde_json::to_value(&named_data).unwrap(), json);
+
+        assert_eq!(
+            NamedData::with_system_name("bar").name_and_version(),
+            ("bar", None)
+        );
+    }
+
     #[test]
     fn test_ser_de_dataset_name_system_namespace() {
         let json = serde_json::json!("_:bar");
@@ -84,6 +84,11 @@ impl SpatialReference {
         }
     }
 
+    /// Return `true` if the coordinates of this spatial reference are geographic, i.e., angles on an ellipsoid
+    pub fn is_geographic(self) -> Result<bool> {
+        Ok(SpatialRef::try_from(self)?.is_geographic())
+    }
+
     /// Return the area of use in EPSG:4326 projection
     pub fn area_of_use<A: AxisAlignedRectangle>(self) -> Result<A> {
         let proj_string = match self.proj_string() {
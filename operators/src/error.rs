@@ -431,6 +431,84 @@ pub enum Error {
         source: crate::processing::RgbOperatorError,
     },
 
+    #[snafu(context(false))]
+    #[snafu(display("Buffer error: {source}"))]
+    Buffer {
+        source: crate::processing::BufferError,
+    },
+
+    #[snafu(context(false))]
+    #[snafu(display("Overlay error: {source}"))]
+    Overlay {
+        source: crate::processing::OverlayError,
+    },
+
+    #[snafu(context(false))]
+    #[snafu(display("Polygonize error: {source}"))]
+    Polygonize {
+        source: crate::processing::PolygonizeError,
+    },
+
+    #[snafu(context(false))]
+    #[snafu(display("Contour error: {source}"))]
+    Contour {
+        source: crate::processing::ContourError,
+    },
+
+    #[snafu(context(false))]
+    #[snafu(display("Reclassify error: {source}"))]
+    Reclassify {
+        source: crate::processing::ReclassifyError,
+    },
+
+    #[snafu(context(false))]
+    #[snafu(display("Mosaic error: {source}"))]
+    Mosaic {
+        source: crate::processing::MosaicError,
+    },
+
+    #[snafu(context(false))]
+    #[snafu(display("Proximity error: {source}"))]
+    Proximity {
+        source: crate::processing::ProximityError,
+    },
+
+    #[snafu(context(false))]
+    #[snafu(display("Hydrology error: {source}"))]
+    Hydrology {
+        source: crate::processing::HydrologyError,
+    },
+
+    #[snafu(context(false))]
+    #[snafu(display("QualityMask error: {source}"))]
+    QualityMask {
+        source: crate::processing::QualityMaskError,
+    },
+
+    #[snafu(context(false))]
+    #[snafu(display("Illumination error: {source}"))]
+    Illumination {
+        source: crate::processing::IlluminationError,
+    },
+
+    #[snafu(context(false))]
+    #[snafu(display("Clustering error: {source}"))]
+    Clustering {
+        source: crate::processing::ClusteringError,
+    },
+
+    #[snafu(context(false))]
+    #[snafu(display("Trajectory error: {source}"))]
+    Trajectory {
+        source: crate::processing::TrajectoryError,
+    },
+
+    #[snafu(context(false))]
+    #[snafu(display("GroupBy error: {source}"))]
+    GroupBy {
+        source: crate::processing::GroupByError,
+    },
+
     #[snafu(context(false))]
     #[snafu(display("Cache can't produce the promissed result error: {source}"))]
     CacheCantProduceResult {
@@ -20,8 +20,8 @@ pub use self::pie_chart::{
     PieChartParams,
 };
 pub use self::statistics::{
-    InitializedStatistics, Statistics, StatisticsParams, StatisticsRasterQueryProcessor,
-    StatisticsVectorQueryProcessor,
+    InitializedStatistics, QuantileEstimationParams, QuantileSketchSpec, Statistics,
+    StatisticsParams, StatisticsRasterQueryProcessor, StatisticsVectorQueryProcessor,
 };
 pub use self::temporal_raster_mean_plot::{
     InitializedMeanRasterPixelValuesOverTime, MeanRasterPixelValuesOverTime,
@@ -9,6 +9,7 @@ use crate::error;
 use crate::error::Error;
 use crate::util::input::MultiRasterOrVectorOperator;
 use crate::util::number_statistics::NumberStatistics;
+use crate::util::statistics::KllSketch;
 use crate::util::Result;
 use async_trait::async_trait;
 use futures::stream::select_all;
@@ -19,8 +20,9 @@ use geoengine_datatypes::primitives::{
     PlotQueryRectangle, RasterQueryRectangle,
 };
 use geoengine_datatypes::raster::ConvertDataTypeParallel;
-use geoengine_datatypes::raster::{GridOrEmpty, GridSize};
+use geoengine_datatypes::raster::{GridOrEmpty, GridSize, RasterTile2D};
 use geoengine_datatypes::spatial_reference::SpatialReferenceOption;
+use ordered_float::NotNan;
 use serde::{Deserialize, Serialize};
 use snafu::ensure;
 use std::collections::HashMap;
@@ -31,6 +33,9 @@ pub const STATISTICS_OPERATOR_NAME: &str = "Statistics";
 ///
 /// Does currently not use a weighted computations, so it assumes equally weighted
 /// time steps in the sources.
+///
+/// Optionally, quantiles are estimated using a mergeable sketch. For rasters, a sketch is built
+/// for each tile and the tile sketches are merged afterwards.
 pub type Statistics = Operator<StatisticsParams, MultipleRasterOrSingleVectorSource>;
 
 impl OperatorName for Statistics {
@@ -44,6 +49,41 @@ pub struct StatisticsParams {
     /// Names of the (numeric) attributes to compute the statistics on.
     #[serde(default)]
     pub column_names: Vec<String>,
+    /// Estimate quantiles with a bounded rank error.
+    /// If `None`, no quantiles are computed.
+    #[serde(default)]
+    pub estimate_quantiles: Option<QuantileEstimationParams>,
+}
+
+/// The parameters for the approximate quantile computation of `Statistics`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuantileEstimationParams {
+    /// The quantiles to estimate, each within the interval [0,1].
+    pub quantiles: Vec<NotNan<f64>>,
+    /// The maximum normalized rank error of the estimates, within the interval (0,1).
+    #[serde(default = "QuantileEstimationParams::default_max_rank_error")]
+    pub max_rank_error: NotNan<f64>,
+}
+
+impl QuantileEstimationParams {
+    fn default_max_rank_error() -> NotNan<f64> {
+        NotNan::new(0.01).expect("0.01 is not NaN")
+    }
+
+    /// Validates the parameters and creates an empty sketch that satisfies the error bound.
+    fn empty_sketch(&self) -> Result<KllSketch> {
+        ensure!(
+            self.quantiles
+                .iter()
+                .all(|q| (0.0..=1.0).contains(&q.into_inner())),
+            error::InvalidOperatorSpec {
+                reason: "Quantiles must be in the interval [0,1].".to_string(),
+            }
+        );
+
+        KllSketch::with_max_rank_error(self.max_rank_error.into_inner()).map_err(Into::into)
+    }
 }
 
 #[typetag::serde]
@@ -57,6 +97,23 @@ impl PlotOperator for Statistics {
     ) -> Result<Box<dyn InitializedPlotOperator>> {
         let name = CanonicOperatorName::from(&self);
 
+        let quantiles = self
+            .params
+            .estimate_quantiles
+            .as_ref()
+            .map(|params| {
+                Ok::<_, Error>(QuantileSketchSpec {
+                    quantiles: params
+                        .quantiles
+                        .iter()
+                        .copied()
+                        .map(NotNan::into_inner)
+                        .collect(),
+                    empty_sketch: params.empty_sketch()?,
+                })
+            })
+            .transpose()?;
+
         match self.sources.source {
             MultiRasterOrVectorOperator::Raster(rasters) => {
                 ensure!( self.params.column_names.is_empty() || self.params.column_names.len() == rasters.len(),
@@ -117,6 +174,7 @@ impl PlotOperator for Statistics {
                             .and_then(|p| BoundingBox2D::new(p.lower_left(), p.upper_right()).ok()),
                     },
                     output_names,
+                    quantiles,
                     rasters,
                 );
 
@@ -166,6 +224,7 @@ impl PlotOperator for Statistics {
                         bbox: in_descriptor.bbox,
                     },
                     column_names,
+                    quantiles,
                     initialized_vector,
                 );
 
@@ -182,6 +241,7 @@ pub struct InitializedStatistics<Op> {
     name: CanonicOperatorName,
     result_descriptor: PlotResultDescriptor,
     column_names: Vec<String>,
+    quantiles: Option<QuantileSketchSpec>,
     source: Op,
 }
 
@@ -190,17 +250,32 @@ impl<Op> InitializedStatistics<Op> {
         name: CanonicOperatorName,
         result_descriptor: PlotResultDescriptor,
         column_names: Vec<String>,
+        quantiles: Option<QuantileSketchSpec>,
         source: Op,
     ) -> Self {
         Self {
             name,
             result_descriptor,
             column_names,
+            quantiles,
             source,
         }
     }
 }
 
+/// The quantiles to estimate together with an empty sketch that is cloned for every partial result.
+#[derive(Debug, Clone)]
+pub struct QuantileSketchSpec {
+    quantiles: Vec<f64>,
+    empty_sketch: KllSketch,
+}
+
+impl QuantileSketchSpec {
+    fn empty_sketch(&self) -> KllSketch {
+        self.empty_sketch.clone()
+    }
+}
+
 impl InitializedPlotOperator for InitializedStatistics<Box<dyn InitializedVectorOperator>> {
     fn result_descriptor(&self) -> &PlotResultDescriptor {
         &self.result_descriptor
@@ -211,6 +286,7 @@ impl InitializedPlotOperator for InitializedStatistics<Box<dyn InitializedVector
             StatisticsVectorQueryProcessor {
                 vector: self.source.query_processor()?,
                 column_names: self.column_names.clone(),
+                quantiles: self.quantiles.clone(),
             }
             .boxed(),
         ))
@@ -235,6 +311,7 @@ impl InitializedPlotOperator for InitializedStatistics<Vec<Box<dyn InitializedRa
                     .map(InitializedRasterOperator::query_processor)
                     .collect::<Result<Vec<_>>>()?,
                 column_names: self.column_names.clone(),
+                quantiles: self.quantiles.clone(),
             }
             .boxed(),
         ))
@@ -249,6 +326,7 @@ impl InitializedPlotOperator for InitializedStatistics<Vec<Box<dyn InitializedRa
 pub struct StatisticsVectorQueryProcessor {
     vector: TypedVectorQueryProcessor,
     column_names: Vec<String>,
+    quantiles: Option<QuantileSketchSpec>,
 }
 
 #[async_trait]
@@ -264,10 +342,20 @@ impl PlotQueryProcessor for StatisticsVectorQueryProcessor {
         query: PlotQueryRectangle,
         ctx: &'a dyn QueryContext,
     ) -> Result<Self::OutputFormat> {
-        let mut number_statistics: HashMap<String, NumberStatistics> = self
+        let mut number_statistics: HashMap<String, (NumberStatistics, Option<KllSketch>)> = self
             .column_names
             .iter()
-            .map(|column| (column.clone(), NumberStatistics::default()))
+            .map(|column| {
+                (
+                    column.clone(),
+                    (
+                        NumberStatistics::default(),
+                        self.quantiles
+                            .as_ref()
+                            .map(QuantileSketchSpec::empty_sketch),
+                    ),
+                )
+            })
             .collect();
 
         call_on_generic_vector_processor!(&self.vector, processor => {
@@ -276,12 +364,17 @@ impl PlotQueryProcessor for StatisticsVectorQueryProcessor {
             while let Some(collection) = query.next().await {
                 let collection = collection?;
 
-                for (column, stats) in &mut number_statistics {
+                for (column, (stats, sketch)) in &mut number_statistics {
                     match collection.data(column) {
                         Ok(data) => data.float_options_iter().for_each(
                             | value | {
                                 match value {
-                                    Some(v) => stats.add(v),
+                                    Some(v) => {
+                                        stats.add(v);
+                                        if let Some(sketch) = sketch {
+                                            sketch.update(v);
+                                        }
+                                    },
                                     None => stats.add_no_data()
                                 }
                             }
@@ -294,8 +387,12 @@ impl PlotQueryProcessor for StatisticsVectorQueryProcessor {
 
         let output: HashMap<String, StatisticsOutput> = number_statistics
             .iter()
-            .map(|(column, number_statistics)| {
-                (column.clone(), StatisticsOutput::from(number_statistics))
+            .map(|(column, (number_statistics, sketch))| {
+                (
+                    column.clone(),
+                    StatisticsOutput::from(number_statistics)
+                        .with_quantiles(self.quantiles.as_ref(), sketch.as_ref()),
+                )
             })
             .collect();
         serde_json::to_value(output).map_err(Into::into)
@@ -306,6 +403,7 @@ impl PlotQueryProcessor for StatisticsVectorQueryProcessor {
 pub struct StatisticsRasterQueryProcessor {
     rasters: Vec<TypedRasterQueryProcessor>,
     column_names: Vec<String>,
+    quantiles: Option<QuantileSketchSpec>,
 }
 
 #[async_trait]
@@ -325,23 +423,42 @@ impl PlotQueryProcessor for StatisticsRasterQueryProcessor {
         let q: RasterQueryRectangle =
             RasterQueryRectangle::from_qrect_and_bands(&query, BandSelection::first());
         for (i, raster_processor) in self.rasters.iter().enumerate() {
+            let empty_sketch = self
+                .quantiles
+                .as_ref()
+                .map(QuantileSketchSpec::empty_sketch);
             queries.push(
                 call_on_generic_raster_processor!(raster_processor, processor => {
                     processor.query(q.clone(), ctx).await?
-                             .and_then(move |tile| crate::util::spawn_blocking_with_thread_pool(ctx.thread_pool().clone(), move || (i, tile.convert_data_type_parallel()) ).map_err(Into::into))
+                             .and_then(move |tile| {
+                                 let empty_sketch = empty_sketch.clone();
+                                 crate::util::spawn_blocking_with_thread_pool(ctx.thread_pool().clone(), move || {
+                                     let tile: RasterTile2D<f64> = tile.convert_data_type_parallel();
+                                     let sketch = empty_sketch.map(|sketch| tile_sketch(sketch, &tile));
+                                     (i, tile, sketch)
+                                 }).map_err(Into::into)
+                             })
                              .boxed()
                 }),
             );
         }
 
         let number_statistics = vec![NumberStatistics::default(); self.rasters.len()];
+        let sketches = vec![
+            self.quantiles
+                .as_ref()
+                .map(QuantileSketchSpec::empty_sketch);
+            self.rasters.len()
+        ];
 
         select_all(queries)
             .try_fold(
-                number_statistics,
-                |number_statistics: Vec<NumberStatistics>, enumerated_raster_tile| async move {
+                (number_statistics, sketches),
+                |(number_statistics, sketches): (Vec<NumberStatistics>, Vec<Option<KllSketch>>),
+                 enumerated_raster_tile| async move {
                     let mut number_statistics = number_statistics;
-                    let (i, raster_tile) = enumerated_raster_tile;
+                    let mut sketches = sketches;
+                    let (i, raster_tile, partial_sketch) = enumerated_raster_tile;
                     match raster_tile.grid_array {
                         GridOrEmpty::Grid(g) => process_raster(
                             &mut number_statistics[i],
@@ -352,14 +469,27 @@ impl PlotQueryProcessor for StatisticsRasterQueryProcessor {
                         }
                     }
 
-                    Ok(number_statistics)
+                    if let (Some(sketch), Some(partial_sketch)) = (&mut sketches[i], partial_sketch)
+                    {
+                        sketch.merge(&partial_sketch);
+                    }
+
+                    Ok((number_statistics, sketches))
                 },
             )
-            .map(|number_statistics| {
-                let output: HashMap<String, StatisticsOutput> = number_statistics?
+            .map(|statistics| {
+                let (number_statistics, sketches) = statistics?;
+                let output: HashMap<String, StatisticsOutput> = number_statistics
                     .iter()
+                    .zip(&sketches)
                     .enumerate()
-                    .map(|(i, stat)| (self.column_names[i].clone(), StatisticsOutput::from(stat)))
+                    .map(|(i, (stat, sketch))| {
+                        (
+                            self.column_names[i].clone(),
+                            StatisticsOutput::from(stat)
+                                .with_quantiles(self.quantiles.as_ref(), sketch.as_ref()),
+                        )
+                    })
                     .collect();
                 serde_json::to_value(output).map_err(Into::into)
             })
@@ -380,8 +510,18 @@ where
     }
 }
 
+/// Builds a sketch of all valid values of a tile
+fn tile_sketch(mut sketch: KllSketch, tile: &RasterTile2D<f64>) -> KllSketch {
+    if let GridOrEmpty::Grid(g) = &tile.grid_array {
+        for value in g.masked_element_deref_iterator().flatten() {
+            sketch.update(value);
+        }
+    }
+    sketch
+}
+
 /// The statistics summary output type for each raster input/vector input column
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct StatisticsOutput {
     pub value_count: usize,
@@ -390,6 +530,34 @@ struct StatisticsOutput {
     pub max: f64,
     pub mean: f64,
     pub stddev: f64,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub quantiles: Vec<QuantileOutput>,
+}
+
+/// An estimated quantile. The `value` is `None` if there were no valid values.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct QuantileOutput {
+    pub quantile: f64,
+    pub value: Option<f64>,
+}
+
+impl StatisticsOutput {
+    fn with_quantiles(
+        mut self,
+        spec: Option<&QuantileSketchSpec>,
+        sketch: Option<&KllSketch>,
+    ) -> Self {
+        if let (Some(spec), Some(sketch)) = (spec, sketch) {
+            self.quantiles = spec
+                .quantiles
+                .iter()
+                .zip(sketch.quantile_estimates(&spec.quantiles))
+                .map(|(&quantile, value)| QuantileOutput { quantile, value })
+                .collect();
+        }
+        self
+    }
 }
 
 impl From<&NumberStatistics> for StatisticsOutput {
@@ -401,6 +569,7 @@ impl From<&NumberStatistics> for StatisticsOutput {
             max: number_statistics.max(),
             mean: number_statistics.mean(),
             stddev: number_statistics.std_dev(),
+            quantiles: Vec::new(),
         }
     }
 }
@@ -433,6 +602,7 @@ mod tests {
         let statistics = Statistics {
             params: StatisticsParams {
                 column_names: vec![],
+                estimate_quantiles: None,
             },
             sources: MultipleRasterOrSingleVectorSource {
                 source: Raster(vec![]),
@@ -464,6 +634,7 @@ mod tests {
         let statistics = Statistics {
             params: StatisticsParams {
                 column_names: vec![],
+                estimate_quantiles: None,
             },
             sources: vec![].into(),
         };
@@ -533,6 +704,7 @@ mod tests {
         let statistics = Statistics {
             params: StatisticsParams {
                 column_names: vec![],
+                estimate_quantiles: None,
             },
             sources: vec![raster_source].into(),
         };
@@ -644,6 +816,7 @@ mod tests {
         let statistics = Statistics {
             params: StatisticsParams {
                 column_names: vec![],
+                estimate_quantiles: None,
             },
             sources: raster_source.into(),
         };
@@ -763,6 +936,7 @@ mod tests {
         let statistics = Statistics {
             params: StatisticsParams {
                 column_names: vec!["A".to_string(), "B".to_string()],
+                estimate_quantiles: None,
             },
             sources: raster_source.into(),
         };
@@ -881,6 +1055,7 @@ mod tests {
         let statistics = Statistics {
             params: StatisticsParams {
                 column_names: vec!["A".to_string()],
+                estimate_quantiles: None,
             },
             sources: raster_source.into(),
         };
@@ -942,6 +1117,7 @@ mod tests {
         let statistics = Statistics {
             params: StatisticsParams {
                 column_names: vec![],
+                estimate_quantiles: None,
             },
             sources: vector_source.into(),
         };
@@ -1039,6 +1215,7 @@ mod tests {
         let statistics = Statistics {
             params: StatisticsParams {
                 column_names: vec!["foo".to_string()],
+                estimate_quantiles: None,
             },
             sources: vector_source.into(),
         };
@@ -1128,6 +1305,7 @@ mod tests {
         let statistics = Statistics {
             params: StatisticsParams {
                 column_names: vec!["foo".to_string(), "bar".to_string()],
+                estimate_quantiles: None,
             },
             sources: vector_source.into(),
         };
@@ -1179,4 +1357,126 @@ mod tests {
             .to_string()
         );
     }
+
+    #[tokio::test]
+    async fn single_raster_quantiles() {
+        let tile_size_in_pixels = [3, 2].into();
+        let tiling_specification = TilingSpecification {
+            origin_coordinate: [0.0, 0.0].into(),
+            tile_size_in_pixels,
+        };
+
+        let raster_source = MockRasterSource {
+            params: MockRasterSourceParams {
+                data: vec![
+                    RasterTile2D::new_with_tile_info(
+                        TimeInterval::default(),
+                        TileInformation {
+                            global_geo_transform: TestDefault::test_default(),
+                            global_tile_position: [0, 0].into(),
+                            tile_size_in_pixels,
+                        },
+                        0,
+                        Grid2D::new([3, 2].into(), vec![1, 2, 3, 4, 5, 6])
+                            .unwrap()
+                            .into(),
+                        CacheHint::default(),
+                    ),
+                    RasterTile2D::new_with_tile_info(
+                        TimeInterval::default(),
+                        TileInformation {
+                            global_geo_transform: TestDefault::test_default(),
+                            global_tile_position: [0, 1].into(),
+                            tile_size_in_pixels,
+                        },
+                        0,
+                        Grid2D::new([3, 2].into(), vec![7, 8, 9, 10, 11, 12])
+                            .unwrap()
+                            .into(),
+                        CacheHint::default(),
+                    ),
+                ],
+                result_descriptor: RasterResultDescriptor {
+                    data_type: RasterDataType::U8,
+                    spatial_reference: SpatialReference::epsg_4326().into(),
+                    time: None,
+                    bbox: None,
+                    resolution: None,
+                    bands: RasterBandDescriptors::new_single_band(),
+                },
+            },
+        }
+        .boxed();
+
+        let statistics = Statistics {
+            params: StatisticsParams {
+                column_names: vec![],
+                estimate_quantiles: Some(QuantileEstimationParams {
+                    quantiles: vec![
+                        NotNan::new(0.0).unwrap(),
+                        NotNan::new(0.5).unwrap(),
+                        NotNan::new(1.0).unwrap(),
+                    ],
+                    max_rank_error: NotNan::new(0.01).unwrap(),
+                }),
+            },
+            sources: vec![raster_source].into(),
+        };
+
+        let execution_context = MockExecutionContext::new_with_tiling_spec(tiling_specification);
+
+        let statistics = statistics
+            .boxed()
+            .initialize(WorkflowOperatorPath::initialize_root(), &execution_context)
+            .await
+            .unwrap();
+
+        let processor = statistics.query_processor().unwrap().json_plain().unwrap();
+
+        let result = processor
+            .plot_query(
+                PlotQueryRectangle {
+                    spatial_bounds: BoundingBox2D::new((0., -4.).into(), (6., 0.).into()).unwrap(),
+                    time_interval: TimeInterval::default(),
+                    spatial_resolution: SpatialResolution::one(),
+                    attributes: PlotSeriesSelection::all(),
+                },
+                &MockQueryContext::new(ChunkByteSize::MIN),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(
+            result["Raster-1"]["quantiles"],
+            json!([
+                { "quantile": 0.0, "value": 1.0 },
+                { "quantile": 0.5, "value": 6.0 },
+                { "quantile": 1.0, "value": 12.0 },
+            ])
+        );
+        assert_eq!(result["Raster-1"]["validCount"], json!(12));
+    }
+
+    #[tokio::test]
+    async fn invalid_quantiles() {
+        let statistics = Statistics {
+            params: StatisticsParams {
+                column_names: vec![],
+                estimate_quantiles: Some(QuantileEstimationParams {
+                    quantiles: vec![NotNan::new(1.5).unwrap()],
+                    max_rank_error: NotNan::new(0.01).unwrap(),
+                }),
+            },
+            sources: vec![].into(),
+        };
+
+        let execution_context = MockExecutionContext::test_default();
+
+        let result = statistics
+            .boxed()
+            .initialize(WorkflowOperatorPath::initialize_root(), &execution_context)
+            .await;
+
+        assert!(result.is_err());
+    }
 }
@@ -0,0 +1,410 @@
+use geo::{BooleanOps, BoundingRect, Coord, LineString, MapCoords, MultiPolygon, Polygon};
+use std::f64::consts::{FRAC_PI_2, PI};
+
+/// The mean earth radius in meters (IUGG)
+pub(crate) const EARTH_RADIUS: f64 = 6_371_008.8;
+
+/// Buffers a geometry by `distance`.
+///
+/// Points and lines are only buffered for positive distances.
+/// Polygons are shrunk for negative distances.
+/// `quadrant_segments` is the number of line segments that approximate a quarter circle.
+pub fn buffer_geometry(
+    geometry: &geo::Geometry<f64>,
+    distance: f64,
+    quadrant_segments: usize,
+) -> MultiPolygon<f64> {
+    match geometry {
+        geo::Geometry::Point(point) => buffer_points(&[point.0], distance, quadrant_segments),
+        geo::Geometry::MultiPoint(points) => {
+            let coords = points.iter().map(|p| p.0).collect::<Vec<_>>();
+            buffer_points(&coords, distance, quadrant_segments)
+        }
+        geo::Geometry::LineString(line_string) => buffer_line_strings(
+            std::slice::from_ref(line_string),
+            distance,
+            quadrant_segments,
+        ),
+        geo::Geometry::MultiLineString(line_strings) => {
+            buffer_line_strings(&line_strings.0, distance, quadrant_segments)
+        }
+        geo::Geometry::Polygon(polygon) => buffer_polygons(
+            &MultiPolygon::new(vec![polygon.clone()]),
+            distance,
+            quadrant_segments,
+        ),
+        geo::Geometry::MultiPolygon(polygons) => {
+            buffer_polygons(polygons, distance, quadrant_segments)
+        }
+        _ => MultiPolygon::new(vec![]),
+    }
+}
+
+/// Buffers a geometry by `distance` meters.
+///
+/// The coordinates of the geometry must be WGS84 longitudes and latitudes.
+/// The buffer is computed in an azimuthal equidistant projection centered on the geometry.
+pub fn buffer_geometry_in_meters(
+    geometry: &geo::Geometry<f64>,
+    distance: f64,
+    quadrant_segments: usize,
+) -> MultiPolygon<f64> {
+    let Some(bounds) = geometry.bounding_rect() else {
+        return MultiPolygon::new(vec![]);
+    };
+
+    let projection = AzimuthalEquidistant::new(bounds.center());
+
+    let projected = geometry.map_coords(|c| projection.forward(c));
+    let buffered = buffer_geometry(&projected, distance, quadrant_segments);
+
+    buffered.map_coords(|c| projection.inverse(c))
+}
+
+fn buffer_points(
+    points: &[Coord<f64>],
+    distance: f64,
+    quadrant_segments: usize,
+) -> MultiPolygon<f64> {
+    if distance <= 0. {
+        return MultiPolygon::new(vec![]);
+    }
+
+    cascaded_union(
+        points
+            .iter()
+            .map(|&center| MultiPolygon::new(vec![circle(center, distance, quadrant_segments)]))
+            .collect(),
+    )
+}
+
+fn buffer_line_strings(
+    line_strings: &[LineString<f64>],
+    distance: f64,
+    quadrant_segments: usize,
+) -> MultiPolygon<f64> {
+    if distance <= 0. {
+        return MultiPolygon::new(vec![]);
+    }
+
+    cascaded_union(
+        line_strings
+            .iter()
+            .flat_map(|line_string| line_string_capsules(line_string, distance, quadrant_segments))
+            .map(|capsule| MultiPolygon::new(vec![capsule]))
+            .collect(),
+    )
+}
+
+fn buffer_polygons(
+    polygons: &MultiPolygon<f64>,
+    distance: f64,
+    quadrant_segments: usize,
+) -> MultiPolygon<f64> {
+    if distance == 0. {
+        return polygons.clone();
+    }
+
+    let boundary = cascaded_union(
+        polygons
+            .iter()
+            .flat_map(|polygon| std::iter::once(polygon.exterior()).chain(polygon.interiors()))
+            .flat_map(|ring| line_string_capsules(ring, distance.abs(), quadrant_segments))
+            .map(|capsule| MultiPolygon::new(vec![capsule]))
+            .collect(),
+    );
+
+    if distance > 0. {
+        polygons.union(&boundary)
+    } else {
+        polygons.difference(&boundary)
+    }
+}
+
+/// Creates a polygon for each segment of the line string that contains all points
+/// within `distance` of the segment.
+fn line_string_capsules(
+    line_string: &LineString<f64>,
+    distance: f64,
+    quadrant_segments: usize,
+) -> Vec<Polygon<f64>> {
+    if line_string.0.len() == 1 {
+        return vec![circle(line_string.0[0], distance, quadrant_segments)];
+    }
+
+    line_string
+        .lines()
+        .map(|line| capsule(line.start, line.end, distance, quadrant_segments))
+        .collect()
+}
+
+fn circle(center: Coord<f64>, radius: f64, quadrant_segments: usize) -> Polygon<f64> {
+    let number_of_segments = 4 * quadrant_segments;
+    let step = 2. * PI / number_of_segments as f64;
+
+    let coords = (0..number_of_segments)
+        .map(|i| {
+            let angle = i as f64 * step;
+            Coord {
+                x: center.x + radius * angle.cos(),
+                y: center.y + radius * angle.sin(),
+            }
+        })
+        .collect::<Vec<_>>();
+
+    Polygon::new(LineString::new(coords), vec![])
+}
+
+/// Creates a stadium-shaped polygon around the segment `(start, end)`.
+fn capsule(
+    start: Coord<f64>,
+    end: Coord<f64>,
+    radius: f64,
+    quadrant_segments: usize,
+) -> Polygon<f64> {
+    let dx = end.x - start.x;
+    let dy = end.y - start.y;
+
+    if dx == 0. && dy == 0. {
+        return circle(start, radius, quadrant_segments);
+    }
+
+    // the angle of the normal vector that points to the left of the segment
+    let normal_angle = dy.atan2(dx) + FRAC_PI_2;
+    let half_circle_segments = 2 * quadrant_segments;
+    let step = PI / half_circle_segments as f64;
+
+    let arc = |center: Coord<f64>, start_angle: f64| {
+        (0..=half_circle_segments).map(move |i| {
+            let angle = start_angle + i as f64 * step;
+            Coord {
+                x: center.x + radius * angle.cos(),
+                y: center.y + radius * angle.sin(),
+            }
+        })
+    };
+
+    // counter-clockwise: half circle around the start point, then half circle around the end point
+    let coords = arc(start, normal_angle)
+        .chain(arc(end, normal_angle + PI))
+        .collect::<Vec<_>>();
+
+    Polygon::new(LineString::new(coords), vec![])
+}
+
+/// Unions the polygons pairwise to keep the intermediate results small.
+pub(crate) fn cascaded_union(mut polygons: Vec<MultiPolygon<f64>>) -> MultiPolygon<f64> {
+    while polygons.len() > 1 {
+        let mut iter = polygons.into_iter();
+        let mut merged = Vec::with_capacity(iter.len() / 2 + 1);
+
+        while let Some(a) = iter.next() {
+            match iter.next() {
+                Some(b) => merged.push(a.union(&b)),
+                None => merged.push(a),
+            }
+        }
+
+        polygons = merged;
+    }
+
+    polygons.pop().unwrap_or_else(|| MultiPolygon::new(vec![]))
+}
+
+/// A spherical azimuthal equidistant projection.
+///
+/// Distances from the center are preserved, so buffers around geometries close to the center
+/// can be computed in meters.
+#[derive(Debug, Clone, Copy)]
+pub struct AzimuthalEquidistant {
+    center_lon: f64,
+    sin_center_lat: f64,
+    cos_center_lat: f64,
+}
+
+impl AzimuthalEquidistant {
+    /// Creates a new projection that is centered on `center` (longitude/latitude in degrees)
+    pub fn new(center: Coord<f64>) -> Self {
+        let center_lat = center.y.to_radians();
+
+        Self {
+            center_lon: center.x.to_radians(),
+            sin_center_lat: center_lat.sin(),
+            cos_center_lat: center_lat.cos(),
+        }
+    }
+
+    /// Projects a longitude/latitude coordinate (in degrees) to meters
+    pub fn forward(&self, coord: Coord<f64>) -> Coord<f64> {
+        let lon = coord.x.to_radians();
+        let lat = coord.y.to_radians();
+
+        let delta_lon = lon - self.center_lon;
+        let (sin_lat, cos_lat) = lat.sin_cos();
+        let cos_delta_lon = delta_lon.cos();
+
+        let cos_c = (self.sin_center_lat * sin_lat + self.cos_center_lat * cos_lat * cos_delta_lon)
+            .clamp(-1., 1.);
+        let c = cos_c.acos();
+
+        let k = if c.abs() < f64::EPSILON {
+            1.
+        } else {
+            c / c.sin()
+        };
+
+        Coord {
+            x: EARTH_RADIUS * k * cos_lat * delta_lon.sin(),
+            y: EARTH_RADIUS
+                * k
+                * (self.cos_center_lat * sin_lat - self.sin_center_lat * cos_lat * cos_delta_lon),
+        }
+    }
+
+    /// Projects a coordinate in meters back to longitude/latitude (in degrees)
+    pub fn inverse(&self, coord: Coord<f64>) -> Coord<f64> {
+        let rho = coord.x.hypot(coord.y);
+
+        if rho < f64::EPSILON {
+            return Coord {
+                x: self.center_lon.to_degrees(),
+                y: self.sin_center_lat.atan2(self.cos_center_lat).to_degrees(),
+            };
+        }
+
+        let c = rho / EARTH_RADIUS;
+        let (sin_c, cos_c) = c.sin_cos();
+
+        let lat = (cos_c * self.sin_center_lat + coord.y * sin_c * self.cos_center_lat / rho)
+            .clamp(-1., 1.)
+            .asin();
+        let lon = self.center_lon
+            + (coord.x * sin_c)
+                .atan2(rho * self.cos_center_lat * cos_c - coord.y * self.sin_center_lat * sin_c);
+
+        Coord {
+            x: lon.to_degrees(),
+            y: lat.to_degrees(),
+        }
+    }
+}
+
+/// Computes the size of `meters` in degrees of longitude at the given latitude (in degrees).
+/// This is an upper bound for the size in degrees of latitude.
+pub fn meters_to_degrees_at_latitude(meters: f64, latitude: f64) -> f64 {
+    let meters_per_degree = EARTH_RADIUS.to_radians() * latitude.to_radians().cos();
+
+    if meters_per_degree <= f64::EPSILON {
+        return 360.;
+    }
+
+    (meters / meters_per_degree).min(360.)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use geo::{Area, Contains, HaversineDistance, Point};
+
+    #[test]
+    fn point_buffer_area() {
+        let buffer = buffer_geometry(&Point::new(0., 0.).into(), 2., 64);
+
+        assert_eq!(buffer.0.len(), 1);
+        assert!((buffer.unsigned_area() - PI * 4.).abs() < 0.01);
+    }
+
+    #[test]
+    fn overlapping_points_are_merged() {
+        let points = geo::MultiPoint::new(vec![Point::new(0., 0.), Point::new(1., 0.)]);
+
+        let buffer = buffer_geometry(&points.into(), 1., 8);
+
+        assert_eq!(buffer.0.len(), 1);
+        assert!(buffer.contains(&Point::new(0.5, 0.)));
+        assert!(!buffer.contains(&Point::new(0.5, 1.)));
+    }
+
+    #[test]
+    fn line_buffer() {
+        let line = LineString::from(vec![(0., 0.), (10., 0.), (10., 10.)]);
+
+        let buffer = buffer_geometry(&line.into(), 1., 8);
+
+        assert_eq!(buffer.0.len(), 1);
+        assert!(buffer.contains(&Point::new(5., 0.9)));
+        assert!(buffer.contains(&Point::new(10.9, 5.)));
+        assert!(!buffer.contains(&Point::new(5., 1.1)));
+        assert!(!buffer.contains(&Point::new(5., 5.)));
+
+        // two rectangles of 10x2 minus the overlap plus the round caps and the corner
+        let expected_area = 2. * 20. + PI;
+        assert!((buffer.unsigned_area() - expected_area).abs() < 0.2);
+    }
+
+    #[test]
+    fn polygon_buffer() {
+        let polygon = Polygon::new(
+            LineString::from(vec![(0., 0.), (10., 0.), (10., 10.), (0., 10.), (0., 0.)]),
+            vec![],
+        );
+
+        let grown = buffer_geometry(&polygon.clone().into(), 1., 16);
+        assert!((grown.unsigned_area() - (100. + 40. + PI)).abs() < 0.1);
+
+        let shrunk = buffer_geometry(&polygon.clone().into(), -1., 16);
+        assert!((shrunk.unsigned_area() - 64.).abs() < 0.01);
+
+        let vanished = buffer_geometry(&polygon.into(), -6., 16);
+        assert!(vanished.0.is_empty());
+    }
+
+    #[test]
+    fn non_positive_distances_for_points_and_lines() {
+        let point = buffer_geometry(&Point::new(0., 0.).into(), 0., 8);
+        assert!(point.0.is_empty());
+
+        let line = buffer_geometry(&LineString::from(vec![(0., 0.), (1., 1.)]).into(), -1., 8);
+        assert!(line.0.is_empty());
+    }
+
+    #[test]
+    fn azimuthal_equidistant_round_trip() {
+        let projection = AzimuthalEquidistant::new(Coord { x: 8.77, y: 50.81 });
+
+        for coord in [
+            Coord { x: 8.77, y: 50.81 },
+            Coord { x: 9.5, y: 51.2 },
+            Coord { x: -70., y: -33. },
+        ] {
+            let round_trip = projection.inverse(projection.forward(coord));
+
+            assert!((round_trip.x - coord.x).abs() < 1e-9);
+            assert!((round_trip.y - coord.y).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn azimuthal_equidistant_preserves_distances_from_center() {
+        let center = Coord { x: 8.77, y: 50.81 };
+        let other = Coord { x: 13.4, y: 52.52 };
+
+        let projection = AzimuthalEquidistant::new(center);
+        let projected = projection.forward(other);
+
+        let expected = Point::from(center).haversine_distance(&Point::from(other));
+
+        assert!((projected.x.hypot(projected.y) - expected).abs() < 1.);
+    }
+
+    #[test]
+    fn buffer_in_meters() {
+        let buffer = buffer_geometry_in_meters(&Point::new(8.77, 50.81).into(), 1000., 16);
+
+        let exterior = &buffer.0[0].exterior().0;
+        for coord in exterior {
+            let distance = Point::new(8.77, 50.81).haversine_distance(&Point::from(*coord));
+            assert!((distance - 1000.).abs() < 1.);
+        }
+    }
+}
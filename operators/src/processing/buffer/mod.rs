@@ -0,0 +1,737 @@
+pub(super) mod geometry;
+
+use self::geometry::{buffer_geometry, buffer_geometry_in_meters, meters_to_degrees_at_latitude};
+use crate::engine::{
+    CanonicOperatorName, ExecutionContext, InitializedSources, InitializedVectorOperator, Operator,
+    OperatorName, QueryContext, QueryProcessor, SingleVectorSource, TypedVectorQueryProcessor,
+    VectorOperator, VectorQueryProcessor, VectorResultDescriptor, WorkflowOperatorPath,
+};
+use crate::util::Result;
+use async_trait::async_trait;
+use futures::stream::BoxStream;
+use futures::{StreamExt, TryStreamExt};
+use geoengine_datatypes::collections::{
+    FeatureCollection, FeatureCollectionInfos, FeatureCollectionModifications,
+    GeoFeatureCollectionModifications, IntoGeometryIterator, MultiLineStringCollection,
+    MultiPointCollection, MultiPolygonCollection, VectorDataType,
+};
+use geoengine_datatypes::operations::reproject::{
+    CoordinateProjection, CoordinateProjector, Reproject,
+};
+use geoengine_datatypes::primitives::{
+    AsGeo, BoundingBox2D, ColumnSelection, Coordinate2D, Geometry, GeometryRef, MultiPolygon,
+    VectorQueryRectangle,
+};
+use geoengine_datatypes::spatial_reference::{SpatialReference, SpatialReferenceOption};
+use geoengine_datatypes::util::arrow::ArrowTyped;
+use serde::{Deserialize, Serialize};
+use snafu::{ensure, Snafu};
+
+/// The `Buffer` operator computes the area within a given distance around each feature.
+///
+/// Points, lines and polygons are buffered and the result is always a `MultiPolygonCollection`.
+/// The attributes of the input features are kept. Features whose buffer is empty, e.g., because
+/// of a non-positive distance for points and lines, are dropped.
+pub type Buffer = Operator<BufferParams, SingleVectorSource>;
+
+impl OperatorName for Buffer {
+    const TYPE_NAME: &'static str = "Buffer";
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BufferParams {
+    /// The buffer distance. Negative distances shrink polygons.
+    pub distance: BufferDistance,
+    /// The units of the buffer distance
+    pub units: BufferUnits,
+    /// The number of line segments that are used to approximate a quarter circle
+    #[serde(default = "BufferParams::default_quadrant_segments")]
+    pub quadrant_segments: usize,
+}
+
+impl BufferParams {
+    const MAX_QUADRANT_SEGMENTS: usize = 128;
+
+    fn default_quadrant_segments() -> usize {
+        8
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub enum BufferDistance {
+    /// The same distance for all features
+    Constant { value: f64 },
+    /// The distance is read from a numeric column. Features with missing values are dropped.
+    Column { column: String },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BufferUnits {
+    /// Meters on the earth's surface.
+    /// The buffer is computed in an azimuthal equidistant projection around each feature.
+    Meters,
+    /// The units of the spatial reference system of the input
+    CrsUnits,
+}
+
+#[typetag::serde]
+#[async_trait]
+impl VectorOperator for Buffer {
+    async fn _initialize(
+        self: Box<Self>,
+        path: WorkflowOperatorPath,
+        context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedVectorOperator>> {
+        ensure!(
+            (1..=BufferParams::MAX_QUADRANT_SEGMENTS).contains(&self.params.quadrant_segments),
+            error::InvalidQuadrantSegments {
+                max: BufferParams::MAX_QUADRANT_SEGMENTS
+            }
+        );
+
+        if let BufferDistance::Constant { value } = self.params.distance {
+            ensure!(value.is_finite(), error::InvalidDistance);
+        }
+
+        let name = CanonicOperatorName::from(&self);
+
+        let sources = self.sources.initialize_sources(path, context).await?;
+        let source = sources.vector;
+
+        let in_desc = source.result_descriptor();
+
+        ensure!(
+            in_desc.data_type != VectorDataType::Data,
+            error::InvalidGeometryType
+        );
+
+        if let BufferDistance::Column { column } = &self.params.distance {
+            match in_desc.column_data_type(column) {
+                Some(data_type) if data_type.is_numeric() => {}
+                Some(_) => {
+                    return Err(BufferError::ColumnIsNotNumeric {
+                        column: column.clone(),
+                    }
+                    .into())
+                }
+                None => {
+                    return Err(BufferError::ColumnDoesNotExist {
+                        column: column.clone(),
+                    }
+                    .into())
+                }
+            }
+        }
+
+        if self.params.units == BufferUnits::Meters {
+            ensure!(
+                in_desc.spatial_reference.is_spatial_ref(),
+                error::MetersRequireSpatialReference
+            );
+        }
+
+        let spec = BufferSpec {
+            distance: self.params.distance,
+            units: self.params.units,
+            quadrant_segments: self.params.quadrant_segments,
+            spatial_reference: in_desc.spatial_reference,
+        };
+
+        let result_descriptor = VectorResultDescriptor {
+            data_type: VectorDataType::MultiPolygon,
+            spatial_reference: in_desc.spatial_reference,
+            columns: in_desc.columns.clone(),
+            time: in_desc.time,
+            bbox: in_desc.bbox.and_then(|bbox| spec.enlarge_bbox(bbox)),
+        };
+
+        Ok(InitializedBuffer {
+            name,
+            result_descriptor,
+            source,
+            spec,
+        }
+        .boxed())
+    }
+
+    span_fn!(Buffer);
+}
+
+pub struct InitializedBuffer {
+    name: CanonicOperatorName,
+    result_descriptor: VectorResultDescriptor,
+    source: Box<dyn InitializedVectorOperator>,
+    spec: BufferSpec,
+}
+
+impl InitializedVectorOperator for InitializedBuffer {
+    fn result_descriptor(&self) -> &VectorResultDescriptor {
+        &self.result_descriptor
+    }
+
+    fn query_processor(&self) -> Result<TypedVectorQueryProcessor> {
+        let processor = match self.source.query_processor()? {
+            TypedVectorQueryProcessor::Data(_) => {
+                return Err(BufferError::InvalidGeometryType.into())
+            }
+            TypedVectorQueryProcessor::MultiPoint(source) => {
+                BufferProcessor::new(source, self.result_descriptor.clone(), self.spec.clone())
+                    .boxed()
+            }
+            TypedVectorQueryProcessor::MultiLineString(source) => {
+                BufferProcessor::new(source, self.result_descriptor.clone(), self.spec.clone())
+                    .boxed()
+            }
+            TypedVectorQueryProcessor::MultiPolygon(source) => {
+                BufferProcessor::new(source, self.result_descriptor.clone(), self.spec.clone())
+                    .boxed()
+            }
+        };
+
+        Ok(TypedVectorQueryProcessor::MultiPolygon(processor))
+    }
+
+    fn canonic_name(&self) -> CanonicOperatorName {
+        self.name.clone()
+    }
+}
+
+#[derive(Debug, Clone)]
+struct BufferSpec {
+    distance: BufferDistance,
+    units: BufferUnits,
+    quadrant_segments: usize,
+    spatial_reference: SpatialReferenceOption,
+}
+
+impl BufferSpec {
+    /// Enlarges the `bbox` by the buffer distance.
+    /// Returns `None` if the distance depends on the features.
+    ///
+    /// For meters, a projected spatial reference is assumed to have metric units.
+    fn enlarge_bbox(&self, bbox: BoundingBox2D) -> Option<BoundingBox2D> {
+        let BufferDistance::Constant { value } = self.distance else {
+            return None;
+        };
+
+        if value <= 0. {
+            return Some(bbox);
+        }
+
+        let margin = match self.units {
+            BufferUnits::Meters
+                if self.spatial_reference == SpatialReference::epsg_4326().into() =>
+            {
+                let max_abs_latitude =
+                    f64::max(bbox.lower_left().y.abs(), bbox.upper_right().y.abs()).min(90.);
+                meters_to_degrees_at_latitude(value, max_abs_latitude)
+            }
+            BufferUnits::Meters | BufferUnits::CrsUnits => value,
+        };
+
+        Some(BoundingBox2D::new_unchecked(
+            Coordinate2D::new(bbox.lower_left().x - margin, bbox.lower_left().y - margin),
+            Coordinate2D::new(bbox.upper_right().x + margin, bbox.upper_right().y + margin),
+        ))
+    }
+
+    fn distances<G>(&self, collection: &FeatureCollection<G>) -> Result<Vec<Option<f64>>>
+    where
+        G: Geometry + ArrowTyped,
+    {
+        Ok(match &self.distance {
+            BufferDistance::Constant { value } => vec![Some(*value); collection.len()],
+            BufferDistance::Column { column } => {
+                collection.data(column)?.float_options_iter().collect()
+            }
+        })
+    }
+
+    fn buffer(&self, geometry: &geo::Geometry<f64>, distance: f64) -> Option<MultiPolygon> {
+        let buffered = match self.units {
+            BufferUnits::Meters => {
+                buffer_geometry_in_meters(geometry, distance, self.quadrant_segments)
+            }
+            BufferUnits::CrsUnits => buffer_geometry(geometry, distance, self.quadrant_segments),
+        };
+
+        if buffered.0.is_empty() {
+            None
+        } else {
+            Some(buffered.into())
+        }
+    }
+
+    /// Buffers all features of the `collection` and drops the ones that do not intersect `bounds`
+    fn buffer_collection<G>(
+        &self,
+        collection: &FeatureCollection<G>,
+        bounds: BoundingBox2D,
+    ) -> Result<MultiPolygonCollection>
+    where
+        G: Geometry + ArrowTyped,
+        FeatureCollection<G>:
+            GeoGeometries + Reproject<CoordinateProjector, Out = FeatureCollection<G>>,
+    {
+        // buffering in meters is done in WGS84
+        let reprojection = match (self.units, self.spatial_reference) {
+            (BufferUnits::Meters, SpatialReferenceOption::SpatialReference(srs))
+                if srs != SpatialReference::epsg_4326() =>
+            {
+                Some(srs)
+            }
+            _ => None,
+        };
+
+        let reprojected;
+        let collection = if let Some(srs) = reprojection {
+            let projector =
+                CoordinateProjector::from_known_srs(srs, SpatialReference::epsg_4326())?;
+            reprojected = collection.reproject(&projector)?;
+            &reprojected
+        } else {
+            collection
+        };
+
+        let buffered = collection
+            .geo_geometries()
+            .iter()
+            .zip(self.distances(collection)?)
+            .map(|(geometry, distance)| {
+                distance
+                    .filter(|d| d.is_finite())
+                    .and_then(|distance| self.buffer(geometry, distance))
+            })
+            .collect::<Vec<_>>();
+
+        let mask = buffered.iter().map(Option::is_some).collect::<Vec<_>>();
+        let geometries = buffered.into_iter().flatten().collect::<Vec<_>>();
+
+        let mut output: MultiPolygonCollection =
+            collection.filter(mask)?.replace_geometries(geometries)?;
+
+        if let Some(srs) = reprojection {
+            let projector =
+                CoordinateProjector::from_known_srs(SpatialReference::epsg_4326(), srs)?;
+            output = output.reproject(&projector)?;
+        }
+
+        let in_bounds = output
+            .geometries()
+            .map(|geometry| {
+                geometry
+                    .bbox()
+                    .map_or(false, |bbox| bbox.intersects_bbox(&bounds))
+            })
+            .collect::<Vec<_>>();
+
+        Ok(output.filter(in_bounds)?)
+    }
+}
+
+/// Access to the geometries of a collection as [`geo`] types
+trait GeoGeometries {
+    fn geo_geometries(&self) -> Vec<geo::Geometry<f64>>;
+}
+
+impl GeoGeometries for MultiPointCollection {
+    fn geo_geometries(&self) -> Vec<geo::Geometry<f64>> {
+        self.geometries().map(|g| g.as_geo().into()).collect()
+    }
+}
+
+impl GeoGeometries for MultiLineStringCollection {
+    fn geo_geometries(&self) -> Vec<geo::Geometry<f64>> {
+        self.geometries().map(|g| g.as_geo().into()).collect()
+    }
+}
+
+impl GeoGeometries for MultiPolygonCollection {
+    fn geo_geometries(&self) -> Vec<geo::Geometry<f64>> {
+        self.geometries().map(|g| g.as_geo().into()).collect()
+    }
+}
+
+struct BufferProcessor<G>
+where
+    G: Geometry,
+{
+    source: Box<dyn VectorQueryProcessor<VectorType = FeatureCollection<G>>>,
+    result_descriptor: VectorResultDescriptor,
+    spec: BufferSpec,
+}
+
+impl<G> BufferProcessor<G>
+where
+    G: Geometry,
+{
+    fn new(
+        source: Box<dyn VectorQueryProcessor<VectorType = FeatureCollection<G>>>,
+        result_descriptor: VectorResultDescriptor,
+        spec: BufferSpec,
+    ) -> Self {
+        Self {
+            source,
+            result_descriptor,
+            spec,
+        }
+    }
+}
+
+#[async_trait]
+impl<G> QueryProcessor for BufferProcessor<G>
+where
+    G: Geometry + ArrowTyped + 'static,
+    FeatureCollection<G>:
+        GeoGeometries + Reproject<CoordinateProjector, Out = FeatureCollection<G>> + 'static,
+{
+    type Output = MultiPolygonCollection;
+    type SpatialBounds = BoundingBox2D;
+    type Selection = ColumnSelection;
+    type ResultDescription = VectorResultDescriptor;
+
+    async fn _query<'a>(
+        &'a self,
+        query: VectorQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<BoxStream<'a, Result<Self::Output>>> {
+        let bounds = query.spatial_bounds;
+
+        // features outside of the query rectangle may intersect it after buffering
+        let mut source_query = query;
+        if let Some(enlarged_bounds) = self.spec.enlarge_bbox(bounds) {
+            source_query.spatial_bounds = enlarged_bounds;
+        }
+
+        let stream = self.source.query(source_query, ctx).await?;
+
+        let stream = stream.and_then(move |collection| {
+            let spec = self.spec.clone();
+            async move {
+                crate::util::spawn_blocking_with_thread_pool(ctx.thread_pool().clone(), move || {
+                    spec.buffer_collection(&collection, bounds)
+                })
+                .await?
+            }
+        });
+
+        Ok(stream.boxed())
+    }
+
+    fn result_descriptor(&self) -> &VectorResultDescriptor {
+        &self.result_descriptor
+    }
+}
+
+#[derive(Debug, Snafu)]
+#[snafu(visibility(pub(crate)), context(suffix(false)), module(error))]
+pub enum BufferError {
+    #[snafu(display("The buffer distance must be a finite number"))]
+    InvalidDistance,
+    #[snafu(display("`quadrantSegments` must be between 1 and {max}"))]
+    InvalidQuadrantSegments { max: usize },
+    #[snafu(display(
+        "Geometry must be of type `MultiPoint`, `MultiLineString` or `MultiPolygon`"
+    ))]
+    InvalidGeometryType,
+    #[snafu(display("Column `{column}` does not exist"))]
+    ColumnDoesNotExist { column: String },
+    #[snafu(display("Column `{column}` is not numeric"))]
+    ColumnIsNotNumeric { column: String },
+    #[snafu(display("Buffering in meters requires a spatial reference"))]
+    MetersRequireSpatialReference,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{MockExecutionContext, MockQueryContext};
+    use crate::mock::MockFeatureCollectionSource;
+    use geoengine_datatypes::collections::DataCollection;
+    use geoengine_datatypes::primitives::{
+        CacheHint, FeatureData, MultiPoint, NoGeometry, SpatialResolution, TimeInterval,
+    };
+    use geoengine_datatypes::util::test::TestDefault;
+
+    #[test]
+    fn test_ser_de() {
+        let operator = Buffer {
+            params: BufferParams {
+                distance: BufferDistance::Column {
+                    column: "radius".to_string(),
+                },
+                units: BufferUnits::Meters,
+                quadrant_segments: 8,
+            },
+            sources: MockFeatureCollectionSource::<MultiPoint>::multiple(vec![])
+                .boxed()
+                .into(),
+        }
+        .boxed();
+
+        let serialized = serde_json::to_value(&operator).unwrap();
+
+        assert_eq!(
+            serialized,
+            serde_json::json!({
+                "type": "Buffer",
+                "params": {
+                    "distance": {
+                        "type": "column",
+                        "column": "radius",
+                    },
+                    "units": "meters",
+                    "quadrantSegments": 8,
+                },
+                "sources": {
+                    "vector": {
+                        "type": "MockFeatureCollectionSourceMultiPoint",
+                        "params": {
+                            "collections": [],
+                            "spatialReference": "EPSG:4326",
+                            "measurements": null,
+                        }
+                    }
+                },
+            })
+        );
+
+        let _operator: Box<dyn VectorOperator> = serde_json::from_value(serialized).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_errors() {
+        let points = MultiPointCollection::from_data(
+            MultiPoint::many(vec![(0.0, 0.0)]).unwrap(),
+            vec![TimeInterval::default()],
+            [("name".to_string(), FeatureData::Text(vec!["a".to_string()]))]
+                .into_iter()
+                .collect(),
+            CacheHint::default(),
+        )
+        .unwrap();
+
+        let initialize = |params: BufferParams, source: Box<dyn VectorOperator>| async move {
+            Buffer {
+                params,
+                sources: source.into(),
+            }
+            .boxed()
+            .initialize(
+                WorkflowOperatorPath::initialize_root(),
+                &MockExecutionContext::test_default(),
+            )
+            .await
+        };
+
+        // infinite distance
+        assert!(initialize(
+            BufferParams {
+                distance: BufferDistance::Constant {
+                    value: f64::INFINITY
+                },
+                units: BufferUnits::CrsUnits,
+                quadrant_segments: 8,
+            },
+            MockFeatureCollectionSource::single(points.clone()).boxed(),
+        )
+        .await
+        .is_err());
+
+        // no segments
+        assert!(initialize(
+            BufferParams {
+                distance: BufferDistance::Constant { value: 1. },
+                units: BufferUnits::CrsUnits,
+                quadrant_segments: 0,
+            },
+            MockFeatureCollectionSource::single(points.clone()).boxed(),
+        )
+        .await
+        .is_err());
+
+        // text column
+        assert!(initialize(
+            BufferParams {
+                distance: BufferDistance::Column {
+                    column: "name".to_string()
+                },
+                units: BufferUnits::CrsUnits,
+                quadrant_segments: 8,
+            },
+            MockFeatureCollectionSource::single(points.clone()).boxed(),
+        )
+        .await
+        .is_err());
+
+        // missing column
+        assert!(initialize(
+            BufferParams {
+                distance: BufferDistance::Column {
+                    column: "foo".to_string()
+                },
+                units: BufferUnits::CrsUnits,
+                quadrant_segments: 8,
+            },
+            MockFeatureCollectionSource::single(points).boxed(),
+        )
+        .await
+        .is_err());
+
+        // no geometries
+        assert!(initialize(
+            BufferParams {
+                distance: BufferDistance::Constant { value: 1. },
+                units: BufferUnits::CrsUnits,
+                quadrant_segments: 8,
+            },
+            MockFeatureCollectionSource::<NoGeometry>::single(DataCollection::empty()).boxed(),
+        )
+        .await
+        .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_point_buffer_with_column_distance() {
+        let points = MultiPointCollection::from_data(
+            MultiPoint::many(vec![(0.0, 0.0), (10.0, 0.0), (20.0, 0.0)]).unwrap(),
+            vec![TimeInterval::default(); 3],
+            [
+                (
+                    "radius".to_string(),
+                    FeatureData::NullableFloat(vec![Some(1.), None, Some(2.)]),
+                ),
+                (
+                    "name".to_string(),
+                    FeatureData::Text(vec!["a".to_string(), "b".to_string(), "c".to_string()]),
+                ),
+            ]
+            .into_iter()
+            .collect(),
+            CacheHint::default(),
+        )
+        .unwrap();
+
+        let buffer = Buffer {
+            params: BufferParams {
+                distance: BufferDistance::Column {
+                    column: "radius".to_string(),
+                },
+                units: BufferUnits::CrsUnits,
+                quadrant_segments: 1,
+            },
+            sources: MockFeatureCollectionSource::single(points).boxed().into(),
+        }
+        .boxed()
+        .initialize(
+            WorkflowOperatorPath::initialize_root(),
+            &MockExecutionContext::test_default(),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            buffer.result_descriptor().data_type,
+            VectorDataType::MultiPolygon
+        );
+
+        let processor = buffer.query_processor().unwrap().multi_polygon().unwrap();
+
+        let query_rectangle = VectorQueryRectangle {
+            spatial_bounds: BoundingBox2D::new((-5., -5.).into(), (25., 5.).into()).unwrap(),
+            time_interval: TimeInterval::default(),
+            spatial_resolution: SpatialResolution::one(),
+            attributes: ColumnSelection::all(),
+        };
+
+        let collections: Vec<MultiPolygonCollection> = processor
+            .query(query_rectangle, &MockQueryContext::test_default())
+            .await
+            .unwrap()
+            .map(Result::unwrap)
+            .collect()
+            .await;
+
+        assert_eq!(collections.len(), 1);
+        assert_eq!(collections[0].len(), 2);
+        assert_eq!(
+            collections[0]
+                .data("name")
+                .unwrap()
+                .strings_iter()
+                .collect::<Vec<_>>(),
+            vec!["a".to_string(), "c".to_string()]
+        );
+
+        // with one segment per quadrant, the buffers are squares rotated by 45 degrees
+        let expected = [
+            BoundingBox2D::new((-1., -1.).into(), (1., 1.).into()).unwrap(),
+            BoundingBox2D::new((18., -2.).into(), (22., 2.).into()).unwrap(),
+        ];
+
+        for (actual, expected) in collections[0].geometries().zip(expected) {
+            let actual = actual.bbox().unwrap();
+
+            assert!((actual.lower_left().x - expected.lower_left().x).abs() < 1e-9);
+            assert!((actual.lower_left().y - expected.lower_left().y).abs() < 1e-9);
+            assert!((actual.upper_right().x - expected.upper_right().x).abs() < 1e-9);
+            assert!((actual.upper_right().y - expected.upper_right().y).abs() < 1e-9);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_query_is_enlarged() {
+        let points = MultiPointCollection::from_data(
+            MultiPoint::many(vec![(0.0, 0.0), (3.0, 0.0)]).unwrap(),
+            vec![TimeInterval::default(); 2],
+            Default::default(),
+            CacheHint::default(),
+        )
+        .unwrap();
+
+        let buffer = Buffer {
+            params: BufferParams {
+                distance: BufferDistance::Constant { value: 2. },
+                units: BufferUnits::CrsUnits,
+                quadrant_segments: 4,
+            },
+            sources: MockFeatureCollectionSource::single(points).boxed().into(),
+        }
+        .boxed()
+        .initialize(
+            WorkflowOperatorPath::initialize_root(),
+            &MockExecutionContext::test_default(),
+        )
+        .await
+        .unwrap();
+
+        let processor = buffer.query_processor().unwrap().multi_polygon().unwrap();
+
+        // the second point lies outside, but its buffer intersects the query rectangle
+        let query_rectangle = VectorQueryRectangle {
+            spatial_bounds: BoundingBox2D::new((-1., -1.).into(), (1.5, 1.).into()).unwrap(),
+            time_interval: TimeInterval::default(),
+            spatial_resolution: SpatialResolution::one(),
+            attributes: ColumnSelection::all(),
+        };
+
+        let collections: Vec<MultiPolygonCollection> = processor
+            .query(query_rectangle, &MockQueryContext::test_default())
+            .await
+            .unwrap()
+            .map(Result::unwrap)
+            .collect()
+            .await;
+
+        assert_eq!(
+            collections
+                .iter()
+                .map(FeatureCollectionInfos::len)
+                .sum::<usize>(),
+            2
+        );
+    }
+}
@@ -0,0 +1,337 @@
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DbscanLabel {
+    Unvisited,
+    Noise,
+    Cluster(u32),
+}
+
+/// A uniform grid over points with a cell size of the search distance
+struct GridIndex {
+    epsilon: f64,
+    cells: HashMap<[i64; 3], Vec<usize>>,
+}
+
+impl GridIndex {
+    fn new(points: &[[f64; 3]], epsilon: f64) -> Self {
+        let mut cells = HashMap::<[i64; 3], Vec<usize>>::new();
+        for (index, point) in points.iter().enumerate() {
+            cells
+                .entry(Self::cell(point, epsilon))
+                .or_default()
+                .push(index);
+        }
+
+        Self { epsilon, cells }
+    }
+
+    fn cell(point: &[f64; 3], epsilon: f64) -> [i64; 3] {
+        point.map(|value| (value / epsilon).floor() as i64)
+    }
+
+    /// All points within the search distance of the point, including the point itself
+    fn neighbors(&self, points: &[[f64; 3]], index: usize) -> Vec<usize> {
+        let point = &points[index];
+        let [x, y, z] = Self::cell(point, self.epsilon);
+        let max_squared_distance = self.epsilon * self.epsilon;
+
+        let mut neighbors = Vec::new();
+        for dx in -1..=1 {
+            for dy in -1..=1 {
+                for dz in -1..=1 {
+                    let Some(cell) = self.cells.get(&[x + dx, y + dy, z + dz]) else {
+                        continue;
+                    };
+
+                    neighbors.extend(cell.iter().copied().filter(|&other| {
+                        squared_distance(point, &points[other]) <= max_squared_distance
+                    }));
+                }
+            }
+        }
+
+        neighbors
+    }
+}
+
+/// Clusters points with DBSCAN.
+///
+/// A point is a core point if at least `min_points` points, including itself, are within the
+/// distance `epsilon`.
+/// Returns the cluster id of each point or `None` for noise.
+pub fn dbscan(points: &[[f64; 3]], epsilon: f64, min_points: usize) -> Vec<Option<u32>> {
+    let index = GridIndex::new(points, epsilon);
+
+    let mut labels = vec![DbscanLabel::Unvisited; points.len()];
+    let mut next_cluster = 0;
+
+    for point in 0..points.len() {
+        if labels[point] != DbscanLabel::Unvisited {
+            continue;
+        }
+
+        let neighbors = index.neighbors(points, point);
+        if neighbors.len() < min_points {
+            labels[point] = DbscanLabel::Noise;
+            continue;
+        }
+
+        let cluster = DbscanLabel::Cluster(next_cluster);
+        next_cluster += 1;
+        labels[point] = cluster;
+
+        let mut queue = neighbors;
+        while let Some(neighbor) = queue.pop() {
+            match labels[neighbor] {
+                // noise that is reachable from a core point is a border point
+                DbscanLabel::Noise => labels[neighbor] = cluster,
+                DbscanLabel::Unvisited => {
+                    labels[neighbor] = cluster;
+
+                    let neighbors = index.neighbors(points, neighbor);
+                    if neighbors.len() >= min_points {
+                        queue.extend(neighbors);
+                    }
+                }
+                DbscanLabel::Cluster(_) => {}
+            }
+        }
+    }
+
+    labels
+        .into_iter()
+        .map(|label| match label {
+            DbscanLabel::Cluster(cluster) => Some(cluster),
+            DbscanLabel::Unvisited | DbscanLabel::Noise => None,
+        })
+        .collect()
+}
+
+fn squared_distance(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b).map(|(a, b)| (a - b) * (a - b)).sum()
+}
+
+/// A k-means model with the centroids in lexicographic order
+#[derive(Debug, Clone, PartialEq)]
+pub struct KMeans {
+    centroids: Vec<Vec<f64>>,
+}
+
+impl KMeans {
+    /// Trains the model with Lloyd's algorithm.
+    ///
+    /// The initial centroids are the sample closest to the mean and then, one after the other,
+    /// the sample farthest from all previous centroids.
+    /// This makes the result deterministic for the same samples.
+    /// There are fewer than `k` centroids if there are fewer distinct samples.
+    pub fn train(samples: &[Vec<f64>], k: usize, max_iterations: usize) -> Option<Self> {
+        let dimensions = samples.first()?.len();
+
+        let center = mean(samples.iter(), dimensions)?;
+        let mut centroids = vec![nearest(samples, &center)?.clone()];
+
+        while centroids.len() < k {
+            let (farthest, distance) = samples
+                .iter()
+                .map(|sample| (sample, Self::nearest_distance(&centroids, sample)))
+                .max_by(|(_, a), (_, b)| a.total_cmp(b))?;
+
+            if distance <= 0. {
+                break;
+            }
+
+            centroids.push(farthest.clone());
+        }
+
+        let mut assignments = vec![usize::MAX; samples.len()];
+        for _ in 0..max_iterations {
+            let mut changed = false;
+            for (assignment, sample) in assignments.iter_mut().zip(samples) {
+                let nearest = Self::nearest_centroid(&centroids, sample);
+                changed |= *assignment != nearest;
+                *assignment = nearest;
+            }
+
+            if !changed {
+                break;
+            }
+
+            for (index, centroid) in centroids.iter_mut().enumerate() {
+                let members = samples
+                    .iter()
+                    .zip(&assignments)
+                    .filter(|(_, assignment)| **assignment == index)
+                    .map(|(sample, _)| sample);
+
+                // empty clusters keep their previous centroid
+                if let Some(mean) = mean(members, dimensions) {
+                    *centroid = mean;
+                }
+            }
+        }
+
+        centroids.sort_by(|a, b| {
+            a.iter()
+                .zip(b)
+                .map(|(a, b)| a.total_cmp(b))
+                .find(|ordering| ordering.is_ne())
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+
+        Some(Self { centroids })
+    }
+
+    /// The index of the centroid that is nearest to the value
+    pub fn predict(&self, value: &[f64]) -> usize {
+        Self::nearest_centroid(&self.centroids, value)
+    }
+
+    fn nearest_centroid(centroids: &[Vec<f64>], value: &[f64]) -> usize {
+        centroids
+            .iter()
+            .map(|centroid| squared_distance(centroid, value))
+            .enumerate()
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map_or(0, |(index, _)| index)
+    }
+
+    fn nearest_distance(centroids: &[Vec<f64>], value: &[f64]) -> f64 {
+        centroids
+            .iter()
+            .map(|centroid| squared_distance(centroid, value))
+            .fold(f64::INFINITY, f64::min)
+    }
+}
+
+fn mean<'a>(samples: impl Iterator<Item = &'a Vec<f64>>, dimensions: usize) -> Option<Vec<f64>> {
+    let mut sum = vec![0.; dimensions];
+    let mut count = 0_usize;
+    for sample in samples {
+        for (sum, value) in sum.iter_mut().zip(sample) {
+            *sum += value;
+        }
+        count += 1;
+    }
+
+    if count == 0 {
+        return None;
+    }
+
+    Some(sum.into_iter().map(|sum| sum / count as f64).collect())
+}
+
+fn nearest<'a>(samples: &'a [Vec<f64>], value: &[f64]) -> Option<&'a Vec<f64>> {
+    samples
+        .iter()
+        .min_by(|a, b| squared_distance(a, value).total_cmp(&squared_distance(b, value)))
+}
+
+/// Scales each dimension of the samples to zero mean and unit variance.
+/// Dimensions without variance are only centered.
+pub fn standardize(samples: &mut [Vec<f64>]) {
+    let Some(dimensions) = samples.first().map(Vec::len) else {
+        return;
+    };
+    let Some(mean) = mean(samples.iter(), dimensions) else {
+        return;
+    };
+
+    let mut variance = vec![0.; dimensions];
+    for sample in samples.iter() {
+        for ((variance, value), mean) in variance.iter_mut().zip(sample).zip(&mean) {
+            *variance += (value - mean) * (value - mean);
+        }
+    }
+
+    let standard_deviations = variance
+        .into_iter()
+        .map(|variance| (variance / samples.len() as f64).sqrt())
+        .collect::<Vec<_>>();
+
+    for sample in samples.iter_mut() {
+        for ((value, mean), standard_deviation) in
+            sample.iter_mut().zip(&mean).zip(&standard_deviations)
+        {
+            *value -= mean;
+            if *standard_deviation > 0. {
+                *value /= standard_deviation;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_clusters_with_dbscan() {
+        let points = [
+            [0., 0., 0.],
+            [1., 0., 0.],
+            [0., 1., 0.],
+            [10., 10., 0.],
+            [11., 10., 0.],
+            [10., 11., 0.],
+            [11., 11., 0.],
+            // a border point of the second cluster
+            [12.5, 11., 0.],
+            [50., 50., 0.],
+        ];
+
+        let labels = dbscan(&points, 1.5, 3);
+
+        assert_eq!(
+            labels,
+            vec![
+                Some(0),
+                Some(0),
+                Some(0),
+                Some(1),
+                Some(1),
+                Some(1),
+                Some(1),
+                Some(1),
+                None
+            ]
+        );
+    }
+
+    #[test]
+    fn it_clusters_with_kmeans() {
+        let samples = vec![
+            vec![10., 10.],
+            vec![0., 0.],
+            vec![11., 10.],
+            vec![1., 0.],
+            vec![0., 1.],
+            vec![10., 11.],
+        ];
+
+        let model = KMeans::train(&samples, 2, 10).unwrap();
+
+        assert_eq!(
+            samples
+                .iter()
+                .map(|sample| model.predict(sample))
+                .collect::<Vec<_>>(),
+            vec![1, 0, 1, 0, 0, 1]
+        );
+
+        // there are only two distinct samples
+        let model = KMeans::train(&[vec![1.], vec![1.], vec![2.]], 3, 10).unwrap();
+        assert_eq!(model.centroids, vec![vec![1.], vec![2.]]);
+
+        assert!(KMeans::train(&[], 2, 10).is_none());
+    }
+
+    #[test]
+    fn it_standardizes() {
+        let mut samples = vec![vec![1., 5.], vec![3., 5.]];
+
+        standardize(&mut samples);
+
+        assert_eq!(samples, vec![vec![-1., 0.], vec![1., 0.]]);
+    }
+}
@@ -0,0 +1,45 @@
+mod algorithms;
+mod point_clustering;
+mod raster_kmeans;
+
+pub use point_clustering::{PointClustering, PointClusteringMethod, PointClusteringParams};
+pub use raster_kmeans::{RasterKMeans, RasterKMeansParams};
+
+use snafu::Snafu;
+
+#[derive(Debug, Snafu)]
+#[snafu(visibility(pub(crate)), context(suffix(false)), module(error))]
+pub enum ClusteringError {
+    #[snafu(display("The input must be points, found {found}"))]
+    InputMustBePoints { found: String },
+
+    #[snafu(display("The column `{column}` already exists"))]
+    ColumnAlreadyExists { column: String },
+
+    #[snafu(display("The column `{column}` does not exist"))]
+    ColumnDoesNotExist { column: String },
+
+    #[snafu(display("The column `{column}` must be numeric"))]
+    ColumnMustBeNumeric { column: String },
+
+    #[snafu(display("At least one column is required for clustering"))]
+    NoColumns,
+
+    #[snafu(display("The distance must be positive and finite"))]
+    InvalidDistance,
+
+    #[snafu(display("The minimum number of points must be positive"))]
+    InvalidMinPoints,
+
+    #[snafu(display("Clustering by distance requires a spatial reference"))]
+    DistanceRequiresSpatialReference,
+
+    #[snafu(display("The number of clusters must be positive"))]
+    InvalidNumberOfClusters,
+
+    #[snafu(display("The number of clusters must not exceed {max}"))]
+    TooManyClusters { max: usize },
+
+    #[snafu(display("The sample size must be positive"))]
+    InvalidSampleSize,
+}
@@ -0,0 +1,535 @@
+use super::algorithms::{dbscan, standardize, KMeans};
+use super::{error, ClusteringError};
+use crate::engine::{
+    CanonicOperatorName, ExecutionContext, InitializedSources, InitializedVectorOperator, Operator,
+    OperatorName, QueryContext, QueryProcessor, SingleVectorSource, TypedVectorQueryProcessor,
+    VectorColumnInfo, VectorOperator, VectorQueryProcessor, VectorResultDescriptor,
+    WorkflowOperatorPath,
+};
+use crate::processing::buffer::geometry::EARTH_RADIUS;
+use crate::util::Result;
+use async_trait::async_trait;
+use futures::stream::BoxStream;
+use futures::{StreamExt, TryStreamExt};
+use geoengine_datatypes::collections::{
+    FeatureCollectionInfos, FeatureCollectionModifications, IntoGeometryIterator,
+    MultiPointCollection, VectorDataType,
+};
+use geoengine_datatypes::primitives::{
+    BoundingBox2D, ColumnSelection, FeatureData, FeatureDataRef, FeatureDataType, Measurement,
+    MultiPointAccess, VectorQueryRectangle,
+};
+use geoengine_datatypes::spatial_reference::{SpatialReference, SpatialReferenceOption};
+use serde::{Deserialize, Serialize};
+use snafu::ensure;
+
+/// The `PointClustering` operator assigns a cluster id to each point feature.
+///
+/// The id is stored in the new integer column `output_column`.
+/// Features that belong to no cluster, e.g., noise of DBSCAN, get no id.
+/// Since all features are clustered together, all chunks are collected before clustering.
+/// Multi-points are clustered by the mean of their coordinates.
+pub type PointClustering = Operator<PointClusteringParams, SingleVectorSource>;
+
+impl OperatorName for PointClustering {
+    const TYPE_NAME: &'static str = "PointClustering";
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PointClusteringParams {
+    pub method: PointClusteringMethod,
+    #[serde(default = "default_output_column")]
+    pub output_column: String,
+}
+
+fn default_output_column() -> String {
+    "cluster".to_string()
+}
+
+fn default_max_iterations() -> usize {
+    100
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub enum PointClusteringMethod {
+    /// Density-based clustering of the locations.
+    /// A point is a core point if at least `min_points` points, including itself, are within
+    /// `distance` meters.
+    /// Points that are not within the distance of a core point are noise.
+    ///
+    /// For a projected spatial reference, its units are assumed to be meters.
+    Dbscan { distance: f64, min_points: usize },
+    /// Clusters the features into `k` clusters by the numeric `columns`.
+    /// The columns are standardized to zero mean and unit variance.
+    /// Features with a missing value get no id.
+    KMeans {
+        columns: Vec<String>,
+        k: usize,
+        #[serde(default = "default_max_iterations")]
+        max_iterations: usize,
+    },
+}
+
+#[typetag::serde]
+#[async_trait]
+impl VectorOperator for PointClustering {
+    async fn _initialize(
+        self: Box<Self>,
+        path: WorkflowOperatorPath,
+        context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedVectorOperator>> {
+        let name = CanonicOperatorName::from(&self);
+
+        let source = self.sources.initialize_sources(path, context).await?.vector;
+
+        let in_desc = source.result_descriptor();
+
+        ensure!(
+            in_desc.data_type == VectorDataType::MultiPoint,
+            error::InputMustBePoints {
+                found: in_desc.data_type.to_string(),
+            }
+        );
+
+        ensure!(
+            !in_desc.columns.contains_key(&self.params.output_column),
+            error::ColumnAlreadyExists {
+                column: self.params.output_column.clone(),
+            }
+        );
+
+        match &self.params.method {
+            PointClusteringMethod::Dbscan {
+                distance,
+                min_points,
+            } => {
+                ensure!(
+                    distance.is_finite() && *distance > 0.,
+                    error::InvalidDistance
+                );
+                ensure!(*min_points > 0, error::InvalidMinPoints);
+                ensure!(
+                    in_desc.spatial_reference.is_spatial_ref(),
+                    error::DistanceRequiresSpatialReference
+                );
+            }
+            PointClusteringMethod::KMeans { columns, k, .. } => {
+                ensure!(*k > 0, error::InvalidNumberOfClusters);
+                ensure!(!columns.is_empty(), error::NoColumns);
+
+                for column in columns {
+                    let Some(info) = in_desc.columns.get(column) else {
+                        return Err(ClusteringError::ColumnDoesNotExist {
+                            column: column.clone(),
+                        }
+                        .into());
+                    };
+                    ensure!(
+                        info.data_type.is_numeric(),
+                        error::ColumnMustBeNumeric {
+                            column: column.clone(),
+                        }
+                    );
+                }
+            }
+        }
+
+        let mut result_descriptor = in_desc.clone();
+        result_descriptor.columns.insert(
+            self.params.output_column.clone(),
+            VectorColumnInfo {
+                data_type: FeatureDataType::Int,
+                measurement: Measurement::Unitless,
+            },
+        );
+
+        Ok(InitializedPointClustering {
+            name,
+            result_descriptor,
+            source,
+            params: self.params,
+        }
+        .boxed())
+    }
+
+    span_fn!(PointClustering);
+}
+
+pub struct InitializedPointClustering {
+    name: CanonicOperatorName,
+    result_descriptor: VectorResultDescriptor,
+    source: Box<dyn InitializedVectorOperator>,
+    params: PointClusteringParams,
+}
+
+impl InitializedVectorOperator for InitializedPointClustering {
+    fn result_descriptor(&self) -> &VectorResultDescriptor {
+        &self.result_descriptor
+    }
+
+    fn query_processor(&self) -> Result<TypedVectorQueryProcessor> {
+        let source = self
+            .source
+            .query_processor()?
+            .multi_point()
+            .expect("checked in `PointClustering` initialization");
+
+        Ok(TypedVectorQueryProcessor::MultiPoint(
+            PointClusteringProcessor {
+                source,
+                result_descriptor: self.result_descriptor.clone(),
+                params: self.params.clone(),
+            }
+            .boxed(),
+        ))
+    }
+
+    fn canonic_name(&self) -> CanonicOperatorName {
+        self.name.clone()
+    }
+}
+
+struct PointClusteringProcessor {
+    source: Box<dyn VectorQueryProcessor<VectorType = MultiPointCollection>>,
+    result_descriptor: VectorResultDescriptor,
+    params: PointClusteringParams,
+}
+
+impl PointClusteringProcessor {
+    /// Computes the cluster ids of the features of all collections
+    fn cluster(
+        collections: &[MultiPointCollection],
+        method: &PointClusteringMethod,
+        spatial_reference: SpatialReferenceOption,
+    ) -> Result<Vec<Option<u32>>> {
+        match method {
+            PointClusteringMethod::Dbscan {
+                distance,
+                min_points,
+            } => {
+                let geographic = spatial_reference == SpatialReference::epsg_4326().into();
+
+                let points = collections
+                    .iter()
+                    .flat_map(|collection| collection.geometries())
+                    .map(|geometry| {
+                        let points = geometry.points();
+                        let (x, y) = points
+                            .iter()
+                            .fold((0., 0.), |(x, y), point| (x + point.x, y + point.y));
+                        let count = points.len() as f64;
+                        location(x / count, y / count, geographic)
+                    })
+                    .collect::<Vec<_>>();
+
+                Ok(dbscan(
+                    &points,
+                    search_distance(*distance, geographic),
+                    *min_points,
+                ))
+            }
+            PointClusteringMethod::KMeans {
+                columns,
+                k,
+                max_iterations,
+            } => {
+                let mut values = Vec::<Option<Vec<f64>>>::new();
+                for collection in collections {
+                    let data = columns
+                        .iter()
+                        .map(|column| collection.data(column))
+                        .collect::<Result<Vec<FeatureDataRef>, _>>()?;
+                    let mut data = data
+                        .iter()
+                        .map(FeatureDataRef::float_options_iter)
+                        .collect::<Vec<_>>();
+
+                    for _ in 0..collection.len() {
+                        values.push(
+                            data.iter_mut()
+                                .map(|data| data.next().flatten())
+                                .collect::<Option<Vec<f64>>>(),
+                        );
+                    }
+                }
+
+                let mut samples = values.iter().flatten().cloned().collect::<Vec<_>>();
+                standardize(&mut samples);
+
+                let Some(model) = KMeans::train(&samples, *k, *max_iterations) else {
+                    return Ok(vec![None; values.len()]);
+                };
+
+                let mut samples = samples.iter();
+                Ok(values
+                    .iter()
+                    .map(|value| {
+                        value.as_ref()?;
+                        samples.next().map(|sample| model.predict(sample) as u32)
+                    })
+                    .collect())
+            }
+        }
+    }
+
+    fn add_cluster_column(
+        collections: &[MultiPointCollection],
+        method: &PointClusteringMethod,
+        spatial_reference: SpatialReferenceOption,
+        output_column: &str,
+    ) -> Result<Vec<MultiPointCollection>> {
+        let clusters = Self::cluster(collections, method, spatial_reference)?;
+
+        let mut clusters = clusters.into_iter();
+        collections
+            .iter()
+            .map(|collection| {
+                let ids = clusters
+                    .by_ref()
+                    .take(collection.len())
+                    .map(|cluster| cluster.map(i64::from))
+                    .collect();
+
+                Ok(collection.add_column(output_column, FeatureData::NullableInt(ids))?)
+            })
+            .collect()
+    }
+}
+
+/// The location of a point for the distance computation.
+/// Geographic coordinates are converted into earth-centered cartesian coordinates in meters.
+fn location(x: f64, y: f64, geographic: bool) -> [f64; 3] {
+    if !geographic {
+        return [x, y, 0.];
+    }
+
+    let (longitude, latitude) = (x.to_radians(), y.to_radians());
+    [
+        EARTH_RADIUS * latitude.cos() * longitude.cos(),
+        EARTH_RADIUS * latitude.cos() * longitude.sin(),
+        EARTH_RADIUS * latitude.sin(),
+    ]
+}
+
+/// The distance in the units of the locations that corresponds to the distance in meters.
+/// For geographic coordinates, this is the chord of the great-circle distance.
+fn search_distance(meters: f64, geographic: bool) -> f64 {
+    if !geographic {
+        return meters;
+    }
+
+    let angle = (meters / EARTH_RADIUS).min(std::f64::consts::PI);
+    2. * EARTH_RADIUS * (angle / 2.).sin()
+}
+
+#[async_trait]
+impl QueryProcessor for PointClusteringProcessor {
+    type Output = MultiPointCollection;
+    type SpatialBounds = BoundingBox2D;
+    type Selection = ColumnSelection;
+    type ResultDescription = VectorResultDescriptor;
+
+    async fn _query<'a>(
+        &'a self,
+        query: VectorQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<BoxStream<'a, Result<Self::Output>>> {
+        let collections: Vec<MultiPointCollection> =
+            self.source.query(query, ctx).await?.try_collect().await?;
+
+        let method = self.params.method.clone();
+        let output_column = self.params.output_column.clone();
+        let spatial_reference = self.result_descriptor.spatial_reference;
+        let clustered =
+            crate::util::spawn_blocking_with_thread_pool(ctx.thread_pool().clone(), move || {
+                Self::add_cluster_column(&collections, &method, spatial_reference, &output_column)
+            })
+            .await??;
+
+        Ok(futures::stream::iter(clustered.into_iter().map(Ok)).boxed())
+    }
+
+    fn result_descriptor(&self) -> &VectorResultDescriptor {
+        &self.result_descriptor
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{MockExecutionContext, MockQueryContext};
+    use crate::mock::MockFeatureCollectionSource;
+    use geoengine_datatypes::collections::ChunksEqualIgnoringCacheHint;
+    use geoengine_datatypes::primitives::{CacheHint, MultiPoint, SpatialResolution, TimeInterval};
+    use geoengine_datatypes::util::test::TestDefault;
+
+    async fn run(
+        chunks: Vec<MultiPointCollection>,
+        method: PointClusteringMethod,
+    ) -> Result<Vec<MultiPointCollection>> {
+        let operator = PointClustering {
+            params: PointClusteringParams {
+                method,
+                output_column: default_output_column(),
+            },
+            sources: MockFeatureCollectionSource::multiple(chunks).boxed().into(),
+        }
+        .boxed()
+        .initialize(
+            WorkflowOperatorPath::initialize_root(),
+            &MockExecutionContext::test_default(),
+        )
+        .await?;
+
+        let processor = operator.query_processor()?.multi_point().unwrap();
+
+        processor
+            .query(
+                VectorQueryRectangle {
+                    spatial_bounds: BoundingBox2D::new((-180., -90.).into(), (180., 90.).into())
+                        .unwrap(),
+                    time_interval: TimeInterval::default(),
+                    spatial_resolution: SpatialResolution::one(),
+                    attributes: ColumnSelection::all(),
+                },
+                &MockQueryContext::test_default(),
+            )
+            .await?
+            .try_collect()
+            .await
+    }
+
+    #[tokio::test]
+    async fn it_clusters_locations_in_meters_across_chunks() {
+        // 0.001° are about 111 meters at the equator
+        let chunks = vec![
+            MultiPointCollection::from_data(
+                MultiPoint::many(vec![(0., 0.), (0.001, 0.), (10., 10.)]).unwrap(),
+                vec![TimeInterval::default(); 3],
+                Default::default(),
+                CacheHint::default(),
+            )
+            .unwrap(),
+            MultiPointCollection::from_data(
+                MultiPoint::many(vec![(0., 0.001), (10., 10.001)]).unwrap(),
+                vec![TimeInterval::default(); 2],
+                Default::default(),
+                CacheHint::default(),
+            )
+            .unwrap(),
+        ];
+
+        let result = run(
+            chunks.clone(),
+            PointClusteringMethod::Dbscan {
+                distance: 200.,
+                min_points: 2,
+            },
+        )
+        .await
+        .unwrap();
+
+        let expected = vec![
+            chunks[0]
+                .add_column(
+                    "cluster",
+                    FeatureData::NullableInt(vec![Some(0), Some(0), Some(1)]),
+                )
+                .unwrap(),
+            chunks[1]
+                .add_column("cluster", FeatureData::NullableInt(vec![Some(0), Some(1)]))
+                .unwrap(),
+        ];
+        assert!(result.chunks_equal_ignoring_cache_hint(&expected));
+
+        // the points are too far apart for a distance of 50 meters
+        let result = run(
+            chunks,
+            PointClusteringMethod::Dbscan {
+                distance: 50.,
+                min_points: 2,
+            },
+        )
+        .await
+        .unwrap();
+
+        assert!(result.iter().all(|collection| collection
+            .data("cluster")
+            .unwrap()
+            .nulls()
+            .into_iter()
+            .all(|null| null)));
+    }
+
+    #[tokio::test]
+    async fn it_clusters_attributes_with_kmeans() {
+        let collection = MultiPointCollection::from_data(
+            MultiPoint::many(vec![(0., 0.); 5]).unwrap(),
+            vec![TimeInterval::default(); 5],
+            [(
+                "value".to_string(),
+                FeatureData::NullableFloat(vec![Some(1.), Some(100.), None, Some(2.), Some(101.)]),
+            )]
+            .into_iter()
+            .collect(),
+            CacheHint::default(),
+        )
+        .unwrap();
+
+        let result = run(
+            vec![collection],
+            PointClusteringMethod::KMeans {
+                columns: vec!["value".to_string()],
+                k: 2,
+                max_iterations: 10,
+            },
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            result[0]
+                .data("cluster")
+                .unwrap()
+                .float_options_iter()
+                .collect::<Vec<_>>(),
+            vec![Some(0.), Some(1.), None, Some(0.), Some(1.)]
+        );
+    }
+
+    #[tokio::test]
+    async fn it_checks_the_parameters() {
+        let collection = MultiPointCollection::from_data(
+            MultiPoint::many(vec![(0., 0.)]).unwrap(),
+            vec![TimeInterval::default()],
+            [("text".to_string(), FeatureData::Text(vec!["a".to_string()]))]
+                .into_iter()
+                .collect(),
+            CacheHint::default(),
+        )
+        .unwrap();
+
+        assert!(run(
+            vec![collection.clone()],
+            PointClusteringMethod::KMeans {
+                columns: vec!["text".to_string()],
+                k: 2,
+                max_iterations: 10,
+            },
+        )
+        .await
+        .is_err());
+
+        assert!(run(
+            vec![collection],
+            PointClusteringMethod::Dbscan {
+                distance: -1.,
+                min_points: 2,
+            },
+        )
+        .await
+        .is_err());
+    }
+}
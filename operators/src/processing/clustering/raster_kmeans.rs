@@ -0,0 +1,470 @@
+use super::algorithms::KMeans;
+use super::error;
+use crate::engine::{
+    CanonicOperatorName, ExecutionContext, InitializedRasterOperator, InitializedSources, Operator,
+    OperatorName, QueryContext, RasterBandDescriptor, RasterBandDescriptors, RasterOperator,
+    RasterQueryProcessor, RasterResultDescriptor, SingleRasterSource, TypedRasterQueryProcessor,
+    WorkflowOperatorPath,
+};
+use crate::processing::RasterTypeConversionQueryProcessor;
+use crate::util::Result;
+use async_trait::async_trait;
+use futures::stream::BoxStream;
+use futures::StreamExt;
+use geoengine_datatypes::primitives::{
+    BandSelection, CacheHint, Measurement, RasterQueryRectangle,
+};
+use geoengine_datatypes::raster::{
+    EmptyGrid2D, Grid2D, GridOrEmpty, GridSize, MaskedGrid2D, RasterDataType, RasterTile2D,
+};
+use serde::{Deserialize, Serialize};
+use snafu::ensure;
+use std::sync::Arc;
+
+/// The `RasterKMeans` operator classifies the pixels of a multi-band raster into `k` clusters.
+///
+/// For each query, the model is trained on a regular sample of at most `sample_size` pixels of
+/// the queried area and then applied to all pixels.
+/// Pixels with no data in any band get no cluster.
+/// The clusters are ordered by their centroids, band by band, so that the cluster ids are
+/// reproducible for the same data.
+pub type RasterKMeans = Operator<RasterKMeansParams, SingleRasterSource>;
+
+impl OperatorName for RasterKMeans {
+    const TYPE_NAME: &'static str = "RasterKMeans";
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RasterKMeansParams {
+    pub k: usize,
+    #[serde(default = "default_sample_size")]
+    pub sample_size: usize,
+    #[serde(default = "default_max_iterations")]
+    pub max_iterations: usize,
+}
+
+fn default_sample_size() -> usize {
+    10_000
+}
+
+fn default_max_iterations() -> usize {
+    100
+}
+
+/// The maximum number of clusters that fit into the `U8` output
+const MAX_CLUSTERS: usize = 256;
+
+#[typetag::serde]
+#[async_trait]
+impl RasterOperator for RasterKMeans {
+    async fn _initialize(
+        self: Box<Self>,
+        path: WorkflowOperatorPath,
+        context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedRasterOperator>> {
+        let name = CanonicOperatorName::from(&self);
+
+        ensure!(self.params.k > 0, error::InvalidNumberOfClusters);
+        ensure!(
+            self.params.k <= MAX_CLUSTERS,
+            error::TooManyClusters { max: MAX_CLUSTERS }
+        );
+        ensure!(self.params.sample_size > 0, error::InvalidSampleSize);
+
+        let source = self.sources.initialize_sources(path, context).await?.raster;
+        let in_desc = source.result_descriptor();
+
+        let classes = (0..self.params.k)
+            .map(|cluster| (cluster as u8, format!("Cluster {cluster}")))
+            .collect();
+
+        let result_descriptor = RasterResultDescriptor {
+            data_type: RasterDataType::U8,
+            spatial_reference: in_desc.spatial_reference,
+            time: in_desc.time,
+            bbox: in_desc.bbox,
+            resolution: in_desc.resolution,
+            bands: RasterBandDescriptors::new(vec![RasterBandDescriptor::new(
+                "cluster".to_string(),
+                Measurement::classification("cluster".to_string(), classes),
+            )])?,
+        };
+
+        Ok(InitializedRasterKMeans {
+            name,
+            result_descriptor,
+            num_bands: in_desc.bands.count(),
+            source,
+            params: self.params,
+        }
+        .boxed())
+    }
+
+    span_fn!(RasterKMeans);
+}
+
+pub struct InitializedRasterKMeans {
+    name: CanonicOperatorName,
+    result_descriptor: RasterResultDescriptor,
+    num_bands: u32,
+    source: Box<dyn InitializedRasterOperator>,
+    params: RasterKMeansParams,
+}
+
+impl InitializedRasterOperator for InitializedRasterKMeans {
+    fn query_processor(&self) -> Result<TypedRasterQueryProcessor> {
+        let source = call_on_generic_raster_processor!(
+            self.source.query_processor()?, p => RasterTypeConversionQueryProcessor::create_boxed(p)
+        );
+
+        Ok(TypedRasterQueryProcessor::U8(
+            RasterKMeansProcessor {
+                source,
+                result_descriptor: self.result_descriptor.clone(),
+                num_bands: self.num_bands,
+                params: self.params.clone(),
+            }
+            .boxed(),
+        ))
+    }
+
+    fn result_descriptor(&self) -> &RasterResultDescriptor {
+        &self.result_descriptor
+    }
+
+    fn canonic_name(&self) -> CanonicOperatorName {
+        self.name.clone()
+    }
+}
+
+struct RasterKMeansProcessor {
+    source: Box<dyn RasterQueryProcessor<RasterType = f32>>,
+    result_descriptor: RasterResultDescriptor,
+    num_bands: u32,
+    params: RasterKMeansParams,
+}
+
+impl RasterKMeansProcessor {
+    /// Collects a sample of the valid pixels of the query
+    async fn sample(
+        &self,
+        query: RasterQueryRectangle,
+        ctx: &dyn QueryContext,
+    ) -> Result<Vec<Vec<f64>>> {
+        let mut sampler = PixelSampler::new(self.params.sample_size);
+
+        let mut tiles = self
+            .source
+            .raster_query(query, ctx)
+            .await?
+            .chunks(self.num_bands as usize);
+
+        while let Some(tiles) = tiles.next().await {
+            let tiles = tiles.into_iter().collect::<Result<Vec<_>>>()?;
+            let Some(bands) = band_values(&tiles) else {
+                continue;
+            };
+
+            for pixel in 0..bands[0].len() {
+                if let Some(value) = pixel_value(&bands, pixel) {
+                    sampler.push(value);
+                }
+            }
+        }
+
+        Ok(sampler.samples)
+    }
+}
+
+/// A deterministic sample of every `step`-th pixel.
+/// Whenever there are twice as many samples as wanted, every other sample is dropped and the step
+/// is doubled.
+struct PixelSampler {
+    size: usize,
+    step: usize,
+    count: usize,
+    samples: Vec<Vec<f64>>,
+}
+
+impl PixelSampler {
+    fn new(size: usize) -> Self {
+        Self {
+            size,
+            step: 1,
+            count: 0,
+            samples: Vec::new(),
+        }
+    }
+
+    fn push(&mut self, value: Vec<f64>) {
+        if self.count % self.step == 0 {
+            self.samples.push(value);
+
+            if self.samples.len() >= 2 * self.size {
+                let mut index = 0;
+                self.samples.retain(|_| {
+                    index += 1;
+                    index % 2 == 1
+                });
+                self.step *= 2;
+            }
+        }
+
+        self.count += 1;
+    }
+}
+
+/// The values of all bands of tiles at the same position or `None` if all tiles are empty
+fn band_values(tiles: &[RasterTile2D<f32>]) -> Option<Vec<Vec<Option<f32>>>> {
+    if tiles.iter().all(RasterTile2D::is_empty) {
+        return None;
+    }
+
+    let num_pixels = tiles[0]
+        .tile_information()
+        .tile_size_in_pixels
+        .number_of_elements();
+    Some(
+        tiles
+            .iter()
+            .map(|tile| match &tile.grid_array {
+                GridOrEmpty::Grid(grid) => grid.masked_element_deref_iterator().collect(),
+                GridOrEmpty::Empty(_) => vec![None; num_pixels],
+            })
+            .collect(),
+    )
+}
+
+/// The values of a pixel in all bands or `None` if any band has no data
+fn pixel_value(bands: &[Vec<Option<f32>>], pixel: usize) -> Option<Vec<f64>> {
+    bands
+        .iter()
+        .map(|band| band[pixel].map(f64::from))
+        .collect()
+}
+
+/// Assigns the pixels of the tiles of all bands at the same position to the nearest cluster
+fn classify_tiles(tiles: &[RasterTile2D<f32>], model: Option<&KMeans>) -> Result<RasterTile2D<u8>> {
+    let first = &tiles[0];
+    let tile_information = first.tile_information();
+    let shape = tile_information.tile_size_in_pixels;
+
+    let cache_hint = tiles
+        .iter()
+        .fold(CacheHint::max_duration(), |cache_hint, tile| {
+            cache_hint.merged(&tile.cache_hint)
+        });
+
+    let empty_tile = || {
+        RasterTile2D::new_with_tile_info(
+            first.time,
+            tile_information,
+            0,
+            EmptyGrid2D::new(shape).into(),
+            cache_hint,
+        )
+    };
+
+    let (Some(model), Some(bands)) = (model, band_values(tiles)) else {
+        return Ok(empty_tile());
+    };
+
+    let num_pixels = shape.number_of_elements();
+    let mut data = Vec::with_capacity(num_pixels);
+    let mut validity = Vec::with_capacity(num_pixels);
+    for pixel in 0..num_pixels {
+        let cluster = pixel_value(&bands, pixel).map(|value| model.predict(&value) as u8);
+        data.push(cluster.unwrap_or_default());
+        validity.push(cluster.is_some());
+    }
+
+    if !validity.contains(&true) {
+        return Ok(empty_tile());
+    }
+
+    let grid = MaskedGrid2D::new(Grid2D::new(shape, data)?, Grid2D::new(shape, validity)?)?;
+
+    Ok(RasterTile2D::new_with_tile_info(
+        first.time,
+        tile_information,
+        0,
+        grid.into(),
+        cache_hint,
+    ))
+}
+
+#[async_trait]
+impl RasterQueryProcessor for RasterKMeansProcessor {
+    type RasterType = u8;
+
+    async fn raster_query<'a>(
+        &'a self,
+        query: RasterQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<BoxStream<'a, Result<RasterTile2D<u8>>>> {
+        let source_query = query.select_bands(BandSelection::first_n(self.num_bands));
+
+        let samples = self.sample(source_query.clone(), ctx).await?;
+        let k = self.params.k;
+        let max_iterations = self.params.max_iterations;
+        let model =
+            crate::util::spawn_blocking_with_thread_pool(ctx.thread_pool().clone(), move || {
+                KMeans::train(&samples, k, max_iterations)
+            })
+            .await?
+            .map(Arc::new);
+
+        let stream = self
+            .source
+            .raster_query(source_query, ctx)
+            .await?
+            .chunks(self.num_bands as usize)
+            .then(move |tiles| {
+                let model = model.clone();
+                async move {
+                    let tiles = tiles.into_iter().collect::<Result<Vec<_>>>()?;
+
+                    crate::util::spawn_blocking_with_thread_pool(
+                        ctx.thread_pool().clone(),
+                        move || classify_tiles(&tiles, model.as_deref()),
+                    )
+                    .await?
+                }
+            });
+
+        Ok(stream.boxed())
+    }
+
+    fn raster_result_descriptor(&self) -> &RasterResultDescriptor {
+        &self.result_descriptor
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{MockExecutionContext, MockQueryContext, MultipleRasterSources};
+    use crate::mock::{MockRasterSource, MockRasterSourceParams};
+    use crate::processing::{RasterStacker, RasterStackerParams};
+    use futures::TryStreamExt;
+    use geoengine_datatypes::primitives::{SpatialPartition2D, SpatialResolution, TimeInterval};
+    use geoengine_datatypes::raster::{RenameBands, TileInformation, TilingSpecification};
+    use geoengine_datatypes::spatial_reference::SpatialReference;
+    use geoengine_datatypes::util::test::TestDefault;
+
+    fn band(values: Vec<Option<u8>>) -> Box<dyn RasterOperator> {
+        let grid = MaskedGrid2D::new(
+            Grid2D::new(
+                [2, 2].into(),
+                values.iter().map(|v| v.unwrap_or(0)).collect(),
+            )
+            .unwrap(),
+            Grid2D::new([2, 2].into(), values.iter().map(Option::is_some).collect()).unwrap(),
+        )
+        .unwrap();
+
+        MockRasterSource {
+            params: MockRasterSourceParams {
+                data: vec![RasterTile2D::new_with_tile_info(
+                    TimeInterval::new_unchecked(0, 10),
+                    TileInformation {
+                        global_geo_transform: TestDefault::test_default(),
+                        global_tile_position: [0, 0].into(),
+                        tile_size_in_pixels: [2, 2].into(),
+                    },
+                    0,
+                    grid.into(),
+                    CacheHint::default(),
+                )],
+                result_descriptor: RasterResultDescriptor {
+                    data_type: RasterDataType::U8,
+                    spatial_reference: SpatialReference::epsg_4326().into(),
+                    time: None,
+                    bbox: None,
+                    resolution: None,
+                    bands: RasterBandDescriptors::new_single_band(),
+                },
+            },
+        }
+        .boxed()
+    }
+
+    #[tokio::test]
+    async fn it_clusters_multi_band_pixels() {
+        let raster = RasterStacker {
+            params: RasterStackerParams {
+                rename_bands: RenameBands::Default,
+            },
+            sources: MultipleRasterSources {
+                rasters: vec![
+                    band(vec![Some(1), Some(200), Some(2), None]),
+                    band(vec![Some(10), Some(250), Some(11), Some(5)]),
+                ],
+            },
+        }
+        .boxed();
+
+        let operator = RasterKMeans {
+            params: RasterKMeansParams {
+                k: 2,
+                sample_size: 10,
+                max_iterations: 10,
+            },
+            sources: SingleRasterSource { raster },
+        }
+        .boxed()
+        .initialize(
+            WorkflowOperatorPath::initialize_root(),
+            &MockExecutionContext::new_with_tiling_spec(TilingSpecification::new(
+                (0., 0.).into(),
+                [2, 2].into(),
+            )),
+        )
+        .await
+        .unwrap();
+
+        let processor = operator.query_processor().unwrap().get_u8().unwrap();
+
+        let tiles: Vec<RasterTile2D<u8>> = processor
+            .raster_query(
+                RasterQueryRectangle {
+                    spatial_bounds: SpatialPartition2D::new_unchecked(
+                        (0., 2.).into(),
+                        (2., 0.).into(),
+                    ),
+                    time_interval: TimeInterval::new_unchecked(0, 10),
+                    spatial_resolution: SpatialResolution::one(),
+                    attributes: BandSelection::first(),
+                },
+                &MockQueryContext::test_default(),
+            )
+            .await
+            .unwrap()
+            .try_collect()
+            .await
+            .unwrap();
+
+        assert_eq!(tiles.len(), 1);
+        let GridOrEmpty::Grid(grid) = &tiles[0].grid_array else {
+            panic!("expected a grid");
+        };
+        assert_eq!(
+            grid.masked_element_deref_iterator().collect::<Vec<_>>(),
+            vec![Some(0), Some(1), Some(0), None]
+        );
+    }
+
+    #[test]
+    fn it_thins_out_the_sample() {
+        let mut sampler = PixelSampler::new(2);
+
+        for value in 0..10 {
+            sampler.push(vec![f64::from(value)]);
+        }
+
+        // the pixels 0, 4 and 8 are kept
+        assert_eq!(sampler.step, 4);
+        assert_eq!(sampler.samples, vec![vec![0.], vec![4.], vec![8.]]);
+    }
+}
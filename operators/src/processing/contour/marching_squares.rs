@@ -0,0 +1,482 @@
+use crate::processing::buffer::geometry::cascaded_union;
+use geo::Area;
+use geoengine_datatypes::raster::{
+    GeoTransform, GridIdx, GridIndexAccess, GridShapeAccess, Pixel, RasterTile2D,
+};
+use num_traits::AsPrimitive;
+use std::collections::BTreeMap;
+
+/// A grid of pixel values that is assembled from the tiles of one time step.
+///
+/// Contours are computed on the pixel centers, so lines and bands are continuous across tile borders.
+pub struct ValueGrid {
+    /// global pixel index of the upper left pixel
+    origin: [isize; 2],
+    width: usize,
+    height: usize,
+    values: Vec<Option<f64>>,
+    geo_transform: GeoTransform,
+}
+
+impl ValueGrid {
+    /// Assembles the non-empty tiles into a single grid.
+    /// Returns `None` if all tiles are empty.
+    pub fn from_tiles<T: Pixel>(tiles: &[RasterTile2D<T>]) -> Option<Self> {
+        let tiles = tiles
+            .iter()
+            .filter(|tile| !tile.is_empty())
+            .collect::<Vec<_>>();
+
+        let first = tiles.first()?;
+        let geo_transform = first.global_geo_transform;
+
+        let mut min = [isize::MAX, isize::MAX];
+        let mut max = [isize::MIN, isize::MIN];
+        for tile in &tiles {
+            let [rows, columns] = tile.grid_shape_array();
+            let GridIdx([y, x]) = tile.tile_information().global_upper_left_pixel_idx();
+
+            min = [min[0].min(y), min[1].min(x)];
+            max = [
+                max[0].max(y + rows as isize),
+                max[1].max(x + columns as isize),
+            ];
+        }
+
+        let height = (max[0] - min[0]) as usize;
+        let width = (max[1] - min[1]) as usize;
+        let mut values = vec![None; width * height];
+
+        for tile in &tiles {
+            let [rows, columns] = tile.grid_shape_array();
+            let GridIdx([offset_y, offset_x]) =
+                tile.tile_information().global_upper_left_pixel_idx();
+
+            for y in 0..rows {
+                for x in 0..columns {
+                    let value = tile
+                        .get_at_grid_index_unchecked(GridIdx([y as isize, x as isize]))
+                        .map(AsPrimitive::<f64>::as_)
+                        .filter(|v| !v.is_nan());
+
+                    let grid_y = (offset_y - min[0]) as usize + y;
+                    let grid_x = (offset_x - min[1]) as usize + x;
+                    values[grid_y * width + grid_x] = value;
+                }
+            }
+        }
+
+        Some(Self {
+            origin: min,
+            width,
+            height,
+            values,
+            geo_transform,
+        })
+    }
+
+    fn value(&self, x: usize, y: usize) -> Option<f64> {
+        self.values[y * self.width + x]
+    }
+
+    fn coordinate(&self, x: usize, y: usize) -> geo::Coord<f64> {
+        self.geo_transform
+            .grid_idx_to_pixel_center_coordinate_2d(
+                [self.origin[0] + y as isize, self.origin[1] + x as isize].into(),
+            )
+            .into()
+    }
+
+    /// The minimum and maximum value of the grid
+    pub fn value_range(&self) -> Option<(f64, f64)> {
+        self.values
+            .iter()
+            .flatten()
+            .fold(None, |range, &v| match range {
+                None => Some((v, v)),
+                Some((min, max)) => Some((f64::min(min, v), f64::max(max, v))),
+            })
+    }
+
+    /// The values of the four corners of the cell with upper left pixel `(x, y)`
+    /// in the order upper left, upper right, lower right, lower left.
+    fn cell(&self, x: usize, y: usize) -> Option<[f64; 4]> {
+        Some([
+            self.value(x, y)?,
+            self.value(x + 1, y)?,
+            self.value(x + 1, y + 1)?,
+            self.value(x, y + 1)?,
+        ])
+    }
+
+    fn cells(&self) -> impl Iterator<Item = (usize, usize)> {
+        let width = self.width.saturating_sub(1);
+        (0..self.height.saturating_sub(1)).flat_map(move |y| (0..width).map(move |x| (x, y)))
+    }
+
+    /// Interpolates the crossing of `level` on an edge.
+    ///
+    /// Edges are always interpolated from their first to their second pixel,
+    /// so neighboring cells compute the exact same coordinate.
+    fn edge_crossing(&self, edge: Edge, level: f64) -> geo::Coord<f64> {
+        let ((x0, y0), (x1, y1)) = edge.pixels();
+
+        let v0 = self.value(x0, y0).unwrap_or(level);
+        let v1 = self.value(x1, y1).unwrap_or(level);
+
+        interpolate(
+            self.coordinate(x0, y0),
+            v0,
+            self.coordinate(x1, y1),
+            v1,
+            level,
+        )
+    }
+}
+
+fn interpolate(
+    c0: geo::Coord<f64>,
+    v0: f64,
+    c1: geo::Coord<f64>,
+    v1: f64,
+    level: f64,
+) -> geo::Coord<f64> {
+    let delta = v1 - v0;
+    let t = if delta.abs() < f64::EPSILON {
+        0.5
+    } else {
+        ((level - v0) / delta).clamp(0., 1.)
+    };
+
+    geo::Coord {
+        x: c0.x + (c1.x - c0.x) * t,
+        y: c0.y + (c1.y - c0.y) * t,
+    }
+}
+
+/// An edge between two neighboring pixel centers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Edge {
+    /// from `(x, y)` to `(x + 1, y)`
+    Horizontal(usize, usize),
+    /// from `(x, y)` to `(x, y + 1)`
+    Vertical(usize, usize),
+}
+
+impl Edge {
+    fn pixels(self) -> ((usize, usize), (usize, usize)) {
+        match self {
+            Self::Horizontal(x, y) => ((x, y), (x + 1, y)),
+            Self::Vertical(x, y) => ((x, y), (x, y + 1)),
+        }
+    }
+}
+
+/// Computes the iso-lines of `level` with marching squares.
+/// Segments of neighboring cells are joined into continuous lines.
+pub fn contour_lines(grid: &ValueGrid, level: f64) -> geo::MultiLineString<f64> {
+    let mut segments = Vec::<(Edge, Edge)>::new();
+
+    for (x, y) in grid.cells() {
+        let Some([ul, ur, lr, ll]) = grid.cell(x, y) else {
+            continue;
+        };
+
+        let top = Edge::Horizontal(x, y);
+        let right = Edge::Vertical(x + 1, y);
+        let bottom = Edge::Horizontal(x, y + 1);
+        let left = Edge::Vertical(x, y);
+
+        let case = (u8::from(ul >= level) << 3)
+            | (u8::from(ur >= level) << 2)
+            | (u8::from(lr >= level) << 1)
+            | u8::from(ll >= level);
+
+        let center_above = (ul + ur + lr + ll) / 4. >= level;
+
+        match case {
+            1 | 14 => segments.push((left, bottom)),
+            2 | 13 => segments.push((bottom, right)),
+            3 | 12 => segments.push((left, right)),
+            4 | 11 => segments.push((top, right)),
+            6 | 9 => segments.push((top, bottom)),
+            7 | 8 => segments.push((left, top)),
+            5 if center_above => {
+                segments.push((left, top));
+                segments.push((bottom, right));
+            }
+            10 if !center_above => {
+                segments.push((left, top));
+                segments.push((bottom, right));
+            }
+            5 | 10 => {
+                segments.push((top, right));
+                segments.push((left, bottom));
+            }
+            _ => {}
+        }
+    }
+
+    let lines = join_segments(&segments)
+        .into_iter()
+        .map(|edges| {
+            geo::LineString::new(
+                edges
+                    .into_iter()
+                    .map(|edge| grid.edge_crossing(edge, level))
+                    .collect(),
+            )
+        })
+        .collect();
+
+    geo::MultiLineString::new(lines)
+}
+
+/// Joins segments that share an edge into chains of edges.
+/// Every edge is shared by at most two cells, so the chains are either open lines or rings.
+fn join_segments(segments: &[(Edge, Edge)]) -> Vec<Vec<Edge>> {
+    let mut adjacency = BTreeMap::<Edge, Vec<usize>>::new();
+    for (i, (a, b)) in segments.iter().enumerate() {
+        adjacency.entry(*a).or_default().push(i);
+        adjacency.entry(*b).or_default().push(i);
+    }
+
+    let mut used = vec![false; segments.len()];
+    let mut chains = Vec::new();
+
+    // start at the ends of open lines first, so they are not split
+    let line_ends = adjacency
+        .iter()
+        .filter(|(_, segments)| segments.len() == 1)
+        .map(|(edge, _)| *edge);
+    let all_edges = adjacency.keys().copied();
+    let starts = line_ends.chain(all_edges).collect::<Vec<_>>();
+
+    for start in starts {
+        while let Some(&first) = adjacency[&start].iter().find(|&&s| !used[s]) {
+            let mut chain = vec![start];
+            let mut current = start;
+            let mut segment = first;
+
+            loop {
+                used[segment] = true;
+
+                let (a, b) = segments[segment];
+                current = if a == current { b } else { a };
+                chain.push(current);
+
+                match adjacency[&current].iter().find(|&&s| !used[s]) {
+                    Some(&next) => segment = next,
+                    None => break,
+                }
+            }
+
+            chains.push(chain);
+        }
+    }
+
+    chains
+}
+
+/// Computes the area whose interpolated values lie within `[lower, upper)`.
+/// Open bounds are denoted by `None`.
+pub fn contour_band(
+    grid: &ValueGrid,
+    lower: Option<f64>,
+    upper: Option<f64>,
+) -> geo::MultiPolygon<f64> {
+    let in_band =
+        |v: f64| lower.map_or(true, |lower| v >= lower) && upper.map_or(true, |upper| v < upper);
+
+    let mut polygons = Vec::new();
+
+    // runs of cells that are completely within the band
+    let mut run_start: Option<(usize, usize)> = None;
+    let close_run =
+        |run_start: &mut Option<(usize, usize)>, end_x: usize, polygons: &mut Vec<_>| {
+            if let Some((start_x, y)) = run_start.take() {
+                let ul = grid.coordinate(start_x, y);
+                let lr = grid.coordinate(end_x, y + 1);
+                polygons.push(geo::MultiPolygon::new(vec![
+                    geo::Rect::new(ul, lr).to_polygon()
+                ]));
+            }
+        };
+
+    for (x, y) in grid.cells() {
+        if x == 0 {
+            close_run(&mut run_start, grid.width - 1, &mut polygons);
+        }
+
+        let Some(values) = grid.cell(x, y) else {
+            close_run(&mut run_start, x, &mut polygons);
+            continue;
+        };
+
+        if values.iter().all(|&v| in_band(v)) {
+            if run_start.is_none() {
+                run_start = Some((x, y));
+            }
+            continue;
+        }
+
+        close_run(&mut run_start, x, &mut polygons);
+
+        if values.iter().all(|&v| !in_band(v)) && !crosses_band(values, lower, upper) {
+            continue;
+        }
+
+        let corners = [(x, y), (x + 1, y), (x + 1, y + 1), (x, y + 1)];
+        let mut ring = corners
+            .iter()
+            .zip(values)
+            .map(|(&(cx, cy), v)| (grid.coordinate(cx, cy), v))
+            .collect::<Vec<_>>();
+
+        if let Some(lower) = lower {
+            ring = clip_ring(&ring, lower, true);
+        }
+        if let Some(upper) = upper {
+            ring = clip_ring(&ring, upper, false);
+        }
+
+        let mut coords = ring.into_iter().map(|(c, _)| c).collect::<Vec<_>>();
+        coords.dedup();
+
+        let polygon = geo::Polygon::new(geo::LineString::new(coords), vec![]);
+
+        // skip degenerated polygons that only touch the band
+        if polygon.unsigned_area() > 0. {
+            polygons.push(geo::MultiPolygon::new(vec![polygon]));
+        }
+    }
+    close_run(&mut run_start, grid.width.saturating_sub(1), &mut polygons);
+
+    cascaded_union(polygons)
+}
+
+/// Checks if the values of a cell lie on both sides of the band
+fn crosses_band(values: [f64; 4], lower: Option<f64>, upper: Option<f64>) -> bool {
+    let below = lower.map_or(false, |lower| values.iter().any(|&v| v < lower));
+    let above = upper.map_or(false, |upper| values.iter().any(|&v| v >= upper));
+    below && above
+}
+
+/// Clips a ring with values at its vertices to the part where the values are above (or below) `level`.
+///
+/// Crossings are interpolated between the vertices ordered by their coordinates,
+/// so neighboring cells compute the exact same coordinates on shared edges.
+fn clip_ring(
+    ring: &[(geo::Coord<f64>, f64)],
+    level: f64,
+    keep_above: bool,
+) -> Vec<(geo::Coord<f64>, f64)> {
+    let inside = |v: f64| if keep_above { v >= level } else { v < level };
+
+    let mut output = Vec::with_capacity(ring.len() + 2);
+
+    for (i, &(c0, v0)) in ring.iter().enumerate() {
+        let (c1, v1) = ring[(i + 1) % ring.len()];
+
+        if inside(v0) {
+            output.push((c0, v0));
+        }
+
+        if inside(v0) != inside(v1) {
+            let crossing = if (c0.x, c0.y) <= (c1.x, c1.y) {
+                interpolate(c0, v0, c1, v1, level)
+            } else {
+                interpolate(c1, v1, c0, v0, level)
+            };
+            output.push((crossing, level));
+        }
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use geo::EuclideanLength;
+    use geoengine_datatypes::primitives::{CacheHint, TimeInterval};
+    use geoengine_datatypes::raster::{Grid2D, TileInformation};
+    use geoengine_datatypes::util::test::TestDefault;
+
+    fn grid(values: Vec<f64>, size: [usize; 2]) -> ValueGrid {
+        let tile = RasterTile2D::new_with_tile_info(
+            TimeInterval::default(),
+            TileInformation {
+                global_geo_transform: TestDefault::test_default(),
+                global_tile_position: [0, 0].into(),
+                tile_size_in_pixels: size.into(),
+            },
+            0,
+            Grid2D::new(size.into(), values).unwrap().into(),
+            CacheHint::default(),
+        );
+
+        ValueGrid::from_tiles(&[tile]).unwrap()
+    }
+
+    #[test]
+    fn it_traces_a_straight_line() {
+        let grid = grid(vec![0., 1., 2., 0., 1., 2., 0., 1., 2.], [3, 3]);
+
+        let lines = contour_lines(&grid, 1.5);
+
+        assert_eq!(lines.0.len(), 1);
+        assert!((lines.euclidean_length() - 2.).abs() < 1e-9);
+        assert!(lines.0[0].coords().all(|c| (c.x - 2.).abs() < 1e-9));
+    }
+
+    #[test]
+    fn it_closes_rings() {
+        let grid = grid(
+            vec![
+                0., 0., 0., 0., //
+                0., 1., 1., 0., //
+                0., 1., 1., 0., //
+                0., 0., 0., 0., //
+            ],
+            [4, 4],
+        );
+
+        let lines = contour_lines(&grid, 0.5);
+
+        assert_eq!(lines.0.len(), 1);
+        assert!(lines.0[0].is_closed());
+    }
+
+    #[test]
+    fn it_fills_bands() {
+        let grid = grid(vec![0., 1., 2., 0., 1., 2., 0., 1., 2.], [3, 3]);
+
+        let lower = contour_band(&grid, None, Some(1.));
+        let upper = contour_band(&grid, Some(1.), None);
+
+        assert!((lower.unsigned_area() - 2.).abs() < 1e-9);
+        assert!((upper.unsigned_area() - 2.).abs() < 1e-9);
+    }
+
+    #[test]
+    fn it_joins_segments() {
+        let segments = [
+            (Edge::Vertical(0, 0), Edge::Horizontal(0, 1)),
+            (Edge::Horizontal(1, 1), Edge::Vertical(2, 0)),
+            (Edge::Horizontal(0, 1), Edge::Horizontal(1, 1)),
+        ];
+
+        let chains = join_segments(&segments);
+
+        assert_eq!(
+            chains,
+            vec![vec![
+                Edge::Vertical(0, 0),
+                Edge::Horizontal(0, 1),
+                Edge::Horizontal(1, 1),
+                Edge::Vertical(2, 0),
+            ]]
+        );
+    }
+}
@@ -0,0 +1,688 @@
+mod marching_squares;
+
+use self::marching_squares::{contour_band, contour_lines, ValueGrid};
+use crate::engine::{
+    CanonicOperatorName, ExecutionContext, InitializedRasterOperator, InitializedSources,
+    InitializedVectorOperator, Operator, OperatorName, QueryContext, QueryProcessor,
+    RasterQueryProcessor, SingleRasterSource, TypedVectorQueryProcessor, VectorColumnInfo,
+    VectorOperator, VectorQueryProcessor, VectorResultDescriptor, WorkflowOperatorPath,
+};
+use crate::util::Result;
+use async_trait::async_trait;
+use futures::stream::BoxStream;
+use futures::{StreamExt, TryStreamExt};
+use geo::BooleanOps;
+use geoengine_datatypes::collections::{
+    BuilderProvider, GeoFeatureCollectionRowBuilder, MultiLineStringCollection,
+    MultiPolygonCollection, VectorDataType,
+};
+use geoengine_datatypes::primitives::{
+    BandSelection, BoundingBox2D, CacheHint, ColumnSelection, FeatureDataType, FeatureDataValue,
+    MultiLineString, MultiPolygon, RasterQueryRectangle, TimeInterval, VectorQueryRectangle,
+};
+use geoengine_datatypes::raster::{Pixel, RasterTile2D};
+use serde::{Deserialize, Serialize};
+use snafu::{ensure, Snafu};
+use std::collections::HashMap;
+
+/// The `Contour` operator computes iso-lines or filled iso-bands of a single-band raster.
+///
+/// The contours are interpolated between pixel centers and are continuous across tile borders.
+/// Thus, all tiles of a time step are assembled before the features are emitted.
+pub type Contour = Operator<ContourParams, SingleRasterSource>;
+
+impl OperatorName for Contour {
+    const TYPE_NAME: &'static str = "Contour";
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContourParams {
+    pub levels: ContourLevels,
+    #[serde(default)]
+    pub output: ContourOutput,
+    /// The name of the output column that contains the level.
+    /// Bands have the columns `<column_name>_min` and `<column_name>_max`.
+    #[serde(default = "ContourParams::default_column_name")]
+    pub column_name: String,
+}
+
+impl ContourParams {
+    const MAX_LEVELS: usize = 1000;
+
+    fn default_column_name() -> String {
+        "level".to_string()
+    }
+
+    fn band_columns(&self) -> (String, String) {
+        (
+            format!("{}_min", self.column_name),
+            format!("{}_max", self.column_name),
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub enum ContourLevels {
+    /// Levels at `offset + k * interval` within the value range of each time step
+    Interval {
+        interval: f64,
+        #[serde(default)]
+        offset: f64,
+    },
+    /// Explicit levels
+    Fixed { levels: Vec<f64> },
+}
+
+impl ContourLevels {
+    fn levels(&self, value_range: (f64, f64)) -> Result<Vec<f64>, ContourError> {
+        match self {
+            Self::Interval { interval, offset } => {
+                let (min, max) = value_range;
+                let first = ((min - offset) / interval).ceil() as i64;
+                let last = ((max - offset) / interval).floor() as i64;
+
+                let count = (last - first + 1).max(0) as usize;
+                ensure!(
+                    count <= ContourParams::MAX_LEVELS,
+                    error::TooManyLevels {
+                        max: ContourParams::MAX_LEVELS
+                    }
+                );
+
+                Ok((first..=last)
+                    .map(|k| offset + k as f64 * interval)
+                    .collect())
+            }
+            Self::Fixed { levels } => Ok(levels.clone()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ContourOutput {
+    /// Iso-lines as `MultiLineString`s
+    #[default]
+    Lines,
+    /// Filled areas between consecutive levels as `MultiPolygon`s.
+    /// The outermost bands are open towards the minimum and maximum value.
+    Bands,
+}
+
+#[typetag::serde]
+#[async_trait]
+impl VectorOperator for Contour {
+    async fn _initialize(
+        self: Box<Self>,
+        path: WorkflowOperatorPath,
+        context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedVectorOperator>> {
+        match &self.params.levels {
+            ContourLevels::Interval { interval, offset } => ensure!(
+                interval.is_finite() && *interval > 0. && offset.is_finite(),
+                error::InvalidInterval
+            ),
+            ContourLevels::Fixed { levels } => ensure!(
+                !levels.is_empty()
+                    && levels.len() <= ContourParams::MAX_LEVELS
+                    && levels.iter().all(|l| l.is_finite())
+                    && levels.windows(2).all(|w| w[0] < w[1]),
+                error::InvalidLevels {
+                    max: ContourParams::MAX_LEVELS
+                }
+            ),
+        }
+        ensure!(!self.params.column_name.is_empty(), error::EmptyColumnName);
+
+        let name = CanonicOperatorName::from(&self);
+
+        let raster = self.sources.initialize_sources(path, context).await?.raster;
+
+        let in_desc = raster.result_descriptor();
+
+        ensure!(in_desc.bands.count() == 1, error::RasterMustHaveSingleBand);
+
+        let column_info = VectorColumnInfo {
+            data_type: FeatureDataType::Float,
+            measurement: in_desc.bands[0].measurement.clone(),
+        };
+
+        let (data_type, columns) = match self.params.output {
+            ContourOutput::Lines => (
+                VectorDataType::MultiLineString,
+                [(self.params.column_name.clone(), column_info)]
+                    .into_iter()
+                    .collect::<HashMap<_, _>>(),
+            ),
+            ContourOutput::Bands => {
+                let (min_column, max_column) = self.params.band_columns();
+                (
+                    VectorDataType::MultiPolygon,
+                    [(min_column, column_info.clone()), (max_column, column_info)]
+                        .into_iter()
+                        .collect(),
+                )
+            }
+        };
+
+        let result_descriptor = VectorResultDescriptor {
+            data_type,
+            spatial_reference: in_desc.spatial_reference,
+            columns,
+            time: in_desc.time,
+            bbox: in_desc
+                .bbox
+                .map(|bbox| BoundingBox2D::from(geo::Rect::from(&bbox))),
+        };
+
+        Ok(InitializedContour {
+            name,
+            result_descriptor,
+            raster,
+            params: self.params,
+        }
+        .boxed())
+    }
+
+    span_fn!(Contour);
+}
+
+pub struct InitializedContour {
+    name: CanonicOperatorName,
+    result_descriptor: VectorResultDescriptor,
+    raster: Box<dyn InitializedRasterOperator>,
+    params: ContourParams,
+}
+
+impl InitializedVectorOperator for InitializedContour {
+    fn result_descriptor(&self) -> &VectorResultDescriptor {
+        &self.result_descriptor
+    }
+
+    fn query_processor(&self) -> Result<TypedVectorQueryProcessor> {
+        let raster = self.raster.query_processor()?;
+        let result_descriptor = self.result_descriptor.clone();
+        let params = self.params.clone();
+
+        Ok(match self.params.output {
+            ContourOutput::Lines => TypedVectorQueryProcessor::MultiLineString(
+                call_on_generic_raster_processor!(raster, raster => ContourLinesProcessor(
+                    ContourProcessor { raster, result_descriptor, params }
+                ).boxed()),
+            ),
+            ContourOutput::Bands => TypedVectorQueryProcessor::MultiPolygon(
+                call_on_generic_raster_processor!(raster, raster => ContourBandsProcessor(
+                    ContourProcessor { raster, result_descriptor, params }
+                ).boxed()),
+            ),
+        })
+    }
+
+    fn canonic_name(&self) -> CanonicOperatorName {
+        self.name.clone()
+    }
+}
+
+struct ContourProcessor<T>
+where
+    T: Pixel,
+{
+    raster: Box<dyn RasterQueryProcessor<RasterType = T>>,
+    result_descriptor: VectorResultDescriptor,
+    params: ContourParams,
+}
+
+/// All tiles of one time step
+struct TimeStep<T> {
+    time: TimeInterval,
+    tiles: Vec<RasterTile2D<T>>,
+}
+
+impl<T> TimeStep<T> {
+    fn cache_hint(&self) -> CacheHint {
+        self.tiles
+            .iter()
+            .fold(CacheHint::max_duration(), |cache_hint, tile| {
+                cache_hint.merged(&tile.cache_hint)
+            })
+    }
+}
+
+type RasterTileStream<'a, T> = BoxStream<'a, Result<RasterTile2D<T>>>;
+
+/// Collects the tiles of the next time step from the stream.
+///
+/// Tiles are ordered by time, so consecutive tiles with the same time form a time step.
+/// The first tile of the following time step is returned as lookahead.
+#[allow(clippy::type_complexity)]
+async fn next_time_step<T: Pixel>(
+    mut tiles: RasterTileStream<'_, T>,
+    lookahead: Option<RasterTile2D<T>>,
+) -> Result<
+    Option<(
+        TimeStep<T>,
+        (RasterTileStream<'_, T>, Option<RasterTile2D<T>>),
+    )>,
+> {
+    let first = match lookahead {
+        Some(first) => first,
+        None => match tiles.try_next().await? {
+            Some(first) => first,
+            None => return Ok(None),
+        },
+    };
+
+    let mut time_step = TimeStep {
+        time: first.time,
+        tiles: vec![first],
+    };
+
+    while let Some(tile) = tiles.try_next().await? {
+        if tile.time != time_step.time {
+            return Ok(Some((time_step, (tiles, Some(tile)))));
+        }
+
+        time_step.tiles.push(tile);
+    }
+
+    Ok(Some((time_step, (tiles, None))))
+}
+
+impl<T> ContourProcessor<T>
+where
+    T: Pixel,
+{
+    /// Queries the tiles and lazily groups them by time.
+    /// The raster stream is ordered by time, so consecutive tiles with the same time form a time step.
+    async fn time_steps<'a>(
+        &'a self,
+        query: &VectorQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<BoxStream<'a, Result<TimeStep<T>>>> {
+        let raster_query =
+            RasterQueryRectangle::from_qrect_and_bands(query, BandSelection::first());
+
+        let tiles = self.raster.raster_query(raster_query, ctx).await?;
+
+        Ok(
+            futures::stream::try_unfold((tiles, None), |(tiles, lookahead)| {
+                next_time_step(tiles, lookahead)
+            })
+            .boxed(),
+        )
+    }
+
+    fn bounds_polygon(bounds: BoundingBox2D) -> geo::MultiPolygon<f64> {
+        geo::MultiPolygon::new(vec![geo::Rect::from(bounds).to_polygon()])
+    }
+
+    fn lines(
+        time_step: &TimeStep<T>,
+        bounds: BoundingBox2D,
+        params: &ContourParams,
+    ) -> Result<MultiLineStringCollection> {
+        let mut builder = MultiLineStringCollection::builder();
+        builder.add_column(params.column_name.clone(), FeatureDataType::Float)?;
+        let mut builder = builder.finish_header();
+
+        if let Some(grid) = ValueGrid::from_tiles(&time_step.tiles) {
+            let bounds = Self::bounds_polygon(bounds);
+
+            let levels = match grid.value_range() {
+                Some(value_range) => params.levels.levels(value_range)?,
+                None => Vec::new(),
+            };
+
+            for level in levels {
+                let lines = bounds.clip(&contour_lines(&grid, level), false);
+                let lines = geo::MultiLineString::new(
+                    lines
+                        .0
+                        .into_iter()
+                        .filter(|line| line.0.len() > 1)
+                        .collect(),
+                );
+
+                if lines.0.is_empty() {
+                    continue;
+                }
+
+                builder.push_geometry(MultiLineString::from(lines));
+                builder.push_time_interval(time_step.time);
+                builder.push_data(&params.column_name, FeatureDataValue::Float(level))?;
+                builder.finish_row();
+            }
+        }
+
+        builder.cache_hint(time_step.cache_hint());
+
+        Ok(builder.build()?)
+    }
+
+    fn bands(
+        time_step: &TimeStep<T>,
+        bounds: BoundingBox2D,
+        params: &ContourParams,
+    ) -> Result<MultiPolygonCollection> {
+        let (min_column, max_column) = params.band_columns();
+
+        let mut builder = MultiPolygonCollection::builder();
+        builder.add_column(min_column.clone(), FeatureDataType::Float)?;
+        builder.add_column(max_column.clone(), FeatureDataType::Float)?;
+        let mut builder = builder.finish_header();
+
+        if let Some(grid) = ValueGrid::from_tiles(&time_step.tiles) {
+            let bounds = Self::bounds_polygon(bounds);
+
+            let levels = match grid.value_range() {
+                Some(value_range) => params.levels.levels(value_range)?,
+                None => Vec::new(),
+            };
+
+            let lower_bounds = std::iter::once(None).chain(levels.iter().copied().map(Some));
+            let upper_bounds = levels
+                .iter()
+                .copied()
+                .map(Some)
+                .chain(std::iter::once(None));
+
+            for (lower, upper) in lower_bounds.zip(upper_bounds) {
+                let band = contour_band(&grid, lower, upper).intersection(&bounds);
+
+                if band.0.is_empty() {
+                    continue;
+                }
+
+                builder.push_geometry(MultiPolygon::from(band));
+                builder.push_time_interval(time_step.time);
+                builder.push_data(&min_column, FeatureDataValue::NullableFloat(lower))?;
+                builder.push_data(&max_column, FeatureDataValue::NullableFloat(upper))?;
+                builder.finish_row();
+            }
+        }
+
+        builder.cache_hint(time_step.cache_hint());
+
+        Ok(builder.build()?)
+    }
+}
+
+struct ContourLinesProcessor<T: Pixel>(ContourProcessor<T>);
+
+struct ContourBandsProcessor<T: Pixel>(ContourProcessor<T>);
+
+#[async_trait]
+impl<T> QueryProcessor for ContourLinesProcessor<T>
+where
+    T: Pixel,
+{
+    type Output = MultiLineStringCollection;
+    type SpatialBounds = BoundingBox2D;
+    type Selection = ColumnSelection;
+    type ResultDescription = VectorResultDescriptor;
+
+    async fn _query<'a>(
+        &'a self,
+        query: VectorQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<BoxStream<'a, Result<Self::Output>>> {
+        let time_steps = self.0.time_steps(&query, ctx).await?;
+        let bounds = query.spatial_bounds;
+
+        let stream = time_steps.and_then(move |time_step| {
+            let params = self.0.params.clone();
+            async move {
+                crate::util::spawn_blocking_with_thread_pool(ctx.thread_pool().clone(), move || {
+                    ContourProcessor::lines(&time_step, bounds, &params)
+                })
+                .await?
+            }
+        });
+
+        Ok(stream.boxed())
+    }
+
+    fn result_descriptor(&self) -> &VectorResultDescriptor {
+        &self.0.result_descriptor
+    }
+}
+
+#[async_trait]
+impl<T> QueryProcessor for ContourBandsProcessor<T>
+where
+    T: Pixel,
+{
+    type Output = MultiPolygonCollection;
+    type SpatialBounds = BoundingBox2D;
+    type Selection = ColumnSelection;
+    type ResultDescription = VectorResultDescriptor;
+
+    async fn _query<'a>(
+        &'a self,
+        query: VectorQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<BoxStream<'a, Result<Self::Output>>> {
+        let time_steps = self.0.time_steps(&query, ctx).await?;
+        let bounds = query.spatial_bounds;
+
+        let stream = time_steps.and_then(move |time_step| {
+            let params = self.0.params.clone();
+            async move {
+                crate::util::spawn_blocking_with_thread_pool(ctx.thread_pool().clone(), move || {
+                    ContourProcessor::bands(&time_step, bounds, &params)
+                })
+                .await?
+            }
+        });
+
+        Ok(stream.boxed())
+    }
+
+    fn result_descriptor(&self) -> &VectorResultDescriptor {
+        &self.0.result_descriptor
+    }
+}
+
+#[derive(Debug, Snafu)]
+#[snafu(visibility(pub(crate)), context(suffix(false)), module(error))]
+pub enum ContourError {
+    #[snafu(display("The interval must be a positive number and the offset must be finite"))]
+    InvalidInterval,
+    #[snafu(display(
+        "Levels must be finite, strictly ascending and there must be between 1 and {max} levels"
+    ))]
+    InvalidLevels { max: usize },
+    #[snafu(display("The interval results in more than {max} levels"))]
+    TooManyLevels { max: usize },
+    #[snafu(display("The column name must not be empty"))]
+    EmptyColumnName,
+    #[snafu(display("Contours can only be computed for single-band rasters"))]
+    RasterMustHaveSingleBand,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{
+        MockExecutionContext, MockQueryContext, RasterBandDescriptors, RasterOperator,
+        RasterResultDescriptor,
+    };
+    use crate::mock::{MockRasterSource, MockRasterSourceParams};
+    use geo::{Area, EuclideanLength};
+    use geoengine_datatypes::collections::{FeatureCollectionInfos, IntoGeometryIterator};
+    use geoengine_datatypes::primitives::{AsGeo, SpatialResolution};
+    use geoengine_datatypes::raster::{
+        Grid2D, RasterDataType, TileInformation, TilingSpecification,
+    };
+    use geoengine_datatypes::spatial_reference::SpatialReference;
+    use geoengine_datatypes::util::test::TestDefault;
+
+    /// A ramp that increases by one per pixel from left to right, split into two tiles
+    fn ramp() -> Box<dyn RasterOperator> {
+        let tile_size_in_pixels = [2, 2].into();
+
+        let tile = |x: isize, values: Vec<u8>| {
+            RasterTile2D::new_with_tile_info(
+                TimeInterval::default(),
+                TileInformation {
+                    global_geo_transform: TestDefault::test_default(),
+                    global_tile_position: [0, x].into(),
+                    tile_size_in_pixels,
+                },
+                0,
+                Grid2D::new(tile_size_in_pixels, values).unwrap().into(),
+                CacheHint::default(),
+            )
+        };
+
+        MockRasterSource {
+            params: MockRasterSourceParams {
+                data: vec![tile(0, vec![0, 1, 0, 1]), tile(1, vec![2, 3, 2, 3])],
+                result_descriptor: RasterResultDescriptor {
+                    data_type: RasterDataType::U8,
+                    spatial_reference: SpatialReference::epsg_4326().into(),
+                    time: None,
+                    bbox: None,
+                    resolution: None,
+                    bands: RasterBandDescriptors::new_single_band(),
+                },
+            },
+        }
+        .boxed()
+    }
+
+    async fn initialize(params: ContourParams) -> Box<dyn InitializedVectorOperator> {
+        let execution_context = MockExecutionContext::new_with_tiling_spec(TilingSpecification {
+            origin_coordinate: [0.0, 0.0].into(),
+            tile_size_in_pixels: [2, 2].into(),
+        });
+
+        Contour {
+            params,
+            sources: ramp().into(),
+        }
+        .boxed()
+        .initialize(WorkflowOperatorPath::initialize_root(), &execution_context)
+        .await
+        .unwrap()
+    }
+
+    fn query_rectangle() -> VectorQueryRectangle {
+        VectorQueryRectangle {
+            spatial_bounds: BoundingBox2D::new((0., -2.).into(), (4., 0.).into()).unwrap(),
+            time_interval: TimeInterval::default(),
+            spatial_resolution: SpatialResolution::one(),
+            attributes: ColumnSelection::all(),
+        }
+    }
+
+    #[tokio::test]
+    async fn it_stitches_lines_across_tiles() {
+        let operator = initialize(ContourParams {
+            levels: ContourLevels::Interval {
+                interval: 1.,
+                offset: 0.5,
+            },
+            output: ContourOutput::Lines,
+            column_name: "level".to_string(),
+        })
+        .await;
+
+        let processor = operator
+            .query_processor()
+            .unwrap()
+            .multi_line_string()
+            .unwrap();
+
+        let result: Vec<MultiLineStringCollection> = processor
+            .query(query_rectangle(), &MockQueryContext::test_default())
+            .await
+            .unwrap()
+            .try_collect()
+            .await
+            .unwrap();
+
+        assert_eq!(result.len(), 1);
+        let result = &result[0];
+
+        let levels = result
+            .data("level")
+            .unwrap()
+            .float_options_iter()
+            .map(Option::unwrap)
+            .collect::<Vec<_>>();
+        assert_eq!(levels, vec![0.5, 1.5, 2.5]);
+
+        // the line at level 1.5 lies on the tile border
+        for geometry in result.geometries() {
+            let lines = geometry.as_geo();
+            assert_eq!(lines.0.len(), 1);
+            assert!((lines.euclidean_length() - 1.).abs() < 1e-9);
+        }
+    }
+
+    #[tokio::test]
+    async fn it_fills_bands() {
+        let operator = initialize(ContourParams {
+            levels: ContourLevels::Fixed { levels: vec![1.5] },
+            output: ContourOutput::Bands,
+            column_name: "level".to_string(),
+        })
+        .await;
+
+        let processor = operator.query_processor().unwrap().multi_polygon().unwrap();
+
+        let result: Vec<MultiPolygonCollection> = processor
+            .query(query_rectangle(), &MockQueryContext::test_default())
+            .await
+            .unwrap()
+            .try_collect()
+            .await
+            .unwrap();
+
+        assert_eq!(result.len(), 1);
+        let result = &result[0];
+
+        assert_eq!(result.len(), 2);
+        assert_eq!(
+            result
+                .data("level_max")
+                .unwrap()
+                .float_options_iter()
+                .collect::<Vec<_>>(),
+            vec![Some(1.5), None]
+        );
+
+        // pixel centers span from x = 0.5 to 3.5 and from y = -0.5 to -1.5
+        let areas = result
+            .geometries()
+            .map(|geometry| geometry.as_geo().unsigned_area())
+            .collect::<Vec<_>>();
+        assert!((areas[0] - 1.5).abs() < 1e-9);
+        assert!((areas[1] - 1.5).abs() < 1e-9);
+    }
+
+    #[tokio::test]
+    async fn it_checks_levels() {
+        let result = Contour {
+            params: ContourParams {
+                levels: ContourLevels::Fixed {
+                    levels: vec![2., 1.],
+                },
+                output: ContourOutput::Lines,
+                column_name: "level".to_string(),
+            },
+            sources: ramp().into(),
+        }
+        .boxed()
+        .initialize(
+            WorkflowOperatorPath::initialize_root(),
+            &MockExecutionContext::test_default(),
+        )
+        .await;
+
+        assert!(result.is_err());
+    }
+}
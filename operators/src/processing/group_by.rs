@@ -0,0 +1,726 @@
+use crate::engine::{
+    CanonicOperatorName, ExecutionContext, InitializedSources, InitializedVectorOperator, Operator,
+    OperatorName, QueryContext, QueryProcessor, SingleVectorSource, TypedVectorQueryProcessor,
+    VectorColumnInfo, VectorOperator, VectorQueryProcessor, VectorResultDescriptor,
+    WorkflowOperatorPath,
+};
+use crate::util::feature_key::FeatureKey;
+use crate::util::Result;
+use async_trait::async_trait;
+use futures::stream::BoxStream;
+use futures::{StreamExt, TryStreamExt};
+use geoengine_datatypes::collections::{
+    BuilderProvider, DataCollection, FeatureCollection, FeatureCollectionInfos, VectorDataType,
+};
+use geoengine_datatypes::primitives::{
+    BoundingBox2D, CacheHint, ColumnSelection, FeatureDataType, FeatureDataValue, Geometry,
+    Measurement, TimeInstance, TimeInterval, TimeStep, VectorQueryRectangle,
+};
+use geoengine_datatypes::util::arrow::ArrowTyped;
+use serde::{Deserialize, Serialize};
+use snafu::{ensure, Snafu};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+/// The `GroupBy` operator aggregates the features with the same values in the `group_by` columns.
+///
+/// The output is a data collection with a feature for each group that contains the group columns
+/// and a column for each aggregate.
+/// If a `time_step` is given, the features are additionally grouped by the time step that
+/// contains the start of their time intervals, relative to the `time_reference`.
+/// Otherwise, the time interval of a group spans the time intervals of all its features.
+///
+/// Null values are ignored by all aggregates.
+/// Since all features are aggregated together, all chunks are collected before aggregating.
+pub type GroupBy = Operator<GroupByParams, SingleVectorSource>;
+
+impl OperatorName for GroupBy {
+    const TYPE_NAME: &'static str = "GroupBy";
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GroupByParams {
+    #[serde(default)]
+    pub group_by: Vec<String>,
+    pub aggregates: Vec<GroupByAggregate>,
+    #[serde(default)]
+    pub time_step: Option<TimeStep>,
+    /// Define an anchor point for `time_step`
+    /// If `None`, the anchor point is `1970-01-01T00:00:00Z` by default
+    #[serde(default)]
+    pub time_reference: Option<TimeInstance>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GroupByAggregate {
+    /// The aggregated column, which is only optional for `count`
+    #[serde(default)]
+    pub column: Option<String>,
+    pub function: GroupByFunction,
+    pub output_column: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum GroupByFunction {
+    /// The number of values or, without a column, of features
+    Count,
+    Sum,
+    Mean,
+    Min,
+    Max,
+    DistinctCount,
+    /// The value of the feature with the earliest start
+    First,
+    /// The value of the feature with the latest start
+    Last,
+}
+
+impl GroupByFunction {
+    fn output_type(self, input: Option<FeatureDataType>) -> FeatureDataType {
+        match (self, input) {
+            (Self::Count | Self::DistinctCount, _) | (_, None) => FeatureDataType::Int,
+            (Self::Sum | Self::Mean, _) => FeatureDataType::Float,
+            (Self::Min | Self::Max | Self::First | Self::Last, Some(data_type)) => data_type,
+        }
+    }
+}
+
+#[derive(Debug, Snafu)]
+#[snafu(visibility(pub(crate)), context(suffix(false)), module(error))]
+pub enum GroupByError {
+    #[snafu(display("The column `{column}` does not exist"))]
+    ColumnDoesNotExist { column: String },
+
+    #[snafu(display("The column `{column}` must be numeric"))]
+    ColumnMustBeNumeric { column: String },
+
+    #[snafu(display("The aggregate function {function:?} requires a column"))]
+    MissingAggregateColumn { function: GroupByFunction },
+
+    #[snafu(display("The output column `{column}` is not unique"))]
+    DuplicateOutputColumn { column: String },
+
+    #[snafu(display("The time step must be positive"))]
+    InvalidTimeStep,
+}
+
+#[typetag::serde]
+#[async_trait]
+impl VectorOperator for GroupBy {
+    async fn _initialize(
+        self: Box<Self>,
+        path: WorkflowOperatorPath,
+        context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedVectorOperator>> {
+        let name = CanonicOperatorName::from(&self);
+
+        let source = self.sources.initialize_sources(path, context).await?.vector;
+
+        let in_desc = source.result_descriptor();
+
+        let column_info =
+            |column: &String| {
+                in_desc.columns.get(column).cloned().ok_or_else(|| {
+                    GroupByError::ColumnDoesNotExist {
+                        column: column.clone(),
+                    }
+                })
+            };
+
+        let mut columns = HashMap::new();
+        let mut group_columns = Vec::with_capacity(self.params.group_by.len());
+        for column in &self.params.group_by {
+            let info = column_info(column)?;
+            group_columns.push((column.clone(), info.data_type));
+            ensure!(
+                columns.insert(column.clone(), info).is_none(),
+                error::DuplicateOutputColumn {
+                    column: column.clone(),
+                }
+            );
+        }
+
+        let mut aggregates = Vec::with_capacity(self.params.aggregates.len());
+        for aggregate in &self.params.aggregates {
+            let input = match &aggregate.column {
+                Some(column) => Some(column_info(column)?),
+                None => {
+                    ensure!(
+                        aggregate.function == GroupByFunction::Count,
+                        error::MissingAggregateColumn {
+                            function: aggregate.function,
+                        }
+                    );
+                    None
+                }
+            };
+
+            if let (GroupByFunction::Sum | GroupByFunction::Mean, Some(input), Some(column)) =
+                (aggregate.function, &input, &aggregate.column)
+            {
+                ensure!(
+                    input.data_type.is_numeric(),
+                    error::ColumnMustBeNumeric {
+                        column: column.clone(),
+                    }
+                );
+            }
+
+            let data_type = aggregate
+                .function
+                .output_type(input.as_ref().map(|input| input.data_type));
+            let measurement = match (aggregate.function, input) {
+                (GroupByFunction::Count | GroupByFunction::DistinctCount, _) | (_, None) => {
+                    Measurement::Unitless
+                }
+                (_, Some(input)) => input.measurement,
+            };
+
+            ensure!(
+                columns
+                    .insert(
+                        aggregate.output_column.clone(),
+                        VectorColumnInfo {
+                            data_type,
+                            measurement,
+                        },
+                    )
+                    .is_none(),
+                error::DuplicateOutputColumn {
+                    column: aggregate.output_column.clone(),
+                }
+            );
+            aggregates.push((aggregate.clone(), data_type));
+        }
+
+        if let Some(time_step) = self.params.time_step {
+            ensure!(time_step.step > 0, error::InvalidTimeStep);
+        }
+
+        let result_descriptor = VectorResultDescriptor {
+            data_type: VectorDataType::Data,
+            spatial_reference: in_desc.spatial_reference,
+            columns,
+            time: in_desc.time,
+            bbox: None,
+        };
+
+        let state = GroupByState {
+            group_columns,
+            aggregates,
+            time_step: self.params.time_step,
+            time_reference: self
+                .params
+                .time_reference
+                .unwrap_or(TimeInstance::EPOCH_START),
+        };
+
+        Ok(InitializedGroupBy {
+            name,
+            result_descriptor,
+            source,
+            state,
+        }
+        .boxed())
+    }
+
+    span_fn!(GroupBy);
+}
+
+/// The validated parameters with the data types of the output columns
+#[derive(Debug, Clone)]
+struct GroupByState {
+    group_columns: Vec<(String, FeatureDataType)>,
+    aggregates: Vec<(GroupByAggregate, FeatureDataType)>,
+    time_step: Option<TimeStep>,
+    time_reference: TimeInstance,
+}
+
+pub struct InitializedGroupBy {
+    name: CanonicOperatorName,
+    result_descriptor: VectorResultDescriptor,
+    source: Box<dyn InitializedVectorOperator>,
+    state: GroupByState,
+}
+
+impl InitializedVectorOperator for InitializedGroupBy {
+    fn result_descriptor(&self) -> &VectorResultDescriptor {
+        &self.result_descriptor
+    }
+
+    fn query_processor(&self) -> Result<TypedVectorQueryProcessor> {
+        Ok(call_on_generic_vector_processor!(
+            self.source.query_processor()?,
+            source => TypedVectorQueryProcessor::Data(
+                GroupByProcessor {
+                    source,
+                    result_descriptor: self.result_descriptor.clone(),
+                    state: self.state.clone(),
+                }
+                .boxed()
+            )
+        ))
+    }
+
+    fn canonic_name(&self) -> CanonicOperatorName {
+        self.name.clone()
+    }
+}
+
+struct GroupByProcessor<G> {
+    source: Box<dyn VectorQueryProcessor<VectorType = FeatureCollection<G>>>,
+    result_descriptor: VectorResultDescriptor,
+    state: GroupByState,
+}
+
+/// The running state of an aggregate of a group
+#[derive(Debug, Clone)]
+enum Accumulator {
+    Count(i64),
+    Sum(Option<f64>),
+    Mean { sum: f64, count: usize },
+    Min(Option<(FeatureKey, FeatureDataValue)>),
+    Max(Option<(FeatureKey, FeatureDataValue)>),
+    DistinctCount(BTreeSet<FeatureKey>),
+    First(Option<(TimeInstance, FeatureDataValue)>),
+    Last(Option<(TimeInstance, FeatureDataValue)>),
+}
+
+impl Accumulator {
+    fn new(function: GroupByFunction) -> Self {
+        match function {
+            GroupByFunction::Count => Self::Count(0),
+            GroupByFunction::Sum => Self::Sum(None),
+            GroupByFunction::Mean => Self::Mean { sum: 0., count: 0 },
+            GroupByFunction::Min => Self::Min(None),
+            GroupByFunction::Max => Self::Max(None),
+            GroupByFunction::DistinctCount => Self::DistinctCount(BTreeSet::new()),
+            GroupByFunction::First => Self::First(None),
+            GroupByFunction::Last => Self::Last(None),
+        }
+    }
+
+    /// Adds the value of a feature or, without a column, only counts the feature
+    fn add(&mut self, value: Option<FeatureDataValue>, start: TimeInstance) {
+        let Some(value) = value else {
+            if let Self::Count(count) = self {
+                *count += 1;
+            }
+            return;
+        };
+
+        let key = FeatureKey::from(&value);
+        let number = match key {
+            FeatureKey::Null => return,
+            FeatureKey::Int(value) => Some(value as f64),
+            FeatureKey::Float(value) => Some(value.0),
+            _ => None,
+        };
+
+        match self {
+            Self::Count(count) => *count += 1,
+            Self::Sum(sum) => *sum = Some(sum.unwrap_or_default() + number.unwrap_or_default()),
+            Self::Mean { sum, count } => {
+                *sum += number.unwrap_or_default();
+                *count += 1;
+            }
+            Self::Min(min) => {
+                if min.as_ref().map_or(true, |(min, _)| key < *min) {
+                    *min = Some((key, value));
+                }
+            }
+            Self::Max(max) => {
+                if max.as_ref().map_or(true, |(max, _)| key > *max) {
+                    *max = Some((key, value));
+                }
+            }
+            Self::DistinctCount(keys) => {
+                keys.insert(key);
+            }
+            Self::First(first) => {
+                if first.as_ref().map_or(true, |(time, _)| start < *time) {
+                    *first = Some((start, value));
+                }
+            }
+            Self::Last(last) => {
+                if last.as_ref().map_or(true, |(time, _)| start >= *time) {
+                    *last = Some((start, value));
+                }
+            }
+        }
+    }
+
+    /// The aggregated value or `None` if there were no values
+    fn finish(self) -> Option<FeatureDataValue> {
+        match self {
+            Self::Count(count) => Some(FeatureDataValue::Int(count)),
+            Self::Sum(sum) => sum.map(FeatureDataValue::Float),
+            Self::Mean { sum, count } => {
+                (count > 0).then(|| FeatureDataValue::Float(sum / count as f64))
+            }
+            Self::Min(value) | Self::Max(value) => value.map(|(_, value)| value),
+            Self::DistinctCount(keys) => Some(FeatureDataValue::Int(keys.len() as i64)),
+            Self::First(value) | Self::Last(value) => value.map(|(_, value)| value),
+        }
+    }
+}
+
+struct Group {
+    values: Vec<FeatureDataValue>,
+    time: TimeInterval,
+    accumulators: Vec<Accumulator>,
+}
+
+impl<G> GroupByProcessor<G>
+where
+    G: Geometry + ArrowTyped,
+{
+    /// The time step that contains the start of the time interval
+    fn time_bucket(state: &GroupByState, time: TimeInterval) -> Result<Option<TimeInterval>> {
+        let Some(time_step) = state.time_step else {
+            return Ok(None);
+        };
+
+        let start = time_step.snap_relative_preserve_bounds(state.time_reference, time.start());
+        let end = if start.is_min() || start.is_max() {
+            TimeInstance::MAX
+        } else {
+            (start + time_step)?
+        };
+
+        Ok(Some(TimeInterval::new(start, end)?))
+    }
+
+    fn group(collections: &[FeatureCollection<G>], state: &GroupByState) -> Result<DataCollection> {
+        let mut groups = BTreeMap::<(Vec<FeatureKey>, Option<TimeInstance>), Group>::new();
+        let mut cache_hint = CacheHint::max_duration();
+
+        for collection in collections {
+            cache_hint.merge_with(&collection.cache_hint);
+
+            let group_data = state
+                .group_columns
+                .iter()
+                .map(|(column, _)| collection.data(column))
+                .collect::<Result<Vec<_>, _>>()?;
+            let aggregate_data = state
+                .aggregates
+                .iter()
+                .map(|(aggregate, _)| {
+                    aggregate
+                        .column
+                        .as_ref()
+                        .map(|column| collection.data(column))
+                        .transpose()
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+
+            for (feature_index, time) in collection.time_intervals().iter().enumerate() {
+                let values = group_data
+                    .iter()
+                    .map(|data| data.get_unchecked(feature_index))
+                    .collect::<Vec<_>>();
+                let keys = values.iter().map(FeatureKey::from).collect::<Vec<_>>();
+                let bucket = Self::time_bucket(state, *time)?;
+
+                let group = groups
+                    .entry((keys, bucket.map(|bucket| bucket.start())))
+                    .or_insert_with(|| Group {
+                        values,
+                        time: bucket.unwrap_or(*time),
+                        accumulators: state
+                            .aggregates
+                            .iter()
+                            .map(|(aggregate, _)| Accumulator::new(aggregate.function))
+                            .collect(),
+                    });
+
+                if bucket.is_none() {
+                    group.time = TimeInterval::new_unchecked(
+                        group.time.start().min(time.start()),
+                        group.time.end().max(time.end()),
+                    );
+                }
+
+                for (accumulator, data) in group.accumulators.iter_mut().zip(&aggregate_data) {
+                    let value = data.as_ref().map(|data| data.get_unchecked(feature_index));
+                    accumulator.add(value, time.start());
+                }
+            }
+        }
+
+        let mut builder = DataCollection::builder();
+        for (column, data_type) in &state.group_columns {
+            builder.add_column(column.clone(), *data_type)?;
+        }
+        for (aggregate, data_type) in &state.aggregates {
+            builder.add_column(aggregate.output_column.clone(), *data_type)?;
+        }
+        let mut builder = builder.finish_header();
+
+        for group in groups.into_values() {
+            builder.push_time_interval(group.time);
+
+            for ((column, _), value) in state.group_columns.iter().zip(group.values) {
+                builder.push_data(column, value)?;
+            }
+
+            for ((aggregate, _), accumulator) in state.aggregates.iter().zip(group.accumulators) {
+                match accumulator.finish() {
+                    Some(value) => builder.push_data(&aggregate.output_column, value)?,
+                    None => builder.push_null(&aggregate.output_column)?,
+                }
+            }
+
+            builder.finish_row();
+        }
+
+        builder.cache_hint(cache_hint);
+
+        Ok(builder.build()?)
+    }
+}
+
+#[async_trait]
+impl<G> QueryProcessor for GroupByProcessor<G>
+where
+    G: Geometry + ArrowTyped + Sync + Send + 'static,
+{
+    type Output = DataCollection;
+    type SpatialBounds = BoundingBox2D;
+    type Selection = ColumnSelection;
+    type ResultDescription = VectorResultDescriptor;
+
+    async fn _query<'a>(
+        &'a self,
+        query: VectorQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<BoxStream<'a, Result<Self::Output>>> {
+        let collections: Vec<FeatureCollection<G>> =
+            self.source.query(query, ctx).await?.try_collect().await?;
+
+        let state = self.state.clone();
+        let grouped =
+            crate::util::spawn_blocking_with_thread_pool(ctx.thread_pool().clone(), move || {
+                Self::group(&collections, &state)
+            })
+            .await??;
+
+        Ok(futures::stream::once(async { Ok(grouped) }).boxed())
+    }
+
+    fn result_descriptor(&self) -> &VectorResultDescriptor {
+        &self.result_descriptor
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{MockExecutionContext, MockQueryContext};
+    use crate::mock::MockFeatureCollectionSource;
+    use geoengine_datatypes::collections::MultiPointCollection;
+    use geoengine_datatypes::primitives::{
+        FeatureData, MultiPoint, SpatialResolution, TimeGranularity,
+    };
+    use geoengine_datatypes::util::test::TestDefault;
+
+    fn observations() -> Vec<MultiPointCollection> {
+        let collection = |species: Vec<Option<&str>>, counts: Vec<Option<i64>>, times: Vec<i64>| {
+            MultiPointCollection::from_data(
+                MultiPoint::many(vec![(0., 0.); species.len()]).unwrap(),
+                times
+                    .into_iter()
+                    .map(|time| TimeInterval::new_unchecked(time, time + 1))
+                    .collect(),
+                [
+                    (
+                        "species".to_string(),
+                        FeatureData::NullableText(
+                            species
+                                .into_iter()
+                                .map(|species| species.map(ToString::to_string))
+                                .collect(),
+                        ),
+                    ),
+                    ("count".to_string(), FeatureData::NullableInt(counts)),
+                ]
+                .into_iter()
+                .collect(),
+                CacheHint::default(),
+            )
+            .unwrap()
+        };
+
+        vec![
+            collection(
+                vec![Some("fox"), Some("owl"), Some("fox")],
+                vec![Some(2), Some(1), None],
+                vec![0, 10, 20],
+            ),
+            collection(vec![Some("fox"), None], vec![Some(4), Some(3)], vec![5, 30]),
+        ]
+    }
+
+    fn aggregate(
+        column: Option<&str>,
+        function: GroupByFunction,
+        output: &str,
+    ) -> GroupByAggregate {
+        GroupByAggregate {
+            column: column.map(ToString::to_string),
+            function,
+            output_column: output.to_string(),
+        }
+    }
+
+    async fn run(params: GroupByParams) -> Result<DataCollection> {
+        let operator = GroupBy {
+            params,
+            sources: MockFeatureCollectionSource::multiple(observations())
+                .boxed()
+                .into(),
+        }
+        .boxed()
+        .initialize(
+            WorkflowOperatorPath::initialize_root(),
+            &MockExecutionContext::test_default(),
+        )
+        .await?;
+
+        let processor = operator.query_processor()?.data().unwrap();
+
+        let mut result: Vec<DataCollection> = processor
+            .query(
+                VectorQueryRectangle {
+                    spatial_bounds: BoundingBox2D::new((-1., -1.).into(), (1., 1.).into()).unwrap(),
+                    time_interval: TimeInterval::default(),
+                    spatial_resolution: SpatialResolution::one(),
+                    attributes: ColumnSelection::all(),
+                },
+                &MockQueryContext::test_default(),
+            )
+            .await?
+            .try_collect()
+            .await?;
+
+        assert_eq!(result.len(), 1);
+        Ok(result.remove(0))
+    }
+
+    fn floats(collection: &DataCollection, column: &str) -> Vec<Option<f64>> {
+        collection
+            .data(column)
+            .unwrap()
+            .float_options_iter()
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn it_aggregates_groups() {
+        let result = run(GroupByParams {
+            group_by: vec!["species".to_string()],
+            aggregates: vec![
+                aggregate(None, GroupByFunction::Count, "features"),
+                aggregate(Some("count"), GroupByFunction::Count, "observations"),
+                aggregate(Some("count"), GroupByFunction::Sum, "sum"),
+                aggregate(Some("count"), GroupByFunction::Mean, "mean"),
+                aggregate(Some("count"), GroupByFunction::Min, "min"),
+                aggregate(Some("count"), GroupByFunction::Max, "max"),
+                aggregate(Some("count"), GroupByFunction::DistinctCount, "distinct"),
+                aggregate(Some("count"), GroupByFunction::First, "first"),
+                aggregate(Some("count"), GroupByFunction::Last, "last"),
+            ],
+            time_step: None,
+            time_reference: None,
+        })
+        .await
+        .unwrap();
+
+        // the groups are ordered by their values with the null group first
+        assert_eq!(result.len(), 3);
+        assert_eq!(
+            result.data("species").unwrap().nulls(),
+            vec![true, false, false]
+        );
+        assert_eq!(
+            floats(&result, "features"),
+            vec![Some(1.), Some(3.), Some(1.)]
+        );
+        assert_eq!(
+            floats(&result, "observations"),
+            vec![Some(1.), Some(2.), Some(1.)]
+        );
+        assert_eq!(floats(&result, "sum"), vec![Some(3.), Some(6.), Some(1.)]);
+        assert_eq!(floats(&result, "mean"), vec![Some(3.), Some(3.), Some(1.)]);
+        assert_eq!(floats(&result, "min"), vec![Some(3.), Some(2.), Some(1.)]);
+        assert_eq!(floats(&result, "max"), vec![Some(3.), Some(4.), Some(1.)]);
+        assert_eq!(
+            floats(&result, "distinct"),
+            vec![Some(1.), Some(2.), Some(1.)]
+        );
+        assert_eq!(floats(&result, "first"), vec![Some(3.), Some(2.), Some(1.)]);
+        assert_eq!(floats(&result, "last"), vec![Some(3.), Some(4.), Some(1.)]);
+        assert_eq!(
+            result.time_intervals(),
+            &[
+                TimeInterval::new_unchecked(30, 31),
+                TimeInterval::new_unchecked(0, 21),
+                TimeInterval::new_unchecked(10, 11),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn it_buckets_time() {
+        let result = run(GroupByParams {
+            group_by: vec![],
+            aggregates: vec![aggregate(Some("count"), GroupByFunction::Sum, "sum")],
+            time_step: Some(TimeStep {
+                granularity: TimeGranularity::Millis,
+                step: 10,
+            }),
+            time_reference: Some(TimeInstance::from_millis_unchecked(5)),
+        })
+        .await
+        .unwrap();
+
+        // the only feature of the third bucket has no count
+        assert_eq!(
+            floats(&result, "sum"),
+            vec![Some(2.), Some(5.), None, Some(3.)]
+        );
+        assert_eq!(
+            result.time_intervals(),
+            &[
+                TimeInterval::new_unchecked(-5, 5),
+                TimeInterval::new_unchecked(5, 15),
+                TimeInterval::new_unchecked(15, 25),
+                TimeInterval::new_unchecked(25, 35),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn it_checks_the_aggregates() {
+        assert!(run(GroupByParams {
+            group_by: vec![],
+            aggregates: vec![aggregate(Some("species"), GroupByFunction::Mean, "mean")],
+            time_step: None,
+            time_reference: None,
+        })
+        .await
+        .is_err());
+
+        assert!(run(GroupByParams {
+            group_by: vec!["species".to_string()],
+            aggregates: vec![aggregate(None, GroupByFunction::Max, "species")],
+            time_step: None,
+            time_reference: None,
+        })
+        .await
+        .is_err());
+    }
+}
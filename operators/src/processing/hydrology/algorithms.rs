@@ -0,0 +1,295 @@
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BinaryHeap, VecDeque};
+
+/// The D8 flow directions as `(code, row offset, column offset)`, starting in the east and
+/// proceeding clockwise.
+pub const D8_DIRECTIONS: [(u8, isize, isize); 8] = [
+    (1, 0, 1),
+    (2, 1, 1),
+    (4, 1, 0),
+    (8, 1, -1),
+    (16, 0, -1),
+    (32, -1, -1),
+    (64, -1, 0),
+    (128, -1, 1),
+];
+
+/// A grid of elevations or flow directions in row-major order
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValueGrid {
+    pub width: usize,
+    pub height: usize,
+    pub values: Vec<Option<f64>>,
+}
+
+impl ValueGrid {
+    pub fn new(width: usize, height: usize, values: Vec<Option<f64>>) -> Self {
+        debug_assert_eq!(values.len(), width * height);
+        Self {
+            width,
+            height,
+            values,
+        }
+    }
+
+    fn value(&self, index: usize) -> Option<f64> {
+        self.values[index]
+    }
+
+    /// The index of the neighbor at the offset or `None` if it is outside of the grid
+    fn neighbor(&self, index: usize, row_offset: isize, column_offset: isize) -> Option<usize> {
+        let row = (index / self.width) as isize + row_offset;
+        let column = (index % self.width) as isize + column_offset;
+
+        if row < 0 || column < 0 || row >= self.height as isize || column >= self.width as isize {
+            return None;
+        }
+
+        Some(row as usize * self.width + column as usize)
+    }
+
+    /// A cell drains out of the grid if it is at the border or next to a cell without data
+    fn is_outlet(&self, index: usize) -> bool {
+        D8_DIRECTIONS.iter().any(|&(_, row_offset, column_offset)| {
+            self.neighbor(index, row_offset, column_offset)
+                .map_or(true, |neighbor| self.value(neighbor).is_none())
+        })
+    }
+}
+
+/// An entry of the priority queue that orders by elevation and then by insertion order
+#[derive(Debug, Clone, Copy)]
+struct QueueEntry {
+    elevation: f64,
+    order: usize,
+    index: usize,
+}
+
+impl PartialEq for QueueEntry {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for QueueEntry {}
+
+impl PartialOrd for QueueEntry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for QueueEntry {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.elevation
+            .total_cmp(&other.elevation)
+            .then(self.order.cmp(&other.order))
+    }
+}
+
+/// Fills all depressions of a digital elevation model with the priority-flood algorithm.
+///
+/// Starting from the outlets, cells are visited in order of their elevation.
+/// Each cell is raised to at least the elevation of the cell it was reached from plus `min_drop`.
+/// A positive `min_drop` ensures that filled areas drain instead of becoming flat.
+pub fn fill_sinks(dem: &ValueGrid, min_drop: f64) -> Vec<Option<f64>> {
+    let mut filled = dem.values.clone();
+    let mut closed = vec![false; filled.len()];
+    let mut queue = BinaryHeap::new();
+    let mut order = 0;
+
+    for (index, &value) in filled.iter().enumerate() {
+        if let Some(elevation) = value {
+            if dem.is_outlet(index) {
+                closed[index] = true;
+                queue.push(Reverse(QueueEntry {
+                    elevation,
+                    order,
+                    index,
+                }));
+                order += 1;
+            }
+        }
+    }
+
+    while let Some(Reverse(cell)) = queue.pop() {
+        for &(_, row_offset, column_offset) in &D8_DIRECTIONS {
+            let Some(neighbor) = dem.neighbor(cell.index, row_offset, column_offset) else {
+                continue;
+            };
+            if closed[neighbor] {
+                continue;
+            }
+            let Some(elevation) = filled[neighbor] else {
+                continue;
+            };
+
+            let elevation = elevation.max(cell.elevation + min_drop);
+            filled[neighbor] = Some(elevation);
+            closed[neighbor] = true;
+
+            queue.push(Reverse(QueueEntry {
+                elevation,
+                order,
+                index: neighbor,
+            }));
+            order += 1;
+        }
+    }
+
+    filled
+}
+
+/// Computes the D8 flow direction of each cell, i.e., the direction of the steepest descent.
+///
+/// Cells without a lower neighbor drain out of the grid if they are outlets and get the code `0` otherwise.
+pub fn flow_direction(dem: &ValueGrid, pixel_size_x: f64, pixel_size_y: f64) -> Vec<Option<f64>> {
+    let diagonal = pixel_size_x.hypot(pixel_size_y);
+
+    (0..dem.values.len())
+        .map(|index| {
+            let elevation = dem.value(index)?;
+
+            let mut steepest: Option<(u8, f64)> = None;
+            let mut outflow: Option<u8> = None;
+
+            for &(code, row_offset, column_offset) in &D8_DIRECTIONS {
+                let neighbor_elevation = dem
+                    .neighbor(index, row_offset, column_offset)
+                    .and_then(|neighbor| dem.value(neighbor));
+
+                let Some(neighbor_elevation) = neighbor_elevation else {
+                    outflow = outflow.or(Some(code));
+                    continue;
+                };
+
+                let distance = match (row_offset, column_offset) {
+                    (0, _) => pixel_size_x,
+                    (_, 0) => pixel_size_y,
+                    _ => diagonal,
+                };
+                let slope = (elevation - neighbor_elevation) / distance;
+
+                if slope > 0. && steepest.map_or(true, |(_, max_slope)| slope > max_slope) {
+                    steepest = Some((code, slope));
+                }
+            }
+
+            let code = steepest.map(|(code, _)| code).or(outflow).unwrap_or(0);
+
+            Some(f64::from(code))
+        })
+        .collect()
+}
+
+/// Computes for each cell the number of upstream cells that drain through it.
+///
+/// Cells with an invalid flow direction do not pass on their flow.
+/// Cells within a cycle of flow directions get no value.
+pub fn flow_accumulation(directions: &ValueGrid) -> Vec<Option<f64>> {
+    let downstream: Vec<Option<usize>> = (0..directions.values.len())
+        .map(|index| {
+            let code = directions.value(index)?;
+
+            let &(_, row_offset, column_offset) = D8_DIRECTIONS
+                .iter()
+                .find(|&&(direction, _, _)| (f64::from(direction) - code).abs() < f64::EPSILON)?;
+
+            directions
+                .neighbor(index, row_offset, column_offset)
+                .filter(|&neighbor| directions.value(neighbor).is_some())
+        })
+        .collect();
+
+    let mut upstream_count = vec![0_usize; downstream.len()];
+    for &target in downstream.iter().flatten() {
+        upstream_count[target] += 1;
+    }
+
+    let mut accumulation = vec![0.; downstream.len()];
+    let mut visited = vec![false; downstream.len()];
+
+    // process the cells in topological order, starting at the sources of the flow
+    let mut queue: VecDeque<usize> = (0..downstream.len())
+        .filter(|&index| directions.value(index).is_some() && upstream_count[index] == 0)
+        .collect();
+
+    while let Some(index) = queue.pop_front() {
+        visited[index] = true;
+
+        let Some(target) = downstream[index] else {
+            continue;
+        };
+
+        accumulation[target] += accumulation[index] + 1.;
+        upstream_count[target] -= 1;
+        if upstream_count[target] == 0 {
+            queue.push_back(target);
+        }
+    }
+
+    accumulation
+        .into_iter()
+        .zip(visited)
+        .map(|(accumulation, visited)| visited.then_some(accumulation))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_fills_sinks() {
+        #[rustfmt::skip]
+        let dem = ValueGrid::new(4, 4, [
+            5., 5., 5., 5.,
+            5., 1., 2., 5.,
+            5., 2., 3., 5.,
+            5., 5., 4., 5.,
+        ].into_iter().map(Some).collect());
+
+        #[rustfmt::skip]
+        assert_eq!(fill_sinks(&dem, 0.), [
+            5., 5., 5., 5.,
+            5., 4., 4., 5.,
+            5., 4., 4., 5.,
+            5., 5., 4., 5.,
+        ].into_iter().map(Some).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn it_computes_flow_directions_and_accumulation() {
+        #[rustfmt::skip]
+        let dem = ValueGrid::new(3, 3, [
+            9., 8., 7.,
+            8., 6., 4.,
+            7., 5., 1.,
+        ].into_iter().map(Some).collect());
+
+        let directions = flow_direction(&dem, 1., 1.);
+
+        #[rustfmt::skip]
+        assert_eq!(directions, [
+            2., 2., 4.,
+            2., 2., 4.,
+            1., 1., 1.,
+        ].into_iter().map(Some).collect::<Vec<_>>());
+
+        let accumulation = flow_accumulation(&ValueGrid::new(3, 3, directions));
+
+        #[rustfmt::skip]
+        assert_eq!(accumulation, [
+            0., 0., 0.,
+            0., 1., 2.,
+            0., 2., 8.,
+        ].into_iter().map(Some).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn it_ignores_cycles() {
+        let directions = ValueGrid::new(2, 1, vec![Some(1.), Some(16.)]);
+
+        assert_eq!(flow_accumulation(&directions), vec![None, None]);
+    }
+}
@@ -0,0 +1,438 @@
+mod algorithms;
+mod processor;
+
+use self::processor::{HydrologyAlgorithm, HydrologyProcessor};
+use crate::engine::{
+    CanonicOperatorName, ExecutionContext, InitializedRasterOperator, InitializedSources, Operator,
+    OperatorName, RasterBandDescriptor, RasterBandDescriptors, RasterOperator,
+    RasterQueryProcessor, RasterResultDescriptor, SingleRasterSource, TypedRasterQueryProcessor,
+    WorkflowOperatorPath,
+};
+use crate::util::Result;
+use async_trait::async_trait;
+use geoengine_datatypes::primitives::{Measurement, SpatialPartition2D};
+use geoengine_datatypes::raster::{Pixel, RasterDataType, TilingSpecification};
+use serde::{Deserialize, Serialize};
+use snafu::{ensure, Snafu};
+use std::collections::HashMap;
+
+/// The `FillSinks` operator fills the depressions of a digital elevation model, so that every
+/// cell drains to the border of the extent or to a cell without data.
+///
+/// Like all hydrology operators, it computes the whole `extent` at once. Without an `extent`, the
+/// query bounds are used, so that results may differ at the borders of different queries.
+pub type FillSinks = Operator<FillSinksParams, SingleRasterSource>;
+
+impl OperatorName for FillSinks {
+    const TYPE_NAME: &'static str = "FillSinks";
+}
+
+/// The `FlowDirection` operator computes the D8 flow direction of a (filled) digital elevation model.
+///
+/// The directions are encoded as `1` (east), `2` (south-east), `4` (south), `8` (south-west),
+/// `16` (west), `32` (north-west), `64` (north) and `128` (north-east).
+/// Cells without a lower neighbor that are not at the border of the data get the code `0`.
+pub type FlowDirection = Operator<FlowDirectionParams, SingleRasterSource>;
+
+impl OperatorName for FlowDirection {
+    const TYPE_NAME: &'static str = "FlowDirection";
+}
+
+/// The `FlowAccumulation` operator computes for each cell the number of upstream cells from a
+/// raster of D8 flow directions.
+pub type FlowAccumulation = Operator<FlowAccumulationParams, SingleRasterSource>;
+
+impl OperatorName for FlowAccumulation {
+    const TYPE_NAME: &'static str = "FlowAccumulation";
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FillSinksParams {
+    /// The minimal increase of elevation along filled cells, so that filled areas are not flat
+    #[serde(default)]
+    pub min_drop: f64,
+    /// The extent that is computed at once
+    pub extent: Option<SpatialPartition2D>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FlowDirectionParams {
+    /// The extent that is computed at once
+    pub extent: Option<SpatialPartition2D>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FlowAccumulationParams {
+    /// The extent that is computed at once
+    pub extent: Option<SpatialPartition2D>,
+}
+
+#[derive(Debug, Snafu)]
+#[snafu(visibility(pub(crate)), context(suffix(false)), module(error))]
+pub enum HydrologyError {
+    #[snafu(display("The raster must have a single band"))]
+    RasterMustHaveSingleBand,
+
+    #[snafu(display("The minimal drop must be non-negative and finite"))]
+    InvalidMinDrop,
+
+    #[snafu(display("The flow directions must be of type U8, but are of type {found:?}"))]
+    FlowDirectionsMustBeU8 { found: RasterDataType },
+
+    #[snafu(display("The extent covers {pixels} pixels, but at most {max} pixels are allowed"))]
+    ExtentTooLarge { pixels: usize, max: usize },
+}
+
+async fn initialize_source(
+    sources: SingleRasterSource,
+    path: WorkflowOperatorPath,
+    context: &dyn ExecutionContext,
+) -> Result<Box<dyn InitializedRasterOperator>> {
+    let source = sources.initialize_sources(path, context).await?.raster;
+
+    ensure!(
+        source.result_descriptor().bands.len() == 1,
+        error::RasterMustHaveSingleBand
+    );
+
+    Ok(source)
+}
+
+fn single_band_descriptor(
+    in_desc: &RasterResultDescriptor,
+    data_type: RasterDataType,
+    measurement: Measurement,
+) -> Result<RasterResultDescriptor> {
+    Ok(RasterResultDescriptor {
+        data_type,
+        spatial_reference: in_desc.spatial_reference,
+        time: in_desc.time,
+        bbox: in_desc.bbox,
+        resolution: in_desc.resolution,
+        bands: RasterBandDescriptors::new(vec![RasterBandDescriptor::new(
+            in_desc.bands[0].name.clone(),
+            measurement,
+        )])?,
+    })
+}
+
+#[typetag::serde]
+#[async_trait]
+impl RasterOperator for FillSinks {
+    async fn _initialize(
+        self: Box<Self>,
+        path: WorkflowOperatorPath,
+        context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedRasterOperator>> {
+        let name = CanonicOperatorName::from(&self);
+
+        let min_drop = self.params.min_drop;
+        ensure!(
+            min_drop.is_finite() && min_drop >= 0.,
+            error::InvalidMinDrop
+        );
+
+        let source = initialize_source(self.sources, path, context).await?;
+
+        Ok(InitializedHydrology {
+            name,
+            result_descriptor: source.result_descriptor().clone(),
+            source,
+            tiling_specification: context.tiling_specification(),
+            extent: self.params.extent,
+            algorithm: HydrologyAlgorithm::FillSinks { min_drop },
+        }
+        .boxed())
+    }
+
+    span_fn!(FillSinks);
+}
+
+#[typetag::serde]
+#[async_trait]
+impl RasterOperator for FlowDirection {
+    async fn _initialize(
+        self: Box<Self>,
+        path: WorkflowOperatorPath,
+        context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedRasterOperator>> {
+        let name = CanonicOperatorName::from(&self);
+
+        let source = initialize_source(self.sources, path, context).await?;
+
+        let classes = [
+            (0, "none"),
+            (1, "east"),
+            (2, "south-east"),
+            (4, "south"),
+            (8, "south-west"),
+            (16, "west"),
+            (32, "north-west"),
+            (64, "north"),
+            (128, "north-east"),
+        ]
+        .into_iter()
+        .map(|(code, name)| (code, name.to_string()))
+        .collect::<HashMap<u8, String>>();
+
+        let result_descriptor = single_band_descriptor(
+            source.result_descriptor(),
+            RasterDataType::U8,
+            Measurement::classification("flow direction".to_string(), classes),
+        )?;
+
+        Ok(InitializedHydrology {
+            name,
+            result_descriptor,
+            source,
+            tiling_specification: context.tiling_specification(),
+            extent: self.params.extent,
+            algorithm: HydrologyAlgorithm::FlowDirection,
+        }
+        .boxed())
+    }
+
+    span_fn!(FlowDirection);
+}
+
+#[typetag::serde]
+#[async_trait]
+impl RasterOperator for FlowAccumulation {
+    async fn _initialize(
+        self: Box<Self>,
+        path: WorkflowOperatorPath,
+        context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedRasterOperator>> {
+        let name = CanonicOperatorName::from(&self);
+
+        let source = initialize_source(self.sources, path, context).await?;
+
+        let data_type = source.result_descriptor().data_type;
+        ensure!(
+            data_type == RasterDataType::U8,
+            error::FlowDirectionsMustBeU8 { found: data_type }
+        );
+
+        let result_descriptor = single_band_descriptor(
+            source.result_descriptor(),
+            RasterDataType::U32,
+            Measurement::continuous("flow accumulation".to_string(), Some("cells".to_string())),
+        )?;
+
+        Ok(InitializedHydrology {
+            name,
+            result_descriptor,
+            source,
+            tiling_specification: context.tiling_specification(),
+            extent: self.params.extent,
+            algorithm: HydrologyAlgorithm::FlowAccumulation,
+        }
+        .boxed())
+    }
+
+    span_fn!(FlowAccumulation);
+}
+
+pub struct InitializedHydrology {
+    name: CanonicOperatorName,
+    result_descriptor: RasterResultDescriptor,
+    source: Box<dyn InitializedRasterOperator>,
+    tiling_specification: TilingSpecification,
+    extent: Option<SpatialPartition2D>,
+    algorithm: HydrologyAlgorithm,
+}
+
+impl InitializedHydrology {
+    /// Creates a processor whose output has the data type of its input
+    fn same_type_processor<T: Pixel>(
+        &self,
+        source: Box<dyn RasterQueryProcessor<RasterType = T>>,
+    ) -> Box<dyn RasterQueryProcessor<RasterType = T>> {
+        HydrologyProcessor::new(
+            source,
+            self.result_descriptor.clone(),
+            self.tiling_specification,
+            self.extent,
+            self.algorithm,
+        )
+        .boxed()
+    }
+}
+
+impl InitializedRasterOperator for InitializedHydrology {
+    fn query_processor(&self) -> Result<TypedRasterQueryProcessor> {
+        let source_processor = self.source.query_processor()?;
+
+        Ok(match self.algorithm {
+            HydrologyAlgorithm::FillSinks { .. } => call_on_generic_raster_processor!(
+                source_processor, p => self.same_type_processor(p).into()
+            ),
+            HydrologyAlgorithm::FlowDirection => {
+                TypedRasterQueryProcessor::U8(call_on_generic_raster_processor!(
+                    source_processor, p => HydrologyProcessor::<_, u8>::new(
+                        p,
+                        self.result_descriptor.clone(),
+                        self.tiling_specification,
+                        self.extent,
+                        self.algorithm,
+                    ).boxed()
+                ))
+            }
+            HydrologyAlgorithm::FlowAccumulation => {
+                TypedRasterQueryProcessor::U32(call_on_generic_raster_processor!(
+                    source_processor, p => HydrologyProcessor::<_, u32>::new(
+                        p,
+                        self.result_descriptor.clone(),
+                        self.tiling_specification,
+                        self.extent,
+                        self.algorithm,
+                    ).boxed()
+                ))
+            }
+        })
+    }
+
+    fn result_descriptor(&self) -> &RasterResultDescriptor {
+        &self.result_descriptor
+    }
+
+    fn canonic_name(&self) -> CanonicOperatorName {
+        self.name.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{MockExecutionContext, MockQueryContext};
+    use crate::mock::{MockRasterSource, MockRasterSourceParams};
+    use futures::TryStreamExt;
+    use geoengine_datatypes::primitives::{
+        BandSelection, CacheHint, RasterQueryRectangle, SpatialResolution, TimeInterval,
+    };
+    use geoengine_datatypes::raster::{
+        Grid2D, GridOrEmpty, RasterTile2D, TileInformation, TilingSpecification,
+    };
+    use geoengine_datatypes::spatial_reference::SpatialReference;
+    use geoengine_datatypes::util::test::TestDefault;
+
+    fn dem() -> Box<dyn RasterOperator> {
+        let tile = |position: [isize; 2], values: Vec<f32>| {
+            RasterTile2D::new_with_tile_info(
+                TimeInterval::default(),
+                TileInformation {
+                    global_geo_transform: TestDefault::test_default(),
+                    global_tile_position: position.into(),
+                    tile_size_in_pixels: [2, 2].into(),
+                },
+                0,
+                Grid2D::new([2, 2].into(), values).unwrap().into(),
+                CacheHint::default(),
+            )
+        };
+
+        MockRasterSource {
+            params: MockRasterSourceParams {
+                data: vec![
+                    tile([0, 0], vec![9., 8., 9., 7.]),
+                    tile([0, 1], vec![6., 5., 4., 2.]),
+                ],
+                result_descriptor: RasterResultDescriptor {
+                    data_type: RasterDataType::F32,
+                    spatial_reference: SpatialReference::epsg_4326().into(),
+                    time: None,
+                    bbox: None,
+                    resolution: None,
+                    bands: RasterBandDescriptors::new_single_band(),
+                },
+            },
+        }
+        .boxed()
+    }
+
+    #[tokio::test]
+    async fn it_accumulates_flow_across_tiles() {
+        let execution_context = MockExecutionContext::new_with_tiling_spec(TilingSpecification {
+            origin_coordinate: [0.0, 0.0].into(),
+            tile_size_in_pixels: [2, 2].into(),
+        });
+
+        let operator = FlowAccumulation {
+            params: FlowAccumulationParams { extent: None },
+            sources: SingleRasterSource {
+                raster: FlowDirection {
+                    params: FlowDirectionParams { extent: None },
+                    sources: SingleRasterSource {
+                        raster: FillSinks {
+                            params: FillSinksParams {
+                                min_drop: 0.,
+                                extent: None,
+                            },
+                            sources: SingleRasterSource { raster: dem() },
+                        }
+                        .boxed(),
+                    },
+                }
+                .boxed(),
+            },
+        }
+        .boxed()
+        .initialize(WorkflowOperatorPath::initialize_root(), &execution_context)
+        .await
+        .unwrap();
+
+        let processor = operator.query_processor().unwrap().get_u32().unwrap();
+
+        let tiles: Vec<RasterTile2D<u32>> = processor
+            .raster_query(
+                RasterQueryRectangle {
+                    spatial_bounds: SpatialPartition2D::new((0., 0.).into(), (4., -2.).into())
+                        .unwrap(),
+                    time_interval: TimeInterval::default(),
+                    spatial_resolution: SpatialResolution::one(),
+                    attributes: BandSelection::first(),
+                },
+                &MockQueryContext::test_default(),
+            )
+            .await
+            .unwrap()
+            .try_collect()
+            .await
+            .unwrap();
+
+        let values: Vec<Vec<Option<u32>>> = tiles
+            .iter()
+            .map(|tile| match &tile.grid_array {
+                GridOrEmpty::Grid(grid) => grid.masked_element_deref_iterator().collect(),
+                GridOrEmpty::Empty(_) => vec![None; 4],
+            })
+            .collect();
+
+        // the flow runs from the western tile to the lowest cell in the south-east
+        assert_eq!(
+            values,
+            vec![
+                vec![Some(0), Some(0), Some(0), Some(2)],
+                vec![Some(0), Some(0), Some(4), Some(7)],
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn it_requires_u8_flow_directions() {
+        let execution_context = MockExecutionContext::test_default();
+
+        let result = FlowAccumulation {
+            params: FlowAccumulationParams { extent: None },
+            sources: SingleRasterSource { raster: dem() },
+        }
+        .boxed()
+        .initialize(WorkflowOperatorPath::initialize_root(), &execution_context)
+        .await;
+
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,355 @@
+use super::algorithms::{fill_sinks, flow_accumulation, flow_direction, ValueGrid};
+use super::error;
+use crate::engine::{QueryContext, RasterQueryProcessor, RasterResultDescriptor};
+use crate::util::{spawn_blocking_with_thread_pool, Result};
+use async_trait::async_trait;
+use futures::stream::BoxStream;
+use futures::{stream, StreamExt, TryStreamExt};
+use geoengine_datatypes::primitives::{
+    BandSelection, CacheHint, RasterQueryRectangle, SpatialPartition2D, TimeInterval,
+};
+use geoengine_datatypes::raster::{
+    EmptyGrid2D, FromPrimitive, GeoTransform, Grid2D, GridIdx, GridOrEmpty, GridSize, MaskedGrid2D,
+    Pixel, RasterTile2D, TileInformation, TilingSpecification,
+};
+use num_traits::{AsPrimitive, Zero};
+use snafu::ensure;
+use std::marker::PhantomData;
+
+/// The maximum number of pixels of the extent of a hydrological computation
+const MAX_PIXELS: usize = 4096 * 4096;
+
+/// The computation that is applied to the whole extent at once
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HydrologyAlgorithm {
+    FillSinks { min_drop: f64 },
+    FlowDirection,
+    FlowAccumulation,
+}
+
+impl HydrologyAlgorithm {
+    fn compute(self, grid: &ValueGrid, pixel_size_x: f64, pixel_size_y: f64) -> Vec<Option<f64>> {
+        match self {
+            Self::FillSinks { min_drop } => fill_sinks(grid, min_drop),
+            Self::FlowDirection => flow_direction(grid, pixel_size_x, pixel_size_y),
+            Self::FlowAccumulation => flow_accumulation(grid),
+        }
+    }
+}
+
+/// A processor that assembles the source tiles of the whole extent into one grid per time step,
+/// applies the hydrological algorithm and splits the result into the queried tiles.
+///
+/// The extent is either fixed or the query bounds. A fixed extent leads to the same results
+/// for every query but requires computing the whole extent for each query.
+pub struct HydrologyProcessor<In, Out> {
+    source: Box<dyn RasterQueryProcessor<RasterType = In>>,
+    result_descriptor: RasterResultDescriptor,
+    tiling_specification: TilingSpecification,
+    extent: Option<SpatialPartition2D>,
+    algorithm: HydrologyAlgorithm,
+    _out: PhantomData<Out>,
+}
+
+impl<In, Out> HydrologyProcessor<In, Out> {
+    pub fn new(
+        source: Box<dyn RasterQueryProcessor<RasterType = In>>,
+        result_descriptor: RasterResultDescriptor,
+        tiling_specification: TilingSpecification,
+        extent: Option<SpatialPartition2D>,
+        algorithm: HydrologyAlgorithm,
+    ) -> Self {
+        Self {
+            source,
+            result_descriptor,
+            tiling_specification,
+            extent,
+            algorithm,
+            _out: PhantomData,
+        }
+    }
+}
+
+/// The global pixel bounds of the extent of the computation
+#[derive(Debug, Clone, Copy)]
+struct PixelBounds {
+    upper_left: [isize; 2],
+    height: usize,
+    width: usize,
+}
+
+impl PixelBounds {
+    fn new(geo_transform: &GeoTransform, extent: &SpatialPartition2D) -> Result<Self> {
+        let GridIdx([min_y, min_x]) = geo_transform.upper_left_pixel_idx(extent);
+        let GridIdx([max_y, max_x]) = geo_transform.lower_right_pixel_idx(extent);
+
+        let height = (max_y - min_y + 1) as usize;
+        let width = (max_x - min_x + 1) as usize;
+
+        ensure!(
+            width
+                .checked_mul(height)
+                .is_some_and(|pixels| pixels <= MAX_PIXELS),
+            error::ExtentTooLarge {
+                pixels: width.saturating_mul(height),
+                max: MAX_PIXELS
+            }
+        );
+
+        Ok(Self {
+            upper_left: [min_y, min_x],
+            height,
+            width,
+        })
+    }
+
+    fn spatial_partition(&self, geo_transform: &GeoTransform) -> SpatialPartition2D {
+        let [y, x] = self.upper_left;
+        SpatialPartition2D::new_unchecked(
+            geo_transform.grid_idx_to_pixel_upper_left_coordinate_2d([y, x].into()),
+            geo_transform.grid_idx_to_pixel_upper_left_coordinate_2d(
+                [y + self.height as isize, x + self.width as isize].into(),
+            ),
+        )
+    }
+
+    /// The index within the extent of a global pixel index
+    fn local_index(&self, global: [isize; 2]) -> Option<usize> {
+        let y = global[0] - self.upper_left[0];
+        let x = global[1] - self.upper_left[1];
+
+        if y < 0 || x < 0 || y >= self.height as isize || x >= self.width as isize {
+            return None;
+        }
+
+        Some(y as usize * self.width + x as usize)
+    }
+}
+
+/// All tiles of one time step
+struct TimeStep<T> {
+    time: TimeInterval,
+    tiles: Vec<RasterTile2D<T>>,
+}
+
+type RasterTileStream<'a, T> = BoxStream<'a, Result<RasterTile2D<T>>>;
+
+/// Collects the tiles of the next time step from the stream.
+///
+/// Tiles are ordered by time, so consecutive tiles with the same time form a time step.
+/// The first tile of the following time step is returned as lookahead.
+#[allow(clippy::type_complexity)]
+async fn next_time_step<T: Pixel>(
+    mut tiles: RasterTileStream<'_, T>,
+    lookahead: Option<RasterTile2D<T>>,
+) -> Result<
+    Option<(
+        TimeStep<T>,
+        (RasterTileStream<'_, T>, Option<RasterTile2D<T>>),
+    )>,
+> {
+    let first = match lookahead {
+        Some(first) => first,
+        None => match tiles.try_next().await? {
+            Some(first) => first,
+            None => return Ok(None),
+        },
+    };
+
+    let mut time_step = TimeStep {
+        time: first.time,
+        tiles: vec![first],
+    };
+
+    while let Some(tile) = tiles.try_next().await? {
+        if tile.time != time_step.time {
+            return Ok(Some((time_step, (tiles, Some(tile)))));
+        }
+
+        time_step.tiles.push(tile);
+    }
+
+    Ok(Some((time_step, (tiles, None))))
+}
+
+#[async_trait]
+impl<In, Out> RasterQueryProcessor for HydrologyProcessor<In, Out>
+where
+    In: Pixel,
+    Out: Pixel,
+{
+    type RasterType = Out;
+
+    async fn raster_query<'a>(
+        &'a self,
+        query: RasterQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<BoxStream<'a, Result<RasterTile2D<Out>>>> {
+        let tiling_strategy = self
+            .tiling_specification
+            .strategy(query.spatial_resolution.x, -query.spatial_resolution.y);
+        let geo_transform = tiling_strategy.geo_transform;
+
+        let pixel_bounds =
+            PixelBounds::new(&geo_transform, &self.extent.unwrap_or(query.spatial_bounds))?;
+
+        let source_query = RasterQueryRectangle {
+            spatial_bounds: pixel_bounds.spatial_partition(&geo_transform),
+            time_interval: query.time_interval,
+            spatial_resolution: query.spatial_resolution,
+            attributes: BandSelection::first(),
+        };
+
+        let tiles = self.source.raster_query(source_query, ctx).await?;
+
+        let time_steps = stream::try_unfold((tiles, None), |(tiles, lookahead)| {
+            next_time_step(tiles, lookahead)
+        });
+
+        let output_tiles: Vec<TileInformation> = tiling_strategy
+            .tile_information_iterator(query.spatial_bounds)
+            .collect();
+
+        let algorithm = self.algorithm;
+        let pixel_size_x = query.spatial_resolution.x;
+        let pixel_size_y = query.spatial_resolution.y;
+
+        let tiles = time_steps
+            .and_then(move |time_step| {
+                let output_tiles = output_tiles.clone();
+                async move {
+                    spawn_blocking_with_thread_pool(ctx.thread_pool().clone(), move || {
+                        let grid = assemble_grid(&time_step.tiles, pixel_bounds);
+                        let result = algorithm.compute(&grid, pixel_size_x, pixel_size_y);
+                        let cache_hint = time_step
+                            .tiles
+                            .iter()
+                            .fold(CacheHint::max_duration(), |cache_hint, tile| {
+                                cache_hint.merged(&tile.cache_hint)
+                            });
+
+                        output_tiles
+                            .into_iter()
+                            .map(|tile_info| {
+                                split_tile(
+                                    &result,
+                                    pixel_bounds,
+                                    tile_info,
+                                    time_step.time,
+                                    cache_hint,
+                                )
+                            })
+                            .collect::<Result<Vec<_>>>()
+                    })
+                    .await?
+                }
+            })
+            .map(|tiles| match tiles {
+                Ok(tiles) => stream::iter(tiles.into_iter().map(Ok)).boxed(),
+                Err(error) => stream::once(async { Err(error) }).boxed(),
+            })
+            .flatten();
+
+        Ok(tiles.boxed())
+    }
+
+    fn raster_result_descriptor(&self) -> &RasterResultDescriptor {
+        &self.result_descriptor
+    }
+}
+
+/// Copies the valid pixels of the tiles into a grid that covers the extent
+fn assemble_grid<T: Pixel>(tiles: &[RasterTile2D<T>], pixel_bounds: PixelBounds) -> ValueGrid {
+    let mut values = vec![None; pixel_bounds.width * pixel_bounds.height];
+
+    for tile in tiles {
+        let GridOrEmpty::Grid(grid) = &tile.grid_array else {
+            continue;
+        };
+
+        let GridIdx([tile_y, tile_x]) = tile.tile_information().global_upper_left_pixel_idx();
+        let [_, tile_width] = tile.grid_shape_array();
+
+        for (index, value) in grid.masked_element_deref_iterator().enumerate() {
+            let global = [
+                tile_y + (index / tile_width) as isize,
+                tile_x + (index % tile_width) as isize,
+            ];
+
+            if let Some(local_index) = pixel_bounds.local_index(global) {
+                values[local_index] = value.map(AsPrimitive::<f64>::as_);
+            }
+        }
+    }
+
+    ValueGrid::new(pixel_bounds.width, pixel_bounds.height, values)
+}
+
+/// Extracts the pixels of an output tile from the result of the computation.
+/// Pixels outside of the extent are empty.
+fn split_tile<T: Pixel>(
+    result: &[Option<f64>],
+    pixel_bounds: PixelBounds,
+    tile_info: TileInformation,
+    time: TimeInterval,
+    cache_hint: CacheHint,
+) -> Result<RasterTile2D<T>> {
+    let tile_shape = tile_info.tile_size_in_pixels;
+    let tile_height = tile_shape.axis_size_y();
+    let tile_width = tile_shape.axis_size_x();
+    let GridIdx([tile_y, tile_x]) = tile_info.global_upper_left_pixel_idx();
+
+    let values: Vec<Option<T>> = (0..tile_height * tile_width)
+        .map(|index| {
+            let global = [
+                tile_y + (index / tile_width) as isize,
+                tile_x + (index % tile_width) as isize,
+            ];
+
+            pixel_bounds
+                .local_index(global)
+                .and_then(|local_index| result[local_index])
+                .map(<T as FromPrimitive<f64>>::from_)
+        })
+        .collect();
+
+    let grid = if values.iter().any(Option::is_some) {
+        MaskedGrid2D::new(
+            Grid2D::new(
+                tile_shape,
+                values.iter().map(|v| v.unwrap_or_else(T::zero)).collect(),
+            )?,
+            Grid2D::new(tile_shape, values.iter().map(Option::is_some).collect())?,
+        )?
+        .into()
+    } else {
+        EmptyGrid2D::new(tile_shape).into()
+    };
+
+    Ok(RasterTile2D::new_with_tile_info(
+        time, tile_info, 0, grid, cache_hint,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::Error;
+    use crate::processing::HydrologyError;
+
+    #[test]
+    fn it_rejects_extents_whose_pixel_count_overflows() {
+        let geo_transform = GeoTransform::new((0., 0.).into(), 1e-10, -1e-10);
+        let extent = SpatialPartition2D::new_unchecked((-1e3, 1e3).into(), (1e3, -1e3).into());
+
+        assert!(matches!(
+            PixelBounds::new(&geo_transform, &extent),
+            Err(Error::Hydrology {
+                source: HydrologyError::ExtentTooLarge {
+                    pixels: usize::MAX,
+                    max: MAX_PIXELS
+                }
+            })
+        ));
+    }
+}
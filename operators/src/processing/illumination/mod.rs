@@ -0,0 +1,792 @@
+mod terrain;
+mod tile_sub_query;
+
+use self::terrain::{Correction, SunAngles};
+use self::tile_sub_query::IlluminationTileNeighborhood;
+use super::buffer::geometry::EARTH_RADIUS;
+use crate::adapters::{stack_individual_aligned_raster_bands, RasterSubQueryAdapter};
+use crate::engine::{
+    CanonicOperatorName, ExecutionContext, InitializedRasterOperator, InitializedSources, Operator,
+    OperatorData, OperatorName, QueryContext, QueryProcessor, RasterBandDescriptor,
+    RasterBandDescriptors, RasterOperator, RasterQueryProcessor, RasterResultDescriptor,
+    SingleRasterSource, TypedRasterQueryProcessor, WorkflowOperatorPath,
+};
+use crate::processing::RasterTypeConversionQueryProcessor;
+use crate::util::sunpos::SunPos;
+use crate::util::{spawn_blocking_with_thread_pool, Result};
+use async_trait::async_trait;
+use futures::stream::BoxStream;
+use futures::{StreamExt, TryStreamExt};
+use geoengine_datatypes::dataset::NamedData;
+use geoengine_datatypes::operations::reproject::{CoordinateProjection, CoordinateProjector};
+use geoengine_datatypes::primitives::{
+    AxisAlignedRectangle, BandSelection, Coordinate2D, Measurement, RasterQueryRectangle,
+    SpatialPartition2D, SpatialPartitioned, TimeInterval,
+};
+use geoengine_datatypes::raster::{
+    EmptyGrid2D, GeoTransform, Grid2D, GridOrEmpty, GridSize, MaskedGrid2D, Pixel, RasterDataType,
+    RasterTile2D, TilingSpecification,
+};
+use geoengine_datatypes::spatial_reference::{SpatialReference, SpatialReferenceOption};
+use num_traits::AsPrimitive;
+use serde::{Deserialize, Serialize};
+use snafu::{ensure, Snafu};
+
+/// The `Hillshade` operator computes the illumination of a digital elevation model by the sun.
+///
+/// The output is the cosine of the angle between the sun and the surface normal, from `0` for
+/// surfaces in shadow to `1` for surfaces that face the sun.
+/// Slope and aspect are derived from the 3x3 neighborhood of each pixel with Horn's method.
+///
+/// The sun is either at a fixed position or at its actual position at the start of the time
+/// interval of each tile, computed at the center of the tile.
+pub type Hillshade = Operator<HillshadeParams, SingleRasterSource>;
+
+impl OperatorName for Hillshade {
+    const TYPE_NAME: &'static str = "Hillshade";
+}
+
+/// The `IlluminationCorrection` operator applies a topographic correction to optical imagery.
+///
+/// Each band of the raster is corrected with the illumination computed from the digital elevation
+/// model like in the `Hillshade` operator.
+/// Pixels that are too dark to be corrected reliably become no data.
+pub type IlluminationCorrection =
+    Operator<IlluminationCorrectionParams, IlluminationCorrectionSources>;
+
+impl OperatorName for IlluminationCorrection {
+    const TYPE_NAME: &'static str = "IlluminationCorrection";
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct HillshadeParams {
+    pub sun: SunPosition,
+    /// A factor to convert the elevations into the units of the horizontal pixel size
+    #[serde(default = "default_z_factor")]
+    pub z_factor: f64,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct IlluminationCorrectionParams {
+    pub sun: SunPosition,
+    pub method: CorrectionMethod,
+    /// A factor to convert the elevations into the units of the horizontal pixel size
+    #[serde(default = "default_z_factor")]
+    pub z_factor: f64,
+}
+
+fn default_z_factor() -> f64 {
+    1.
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub enum SunPosition {
+    /// The sun at a fixed azimuth (clockwise from north) and elevation in degrees
+    Fixed { azimuth: f64, elevation: f64 },
+    /// The sun at its position at the start of the time interval of each tile
+    Actual,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub enum CorrectionMethod {
+    /// The cosine correction, which tends to overcorrect weakly illuminated slopes
+    Cosine,
+    /// The C-correction with a constant `c`, usually derived from the regression of the band
+    /// against the illumination
+    C { c: f64 },
+}
+
+impl From<CorrectionMethod> for Correction {
+    fn from(method: CorrectionMethod) -> Self {
+        match method {
+            CorrectionMethod::Cosine => Self::Cosine,
+            CorrectionMethod::C { c } => Self::C { c },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IlluminationCorrectionSources {
+    pub raster: Box<dyn RasterOperator>,
+    pub dem: Box<dyn RasterOperator>,
+}
+
+impl OperatorData for IlluminationCorrectionSources {
+    fn data_names_collect(&self, data_names: &mut Vec<NamedData>) {
+        self.raster.data_names_collect(data_names);
+        self.dem.data_names_collect(data_names);
+    }
+}
+
+struct InitializedIlluminationCorrectionSources {
+    raster: Box<dyn InitializedRasterOperator>,
+    dem: Box<dyn InitializedRasterOperator>,
+}
+
+#[async_trait]
+impl InitializedSources<InitializedIlluminationCorrectionSources>
+    for IlluminationCorrectionSources
+{
+    async fn initialize_sources(
+        self,
+        path: WorkflowOperatorPath,
+        context: &dyn ExecutionContext,
+    ) -> Result<InitializedIlluminationCorrectionSources> {
+        Ok(InitializedIlluminationCorrectionSources {
+            raster: self
+                .raster
+                .initialize(path.clone_and_append(0), context)
+                .await?,
+            dem: self
+                .dem
+                .initialize(path.clone_and_append(1), context)
+                .await?,
+        })
+    }
+}
+
+#[derive(Debug, Snafu)]
+#[snafu(visibility(pub(crate)), context(suffix(false)), module(error))]
+pub enum IlluminationError {
+    #[snafu(display("The digital elevation model must have a single band"))]
+    DemMustHaveSingleBand,
+
+    #[snafu(display(
+        "The raster and the digital elevation model must have the same spatial reference"
+    ))]
+    SpatialReferenceMismatch,
+
+    #[snafu(display("The actual sun position requires a spatial reference"))]
+    ActualSunRequiresSpatialReference,
+
+    #[snafu(display("The sun elevation must be between 0 and 90 degrees"))]
+    InvalidSunElevation,
+
+    #[snafu(display("The z-factor must be positive and finite"))]
+    InvalidZFactor,
+
+    #[snafu(display("The time {time} has no valid timestamp for the sun position"))]
+    InvalidTimestamp { time: TimeInterval },
+}
+
+/// Determines the sun position and the pixel sizes in meters for tiles in a spatial reference
+#[derive(Debug, Clone)]
+pub struct IlluminationModel {
+    sun: SunPosition,
+    spatial_reference: SpatialReferenceOption,
+}
+
+impl IlluminationModel {
+    fn new(
+        sun: SunPosition,
+        z_factor: f64,
+        spatial_reference: SpatialReferenceOption,
+    ) -> Result<Self, IlluminationError> {
+        ensure!(z_factor.is_finite() && z_factor > 0., error::InvalidZFactor);
+
+        match sun {
+            SunPosition::Fixed { elevation, .. } => {
+                ensure!((0. ..=90.).contains(&elevation), error::InvalidSunElevation);
+            }
+            SunPosition::Actual => {
+                ensure!(
+                    spatial_reference.is_spatial_ref(),
+                    error::ActualSunRequiresSpatialReference
+                );
+            }
+        }
+
+        Ok(Self {
+            sun,
+            spatial_reference,
+        })
+    }
+
+    /// The sun position at the start of `time` at the center of `bounds`
+    fn sun_angles(&self, time: TimeInterval, bounds: SpatialPartition2D) -> Result<SunAngles> {
+        let SunPosition::Fixed { azimuth, elevation } = self.sun else {
+            return self.actual_sun_angles(time, bounds);
+        };
+
+        Ok(SunAngles {
+            azimuth,
+            zenith: 90. - elevation,
+        })
+    }
+
+    fn actual_sun_angles(
+        &self,
+        time: TimeInterval,
+        bounds: SpatialPartition2D,
+    ) -> Result<SunAngles> {
+        let SpatialReferenceOption::SpatialReference(spatial_reference) = self.spatial_reference
+        else {
+            return Err(IlluminationError::ActualSunRequiresSpatialReference.into());
+        };
+
+        let timestamp = time
+            .start()
+            .as_date_time()
+            .ok_or(IlluminationError::InvalidTimestamp { time })?;
+
+        let mut center = bounds_center(bounds);
+        if spatial_reference != SpatialReference::epsg_4326() {
+            let projector = CoordinateProjector::from_known_srs(
+                spatial_reference,
+                SpatialReference::epsg_4326(),
+            )?;
+            center = projector.project_coordinate(center)?;
+        }
+
+        let (azimuth, zenith) = SunPos::new(&timestamp).solar_azimuth_zenith(center.y, center.x);
+
+        Ok(SunAngles { azimuth, zenith })
+    }
+
+    /// The pixel sizes in meters for geographic coordinates and in coordinate units otherwise
+    fn pixel_size_in_meters(
+        &self,
+        geo_transform: &GeoTransform,
+        bounds: SpatialPartition2D,
+    ) -> (f64, f64) {
+        let dx = geo_transform.x_pixel_size().abs();
+        let dy = geo_transform.y_pixel_size().abs();
+
+        if self.spatial_reference == SpatialReference::epsg_4326().into() {
+            let meters_per_degree = EARTH_RADIUS.to_radians();
+            let latitude = bounds_center(bounds).y;
+            (
+                dx * meters_per_degree * latitude.to_radians().cos(),
+                dy * meters_per_degree,
+            )
+        } else {
+            (dx, dy)
+        }
+    }
+}
+
+fn bounds_center(bounds: SpatialPartition2D) -> Coordinate2D {
+    Coordinate2D::new(
+        (bounds.upper_left().x + bounds.lower_right().x) / 2.,
+        (bounds.upper_left().y + bounds.lower_right().y) / 2.,
+    )
+}
+
+#[typetag::serde]
+#[async_trait]
+impl RasterOperator for Hillshade {
+    async fn _initialize(
+        self: Box<Self>,
+        path: WorkflowOperatorPath,
+        context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedRasterOperator>> {
+        let name = CanonicOperatorName::from(&self);
+
+        let dem = self.sources.initialize_sources(path, context).await?.raster;
+        let in_desc = dem.result_descriptor();
+
+        ensure!(in_desc.bands.len() == 1, error::DemMustHaveSingleBand);
+
+        let model = IlluminationModel::new(
+            self.params.sun,
+            self.params.z_factor,
+            in_desc.spatial_reference,
+        )?;
+
+        let result_descriptor = RasterResultDescriptor {
+            data_type: RasterDataType::F32,
+            spatial_reference: in_desc.spatial_reference,
+            time: in_desc.time,
+            bbox: in_desc.bbox,
+            resolution: in_desc.resolution,
+            bands: RasterBandDescriptors::new(vec![RasterBandDescriptor::new(
+                "illumination".to_string(),
+                Measurement::continuous("illumination".to_string(), None),
+            )])?,
+        };
+
+        Ok(InitializedHillshade {
+            name,
+            result_descriptor,
+            dem,
+            model,
+            z_factor: self.params.z_factor,
+            tiling_specification: context.tiling_specification(),
+        }
+        .boxed())
+    }
+
+    span_fn!(Hillshade);
+}
+
+pub struct InitializedHillshade {
+    name: CanonicOperatorName,
+    result_descriptor: RasterResultDescriptor,
+    dem: Box<dyn InitializedRasterOperator>,
+    model: IlluminationModel,
+    z_factor: f64,
+    tiling_specification: TilingSpecification,
+}
+
+impl InitializedRasterOperator for InitializedHillshade {
+    fn query_processor(&self) -> Result<TypedRasterQueryProcessor> {
+        Ok(IlluminationProcessor {
+            dem: dem_processor(self.dem.as_ref())?,
+            result_descriptor: self.result_descriptor.clone(),
+            model: self.model.clone(),
+            z_factor: self.z_factor,
+            clamp_shadows: true,
+            tiling_specification: self.tiling_specification,
+        }
+        .boxed()
+        .into())
+    }
+
+    fn result_descriptor(&self) -> &RasterResultDescriptor {
+        &self.result_descriptor
+    }
+
+    fn canonic_name(&self) -> CanonicOperatorName {
+        self.name.clone()
+    }
+}
+
+fn dem_processor(
+    dem: &dyn InitializedRasterOperator,
+) -> Result<Box<dyn RasterQueryProcessor<RasterType = f32>>> {
+    Ok(call_on_generic_raster_processor!(
+        dem.query_processor()?, p => RasterTypeConversionQueryProcessor::create_boxed(p)
+    ))
+}
+
+/// Computes the cosine of the angle between the sun and the surface normal for each pixel
+struct IlluminationProcessor {
+    dem: Box<dyn RasterQueryProcessor<RasterType = f32>>,
+    result_descriptor: RasterResultDescriptor,
+    model: IlluminationModel,
+    z_factor: f64,
+    /// Whether surfaces that face away from the sun get `0` instead of negative values
+    clamp_shadows: bool,
+    tiling_specification: TilingSpecification,
+}
+
+#[async_trait]
+impl QueryProcessor for IlluminationProcessor {
+    type Output = RasterTile2D<f32>;
+    type SpatialBounds = SpatialPartition2D;
+    type Selection = BandSelection;
+    type ResultDescription = RasterResultDescriptor;
+
+    async fn _query<'a>(
+        &'a self,
+        query: RasterQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<BoxStream<'a, Result<Self::Output>>> {
+        stack_individual_aligned_raster_bands(&query, ctx, |query, ctx| async move {
+            let sub_query = IlluminationTileNeighborhood::new(
+                self.model.clone(),
+                self.z_factor,
+                self.clamp_shadows,
+                self.tiling_specification,
+            );
+
+            Ok(RasterSubQueryAdapter::<'a, f32, _, _>::new(
+                &self.dem,
+                query,
+                self.tiling_specification,
+                ctx,
+                sub_query,
+            )
+            .filter_and_fill(
+                crate::adapters::FillerTileCacheExpirationStrategy::DerivedFromSurroundingTiles,
+            ))
+        })
+        .await
+    }
+
+    fn result_descriptor(&self) -> &RasterResultDescriptor {
+        &self.result_descriptor
+    }
+}
+
+#[typetag::serde]
+#[async_trait]
+impl RasterOperator for IlluminationCorrection {
+    async fn _initialize(
+        self: Box<Self>,
+        path: WorkflowOperatorPath,
+        context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedRasterOperator>> {
+        let name = CanonicOperatorName::from(&self);
+
+        let sources = self.sources.initialize_sources(path, context).await?;
+        let raster_desc = sources.raster.result_descriptor();
+        let dem_desc = sources.dem.result_descriptor();
+
+        ensure!(dem_desc.bands.len() == 1, error::DemMustHaveSingleBand);
+        ensure!(
+            raster_desc.spatial_reference == dem_desc.spatial_reference,
+            error::SpatialReferenceMismatch
+        );
+
+        let model = IlluminationModel::new(
+            self.params.sun,
+            self.params.z_factor,
+            dem_desc.spatial_reference,
+        )?;
+
+        let result_descriptor = RasterResultDescriptor {
+            data_type: RasterDataType::F32,
+            ..raster_desc.clone()
+        };
+
+        Ok(InitializedIlluminationCorrection {
+            name,
+            result_descriptor,
+            raster: sources.raster,
+            dem: sources.dem,
+            model,
+            z_factor: self.params.z_factor,
+            correction: self.params.method.into(),
+            tiling_specification: context.tiling_specification(),
+        }
+        .boxed())
+    }
+
+    span_fn!(IlluminationCorrection);
+}
+
+pub struct InitializedIlluminationCorrection {
+    name: CanonicOperatorName,
+    result_descriptor: RasterResultDescriptor,
+    raster: Box<dyn InitializedRasterOperator>,
+    dem: Box<dyn InitializedRasterOperator>,
+    model: IlluminationModel,
+    z_factor: f64,
+    correction: Correction,
+    tiling_specification: TilingSpecification,
+}
+
+impl InitializedRasterOperator for InitializedIlluminationCorrection {
+    fn query_processor(&self) -> Result<TypedRasterQueryProcessor> {
+        let illumination = IlluminationProcessor {
+            dem: dem_processor(self.dem.as_ref())?,
+            result_descriptor: self.dem.result_descriptor().clone(),
+            model: self.model.clone(),
+            z_factor: self.z_factor,
+            clamp_shadows: false,
+            tiling_specification: self.tiling_specification,
+        };
+
+        Ok(call_on_generic_raster_processor!(
+            self.raster.query_processor()?, p => IlluminationCorrectionProcessor {
+                raster: p,
+                illumination,
+                result_descriptor: self.result_descriptor.clone(),
+                correction: self.correction,
+            }
+            .boxed()
+            .into()
+        ))
+    }
+
+    fn result_descriptor(&self) -> &RasterResultDescriptor {
+        &self.result_descriptor
+    }
+
+    fn canonic_name(&self) -> CanonicOperatorName {
+        self.name.clone()
+    }
+}
+
+struct IlluminationCorrectionProcessor<T> {
+    raster: Box<dyn RasterQueryProcessor<RasterType = T>>,
+    illumination: IlluminationProcessor,
+    result_descriptor: RasterResultDescriptor,
+    correction: Correction,
+}
+
+impl<T: Pixel> IlluminationCorrectionProcessor<T> {
+    /// Queries the illumination of the tile and corrects its pixels
+    async fn correct_tile(
+        &self,
+        tile: RasterTile2D<T>,
+        ctx: &dyn QueryContext,
+    ) -> Result<RasterTile2D<f32>> {
+        let tile_info = tile.tile_information();
+        let bounds = tile_info.spatial_partition();
+
+        let GridOrEmpty::Grid(grid) = tile.grid_array else {
+            return Ok(RasterTile2D::new_with_tile_info(
+                tile.time,
+                tile_info,
+                tile.band,
+                EmptyGrid2D::new(tile_info.tile_size_in_pixels).into(),
+                tile.cache_hint,
+            ));
+        };
+
+        let illumination_query = RasterQueryRectangle {
+            spatial_bounds: bounds,
+            time_interval: TimeInterval::new_instant(tile.time.start())?,
+            spatial_resolution: tile.global_geo_transform.spatial_resolution(),
+            attributes: BandSelection::first(),
+        };
+
+        let illumination_tiles: Vec<RasterTile2D<f32>> = self
+            .illumination
+            .raster_query(illumination_query, ctx)
+            .await?
+            .try_collect()
+            .await?;
+        let illumination = illumination_tiles
+            .into_iter()
+            .find(|illumination| illumination.tile_position == tile.tile_position);
+
+        let cos_zenith = self
+            .illumination
+            .model
+            .sun_angles(tile.time, bounds)?
+            .zenith
+            .to_radians()
+            .cos();
+        let correction = self.correction;
+
+        let cache_hint = illumination
+            .as_ref()
+            .map_or(tile.cache_hint, |illumination| {
+                tile.cache_hint.merged(&illumination.cache_hint)
+            });
+
+        let corrected = spawn_blocking_with_thread_pool(ctx.thread_pool().clone(), move || {
+            let incidences: Vec<Option<f32>> = match illumination.map(|tile| tile.grid_array) {
+                Some(GridOrEmpty::Grid(illumination)) => {
+                    illumination.masked_element_deref_iterator().collect()
+                }
+                _ => vec![None; tile_info.tile_size_in_pixels.number_of_elements()],
+            };
+
+            let (values, validity): (Vec<f32>, Vec<bool>) = grid
+                .masked_element_deref_iterator()
+                .zip(incidences)
+                .map(|(value, incidence)| {
+                    let corrected = value.zip(incidence).and_then(|(value, incidence)| {
+                        correction.correct(
+                            AsPrimitive::<f64>::as_(value),
+                            f64::from(incidence),
+                            cos_zenith,
+                        )
+                    });
+                    (corrected.unwrap_or_default() as f32, corrected.is_some())
+                })
+                .unzip();
+
+            MaskedGrid2D::new(
+                Grid2D::new(tile_info.tile_size_in_pixels, values)?,
+                Grid2D::new(tile_info.tile_size_in_pixels, validity)?,
+            )
+        })
+        .await??;
+
+        Ok(RasterTile2D::new_with_tile_info(
+            tile.time,
+            tile_info,
+            tile.band,
+            corrected.into(),
+            cache_hint,
+        ))
+    }
+}
+
+#[async_trait]
+impl<T: Pixel> QueryProcessor for IlluminationCorrectionProcessor<T> {
+    type Output = RasterTile2D<f32>;
+    type SpatialBounds = SpatialPartition2D;
+    type Selection = BandSelection;
+    type ResultDescription = RasterResultDescriptor;
+
+    async fn _query<'a>(
+        &'a self,
+        query: RasterQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<BoxStream<'a, Result<Self::Output>>> {
+        let tiles = self.raster.raster_query(query, ctx).await?;
+
+        Ok(tiles
+            .and_then(move |tile| self.correct_tile(tile, ctx))
+            .boxed())
+    }
+
+    fn result_descriptor(&self) -> &RasterResultDescriptor {
+        &self.result_descriptor
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{MockExecutionContext, MockQueryContext};
+    use crate::mock::{MockRasterSource, MockRasterSourceParams};
+    use geoengine_datatypes::primitives::{CacheHint, SpatialResolution};
+    use geoengine_datatypes::raster::{TileInformation, TilingSpecification};
+    use geoengine_datatypes::util::test::TestDefault;
+
+    /// Two 3x3 tiles of a plane that descends towards the east by one unit per pixel
+    fn dem() -> Box<dyn RasterOperator> {
+        source(
+            vec![
+                vec![10., 9., 8., 10., 9., 8., 10., 9., 8.],
+                vec![7., 6., 5., 7., 6., 5., 7., 6., 5.],
+            ],
+            RasterDataType::F32,
+        )
+    }
+
+    fn source(tiles: Vec<Vec<f32>>, data_type: RasterDataType) -> Box<dyn RasterOperator> {
+        MockRasterSource {
+            params: MockRasterSourceParams {
+                data: tiles
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, values)| {
+                        RasterTile2D::new_with_tile_info(
+                            TimeInterval::default(),
+                            TileInformation {
+                                global_geo_transform: TestDefault::test_default(),
+                                global_tile_position: [0, i as isize].into(),
+                                tile_size_in_pixels: [3, 3].into(),
+                            },
+                            0,
+                            Grid2D::new([3, 3].into(), values).unwrap().into(),
+                            CacheHint::default(),
+                        )
+                    })
+                    .collect(),
+                result_descriptor: RasterResultDescriptor {
+                    data_type,
+                    spatial_reference: SpatialReferenceOption::Unreferenced,
+                    time: None,
+                    bbox: None,
+                    resolution: None,
+                    bands: RasterBandDescriptors::new_single_band(),
+                },
+            },
+        }
+        .boxed()
+    }
+
+    async fn query(operator: Box<dyn RasterOperator>) -> Result<Vec<Vec<Option<f32>>>> {
+        let execution_context = MockExecutionContext::new_with_tiling_spec(TilingSpecification {
+            origin_coordinate: [0.0, 0.0].into(),
+            tile_size_in_pixels: [3, 3].into(),
+        });
+
+        let processor = operator
+            .initialize(WorkflowOperatorPath::initialize_root(), &execution_context)
+            .await?
+            .query_processor()?
+            .get_f32()
+            .unwrap();
+
+        let tiles: Vec<RasterTile2D<f32>> = processor
+            .raster_query(
+                RasterQueryRectangle {
+                    spatial_bounds: SpatialPartition2D::new((0., 0.).into(), (6., -3.).into())
+                        .unwrap(),
+                    time_interval: TimeInterval::default(),
+                    spatial_resolution: SpatialResolution::one(),
+                    attributes: BandSelection::first(),
+                },
+                &MockQueryContext::test_default(),
+            )
+            .await?
+            .try_collect()
+            .await?;
+
+        Ok(tiles
+            .iter()
+            .map(|tile| match &tile.grid_array {
+                GridOrEmpty::Grid(grid) => grid.masked_element_deref_iterator().collect(),
+                GridOrEmpty::Empty(_) => vec![None; 9],
+            })
+            .collect())
+    }
+
+    const EAST_SUN: SunPosition = SunPosition::Fixed {
+        azimuth: 90.,
+        elevation: 45.,
+    };
+
+    #[tokio::test]
+    async fn it_computes_the_hillshade() {
+        let tiles = query(
+            Hillshade {
+                params: HillshadeParams {
+                    sun: EAST_SUN,
+                    z_factor: 1.,
+                },
+                sources: SingleRasterSource { raster: dem() },
+            }
+            .boxed(),
+        )
+        .await
+        .unwrap();
+
+        // the center row at the tile border has the full neighborhood and faces the sun
+        assert!((tiles[0][5].unwrap() - 1.).abs() < 1e-6);
+        assert!((tiles[1][3].unwrap() - 1.).abs() < 1e-6);
+
+        assert!(tiles
+            .iter()
+            .flatten()
+            .all(|value| value.map_or(false, |value| (0. ..=1.).contains(&value))));
+    }
+
+    #[tokio::test]
+    async fn it_corrects_the_illumination() {
+        let tiles = query(
+            IlluminationCorrection {
+                params: IlluminationCorrectionParams {
+                    sun: EAST_SUN,
+                    method: CorrectionMethod::Cosine,
+                    z_factor: 1.,
+                },
+                sources: IlluminationCorrectionSources {
+                    raster: source(vec![vec![100.; 9], vec![100.; 9]], RasterDataType::F32),
+                    dem: dem(),
+                },
+            }
+            .boxed(),
+        )
+        .await
+        .unwrap();
+
+        let expected = 100. * 45_f32.to_radians().cos();
+        assert!((tiles[0][5].unwrap() - expected).abs() < 1e-3);
+        assert!((tiles[1][3].unwrap() - expected).abs() < 1e-3);
+    }
+
+    #[tokio::test]
+    async fn it_requires_a_spatial_reference_for_the_actual_sun() {
+        let result = Hillshade {
+            params: HillshadeParams {
+                sun: SunPosition::Actual,
+                z_factor: 1.,
+            },
+            sources: SingleRasterSource { raster: dem() },
+        }
+        .boxed()
+        .initialize(
+            WorkflowOperatorPath::initialize_root(),
+            &MockExecutionContext::test_default(),
+        )
+        .await;
+
+        assert!(result.is_err());
+    }
+}
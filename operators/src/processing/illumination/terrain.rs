@@ -0,0 +1,142 @@
+use std::f64::consts::TAU;
+
+/// Illumination values below this threshold are too dark to be corrected reliably
+pub const MIN_ILLUMINATION: f64 = 0.01;
+
+/// The position of the sun in degrees, with the azimuth clockwise from north
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SunAngles {
+    pub azimuth: f64,
+    pub zenith: f64,
+}
+
+/// The slope and the aspect of a pixel in radians, with the aspect clockwise from north
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SlopeAspect {
+    pub slope: f64,
+    pub aspect: f64,
+}
+
+/// Computes the slope and aspect of the center pixel of a 3x3 window in row-major order with
+/// Horn's method.
+/// Missing neighbors are replaced by the center pixel.
+///
+/// `dx` and `dy` are the pixel sizes in the units of the elevations.
+pub fn slope_aspect(window: &[Option<f64>; 9], dx: f64, dy: f64) -> Option<SlopeAspect> {
+    let center = window[4]?;
+    let [a, b, c, d, _, f, g, h, i] = window.map(|value| value.unwrap_or(center));
+
+    let dz_dx = ((c + 2. * f + i) - (a + 2. * d + g)) / (8. * dx);
+    // rows increase towards the south
+    let dz_dy = ((g + 2. * h + i) - (a + 2. * b + c)) / (8. * dy);
+
+    let slope = dz_dx.hypot(dz_dy).atan();
+
+    // the direction of the steepest descent as east and north components
+    let aspect = (-dz_dx).atan2(dz_dy).rem_euclid(TAU);
+
+    Some(SlopeAspect { slope, aspect })
+}
+
+/// Computes the cosine of the angle between the sun and the surface normal.
+/// Negative values denote surfaces that face away from the sun.
+pub fn cos_incidence(terrain: SlopeAspect, sun: SunAngles) -> f64 {
+    let zenith = sun.zenith.to_radians();
+    let azimuth = sun.azimuth.to_radians();
+
+    zenith.cos() * terrain.slope.cos()
+        + zenith.sin() * terrain.slope.sin() * (azimuth - terrain.aspect).cos()
+}
+
+/// The topographic correction of reflectances
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Correction {
+    /// Scales by the ratio of the illumination of a flat surface and the actual illumination
+    Cosine,
+    /// Dampens the cosine correction by adding the constant `c` to both illuminations
+    C { c: f64 },
+}
+
+impl Correction {
+    /// Corrects a value or returns `None` if the pixel is too dark to be corrected
+    pub fn correct(self, value: f64, cos_incidence: f64, cos_zenith: f64) -> Option<f64> {
+        let c = match self {
+            Self::Cosine => 0.,
+            Self::C { c } => c,
+        };
+
+        let illumination = cos_incidence + c;
+        if illumination < MIN_ILLUMINATION {
+            return None;
+        }
+
+        Some(value * (cos_zenith + c) / illumination)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_computes_slope_and_aspect() {
+        // a plane that descends towards the east by one unit per pixel
+        let window = [2., 1., 0., 2., 1., 0., 2., 1., 0.].map(Some);
+
+        let SlopeAspect { slope, aspect } = slope_aspect(&window, 1., 1.).unwrap();
+
+        assert!((slope - 45_f64.to_radians()).abs() < 1e-9);
+        assert!((aspect - 90_f64.to_radians()).abs() < 1e-9);
+
+        // a plane that descends towards the north with missing neighbors
+        let window = [
+            None,
+            Some(0.),
+            None,
+            Some(1.),
+            Some(1.),
+            Some(1.),
+            Some(2.),
+            Some(2.),
+            Some(2.),
+        ];
+
+        let SlopeAspect { aspect, .. } = slope_aspect(&window, 1., 1.).unwrap();
+
+        assert!(aspect.abs() < 1e-9 || (aspect - TAU).abs() < 1e-9);
+
+        assert!(slope_aspect(&[None; 9], 1., 1.).is_none());
+    }
+
+    #[test]
+    fn it_computes_the_illumination() {
+        let flat = SlopeAspect {
+            slope: 0.,
+            aspect: 0.,
+        };
+        let east_facing = SlopeAspect {
+            slope: 45_f64.to_radians(),
+            aspect: 90_f64.to_radians(),
+        };
+        let sun = SunAngles {
+            azimuth: 90.,
+            zenith: 45.,
+        };
+
+        assert!((cos_incidence(flat, sun) - 45_f64.to_radians().cos()).abs() < 1e-9);
+        assert!((cos_incidence(east_facing, sun) - 1.).abs() < 1e-9);
+
+        let west_sun = SunAngles {
+            azimuth: 270.,
+            zenith: 45.,
+        };
+        assert!((cos_incidence(east_facing, west_sun)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn it_corrects_values() {
+        assert!((Correction::Cosine.correct(10., 0.5, 0.25).unwrap() - 5.).abs() < 1e-9);
+        assert!((Correction::C { c: 0.5 }.correct(10., 0.5, 0.25).unwrap() - 7.5).abs() < 1e-9);
+        assert!(Correction::Cosine.correct(10., 0., 0.25).is_none());
+    }
+}
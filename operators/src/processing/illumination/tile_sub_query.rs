@@ -0,0 +1,289 @@
+use super::terrain::{cos_incidence, slope_aspect};
+use super::IlluminationModel;
+use crate::adapters::{FoldTileAccu, SubQueryTileAggregator};
+use crate::util::Result;
+use async_trait::async_trait;
+use futures::future::BoxFuture;
+use futures::FutureExt;
+use geoengine_datatypes::primitives::{
+    AxisAlignedRectangle, CacheHint, Coordinate2D, RasterQueryRectangle, SpatialPartition2D,
+    SpatialPartitioned, TimeInstance, TimeInterval,
+};
+use geoengine_datatypes::raster::{
+    Blit, EmptyGrid2D, GeoTransform, Grid2D, GridOrEmpty, GridSize, MaskedGrid2D, RasterTile2D,
+    TileInformation, TilingSpecification,
+};
+use rayon::ThreadPool;
+use std::sync::Arc;
+use tokio::task::JoinHandle;
+
+/// A sub-query aggregator that queries for each output tile an input tile of elevations that is
+/// enlarged by one pixel on each side to compute the slope and aspect of the border pixels.
+#[derive(Debug, Clone)]
+pub struct IlluminationTileNeighborhood {
+    model: IlluminationModel,
+    z_factor: f64,
+    clamp_shadows: bool,
+    tiling_specification: TilingSpecification,
+}
+
+impl IlluminationTileNeighborhood {
+    pub fn new(
+        model: IlluminationModel,
+        z_factor: f64,
+        clamp_shadows: bool,
+        tiling_specification: TilingSpecification,
+    ) -> Self {
+        Self {
+            model,
+            z_factor,
+            clamp_shadows,
+            tiling_specification,
+        }
+    }
+}
+
+impl<'a> SubQueryTileAggregator<'a, f32> for IlluminationTileNeighborhood {
+    type FoldFuture = FoldFuture;
+
+    type FoldMethod = fn(IlluminationAccu, RasterTile2D<f32>) -> Self::FoldFuture;
+
+    type TileAccu = IlluminationAccu;
+    type TileAccuFuture = BoxFuture<'a, Result<Self::TileAccu>>;
+
+    /// Create an enlarged tile to store the elevations of the neighborhood
+    fn new_fold_accu(
+        &self,
+        tile_info: TileInformation,
+        query_rect: RasterQueryRectangle,
+        pool: &Arc<ThreadPool>,
+    ) -> Self::TileAccuFuture {
+        let pool = pool.clone();
+        let tiling_specification = self.tiling_specification;
+        let model = self.model.clone();
+        let z_factor = self.z_factor;
+        let clamp_shadows = self.clamp_shadows;
+        crate::util::spawn_blocking(move || IlluminationAccu {
+            output_info: tile_info,
+            input_tile: create_enlarged_tile(&query_rect, tiling_specification),
+            pool,
+            model,
+            z_factor,
+            clamp_shadows,
+        })
+        .map(|accu| accu.map_err(Into::into))
+        .boxed()
+    }
+
+    /// Enlarge the spatial bounds to all sides by one pixel
+    fn tile_query_rectangle(
+        &self,
+        tile_info: TileInformation,
+        query_rect: RasterQueryRectangle,
+        start_time: TimeInstance,
+        band_idx: u32,
+    ) -> Result<Option<RasterQueryRectangle>> {
+        let spatial_bounds = tile_info.spatial_partition();
+
+        let margin_pixels = Coordinate2D::from((
+            tile_info.global_geo_transform.x_pixel_size(),
+            tile_info.global_geo_transform.y_pixel_size(),
+        ));
+
+        let enlarged_spatial_bounds = SpatialPartition2D::new(
+            spatial_bounds.upper_left() - margin_pixels,
+            spatial_bounds.lower_right() + margin_pixels,
+        )?;
+
+        Ok(Some(RasterQueryRectangle {
+            spatial_bounds: enlarged_spatial_bounds,
+            time_interval: TimeInterval::new_instant(start_time)?,
+            spatial_resolution: query_rect.spatial_resolution,
+            attributes: band_idx.into(),
+        }))
+    }
+
+    fn fold_method(&self) -> Self::FoldMethod {
+        |accu, tile| {
+            crate::util::spawn_blocking(|| merge_tile_into_enlarged_tile(accu, tile))
+                .map(flatten_result)
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct IlluminationAccu {
+    pub output_info: TileInformation,
+    pub input_tile: RasterTile2D<f32>,
+    pub pool: Arc<ThreadPool>,
+    pub model: IlluminationModel,
+    pub z_factor: f64,
+    pub clamp_shadows: bool,
+}
+
+#[async_trait]
+impl FoldTileAccu for IlluminationAccu {
+    type RasterType = f32;
+
+    /// now that we collected all the elevations we compute the illumination
+    async fn into_tile(self) -> Result<RasterTile2D<Self::RasterType>> {
+        let bounds = self.output_info.spatial_partition();
+        let sun = self.model.sun_angles(self.input_tile.time, bounds)?;
+        let (dx, dy) = self
+            .model
+            .pixel_size_in_meters(&self.output_info.global_geo_transform, bounds);
+        let z_factor = self.z_factor;
+        let clamp_shadows = self.clamp_shadows;
+
+        crate::util::spawn_blocking_with_thread_pool(self.pool, move || {
+            compute_illumination_tile(self.input_tile, &self.output_info, |window| {
+                let terrain = slope_aspect(window, dx / z_factor, dy / z_factor)?;
+                let illumination = cos_incidence(terrain, sun);
+                Some(if clamp_shadows {
+                    illumination.max(0.)
+                } else {
+                    illumination
+                })
+            })
+        })
+        .await?
+    }
+
+    fn thread_pool(&self) -> &Arc<ThreadPool> {
+        &self.pool
+    }
+}
+
+/// Applies the function to the 3x3 window of each pixel of the inner tile
+fn compute_illumination_tile(
+    input: RasterTile2D<f32>,
+    info_out: &TileInformation,
+    illumination: impl Fn(&[Option<f64>; 9]) -> Option<f64>,
+) -> Result<RasterTile2D<f32>> {
+    let empty_tile = |input: &RasterTile2D<f32>| {
+        RasterTile2D::new_with_tile_info(
+            input.time,
+            *info_out,
+            input.band,
+            EmptyGrid2D::new(info_out.tile_size_in_pixels).into(),
+            input.cache_hint.clone_with_current_datetime(),
+        )
+    };
+
+    let GridOrEmpty::Grid(input_grid) = &input.grid_array else {
+        return Ok(empty_tile(&input));
+    };
+
+    let width = input_grid.axis_size_x();
+    let elevations: Vec<Option<f64>> = input_grid
+        .masked_element_deref_iterator()
+        .map(|value| value.map(f64::from))
+        .collect();
+
+    let out_width = info_out.tile_size_in_pixels.axis_size_x();
+    let out_height = info_out.tile_size_in_pixels.axis_size_y();
+
+    let mut out_values = Vec::with_capacity(out_width * out_height);
+    let mut out_validity = Vec::with_capacity(out_width * out_height);
+    for y in 0..out_height {
+        for x in 0..out_width {
+            // the window of the output pixel starts at the same position in the enlarged tile
+            let mut window = [None; 9];
+            for (i, value) in window.iter_mut().enumerate() {
+                *value = elevations[(y + i / 3) * width + x + i % 3];
+            }
+
+            let value = illumination(&window);
+            out_values.push(value.unwrap_or_default() as f32);
+            out_validity.push(value.is_some());
+        }
+    }
+
+    if !out_validity.contains(&true) {
+        return Ok(empty_tile(&input));
+    }
+
+    let out_grid = MaskedGrid2D::new(
+        Grid2D::new(info_out.tile_size_in_pixels, out_values)?,
+        Grid2D::new(info_out.tile_size_in_pixels, out_validity)?,
+    )?;
+
+    Ok(RasterTile2D::new(
+        input.time,
+        info_out.global_tile_position,
+        input.band,
+        info_out.global_geo_transform,
+        out_grid.into(),
+        input.cache_hint.clone_with_current_datetime(),
+    ))
+}
+
+/// Creates a single tile that fits the input tiles plus one pixel on each side
+fn create_enlarged_tile(
+    query_rect: &RasterQueryRectangle,
+    tiling_specification: TilingSpecification,
+) -> RasterTile2D<f32> {
+    let tiling = tiling_specification.strategy(
+        query_rect.spatial_resolution.x,
+        -query_rect.spatial_resolution.y,
+    );
+
+    let geo_transform = GeoTransform::new(
+        query_rect.spatial_bounds.upper_left(),
+        query_rect.spatial_resolution.x,
+        -query_rect.spatial_resolution.y,
+    );
+
+    let shape = [
+        tiling.tile_size_in_pixels.axis_size_y() + 2,
+        tiling.tile_size_in_pixels.axis_size_x() + 2,
+    ];
+
+    // create a non-aligned (w.r.t. the tiling specification) grid by setting the origin to the top-left of the tile and the tile-index to [0, 0]
+    RasterTile2D::new(
+        query_rect.time_interval,
+        [0, 0].into(),
+        0,
+        geo_transform,
+        GridOrEmpty::from(EmptyGrid2D::new(shape.into())),
+        CacheHint::max_duration(),
+    )
+}
+
+type FoldFutureFn =
+    fn(Result<Result<IlluminationAccu>, tokio::task::JoinError>) -> Result<IlluminationAccu>;
+type FoldFuture = futures::future::Map<JoinHandle<Result<IlluminationAccu>>, FoldFutureFn>;
+
+/// Turn a result of results into a result
+fn flatten_result(
+    result: Result<Result<IlluminationAccu>, tokio::task::JoinError>,
+) -> Result<IlluminationAccu> {
+    match result {
+        Ok(r) => r,
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Merge, step by step, the input tiles into the larger accumulator tile
+fn merge_tile_into_enlarged_tile(
+    mut accu: IlluminationAccu,
+    tile: RasterTile2D<f32>,
+) -> Result<IlluminationAccu> {
+    // get the time and band now because they are not known when the accu was created
+    accu.input_tile.time = tile.time;
+    accu.input_tile.band = tile.band;
+
+    // the tiles only contribute to the cache hint if they are not empty
+    if tile.is_empty() {
+        return Ok(accu);
+    }
+    accu.input_tile.cache_hint.merge_with(&tile.cache_hint);
+
+    let mut accu_input_tile = accu.input_tile.into_materialized_tile();
+    accu_input_tile.blit(tile)?;
+
+    Ok(IlluminationAccu {
+        input_tile: accu_input_tile.into(),
+        ..accu
+    })
+}
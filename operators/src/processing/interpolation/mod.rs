@@ -41,7 +41,7 @@ pub enum InputResolution {
     Source,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
 pub enum InterpolationMethod {
     NearestNeighbor,
@@ -89,23 +89,13 @@ impl RasterOperator for Interpolation {
             in_descriptor.resolution.expect("checked in ensure")
         };
 
-        let out_descriptor = RasterResultDescriptor {
-            spatial_reference: in_descriptor.spatial_reference,
-            data_type: in_descriptor.data_type,
-            bbox: in_descriptor.bbox,
-            time: in_descriptor.time,
-            resolution: None, // after interpolation the resolution is uncapped
-            bands: in_descriptor.bands.clone(),
-        };
-
-        let initialized_operator = InitializedInterpolation {
+        let initialized_operator = InitializedInterpolation::new_with_input(
             name,
-            result_descriptor: out_descriptor,
-            raster_source,
-            interpolation_method: self.params.interpolation,
+            self.params.interpolation,
             input_resolution,
-            tiling_specification: context.tiling_specification(),
-        };
+            raster_source,
+            context.tiling_specification(),
+        );
 
         Ok(initialized_operator.boxed())
     }
@@ -122,6 +112,38 @@ pub struct InitializedInterpolation {
     tiling_specification: TilingSpecification,
 }
 
+impl InitializedInterpolation {
+    /// Create a new `InitializedInterpolation` for an already initialized `raster_source`
+    /// that is interpolated from its `input_resolution`.
+    pub fn new_with_input(
+        name: CanonicOperatorName,
+        interpolation_method: InterpolationMethod,
+        input_resolution: SpatialResolution,
+        raster_source: Box<dyn InitializedRasterOperator>,
+        tiling_specification: TilingSpecification,
+    ) -> Self {
+        let in_descriptor = raster_source.result_descriptor();
+
+        let result_descriptor = RasterResultDescriptor {
+            spatial_reference: in_descriptor.spatial_reference,
+            data_type: in_descriptor.data_type,
+            bbox: in_descriptor.bbox,
+            time: in_descriptor.time,
+            resolution: None, // after interpolation the resolution is uncapped
+            bands: in_descriptor.bands.clone(),
+        };
+
+        Self {
+            name,
+            result_descriptor,
+            raster_source,
+            interpolation_method,
+            input_resolution,
+            tiling_specification,
+        }
+    }
+}
+
 impl InitializedRasterOperator for InitializedInterpolation {
     fn query_processor(&self) -> Result<TypedRasterQueryProcessor> {
         let source_processor = self.raster_source.query_processor()?;
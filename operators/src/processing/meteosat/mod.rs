@@ -1,3 +1,4 @@
+use self::satellite::Platform;
 use geoengine_datatypes::raster::RasterPropertiesKey;
 
 mod radiance;
@@ -5,30 +6,30 @@ mod reflectance;
 mod satellite;
 mod temperature;
 
-fn new_slope_key() -> RasterPropertiesKey {
+fn new_slope_key(platform: Platform) -> RasterPropertiesKey {
     RasterPropertiesKey {
-        domain: Some("msg".into()),
+        domain: Some(platform.metadata_domain().into()),
         key: "calibration_slope".into(),
     }
 }
 
-fn new_offset_key() -> RasterPropertiesKey {
+fn new_offset_key(platform: Platform) -> RasterPropertiesKey {
     RasterPropertiesKey {
-        domain: Some("msg".into()),
+        domain: Some(platform.metadata_domain().into()),
         key: "calibration_offset".into(),
     }
 }
 
-fn new_channel_key() -> RasterPropertiesKey {
+fn new_channel_key(platform: Platform) -> RasterPropertiesKey {
     RasterPropertiesKey {
-        domain: Some("msg".into()),
+        domain: Some(platform.metadata_domain().into()),
         key: "channel_number".into(),
     }
 }
 
-fn new_satellite_key() -> RasterPropertiesKey {
+fn new_satellite_key(platform: Platform) -> RasterPropertiesKey {
     RasterPropertiesKey {
-        domain: Some("msg".into()),
+        domain: Some(platform.metadata_domain().into()),
         key: "satellite_number".into(),
     }
 }
@@ -62,7 +63,7 @@ mod test_util {
     };
     use crate::mock::{MockRasterSource, MockRasterSourceParams};
     use crate::processing::meteosat::{
-        new_channel_key, new_offset_key, new_satellite_key, new_slope_key,
+        new_channel_key, new_offset_key, new_satellite_key, new_slope_key, Platform,
     };
     use crate::source::{
         FileNotFoundHandling, GdalDatasetGeoTransform, GdalDatasetParameters, GdalMetaDataRegular,
@@ -104,19 +105,31 @@ mod test_util {
         let mut props = RasterProperties::default();
 
         if let Some(v) = channel {
-            props.insert_property(new_channel_key(), RasterPropertiesEntry::Number(v.as_()));
+            props.insert_property(
+                new_channel_key(Platform::Meteosat),
+                RasterPropertiesEntry::Number(v.as_()),
+            );
         }
 
         if let Some(v) = satellite {
-            props.insert_property(new_satellite_key(), RasterPropertiesEntry::Number(v.as_()));
+            props.insert_property(
+                new_satellite_key(Platform::Meteosat),
+                RasterPropertiesEntry::Number(v.as_()),
+            );
         }
 
         if let Some(v) = slope {
-            props.insert_property(new_slope_key(), RasterPropertiesEntry::Number(v));
+            props.insert_property(
+                new_slope_key(Platform::Meteosat),
+                RasterPropertiesEntry::Number(v),
+            );
         }
 
         if let Some(v) = offset {
-            props.insert_property(new_offset_key(), RasterPropertiesEntry::Number(v));
+            props.insert_property(
+                new_offset_key(Platform::Meteosat),
+                RasterPropertiesEntry::Number(v),
+            );
         }
         props
     }
@@ -243,19 +256,19 @@ mod test_util {
                 no_data_value,
                 properties_mapping: Some(vec![
                     GdalMetadataMapping::identity(
-                        new_satellite_key(),
+                        new_satellite_key(Platform::Meteosat),
                         RasterPropertiesEntryType::Number,
                     ),
                     GdalMetadataMapping::identity(
-                        new_channel_key(),
+                        new_channel_key(Platform::Meteosat),
                         RasterPropertiesEntryType::Number,
                     ),
                     GdalMetadataMapping::identity(
-                        new_offset_key(),
+                        new_offset_key(Platform::Meteosat),
                         RasterPropertiesEntryType::Number,
                     ),
                     GdalMetadataMapping::identity(
-                        new_slope_key(),
+                        new_slope_key(Platform::Meteosat),
                         RasterPropertiesEntryType::Number,
                     ),
                 ]),
@@ -23,15 +23,21 @@ use serde::{Deserialize, Serialize};
 // Output type is always f32
 type PixelOut = f32;
 use crate::error::Error;
+use crate::processing::meteosat::satellite::Platform;
 use crate::processing::meteosat::{new_offset_key, new_slope_key};
 use RasterDataType::F32 as RasterOut;
 use TypedRasterQueryProcessor::F32 as QueryProcessorOut;
 
-#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+/// Parameters for the `Radiance` operator.
+/// * `platform` determines the domain of the calibration properties, defaults to Meteosat.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default)]
 #[serde(rename_all = "camelCase")]
-pub struct RadianceParams {}
+pub struct RadianceParams {
+    #[serde(default)]
+    pub platform: Platform,
+}
 
-/// The radiance operator converts a raw MSG raster into radiance.
+/// The radiance operator converts a raw raster of a geostationary satellite into radiance.
 /// This is done by applying the following formula to every pixel:
 ///
 /// `p_new = offset + p_old * slope`
@@ -41,8 +47,10 @@ pub struct RadianceParams {}
 /// raster.
 /// The exact names of the properties are:
 ///
-/// - offset: `msg.calibration_offset`
-/// - slope: `msg.calibration_slope`
+/// - offset: `<domain>.calibration_offset`
+/// - slope: `<domain>.calibration_slope`
+///
+/// The domain is `msg` for Meteosat, `goes` for GOES-R and `himawari` for Himawari.
 pub type Radiance = Operator<RadianceParams, SingleRasterSource>;
 
 impl OperatorName for Radiance {
@@ -53,6 +61,7 @@ pub struct InitializedRadiance {
     name: CanonicOperatorName,
     result_descriptor: RasterResultDescriptor,
     source: Box<dyn InitializedRasterOperator>,
+    platform: Platform,
 }
 
 #[typetag::serde]
@@ -129,6 +138,7 @@ impl RasterOperator for Radiance {
             name,
             result_descriptor: out_desc,
             source: input,
+            platform: self.params.platform,
         };
 
         Ok(initialized_operator.boxed())
@@ -147,34 +157,34 @@ impl InitializedRasterOperator for InitializedRadiance {
 
         Ok(match q {
             TypedRasterQueryProcessor::U8(p) => QueryProcessorOut(Box::new(
-                RadianceProcessor::new(p, self.result_descriptor.clone()),
+                RadianceProcessor::new(p, self.result_descriptor.clone(), self.platform),
             )),
             TypedRasterQueryProcessor::U16(p) => QueryProcessorOut(Box::new(
-                RadianceProcessor::new(p, self.result_descriptor.clone()),
+                RadianceProcessor::new(p, self.result_descriptor.clone(), self.platform),
             )),
             TypedRasterQueryProcessor::U32(p) => QueryProcessorOut(Box::new(
-                RadianceProcessor::new(p, self.result_descriptor.clone()),
+                RadianceProcessor::new(p, self.result_descriptor.clone(), self.platform),
             )),
             TypedRasterQueryProcessor::U64(p) => QueryProcessorOut(Box::new(
-                RadianceProcessor::new(p, self.result_descriptor.clone()),
+                RadianceProcessor::new(p, self.result_descriptor.clone(), self.platform),
             )),
             TypedRasterQueryProcessor::I8(p) => QueryProcessorOut(Box::new(
-                RadianceProcessor::new(p, self.result_descriptor.clone()),
+                RadianceProcessor::new(p, self.result_descriptor.clone(), self.platform),
             )),
             TypedRasterQueryProcessor::I16(p) => QueryProcessorOut(Box::new(
-                RadianceProcessor::new(p, self.result_descriptor.clone()),
+                RadianceProcessor::new(p, self.result_descriptor.clone(), self.platform),
             )),
             TypedRasterQueryProcessor::I32(p) => QueryProcessorOut(Box::new(
-                RadianceProcessor::new(p, self.result_descriptor.clone()),
+                RadianceProcessor::new(p, self.result_descriptor.clone(), self.platform),
             )),
             TypedRasterQueryProcessor::I64(p) => QueryProcessorOut(Box::new(
-                RadianceProcessor::new(p, self.result_descriptor.clone()),
+                RadianceProcessor::new(p, self.result_descriptor.clone(), self.platform),
             )),
             TypedRasterQueryProcessor::F32(p) => QueryProcessorOut(Box::new(
-                RadianceProcessor::new(p, self.result_descriptor.clone()),
+                RadianceProcessor::new(p, self.result_descriptor.clone(), self.platform),
             )),
             TypedRasterQueryProcessor::F64(p) => QueryProcessorOut(Box::new(
-                RadianceProcessor::new(p, self.result_descriptor.clone()),
+                RadianceProcessor::new(p, self.result_descriptor.clone(), self.platform),
             )),
         })
     }
@@ -199,12 +209,12 @@ where
     Q: RasterQueryProcessor<RasterType = P>,
     P: Pixel,
 {
-    pub fn new(source: Q, result_descriptor: RasterResultDescriptor) -> Self {
+    pub fn new(source: Q, result_descriptor: RasterResultDescriptor, platform: Platform) -> Self {
         Self {
             source,
             result_descriptor,
-            offset_key: new_offset_key(),
-            slope_key: new_slope_key(),
+            offset_key: new_offset_key(platform),
+            slope_key: new_slope_key(platform),
         }
     }
 
@@ -282,7 +292,7 @@ mod tests {
     //     let result = test_util::process(
     //         move || {
     //             RasterOperator::boxed(Radiance {
-    //                 params: RadianceParams {},
+    //                 params: RadianceParams::default(),
     //                 sources: SingleRasterSource {
     //                     raster: src.boxed(),
     //                 },
@@ -313,7 +323,7 @@ mod tests {
                     sources: SingleRasterSource {
                         raster: src.boxed(),
                     },
-                    params: RadianceParams {},
+                    params: RadianceParams::default(),
                 })
             },
             test_util::create_mock_query(),
@@ -356,7 +366,7 @@ mod tests {
                     sources: SingleRasterSource {
                         raster: src.boxed(),
                     },
-                    params: RadianceParams {},
+                    params: RadianceParams::default(),
                 })
             },
             test_util::create_mock_query(),
@@ -387,7 +397,7 @@ mod tests {
                     sources: SingleRasterSource {
                         raster: src.boxed(),
                     },
-                    params: RadianceParams {},
+                    params: RadianceParams::default(),
                 })
             },
             test_util::create_mock_query(),
@@ -416,7 +426,7 @@ mod tests {
                     sources: SingleRasterSource {
                         raster: src.boxed(),
                     },
-                    params: RadianceParams {},
+                    params: RadianceParams::default(),
                 })
             },
             test_util::create_mock_query(),
@@ -444,7 +454,7 @@ mod tests {
                     test_util::create_mock_source::<u8>(props, None, Some(Measurement::Unitless));
 
                 RasterOperator::boxed(Radiance {
-                    params: RadianceParams {},
+                    params: RadianceParams::default(),
                     sources: SingleRasterSource {
                         raster: src.boxed(),
                     },
@@ -480,7 +490,7 @@ mod tests {
                 );
 
                 RasterOperator::boxed(Radiance {
-                    params: RadianceParams {},
+                    params: RadianceParams::default(),
                     sources: SingleRasterSource {
                         raster: src.boxed(),
                     },
@@ -517,7 +527,7 @@ mod tests {
                 );
 
                 RasterOperator::boxed(Radiance {
-                    params: RadianceParams {},
+                    params: RadianceParams::default(),
                     sources: SingleRasterSource {
                         raster: src.boxed(),
                     },
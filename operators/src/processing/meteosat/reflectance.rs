@@ -26,7 +26,7 @@ use serde::{Deserialize, Serialize};
 
 // Output type is always f32
 type PixelOut = f32;
-use crate::processing::meteosat::satellite::{Channel, Satellite};
+use crate::processing::meteosat::satellite::{Channel, Platform, Satellite};
 use crate::processing::meteosat::{new_channel_key, new_satellite_key};
 use crate::util::sunpos::SunPos;
 use RasterDataType::F32 as RasterOut;
@@ -35,6 +35,7 @@ use RasterDataType::F32 as RasterOut;
 /// * `solar_correction` switch to enable solar correction.
 /// * `force_hrv` switch to force the use of the hrv channel.
 /// * `force_satellite` forces the use of the satellite with the given name.
+/// * `platform` determines the satellites and the domain of the metadata properties, defaults to Meteosat.
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Default, Copy)]
 #[serde(rename_all = "camelCase")]
 pub struct ReflectanceParams {
@@ -42,9 +43,11 @@ pub struct ReflectanceParams {
     #[serde(rename = "forceHRV")]
     pub force_hrv: bool,
     pub force_satellite: Option<u8>,
+    #[serde(default)]
+    pub platform: Platform,
 }
 
-/// The reflectance operator consumes an MSG image preprocessed
+/// The reflectance operator consumes an image of a geostationary satellite preprocessed
 /// via the radiance operator and computes the reflectance value
 /// from a given radiance raster.
 pub type Reflectance = Operator<ReflectanceParams, SingleRasterSource>;
@@ -192,8 +195,8 @@ where
             source,
             result_descriptor,
             params,
-            channel_key: new_channel_key(),
-            satellite_key: new_satellite_key(),
+            channel_key: new_channel_key(params.platform),
+            satellite_key: new_satellite_key(params.platform),
         }
     }
 
@@ -203,7 +206,7 @@ where
         satellite: &'a Satellite,
     ) -> Result<&'a Channel> {
         if self.params.force_hrv {
-            Ok(satellite.hrv())
+            satellite.hrv()
         } else {
             let channel_id = tile
                 .properties
@@ -218,7 +221,7 @@ where
             Some(id) => id,
             _ => tile.properties.number_property(&self.satellite_key)?,
         };
-        Satellite::satellite_by_msg_id(id)
+        self.params.platform.satellite(id)
     }
 
     async fn process_tile_async(
@@ -244,6 +247,7 @@ where
             None
         };
         let etsr = channel.etsr / std::f64::consts::PI;
+        let sub_lon = satellite.sub_lon;
         let esd = calculate_esd(&timestamp);
         let tile_geo_transform = tile.tile_geo_transform();
 
@@ -253,7 +257,7 @@ where
                     let geos_coord =
                         tile_geo_transform.grid_idx_to_pixel_center_coordinate_2d(grid_idx);
 
-                    let (lat, lon) = channel.view_angle_lat_lon(geos_coord, 0.0);
+                    let (lat, lon) = channel.view_angle_lat_lon(geos_coord, sub_lon);
                     let (_, zenith) = sun_pos.solar_azimuth_zenith(lat, lon);
 
                     (f64::from(p) * esd * esd / (etsr * zenith.min(80.0).to_radians().cos()))
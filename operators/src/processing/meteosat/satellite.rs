@@ -1,36 +1,143 @@
 //! # Satellite
 //! Contains the `Satellite` struct and utility methods
-//! for working with Meteosat satellites 8-11
+//! for working with geostationary satellites, i.e.,
+//! Meteosat 8-11 (SEVIRI), GOES 16-18 (ABI) and Himawari 8-9 (AHI)
 
 use crate::error::Error;
 use crate::util::Result;
 use geoengine_datatypes::primitives::Coordinate2D;
+use serde::{Deserialize, Serialize};
 
 const VIEW_ANGLE: f64 = 65536.0 / (-13_642_337.0 * 3_000.403_165_817);
 const VIEW_ANGLE_CH11: f64 = 65536.0 / (-40_927_014.0 * 1_000.134_348_869);
+/// The view angle factor for coordinates of the `geos` projection in meters
+const VIEW_ANGLE_GEOS: f64 = -180.0 / (std::f64::consts::PI * 35_786_023.0);
 
 const C1: f64 = 1.191_042_73e-16;
 const C2: f64 = 0.014_387_752_3;
 
-/// Represents the parameters of a Meteosat satellite
+const PLANCK: f64 = 6.626_070_15e-34;
+const SPEED_OF_LIGHT: f64 = 299_792_458.0;
+const BOLTZMANN: f64 = 1.380_649e-23;
+
+/// The family of geostationary satellites that determines the calibration and the metadata keys
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Platform {
+    /// Meteosat Second Generation with the SEVIRI instrument
+    #[default]
+    Meteosat,
+    /// GOES-R series with the ABI instrument
+    GoesR,
+    /// Himawari with the AHI instrument
+    Himawari,
+}
+
+impl Platform {
+    /// The domain of the raster properties that contain the calibration metadata
+    pub fn metadata_domain(self) -> &'static str {
+        match self {
+            Self::Meteosat => "msg",
+            Self::GoesR => "goes",
+            Self::Himawari => "himawari",
+        }
+    }
+
+    /// The number of distinct raw values of the instrument
+    pub fn raw_value_count(self) -> usize {
+        match self {
+            Self::Meteosat => 1024,
+            Self::GoesR | Self::Himawari => 16384,
+        }
+    }
+
+    /// Retrieves the satellite for its `number` within the platform, i.e.,
+    /// 1-4 for Meteosat 8-11, 16-18 for GOES and 8-9 for Himawari.
+    pub fn satellite(self, number: u8) -> Result<&'static Satellite> {
+        match (self, number) {
+            (Self::Meteosat, number) => Satellite::satellite_by_msg_id(number),
+            (Self::GoesR, 16) => Ok(&GOES_16),
+            (Self::GoesR, 17) => Ok(&GOES_17),
+            (Self::GoesR, 18) => Ok(&GOES_18),
+            (Self::Himawari, 8) => Ok(&HIMAWARI_08),
+            (Self::Himawari, 9) => Ok(&HIMAWARI_09),
+            _ => Err(Error::InvalidMeteosatSatellite),
+        }
+    }
+}
+
+/// Represents the parameters of a geostationary satellite
 #[derive(Debug, PartialEq)]
 pub struct Satellite {
     pub name: &'static str,
-    pub meteosat_id: u8,
-    pub msg_id: u8,
-    pub channels: [Channel; 12],
+    pub platform: Platform,
+    pub number: u8,
+    /// The longitude of the sub-satellite point
+    pub sub_lon: f64,
+    pub channels: &'static [Channel],
 }
 
-/// Represents a channel of a meteosat satellite.
+/// Represents a channel of a geostationary satellite.
 #[derive(Debug, PartialEq)]
 pub struct Channel {
     pub name: &'static str,
     view_angle_factor: f64,
     pub cwl: f64,
+    /// The extraterrestrial solar irradiance in the radiance units of the channel
     pub etsr: f64,
-    pub vc: f64,
-    pub alpha: f64,
-    pub beta: f64,
+    pub brightness_temperature: Option<BrightnessTemperature>,
+}
+
+/// The conversion of radiances of thermal channels into brightness temperatures
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BrightnessTemperature {
+    /// SEVIRI with the central wavenumber `vc` in cm^-1 and the linear correction `alpha` and `beta`
+    /// for radiances in mW·m^-2·sr^-1·(cm^-1)^-1
+    Seviri { vc: f64, alpha: f64, beta: f64 },
+    /// ABI with the Planck function constants `fk1` and `fk2` and the linear correction `bc1` and
+    /// `bc2` for radiances in mW·m^-2·sr^-1·(cm^-1)^-1
+    Abi {
+        fk1: f64,
+        fk2: f64,
+        bc1: f64,
+        bc2: f64,
+    },
+    /// AHI with the central `wavelength` in µm and the quadratic correction `c0`, `c1` and `c2`
+    /// for radiances in W·m^-2·sr^-1·µm^-1
+    Ahi {
+        wavelength: f64,
+        c0: f64,
+        c1: f64,
+        c2: f64,
+    },
+}
+
+impl BrightnessTemperature {
+    /// Calculates the temperature in K from the given radiance value.
+    pub fn temperature_from_radiance(self, radiance: f64) -> f64 {
+        match self {
+            Self::Seviri { vc, alpha, beta } => {
+                let temp = (C1 * 1.0e6 * vc.powi(3)) / (1.0e-5 * radiance);
+                ((C2 * 100.0 * vc / (temp + 1.0).ln()) - beta) / alpha
+            }
+            Self::Abi { fk1, fk2, bc1, bc2 } => (fk2 / (fk1 / radiance + 1.0).ln() - bc1) / bc2,
+            Self::Ahi {
+                wavelength,
+                c0,
+                c1,
+                c2,
+            } => {
+                let wavelength = wavelength * 1.0e-6;
+                // radiance per meter instead of per µm
+                let radiance = radiance * 1.0e6;
+                let effective = (PLANCK * SPEED_OF_LIGHT / (BOLTZMANN * wavelength))
+                    / ((2.0 * PLANCK * SPEED_OF_LIGHT.powi(2)) / (wavelength.powi(5) * radiance)
+                        + 1.0)
+                        .ln();
+                c0 + c1 * effective + c2 * effective.powi(2)
+            }
+        }
+    }
 }
 
 impl Satellite {
@@ -45,7 +152,7 @@ impl Satellite {
         }
     }
 
-    /// Returns the channel for the given id (0-11).
+    /// Returns the channel for the given id (starting at 0).
     pub fn channel(&self, channel_id: usize) -> Result<&Channel> {
         match self.channels.get(channel_id) {
             Some(c) => Ok(c),
@@ -55,9 +162,12 @@ impl Satellite {
         }
     }
 
-    /// Returns the HRV channel
-    pub fn hrv(&self) -> &Channel {
-        unsafe { self.channels.get_unchecked(11) }
+    /// Returns the HRV channel, which only exists for Meteosat
+    pub fn hrv(&self) -> Result<&Channel> {
+        match self.platform {
+            Platform::Meteosat => self.channel(11),
+            Platform::GoesR | Platform::Himawari => Err(Error::InvalidChannel { channel: 11 }),
+        }
     }
 }
 
@@ -107,9 +217,10 @@ impl Channel {
     }
 
     /// Calculates the temperature in K from the given channel and radiance value.
-    pub fn calculate_temperature_from_radiance(&self, radiance: f64) -> f64 {
-        let temp = (C1 * 1.0e6 * self.vc.powi(3)) / (1.0e-5 * radiance);
-        ((C2 * 100.0 * self.vc / (temp + 1.0).ln()) - self.beta) / self.alpha
+    /// Returns `None` for channels without thermal calibration.
+    pub fn calculate_temperature_from_radiance(&self, radiance: f64) -> Option<f64> {
+        self.brightness_temperature
+            .map(|calibration| calibration.temperature_from_radiance(radiance))
     }
 }
 
@@ -120,471 +231,892 @@ impl Channel {
 
 static METEOSAT_08: Satellite = Satellite {
     name: "Meteosat-8",
-    meteosat_id: 8,
-    msg_id: 1,
-    channels: [
+    platform: Platform::Meteosat,
+    number: 1,
+    sub_lon: 0.0,
+    channels: &[
         Channel {
             name: "VIS006",
             view_angle_factor: VIEW_ANGLE,
             cwl: 0.639,
             etsr: 65.2296,
-            vc: 0.0000,
-            alpha: 0.0000,
-            beta: 0.0000,
+            brightness_temperature: None,
         },
         Channel {
             name: "VIS008",
             view_angle_factor: VIEW_ANGLE,
             cwl: 0.809,
             etsr: 73.0127,
-            vc: 0.0000,
-            alpha: 0.0000,
-            beta: 0.0000,
+            brightness_temperature: None,
         },
         Channel {
             name: "IR_016",
             view_angle_factor: VIEW_ANGLE,
             cwl: 1.635,
             etsr: 62.3715,
-            vc: 0.0000,
-            alpha: 0.0000,
-            beta: 0.0000,
+            brightness_temperature: None,
         },
         Channel {
             name: "IR_039",
             view_angle_factor: VIEW_ANGLE,
             cwl: 3.965,
             etsr: 0.0000,
-            vc: 2567.3300,
-            alpha: 0.9956,
-            beta: 3.4100,
+            brightness_temperature: Some(BrightnessTemperature::Seviri {
+                vc: 2567.3300,
+                alpha: 0.9956,
+                beta: 3.4100,
+            }),
         },
         Channel {
             name: "WV_062",
             view_angle_factor: VIEW_ANGLE,
             cwl: 6.337,
             etsr: 0.0000,
-            vc: 1598.1030,
-            alpha: 0.9962,
-            beta: 2.2180,
+            brightness_temperature: Some(BrightnessTemperature::Seviri {
+                vc: 1598.1030,
+                alpha: 0.9962,
+                beta: 2.2180,
+            }),
         },
         Channel {
             name: "WV_073",
             view_angle_factor: VIEW_ANGLE,
             cwl: 7.362,
             etsr: 0.0000,
-            vc: 1362.0810,
-            alpha: 0.9991,
-            beta: 0.4780,
+            brightness_temperature: Some(BrightnessTemperature::Seviri {
+                vc: 1362.0810,
+                alpha: 0.9991,
+                beta: 0.4780,
+            }),
         },
         Channel {
             name: "IR_087",
             view_angle_factor: VIEW_ANGLE,
             cwl: 8.718,
             etsr: 0.0000,
-            vc: 1149.0690,
-            alpha: 0.9996,
-            beta: 0.1790,
+            brightness_temperature: Some(BrightnessTemperature::Seviri {
+                vc: 1149.0690,
+                alpha: 0.9996,
+                beta: 0.1790,
+            }),
         },
         Channel {
             name: "IR_097",
             view_angle_factor: VIEW_ANGLE,
             cwl: 9.668,
             etsr: 0.0000,
-            vc: 1034.3430,
-            alpha: 0.9999,
-            beta: 0.0600,
+            brightness_temperature: Some(BrightnessTemperature::Seviri {
+                vc: 1034.3430,
+                alpha: 0.9999,
+                beta: 0.0600,
+            }),
         },
         Channel {
             name: "IR_108",
             view_angle_factor: VIEW_ANGLE,
             cwl: 10.763,
             etsr: 0.0000,
-            vc: 930.6470,
-            alpha: 0.9983,
-            beta: 0.6250,
+            brightness_temperature: Some(BrightnessTemperature::Seviri {
+                vc: 930.6470,
+                alpha: 0.9983,
+                beta: 0.6250,
+            }),
         },
         Channel {
             name: "IR_120",
             view_angle_factor: VIEW_ANGLE,
             cwl: 11.938,
             etsr: 0.0000,
-            vc: 839.6600,
-            alpha: 0.9988,
-            beta: 0.3970,
+            brightness_temperature: Some(BrightnessTemperature::Seviri {
+                vc: 839.6600,
+                alpha: 0.9988,
+                beta: 0.3970,
+            }),
         },
         Channel {
             name: "IR_134",
             view_angle_factor: VIEW_ANGLE,
             cwl: 13.355,
             etsr: 0.0000,
-            vc: 752.3870,
-            alpha: 0.9981,
-            beta: 0.5780,
+            brightness_temperature: Some(BrightnessTemperature::Seviri {
+                vc: 752.3870,
+                alpha: 0.9981,
+                beta: 0.5780,
+            }),
         },
         Channel {
             name: "HRV",
             view_angle_factor: VIEW_ANGLE_CH11,
             cwl: 0.674,
             etsr: 78.7599,
-            vc: 0.0000,
-            alpha: 0.0000,
-            beta: 0.0000,
+            brightness_temperature: None,
         },
     ],
 };
 
 static METEOSAT_09: Satellite = Satellite {
     name: "Meteosat-9",
-    meteosat_id: 9,
-    msg_id: 2,
-    channels: [
+    platform: Platform::Meteosat,
+    number: 2,
+    sub_lon: 0.0,
+    channels: &[
         Channel {
             name: "VIS006",
             view_angle_factor: VIEW_ANGLE,
             cwl: 0.639,
             etsr: 65.2065,
-            vc: 0.0000,
-            alpha: 0.0000,
-            beta: 0.0000,
+            brightness_temperature: None,
         },
         Channel {
             name: "VIS008",
             view_angle_factor: VIEW_ANGLE,
             cwl: 0.809,
             etsr: 73.1869,
-            vc: 0.0000,
-            alpha: 0.0000,
-            beta: 0.0000,
+            brightness_temperature: None,
         },
         Channel {
             name: "IR_016",
             view_angle_factor: VIEW_ANGLE,
             cwl: 1.635,
             etsr: 61.9923,
-            vc: 0.0000,
-            alpha: 0.0000,
-            beta: 0.0000,
+            brightness_temperature: None,
         },
         Channel {
             name: "IR_039",
             view_angle_factor: VIEW_ANGLE,
             cwl: 3.965,
             etsr: 0.0000,
-            vc: 2568.8320,
-            alpha: 0.9954,
-            beta: 3.4380,
+            brightness_temperature: Some(BrightnessTemperature::Seviri {
+                vc: 2568.8320,
+                alpha: 0.9954,
+                beta: 3.4380,
+            }),
         },
         Channel {
             name: "WV_062",
             view_angle_factor: VIEW_ANGLE,
             cwl: 6.337,
             etsr: 0.0000,
-            vc: 1600.5480,
-            alpha: 0.9963,
-            beta: 2.1850,
+            brightness_temperature: Some(BrightnessTemperature::Seviri {
+                vc: 1600.5480,
+                alpha: 0.9963,
+                beta: 2.1850,
+            }),
         },
         Channel {
             name: "WV_073",
             view_angle_factor: VIEW_ANGLE,
             cwl: 7.362,
             etsr: 0.0000,
-            vc: 1360.3300,
-            alpha: 0.9991,
-            beta: 0.4700,
+            brightness_temperature: Some(BrightnessTemperature::Seviri {
+                vc: 1360.3300,
+                alpha: 0.9991,
+                beta: 0.4700,
+            }),
         },
         Channel {
             name: "IR_087",
             view_angle_factor: VIEW_ANGLE,
             cwl: 8.718,
             etsr: 0.0000,
-            vc: 1148.6200,
-            alpha: 0.9996,
-            beta: 0.1790,
+            brightness_temperature: Some(BrightnessTemperature::Seviri {
+                vc: 1148.6200,
+                alpha: 0.9996,
+                beta: 0.1790,
+            }),
         },
         Channel {
             name: "IR_097",
             view_angle_factor: VIEW_ANGLE,
             cwl: 9.668,
             etsr: 0.0000,
-            vc: 1035.2890,
-            alpha: 0.9999,
-            beta: 0.0560,
+            brightness_temperature: Some(BrightnessTemperature::Seviri {
+                vc: 1035.2890,
+                alpha: 0.9999,
+                beta: 0.0560,
+            }),
         },
         Channel {
             name: "IR_108",
             view_angle_factor: VIEW_ANGLE,
             cwl: 10.763,
             etsr: 0.0000,
-            vc: 931.7000,
-            alpha: 0.9983,
-            beta: 0.6400,
+            brightness_temperature: Some(BrightnessTemperature::Seviri {
+                vc: 931.7000,
+                alpha: 0.9983,
+                beta: 0.6400,
+            }),
         },
         Channel {
             name: "IR_120",
             view_angle_factor: VIEW_ANGLE,
             cwl: 11.938,
             etsr: 0.0000,
-            vc: 836.4450,
-            alpha: 0.9988,
-            beta: 0.4080,
+            brightness_temperature: Some(BrightnessTemperature::Seviri {
+                vc: 836.4450,
+                alpha: 0.9988,
+                beta: 0.4080,
+            }),
         },
         Channel {
             name: "IR_134",
             view_angle_factor: VIEW_ANGLE,
             cwl: 13.355,
             etsr: 0.0000,
-            vc: 751.7920,
-            alpha: 0.9981,
-            beta: 0.5610,
+            brightness_temperature: Some(BrightnessTemperature::Seviri {
+                vc: 751.7920,
+                alpha: 0.9981,
+                beta: 0.5610,
+            }),
         },
         Channel {
             name: "HRV",
             view_angle_factor: VIEW_ANGLE_CH11,
             cwl: 0.674,
             etsr: 79.0113,
-            vc: 0.0000,
-            alpha: 0.0000,
-            beta: 0.0000,
+            brightness_temperature: None,
         },
     ],
 };
 
 static METEOSAT_10: Satellite = Satellite {
     name: "Meteosat-10",
-    meteosat_id: 10,
-    msg_id: 3,
-    channels: [
+    platform: Platform::Meteosat,
+    number: 3,
+    sub_lon: 0.0,
+    channels: &[
         Channel {
             name: "VIS006",
             view_angle_factor: VIEW_ANGLE,
             cwl: 0.639,
             etsr: 65.5148,
-            vc: 0.0000,
-            alpha: 0.0000,
-            beta: 0.0000,
+            brightness_temperature: None,
         },
         Channel {
             name: "VIS008",
             view_angle_factor: VIEW_ANGLE,
             cwl: 0.809,
             etsr: 73.1807,
-            vc: 0.0000,
-            alpha: 0.0000,
-            beta: 0.0000,
+            brightness_temperature: None,
         },
         Channel {
             name: "IR_016",
             view_angle_factor: VIEW_ANGLE,
             cwl: 1.635,
             etsr: 62.0208,
-            vc: 0.0000,
-            alpha: 0.0000,
-            beta: 0.0000,
+            brightness_temperature: None,
         },
         Channel {
             name: "IR_039",
             view_angle_factor: VIEW_ANGLE,
             cwl: 3.965,
             etsr: 0.0000,
-            vc: 2547.7710,
-            alpha: 0.9915,
-            beta: 2.9002,
+            brightness_temperature: Some(BrightnessTemperature::Seviri {
+                vc: 2547.7710,
+                alpha: 0.9915,
+                beta: 2.9002,
+            }),
         },
         Channel {
             name: "WV_062",
             view_angle_factor: VIEW_ANGLE,
             cwl: 6.337,
             etsr: 0.0000,
-            vc: 1595.6210,
-            alpha: 0.9960,
-            beta: 2.0337,
+            brightness_temperature: Some(BrightnessTemperature::Seviri {
+                vc: 1595.6210,
+                alpha: 0.9960,
+                beta: 2.0337,
+            }),
         },
         Channel {
             name: "WV_073",
             view_angle_factor: VIEW_ANGLE,
             cwl: 7.362,
             etsr: 0.0000,
-            vc: 1360.3370,
-            alpha: 0.9991,
-            beta: 0.4340,
+            brightness_temperature: Some(BrightnessTemperature::Seviri {
+                vc: 1360.3370,
+                alpha: 0.9991,
+                beta: 0.4340,
+            }),
         },
         Channel {
             name: "IR_087",
             view_angle_factor: VIEW_ANGLE,
             cwl: 8.718,
             etsr: 0.0000,
-            vc: 1148.1300,
-            alpha: 0.9996,
-            beta: 0.1714,
+            brightness_temperature: Some(BrightnessTemperature::Seviri {
+                vc: 1148.1300,
+                alpha: 0.9996,
+                beta: 0.1714,
+            }),
         },
         Channel {
             name: "IR_097",
             view_angle_factor: VIEW_ANGLE,
             cwl: 9.668,
             etsr: 0.0000,
-            vc: 1034.7150,
-            alpha: 0.9999,
-            beta: 0.0527,
+            brightness_temperature: Some(BrightnessTemperature::Seviri {
+                vc: 1034.7150,
+                alpha: 0.9999,
+                beta: 0.0527,
+            }),
         },
         Channel {
             name: "IR_108",
             view_angle_factor: VIEW_ANGLE,
             cwl: 10.763,
             etsr: 0.0000,
-            vc: 929.8420,
-            alpha: 0.9983,
-            beta: 0.6084,
+            brightness_temperature: Some(BrightnessTemperature::Seviri {
+                vc: 929.8420,
+                alpha: 0.9983,
+                beta: 0.6084,
+            }),
         },
         Channel {
             name: "IR_120",
             view_angle_factor: VIEW_ANGLE,
             cwl: 11.938,
             etsr: 0.0000,
-            vc: 838.6590,
-            alpha: 0.9988,
-            beta: 0.3882,
+            brightness_temperature: Some(BrightnessTemperature::Seviri {
+                vc: 838.6590,
+                alpha: 0.9988,
+                beta: 0.3882,
+            }),
         },
         Channel {
             name: "IR_134",
             view_angle_factor: VIEW_ANGLE,
             cwl: 13.355,
             etsr: 0.0000,
-            vc: 750.6530,
-            alpha: 0.9982,
-            beta: 0.5390,
+            brightness_temperature: Some(BrightnessTemperature::Seviri {
+                vc: 750.6530,
+                alpha: 0.9982,
+                beta: 0.5390,
+            }),
         },
         Channel {
             name: "HRV",
             view_angle_factor: VIEW_ANGLE_CH11,
             cwl: 0.674,
             etsr: 78.9416,
-            vc: 0.0000,
-            alpha: 0.0000,
-            beta: 0.0000,
+            brightness_temperature: None,
         },
     ],
 };
 
 static METEOSAT_11: Satellite = Satellite {
     name: "Meteosat-11",
-    meteosat_id: 11,
-    msg_id: 4,
-    channels: [
+    platform: Platform::Meteosat,
+    number: 4,
+    sub_lon: 0.0,
+    channels: &[
         Channel {
             name: "VIS006",
             view_angle_factor: VIEW_ANGLE,
             cwl: 0.639,
             etsr: 65.2656,
-            vc: 0.0000,
-            alpha: 0.0000,
-            beta: 0.0000,
+            brightness_temperature: None,
         },
         Channel {
             name: "VIS008",
             view_angle_factor: VIEW_ANGLE,
             cwl: 0.809,
             etsr: 73.1692,
-            vc: 0.0000,
-            alpha: 0.0000,
-            beta: 0.0000,
+            brightness_temperature: None,
         },
         Channel {
             name: "IR_016",
             view_angle_factor: VIEW_ANGLE,
             cwl: 1.635,
             etsr: 61.9416,
-            vc: 0.0000,
-            alpha: 0.0000,
-            beta: 0.0000,
+            brightness_temperature: None,
         },
         Channel {
             name: "IR_039",
             view_angle_factor: VIEW_ANGLE,
             cwl: 3.965,
             etsr: 0.0000,
-            vc: 2555.2800,
-            alpha: 0.9916,
-            beta: 2.9438,
+            brightness_temperature: Some(BrightnessTemperature::Seviri {
+                vc: 2555.2800,
+                alpha: 0.9916,
+                beta: 2.9438,
+            }),
         },
         Channel {
             name: "WV_062",
             view_angle_factor: VIEW_ANGLE,
             cwl: 6.337,
             etsr: 0.0000,
-            vc: 1596.0800,
-            alpha: 0.9959,
-            beta: 2.0780,
+            brightness_temperature: Some(BrightnessTemperature::Seviri {
+                vc: 1596.0800,
+                alpha: 0.9959,
+                beta: 2.0780,
+            }),
         },
         Channel {
             name: "WV_073",
             view_angle_factor: VIEW_ANGLE,
             cwl: 7.362,
             etsr: 0.0000,
-            vc: 1361.7480,
-            alpha: 0.9990,
-            beta: 0.4929,
+            brightness_temperature: Some(BrightnessTemperature::Seviri {
+                vc: 1361.7480,
+                alpha: 0.9990,
+                beta: 0.4929,
+            }),
         },
         Channel {
             name: "IR_087",
             view_angle_factor: VIEW_ANGLE,
             cwl: 8.718,
             etsr: 0.0000,
-            vc: 1147.4330,
-            alpha: 0.9996,
-            beta: 0.1731,
+            brightness_temperature: Some(BrightnessTemperature::Seviri {
+                vc: 1147.4330,
+                alpha: 0.9996,
+                beta: 0.1731,
+            }),
         },
         Channel {
             name: "IR_097",
             view_angle_factor: VIEW_ANGLE,
             cwl: 9.668,
             etsr: 0.0000,
-            vc: 1034.8510,
-            alpha: 0.9998,
-            beta: 0.0597,
+            brightness_temperature: Some(BrightnessTemperature::Seviri {
+                vc: 1034.8510,
+                alpha: 0.9998,
+                beta: 0.0597,
+            }),
         },
         Channel {
             name: "IR_108",
             view_angle_factor: VIEW_ANGLE,
             cwl: 10.763,
             etsr: 0.0000,
-            vc: 931.1220,
-            alpha: 0.9983,
-            beta: 0.6256,
+            brightness_temperature: Some(BrightnessTemperature::Seviri {
+                vc: 931.1220,
+                alpha: 0.9983,
+                beta: 0.6256,
+            }),
         },
         Channel {
             name: "IR_120",
             view_angle_factor: VIEW_ANGLE,
             cwl: 11.938,
             etsr: 0.0000,
-            vc: 839.1130,
-            alpha: 0.9988,
-            beta: 0.4002,
+            brightness_temperature: Some(BrightnessTemperature::Seviri {
+                vc: 839.1130,
+                alpha: 0.9988,
+                beta: 0.4002,
+            }),
         },
         Channel {
             name: "IR_134",
             view_angle_factor: VIEW_ANGLE,
             cwl: 13.355,
             etsr: 0.0000,
-            vc: 748.5850,
-            alpha: 0.9981,
-            beta: 0.5635,
+            brightness_temperature: Some(BrightnessTemperature::Seviri {
+                vc: 748.5850,
+                alpha: 0.9981,
+                beta: 0.5635,
+            }),
         },
         Channel {
             name: "HRV",
             view_angle_factor: VIEW_ANGLE_CH11,
             cwl: 0.674,
             etsr: 79.0035,
-            vc: 0.0000,
-            alpha: 0.0000,
-            beta: 0.0000,
+            brightness_temperature: None,
         },
     ],
 };
 
+//
+// The reflective channels use the band-averaged solar irradiance (esun) and the thermal channels
+// the Planck function constants of the GOES-16 ABI L1b products.
+// The L1b products of each satellite contain the exact values.
+//
+
+static ABI_CHANNELS: [Channel; 16] = [
+    Channel {
+        name: "C01",
+        view_angle_factor: VIEW_ANGLE_GEOS,
+        cwl: 0.47,
+        etsr: 2_017.164_8,
+        brightness_temperature: None,
+    },
+    Channel {
+        name: "C02",
+        view_angle_factor: VIEW_ANGLE_GEOS,
+        cwl: 0.64,
+        etsr: 1_631.335_1,
+        brightness_temperature: None,
+    },
+    Channel {
+        name: "C03",
+        view_angle_factor: VIEW_ANGLE_GEOS,
+        cwl: 0.865,
+        etsr: 957.069_9,
+        brightness_temperature: None,
+    },
+    Channel {
+        name: "C04",
+        view_angle_factor: VIEW_ANGLE_GEOS,
+        cwl: 1.378,
+        etsr: 360.901_8,
+        brightness_temperature: None,
+    },
+    Channel {
+        name: "C05",
+        view_angle_factor: VIEW_ANGLE_GEOS,
+        cwl: 1.61,
+        etsr: 242.540_4,
+        brightness_temperature: None,
+    },
+    Channel {
+        name: "C06",
+        view_angle_factor: VIEW_ANGLE_GEOS,
+        cwl: 2.25,
+        etsr: 77.676_3,
+        brightness_temperature: None,
+    },
+    Channel {
+        name: "C07",
+        view_angle_factor: VIEW_ANGLE_GEOS,
+        cwl: 3.9,
+        etsr: 0.0,
+        brightness_temperature: Some(BrightnessTemperature::Abi {
+            fk1: 2.022_63e5,
+            fk2: 3.698_19e3,
+            bc1: 0.433_61,
+            bc2: 0.999_39,
+        }),
+    },
+    Channel {
+        name: "C08",
+        view_angle_factor: VIEW_ANGLE_GEOS,
+        cwl: 6.185,
+        etsr: 0.0,
+        brightness_temperature: Some(BrightnessTemperature::Abi {
+            fk1: 5.068_71e4,
+            fk2: 2.331_58e3,
+            bc1: 1.552_28,
+            bc2: 0.996_67,
+        }),
+    },
+    Channel {
+        name: "C09",
+        view_angle_factor: VIEW_ANGLE_GEOS,
+        cwl: 6.95,
+        etsr: 0.0,
+        brightness_temperature: Some(BrightnessTemperature::Abi {
+            fk1: 3.582_83e4,
+            fk2: 2.076_95e3,
+            bc1: 0.344_27,
+            bc2: 0.999_18,
+        }),
+    },
+    Channel {
+        name: "C10",
+        view_angle_factor: VIEW_ANGLE_GEOS,
+        cwl: 7.34,
+        etsr: 0.0,
+        brightness_temperature: Some(BrightnessTemperature::Abi {
+            fk1: 3.017_40e4,
+            fk2: 1.961_38e3,
+            bc1: 0.056_51,
+            bc2: 0.999_86,
+        }),
+    },
+    Channel {
+        name: "C11",
+        view_angle_factor: VIEW_ANGLE_GEOS,
+        cwl: 8.5,
+        etsr: 0.0,
+        brightness_temperature: Some(BrightnessTemperature::Abi {
+            fk1: 1.977_99e4,
+            fk2: 1.703_83e3,
+            bc1: 0.187_33,
+            bc2: 0.999_48,
+        }),
+    },
+    Channel {
+        name: "C12",
+        view_angle_factor: VIEW_ANGLE_GEOS,
+        cwl: 9.61,
+        etsr: 0.0,
+        brightness_temperature: Some(BrightnessTemperature::Abi {
+            fk1: 1.343_21e4,
+            fk2: 1.497_84e3,
+            bc1: 0.091_02,
+            bc2: 0.999_71,
+        }),
+    },
+    Channel {
+        name: "C13",
+        view_angle_factor: VIEW_ANGLE_GEOS,
+        cwl: 10.35,
+        etsr: 0.0,
+        brightness_temperature: Some(BrightnessTemperature::Abi {
+            fk1: 1.080_33e4,
+            fk2: 1.392_16e3,
+            bc1: 0.075_50,
+            bc2: 0.999_75,
+        }),
+    },
+    Channel {
+        name: "C14",
+        view_angle_factor: VIEW_ANGLE_GEOS,
+        cwl: 11.2,
+        etsr: 0.0,
+        brightness_temperature: Some(BrightnessTemperature::Abi {
+            fk1: 8.510_22e3,
+            fk2: 1.285_21e3,
+            bc1: 0.225_16,
+            bc2: 0.999_20,
+        }),
+    },
+    Channel {
+        name: "C15",
+        view_angle_factor: VIEW_ANGLE_GEOS,
+        cwl: 12.3,
+        etsr: 0.0,
+        brightness_temperature: Some(BrightnessTemperature::Abi {
+            fk1: 6.454_62e3,
+            fk2: 1.173_03e3,
+            bc1: 0.217_02,
+            bc2: 0.999_16,
+        }),
+    },
+    Channel {
+        name: "C16",
+        view_angle_factor: VIEW_ANGLE_GEOS,
+        cwl: 13.3,
+        etsr: 0.0,
+        brightness_temperature: Some(BrightnessTemperature::Abi {
+            fk1: 5.101_27e3,
+            fk2: 1.084_53e3,
+            bc1: 0.062_66,
+            bc2: 0.999_74,
+        }),
+    },
+];
+
+static GOES_16: Satellite = Satellite {
+    name: "GOES-16",
+    platform: Platform::GoesR,
+    number: 16,
+    sub_lon: -75.2,
+    channels: &ABI_CHANNELS,
+};
+
+static GOES_17: Satellite = Satellite {
+    name: "GOES-17",
+    platform: Platform::GoesR,
+    number: 17,
+    sub_lon: -137.2,
+    channels: &ABI_CHANNELS,
+};
+
+static GOES_18: Satellite = Satellite {
+    name: "GOES-18",
+    platform: Platform::GoesR,
+    number: 18,
+    sub_lon: -137.0,
+    channels: &ABI_CHANNELS,
+};
+
+//
+// The reflective channels use the band-averaged solar irradiance and the thermal channels the
+// central wavelengths of the Himawari-8 AHI.
+// The correction of the effective temperature is omitted since its coefficients are specific to
+// each data file.
+//
+
+static AHI_CHANNELS: [Channel; 16] = [
+    Channel {
+        name: "B01",
+        view_angle_factor: VIEW_ANGLE_GEOS,
+        cwl: 0.47,
+        etsr: 2_044.6,
+        brightness_temperature: None,
+    },
+    Channel {
+        name: "B02",
+        view_angle_factor: VIEW_ANGLE_GEOS,
+        cwl: 0.51,
+        etsr: 1_873.4,
+        brightness_temperature: None,
+    },
+    Channel {
+        name: "B03",
+        view_angle_factor: VIEW_ANGLE_GEOS,
+        cwl: 0.64,
+        etsr: 1_609.7,
+        brightness_temperature: None,
+    },
+    Channel {
+        name: "B04",
+        view_angle_factor: VIEW_ANGLE_GEOS,
+        cwl: 0.86,
+        etsr: 986.8,
+        brightness_temperature: None,
+    },
+    Channel {
+        name: "B05",
+        view_angle_factor: VIEW_ANGLE_GEOS,
+        cwl: 1.6,
+        etsr: 240.5,
+        brightness_temperature: None,
+    },
+    Channel {
+        name: "B06",
+        view_angle_factor: VIEW_ANGLE_GEOS,
+        cwl: 2.3,
+        etsr: 77.0,
+        brightness_temperature: None,
+    },
+    Channel {
+        name: "B07",
+        view_angle_factor: VIEW_ANGLE_GEOS,
+        cwl: 3.885_3,
+        etsr: 0.0,
+        brightness_temperature: Some(BrightnessTemperature::Ahi {
+            wavelength: 3.885_3,
+            c0: 0.0,
+            c1: 1.0,
+            c2: 0.0,
+        }),
+    },
+    Channel {
+        name: "B08",
+        view_angle_factor: VIEW_ANGLE_GEOS,
+        cwl: 6.242_9,
+        etsr: 0.0,
+        brightness_temperature: Some(BrightnessTemperature::Ahi {
+            wavelength: 6.242_9,
+            c0: 0.0,
+            c1: 1.0,
+            c2: 0.0,
+        }),
+    },
+    Channel {
+        name: "B09",
+        view_angle_factor: VIEW_ANGLE_GEOS,
+        cwl: 6.941,
+        etsr: 0.0,
+        brightness_temperature: Some(BrightnessTemperature::Ahi {
+            wavelength: 6.941,
+            c0: 0.0,
+            c1: 1.0,
+            c2: 0.0,
+        }),
+    },
+    Channel {
+        name: "B10",
+        view_angle_factor: VIEW_ANGLE_GEOS,
+        cwl: 7.346_7,
+        etsr: 0.0,
+        brightness_temperature: Some(BrightnessTemperature::Ahi {
+            wavelength: 7.346_7,
+            c0: 0.0,
+            c1: 1.0,
+            c2: 0.0,
+        }),
+    },
+    Channel {
+        name: "B11",
+        view_angle_factor: VIEW_ANGLE_GEOS,
+        cwl: 8.592_6,
+        etsr: 0.0,
+        brightness_temperature: Some(BrightnessTemperature::Ahi {
+            wavelength: 8.592_6,
+            c0: 0.0,
+            c1: 1.0,
+            c2: 0.0,
+        }),
+    },
+    Channel {
+        name: "B12",
+        view_angle_factor: VIEW_ANGLE_GEOS,
+        cwl: 9.637_2,
+        etsr: 0.0,
+        brightness_temperature: Some(BrightnessTemperature::Ahi {
+            wavelength: 9.637_2,
+            c0: 0.0,
+            c1: 1.0,
+            c2: 0.0,
+        }),
+    },
+    Channel {
+        name: "B13",
+        view_angle_factor: VIEW_ANGLE_GEOS,
+        cwl: 10.407_3,
+        etsr: 0.0,
+        brightness_temperature: Some(BrightnessTemperature::Ahi {
+            wavelength: 10.407_3,
+            c0: 0.0,
+            c1: 1.0,
+            c2: 0.0,
+        }),
+    },
+    Channel {
+        name: "B14",
+        view_angle_factor: VIEW_ANGLE_GEOS,
+        cwl: 11.239_5,
+        etsr: 0.0,
+        brightness_temperature: Some(BrightnessTemperature::Ahi {
+            wavelength: 11.239_5,
+            c0: 0.0,
+            c1: 1.0,
+            c2: 0.0,
+        }),
+    },
+    Channel {
+        name: "B15",
+        view_angle_factor: VIEW_ANGLE_GEOS,
+        cwl: 12.380_6,
+        etsr: 0.0,
+        brightness_temperature: Some(BrightnessTemperature::Ahi {
+            wavelength: 12.380_6,
+            c0: 0.0,
+            c1: 1.0,
+            c2: 0.0,
+        }),
+    },
+    Channel {
+        name: "B16",
+        view_angle_factor: VIEW_ANGLE_GEOS,
+        cwl: 13.280_7,
+        etsr: 0.0,
+        brightness_temperature: Some(BrightnessTemperature::Ahi {
+            wavelength: 13.280_7,
+            c0: 0.0,
+            c1: 1.0,
+            c2: 0.0,
+        }),
+    },
+];
+
+static HIMAWARI_08: Satellite = Satellite {
+    name: "Himawari-8",
+    platform: Platform::Himawari,
+    number: 8,
+    sub_lon: 140.7,
+    channels: &AHI_CHANNELS,
+};
+
+static HIMAWARI_09: Satellite = Satellite {
+    name: "Himawari-9",
+    platform: Platform::Himawari,
+    number: 9,
+    sub_lon: 140.7,
+    channels: &AHI_CHANNELS,
+};
+
 #[cfg(test)]
 mod tests {
-    use crate::processing::meteosat::satellite::Satellite;
+    use crate::processing::meteosat::satellite::{
+        BrightnessTemperature, Platform, Satellite, BOLTZMANN, PLANCK, SPEED_OF_LIGHT,
+    };
 
     #[tokio::test]
     async fn satellite_by_msg_id_ok() {
@@ -611,4 +1143,65 @@ mod tests {
             .channel(42)
             .is_err());
     }
+
+    #[test]
+    fn satellite_by_platform() {
+        assert_eq!(Platform::Meteosat.satellite(4).unwrap().name, "Meteosat-11");
+        assert_eq!(Platform::GoesR.satellite(16).unwrap().name, "GOES-16");
+        assert_eq!(Platform::Himawari.satellite(9).unwrap().name, "Himawari-9");
+        assert!(Platform::GoesR.satellite(1).is_err());
+
+        assert!(Platform::Meteosat.satellite(1).unwrap().hrv().is_ok());
+        assert!(Platform::GoesR.satellite(16).unwrap().hrv().is_err());
+    }
+
+    #[test]
+    fn abi_temperature() {
+        let channel = Platform::GoesR.satellite(16).unwrap().channel(12).unwrap();
+        let Some(BrightnessTemperature::Abi { fk1, fk2, bc1, bc2 }) =
+            channel.brightness_temperature
+        else {
+            panic!("C13 must be a thermal channel");
+        };
+
+        // inverse of the conversion for 300 K
+        let radiance = fk1 / ((fk2 / (bc1 + bc2 * 300.0)).exp() - 1.0);
+
+        let temperature = channel
+            .calculate_temperature_from_radiance(radiance)
+            .unwrap();
+        assert!((temperature - 300.0).abs() < 1e-9);
+
+        assert!(Platform::GoesR
+            .satellite(16)
+            .unwrap()
+            .channel(0)
+            .unwrap()
+            .calculate_temperature_from_radiance(1.0)
+            .is_none());
+    }
+
+    #[test]
+    fn ahi_temperature() {
+        let channel = Platform::Himawari
+            .satellite(8)
+            .unwrap()
+            .channel(12)
+            .unwrap();
+
+        // Planck's law for 300 K at 10.4073 µm in W·m^-2·sr^-1·µm^-1
+        let wavelength = 10.4073e-6;
+        let radiance = 2.0 * PLANCK * SPEED_OF_LIGHT.powi(2)
+            / (wavelength.powi(5)
+                * ((PLANCK * SPEED_OF_LIGHT / (wavelength * BOLTZMANN * 300.0)).exp() - 1.0))
+            * 1.0e-6;
+
+        // approximately 9.9 W·m^-2·sr^-1·µm^-1
+        assert!((radiance - 9.9).abs() < 0.2);
+
+        let temperature = channel
+            .calculate_temperature_from_radiance(radiance)
+            .unwrap();
+        assert!((temperature - 300.0).abs() < 1e-6);
+    }
 }
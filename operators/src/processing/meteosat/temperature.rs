@@ -26,7 +26,7 @@ use serde::{Deserialize, Serialize};
 
 // Output type is always f32
 type PixelOut = f32;
-use crate::processing::meteosat::satellite::{Channel, Satellite};
+use crate::processing::meteosat::satellite::{BrightnessTemperature, Platform, Satellite};
 use crate::processing::meteosat::{
     new_channel_key, new_offset_key, new_satellite_key, new_slope_key,
 };
@@ -34,14 +34,17 @@ use RasterDataType::F32 as RasterOut;
 
 /// Parameters for the `Temperature` operator.
 /// * `force_satellite` forces the use of the satellite with the given name.
+/// * `platform` determines the satellites and the domain of the metadata properties, defaults to Meteosat.
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct TemperatureParams {
     force_satellite: Option<u8>,
+    #[serde(default)]
+    platform: Platform,
 }
 
 /// The temperature operator approximates BT from
-/// the raw MSG rasters.
+/// the raw rasters of the thermal channels of geostationary satellites.
 pub type Temperature = Operator<TemperatureParams, SingleRasterSource>;
 
 impl OperatorName for Temperature {
@@ -212,10 +215,10 @@ where
             source,
             result_descriptor,
             params,
-            satellite_key: new_satellite_key(),
-            channel_key: new_channel_key(),
-            offset_key: new_offset_key(),
-            slope_key: new_slope_key(),
+            satellite_key: new_satellite_key(params.platform),
+            channel_key: new_channel_key(params.platform),
+            offset_key: new_offset_key(params.platform),
+            slope_key: new_slope_key(params.platform),
         }
     }
 
@@ -224,21 +227,25 @@ where
             Some(id) => id,
             _ => tile.properties.number_property(&self.satellite_key)?,
         };
-        Satellite::satellite_by_msg_id(id)
+        self.params.platform.satellite(id)
     }
 
-    fn channel<'a>(&self, tile: &RasterTile2D<P>, satellite: &'a Satellite) -> Result<&'a Channel> {
+    /// The calibration of the thermal channel of the tile
+    fn calibration(
+        &self,
+        tile: &RasterTile2D<P>,
+        satellite: &Satellite,
+    ) -> Result<BrightnessTemperature> {
         let channel_id = tile
             .properties
             .number_property::<usize>(&self.channel_key)?
             - 1;
-        if (3..=10).contains(&channel_id) {
-            satellite.channel(channel_id)
-        } else {
-            Err(Error::InvalidChannel {
+        satellite
+            .channel(channel_id)?
+            .brightness_temperature
+            .ok_or(Error::InvalidChannel {
                 channel: channel_id,
             })
-        }
     }
 
     async fn process_tile_async(
@@ -247,12 +254,13 @@ where
         pool: Arc<ThreadPool>,
     ) -> Result<RasterTile2D<PixelOut>> {
         let satellite = self.satellite(&tile)?;
-        let channel = self.channel(&tile, satellite)?;
+        let calibration = self.calibration(&tile, satellite)?;
+        let raw_value_count = satellite.platform.raw_value_count();
         let offset = tile.properties.number_property::<f64>(&self.offset_key)?;
         let slope = tile.properties.number_property::<f64>(&self.slope_key)?;
 
         let temp_tile = crate::util::spawn_blocking_with_thread_pool(pool.clone(), move || {
-            let lut = create_lookup_table(channel, offset, slope, &pool);
+            let lut = create_lookup_table(calibration, raw_value_count, offset, slope, &pool);
 
             let map_fn = move |pixel_option: Option<P>| {
                 pixel_option.and_then(|p| {
@@ -269,12 +277,18 @@ where
     }
 }
 
-fn create_lookup_table(channel: &Channel, offset: f64, slope: f64, _pool: &ThreadPool) -> Vec<f32> {
+fn create_lookup_table(
+    calibration: BrightnessTemperature,
+    raw_value_count: usize,
+    offset: f64,
+    slope: f64,
+    _pool: &ThreadPool,
+) -> Vec<f32> {
     // this should propably be done with SIMD not a threadpool
-    (0..1024)
+    (0..raw_value_count)
         .map(|i| {
-            let radiance = offset + f64::from(i) * slope;
-            channel.calculate_temperature_from_radiance(radiance) as f32
+            let radiance = offset + i as f64 * slope;
+            calibration.temperature_from_radiance(radiance) as f32
         })
         .collect::<Vec<f32>>()
 }
@@ -428,6 +442,7 @@ mod tests {
                 RasterOperator::boxed(Temperature {
                     params: TemperatureParams {
                         force_satellite: Some(4),
+                        ..Default::default()
                     },
                     sources: SingleRasterSource {
                         raster: src.boxed(),
@@ -518,6 +533,7 @@ mod tests {
                 RasterOperator::boxed(Temperature {
                     params: TemperatureParams {
                         force_satellite: Some(13),
+                        ..Default::default()
                     },
                     sources: SingleRasterSource {
                         raster: src.boxed(),
@@ -1,43 +1,83 @@
+mod buffer;
 mod circle_merging_quadtree;
+mod clustering;
 mod column_range_filter;
+mod contour;
 mod expression;
+mod group_by;
+mod hydrology;
+mod illumination;
 mod interpolation;
 mod line_simplification;
 mod map_query;
 mod meteosat;
+mod mosaic;
 mod neighborhood_aggregate;
+mod overlay;
 mod point_in_polygon;
+mod polygonize;
+mod proximity;
+mod quality_mask;
 mod raster_scaling;
 mod raster_stacker;
 mod raster_type_conversion;
 mod raster_vector_join;
 mod rasterization;
+mod reclassify;
 mod reprojection;
 mod rgb;
 mod temporal_raster_aggregation;
 mod time_projection;
 mod time_shift;
+mod trajectory;
 mod vector_join;
 
+pub use buffer::{Buffer, BufferDistance, BufferError, BufferParams, BufferUnits};
 pub use circle_merging_quadtree::{
     InitializedVisualPointClustering, VisualPointClustering, VisualPointClusteringParams,
 };
+pub use clustering::{
+    ClusteringError, PointClustering, PointClusteringMethod, PointClusteringParams, RasterKMeans,
+    RasterKMeansParams,
+};
+pub use contour::{Contour, ContourError, ContourLevels, ContourOutput, ContourParams};
 pub use expression::{
     initialize_expression_dependencies, Expression, ExpressionParams, RasterExpressionError,
     VectorExpression, VectorExpressionError, VectorExpressionParams,
 };
-pub use interpolation::{Interpolation, InterpolationError, InterpolationParams};
+pub use group_by::{GroupBy, GroupByAggregate, GroupByError, GroupByFunction, GroupByParams};
+pub use hydrology::{
+    FillSinks, FillSinksParams, FlowAccumulation, FlowAccumulationParams, FlowDirection,
+    FlowDirectionParams, HydrologyError,
+};
+pub use illumination::{
+    CorrectionMethod, Hillshade, HillshadeParams, IlluminationCorrection,
+    IlluminationCorrectionParams, IlluminationCorrectionSources, IlluminationError, SunPosition,
+};
+pub use interpolation::{
+    InputResolution, Interpolation, InterpolationError, InterpolationMethod, InterpolationParams,
+};
 pub use line_simplification::{
     LineSimplification, LineSimplificationError, LineSimplificationParams,
 };
+pub use mosaic::{Mosaic, MosaicError, MosaicMethod, MosaicParams};
 pub use neighborhood_aggregate::{
     AggregateFunctionParams, NeighborhoodAggregate, NeighborhoodAggregateError,
     NeighborhoodAggregateParams, NeighborhoodParams,
 };
+pub use overlay::{
+    Clip, ClipParams, ClipSources, Dissolve, DissolveParams, OverlayError, OverlayOperation,
+    PolygonOverlay, PolygonOverlayParams, PolygonOverlaySources,
+};
 pub use point_in_polygon::{
     PointInPolygonFilter, PointInPolygonFilterParams, PointInPolygonFilterSource,
     PointInPolygonTester,
 };
+pub use polygonize::{Polygonize, PolygonizeClassification, PolygonizeError, PolygonizeParams};
+pub use proximity::{Proximity, ProximityError, ProximityParams};
+pub use quality_mask::{
+    QualityMask, QualityMaskError, QualityMaskParams, QualityMaskRule, QualityMaskSources,
+};
 pub use raster_stacker::{RasterStacker, RasterStackerParams};
 pub use raster_type_conversion::{
     RasterTypeConversion, RasterTypeConversionParams, RasterTypeConversionQueryProcessor,
@@ -46,6 +86,10 @@ pub use raster_vector_join::{
     ColumnNames, FeatureAggregationMethod, RasterVectorJoin, RasterVectorJoinParams,
     TemporalAggregationMethod,
 };
+pub use reclassify::{
+    Reclassify, ReclassifyError, ReclassifyInput, ReclassifyParams, ReclassifyRule,
+    ReclassifySources, ReclassifyTable,
+};
 pub use reprojection::{
     InitializedRasterReprojection, InitializedVectorReprojection, Reprojection, ReprojectionParams,
 };
@@ -55,3 +99,4 @@ pub use temporal_raster_aggregation::{
 };
 pub use time_projection::{TimeProjection, TimeProjectionError, TimeProjectionParams};
 pub use time_shift::{TimeShift, TimeShiftError, TimeShiftParams};
+pub use trajectory::{Trajectory, TrajectoryError, TrajectoryOutput, TrajectoryParams};
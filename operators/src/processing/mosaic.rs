@@ -0,0 +1,583 @@
+use super::interpolation::{
+    InitializedInterpolation, InputResolution, Interpolation, InterpolationMethod,
+    InterpolationParams,
+};
+use super::reprojection::{InitializedRasterReprojection, Reprojection, ReprojectionParams};
+use crate::adapters::{QueryWrapper, RasterStackerAdapter, RasterStackerSource};
+use crate::engine::{
+    CanonicOperatorName, ExecutionContext, InitializedRasterOperator, InitializedSources,
+    MultipleRasterSources, Operator, OperatorName, QueryContext, RasterOperator,
+    RasterQueryProcessor, RasterResultDescriptor, SingleRasterOrVectorSource, SingleRasterSource,
+    TypedRasterQueryProcessor, WorkflowOperatorPath,
+};
+use crate::util::input::RasterOrVectorOperator;
+use crate::util::Result;
+use async_trait::async_trait;
+use futures::stream::BoxStream;
+use futures::{StreamExt, TryStreamExt};
+use geoengine_datatypes::primitives::{
+    partitions_extent, time_interval_extent, CacheHint, RasterQueryRectangle, SpatialResolution,
+};
+use geoengine_datatypes::raster::{
+    EmptyGrid2D, FromPrimitive, Grid2D, GridOrEmpty, GridSize, MaskedGrid2D, Pixel, RasterDataType,
+    RasterTile2D,
+};
+use geoengine_datatypes::spatial_reference::SpatialReference;
+use num_traits::{AsPrimitive, Zero};
+use serde::{Deserialize, Serialize};
+use snafu::{ensure, Snafu};
+
+/// The `Mosaic` operator merges rasters that cover different or overlapping areas into one raster.
+///
+/// Where several inputs have valid pixels, the `method` decides on the output value.
+/// Inputs in a different spatial reference than the first input are reprojected.
+/// If an `interpolation` is given, inputs with a coarser resolution than the finest input are interpolated.
+///
+/// All inputs must have the same data type and number of bands. The inputs are temporally aligned.
+pub type Mosaic = Operator<MosaicParams, MultipleRasterSources>;
+
+impl OperatorName for Mosaic {
+    const TYPE_NAME: &'static str = "Mosaic";
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MosaicParams {
+    pub method: MosaicMethod,
+    #[serde(default)]
+    pub interpolation: Option<InterpolationMethod>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub enum MosaicMethod {
+    /// The value of the first input with a valid pixel
+    FirstValid,
+    /// The value of the last input with a valid pixel
+    LastValid,
+    Min,
+    Max,
+    Mean,
+    /// The value of the input with the highest priority that has a valid pixel.
+    /// For equal priorities, the first input wins.
+    Priority {
+        priorities: Vec<i64>,
+    },
+}
+
+impl MosaicMethod {
+    fn combine<T: Pixel>(&self, values: &[Option<T>]) -> Option<T> {
+        let mut valid = values.iter().flatten().copied();
+
+        match self {
+            Self::FirstValid => valid.next(),
+            Self::LastValid => valid.last(),
+            Self::Min => valid.reduce(|a, b| if b < a { b } else { a }),
+            Self::Max => valid.reduce(|a, b| if b > a { b } else { a }),
+            Self::Mean => {
+                let (sum, count) = valid.fold((0., 0_usize), |(sum, count), value| {
+                    (sum + AsPrimitive::<f64>::as_(value), count + 1)
+                });
+                (count > 0).then(|| T::from_(sum / count as f64))
+            }
+            Self::Priority { priorities } => values
+                .iter()
+                .zip(priorities)
+                .filter_map(|(value, priority)| value.map(|value| (value, *priority)))
+                .fold(None, |best, (value, priority)| match best {
+                    Some((_, best_priority)) if best_priority >= priority => best,
+                    _ => Some((value, priority)),
+                })
+                .map(|(value, _)| value),
+        }
+    }
+}
+
+#[typetag::serde]
+#[async_trait]
+impl RasterOperator for Mosaic {
+    async fn _initialize(
+        self: Box<Self>,
+        path: WorkflowOperatorPath,
+        context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedRasterOperator>> {
+        let name = CanonicOperatorName::from(&self);
+
+        let num_sources = self.sources.rasters.len();
+        ensure!(
+            (1..=Mosaic::MAX_SOURCES).contains(&num_sources),
+            error::InvalidNumberOfSources {
+                max: Mosaic::MAX_SOURCES,
+                found: num_sources,
+            }
+        );
+        if let MosaicMethod::Priority { priorities } = &self.params.method {
+            ensure!(
+                priorities.len() == num_sources,
+                error::InvalidNumberOfPriorities {
+                    expected: num_sources,
+                    found: priorities.len(),
+                }
+            );
+        }
+
+        // keep the operators to derive the names of the alignment operators
+        let operators = self.sources.rasters.clone();
+        let sources = self
+            .sources
+            .initialize_sources(path, context)
+            .await?
+            .rasters;
+
+        let first = sources[0].result_descriptor().clone();
+
+        let mut aligned = Vec::with_capacity(num_sources);
+        for (operator, source) in operators.into_iter().zip(sources) {
+            let in_desc = source.result_descriptor();
+
+            ensure!(
+                in_desc.data_type == first.data_type,
+                error::DataTypeMismatch {
+                    expected: first.data_type,
+                    found: in_desc.data_type,
+                }
+            );
+            ensure!(
+                in_desc.bands.count() == first.bands.count(),
+                error::BandCountMismatch {
+                    expected: first.bands.count(),
+                    found: in_desc.bands.count(),
+                }
+            );
+
+            if in_desc.spatial_reference == first.spatial_reference {
+                aligned.push((operator, source));
+                continue;
+            }
+
+            let Some(target_spatial_reference) =
+                Option::<SpatialReference>::from(first.spatial_reference)
+            else {
+                return Err(MosaicError::UnreferencedFirstSource.into());
+            };
+
+            let params = ReprojectionParams {
+                target_spatial_reference,
+            };
+            let reprojection = Reprojection {
+                params,
+                sources: SingleRasterOrVectorSource {
+                    source: RasterOrVectorOperator::Raster(operator),
+                },
+            };
+
+            let source = InitializedRasterReprojection::try_new_with_input(
+                CanonicOperatorName::from(&reprojection),
+                params,
+                source,
+                context.tiling_specification(),
+            )?;
+
+            aligned.push((
+                Box::new(reprojection) as Box<dyn RasterOperator>,
+                source.boxed(),
+            ));
+        }
+
+        let resolution = aligned
+            .iter()
+            .map(|(_, source)| source.result_descriptor().resolution)
+            .reduce(|a, b| match (a, b) {
+                (Some(a), Some(b)) => {
+                    Some(SpatialResolution::new_unchecked(a.x.min(b.x), a.y.min(b.y)))
+                }
+                _ => None,
+            })
+            .flatten();
+
+        let result_descriptor = RasterResultDescriptor {
+            data_type: first.data_type,
+            spatial_reference: first.spatial_reference,
+            time: time_interval_extent(aligned.iter().map(|(_, s)| s.result_descriptor().time)),
+            bbox: partitions_extent(aligned.iter().map(|(_, s)| s.result_descriptor().bbox)),
+            resolution,
+            bands: first.bands.clone(),
+        };
+
+        let sources = aligned
+            .into_iter()
+            .map(|(operator, source)| {
+                let (Some(interpolation), Some(resolution), Some(input_resolution)) = (
+                    self.params.interpolation,
+                    resolution,
+                    source.result_descriptor().resolution,
+                ) else {
+                    return source;
+                };
+
+                if input_resolution.x <= resolution.x && input_resolution.y <= resolution.y {
+                    return source;
+                }
+
+                let name = CanonicOperatorName::from(&Interpolation {
+                    params: InterpolationParams {
+                        interpolation,
+                        input_resolution: InputResolution::Value(input_resolution),
+                    },
+                    sources: SingleRasterSource { raster: operator },
+                });
+
+                InitializedInterpolation::new_with_input(
+                    name,
+                    interpolation,
+                    input_resolution,
+                    source,
+                    context.tiling_specification(),
+                )
+                .boxed()
+            })
+            .collect();
+
+        Ok(InitializedMosaic {
+            name,
+            result_descriptor,
+            sources,
+            method: self.params.method,
+        }
+        .boxed())
+    }
+
+    span_fn!(Mosaic);
+}
+
+impl Mosaic {
+    const MAX_SOURCES: usize = 16;
+}
+
+pub struct InitializedMosaic {
+    name: CanonicOperatorName,
+    result_descriptor: RasterResultDescriptor,
+    sources: Vec<Box<dyn InitializedRasterOperator>>,
+    method: MosaicMethod,
+}
+
+impl InitializedRasterOperator for InitializedMosaic {
+    fn result_descriptor(&self) -> &RasterResultDescriptor {
+        &self.result_descriptor
+    }
+
+    fn query_processor(&self) -> Result<TypedRasterQueryProcessor> {
+        let processors = self
+            .sources
+            .iter()
+            .map(InitializedRasterOperator::query_processor)
+            .collect::<Result<Vec<_>>>()?;
+
+        macro_rules! mosaic_processor {
+            ($variant:ident, $get:ident) => {
+                TypedRasterQueryProcessor::$variant(
+                    MosaicProcessor {
+                        sources: processors
+                            .into_iter()
+                            .map(|p| p.$get().expect("checked in `Mosaic` initialization"))
+                            .collect(),
+                        result_descriptor: self.result_descriptor.clone(),
+                        method: self.method.clone(),
+                    }
+                    .boxed(),
+                )
+            };
+        }
+
+        Ok(match self.result_descriptor.data_type {
+            RasterDataType::U8 => mosaic_processor!(U8, get_u8),
+            RasterDataType::U16 => mosaic_processor!(U16, get_u16),
+            RasterDataType::U32 => mosaic_processor!(U32, get_u32),
+            RasterDataType::U64 => mosaic_processor!(U64, get_u64),
+            RasterDataType::I8 => mosaic_processor!(I8, get_i8),
+            RasterDataType::I16 => mosaic_processor!(I16, get_i16),
+            RasterDataType::I32 => mosaic_processor!(I32, get_i32),
+            RasterDataType::I64 => mosaic_processor!(I64, get_i64),
+            RasterDataType::F32 => mosaic_processor!(F32, get_f32),
+            RasterDataType::F64 => mosaic_processor!(F64, get_f64),
+        })
+    }
+
+    fn canonic_name(&self) -> CanonicOperatorName {
+        self.name.clone()
+    }
+}
+
+struct MosaicProcessor<T> {
+    sources: Vec<Box<dyn RasterQueryProcessor<RasterType = T>>>,
+    result_descriptor: RasterResultDescriptor,
+    method: MosaicMethod,
+}
+
+/// Merges the tiles of all sources at the same position, band and time.
+fn mosaic_tiles<T: Pixel>(
+    tiles: &[RasterTile2D<T>],
+    method: &MosaicMethod,
+) -> Result<RasterTile2D<T>> {
+    let first = &tiles[0];
+    let tile_information = first.tile_information();
+    let shape = tile_information.tile_size_in_pixels;
+
+    let cache_hint = tiles
+        .iter()
+        .fold(CacheHint::max_duration(), |cache_hint, tile| {
+            cache_hint.merged(&tile.cache_hint)
+        });
+
+    if tiles.iter().all(RasterTile2D::is_empty) {
+        return Ok(RasterTile2D::new_with_tile_info(
+            first.time,
+            tile_information,
+            first.band,
+            EmptyGrid2D::new(shape).into(),
+            cache_hint,
+        ));
+    }
+
+    let num_pixels = shape.number_of_elements();
+    let values = tiles
+        .iter()
+        .map(|tile| match &tile.grid_array {
+            GridOrEmpty::Grid(grid) => grid.masked_element_deref_iterator().collect(),
+            GridOrEmpty::Empty(_) => vec![None; num_pixels],
+        })
+        .collect::<Vec<Vec<Option<T>>>>();
+
+    let mut data = Vec::with_capacity(num_pixels);
+    let mut validity = Vec::with_capacity(num_pixels);
+    let mut pixel = Vec::with_capacity(tiles.len());
+    for i in 0..num_pixels {
+        pixel.clear();
+        pixel.extend(values.iter().map(|values| values[i]));
+
+        let value = method.combine(&pixel);
+        data.push(value.unwrap_or_else(T::zero));
+        validity.push(value.is_some());
+    }
+
+    let grid = MaskedGrid2D::new(Grid2D::new(shape, data)?, Grid2D::new(shape, validity)?)?;
+
+    Ok(RasterTile2D::new_with_tile_info(
+        first.time,
+        tile_information,
+        first.band,
+        grid.into(),
+        cache_hint,
+    ))
+}
+
+#[async_trait]
+impl<T> RasterQueryProcessor for MosaicProcessor<T>
+where
+    T: Pixel,
+{
+    type RasterType = T;
+
+    async fn raster_query<'a>(
+        &'a self,
+        query: RasterQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<BoxStream<'a, Result<RasterTile2D<T>>>> {
+        let num_sources = self.sources.len();
+        let num_bands = query.attributes.count() as usize;
+
+        let sources = self
+            .sources
+            .iter()
+            .map(|source| RasterStackerSource {
+                queryable: QueryWrapper { p: source, ctx },
+                band_idxs: query.attributes.as_vec(),
+            })
+            .collect();
+
+        // the stacker outputs all bands of all sources for each tile position before moving on
+        let stream = RasterStackerAdapter::new(sources, query.into())
+            .chunks(num_sources * num_bands)
+            .then(move |tiles| async move {
+                let tiles = tiles.into_iter().collect::<Result<Vec<_>>>()?;
+                let method = self.method.clone();
+
+                crate::util::spawn_blocking_with_thread_pool(ctx.thread_pool().clone(), move || {
+                    (0..num_bands)
+                        .map(|band| {
+                            let band_tiles = (0..num_sources)
+                                .map(|source| tiles[source * num_bands + band].clone())
+                                .collect::<Vec<_>>();
+                            mosaic_tiles(&band_tiles, &method)
+                        })
+                        .collect::<Result<Vec<_>>>()
+                })
+                .await?
+            })
+            .map_ok(|tiles| futures::stream::iter(tiles.into_iter().map(Ok)))
+            .try_flatten();
+
+        Ok(stream.boxed())
+    }
+
+    fn raster_result_descriptor(&self) -> &RasterResultDescriptor {
+        &self.result_descriptor
+    }
+}
+
+#[derive(Debug, Snafu)]
+#[snafu(visibility(pub(crate)), context(suffix(false)), module(error))]
+pub enum MosaicError {
+    #[snafu(display("Mosaic requires between 1 and {max} inputs, found {found}"))]
+    InvalidNumberOfSources { max: usize, found: usize },
+    #[snafu(display("Expected {expected} priorities, one for each input, found {found}"))]
+    InvalidNumberOfPriorities { expected: usize, found: usize },
+    #[snafu(display("All inputs must have the data type {expected:?}, found {found:?}"))]
+    DataTypeMismatch {
+        expected: RasterDataType,
+        found: RasterDataType,
+    },
+    #[snafu(display("All inputs must have {expected} bands, found {found}"))]
+    BandCountMismatch { expected: u32, found: u32 },
+    #[snafu(display(
+        "The first input must have a spatial reference to reproject the other inputs"
+    ))]
+    UnreferencedFirstSource,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{MockExecutionContext, MockQueryContext, RasterBandDescriptors};
+    use crate::mock::{MockRasterSource, MockRasterSourceParams};
+    use geoengine_datatypes::primitives::{
+        BandSelection, SpatialPartition2D, SpatialResolution, TimeInterval,
+    };
+    use geoengine_datatypes::raster::{TileInformation, TilingSpecification};
+    use geoengine_datatypes::util::test::TestDefault;
+
+    fn source(values: Vec<Option<u8>>) -> Box<dyn RasterOperator> {
+        let grid = MaskedGrid2D::new(
+            Grid2D::new(
+                [2, 2].into(),
+                values.iter().map(|v| v.unwrap_or(0)).collect(),
+            )
+            .unwrap(),
+            Grid2D::new([2, 2].into(), values.iter().map(Option::is_some).collect()).unwrap(),
+        )
+        .unwrap();
+
+        MockRasterSource {
+            params: MockRasterSourceParams {
+                data: vec![RasterTile2D::new_with_tile_info(
+                    TimeInterval::default(),
+                    TileInformation {
+                        global_geo_transform: TestDefault::test_default(),
+                        global_tile_position: [0, 0].into(),
+                        tile_size_in_pixels: [2, 2].into(),
+                    },
+                    0,
+                    grid.into(),
+                    CacheHint::default(),
+                )],
+                result_descriptor: RasterResultDescriptor {
+                    data_type: RasterDataType::U8,
+                    spatial_reference: SpatialReference::epsg_4326().into(),
+                    time: None,
+                    bbox: None,
+                    resolution: None,
+                    bands: RasterBandDescriptors::new_single_band(),
+                },
+            },
+        }
+        .boxed()
+    }
+
+    async fn mosaic(method: MosaicMethod) -> Result<Vec<Option<u8>>> {
+        let execution_context = MockExecutionContext::new_with_tiling_spec(TilingSpecification {
+            origin_coordinate: [0.0, 0.0].into(),
+            tile_size_in_pixels: [2, 2].into(),
+        });
+
+        let operator = Mosaic {
+            params: MosaicParams {
+                method,
+                interpolation: None,
+            },
+            sources: MultipleRasterSources {
+                rasters: vec![
+                    source(vec![Some(2), None, Some(3), None]),
+                    source(vec![Some(10), Some(20), None, None]),
+                ],
+            },
+        }
+        .boxed()
+        .initialize(WorkflowOperatorPath::initialize_root(), &execution_context)
+        .await?;
+
+        let processor = operator.query_processor()?.get_u8().unwrap();
+
+        let tiles: Vec<RasterTile2D<u8>> = processor
+            .raster_query(
+                RasterQueryRectangle {
+                    spatial_bounds: SpatialPartition2D::new((0., 0.).into(), (2., -2.).into())
+                        .unwrap(),
+                    time_interval: TimeInterval::default(),
+                    spatial_resolution: SpatialResolution::one(),
+                    attributes: BandSelection::first(),
+                },
+                &MockQueryContext::test_default(),
+            )
+            .await?
+            .try_collect()
+            .await?;
+
+        assert_eq!(tiles.len(), 1);
+        let GridOrEmpty::Grid(grid) = &tiles[0].grid_array else {
+            panic!("expected a grid");
+        };
+
+        Ok(grid.masked_element_deref_iterator().collect())
+    }
+
+    #[tokio::test]
+    async fn it_mosaics_with_all_methods() {
+        assert_eq!(
+            mosaic(MosaicMethod::FirstValid).await.unwrap(),
+            vec![Some(2), Some(20), Some(3), None]
+        );
+        assert_eq!(
+            mosaic(MosaicMethod::LastValid).await.unwrap(),
+            vec![Some(10), Some(20), Some(3), None]
+        );
+        assert_eq!(
+            mosaic(MosaicMethod::Min).await.unwrap(),
+            vec![Some(2), Some(20), Some(3), None]
+        );
+        assert_eq!(
+            mosaic(MosaicMethod::Max).await.unwrap(),
+            vec![Some(10), Some(20), Some(3), None]
+        );
+        assert_eq!(
+            mosaic(MosaicMethod::Mean).await.unwrap(),
+            vec![Some(6), Some(20), Some(3), None]
+        );
+        assert_eq!(
+            mosaic(MosaicMethod::Priority {
+                priorities: vec![1, 2]
+            })
+            .await
+            .unwrap(),
+            vec![Some(10), Some(20), Some(3), None]
+        );
+    }
+
+    #[tokio::test]
+    async fn it_checks_the_number_of_priorities() {
+        assert!(mosaic(MosaicMethod::Priority {
+            priorities: vec![1]
+        })
+        .await
+        .is_err());
+    }
+}
@@ -0,0 +1,544 @@
+use super::{error, replace_clipped_geometries, OverlayError, OverlayPolygons};
+use crate::engine::{
+    CanonicOperatorName, ExecutionContext, InitializedSources, InitializedVectorOperator, Operator,
+    OperatorData, OperatorName, QueryContext, QueryProcessor, TypedVectorQueryProcessor,
+    VectorOperator, VectorQueryProcessor, VectorResultDescriptor, WorkflowOperatorPath,
+};
+use crate::util::Result;
+use async_trait::async_trait;
+use futures::stream::BoxStream;
+use futures::{StreamExt, TryStreamExt};
+use geo::{BooleanOps, Intersects};
+use geoengine_datatypes::collections::{
+    FeatureCollection, FeatureCollectionInfos, IntoGeometryIterator, MultiLineStringCollection,
+    MultiPointCollection, MultiPolygonCollection, VectorDataType,
+};
+use geoengine_datatypes::dataset::NamedData;
+use geoengine_datatypes::primitives::{
+    AsGeo, BoundingBox2D, ColumnSelection, Geometry, GeometryRef, MultiLineString, MultiPoint,
+    MultiPointAccess, MultiPolygon, VectorQueryRectangle,
+};
+use geoengine_datatypes::util::arrow::ArrowTyped;
+use serde::{Deserialize, Serialize};
+use snafu::ensure;
+
+/// The `Clip` operator cuts the features of the `vector` source to the polygons of the `mask` source.
+///
+/// Points outside of all mask polygons are removed, lines and polygons are cut at the mask boundary.
+/// Features that are completely outside of the mask are dropped.
+/// Only mask polygons whose validity intersects the validity of a feature are considered.
+pub type Clip = Operator<ClipParams, ClipSources>;
+
+impl OperatorName for Clip {
+    const TYPE_NAME: &'static str = "Clip";
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClipParams {}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClipSources {
+    pub vector: Box<dyn VectorOperator>,
+    pub mask: Box<dyn VectorOperator>,
+}
+
+impl OperatorData for ClipSources {
+    fn data_names_collect(&self, data_names: &mut Vec<NamedData>) {
+        self.vector.data_names_collect(data_names);
+        self.mask.data_names_collect(data_names);
+    }
+}
+
+struct InitializedClipSources {
+    vector: Box<dyn InitializedVectorOperator>,
+    mask: Box<dyn InitializedVectorOperator>,
+}
+
+#[async_trait]
+impl InitializedSources<InitializedClipSources> for ClipSources {
+    async fn initialize_sources(
+        self,
+        path: WorkflowOperatorPath,
+        context: &dyn ExecutionContext,
+    ) -> Result<InitializedClipSources> {
+        Ok(InitializedClipSources {
+            vector: self
+                .vector
+                .initialize(path.clone_and_append(0), context)
+                .await?,
+            mask: self
+                .mask
+                .initialize(path.clone_and_append(1), context)
+                .await?,
+        })
+    }
+}
+
+#[typetag::serde]
+#[async_trait]
+impl VectorOperator for Clip {
+    async fn _initialize(
+        self: Box<Self>,
+        path: WorkflowOperatorPath,
+        context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedVectorOperator>> {
+        let name = CanonicOperatorName::from(&self);
+
+        let sources = self.sources.initialize_sources(path, context).await?;
+
+        let vector_desc = sources.vector.result_descriptor();
+        let mask_desc = sources.mask.result_descriptor();
+
+        ensure!(
+            vector_desc.data_type != VectorDataType::Data,
+            error::InputMustHaveGeometries
+        );
+        ensure!(
+            mask_desc.data_type == VectorDataType::MultiPolygon,
+            error::InputMustBePolygons {
+                input: "mask",
+                found: mask_desc.data_type.to_string(),
+            }
+        );
+        ensure!(
+            vector_desc.spatial_reference == mask_desc.spatial_reference,
+            error::SpatialReferenceMismatch {
+                left: vector_desc.spatial_reference.to_string(),
+                right: mask_desc.spatial_reference.to_string(),
+            }
+        );
+
+        let bbox = match (vector_desc.bbox, mask_desc.bbox) {
+            (Some(vector_bbox), Some(mask_bbox)) => vector_bbox.intersection(&mask_bbox),
+            (bbox, _) => bbox,
+        };
+
+        let result_descriptor = VectorResultDescriptor {
+            bbox,
+            ..vector_desc.clone()
+        };
+
+        Ok(InitializedClip {
+            name,
+            result_descriptor,
+            vector: sources.vector,
+            mask: sources.mask,
+        }
+        .boxed())
+    }
+
+    span_fn!(Clip);
+}
+
+pub struct InitializedClip {
+    name: CanonicOperatorName,
+    result_descriptor: VectorResultDescriptor,
+    vector: Box<dyn InitializedVectorOperator>,
+    mask: Box<dyn InitializedVectorOperator>,
+}
+
+impl InitializedVectorOperator for InitializedClip {
+    fn result_descriptor(&self) -> &VectorResultDescriptor {
+        &self.result_descriptor
+    }
+
+    fn query_processor(&self) -> Result<TypedVectorQueryProcessor> {
+        let mask = self
+            .mask
+            .query_processor()?
+            .multi_polygon()
+            .expect("checked in `Clip` initialization");
+
+        Ok(match self.vector.query_processor()? {
+            TypedVectorQueryProcessor::Data(_) => {
+                return Err(OverlayError::InputMustHaveGeometries.into())
+            }
+            TypedVectorQueryProcessor::MultiPoint(vector) => TypedVectorQueryProcessor::MultiPoint(
+                ClipProcessor::new(vector, mask, self.result_descriptor.clone()).boxed(),
+            ),
+            TypedVectorQueryProcessor::MultiLineString(vector) => {
+                TypedVectorQueryProcessor::MultiLineString(
+                    ClipProcessor::new(vector, mask, self.result_descriptor.clone()).boxed(),
+                )
+            }
+            TypedVectorQueryProcessor::MultiPolygon(vector) => {
+                TypedVectorQueryProcessor::MultiPolygon(
+                    ClipProcessor::new(vector, mask, self.result_descriptor.clone()).boxed(),
+                )
+            }
+        })
+    }
+
+    fn canonic_name(&self) -> CanonicOperatorName {
+        self.name.clone()
+    }
+}
+
+struct ClipProcessor<G>
+where
+    G: Geometry,
+{
+    vector: Box<dyn VectorQueryProcessor<VectorType = FeatureCollection<G>>>,
+    mask: Box<dyn VectorQueryProcessor<VectorType = MultiPolygonCollection>>,
+    result_descriptor: VectorResultDescriptor,
+}
+
+impl<G> ClipProcessor<G>
+where
+    G: Geometry,
+{
+    fn new(
+        vector: Box<dyn VectorQueryProcessor<VectorType = FeatureCollection<G>>>,
+        mask: Box<dyn VectorQueryProcessor<VectorType = MultiPolygonCollection>>,
+        result_descriptor: VectorResultDescriptor,
+    ) -> Self {
+        Self {
+            vector,
+            mask,
+            result_descriptor,
+        }
+    }
+}
+
+#[async_trait]
+impl<G> QueryProcessor for ClipProcessor<G>
+where
+    G: Geometry + ArrowTyped + 'static,
+    FeatureCollection<G>: ClipFeatures + 'static,
+{
+    type Output = FeatureCollection<G>;
+    type SpatialBounds = BoundingBox2D;
+    type Selection = ColumnSelection;
+    type ResultDescription = VectorResultDescriptor;
+
+    async fn _query<'a>(
+        &'a self,
+        query: VectorQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<BoxStream<'a, Result<Self::Output>>> {
+        let mask = OverlayPolygons::query(self.mask.as_ref(), query.clone(), ctx).await?;
+
+        // without mask polygons, there is nothing left to output
+        if mask.is_empty() {
+            return Ok(futures::stream::empty().boxed());
+        }
+
+        let mask = std::sync::Arc::new(mask);
+
+        let stream = self
+            .vector
+            .query(query, ctx)
+            .await?
+            .and_then(move |collection| {
+                let mask = mask.clone();
+                async move {
+                    crate::util::spawn_blocking_with_thread_pool(
+                        ctx.thread_pool().clone(),
+                        move || {
+                            let mut clipped = collection.clip_features(&mask)?;
+                            clipped.cache_hint.merge_with(&mask.cache_hint);
+                            Ok(clipped)
+                        },
+                    )
+                    .await?
+                }
+            });
+
+        Ok(stream.boxed())
+    }
+
+    fn result_descriptor(&self) -> &VectorResultDescriptor {
+        &self.result_descriptor
+    }
+}
+
+/// Clipping of the features of a collection to overlay polygons
+trait ClipFeatures: Sized + Send {
+    fn clip_features(&self, mask: &OverlayPolygons) -> Result<Self>;
+}
+
+impl ClipFeatures for MultiPointCollection {
+    fn clip_features(&self, mask: &OverlayPolygons) -> Result<Self> {
+        let clipped = self
+            .geometries()
+            .zip(self.time_intervals())
+            .map(|(geometry, time)| {
+                let bbox = geometry.bbox()?;
+                let candidates = mask.candidates(&bbox, time).collect::<Vec<_>>();
+
+                let coordinates = geometry
+                    .points()
+                    .iter()
+                    .filter(|&&coordinate| {
+                        let point = geo::Point::from(geo::Coord::from(coordinate));
+                        candidates
+                            .iter()
+                            .any(|polygon| polygon.geometry.intersects(&point))
+                    })
+                    .copied()
+                    .collect::<Vec<_>>();
+
+                MultiPoint::new(coordinates).ok()
+            })
+            .collect();
+
+        replace_clipped_geometries(self, clipped)
+    }
+}
+
+impl ClipFeatures for MultiLineStringCollection {
+    fn clip_features(&self, mask: &OverlayPolygons) -> Result<Self> {
+        let clipped = self
+            .geometries()
+            .zip(self.time_intervals())
+            .map(|(geometry, time)| {
+                let mask = mask.candidates_union(&geometry.bbox()?, time)?;
+
+                let lines = mask.clip(&geometry.as_geo(), false);
+                let lines = geo::MultiLineString::new(
+                    lines
+                        .0
+                        .into_iter()
+                        .filter(|line| line.0.len() > 1)
+                        .collect(),
+                );
+
+                (!lines.0.is_empty()).then(|| MultiLineString::from(lines))
+            })
+            .collect();
+
+        replace_clipped_geometries(self, clipped)
+    }
+}
+
+impl ClipFeatures for MultiPolygonCollection {
+    fn clip_features(&self, mask: &OverlayPolygons) -> Result<Self> {
+        let clipped = self
+            .geometries()
+            .zip(self.time_intervals())
+            .map(|(geometry, time)| {
+                let mask = mask.candidates_union(&geometry.bbox()?, time)?;
+
+                let polygons = geometry.as_geo().intersection(&mask);
+
+                (!polygons.0.is_empty()).then(|| MultiPolygon::from(polygons))
+            })
+            .collect();
+
+        replace_clipped_geometries(self, clipped)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{MockExecutionContext, MockQueryContext};
+    use crate::mock::MockFeatureCollectionSource;
+    use geoengine_datatypes::primitives::{
+        CacheHint, FeatureData, SpatialResolution, TimeInterval,
+    };
+    use geoengine_datatypes::util::test::TestDefault;
+
+    fn mask() -> MultiPolygonCollection {
+        MultiPolygonCollection::from_data(
+            vec![MultiPolygon::new(vec![vec![vec![
+                (0.0, 0.0).into(),
+                (10.0, 0.0).into(),
+                (10.0, 10.0).into(),
+                (0.0, 10.0).into(),
+                (0.0, 0.0).into(),
+            ]]])
+            .unwrap()],
+            vec![TimeInterval::default()],
+            Default::default(),
+            CacheHint::default(),
+        )
+        .unwrap()
+    }
+
+    fn query_rectangle() -> VectorQueryRectangle {
+        VectorQueryRectangle {
+            spatial_bounds: BoundingBox2D::new((-20., -20.).into(), (20., 20.).into()).unwrap(),
+            time_interval: TimeInterval::default(),
+            spatial_resolution: SpatialResolution::one(),
+            attributes: ColumnSelection::all(),
+        }
+    }
+
+    #[tokio::test]
+    async fn it_clips_points() {
+        let points = MultiPointCollection::from_data(
+            vec![
+                MultiPoint::new(vec![(1.0, 1.0).into(), (15.0, 1.0).into()]).unwrap(),
+                MultiPoint::new(vec![(15.0, 15.0).into()]).unwrap(),
+            ],
+            vec![TimeInterval::default(); 2],
+            [("foo".to_string(), FeatureData::Int(vec![1, 2]))]
+                .into_iter()
+                .collect(),
+            CacheHint::default(),
+        )
+        .unwrap();
+
+        let clip = Clip {
+            params: ClipParams {},
+            sources: ClipSources {
+                vector: MockFeatureCollectionSource::single(points).boxed(),
+                mask: MockFeatureCollectionSource::single(mask()).boxed(),
+            },
+        }
+        .boxed()
+        .initialize(
+            WorkflowOperatorPath::initialize_root(),
+            &MockExecutionContext::test_default(),
+        )
+        .await
+        .unwrap();
+
+        let processor = clip.query_processor().unwrap().multi_point().unwrap();
+
+        let collections: Vec<MultiPointCollection> = processor
+            .query(query_rectangle(), &MockQueryContext::test_default())
+            .await
+            .unwrap()
+            .try_collect()
+            .await
+            .unwrap();
+
+        assert_eq!(collections.len(), 1);
+        assert_eq!(
+            collections[0],
+            MultiPointCollection::from_data(
+                vec![MultiPoint::new(vec![(1.0, 1.0).into()]).unwrap()],
+                vec![TimeInterval::default()],
+                [("foo".to_string(), FeatureData::Int(vec![1]))]
+                    .into_iter()
+                    .collect(),
+                CacheHint::default(),
+            )
+            .unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn it_clips_lines() {
+        let lines = MultiLineStringCollection::from_data(
+            vec![MultiLineString::new(vec![vec![(-5.0, 5.0).into(), (5.0, 5.0).into()]]).unwrap()],
+            vec![TimeInterval::default()],
+            Default::default(),
+            CacheHint::default(),
+        )
+        .unwrap();
+
+        let clip = Clip {
+            params: ClipParams {},
+            sources: ClipSources {
+                vector: MockFeatureCollectionSource::single(lines).boxed(),
+                mask: MockFeatureCollectionSource::single(mask()).boxed(),
+            },
+        }
+        .boxed()
+        .initialize(
+            WorkflowOperatorPath::initialize_root(),
+            &MockExecutionContext::test_default(),
+        )
+        .await
+        .unwrap();
+
+        let processor = clip.query_processor().unwrap().multi_line_string().unwrap();
+
+        let collections: Vec<MultiLineStringCollection> = processor
+            .query(query_rectangle(), &MockQueryContext::test_default())
+            .await
+            .unwrap()
+            .try_collect()
+            .await
+            .unwrap();
+
+        assert_eq!(collections.len(), 1);
+        assert_eq!(collections[0].len(), 1);
+
+        let bbox = collections[0].geometries().next().unwrap().bbox().unwrap();
+        assert_eq!(
+            bbox,
+            BoundingBox2D::new((0.0, 5.0).into(), (5.0, 5.0).into()).unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn it_clips_polygons() {
+        let polygons = MultiPolygonCollection::from_data(
+            vec![MultiPolygon::new(vec![vec![vec![
+                (5.0, 5.0).into(),
+                (15.0, 5.0).into(),
+                (15.0, 15.0).into(),
+                (5.0, 15.0).into(),
+                (5.0, 5.0).into(),
+            ]]])
+            .unwrap()],
+            vec![TimeInterval::default()],
+            Default::default(),
+            CacheHint::default(),
+        )
+        .unwrap();
+
+        let clip = Clip {
+            params: ClipParams {},
+            sources: ClipSources {
+                vector: MockFeatureCollectionSource::single(polygons).boxed(),
+                mask: MockFeatureCollectionSource::single(mask()).boxed(),
+            },
+        }
+        .boxed()
+        .initialize(
+            WorkflowOperatorPath::initialize_root(),
+            &MockExecutionContext::test_default(),
+        )
+        .await
+        .unwrap();
+
+        let processor = clip.query_processor().unwrap().multi_polygon().unwrap();
+
+        let collections: Vec<MultiPolygonCollection> = processor
+            .query(query_rectangle(), &MockQueryContext::test_default())
+            .await
+            .unwrap()
+            .try_collect()
+            .await
+            .unwrap();
+
+        assert_eq!(collections.len(), 1);
+
+        let area = geo::Area::unsigned_area(&collections[0].geometries().next().unwrap().as_geo());
+        assert!((area - 25.).abs() < 1e-9);
+    }
+
+    #[tokio::test]
+    async fn it_requires_polygon_mask() {
+        let points = MultiPointCollection::from_data(
+            vec![MultiPoint::new(vec![(1.0, 1.0).into()]).unwrap()],
+            vec![TimeInterval::default()],
+            Default::default(),
+            CacheHint::default(),
+        )
+        .unwrap();
+
+        let result = Clip {
+            params: ClipParams {},
+            sources: ClipSources {
+                vector: MockFeatureCollectionSource::single(points.clone()).boxed(),
+                mask: MockFeatureCollectionSource::single(points).boxed(),
+            },
+        }
+        .boxed()
+        .initialize(
+            WorkflowOperatorPath::initialize_root(),
+            &MockExecutionContext::test_default(),
+        )
+        .await;
+
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,355 @@
+use super::{error, OverlayError};
+use crate::engine::{
+    CanonicOperatorName, ExecutionContext, InitializedSources, InitializedVectorOperator, Operator,
+    OperatorName, QueryContext, QueryProcessor, SingleVectorSource, TypedVectorQueryProcessor,
+    VectorOperator, VectorQueryProcessor, VectorResultDescriptor, WorkflowOperatorPath,
+};
+use crate::processing::buffer::geometry::cascaded_union;
+use crate::util::feature_key::FeatureKey;
+use crate::util::Result;
+use async_trait::async_trait;
+use futures::stream::BoxStream;
+use futures::{StreamExt, TryStreamExt};
+use geoengine_datatypes::collections::{
+    BuilderProvider, FeatureCollectionInfos, GeoFeatureCollectionRowBuilder, IntoGeometryIterator,
+    MultiPolygonCollection, VectorDataType,
+};
+use geoengine_datatypes::primitives::{
+    AsGeo, BoundingBox2D, CacheHint, ColumnSelection, FeatureDataType, FeatureDataValue,
+    MultiPolygon, TimeInstance, TimeInterval, VectorQueryRectangle,
+};
+use serde::{Deserialize, Serialize};
+use snafu::ensure;
+use std::collections::BTreeMap;
+
+/// The `Dissolve` operator merges all polygons that share the same value in `column`
+/// and the same validity into a single feature.
+///
+/// If no column is given, all polygons with the same validity are merged.
+/// The output only contains the dissolve column.
+/// Since polygons of different chunks may be merged, the output is a single collection.
+pub type Dissolve = Operator<DissolveParams, SingleVectorSource>;
+
+impl OperatorName for Dissolve {
+    const TYPE_NAME: &'static str = "Dissolve";
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DissolveParams {
+    #[serde(default)]
+    pub column: Option<String>,
+}
+
+#[typetag::serde]
+#[async_trait]
+impl VectorOperator for Dissolve {
+    async fn _initialize(
+        self: Box<Self>,
+        path: WorkflowOperatorPath,
+        context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedVectorOperator>> {
+        let name = CanonicOperatorName::from(&self);
+
+        let source = self.sources.initialize_sources(path, context).await?.vector;
+
+        let in_desc = source.result_descriptor();
+
+        ensure!(
+            in_desc.data_type == VectorDataType::MultiPolygon,
+            error::InputMustBePolygons {
+                input: "vector",
+                found: in_desc.data_type.to_string(),
+            }
+        );
+
+        let column = match &self.params.column {
+            Some(column) => {
+                let Some(info) = in_desc.columns.get(column) else {
+                    return Err(OverlayError::ColumnDoesNotExist {
+                        column: column.clone(),
+                    }
+                    .into());
+                };
+                Some((column.clone(), info.clone()))
+            }
+            None => None,
+        };
+
+        let result_descriptor = VectorResultDescriptor {
+            columns: column.clone().into_iter().collect(),
+            ..in_desc.clone()
+        };
+
+        Ok(InitializedDissolve {
+            name,
+            result_descriptor,
+            source,
+            column: column.map(|(column, info)| (column, info.data_type)),
+        }
+        .boxed())
+    }
+
+    span_fn!(Dissolve);
+}
+
+pub struct InitializedDissolve {
+    name: CanonicOperatorName,
+    result_descriptor: VectorResultDescriptor,
+    source: Box<dyn InitializedVectorOperator>,
+    column: Option<(String, FeatureDataType)>,
+}
+
+impl InitializedVectorOperator for InitializedDissolve {
+    fn result_descriptor(&self) -> &VectorResultDescriptor {
+        &self.result_descriptor
+    }
+
+    fn query_processor(&self) -> Result<TypedVectorQueryProcessor> {
+        let source = self
+            .source
+            .query_processor()?
+            .multi_polygon()
+            .expect("checked in `Dissolve` initialization");
+
+        Ok(TypedVectorQueryProcessor::MultiPolygon(
+            DissolveProcessor {
+                source,
+                result_descriptor: self.result_descriptor.clone(),
+                column: self.column.clone(),
+            }
+            .boxed(),
+        ))
+    }
+
+    fn canonic_name(&self) -> CanonicOperatorName {
+        self.name.clone()
+    }
+}
+
+struct DissolveProcessor {
+    source: Box<dyn VectorQueryProcessor<VectorType = MultiPolygonCollection>>,
+    result_descriptor: VectorResultDescriptor,
+    column: Option<(String, FeatureDataType)>,
+}
+
+struct DissolveGroup {
+    value: Option<FeatureDataValue>,
+    polygons: Vec<geo::MultiPolygon<f64>>,
+}
+
+impl DissolveProcessor {
+    fn dissolve(
+        collections: &[MultiPolygonCollection],
+        column: Option<&(String, FeatureDataType)>,
+    ) -> Result<MultiPolygonCollection> {
+        let mut groups = BTreeMap::<(FeatureKey, TimeInstance, TimeInstance), DissolveGroup>::new();
+        let mut cache_hint = CacheHint::max_duration();
+
+        for collection in collections {
+            cache_hint.merge_with(&collection.cache_hint);
+
+            let data = match column {
+                Some((column, _)) => Some(collection.data(column)?),
+                None => None,
+            };
+
+            for (feature_index, (geometry, time)) in collection
+                .geometries()
+                .zip(collection.time_intervals())
+                .enumerate()
+            {
+                let value = data.as_ref().map(|data| data.get_unchecked(feature_index));
+                let key = value.as_ref().map_or(FeatureKey::Null, FeatureKey::from);
+
+                groups
+                    .entry((key, time.start(), time.end()))
+                    .or_insert_with(|| DissolveGroup {
+                        value,
+                        polygons: Vec::new(),
+                    })
+                    .polygons
+                    .push(geometry.as_geo());
+            }
+        }
+
+        let mut builder = MultiPolygonCollection::builder();
+        if let Some((column, data_type)) = column {
+            builder.add_column(column.clone(), *data_type)?;
+        }
+        let mut builder = builder.finish_header();
+
+        for ((_, start, end), group) in groups {
+            let polygons = cascaded_union(group.polygons);
+            if polygons.0.is_empty() {
+                continue;
+            }
+
+            builder.push_geometry(MultiPolygon::from(polygons));
+            builder.push_time_interval(TimeInterval::new_unchecked(start, end));
+
+            if let (Some((column, _)), Some(value)) = (column, group.value) {
+                builder.push_data(column, value)?;
+            }
+
+            builder.finish_row();
+        }
+
+        builder.cache_hint(cache_hint);
+
+        Ok(builder.build()?)
+    }
+}
+
+#[async_trait]
+impl QueryProcessor for DissolveProcessor {
+    type Output = MultiPolygonCollection;
+    type SpatialBounds = BoundingBox2D;
+    type Selection = ColumnSelection;
+    type ResultDescription = VectorResultDescriptor;
+
+    async fn _query<'a>(
+        &'a self,
+        query: VectorQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<BoxStream<'a, Result<Self::Output>>> {
+        let collections: Vec<MultiPolygonCollection> =
+            self.source.query(query, ctx).await?.try_collect().await?;
+
+        let column = self.column.clone();
+        let dissolved =
+            crate::util::spawn_blocking_with_thread_pool(ctx.thread_pool().clone(), move || {
+                Self::dissolve(&collections, column.as_ref())
+            })
+            .await??;
+
+        Ok(futures::stream::once(async { Ok(dissolved) }).boxed())
+    }
+
+    fn result_descriptor(&self) -> &VectorResultDescriptor {
+        &self.result_descriptor
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{MockExecutionContext, MockQueryContext};
+    use crate::mock::MockFeatureCollectionSource;
+    use geo::Area;
+    use geoengine_datatypes::primitives::{FeatureData, SpatialResolution};
+    use geoengine_datatypes::util::test::TestDefault;
+
+    fn square(x: f64, y: f64, size: f64) -> MultiPolygon {
+        MultiPolygon::new(vec![vec![vec![
+            (x, y).into(),
+            (x + size, y).into(),
+            (x + size, y + size).into(),
+            (x, y + size).into(),
+            (x, y).into(),
+        ]]])
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn it_dissolves_by_column_across_chunks() {
+        let chunks = vec![
+            MultiPolygonCollection::from_data(
+                vec![square(0., 0., 10.), square(20., 0., 10.)],
+                vec![TimeInterval::default(); 2],
+                [(
+                    "class".to_string(),
+                    FeatureData::Text(vec!["forest".to_string(), "urban".to_string()]),
+                )]
+                .into_iter()
+                .collect(),
+                CacheHint::default(),
+            )
+            .unwrap(),
+            MultiPolygonCollection::from_data(
+                vec![square(5., 0., 10.)],
+                vec![TimeInterval::default()],
+                [(
+                    "class".to_string(),
+                    FeatureData::Text(vec!["forest".to_string()]),
+                )]
+                .into_iter()
+                .collect(),
+                CacheHint::default(),
+            )
+            .unwrap(),
+        ];
+
+        let dissolve = Dissolve {
+            params: DissolveParams {
+                column: Some("class".to_string()),
+            },
+            sources: MockFeatureCollectionSource::multiple(chunks).boxed().into(),
+        }
+        .boxed()
+        .initialize(
+            WorkflowOperatorPath::initialize_root(),
+            &MockExecutionContext::test_default(),
+        )
+        .await
+        .unwrap();
+
+        let processor = dissolve.query_processor().unwrap().multi_polygon().unwrap();
+
+        let result: Vec<MultiPolygonCollection> = processor
+            .query(
+                VectorQueryRectangle {
+                    spatial_bounds: BoundingBox2D::new((-10., -10.).into(), (40., 40.).into())
+                        .unwrap(),
+                    time_interval: TimeInterval::default(),
+                    spatial_resolution: SpatialResolution::one(),
+                    attributes: ColumnSelection::all(),
+                },
+                &MockQueryContext::test_default(),
+            )
+            .await
+            .unwrap()
+            .try_collect()
+            .await
+            .unwrap();
+
+        assert_eq!(result.len(), 1);
+        let result = &result[0];
+
+        assert_eq!(
+            result
+                .data("class")
+                .unwrap()
+                .strings_iter()
+                .collect::<Vec<_>>(),
+            vec!["forest".to_string(), "urban".to_string()]
+        );
+
+        let areas = result
+            .geometries()
+            .map(|geometry| geometry.as_geo().unsigned_area())
+            .collect::<Vec<_>>();
+        assert!((areas[0] - 150.).abs() < 1e-9);
+        assert!((areas[1] - 100.).abs() < 1e-9);
+    }
+
+    #[tokio::test]
+    async fn it_checks_the_column() {
+        let dissolve = Dissolve {
+            params: DissolveParams {
+                column: Some("foo".to_string()),
+            },
+            sources: MockFeatureCollectionSource::single(MultiPolygonCollection::empty())
+                .boxed()
+                .into(),
+        }
+        .boxed()
+        .initialize(
+            WorkflowOperatorPath::initialize_root(),
+            &MockExecutionContext::test_default(),
+        )
+        .await;
+
+        assert!(dissolve.is_err());
+    }
+}
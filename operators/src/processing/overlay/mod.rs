@@ -0,0 +1,149 @@
+mod clip;
+mod dissolve;
+mod polygon_overlay;
+
+use crate::engine::{QueryContext, VectorQueryProcessor};
+use crate::processing::buffer::geometry::cascaded_union;
+use crate::util::Result;
+use futures::TryStreamExt;
+use geoengine_datatypes::collections::{
+    FeatureCollection, FeatureCollectionInfos, FeatureCollectionModifications,
+    GeoFeatureCollectionModifications, IntoGeometryIterator, MultiPolygonCollection,
+};
+use geoengine_datatypes::primitives::{
+    AsGeo, BoundingBox2D, CacheHint, Geometry, GeometryRef, TimeInterval, VectorQueryRectangle,
+};
+use geoengine_datatypes::util::arrow::ArrowTyped;
+use snafu::Snafu;
+
+pub use clip::{Clip, ClipParams, ClipSources};
+pub use dissolve::{Dissolve, DissolveParams};
+pub use polygon_overlay::{
+    OverlayOperation, PolygonOverlay, PolygonOverlayParams, PolygonOverlaySources,
+};
+
+/// All polygons of an overlay source that were returned for a query
+struct OverlayPolygons {
+    collections: Vec<MultiPolygonCollection>,
+    polygons: Vec<OverlayPolygon>,
+    cache_hint: CacheHint,
+}
+
+struct OverlayPolygon {
+    geometry: geo::MultiPolygon<f64>,
+    bbox: BoundingBox2D,
+    time: TimeInterval,
+    collection_index: usize,
+    feature_index: usize,
+}
+
+impl OverlayPolygons {
+    /// Queries the whole overlay source since every chunk of the other input
+    /// may intersect with any of its polygons.
+    async fn query(
+        processor: &dyn VectorQueryProcessor<VectorType = MultiPolygonCollection>,
+        query: VectorQueryRectangle,
+        ctx: &dyn QueryContext,
+    ) -> Result<Self> {
+        let collections: Vec<MultiPolygonCollection> = processor
+            .vector_query(query, ctx)
+            .await?
+            .try_collect()
+            .await?;
+
+        Ok(Self::from_collections(collections))
+    }
+
+    fn from_collections(collections: Vec<MultiPolygonCollection>) -> Self {
+        let mut polygons = Vec::new();
+        let mut cache_hint = CacheHint::max_duration();
+
+        for (collection_index, collection) in collections.iter().enumerate() {
+            cache_hint.merge_with(&collection.cache_hint);
+
+            for (feature_index, (geometry, time)) in collection
+                .geometries()
+                .zip(collection.time_intervals())
+                .enumerate()
+            {
+                let Some(bbox) = geometry.bbox() else {
+                    continue;
+                };
+
+                polygons.push(OverlayPolygon {
+                    geometry: geometry.as_geo(),
+                    bbox,
+                    time: *time,
+                    collection_index,
+                    feature_index,
+                });
+            }
+        }
+
+        Self {
+            collections,
+            polygons,
+            cache_hint,
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.polygons.is_empty()
+    }
+
+    /// Returns all polygons whose bounding box and validity intersect with the given ones
+    fn candidates<'s>(
+        &'s self,
+        bbox: &'s BoundingBox2D,
+        time: &'s TimeInterval,
+    ) -> impl Iterator<Item = &'s OverlayPolygon> + 's {
+        self.polygons.iter().filter(move |polygon| {
+            polygon.bbox.intersects_bbox(bbox) && polygon.time.intersects(time)
+        })
+    }
+
+    /// Returns the union of all candidate polygons or `None` if there are no candidates
+    fn candidates_union(
+        &self,
+        bbox: &BoundingBox2D,
+        time: &TimeInterval,
+    ) -> Option<geo::MultiPolygon<f64>> {
+        let candidates = self
+            .candidates(bbox, time)
+            .map(|polygon| polygon.geometry.clone())
+            .collect::<Vec<_>>();
+
+        if candidates.is_empty() {
+            None
+        } else {
+            Some(cascaded_union(candidates))
+        }
+    }
+}
+
+/// Removes all features without geometry and replaces the geometries of the remaining ones
+fn replace_clipped_geometries<G>(
+    collection: &FeatureCollection<G>,
+    clipped: Vec<Option<G>>,
+) -> Result<FeatureCollection<G>>
+where
+    G: Geometry + ArrowTyped,
+{
+    let mask = clipped.iter().map(Option::is_some).collect::<Vec<_>>();
+    let geometries = clipped.into_iter().flatten().collect::<Vec<_>>();
+
+    Ok(collection.filter(mask)?.replace_geometries(geometries)?)
+}
+
+#[derive(Debug, Snafu)]
+#[snafu(visibility(pub(crate)), context(suffix(false)), module(error))]
+pub enum OverlayError {
+    #[snafu(display("Input `{input}` must be of type `MultiPolygon`, but is `{found}`"))]
+    InputMustBePolygons { input: String, found: String },
+    #[snafu(display("The clipped input must have geometries"))]
+    InputMustHaveGeometries,
+    #[snafu(display("The spatial references of the inputs do not match: `{left}` and `{right}`"))]
+    SpatialReferenceMismatch { left: String, right: String },
+    #[snafu(display("Column `{column}` does not exist"))]
+    ColumnDoesNotExist { column: String },
+}
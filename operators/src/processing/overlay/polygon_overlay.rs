@@ -0,0 +1,509 @@
+use super::{error, replace_clipped_geometries, OverlayPolygons};
+use crate::engine::{
+    CanonicOperatorName, ExecutionContext, InitializedSources, InitializedVectorOperator, Operator,
+    OperatorData, OperatorName, QueryContext, QueryProcessor, TypedVectorQueryProcessor,
+    VectorOperator, VectorQueryProcessor, VectorResultDescriptor, WorkflowOperatorPath,
+};
+use crate::processing::vector_join::util::translation_table;
+use crate::util::Result;
+use async_trait::async_trait;
+use futures::stream::BoxStream;
+use futures::{StreamExt, TryStreamExt};
+use geo::BooleanOps;
+use geoengine_datatypes::collections::{
+    BuilderProvider, FeatureCollectionInfos, GeoFeatureCollectionRowBuilder, IntoGeometryIterator,
+    MultiPolygonCollection, VectorDataType,
+};
+use geoengine_datatypes::dataset::NamedData;
+use geoengine_datatypes::primitives::{
+    AsGeo, BoundingBox2D, ColumnSelection, FeatureDataType, GeometryRef, MultiPolygon,
+    VectorQueryRectangle,
+};
+use serde::{Deserialize, Serialize};
+use snafu::ensure;
+use std::sync::Arc;
+
+/// The `PolygonOverlay` operator overlays the polygons of two `MultiPolygonCollection` sources.
+///
+/// Right polygons are only overlaid with left polygons if their validities intersect.
+pub type PolygonOverlay = Operator<PolygonOverlayParams, PolygonOverlaySources>;
+
+impl OperatorName for PolygonOverlay {
+    const TYPE_NAME: &'static str = "PolygonOverlay";
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PolygonOverlayParams {
+    pub operation: OverlayOperation,
+    /// which suffix to use if columns of the right input have conflicting names?
+    /// the default is "right"
+    #[serde(default)]
+    pub right_column_suffix: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum OverlayOperation {
+    /// Outputs a feature for each intersecting pair of left and right polygons.
+    /// The features carry the attributes of both inputs and the intersection of their validities.
+    Intersection,
+    /// Removes the area of the right polygons from the left polygons.
+    /// The features keep the attributes of the left input.
+    Difference,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PolygonOverlaySources {
+    pub left: Box<dyn VectorOperator>,
+    pub right: Box<dyn VectorOperator>,
+}
+
+impl OperatorData for PolygonOverlaySources {
+    fn data_names_collect(&self, data_names: &mut Vec<NamedData>) {
+        self.left.data_names_collect(data_names);
+        self.right.data_names_collect(data_names);
+    }
+}
+
+struct InitializedPolygonOverlaySources {
+    left: Box<dyn InitializedVectorOperator>,
+    right: Box<dyn InitializedVectorOperator>,
+}
+
+#[async_trait]
+impl InitializedSources<InitializedPolygonOverlaySources> for PolygonOverlaySources {
+    async fn initialize_sources(
+        self,
+        path: WorkflowOperatorPath,
+        context: &dyn ExecutionContext,
+    ) -> Result<InitializedPolygonOverlaySources> {
+        Ok(InitializedPolygonOverlaySources {
+            left: self
+                .left
+                .initialize(path.clone_and_append(0), context)
+                .await?,
+            right: self
+                .right
+                .initialize(path.clone_and_append(1), context)
+                .await?,
+        })
+    }
+}
+
+#[typetag::serde]
+#[async_trait]
+impl VectorOperator for PolygonOverlay {
+    async fn _initialize(
+        self: Box<Self>,
+        path: WorkflowOperatorPath,
+        context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedVectorOperator>> {
+        let name = CanonicOperatorName::from(&self);
+
+        let sources = self.sources.initialize_sources(path, context).await?;
+
+        let left_desc = sources.left.result_descriptor();
+        let right_desc = sources.right.result_descriptor();
+
+        for (input, desc) in [("left", left_desc), ("right", right_desc)] {
+            ensure!(
+                desc.data_type == VectorDataType::MultiPolygon,
+                error::InputMustBePolygons {
+                    input,
+                    found: desc.data_type.to_string(),
+                }
+            );
+        }
+        ensure!(
+            left_desc.spatial_reference == right_desc.spatial_reference,
+            error::SpatialReferenceMismatch {
+                left: left_desc.spatial_reference.to_string(),
+                right: right_desc.spatial_reference.to_string(),
+            }
+        );
+
+        let (result_descriptor, right_columns) = match self.params.operation {
+            OverlayOperation::Intersection => {
+                let right_column_suffix = self
+                    .params
+                    .right_column_suffix
+                    .as_deref()
+                    .unwrap_or("right");
+                let column_translation_table = translation_table(
+                    left_desc.columns.keys(),
+                    right_desc.columns.keys(),
+                    right_column_suffix,
+                );
+
+                let mut columns = left_desc.columns.clone();
+                let mut right_columns = Vec::with_capacity(right_desc.columns.len());
+                for (column, info) in &right_desc.columns {
+                    let translated = column_translation_table[column].clone();
+                    columns.insert(translated.clone(), info.clone());
+                    right_columns.push((column.clone(), translated, info.data_type));
+                }
+
+                let bbox = match (left_desc.bbox, right_desc.bbox) {
+                    (Some(left_bbox), Some(right_bbox)) => left_bbox.intersection(&right_bbox),
+                    _ => None,
+                };
+
+                (
+                    VectorResultDescriptor {
+                        columns,
+                        bbox,
+                        ..left_desc.clone()
+                    },
+                    right_columns,
+                )
+            }
+            OverlayOperation::Difference => (left_desc.clone(), Vec::new()),
+        };
+
+        Ok(InitializedPolygonOverlay {
+            name,
+            result_descriptor,
+            left: sources.left,
+            right: sources.right,
+            operation: self.params.operation,
+            right_columns: Arc::new(right_columns),
+        }
+        .boxed())
+    }
+
+    span_fn!(PolygonOverlay);
+}
+
+pub struct InitializedPolygonOverlay {
+    name: CanonicOperatorName,
+    result_descriptor: VectorResultDescriptor,
+    left: Box<dyn InitializedVectorOperator>,
+    right: Box<dyn InitializedVectorOperator>,
+    operation: OverlayOperation,
+    right_columns: Arc<Vec<(String, String, FeatureDataType)>>,
+}
+
+impl InitializedVectorOperator for InitializedPolygonOverlay {
+    fn result_descriptor(&self) -> &VectorResultDescriptor {
+        &self.result_descriptor
+    }
+
+    fn query_processor(&self) -> Result<TypedVectorQueryProcessor> {
+        let left = self
+            .left
+            .query_processor()?
+            .multi_polygon()
+            .expect("checked in `PolygonOverlay` initialization");
+        let right = self
+            .right
+            .query_processor()?
+            .multi_polygon()
+            .expect("checked in `PolygonOverlay` initialization");
+
+        Ok(TypedVectorQueryProcessor::MultiPolygon(
+            PolygonOverlayProcessor {
+                left,
+                right,
+                result_descriptor: self.result_descriptor.clone(),
+                operation: self.operation,
+                right_columns: self.right_columns.clone(),
+            }
+            .boxed(),
+        ))
+    }
+
+    fn canonic_name(&self) -> CanonicOperatorName {
+        self.name.clone()
+    }
+}
+
+struct PolygonOverlayProcessor {
+    left: Box<dyn VectorQueryProcessor<VectorType = MultiPolygonCollection>>,
+    right: Box<dyn VectorQueryProcessor<VectorType = MultiPolygonCollection>>,
+    result_descriptor: VectorResultDescriptor,
+    operation: OverlayOperation,
+    /// original name, output name and type of the right columns
+    right_columns: Arc<Vec<(String, String, FeatureDataType)>>,
+}
+
+impl PolygonOverlayProcessor {
+    fn intersection(
+        left: &MultiPolygonCollection,
+        right: &OverlayPolygons,
+        right_columns: &[(String, String, FeatureDataType)],
+    ) -> Result<MultiPolygonCollection> {
+        let mut builder = MultiPolygonCollection::builder();
+        for (column, data_type) in left.column_types() {
+            builder.add_column(column, data_type)?;
+        }
+        for (_, column, data_type) in right_columns {
+            builder.add_column(column.clone(), *data_type)?;
+        }
+        let mut builder = builder.finish_header();
+
+        for (left_index, (geometry, time)) in
+            left.geometries().zip(left.time_intervals()).enumerate()
+        {
+            let Some(bbox) = geometry.bbox() else {
+                continue;
+            };
+            let geometry = geometry.as_geo();
+
+            for candidate in right.candidates(&bbox, time) {
+                let intersection = geometry.intersection(&candidate.geometry);
+                if intersection.0.is_empty() {
+                    continue;
+                }
+
+                let time = time
+                    .intersect(&candidate.time)
+                    .expect("candidates intersect in time");
+
+                builder.push_geometry(MultiPolygon::from(intersection));
+                builder.push_time_interval(time);
+
+                for column in left.column_names() {
+                    builder.push_data(column, left.data(column)?.get_unchecked(left_index))?;
+                }
+
+                let right_collection = &right.collections[candidate.collection_index];
+                for (column, translated, _) in right_columns {
+                    builder.push_data(
+                        translated,
+                        right_collection
+                            .data(column)?
+                            .get_unchecked(candidate.feature_index),
+                    )?;
+                }
+
+                builder.finish_row();
+            }
+        }
+
+        builder.cache_hint(left.cache_hint.merged(&right.cache_hint));
+
+        Ok(builder.build()?)
+    }
+
+    fn difference(
+        left: &MultiPolygonCollection,
+        right: &OverlayPolygons,
+    ) -> Result<MultiPolygonCollection> {
+        let differences = left
+            .geometries()
+            .zip(left.time_intervals())
+            .map(|(geometry, time)| {
+                let geo_geometry = geometry.as_geo();
+
+                let Some(subtrahend) = geometry
+                    .bbox()
+                    .and_then(|bbox| right.candidates_union(&bbox, time))
+                else {
+                    return Some(MultiPolygon::from(geo_geometry));
+                };
+
+                let difference = geo_geometry.difference(&subtrahend);
+
+                (!difference.0.is_empty()).then(|| MultiPolygon::from(difference))
+            })
+            .collect();
+
+        let mut output = replace_clipped_geometries(left, differences)?;
+        output.cache_hint.merge_with(&right.cache_hint);
+
+        Ok(output)
+    }
+}
+
+#[async_trait]
+impl QueryProcessor for PolygonOverlayProcessor {
+    type Output = MultiPolygonCollection;
+    type SpatialBounds = BoundingBox2D;
+    type Selection = ColumnSelection;
+    type ResultDescription = VectorResultDescriptor;
+
+    async fn _query<'a>(
+        &'a self,
+        query: VectorQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<BoxStream<'a, Result<Self::Output>>> {
+        let right =
+            Arc::new(OverlayPolygons::query(self.right.as_ref(), query.clone(), ctx).await?);
+
+        if right.is_empty() && self.operation == OverlayOperation::Intersection {
+            return Ok(futures::stream::empty().boxed());
+        }
+
+        let stream = self.left.query(query, ctx).await?.and_then(move |left| {
+            let right = right.clone();
+            let right_columns = self.right_columns.clone();
+            let operation = self.operation;
+
+            async move {
+                crate::util::spawn_blocking_with_thread_pool(ctx.thread_pool().clone(), move || {
+                    match operation {
+                        OverlayOperation::Intersection => {
+                            Self::intersection(&left, &right, &right_columns)
+                        }
+                        OverlayOperation::Difference => Self::difference(&left, &right),
+                    }
+                })
+                .await?
+            }
+        });
+
+        Ok(stream.boxed())
+    }
+
+    fn result_descriptor(&self) -> &VectorResultDescriptor {
+        &self.result_descriptor
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{MockExecutionContext, MockQueryContext};
+    use crate::mock::MockFeatureCollectionSource;
+    use geo::Area;
+    use geoengine_datatypes::primitives::{
+        CacheHint, FeatureData, SpatialResolution, TimeInterval,
+    };
+    use geoengine_datatypes::util::test::TestDefault;
+
+    fn square(x: f64, y: f64, size: f64) -> MultiPolygon {
+        MultiPolygon::new(vec![vec![vec![
+            (x, y).into(),
+            (x + size, y).into(),
+            (x + size, y + size).into(),
+            (x, y + size).into(),
+            (x, y).into(),
+        ]]])
+        .unwrap()
+    }
+
+    async fn overlay(
+        operation: OverlayOperation,
+        left: MultiPolygonCollection,
+        right: MultiPolygonCollection,
+    ) -> Vec<MultiPolygonCollection> {
+        let operator = PolygonOverlay {
+            params: PolygonOverlayParams {
+                operation,
+                right_column_suffix: None,
+            },
+            sources: PolygonOverlaySources {
+                left: MockFeatureCollectionSource::single(left).boxed(),
+                right: MockFeatureCollectionSource::single(right).boxed(),
+            },
+        }
+        .boxed()
+        .initialize(
+            WorkflowOperatorPath::initialize_root(),
+            &MockExecutionContext::test_default(),
+        )
+        .await
+        .unwrap();
+
+        let processor = operator.query_processor().unwrap().multi_polygon().unwrap();
+
+        processor
+            .query(
+                VectorQueryRectangle {
+                    spatial_bounds: BoundingBox2D::new((-10., -10.).into(), (30., 30.).into())
+                        .unwrap(),
+                    time_interval: TimeInterval::default(),
+                    spatial_resolution: SpatialResolution::one(),
+                    attributes: ColumnSelection::all(),
+                },
+                &MockQueryContext::test_default(),
+            )
+            .await
+            .unwrap()
+            .try_collect()
+            .await
+            .unwrap()
+    }
+
+    fn land_use() -> MultiPolygonCollection {
+        MultiPolygonCollection::from_data(
+            vec![square(0., 0., 10.), square(20., 20., 5.)],
+            vec![TimeInterval::default(); 2],
+            [(
+                "class".to_string(),
+                FeatureData::Text(vec!["forest".to_string(), "urban".to_string()]),
+            )]
+            .into_iter()
+            .collect(),
+            CacheHint::default(),
+        )
+        .unwrap()
+    }
+
+    fn regions() -> MultiPolygonCollection {
+        MultiPolygonCollection::from_data(
+            vec![square(5., 5., 10.)],
+            vec![TimeInterval::default()],
+            [
+                ("class".to_string(), FeatureData::Int(vec![1])),
+                ("region".to_string(), FeatureData::Int(vec![42])),
+            ]
+            .into_iter()
+            .collect(),
+            CacheHint::default(),
+        )
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn it_intersects() {
+        let result = overlay(OverlayOperation::Intersection, land_use(), regions()).await;
+
+        assert_eq!(result.len(), 1);
+        let result = &result[0];
+
+        assert_eq!(result.len(), 1);
+        assert_eq!(
+            result
+                .data("class")
+                .unwrap()
+                .strings_iter()
+                .collect::<Vec<_>>(),
+            vec!["forest".to_string()]
+        );
+        assert_eq!(
+            result.data("classright").unwrap().get_unchecked(0),
+            geoengine_datatypes::primitives::FeatureDataValue::Int(1)
+        );
+        assert_eq!(
+            result.data("region").unwrap().get_unchecked(0),
+            geoengine_datatypes::primitives::FeatureDataValue::Int(42)
+        );
+
+        let area = result.geometries().next().unwrap().as_geo().unsigned_area();
+        assert!((area - 25.).abs() < 1e-9);
+    }
+
+    #[tokio::test]
+    async fn it_computes_the_difference() {
+        let result = overlay(OverlayOperation::Difference, land_use(), regions()).await;
+
+        assert_eq!(result.len(), 1);
+        let result = &result[0];
+
+        assert_eq!(result.len(), 2);
+        assert_eq!(
+            result.column_names().cloned().collect::<Vec<_>>(),
+            vec!["class".to_string()]
+        );
+
+        let areas = result
+            .geometries()
+            .map(|geometry| geometry.as_geo().unsigned_area())
+            .collect::<Vec<_>>();
+        assert!((areas[0] - 75.).abs() < 1e-9);
+        assert!((areas[1] - 25.).abs() < 1e-9);
+    }
+}
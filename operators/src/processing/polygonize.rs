@@ -0,0 +1,681 @@
+use crate::engine::{
+    CanonicOperatorName, ExecutionContext, InitializedRasterOperator, InitializedSources,
+    InitializedVectorOperator, Operator, OperatorName, QueryContext, QueryProcessor,
+    RasterQueryProcessor, SingleRasterSource, TypedVectorQueryProcessor, VectorColumnInfo,
+    VectorOperator, VectorQueryProcessor, VectorResultDescriptor, WorkflowOperatorPath,
+};
+use crate::processing::buffer::geometry::cascaded_union;
+use crate::util::Result;
+use async_trait::async_trait;
+use futures::stream::BoxStream;
+use futures::{StreamExt, TryStreamExt};
+use geo::BooleanOps;
+use geoengine_datatypes::collections::{
+    BuilderProvider, GeoFeatureCollectionRowBuilder, MultiPolygonCollection, VectorDataType,
+};
+use geoengine_datatypes::primitives::{
+    BandSelection, BoundingBox2D, CacheHint, ColumnSelection, FeatureDataType, FeatureDataValue,
+    Measurement, MultiPolygon, RasterQueryRectangle, TimeInterval, VectorQueryRectangle,
+};
+use geoengine_datatypes::raster::{
+    GeoTransform, GridIdx, GridIdx2D, GridIndexAccess, GridShapeAccess, Pixel, RasterTile2D,
+};
+use num_traits::AsPrimitive;
+use ordered_float::OrderedFloat;
+use serde::{Deserialize, Serialize};
+use snafu::{ensure, Snafu};
+use std::collections::{BTreeMap, HashMap};
+
+/// The `Polygonize` operator converts contiguous regions of equal class of a raster
+/// into a `MultiPolygonCollection`.
+///
+/// Each region becomes a feature that carries its class as an attribute.
+/// Regions are merged across tile boundaries.
+/// Thus, all tiles of a time step are processed before the features are emitted.
+pub type Polygonize = Operator<PolygonizeParams, SingleRasterSource>;
+
+impl OperatorName for Polygonize {
+    const TYPE_NAME: &'static str = "Polygonize";
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PolygonizeParams {
+    /// How pixel values are mapped to classes
+    #[serde(default)]
+    pub classification: PolygonizeClassification,
+    /// The name of the output column that contains the class
+    #[serde(default = "PolygonizeParams::default_column_name")]
+    pub column_name: String,
+}
+
+impl PolygonizeParams {
+    fn default_column_name() -> String {
+        "value".to_string()
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub enum PolygonizeClassification {
+    /// Every distinct pixel value is a class, e.g., for categorical rasters.
+    /// The output column contains the pixel value.
+    #[default]
+    Values,
+    /// The classes are separated by ascending thresholds.
+    /// Values below the first threshold are in class `0`,
+    /// values greater or equal to the `i`-th threshold are in class `i + 1`.
+    /// The output column contains the class index.
+    Thresholds { thresholds: Vec<f64> },
+}
+
+impl PolygonizeClassification {
+    fn classify(&self, value: f64) -> Option<OrderedFloat<f64>> {
+        if value.is_nan() {
+            return None;
+        }
+
+        Some(OrderedFloat(match self {
+            Self::Values => value,
+            Self::Thresholds { thresholds } => {
+                thresholds.partition_point(|threshold| *threshold <= value) as f64
+            }
+        }))
+    }
+
+    fn column_data_type(&self) -> FeatureDataType {
+        match self {
+            Self::Values => FeatureDataType::Float,
+            Self::Thresholds { .. } => FeatureDataType::Int,
+        }
+    }
+
+    fn column_value(&self, class: OrderedFloat<f64>) -> FeatureDataValue {
+        match self {
+            Self::Values => FeatureDataValue::Float(class.0),
+            Self::Thresholds { .. } => FeatureDataValue::Int(class.0 as i64),
+        }
+    }
+}
+
+#[typetag::serde]
+#[async_trait]
+impl VectorOperator for Polygonize {
+    async fn _initialize(
+        self: Box<Self>,
+        path: WorkflowOperatorPath,
+        context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedVectorOperator>> {
+        if let PolygonizeClassification::Thresholds { thresholds } = &self.params.classification {
+            ensure!(
+                !thresholds.is_empty()
+                    && thresholds.iter().all(|t| t.is_finite())
+                    && thresholds.windows(2).all(|w| w[0] < w[1]),
+                error::InvalidThresholds
+            );
+        }
+        ensure!(!self.params.column_name.is_empty(), error::EmptyColumnName);
+
+        let name = CanonicOperatorName::from(&self);
+
+        let raster = self.sources.initialize_sources(path, context).await?.raster;
+
+        let in_desc = raster.result_descriptor();
+
+        let measurement = match self.params.classification {
+            PolygonizeClassification::Values => in_desc.bands[0].measurement.clone(),
+            PolygonizeClassification::Thresholds { .. } => Measurement::Unitless,
+        };
+
+        let result_descriptor = VectorResultDescriptor {
+            data_type: VectorDataType::MultiPolygon,
+            spatial_reference: in_desc.spatial_reference,
+            columns: [(
+                self.params.column_name.clone(),
+                VectorColumnInfo {
+                    data_type: self.params.classification.column_data_type(),
+                    measurement,
+                },
+            )]
+            .into_iter()
+            .collect(),
+            time: in_desc.time,
+            bbox: in_desc
+                .bbox
+                .map(|bbox| BoundingBox2D::from(geo::Rect::from(&bbox))),
+        };
+
+        Ok(InitializedPolygonize {
+            name,
+            result_descriptor,
+            raster,
+            classification: self.params.classification,
+            column_name: self.params.column_name,
+        }
+        .boxed())
+    }
+
+    span_fn!(Polygonize);
+}
+
+pub struct InitializedPolygonize {
+    name: CanonicOperatorName,
+    result_descriptor: VectorResultDescriptor,
+    raster: Box<dyn InitializedRasterOperator>,
+    classification: PolygonizeClassification,
+    column_name: String,
+}
+
+impl InitializedVectorOperator for InitializedPolygonize {
+    fn result_descriptor(&self) -> &VectorResultDescriptor {
+        &self.result_descriptor
+    }
+
+    fn query_processor(&self) -> Result<TypedVectorQueryProcessor> {
+        let processor = call_on_generic_raster_processor!(
+            self.raster.query_processor()?,
+            raster => PolygonizeProcessor {
+                raster,
+                result_descriptor: self.result_descriptor.clone(),
+                classification: self.classification.clone(),
+                column_name: self.column_name.clone(),
+            }
+            .boxed()
+        );
+
+        Ok(TypedVectorQueryProcessor::MultiPolygon(processor))
+    }
+
+    fn canonic_name(&self) -> CanonicOperatorName {
+        self.name.clone()
+    }
+}
+
+struct PolygonizeProcessor<T>
+where
+    T: Pixel,
+{
+    raster: Box<dyn RasterQueryProcessor<RasterType = T>>,
+    result_descriptor: VectorResultDescriptor,
+    classification: PolygonizeClassification,
+    column_name: String,
+}
+
+/// The regions of a single tile
+struct TileRegions {
+    time: TimeInterval,
+    cache_hint: CacheHint,
+    classes: BTreeMap<OrderedFloat<f64>, Vec<geo::Polygon<f64>>>,
+}
+
+impl<T> PolygonizeProcessor<T>
+where
+    T: Pixel,
+{
+    /// Creates rectangles for runs of equal classes in each row and
+    /// extends them downwards as long as the next row has the same run.
+    fn tile_regions(
+        tile: &RasterTile2D<T>,
+        classification: &PolygonizeClassification,
+    ) -> TileRegions {
+        let mut classes = BTreeMap::<OrderedFloat<f64>, Vec<geo::Polygon<f64>>>::new();
+
+        if tile.is_empty() {
+            return TileRegions {
+                time: tile.time,
+                cache_hint: tile.cache_hint,
+                classes,
+            };
+        }
+
+        let [rows, columns] = tile.grid_shape_array();
+        let GridIdx([offset_y, offset_x]) = tile.tile_information().global_upper_left_pixel_idx();
+        let geo_transform = tile.global_geo_transform;
+
+        let mut push_rectangle = |class, run: Run, end_row: usize| {
+            classes.entry(class).or_default().push(pixel_rectangle(
+                &geo_transform,
+                [
+                    offset_y + run.start_row as isize,
+                    offset_x + run.start_column as isize,
+                ]
+                .into(),
+                [
+                    offset_y + end_row as isize,
+                    offset_x + run.end_column as isize,
+                ]
+                .into(),
+            ));
+        };
+
+        // runs of the previous row that may be extended
+        let mut open_runs = HashMap::<(usize, usize, OrderedFloat<f64>), Run>::new();
+
+        for y in 0..rows {
+            let mut row_runs = HashMap::with_capacity(open_runs.len());
+
+            let mut x = 0;
+            while x < columns {
+                let class = tile
+                    .get_at_grid_index_unchecked(GridIdx([y as isize, x as isize]))
+                    .and_then(|value| classification.classify(value.as_()));
+
+                let start_column = x;
+                x += 1;
+
+                let Some(class) = class else {
+                    continue;
+                };
+
+                while x < columns
+                    && tile
+                        .get_at_grid_index_unchecked(GridIdx([y as isize, x as isize]))
+                        .and_then(|value| classification.classify(value.as_()))
+                        == Some(class)
+                {
+                    x += 1;
+                }
+
+                let key = (start_column, x, class);
+                let run = open_runs.remove(&key).unwrap_or(Run {
+                    start_row: y,
+                    start_column,
+                    end_column: x,
+                });
+                row_runs.insert(key, run);
+            }
+
+            for ((_, _, class), run) in open_runs.drain() {
+                push_rectangle(class, run, y);
+            }
+
+            open_runs = row_runs;
+        }
+
+        for ((_, _, class), run) in open_runs {
+            push_rectangle(class, run, rows);
+        }
+
+        TileRegions {
+            time: tile.time,
+            cache_hint: tile.cache_hint,
+            classes,
+        }
+    }
+
+    /// Merges the regions of all tiles of one time step and splits them into contiguous features
+    fn regions_to_collection(
+        time: TimeInterval,
+        regions: Vec<TileRegions>,
+        bounds: BoundingBox2D,
+        classification: &PolygonizeClassification,
+        column_name: &str,
+    ) -> Result<MultiPolygonCollection> {
+        let mut classes = BTreeMap::<OrderedFloat<f64>, Vec<geo::MultiPolygon<f64>>>::new();
+        let mut cache_hint = CacheHint::max_duration();
+
+        for tile_regions in regions {
+            cache_hint.merge_with(&tile_regions.cache_hint);
+
+            for (class, polygons) in tile_regions.classes {
+                classes.entry(class).or_default().extend(
+                    polygons
+                        .into_iter()
+                        .map(|p| geo::MultiPolygon::new(vec![p])),
+                );
+            }
+        }
+
+        let bounds = geo::MultiPolygon::new(vec![geo::Rect::from(bounds).to_polygon()]);
+
+        let mut builder = MultiPolygonCollection::builder();
+        builder.add_column(column_name.to_string(), classification.column_data_type())?;
+        let mut builder = builder.finish_header();
+
+        for (class, polygons) in classes {
+            let merged = cascaded_union(polygons).intersection(&bounds);
+
+            for polygon in merged {
+                builder.push_geometry(MultiPolygon::from(geo::MultiPolygon::new(vec![polygon])));
+                builder.push_time_interval(time);
+                builder.push_data(column_name, classification.column_value(class))?;
+                builder.finish_row();
+            }
+        }
+
+        builder.cache_hint(cache_hint);
+
+        Ok(builder.build()?)
+    }
+}
+
+type TileRegionsStream<'a> = BoxStream<'a, Result<TileRegions>>;
+
+/// Collects the regions of the next time step from the stream.
+///
+/// Tiles are ordered by time, so consecutive tiles with the same time form a time step.
+/// The first tile of the following time step is returned as lookahead.
+#[allow(clippy::type_complexity)]
+async fn next_time_step(
+    mut regions: TileRegionsStream<'_>,
+    lookahead: Option<TileRegions>,
+) -> Result<
+    Option<(
+        (TimeInterval, Vec<TileRegions>),
+        (TileRegionsStream<'_>, Option<TileRegions>),
+    )>,
+> {
+    let first = match lookahead {
+        Some(first) => first,
+        None => match regions.try_next().await? {
+            Some(first) => first,
+            None => return Ok(None),
+        },
+    };
+
+    let time = first.time;
+    let mut time_step = vec![first];
+
+    while let Some(tile_regions) = regions.try_next().await? {
+        if tile_regions.time != time {
+            return Ok(Some(((time, time_step), (regions, Some(tile_regions)))));
+        }
+
+        time_step.push(tile_regions);
+    }
+
+    Ok(Some(((time, time_step), (regions, None))))
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Run {
+    start_row: usize,
+    start_column: usize,
+    end_column: usize,
+}
+
+/// Creates the rectangle that spans from the upper left corner of the `upper_left` pixel
+/// to the upper left corner of the `lower_right_exclusive` pixel.
+///
+/// Uses the global geo transform so that neighboring tiles share the exact same coordinates.
+fn pixel_rectangle(
+    geo_transform: &GeoTransform,
+    upper_left: GridIdx2D,
+    lower_right_exclusive: GridIdx2D,
+) -> geo::Polygon<f64> {
+    let a = geo_transform.grid_idx_to_pixel_upper_left_coordinate_2d(upper_left);
+    let b = geo_transform.grid_idx_to_pixel_upper_left_coordinate_2d(lower_right_exclusive);
+
+    geo::Rect::new(geo::Coord::from(a), geo::Coord::from(b)).to_polygon()
+}
+
+#[async_trait]
+impl<T> QueryProcessor for PolygonizeProcessor<T>
+where
+    T: Pixel,
+{
+    type Output = MultiPolygonCollection;
+    type SpatialBounds = BoundingBox2D;
+    type Selection = ColumnSelection;
+    type ResultDescription = VectorResultDescriptor;
+
+    async fn _query<'a>(
+        &'a self,
+        query: VectorQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<BoxStream<'a, Result<Self::Output>>> {
+        let bounds = query.spatial_bounds;
+
+        let raster_query =
+            RasterQueryRectangle::from_qrect_and_bands(&query, BandSelection::first());
+
+        let regions = self
+            .raster
+            .raster_query(raster_query, ctx)
+            .await?
+            .and_then(|tile| {
+                let classification = self.classification.clone();
+                async move {
+                    crate::util::spawn_blocking_with_thread_pool(
+                        ctx.thread_pool().clone(),
+                        move || Self::tile_regions(&tile, &classification),
+                    )
+                    .await
+                    .map_err(Into::into)
+                }
+            })
+            .boxed();
+
+        let time_steps = futures::stream::try_unfold((regions, None), |(regions, lookahead)| {
+            next_time_step(regions, lookahead)
+        });
+
+        let stream = time_steps.and_then(move |(time, regions)| {
+            let classification = self.classification.clone();
+            let column_name = self.column_name.clone();
+            async move {
+                crate::util::spawn_blocking_with_thread_pool(ctx.thread_pool().clone(), move || {
+                    Self::regions_to_collection(
+                        time,
+                        regions,
+                        bounds,
+                        &classification,
+                        &column_name,
+                    )
+                })
+                .await?
+            }
+        });
+
+        Ok(stream.boxed())
+    }
+
+    fn result_descriptor(&self) -> &VectorResultDescriptor {
+        &self.result_descriptor
+    }
+}
+
+#[derive(Debug, Snafu)]
+#[snafu(visibility(pub(crate)), context(suffix(false)), module(error))]
+pub enum PolygonizeError {
+    #[snafu(display("Thresholds must be finite, non-empty and strictly ascending"))]
+    InvalidThresholds,
+    #[snafu(display("The column name must not be empty"))]
+    EmptyColumnName,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{
+        MockExecutionContext, MockQueryContext, RasterBandDescriptors, RasterOperator,
+        RasterResultDescriptor,
+    };
+    use crate::mock::{MockRasterSource, MockRasterSourceParams};
+    use geo::Area;
+    use geoengine_datatypes::collections::{FeatureCollectionInfos, IntoGeometryIterator};
+    use geoengine_datatypes::primitives::{AsGeo, SpatialResolution};
+    use geoengine_datatypes::raster::{
+        Grid2D, RasterDataType, TileInformation, TilingSpecification,
+    };
+    use geoengine_datatypes::spatial_reference::SpatialReference;
+    use geoengine_datatypes::util::test::TestDefault;
+
+    fn raster_source(times: &[TimeInterval]) -> Box<dyn RasterOperator> {
+        let tile_size_in_pixels = [2, 2].into();
+
+        // 1 1 | 2 2
+        // 1 2 | 2 0
+        let tiles = times
+            .iter()
+            .flat_map(|&time| {
+                [
+                    RasterTile2D::new_with_tile_info(
+                        time,
+                        TileInformation {
+                            global_geo_transform: TestDefault::test_default(),
+                            global_tile_position: [0, 0].into(),
+                            tile_size_in_pixels,
+                        },
+                        0,
+                        Grid2D::new([2, 2].into(), vec![1_u8, 1, 1, 2])
+                            .unwrap()
+                            .into(),
+                        CacheHint::default(),
+                    ),
+                    RasterTile2D::new_with_tile_info(
+                        time,
+                        TileInformation {
+                            global_geo_transform: TestDefault::test_default(),
+                            global_tile_position: [0, 1].into(),
+                            tile_size_in_pixels,
+                        },
+                        0,
+                        Grid2D::new([2, 2].into(), vec![2_u8, 2, 2, 0])
+                            .unwrap()
+                            .into(),
+                        CacheHint::default(),
+                    ),
+                ]
+            })
+            .collect();
+
+        MockRasterSource {
+            params: MockRasterSourceParams {
+                data: tiles,
+                result_descriptor: RasterResultDescriptor {
+                    data_type: RasterDataType::U8,
+                    spatial_reference: SpatialReference::epsg_4326().into(),
+                    time: None,
+                    bbox: None,
+                    resolution: None,
+                    bands: RasterBandDescriptors::new_single_band(),
+                },
+            },
+        }
+        .boxed()
+    }
+
+    async fn polygonize(
+        classification: PolygonizeClassification,
+        times: &[TimeInterval],
+    ) -> Vec<MultiPolygonCollection> {
+        let execution_context = MockExecutionContext::new_with_tiling_spec(TilingSpecification {
+            origin_coordinate: [0.0, 0.0].into(),
+            tile_size_in_pixels: [2, 2].into(),
+        });
+
+        let operator = Polygonize {
+            params: PolygonizeParams {
+                classification,
+                column_name: "class".to_string(),
+            },
+            sources: raster_source(times).into(),
+        }
+        .boxed()
+        .initialize(WorkflowOperatorPath::initialize_root(), &execution_context)
+        .await
+        .unwrap();
+
+        let processor = operator.query_processor().unwrap().multi_polygon().unwrap();
+
+        processor
+            .query(
+                VectorQueryRectangle {
+                    spatial_bounds: BoundingBox2D::new((0., -2.).into(), (4., 0.).into()).unwrap(),
+                    time_interval: TimeInterval::default(),
+                    spatial_resolution: SpatialResolution::one(),
+                    attributes: ColumnSelection::all(),
+                },
+                &MockQueryContext::test_default(),
+            )
+            .await
+            .unwrap()
+            .try_collect()
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn it_polygonizes_values_across_tiles() {
+        let result = polygonize(PolygonizeClassification::Values, &[TimeInterval::default()]).await;
+
+        assert_eq!(result.len(), 1);
+        let result = &result[0];
+
+        let classes = result
+            .data("class")
+            .unwrap()
+            .float_options_iter()
+            .map(Option::unwrap)
+            .collect::<Vec<_>>();
+        let areas = result
+            .geometries()
+            .map(|geometry| geometry.as_geo().unsigned_area())
+            .collect::<Vec<_>>();
+
+        assert_eq!(classes, vec![0., 1., 2.]);
+        assert_eq!(areas, vec![1., 3., 4.]);
+    }
+
+    #[tokio::test]
+    async fn it_polygonizes_thresholds() {
+        let result = polygonize(
+            PolygonizeClassification::Thresholds {
+                thresholds: vec![1.],
+            },
+            &[TimeInterval::default()],
+        )
+        .await;
+
+        assert_eq!(result.len(), 1);
+        let result = &result[0];
+
+        assert_eq!(result.len(), 2);
+
+        let areas = result
+            .geometries()
+            .map(|geometry| geometry.as_geo().unsigned_area())
+            .collect::<Vec<_>>();
+
+        assert_eq!(areas, vec![1., 7.]);
+    }
+
+    #[tokio::test]
+    async fn it_polygonizes_each_time_step() {
+        let times = [
+            TimeInterval::new(0, 10).unwrap(),
+            TimeInterval::new(10, 20).unwrap(),
+        ];
+
+        let result = polygonize(PolygonizeClassification::Values, &times).await;
+
+        assert_eq!(result.len(), 2);
+
+        for (collection, time) in result.iter().zip(times) {
+            assert_eq!(collection.len(), 3);
+            assert!(collection.time_intervals().iter().all(|t| *t == time));
+        }
+    }
+
+    #[tokio::test]
+    async fn it_rejects_invalid_thresholds() {
+        let result = Polygonize {
+            params: PolygonizeParams {
+                classification: PolygonizeClassification::Thresholds {
+                    thresholds: vec![2., 1.],
+                },
+                column_name: "class".to_string(),
+            },
+            sources: raster_source(&[TimeInterval::default()]).into(),
+        }
+        .boxed()
+        .initialize(
+            WorkflowOperatorPath::initialize_root(),
+            &MockExecutionContext::test_default(),
+        )
+        .await;
+
+        assert!(result.is_err());
+    }
+}
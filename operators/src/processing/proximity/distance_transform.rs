@@ -0,0 +1,120 @@
+use rayon::prelude::*;
+
+/// A squared distance that is larger than every distance within a tile.
+/// It marks pixels without a valid source pixel.
+pub const FAR: f64 = 1e20;
+
+/// Computes the exact squared euclidean distance transform of a grid in row-major order.
+///
+/// Input cells with value `0` are sources and cells with value [`FAR`] are background.
+/// The distances respect the (anisotropic) pixel sizes `dx` and `dy`.
+///
+/// Uses the separable algorithm of Felzenszwalb and Huttenlocher: first all columns, then all rows.
+pub fn squared_distance_transform(grid: &mut [f64], width: usize, height: usize, dx: f64, dy: f64) {
+    debug_assert_eq!(grid.len(), width * height);
+
+    let columns: Vec<Vec<f64>> = (0..width)
+        .into_par_iter()
+        .map(|x| {
+            let column: Vec<f64> = (0..height).map(|y| grid[y * width + x]).collect();
+            squared_distance_transform_1d(&column, dy)
+        })
+        .collect();
+
+    grid.par_chunks_mut(width)
+        .enumerate()
+        .for_each(|(y, row_out)| {
+            let row: Vec<f64> = columns.iter().map(|column| column[y]).collect();
+            row_out.copy_from_slice(&squared_distance_transform_1d(&row, dx));
+        });
+}
+
+/// Computes the lower envelope of parabolas rooted at `(q * spacing, f[q])`.
+fn squared_distance_transform_1d(f: &[f64], spacing: f64) -> Vec<f64> {
+    let n = f.len();
+    let mut distances = vec![FAR; n];
+
+    if n == 0 {
+        return distances;
+    }
+
+    let position = |q: usize| q as f64 * spacing;
+
+    // indices of the parabolas of the lower envelope and the boundaries between them
+    let mut vertices = vec![0_usize; n];
+    let mut boundaries = vec![0.; n + 1];
+
+    let mut k = 0;
+    boundaries[0] = f64::NEG_INFINITY;
+    boundaries[1] = f64::INFINITY;
+
+    let intersection = |q: usize, r: usize| {
+        ((f[q] + position(q).powi(2)) - (f[r] + position(r).powi(2)))
+            / (2. * (position(q) - position(r)))
+    };
+
+    for q in 1..n {
+        let mut s = intersection(q, vertices[k]);
+        // the boundary at `k == 0` is negative infinity, so `k` never underflows
+        while s <= boundaries[k] {
+            k -= 1;
+            s = intersection(q, vertices[k]);
+        }
+
+        k += 1;
+        vertices[k] = q;
+        boundaries[k] = s;
+        boundaries[k + 1] = f64::INFINITY;
+    }
+
+    k = 0;
+    for (q, distance) in distances.iter_mut().enumerate() {
+        while boundaries[k + 1] < position(q) {
+            k += 1;
+        }
+        let r = vertices[k];
+        *distance = (position(q) - position(r)).powi(2) + f[r];
+    }
+
+    distances
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_computes_distances_in_one_dimension() {
+        let distances = squared_distance_transform_1d(&[FAR, 0., FAR, FAR, FAR, 0.], 2.);
+
+        assert_eq!(
+            distances.into_iter().map(f64::sqrt).collect::<Vec<_>>(),
+            vec![2., 0., 2., 4., 2., 0.]
+        );
+    }
+
+    #[test]
+    fn it_computes_anisotropic_distances() {
+        #[rustfmt::skip]
+        let mut grid = vec![
+            0., FAR, FAR,
+            FAR, FAR, FAR,
+        ];
+
+        squared_distance_transform(&mut grid, 3, 2, 3., 4.);
+
+        assert_eq!(
+            grid.into_iter().map(f64::sqrt).collect::<Vec<_>>(),
+            vec![0., 3., 6., 4., 5., 52_f64.sqrt()]
+        );
+    }
+
+    #[test]
+    fn it_keeps_far_without_sources() {
+        let mut grid = vec![FAR; 4];
+
+        squared_distance_transform(&mut grid, 2, 2, 1., 1.);
+
+        assert!(grid.into_iter().all(|d| d >= FAR));
+    }
+}
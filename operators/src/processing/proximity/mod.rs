@@ -0,0 +1,926 @@
+mod distance_transform;
+mod tile_sub_query;
+
+use self::tile_sub_query::ProximityTileNeighborhood;
+use super::buffer::geometry::EARTH_RADIUS;
+use crate::adapters::{stack_individual_aligned_raster_bands, RasterSubQueryAdapter};
+use crate::engine::{
+    CanonicOperatorName, ExecutionContext, InitializedRasterOperator, InitializedSources,
+    InitializedVectorOperator, Operator, OperatorName, QueryContext, QueryProcessor,
+    RasterBandDescriptor, RasterBandDescriptors, RasterOperator, RasterQueryProcessor,
+    RasterResultDescriptor, SingleRasterOrVectorSource, SingleRasterSource, SingleVectorSource,
+    TypedRasterQueryProcessor, TypedVectorQueryProcessor, WorkflowOperatorPath,
+};
+use crate::processing::RasterTypeConversionQueryProcessor;
+use crate::util::input::RasterOrVectorOperator;
+use crate::util::{spawn_blocking_with_thread_pool, Result};
+use async_trait::async_trait;
+use futures::stream::BoxStream;
+use futures::{stream, StreamExt};
+use geo::Contains;
+use geoengine_datatypes::collections::{FeatureCollection, IntoGeometryIterator, VectorDataType};
+use geoengine_datatypes::primitives::{
+    AxisAlignedRectangle, BandSelection, BoundingBox2D, CacheHint, ColumnSelection, Coordinate2D,
+    Measurement, MultiLineStringAccess, MultiPointAccess, MultiPolygonAccess, RasterQueryRectangle,
+    SpatialPartition2D, SpatialPartitioned, SpatialResolution, TimeInterval, VectorQueryRectangle,
+};
+use geoengine_datatypes::raster::{
+    EmptyGrid2D, GeoTransform, Grid2D, GridSize, MaskedGrid2D, RasterDataType, RasterTile2D,
+    TileInformation, TilingSpecification,
+};
+use geoengine_datatypes::spatial_reference::SpatialReferenceOption;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use snafu::{ensure, Snafu};
+
+/// The `Proximity` operator computes for every pixel the distance to the nearest valid pixel of
+/// a raster source or to the nearest feature of a vector source.
+///
+/// Distances are measured in meters for rasters and vectors in EPSG:4326 and in the units of the
+/// spatial reference otherwise.
+/// The search is bounded by `max_distance`. Pixels without a valid pixel or feature within this
+/// distance are no data. This allows computing each tile independently from its surrounding tiles.
+///
+/// For geographic coordinates, distances are approximated by scaling longitudes by the cosine of
+/// the latitude at the center of each tile.
+pub type Proximity = Operator<ProximityParams, SingleRasterOrVectorSource>;
+
+impl OperatorName for Proximity {
+    const TYPE_NAME: &'static str = "Proximity";
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ProximityParams {
+    /// The maximum distance to search for valid pixels or features
+    pub max_distance: f64,
+}
+
+#[derive(Debug, Snafu)]
+#[snafu(visibility(pub(crate)), context(suffix(false)), module(error))]
+pub enum ProximityError {
+    #[snafu(display("The maximum distance must be positive and finite"))]
+    InvalidMaxDistance,
+
+    #[snafu(display(
+        "The maximum distance {max_distance} must not exceed the size of a tile at the query resolution"
+    ))]
+    SearchRadiusTooLarge { max_distance: f64 },
+
+    #[snafu(display("The vector source must contain geometries"))]
+    VectorSourceMustHaveGeometries,
+}
+
+/// Describes how coordinates are converted into distances
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DistanceUnits {
+    /// Geographic coordinates where distances are measured in meters
+    Meters,
+    /// Distances are measured in the units of the spatial reference
+    CoordinateUnits,
+}
+
+impl DistanceUnits {
+    fn from_spatial_reference(spatial_reference: SpatialReferenceOption) -> Result<Self> {
+        let geographic = match spatial_reference {
+            SpatialReferenceOption::SpatialReference(spatial_reference) => {
+                spatial_reference.is_geographic()?
+            }
+            SpatialReferenceOption::Unreferenced => false,
+        };
+
+        Ok(if geographic {
+            Self::Meters
+        } else {
+            Self::CoordinateUnits
+        })
+    }
+
+    fn unit(self) -> Option<String> {
+        match self {
+            Self::Meters => Some("m".to_string()),
+            Self::CoordinateUnits => None,
+        }
+    }
+
+    /// The distance units per coordinate unit in x and y direction at a latitude
+    fn units_per_coordinate(self, latitude: f64) -> (f64, f64) {
+        match self {
+            Self::Meters => {
+                let meters_per_degree = EARTH_RADIUS.to_radians();
+                (
+                    meters_per_degree * latitude.to_radians().cos(),
+                    meters_per_degree,
+                )
+            }
+            Self::CoordinateUnits => (1., 1.),
+        }
+    }
+
+    /// The margin in coordinate units that covers `max_distance` around all points of `bounds`
+    fn search_margin(self, max_distance: f64, bounds: SpatialPartition2D) -> Coordinate2D {
+        match self {
+            Self::Meters => {
+                let (_, meters_per_degree) = self.units_per_coordinate(0.);
+                let y = max_distance / meters_per_degree;
+
+                // the longitudes are closest together at the latitude nearest to a pole
+                let extreme_latitude = f64::max(
+                    (bounds.upper_left().y + y).abs(),
+                    (bounds.lower_right().y - y).abs(),
+                )
+                .min(90.);
+                let (meters_per_degree_x, _) = self.units_per_coordinate(extreme_latitude);
+
+                Coordinate2D::new(max_distance / meters_per_degree_x, y)
+            }
+            Self::CoordinateUnits => Coordinate2D::new(max_distance, max_distance),
+        }
+    }
+
+    /// Enlarges `bounds` by the `margin` and clips it to the valid coordinates
+    fn enlarged_bounds(self, bounds: SpatialPartition2D, margin: Coordinate2D) -> BoundingBox2D {
+        let mut lower_left = bounds.lower_left() - margin;
+        let mut upper_right = bounds.upper_right() + margin;
+
+        if self == Self::Meters {
+            lower_left = Coordinate2D::new(lower_left.x.max(-180.), lower_left.y.max(-90.));
+            upper_right = Coordinate2D::new(upper_right.x.min(180.), upper_right.y.min(90.));
+        }
+
+        BoundingBox2D::new_unchecked(lower_left, upper_right)
+    }
+}
+
+#[typetag::serde]
+#[async_trait]
+impl RasterOperator for Proximity {
+    async fn _initialize(
+        self: Box<Self>,
+        path: WorkflowOperatorPath,
+        context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedRasterOperator>> {
+        let name = CanonicOperatorName::from(&self);
+
+        let max_distance = self.params.max_distance;
+        ensure!(
+            max_distance.is_finite() && max_distance > 0.,
+            error::InvalidMaxDistance
+        );
+
+        let tiling_specification = context.tiling_specification();
+
+        match self.sources.source {
+            RasterOrVectorOperator::Raster(raster) => {
+                let source = SingleRasterSource { raster }
+                    .initialize_sources(path, context)
+                    .await?
+                    .raster;
+
+                let in_desc = source.result_descriptor();
+                let units = DistanceUnits::from_spatial_reference(in_desc.spatial_reference)?;
+
+                let bands = in_desc
+                    .bands
+                    .iter()
+                    .map(|band| {
+                        RasterBandDescriptor::new(band.name.clone(), distance_measurement(units))
+                    })
+                    .collect::<Vec<_>>();
+
+                let result_descriptor = RasterResultDescriptor {
+                    data_type: RasterDataType::F32,
+                    spatial_reference: in_desc.spatial_reference,
+                    time: in_desc.time,
+                    bbox: in_desc.bbox,
+                    resolution: in_desc.resolution,
+                    bands: RasterBandDescriptors::new(bands)?,
+                };
+
+                Ok(InitializedRasterProximity {
+                    name,
+                    result_descriptor,
+                    source,
+                    units,
+                    max_distance,
+                    tiling_specification,
+                }
+                .boxed())
+            }
+            RasterOrVectorOperator::Vector(vector) => {
+                let source = SingleVectorSource { vector }
+                    .initialize_sources(path, context)
+                    .await?
+                    .vector;
+
+                let in_desc = source.result_descriptor();
+                ensure!(
+                    in_desc.data_type != VectorDataType::Data,
+                    error::VectorSourceMustHaveGeometries
+                );
+
+                let units = DistanceUnits::from_spatial_reference(in_desc.spatial_reference)?;
+
+                let result_descriptor = RasterResultDescriptor {
+                    data_type: RasterDataType::F32,
+                    spatial_reference: in_desc.spatial_reference,
+                    time: in_desc.time,
+                    bbox: None,
+                    resolution: None,
+                    bands: RasterBandDescriptors::new(vec![RasterBandDescriptor::new(
+                        "distance".to_string(),
+                        distance_measurement(units),
+                    )])?,
+                };
+
+                Ok(InitializedVectorProximity {
+                    name,
+                    result_descriptor,
+                    source,
+                    units,
+                    max_distance,
+                    tiling_specification,
+                }
+                .boxed())
+            }
+        }
+    }
+
+    span_fn!(Proximity);
+}
+
+fn distance_measurement(units: DistanceUnits) -> Measurement {
+    Measurement::continuous("distance".to_string(), units.unit())
+}
+
+pub struct InitializedRasterProximity {
+    name: CanonicOperatorName,
+    result_descriptor: RasterResultDescriptor,
+    source: Box<dyn InitializedRasterOperator>,
+    units: DistanceUnits,
+    max_distance: f64,
+    tiling_specification: TilingSpecification,
+}
+
+impl InitializedRasterOperator for InitializedRasterProximity {
+    fn query_processor(&self) -> Result<TypedRasterQueryProcessor> {
+        let source_processor = self.source.query_processor()?;
+
+        // distances are computed on `f32` tiles since the sub-query output type equals its input type
+        let source: Box<dyn RasterQueryProcessor<RasterType = f32>> = call_on_generic_raster_processor!(
+            source_processor, p => RasterTypeConversionQueryProcessor::create_boxed(p)
+        );
+
+        Ok(TypedRasterQueryProcessor::F32(
+            RasterProximityProcessor {
+                source,
+                result_descriptor: self.result_descriptor.clone(),
+                units: self.units,
+                max_distance: self.max_distance,
+                tiling_specification: self.tiling_specification,
+            }
+            .boxed(),
+        ))
+    }
+
+    fn result_descriptor(&self) -> &RasterResultDescriptor {
+        &self.result_descriptor
+    }
+
+    fn canonic_name(&self) -> CanonicOperatorName {
+        self.name.clone()
+    }
+}
+
+pub struct RasterProximityProcessor {
+    source: Box<dyn RasterQueryProcessor<RasterType = f32>>,
+    result_descriptor: RasterResultDescriptor,
+    units: DistanceUnits,
+    max_distance: f64,
+    tiling_specification: TilingSpecification,
+}
+
+#[async_trait]
+impl QueryProcessor for RasterProximityProcessor {
+    type Output = RasterTile2D<f32>;
+    type SpatialBounds = SpatialPartition2D;
+    type Selection = BandSelection;
+    type ResultDescription = RasterResultDescriptor;
+
+    async fn _query<'a>(
+        &'a self,
+        query: RasterQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<BoxStream<'a, Result<Self::Output>>> {
+        stack_individual_aligned_raster_bands(&query, ctx, |query, ctx| async move {
+            let sub_query = ProximityTileNeighborhood::new(
+                self.units,
+                self.max_distance,
+                self.tiling_specification,
+            );
+
+            Ok(RasterSubQueryAdapter::<'a, f32, _, _>::new(
+                &self.source,
+                query,
+                self.tiling_specification,
+                ctx,
+                sub_query,
+            )
+            .filter_and_fill(
+                crate::adapters::FillerTileCacheExpirationStrategy::DerivedFromSurroundingTiles,
+            ))
+        })
+        .await
+    }
+
+    fn result_descriptor(&self) -> &RasterResultDescriptor {
+        &self.result_descriptor
+    }
+}
+
+pub struct InitializedVectorProximity {
+    name: CanonicOperatorName,
+    result_descriptor: RasterResultDescriptor,
+    source: Box<dyn InitializedVectorOperator>,
+    units: DistanceUnits,
+    max_distance: f64,
+    tiling_specification: TilingSpecification,
+}
+
+impl InitializedRasterOperator for InitializedVectorProximity {
+    fn query_processor(&self) -> Result<TypedRasterQueryProcessor> {
+        Ok(TypedRasterQueryProcessor::F32(
+            VectorProximityProcessor {
+                source: self.source.query_processor()?,
+                result_descriptor: self.result_descriptor.clone(),
+                units: self.units,
+                max_distance: self.max_distance,
+                tiling_specification: self.tiling_specification,
+            }
+            .boxed(),
+        ))
+    }
+
+    fn result_descriptor(&self) -> &RasterResultDescriptor {
+        &self.result_descriptor
+    }
+
+    fn canonic_name(&self) -> CanonicOperatorName {
+        self.name.clone()
+    }
+}
+
+pub struct VectorProximityProcessor {
+    source: TypedVectorQueryProcessor,
+    result_descriptor: RasterResultDescriptor,
+    units: DistanceUnits,
+    max_distance: f64,
+    tiling_specification: TilingSpecification,
+}
+
+impl VectorProximityProcessor {
+    /// Queries all features within the search margin of the tile and computes the distance for each pixel
+    async fn proximity_tile(
+        &self,
+        tile_info: TileInformation,
+        time_interval: TimeInterval,
+        spatial_resolution: SpatialResolution,
+        ctx: &dyn QueryContext,
+    ) -> Result<RasterTile2D<f32>> {
+        let tile_bounds = tile_info.spatial_partition();
+        let margin = self.units.search_margin(self.max_distance, tile_bounds);
+
+        let vector_query = VectorQueryRectangle {
+            spatial_bounds: self.units.enlarged_bounds(tile_bounds, margin),
+            time_interval,
+            spatial_resolution,
+            attributes: ColumnSelection::all(),
+        };
+
+        let projection = LocalProjection::new(self.units, tile_bounds);
+        let mut targets = DistanceTargets::default();
+
+        let cache_hint = match &self.source {
+            TypedVectorQueryProcessor::MultiPoint(processor) => {
+                collect_targets(
+                    processor.query(vector_query, ctx).await?,
+                    &mut targets,
+                    |targets, collection| {
+                        for multi_point in collection.geometries() {
+                            targets.add_points(multi_point.points(), &projection);
+                        }
+                    },
+                )
+                .await?
+            }
+            TypedVectorQueryProcessor::MultiLineString(processor) => {
+                collect_targets(
+                    processor.query(vector_query, ctx).await?,
+                    &mut targets,
+                    |targets, collection| {
+                        for multi_line_string in collection.geometries() {
+                            for line in multi_line_string.lines() {
+                                targets.add_line(line, &projection);
+                            }
+                        }
+                    },
+                )
+                .await?
+            }
+            TypedVectorQueryProcessor::MultiPolygon(processor) => {
+                collect_targets(
+                    processor.query(vector_query, ctx).await?,
+                    &mut targets,
+                    |targets, collection| {
+                        for multi_polygon in collection.geometries() {
+                            for polygon in multi_polygon.polygons() {
+                                targets.add_polygon(polygon, &projection);
+                            }
+                        }
+                    },
+                )
+                .await?
+            }
+            TypedVectorQueryProcessor::Data(_) => {
+                return Err(ProximityError::VectorSourceMustHaveGeometries.into())
+            }
+        };
+
+        let max_distance = self.max_distance;
+        spawn_blocking_with_thread_pool(ctx.thread_pool().clone(), move || {
+            targets.into_tile(
+                tile_info,
+                time_interval,
+                cache_hint,
+                &projection,
+                max_distance,
+            )
+        })
+        .await?
+    }
+}
+
+#[async_trait]
+impl RasterQueryProcessor for VectorProximityProcessor {
+    type RasterType = f32;
+
+    /// Computes the tiles like the rasterization of vectors.
+    /// For each tile, all features within the search margin are queried.
+    async fn raster_query<'a>(
+        &'a self,
+        query: RasterQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<BoxStream<'a, Result<RasterTile2D<Self::RasterType>>>> {
+        let tiling_strategy = self
+            .tiling_specification
+            .strategy(query.spatial_resolution.x, -query.spatial_resolution.y);
+
+        let time_interval = query.time_interval;
+        let spatial_resolution = query.spatial_resolution;
+
+        let tiles = stream::iter(tiling_strategy.tile_information_iterator(query.spatial_bounds))
+            .then(move |tile_info| {
+                self.proximity_tile(tile_info, time_interval, spatial_resolution, ctx)
+            });
+
+        Ok(tiles.boxed())
+    }
+
+    fn raster_result_descriptor(&self) -> &RasterResultDescriptor {
+        &self.result_descriptor
+    }
+}
+
+async fn collect_targets<G, F>(
+    mut collections: BoxStream<'_, Result<FeatureCollection<G>>>,
+    targets: &mut DistanceTargets,
+    add_collection: F,
+) -> Result<CacheHint>
+where
+    F: Fn(&mut DistanceTargets, &FeatureCollection<G>),
+{
+    let mut cache_hint = CacheHint::max_duration();
+
+    while let Some(collection) = collections.next().await {
+        let collection = collection?;
+        cache_hint.merge_with(&collection.cache_hint);
+        add_collection(targets, &collection);
+    }
+
+    Ok(cache_hint)
+}
+
+/// Maps coordinates into a local cartesian system in distance units around the center of a tile
+#[derive(Debug, Clone, Copy)]
+struct LocalProjection {
+    center: Coordinate2D,
+    units_per_x: f64,
+    units_per_y: f64,
+}
+
+impl LocalProjection {
+    fn new(units: DistanceUnits, bounds: SpatialPartition2D) -> Self {
+        let center = Coordinate2D::new(
+            (bounds.upper_left().x + bounds.lower_right().x) / 2.,
+            (bounds.upper_left().y + bounds.lower_right().y) / 2.,
+        );
+        let (units_per_x, units_per_y) = units.units_per_coordinate(center.y);
+
+        Self {
+            center,
+            units_per_x,
+            units_per_y,
+        }
+    }
+
+    fn project(&self, coordinate: Coordinate2D) -> geo::Coord<f64> {
+        geo::Coord {
+            x: (coordinate.x - self.center.x) * self.units_per_x,
+            y: (coordinate.y - self.center.y) * self.units_per_y,
+        }
+    }
+}
+
+/// The geometries to measure distances to in local coordinates
+#[derive(Debug, Default)]
+struct DistanceTargets {
+    points: Vec<geo::Coord<f64>>,
+    segments: Vec<geo::Line<f64>>,
+    polygons: Vec<geo::Polygon<f64>>,
+}
+
+impl DistanceTargets {
+    fn add_points(&mut self, points: &[Coordinate2D], projection: &LocalProjection) {
+        self.points
+            .extend(points.iter().map(|&point| projection.project(point)));
+    }
+
+    fn add_line(&mut self, line: &[Coordinate2D], projection: &LocalProjection) {
+        if let [point] = line {
+            self.points.push(projection.project(*point));
+        }
+
+        self.segments.extend(line.windows(2).map(|segment| {
+            geo::Line::new(
+                projection.project(segment[0]),
+                projection.project(segment[1]),
+            )
+        }));
+    }
+
+    fn add_polygon<R: AsRef<[Coordinate2D]>>(&mut self, rings: &[R], projection: &LocalProjection) {
+        let mut rings = rings.iter().map(|ring| {
+            geo::LineString::from(
+                ring.as_ref()
+                    .iter()
+                    .map(|&coordinate| projection.project(coordinate))
+                    .collect::<Vec<_>>(),
+            )
+        });
+
+        let Some(exterior) = rings.next() else {
+            return;
+        };
+        let polygon = geo::Polygon::new(exterior, rings.collect());
+
+        // the distance outside of a polygon is the distance to its boundary
+        self.segments.extend(polygon.exterior().lines());
+        for interior in polygon.interiors() {
+            self.segments.extend(interior.lines());
+        }
+
+        self.polygons.push(polygon);
+    }
+
+    fn squared_distance(&self, coordinate: geo::Coord<f64>) -> f64 {
+        if self
+            .polygons
+            .iter()
+            .any(|polygon| polygon.contains(&coordinate))
+        {
+            return 0.;
+        }
+
+        let point_distances = self.points.iter().map(|&point| {
+            let delta = coordinate - point;
+            delta.x * delta.x + delta.y * delta.y
+        });
+
+        let segment_distances = self
+            .segments
+            .iter()
+            .map(|segment| squared_distance_to_segment(coordinate, segment));
+
+        point_distances
+            .chain(segment_distances)
+            .fold(f64::INFINITY, f64::min)
+    }
+
+    fn into_tile(
+        self,
+        tile_info: TileInformation,
+        time_interval: TimeInterval,
+        cache_hint: CacheHint,
+        projection: &LocalProjection,
+        max_distance: f64,
+    ) -> Result<RasterTile2D<f32>> {
+        let tile_shape = tile_info.tile_size_in_pixels;
+        let tile_geo_transform: GeoTransform = tile_info.tile_geo_transform();
+        let width = tile_shape.axis_size_x();
+        let max_squared_distance = max_distance * max_distance;
+
+        let (values, validity): (Vec<f32>, Vec<bool>) = (0..tile_shape.number_of_elements())
+            .into_par_iter()
+            .map(|linear_index| {
+                let pixel_center = tile_geo_transform.grid_idx_to_pixel_center_coordinate_2d(
+                    [
+                        (linear_index / width) as isize,
+                        (linear_index % width) as isize,
+                    ]
+                    .into(),
+                );
+                let squared_distance = self.squared_distance(projection.project(pixel_center));
+
+                if squared_distance <= max_squared_distance {
+                    (squared_distance.sqrt() as f32, true)
+                } else {
+                    (0., false)
+                }
+            })
+            .unzip();
+
+        let grid = if validity.contains(&true) {
+            MaskedGrid2D::new(
+                Grid2D::new(tile_shape, values)?,
+                Grid2D::new(tile_shape, validity)?,
+            )?
+            .into()
+        } else {
+            EmptyGrid2D::new(tile_shape).into()
+        };
+
+        Ok(RasterTile2D::new_with_tile_info(
+            time_interval,
+            tile_info,
+            0,
+            grid,
+            cache_hint,
+        ))
+    }
+}
+
+fn squared_distance_to_segment(coordinate: geo::Coord<f64>, segment: &geo::Line<f64>) -> f64 {
+    let delta = segment.delta();
+    let length_squared = delta.x * delta.x + delta.y * delta.y;
+
+    let t = if length_squared > 0. {
+        let offset = coordinate - segment.start;
+        ((offset.x * delta.x + offset.y * delta.y) / length_squared).clamp(0., 1.)
+    } else {
+        0.
+    };
+
+    let nearest = segment.start + delta * t;
+    let distance = coordinate - nearest;
+    distance.x * distance.x + distance.y * distance.y
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{MockExecutionContext, MockQueryContext};
+    use crate::mock::{MockFeatureCollectionSource, MockRasterSource, MockRasterSourceParams};
+    use futures::TryStreamExt;
+    use geoengine_datatypes::collections::MultiPointCollection;
+    use geoengine_datatypes::primitives::MultiPoint;
+    use geoengine_datatypes::raster::GridOrEmpty;
+    use geoengine_datatypes::spatial_reference::{SpatialReference, SpatialReferenceAuthority};
+    use geoengine_datatypes::util::test::TestDefault;
+
+    fn tile(position: [isize; 2], values: &[Option<u8>]) -> RasterTile2D<u8> {
+        let grid = MaskedGrid2D::new(
+            Grid2D::new(
+                [3, 3].into(),
+                values.iter().map(|v| v.unwrap_or(0)).collect(),
+            )
+            .unwrap(),
+            Grid2D::new([3, 3].into(), values.iter().map(Option::is_some).collect()).unwrap(),
+        )
+        .unwrap();
+
+        RasterTile2D::new_with_tile_info(
+            TimeInterval::default(),
+            TileInformation {
+                global_geo_transform: TestDefault::test_default(),
+                global_tile_position: position.into(),
+                tile_size_in_pixels: [3, 3].into(),
+            },
+            0,
+            grid.into(),
+            CacheHint::default(),
+        )
+    }
+
+    async fn proximity(
+        source: SingleRasterOrVectorSource,
+        max_distance: f64,
+        spatial_bounds: SpatialPartition2D,
+        spatial_resolution: SpatialResolution,
+    ) -> Result<Vec<Vec<Option<f32>>>> {
+        let execution_context = MockExecutionContext::new_with_tiling_spec(TilingSpecification {
+            origin_coordinate: [0.0, 0.0].into(),
+            tile_size_in_pixels: [3, 3].into(),
+        });
+
+        let operator = Proximity {
+            params: ProximityParams { max_distance },
+            sources: source,
+        }
+        .boxed()
+        .initialize(WorkflowOperatorPath::initialize_root(), &execution_context)
+        .await?;
+
+        assert_eq!(operator.result_descriptor().data_type, RasterDataType::F32);
+
+        let processor = operator.query_processor()?.get_f32().unwrap();
+
+        let tiles: Vec<RasterTile2D<f32>> = processor
+            .raster_query(
+                RasterQueryRectangle {
+                    spatial_bounds,
+                    time_interval: TimeInterval::default(),
+                    spatial_resolution,
+                    attributes: BandSelection::first(),
+                },
+                &MockQueryContext::test_default(),
+            )
+            .await?
+            .try_collect()
+            .await?;
+
+        Ok(tiles
+            .into_iter()
+            .map(|tile| match tile.grid_array {
+                GridOrEmpty::Grid(grid) => grid
+                    .masked_element_deref_iterator()
+                    .map(|value| value.map(|value| (value * 1000.).round() / 1000.))
+                    .collect(),
+                GridOrEmpty::Empty(_) => vec![None; 9],
+            })
+            .collect())
+    }
+
+    #[tokio::test]
+    #[allow(clippy::float_cmp)]
+    async fn it_computes_distances_across_tile_borders() {
+        let source = MockRasterSource {
+            params: MockRasterSourceParams {
+                data: vec![
+                    tile(
+                        [0, 0],
+                        &[None, None, None, None, None, Some(1), None, None, None],
+                    ),
+                    tile([0, 1], &[None; 9]),
+                ],
+                result_descriptor: RasterResultDescriptor {
+                    data_type: RasterDataType::U8,
+                    spatial_reference: SpatialReferenceOption::Unreferenced,
+                    time: None,
+                    bbox: None,
+                    resolution: None,
+                    bands: RasterBandDescriptors::new_single_band(),
+                },
+            },
+        }
+        .boxed();
+
+        let tiles = proximity(
+            source.into(),
+            2.5,
+            SpatialPartition2D::new((0., 0.).into(), (6., -3.).into()).unwrap(),
+            SpatialResolution::one(),
+        )
+        .await
+        .unwrap();
+
+        let rounded = |value: f32| Some((value * 1000.).round() / 1000.);
+
+        assert_eq!(
+            tiles,
+            vec![
+                vec![
+                    rounded(5_f32.sqrt()),
+                    rounded(2_f32.sqrt()),
+                    Some(1.),
+                    Some(2.),
+                    Some(1.),
+                    Some(0.),
+                    rounded(5_f32.sqrt()),
+                    rounded(2_f32.sqrt()),
+                    Some(1.),
+                ],
+                vec![
+                    rounded(2_f32.sqrt()),
+                    rounded(5_f32.sqrt()),
+                    None,
+                    Some(1.),
+                    Some(2.),
+                    None,
+                    rounded(2_f32.sqrt()),
+                    rounded(5_f32.sqrt()),
+                    None,
+                ],
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn it_computes_distances_to_features_in_meters() {
+        let points = MultiPointCollection::from_data(
+            MultiPoint::many(vec![(0.5, -0.5)]).unwrap(),
+            vec![TimeInterval::default()],
+            Default::default(),
+            CacheHint::default(),
+        )
+        .unwrap();
+
+        let source = MockFeatureCollectionSource::single(points).boxed();
+
+        let tiles = proximity(
+            source.into(),
+            150_000.,
+            SpatialPartition2D::new((0., 0.).into(), (3., -3.).into()).unwrap(),
+            SpatialResolution::one(),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(tiles.len(), 1);
+
+        let meters_per_degree = EARTH_RADIUS.to_radians();
+        let expected_x = meters_per_degree * 1.5_f64.to_radians().cos();
+
+        let distances = &tiles[0];
+        assert_eq!(distances[0], Some(0.));
+        assert!((f64::from(distances[1].unwrap()) - expected_x).abs() < 1.);
+        assert!(distances[2].is_none());
+        assert!((f64::from(distances[3].unwrap()) - meters_per_degree).abs() < 1.);
+        assert!(distances[4].is_none());
+    }
+
+    #[tokio::test]
+    async fn it_checks_the_max_distance() {
+        let source = MockRasterSource {
+            params: MockRasterSourceParams {
+                data: vec![tile([0, 0], &[None; 9])],
+                result_descriptor: RasterResultDescriptor {
+                    data_type: RasterDataType::U8,
+                    spatial_reference: SpatialReferenceOption::Unreferenced,
+                    time: None,
+                    bbox: None,
+                    resolution: None,
+                    bands: RasterBandDescriptors::new_single_band(),
+                },
+            },
+        }
+        .boxed();
+
+        let bounds = SpatialPartition2D::new((0., 0.).into(), (3., -3.).into()).unwrap();
+
+        assert!(
+            proximity(source.clone().into(), -1., bounds, SpatialResolution::one())
+                .await
+                .is_err()
+        );
+        assert!(
+            proximity(source.into(), 10., bounds, SpatialResolution::one())
+                .await
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn it_measures_geographic_distances_in_meters() {
+        for code in [4326, 4258, 4269] {
+            assert_eq!(
+                DistanceUnits::from_spatial_reference(
+                    SpatialReference::new(SpatialReferenceAuthority::Epsg, code).into()
+                )
+                .unwrap(),
+                DistanceUnits::Meters
+            );
+        }
+
+        assert_eq!(
+            DistanceUnits::from_spatial_reference(
+                SpatialReference::new(SpatialReferenceAuthority::Epsg, 32632).into()
+            )
+            .unwrap(),
+            DistanceUnits::CoordinateUnits
+        );
+        assert_eq!(
+            DistanceUnits::from_spatial_reference(SpatialReferenceOption::Unreferenced).unwrap(),
+            DistanceUnits::CoordinateUnits
+        );
+    }
+}
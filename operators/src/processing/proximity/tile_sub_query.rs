@@ -0,0 +1,348 @@
+use super::distance_transform::{squared_distance_transform, FAR};
+use super::{error, DistanceUnits};
+use crate::adapters::{FoldTileAccu, SubQueryTileAggregator};
+use crate::util::Result;
+use async_trait::async_trait;
+use futures::future::BoxFuture;
+use futures::FutureExt;
+use geoengine_datatypes::primitives::{
+    AxisAlignedRectangle, CacheHint, Coordinate2D, RasterQueryRectangle, SpatialPartition2D,
+    SpatialPartitioned, TimeInstance, TimeInterval,
+};
+use geoengine_datatypes::raster::{
+    Blit, EmptyGrid2D, GeoTransform, Grid2D, GridOrEmpty, GridSize, MaskedGrid2D, RasterTile2D,
+    TileInformation, TilingSpecification,
+};
+use rayon::ThreadPool;
+use snafu::ensure;
+use std::sync::Arc;
+use tokio::task::JoinHandle;
+
+/// A sub-query aggregator that queries for each output tile an input tile that is enlarged by the
+/// search radius of the proximity computation.
+/// The search radius must not exceed the size of a tile, so at most the 8 surrounding tiles are queried.
+#[derive(Debug, Clone)]
+pub struct ProximityTileNeighborhood {
+    units: DistanceUnits,
+    max_distance: f64,
+    tiling_specification: TilingSpecification,
+}
+
+impl ProximityTileNeighborhood {
+    pub fn new(
+        units: DistanceUnits,
+        max_distance: f64,
+        tiling_specification: TilingSpecification,
+    ) -> Self {
+        Self {
+            units,
+            max_distance,
+            tiling_specification,
+        }
+    }
+
+    /// The search radius of a tile in pixels and the pixel size in distance units
+    fn search_radius(&self, tile_info: &TileInformation) -> Result<TileSearchRadius> {
+        search_radius(self.units, self.max_distance, tile_info)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TileSearchRadius {
+    pub x_pixels: usize,
+    pub y_pixels: usize,
+    /// width of a pixel in distance units
+    pub dx: f64,
+    /// height of a pixel in distance units
+    pub dy: f64,
+}
+
+pub fn search_radius(
+    units: DistanceUnits,
+    max_distance: f64,
+    tile_info: &TileInformation,
+) -> Result<TileSearchRadius> {
+    let bounds = tile_info.spatial_partition();
+    let pixel_size_x = tile_info.global_geo_transform.x_pixel_size().abs();
+    let pixel_size_y = tile_info.global_geo_transform.y_pixel_size().abs();
+
+    let margin = units.search_margin(max_distance, bounds);
+
+    let x_pixels = (margin.x / pixel_size_x).ceil();
+    let y_pixels = (margin.y / pixel_size_y).ceil();
+
+    ensure!(
+        x_pixels <= tile_info.tile_size_in_pixels.axis_size_x() as f64
+            && y_pixels <= tile_info.tile_size_in_pixels.axis_size_y() as f64,
+        error::SearchRadiusTooLarge { max_distance }
+    );
+
+    let (units_per_x, units_per_y) =
+        units.units_per_coordinate((bounds.upper_left().y + bounds.lower_right().y) / 2.);
+
+    Ok(TileSearchRadius {
+        x_pixels: x_pixels as usize,
+        y_pixels: y_pixels as usize,
+        dx: units_per_x * pixel_size_x,
+        dy: units_per_y * pixel_size_y,
+    })
+}
+
+impl<'a> SubQueryTileAggregator<'a, f32> for ProximityTileNeighborhood {
+    type FoldFuture = FoldFuture;
+
+    type FoldMethod = fn(ProximityAccu, RasterTile2D<f32>) -> Self::FoldFuture;
+
+    type TileAccu = ProximityAccu;
+    type TileAccuFuture = BoxFuture<'a, Result<Self::TileAccu>>;
+
+    /// Create an enlarged tile to store the values within the search radius
+    fn new_fold_accu(
+        &self,
+        tile_info: TileInformation,
+        query_rect: RasterQueryRectangle,
+        pool: &Arc<ThreadPool>,
+    ) -> Self::TileAccuFuture {
+        let pool = pool.clone();
+        let tiling_specification = self.tiling_specification;
+        let max_distance = self.max_distance;
+        let search_radius = self.search_radius(&tile_info);
+        crate::util::spawn_blocking(move || -> Result<ProximityAccu> {
+            Ok(create_enlarged_tile(
+                tile_info,
+                &query_rect,
+                pool,
+                tiling_specification,
+                search_radius?,
+                max_distance,
+            ))
+        })
+        .map(flatten_result)
+        .boxed()
+    }
+
+    /// Enlarge the spatial bounds to all sides by the search radius
+    fn tile_query_rectangle(
+        &self,
+        tile_info: TileInformation,
+        query_rect: RasterQueryRectangle,
+        start_time: TimeInstance,
+        band_idx: u32,
+    ) -> Result<Option<RasterQueryRectangle>> {
+        let spatial_bounds = tile_info.spatial_partition();
+        let search_radius = self.search_radius(&tile_info)?;
+
+        let margin_pixels = Coordinate2D::from((
+            search_radius.x_pixels as f64 * tile_info.global_geo_transform.x_pixel_size(),
+            search_radius.y_pixels as f64 * tile_info.global_geo_transform.y_pixel_size(),
+        ));
+
+        let enlarged_spatial_bounds = SpatialPartition2D::new(
+            spatial_bounds.upper_left() - margin_pixels,
+            spatial_bounds.lower_right() + margin_pixels,
+        )?;
+
+        Ok(Some(RasterQueryRectangle {
+            spatial_bounds: enlarged_spatial_bounds,
+            time_interval: TimeInterval::new_instant(start_time)?,
+            spatial_resolution: query_rect.spatial_resolution,
+            attributes: band_idx.into(),
+        }))
+    }
+
+    fn fold_method(&self) -> Self::FoldMethod {
+        |accu, tile| {
+            crate::util::spawn_blocking(|| merge_tile_into_enlarged_tile(accu, tile))
+                .map(flatten_result)
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ProximityAccu {
+    pub output_info: TileInformation,
+    pub input_tile: RasterTile2D<f32>,
+    pub pool: Arc<ThreadPool>,
+    pub search_radius: TileSearchRadius,
+    pub max_distance: f64,
+}
+
+#[async_trait]
+impl FoldTileAccu for ProximityAccu {
+    type RasterType = f32;
+
+    /// now that we collected all the input tile pixels we compute the distances
+    async fn into_tile(self) -> Result<RasterTile2D<Self::RasterType>> {
+        crate::util::spawn_blocking_with_thread_pool(self.pool, move || {
+            compute_proximity_tile(
+                self.input_tile,
+                &self.output_info,
+                self.search_radius,
+                self.max_distance,
+            )
+        })
+        .await?
+    }
+
+    fn thread_pool(&self) -> &Arc<ThreadPool> {
+        &self.pool
+    }
+}
+
+/// Computes the distance to the nearest valid pixel for all pixels of the inner tile
+fn compute_proximity_tile(
+    input: RasterTile2D<f32>,
+    info_out: &TileInformation,
+    search_radius: TileSearchRadius,
+    max_distance: f64,
+) -> Result<RasterTile2D<f32>> {
+    let empty_tile = |input: &RasterTile2D<f32>| {
+        RasterTile2D::new_with_tile_info(
+            input.time,
+            *info_out,
+            input.band,
+            EmptyGrid2D::new(info_out.tile_size_in_pixels).into(),
+            input.cache_hint.clone_with_current_datetime(),
+        )
+    };
+
+    let GridOrEmpty::Grid(input_grid) = &input.grid_array else {
+        return Ok(empty_tile(&input));
+    };
+
+    let width = input_grid.axis_size_x();
+    let height = input_grid.axis_size_y();
+
+    let mut distances: Vec<f64> = input_grid
+        .masked_element_deref_iterator()
+        .map(|value| if value.is_some() { 0. } else { FAR })
+        .collect();
+
+    squared_distance_transform(
+        &mut distances,
+        width,
+        height,
+        search_radius.dx,
+        search_radius.dy,
+    );
+
+    let out_width = info_out.tile_size_in_pixels.axis_size_x();
+    let out_height = info_out.tile_size_in_pixels.axis_size_y();
+    let max_squared_distance = max_distance * max_distance;
+
+    let mut out_values = Vec::with_capacity(out_width * out_height);
+    let mut out_validity = Vec::with_capacity(out_width * out_height);
+    for y in 0..out_height {
+        let row_start = (y + search_radius.y_pixels) * width + search_radius.x_pixels;
+        for &squared_distance in &distances[row_start..row_start + out_width] {
+            let is_valid = squared_distance <= max_squared_distance;
+            out_values.push(if is_valid {
+                squared_distance.sqrt() as f32
+            } else {
+                0.
+            });
+            out_validity.push(is_valid);
+        }
+    }
+
+    if !out_validity.contains(&true) {
+        return Ok(empty_tile(&input));
+    }
+
+    let out_grid = MaskedGrid2D::new(
+        Grid2D::new(info_out.tile_size_in_pixels, out_values)?,
+        Grid2D::new(info_out.tile_size_in_pixels, out_validity)?,
+    )?;
+
+    Ok(RasterTile2D::new(
+        input.time,
+        info_out.global_tile_position,
+        input.band,
+        info_out.global_geo_transform,
+        out_grid.into(),
+        input.cache_hint.clone_with_current_datetime(),
+    ))
+}
+
+fn create_enlarged_tile(
+    tile_info: TileInformation,
+    query_rect: &RasterQueryRectangle,
+    pool: Arc<ThreadPool>,
+    tiling_specification: TilingSpecification,
+    search_radius: TileSearchRadius,
+    max_distance: f64,
+) -> ProximityAccu {
+    // create an accumulator as a single tile that fits all the input tiles + the search radius
+    let tiling = tiling_specification.strategy(
+        query_rect.spatial_resolution.x,
+        -query_rect.spatial_resolution.y,
+    );
+
+    let geo_transform = GeoTransform::new(
+        query_rect.spatial_bounds.upper_left(),
+        query_rect.spatial_resolution.x,
+        -query_rect.spatial_resolution.y,
+    );
+
+    let shape = [
+        tiling.tile_size_in_pixels.axis_size_y() + 2 * search_radius.y_pixels,
+        tiling.tile_size_in_pixels.axis_size_x() + 2 * search_radius.x_pixels,
+    ];
+
+    // create a non-aligned (w.r.t. the tiling specification) grid by setting the origin to the top-left of the tile and the tile-index to [0, 0]
+    let input_tile = RasterTile2D::new(
+        query_rect.time_interval,
+        [0, 0].into(),
+        0,
+        geo_transform,
+        GridOrEmpty::from(EmptyGrid2D::new(shape.into())),
+        CacheHint::max_duration(),
+    );
+
+    ProximityAccu {
+        output_info: tile_info,
+        input_tile,
+        pool,
+        search_radius,
+        max_distance,
+    }
+}
+
+type FoldFutureFn =
+    fn(Result<Result<ProximityAccu>, tokio::task::JoinError>) -> Result<ProximityAccu>;
+type FoldFuture = futures::future::Map<JoinHandle<Result<ProximityAccu>>, FoldFutureFn>;
+
+/// Turn a result of results into a result
+fn flatten_result<T, E>(result: Result<Result<T>, E>) -> Result<T>
+where
+    crate::error::Error: From<E>,
+{
+    match result {
+        Ok(r) => r,
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Merge, step by step, the input tiles into the larger accumulator tile
+fn merge_tile_into_enlarged_tile(
+    mut accu: ProximityAccu,
+    tile: RasterTile2D<f32>,
+) -> Result<ProximityAccu> {
+    // get the time and band now because they are not known when the accu was created
+    accu.input_tile.time = tile.time;
+    accu.input_tile.band = tile.band;
+
+    // the tiles only contribute to the cache hint if they are not empty
+    if tile.is_empty() {
+        return Ok(accu);
+    }
+    accu.input_tile.cache_hint.merge_with(&tile.cache_hint);
+
+    let mut accu_input_tile = accu.input_tile.into_materialized_tile();
+    accu_input_tile.blit(tile)?;
+
+    Ok(ProximityAccu {
+        input_tile: accu_input_tile.into(),
+        ..accu
+    })
+}
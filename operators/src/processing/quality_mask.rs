@@ -0,0 +1,544 @@
+use crate::engine::{
+    CanonicOperatorName, ExecutionContext, InitializedRasterOperator, InitializedSources, Operator,
+    OperatorData, OperatorName, QueryContext, QueryProcessor, RasterOperator, RasterQueryProcessor,
+    RasterResultDescriptor, TypedRasterQueryProcessor, WorkflowOperatorPath,
+};
+use crate::processing::RasterTypeConversionQueryProcessor;
+use crate::util::{spawn_blocking_with_thread_pool, Result};
+use async_trait::async_trait;
+use futures::stream::BoxStream;
+use futures::{StreamExt, TryStreamExt};
+use geoengine_datatypes::dataset::NamedData;
+use geoengine_datatypes::primitives::{
+    BandSelection, RasterQueryRectangle, SpatialPartition2D, TimeInterval,
+};
+use geoengine_datatypes::raster::{GridIdx, GridOrEmpty, GridSize, Pixel, RasterTile2D};
+use serde::{Deserialize, Serialize};
+use snafu::{ensure, Snafu};
+
+/// The `QualityMask` operator masks the pixels of a raster according to a quality raster.
+///
+/// The quality raster is either a classification, e.g., the scene classification (SCL) of
+/// Sentinel-2 L2A, or a bitmask, e.g., the `QA_PIXEL` band of Landsat Collection 2.
+/// Pixels whose quality is flagged by the `rule` become no-data.
+/// The flagged area can be enlarged by a `dilation` radius in pixels, e.g., to remove cloud edges.
+/// Pixels without quality information are masked as well, but are not dilated.
+///
+/// The quality raster must have a single band and is queried at the resolution of the raster.
+pub type QualityMask = Operator<QualityMaskParams, QualityMaskSources>;
+
+impl OperatorName for QualityMask {
+    const TYPE_NAME: &'static str = "QualityMask";
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QualityMaskParams {
+    pub rule: QualityMaskRule,
+    /// The radius in pixels by which the flagged area is enlarged
+    #[serde(default)]
+    pub dilation: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub enum QualityMaskRule {
+    /// Flags pixels whose quality value is one of the `classes`.
+    /// For the Sentinel-2 SCL, `[3, 8, 9, 10]` flags cloud shadows, clouds and cirrus.
+    Classes { classes: Vec<u32> },
+    /// Flags pixels where any of the `bits` (starting at `0`) is set.
+    /// For the Landsat `QA_PIXEL`, `[1, 2, 3, 4]` flags dilated clouds, cirrus, clouds and cloud shadows.
+    BitFlags { bits: Vec<u8> },
+}
+
+impl QualityMaskRule {
+    fn is_flagged(&self, quality: u32) -> bool {
+        match self {
+            Self::Classes { classes } => classes.contains(&quality),
+            Self::BitFlags { bits } => bits.iter().any(|&bit| quality & (1 << bit) != 0),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QualityMaskSources {
+    pub raster: Box<dyn RasterOperator>,
+    pub quality: Box<dyn RasterOperator>,
+}
+
+impl OperatorData for QualityMaskSources {
+    fn data_names_collect(&self, data_names: &mut Vec<NamedData>) {
+        self.raster.data_names_collect(data_names);
+        self.quality.data_names_collect(data_names);
+    }
+}
+
+struct InitializedQualityMaskSources {
+    raster: Box<dyn InitializedRasterOperator>,
+    quality: Box<dyn InitializedRasterOperator>,
+}
+
+#[async_trait]
+impl InitializedSources<InitializedQualityMaskSources> for QualityMaskSources {
+    async fn initialize_sources(
+        self,
+        path: WorkflowOperatorPath,
+        context: &dyn ExecutionContext,
+    ) -> Result<InitializedQualityMaskSources> {
+        Ok(InitializedQualityMaskSources {
+            raster: self
+                .raster
+                .initialize(path.clone_and_append(0), context)
+                .await?,
+            quality: self
+                .quality
+                .initialize(path.clone_and_append(1), context)
+                .await?,
+        })
+    }
+}
+
+#[derive(Debug, Snafu)]
+#[snafu(visibility(pub(crate)), context(suffix(false)), module(error))]
+pub enum QualityMaskError {
+    #[snafu(display("The quality raster must have a single band"))]
+    QualityMustHaveSingleBand,
+
+    #[snafu(display("The raster and the quality raster must have the same spatial reference"))]
+    SpatialReferenceMismatch,
+
+    #[snafu(display("The bit {bit} is out of range, the maximum bit is 31"))]
+    InvalidBit { bit: u8 },
+
+    #[snafu(display("The dilation must not exceed the tile size of {max} pixels"))]
+    DilationTooLarge { max: usize },
+}
+
+#[typetag::serde]
+#[async_trait]
+impl RasterOperator for QualityMask {
+    async fn _initialize(
+        self: Box<Self>,
+        path: WorkflowOperatorPath,
+        context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedRasterOperator>> {
+        let name = CanonicOperatorName::from(&self);
+
+        if let QualityMaskRule::BitFlags { bits } = &self.params.rule {
+            if let Some(&bit) = bits.iter().find(|&&bit| bit >= 32) {
+                return Err(QualityMaskError::InvalidBit { bit }.into());
+            }
+        }
+
+        let tile_size = context.tiling_specification().tile_size_in_pixels;
+        let max_dilation = tile_size.axis_size_x().min(tile_size.axis_size_y());
+        ensure!(
+            self.params.dilation as usize <= max_dilation,
+            error::DilationTooLarge { max: max_dilation }
+        );
+
+        let sources = self.sources.initialize_sources(path, context).await?;
+
+        let raster_desc = sources.raster.result_descriptor();
+        let quality_desc = sources.quality.result_descriptor();
+
+        ensure!(
+            quality_desc.bands.len() == 1,
+            error::QualityMustHaveSingleBand
+        );
+        ensure!(
+            raster_desc.spatial_reference == quality_desc.spatial_reference,
+            error::SpatialReferenceMismatch
+        );
+
+        Ok(InitializedQualityMask {
+            name,
+            result_descriptor: raster_desc.clone(),
+            raster: sources.raster,
+            quality: sources.quality,
+            rule: self.params.rule,
+            dilation: self.params.dilation as usize,
+        }
+        .boxed())
+    }
+
+    span_fn!(QualityMask);
+}
+
+pub struct InitializedQualityMask {
+    name: CanonicOperatorName,
+    result_descriptor: RasterResultDescriptor,
+    raster: Box<dyn InitializedRasterOperator>,
+    quality: Box<dyn InitializedRasterOperator>,
+    rule: QualityMaskRule,
+    dilation: usize,
+}
+
+impl InitializedRasterOperator for InitializedQualityMask {
+    fn query_processor(&self) -> Result<TypedRasterQueryProcessor> {
+        let raster = self.raster.query_processor()?;
+
+        let quality: Box<dyn RasterQueryProcessor<RasterType = u32>> = call_on_generic_raster_processor!(
+            self.quality.query_processor()?, p => RasterTypeConversionQueryProcessor::create_boxed(p)
+        );
+
+        Ok(
+            call_on_generic_raster_processor!(raster, p => QualityMaskProcessor {
+                    raster: p,
+                    quality,
+                    result_descriptor: self.result_descriptor.clone(),
+                    rule: self.rule.clone(),
+                    dilation: self.dilation,
+                }
+                .boxed()
+                .into()
+            ),
+        )
+    }
+
+    fn result_descriptor(&self) -> &RasterResultDescriptor {
+        &self.result_descriptor
+    }
+
+    fn canonic_name(&self) -> CanonicOperatorName {
+        self.name.clone()
+    }
+}
+
+struct QualityMaskProcessor<T> {
+    raster: Box<dyn RasterQueryProcessor<RasterType = T>>,
+    quality: Box<dyn RasterQueryProcessor<RasterType = u32>>,
+    result_descriptor: RasterResultDescriptor,
+    rule: QualityMaskRule,
+    dilation: usize,
+}
+
+impl<T: Pixel> QualityMaskProcessor<T> {
+    /// Queries the quality of the tile, enlarged by the dilation, and masks the flagged pixels
+    async fn mask_tile(
+        &self,
+        tile: RasterTile2D<T>,
+        ctx: &dyn QueryContext,
+    ) -> Result<RasterTile2D<T>> {
+        if tile.is_empty() {
+            return Ok(tile);
+        }
+
+        let GridIdx([tile_y, tile_x]) = tile.tile_information().global_upper_left_pixel_idx();
+        let [tile_height, tile_width] = tile.grid_shape_array();
+        let dilation = self.dilation as isize;
+
+        let region = QualityRegion {
+            upper_left: [tile_y - dilation, tile_x - dilation],
+            height: tile_height + 2 * self.dilation,
+            width: tile_width + 2 * self.dilation,
+        };
+
+        let geo_transform = tile.global_geo_transform;
+        let [y, x] = region.upper_left;
+        let quality_query = RasterQueryRectangle {
+            spatial_bounds: SpatialPartition2D::new(
+                geo_transform.grid_idx_to_pixel_upper_left_coordinate_2d([y, x].into()),
+                geo_transform.grid_idx_to_pixel_upper_left_coordinate_2d(
+                    [y + region.height as isize, x + region.width as isize].into(),
+                ),
+            )?,
+            time_interval: TimeInterval::new_instant(tile.time.start())?,
+            spatial_resolution: geo_transform.spatial_resolution(),
+            attributes: BandSelection::first(),
+        };
+
+        let quality_tiles: Vec<RasterTile2D<u32>> = self
+            .quality
+            .raster_query(quality_query, ctx)
+            .await?
+            .try_collect()
+            .await?;
+
+        let rule = self.rule.clone();
+        let dilation = self.dilation;
+        spawn_blocking_with_thread_pool(ctx.thread_pool().clone(), move || {
+            let flags = region.flags(&quality_tiles, &rule);
+            apply_mask(tile, &flags, region.width, dilation)
+        })
+        .await
+        .map_err(Into::into)
+    }
+}
+
+/// The pixels of the quality raster around a tile
+#[derive(Debug, Clone, Copy)]
+struct QualityRegion {
+    upper_left: [isize; 2],
+    height: usize,
+    width: usize,
+}
+
+impl QualityRegion {
+    /// For each pixel of the region, whether it is flagged or `None` if there is no quality information
+    fn flags(
+        &self,
+        quality_tiles: &[RasterTile2D<u32>],
+        rule: &QualityMaskRule,
+    ) -> Vec<Option<bool>> {
+        let mut flags = vec![None; self.width * self.height];
+
+        for tile in quality_tiles {
+            let GridOrEmpty::Grid(grid) = &tile.grid_array else {
+                continue;
+            };
+
+            let GridIdx([tile_y, tile_x]) = tile.tile_information().global_upper_left_pixel_idx();
+            let [_, tile_width] = tile.grid_shape_array();
+
+            for (index, quality) in grid.masked_element_deref_iterator().enumerate() {
+                let y = tile_y + (index / tile_width) as isize - self.upper_left[0];
+                let x = tile_x + (index % tile_width) as isize - self.upper_left[1];
+
+                if y < 0 || x < 0 || y >= self.height as isize || x >= self.width as isize {
+                    continue;
+                }
+
+                flags[y as usize * self.width + x as usize] =
+                    quality.map(|quality| rule.is_flagged(quality));
+            }
+        }
+
+        flags
+    }
+}
+
+/// Masks the pixels of the tile that have no quality information or a flagged pixel within
+/// the dilation radius.
+/// The `flags` cover the tile plus the dilation on each side.
+fn apply_mask<T: Pixel>(
+    mut tile: RasterTile2D<T>,
+    flags: &[Option<bool>],
+    flags_width: usize,
+    dilation: usize,
+) -> RasterTile2D<T> {
+    let [_, tile_width] = tile.grid_shape_array();
+    let radius = dilation as isize;
+
+    let is_masked = |index: usize| {
+        let y = index / tile_width + dilation;
+        let x = index % tile_width + dilation;
+
+        if flags[y * flags_width + x].is_none() {
+            return true;
+        }
+
+        (-radius..=radius).any(|dy| {
+            (-radius..=radius).any(|dx| {
+                dy * dy + dx * dx <= radius * radius
+                    && flags[(y as isize + dy) as usize * flags_width + (x as isize + dx) as usize]
+                        == Some(true)
+            })
+        })
+    };
+
+    if let GridOrEmpty::Grid(grid) = &mut tile.grid_array {
+        for (index, is_valid) in grid.mask_mut().data.iter_mut().enumerate() {
+            if *is_valid && is_masked(index) {
+                *is_valid = false;
+            }
+        }
+    }
+
+    tile
+}
+
+#[async_trait]
+impl<T: Pixel> QueryProcessor for QualityMaskProcessor<T> {
+    type Output = RasterTile2D<T>;
+    type SpatialBounds = SpatialPartition2D;
+    type Selection = BandSelection;
+    type ResultDescription = RasterResultDescriptor;
+
+    async fn _query<'a>(
+        &'a self,
+        query: RasterQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<BoxStream<'a, Result<Self::Output>>> {
+        let tiles = self.raster.raster_query(query, ctx).await?;
+
+        Ok(tiles
+            .and_then(move |tile| self.mask_tile(tile, ctx))
+            .boxed())
+    }
+
+    fn result_descriptor(&self) -> &RasterResultDescriptor {
+        &self.result_descriptor
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{MockExecutionContext, MockQueryContext, RasterBandDescriptors};
+    use crate::mock::{MockRasterSource, MockRasterSourceParams};
+    use geoengine_datatypes::primitives::{CacheHint, SpatialResolution};
+    use geoengine_datatypes::raster::{
+        Grid2D, RasterDataType, TileInformation, TilingSpecification,
+    };
+    use geoengine_datatypes::spatial_reference::SpatialReference;
+    use geoengine_datatypes::util::test::TestDefault;
+
+    fn source(tiles: Vec<Vec<u8>>) -> Box<dyn RasterOperator> {
+        MockRasterSource {
+            params: MockRasterSourceParams {
+                data: tiles
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, values)| {
+                        RasterTile2D::new_with_tile_info(
+                            TimeInterval::default(),
+                            TileInformation {
+                                global_geo_transform: TestDefault::test_default(),
+                                global_tile_position: [0, i as isize].into(),
+                                tile_size_in_pixels: [2, 2].into(),
+                            },
+                            0,
+                            Grid2D::new([2, 2].into(), values).unwrap().into(),
+                            CacheHint::default(),
+                        )
+                    })
+                    .collect(),
+                result_descriptor: RasterResultDescriptor {
+                    data_type: RasterDataType::U8,
+                    spatial_reference: SpatialReference::epsg_4326().into(),
+                    time: None,
+                    bbox: None,
+                    resolution: None,
+                    bands: RasterBandDescriptors::new_single_band(),
+                },
+            },
+        }
+        .boxed()
+    }
+
+    async fn mask(
+        params: QualityMaskParams,
+        quality: Vec<Vec<u8>>,
+    ) -> Result<Vec<Vec<Option<u8>>>> {
+        let execution_context = MockExecutionContext::new_with_tiling_spec(TilingSpecification {
+            origin_coordinate: [0.0, 0.0].into(),
+            tile_size_in_pixels: [2, 2].into(),
+        });
+
+        let operator = QualityMask {
+            params,
+            sources: QualityMaskSources {
+                raster: source(vec![vec![1, 2, 3, 4], vec![5, 6, 7, 8]]),
+                quality: source(quality),
+            },
+        }
+        .boxed()
+        .initialize(WorkflowOperatorPath::initialize_root(), &execution_context)
+        .await?;
+
+        let processor = operator.query_processor()?.get_u8().unwrap();
+
+        let tiles: Vec<RasterTile2D<u8>> = processor
+            .raster_query(
+                RasterQueryRectangle {
+                    spatial_bounds: SpatialPartition2D::new((0., 0.).into(), (4., -2.).into())
+                        .unwrap(),
+                    time_interval: TimeInterval::default(),
+                    spatial_resolution: SpatialResolution::one(),
+                    attributes: BandSelection::first(),
+                },
+                &MockQueryContext::test_default(),
+            )
+            .await?
+            .try_collect()
+            .await?;
+
+        Ok(tiles
+            .iter()
+            .map(|tile| match &tile.grid_array {
+                GridOrEmpty::Grid(grid) => grid.masked_element_deref_iterator().collect(),
+                GridOrEmpty::Empty(_) => vec![None; 4],
+            })
+            .collect())
+    }
+
+    #[tokio::test]
+    async fn it_masks_classes() {
+        let masked = mask(
+            QualityMaskParams {
+                rule: QualityMaskRule::Classes {
+                    classes: vec![3, 8, 9, 10],
+                },
+                dilation: 0,
+            },
+            vec![vec![4, 8, 5, 4], vec![3, 4, 10, 11]],
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            masked,
+            vec![
+                vec![Some(1), None, Some(3), Some(4)],
+                vec![None, Some(6), None, Some(8)],
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn it_masks_bit_flags() {
+        let masked = mask(
+            QualityMaskParams {
+                rule: QualityMaskRule::BitFlags { bits: vec![1, 3] },
+                dilation: 0,
+            },
+            vec![vec![0b0001, 0b0010, 0b1000, 0b0101], vec![0, 1, 4, 0b1010]],
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            masked,
+            vec![
+                vec![Some(1), None, None, Some(4)],
+                vec![Some(5), Some(6), Some(7), None],
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn it_dilates_across_tiles() {
+        let masked = mask(
+            QualityMaskParams {
+                rule: QualityMaskRule::Classes { classes: vec![9] },
+                dilation: 1,
+            },
+            vec![vec![4, 4, 4, 4], vec![9, 4, 4, 4]],
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            masked,
+            vec![
+                vec![Some(1), None, Some(3), Some(4)],
+                vec![None, None, None, Some(8)],
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn it_checks_the_bits() {
+        assert!(mask(
+            QualityMaskParams {
+                rule: QualityMaskRule::BitFlags { bits: vec![32] },
+                dilation: 0,
+            },
+            vec![vec![0; 4], vec![0; 4]],
+        )
+        .await
+        .is_err());
+    }
+}
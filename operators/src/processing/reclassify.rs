@@ -0,0 +1,793 @@
+use crate::engine::{
+    CanonicOperatorName, ExecutionContext, InitializedRasterOperator, InitializedSources,
+    InitializedVectorOperator, Operator, OperatorData, OperatorName, QueryContext, QueryProcessor,
+    RasterBandDescriptor, RasterBandDescriptors, RasterOperator, RasterQueryProcessor,
+    RasterResultDescriptor, TypedRasterQueryProcessor, VectorOperator, VectorQueryProcessor,
+    WorkflowOperatorPath,
+};
+use crate::util::Result;
+use async_trait::async_trait;
+use futures::stream::BoxStream;
+use futures::{StreamExt, TryFutureExt, TryStreamExt};
+use geoengine_datatypes::collections::{DataCollection, FeatureCollectionInfos, VectorDataType};
+use geoengine_datatypes::dataset::NamedData;
+use geoengine_datatypes::primitives::{
+    BandSelection, BoundingBox2D, ColumnSelection, FeatureDataType, Measurement,
+    RasterQueryRectangle, SpatialPartition2D, VectorQueryRectangle,
+};
+use geoengine_datatypes::raster::{MapElementsParallel, Pixel, RasterDataType, RasterTile2D};
+use num_traits::AsPrimitive;
+use serde::{Deserialize, Serialize};
+use snafu::{ensure, Snafu};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// The `Reclassify` operator maps the values of a single-band raster to classes.
+///
+/// The classes are defined by a table of rules that either match a single value or a
+/// half-open value range `[min, max)`. The first matching rule wins.
+/// Pixels that match no rule get the `default_class` and no-data pixels get the `no_data_class`.
+/// If these classes are not set, the pixels become no-data.
+///
+/// The table is either part of the parameters or read from a `DataCollection` source.
+/// The output raster is of type `U8`.
+pub type Reclassify = Operator<ReclassifyParams, ReclassifySources>;
+
+impl OperatorName for Reclassify {
+    const TYPE_NAME: &'static str = "Reclassify";
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReclassifyParams {
+    pub table: ReclassifyTable,
+    #[serde(default)]
+    pub default_class: Option<u8>,
+    #[serde(default)]
+    pub no_data_class: Option<u8>,
+    /// If set, the output band is a `Measurement::Classification` with this measurement name.
+    #[serde(default)]
+    pub measurement: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub enum ReclassifyTable {
+    /// Rules that are part of the workflow
+    Rules { rules: Vec<ReclassifyRule> },
+    /// Rules that are read from the `table` source, one rule per feature.
+    ///
+    /// Either the `value_column` or at least one of the `min_column` and `max_column` must be set.
+    /// Null bounds are treated as open.
+    /// Since the table is only read at query time, the class names are given by `classes`.
+    Collection {
+        #[serde(default)]
+        value_column: Option<String>,
+        #[serde(default)]
+        min_column: Option<String>,
+        #[serde(default)]
+        max_column: Option<String>,
+        class_column: String,
+        #[serde(default)]
+        classes: HashMap<u8, String>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReclassifyRule {
+    #[serde(flatten)]
+    pub input: ReclassifyInput,
+    pub class: u8,
+    #[serde(default)]
+    pub name: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub enum ReclassifyInput {
+    Value {
+        value: f64,
+    },
+    /// The half-open range `[min, max)`, missing bounds are open
+    Range {
+        #[serde(default)]
+        min: Option<f64>,
+        #[serde(default)]
+        max: Option<f64>,
+    },
+}
+
+impl ReclassifyInput {
+    #[allow(clippy::float_cmp)] // values are matched exactly, e.g., for integer rasters
+    fn matches(&self, value: f64) -> bool {
+        match *self {
+            Self::Value { value: v } => value == v,
+            Self::Range { min, max } => {
+                min.map_or(true, |min| min <= value) && max.map_or(true, |max| value < max)
+            }
+        }
+    }
+
+    fn is_valid(&self) -> bool {
+        match *self {
+            Self::Value { value } => !value.is_nan(),
+            Self::Range { min, max } => {
+                !min.map_or(false, f64::is_nan)
+                    && !max.map_or(false, f64::is_nan)
+                    && min.zip(max).map_or(true, |(min, max)| min < max)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReclassifySources {
+    pub raster: Box<dyn RasterOperator>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub table: Option<Box<dyn VectorOperator>>,
+}
+
+impl OperatorData for ReclassifySources {
+    fn data_names_collect(&self, data_names: &mut Vec<NamedData>) {
+        self.raster.data_names_collect(data_names);
+        if let Some(table) = &self.table {
+            table.data_names_collect(data_names);
+        }
+    }
+}
+
+struct InitializedReclassifySources {
+    raster: Box<dyn InitializedRasterOperator>,
+    table: Option<Box<dyn InitializedVectorOperator>>,
+}
+
+#[async_trait]
+impl InitializedSources<InitializedReclassifySources> for ReclassifySources {
+    async fn initialize_sources(
+        self,
+        path: WorkflowOperatorPath,
+        context: &dyn ExecutionContext,
+    ) -> Result<InitializedReclassifySources> {
+        let raster = self
+            .raster
+            .initialize(path.clone_and_append(0), context)
+            .await?;
+
+        let table = match self.table {
+            Some(table) => Some(table.initialize(path.clone_and_append(1), context).await?),
+            None => None,
+        };
+
+        Ok(InitializedReclassifySources { raster, table })
+    }
+}
+
+/// The columns of a `DataCollection` table
+#[derive(Debug, Clone)]
+struct TableColumns {
+    value: Option<String>,
+    min: Option<String>,
+    max: Option<String>,
+    class: String,
+}
+
+impl TableColumns {
+    fn names(&self) -> impl Iterator<Item = &String> {
+        self.value
+            .iter()
+            .chain(&self.min)
+            .chain(&self.max)
+            .chain(std::iter::once(&self.class))
+    }
+}
+
+#[typetag::serde]
+#[async_trait]
+impl RasterOperator for Reclassify {
+    async fn _initialize(
+        self: Box<Self>,
+        path: WorkflowOperatorPath,
+        context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedRasterOperator>> {
+        let name = CanonicOperatorName::from(&self);
+
+        let sources = self.sources.initialize_sources(path, context).await?;
+
+        let in_desc = sources.raster.result_descriptor();
+
+        ensure!(in_desc.bands.count() == 1, error::RasterMustHaveSingleBand);
+
+        let (table, classes) = match (self.params.table, sources.table) {
+            (ReclassifyTable::Rules { rules }, None) => {
+                ensure!(
+                    rules.iter().all(|rule| rule.input.is_valid()),
+                    error::InvalidRule
+                );
+
+                let classes = rules
+                    .iter()
+                    .filter_map(|rule| rule.name.clone().map(|name| (rule.class, name)))
+                    .collect();
+                let rules = rules.into_iter().map(|rule| (rule.input, rule.class));
+
+                (InitializedTable::Rules(Arc::new(rules.collect())), classes)
+            }
+            (
+                ReclassifyTable::Collection {
+                    value_column,
+                    min_column,
+                    max_column,
+                    class_column,
+                    classes,
+                },
+                Some(table),
+            ) => {
+                ensure!(
+                    value_column.is_some() != (min_column.is_some() || max_column.is_some()),
+                    error::InvalidTableColumns
+                );
+
+                let table_desc = table.result_descriptor();
+                ensure!(
+                    table_desc.data_type == VectorDataType::Data,
+                    error::TableMustBeDataCollection {
+                        found: table_desc.data_type.to_string(),
+                    }
+                );
+
+                let columns = TableColumns {
+                    value: value_column,
+                    min: min_column,
+                    max: max_column,
+                    class: class_column,
+                };
+
+                for column in columns.names() {
+                    let Some(info) = table_desc.columns.get(column) else {
+                        return Err(ReclassifyError::ColumnDoesNotExist {
+                            column: column.clone(),
+                        }
+                        .into());
+                    };
+                    ensure!(
+                        matches!(
+                            info.data_type,
+                            FeatureDataType::Int
+                                | FeatureDataType::Float
+                                | FeatureDataType::Category
+                        ),
+                        error::ColumnMustBeNumeric {
+                            column: column.clone(),
+                        }
+                    );
+                }
+
+                (
+                    InitializedTable::Collection {
+                        processor: table,
+                        columns,
+                    },
+                    classes,
+                )
+            }
+            (ReclassifyTable::Rules { .. }, Some(_)) => {
+                return Err(ReclassifyError::UnexpectedTableSource.into())
+            }
+            (ReclassifyTable::Collection { .. }, None) => {
+                return Err(ReclassifyError::MissingTableSource.into())
+            }
+        };
+
+        let measurement = match self.params.measurement {
+            Some(measurement) => Measurement::classification(measurement, classes),
+            None => Measurement::Unitless,
+        };
+
+        let result_descriptor = RasterResultDescriptor {
+            data_type: RasterDataType::U8,
+            spatial_reference: in_desc.spatial_reference,
+            time: in_desc.time,
+            bbox: in_desc.bbox,
+            resolution: in_desc.resolution,
+            bands: RasterBandDescriptors::new(vec![RasterBandDescriptor::new(
+                in_desc.bands[0].name.clone(),
+                measurement,
+            )])?,
+        };
+
+        Ok(InitializedReclassify {
+            name,
+            result_descriptor,
+            raster: sources.raster,
+            table,
+            default_class: self.params.default_class,
+            no_data_class: self.params.no_data_class,
+        }
+        .boxed())
+    }
+
+    span_fn!(Reclassify);
+}
+
+enum InitializedTable {
+    Rules(Arc<Vec<(ReclassifyInput, u8)>>),
+    Collection {
+        processor: Box<dyn InitializedVectorOperator>,
+        columns: TableColumns,
+    },
+}
+
+pub struct InitializedReclassify {
+    name: CanonicOperatorName,
+    result_descriptor: RasterResultDescriptor,
+    raster: Box<dyn InitializedRasterOperator>,
+    table: InitializedTable,
+    default_class: Option<u8>,
+    no_data_class: Option<u8>,
+}
+
+impl InitializedRasterOperator for InitializedReclassify {
+    fn result_descriptor(&self) -> &RasterResultDescriptor {
+        &self.result_descriptor
+    }
+
+    fn query_processor(&self) -> Result<TypedRasterQueryProcessor> {
+        let source = self.raster.query_processor()?;
+
+        let table = match &self.table {
+            InitializedTable::Rules(rules) => RulesSource::Rules(rules.clone()),
+            InitializedTable::Collection { processor, columns } => RulesSource::Collection {
+                processor: processor
+                    .query_processor()?
+                    .data()
+                    .expect("checked in `Reclassify` initialization"),
+                columns: columns.clone(),
+            },
+        };
+
+        Ok(TypedRasterQueryProcessor::U8(
+            call_on_generic_raster_processor!(source, source => ReclassifyProcessor {
+                source,
+                result_descriptor: self.result_descriptor.clone(),
+                table,
+                default_class: self.default_class,
+                no_data_class: self.no_data_class,
+            }
+            .boxed()),
+        ))
+    }
+
+    fn canonic_name(&self) -> CanonicOperatorName {
+        self.name.clone()
+    }
+}
+
+enum RulesSource {
+    Rules(Arc<Vec<(ReclassifyInput, u8)>>),
+    Collection {
+        processor: Box<dyn VectorQueryProcessor<VectorType = DataCollection>>,
+        columns: TableColumns,
+    },
+}
+
+struct ReclassifyProcessor<P>
+where
+    P: Pixel,
+{
+    source: Box<dyn RasterQueryProcessor<RasterType = P>>,
+    result_descriptor: RasterResultDescriptor,
+    table: RulesSource,
+    default_class: Option<u8>,
+    no_data_class: Option<u8>,
+}
+
+impl<P> ReclassifyProcessor<P>
+where
+    P: Pixel,
+{
+    /// Reads the rules from the table source.
+    /// All features that are valid within the query time are used, in the order of the stream.
+    async fn rules(
+        &self,
+        query: &RasterQueryRectangle,
+        ctx: &dyn QueryContext,
+    ) -> Result<Arc<Vec<(ReclassifyInput, u8)>>> {
+        let (processor, columns) = match &self.table {
+            RulesSource::Rules(rules) => return Ok(rules.clone()),
+            RulesSource::Collection { processor, columns } => (processor, columns),
+        };
+
+        let table_query = VectorQueryRectangle {
+            spatial_bounds: BoundingBox2D::from(geo::Rect::from(&query.spatial_bounds)),
+            time_interval: query.time_interval,
+            spatial_resolution: query.spatial_resolution,
+            attributes: ColumnSelection::all(),
+        };
+
+        let collections: Vec<DataCollection> = processor
+            .query(table_query, ctx)
+            .await?
+            .try_collect()
+            .await?;
+
+        let mut rules = Vec::new();
+        for collection in &collections {
+            let values = |column: &Option<String>| -> Result<Vec<Option<f64>>> {
+                Ok(match column {
+                    Some(column) => collection.data(column)?.float_options_iter().collect(),
+                    None => vec![None; collection.len()],
+                })
+            };
+
+            let value = values(&columns.value)?;
+            let min = values(&columns.min)?;
+            let max = values(&columns.max)?;
+            let class = values(&Some(columns.class.clone()))?;
+
+            for (((value, min), max), class) in value.into_iter().zip(min).zip(max).zip(class) {
+                let Some(class) = class else {
+                    continue;
+                };
+                ensure!(
+                    (0. ..=f64::from(u8::MAX)).contains(&class)
+                        && class.fract().abs() < f64::EPSILON,
+                    error::ClassOutOfRange { class }
+                );
+
+                let input = match (&columns.value, value) {
+                    (Some(_), Some(value)) => ReclassifyInput::Value { value },
+                    (Some(_), None) => continue,
+                    (None, _) => ReclassifyInput::Range { min, max },
+                };
+                ensure!(input.is_valid(), error::InvalidRule);
+
+                rules.push((input, class as u8));
+            }
+        }
+
+        Ok(Arc::new(rules))
+    }
+}
+
+fn classify(rules: &[(ReclassifyInput, u8)], value: f64) -> Option<u8> {
+    rules
+        .iter()
+        .find(|(input, _)| input.matches(value))
+        .map(|(_, class)| *class)
+}
+
+#[async_trait]
+impl<P> QueryProcessor for ReclassifyProcessor<P>
+where
+    P: Pixel,
+{
+    type Output = RasterTile2D<u8>;
+    type SpatialBounds = SpatialPartition2D;
+    type Selection = BandSelection;
+    type ResultDescription = RasterResultDescriptor;
+
+    async fn _query<'a>(
+        &'a self,
+        query: RasterQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<BoxStream<'a, Result<Self::Output>>> {
+        let rules = self.rules(&query, ctx).await?;
+
+        let default_class = self.default_class;
+        let no_data_class = self.no_data_class;
+
+        let stream = self
+            .source
+            .raster_query(query, ctx)
+            .await?
+            .and_then(move |tile| {
+                let rules = rules.clone();
+                crate::util::spawn_blocking_with_thread_pool(ctx.thread_pool().clone(), move || {
+                    tile.map_elements_parallel(move |pixel: Option<P>| match pixel {
+                        Some(pixel) => classify(&rules, pixel.as_()).or(default_class),
+                        None => no_data_class,
+                    })
+                })
+                .map_err(Into::into)
+            });
+
+        Ok(stream.boxed())
+    }
+
+    fn result_descriptor(&self) -> &RasterResultDescriptor {
+        &self.result_descriptor
+    }
+}
+
+#[derive(Debug, Snafu)]
+#[snafu(visibility(pub(crate)), context(suffix(false)), module(error))]
+pub enum ReclassifyError {
+    #[snafu(display("Reclassify can only be applied to single-band rasters"))]
+    RasterMustHaveSingleBand,
+    #[snafu(display(
+        "Rules must have a non-NaN value or a range with non-NaN bounds where `min` is less than `max`"
+    ))]
+    InvalidRule,
+    #[snafu(display("A collection table requires a `table` source"))]
+    MissingTableSource,
+    #[snafu(display("A `table` source is only allowed for collection tables"))]
+    UnexpectedTableSource,
+    #[snafu(display("The table must be a data collection, found {found}"))]
+    TableMustBeDataCollection { found: String },
+    #[snafu(display(
+        "A collection table requires either a value column or at least one of a min and max column"
+    ))]
+    InvalidTableColumns,
+    #[snafu(display("Column `{column}` does not exist in the table"))]
+    ColumnDoesNotExist { column: String },
+    #[snafu(display("Column `{column}` must be numeric"))]
+    ColumnMustBeNumeric { column: String },
+    #[snafu(display("Class {class} is not in the range 0 to 255"))]
+    ClassOutOfRange { class: f64 },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{MockExecutionContext, MockQueryContext};
+    use crate::mock::{MockFeatureCollectionSource, MockRasterSource, MockRasterSourceParams};
+    use geoengine_datatypes::primitives::{
+        CacheHint, FeatureData, SpatialResolution, TimeInterval,
+    };
+    use geoengine_datatypes::raster::{Grid2D, GridOrEmpty, MaskedGrid2D, TileInformation};
+    use geoengine_datatypes::spatial_reference::SpatialReference;
+    use geoengine_datatypes::util::test::TestDefault;
+
+    fn raster() -> Box<dyn RasterOperator> {
+        let grid = MaskedGrid2D::new(
+            Grid2D::new([2, 3].into(), vec![1., 2.5, 4., 7., 9., 0.]).unwrap(),
+            Grid2D::new([2, 3].into(), vec![true, true, true, true, true, false]).unwrap(),
+        )
+        .unwrap();
+
+        MockRasterSource {
+            params: MockRasterSourceParams {
+                data: vec![RasterTile2D::<f64>::new_with_tile_info(
+                    TimeInterval::default(),
+                    TileInformation {
+                        global_geo_transform: TestDefault::test_default(),
+                        global_tile_position: [0, 0].into(),
+                        tile_size_in_pixels: [2, 3].into(),
+                    },
+                    0,
+                    grid.into(),
+                    CacheHint::default(),
+                )],
+                result_descriptor: RasterResultDescriptor {
+                    data_type: RasterDataType::F64,
+                    spatial_reference: SpatialReference::epsg_4326().into(),
+                    time: None,
+                    bbox: None,
+                    resolution: None,
+                    bands: RasterBandDescriptors::new_single_band(),
+                },
+            },
+        }
+        .boxed()
+    }
+
+    async fn reclassify(
+        params: ReclassifyParams,
+        table: Option<Box<dyn VectorOperator>>,
+    ) -> (RasterResultDescriptor, Vec<Option<u8>>) {
+        let execution_context = MockExecutionContext::new_with_tiling_spec(
+            geoengine_datatypes::raster::TilingSpecification {
+                origin_coordinate: [0.0, 0.0].into(),
+                tile_size_in_pixels: [2, 3].into(),
+            },
+        );
+
+        let operator = Reclassify {
+            params,
+            sources: ReclassifySources {
+                raster: raster(),
+                table,
+            },
+        }
+        .boxed()
+        .initialize(WorkflowOperatorPath::initialize_root(), &execution_context)
+        .await
+        .unwrap();
+
+        let processor = operator.query_processor().unwrap().get_u8().unwrap();
+
+        let tiles: Vec<RasterTile2D<u8>> = processor
+            .raster_query(
+                RasterQueryRectangle {
+                    spatial_bounds: SpatialPartition2D::new((0., 0.).into(), (3., -2.).into())
+                        .unwrap(),
+                    time_interval: TimeInterval::default(),
+                    spatial_resolution: SpatialResolution::one(),
+                    attributes: BandSelection::first(),
+                },
+                &MockQueryContext::test_default(),
+            )
+            .await
+            .unwrap()
+            .try_collect()
+            .await
+            .unwrap();
+
+        assert_eq!(tiles.len(), 1);
+        let GridOrEmpty::Grid(grid) = &tiles[0].grid_array else {
+            panic!("expected a grid");
+        };
+
+        (
+            operator.result_descriptor().clone(),
+            grid.masked_element_deref_iterator().collect(),
+        )
+    }
+
+    #[tokio::test]
+    async fn it_reclassifies_values_and_ranges() {
+        let (result_descriptor, classes) = reclassify(
+            ReclassifyParams {
+                table: ReclassifyTable::Rules {
+                    rules: vec![
+                        ReclassifyRule {
+                            input: ReclassifyInput::Value { value: 1. },
+                            class: 10,
+                            name: Some("one".to_string()),
+                        },
+                        ReclassifyRule {
+                            input: ReclassifyInput::Range {
+                                min: Some(2.),
+                                max: Some(7.),
+                            },
+                            class: 20,
+                            name: Some("low".to_string()),
+                        },
+                        ReclassifyRule {
+                            input: ReclassifyInput::Range {
+                                min: Some(7.),
+                                max: Some(8.),
+                            },
+                            class: 30,
+                            name: None,
+                        },
+                    ],
+                },
+                default_class: Some(0),
+                no_data_class: Some(255),
+                measurement: Some("landcover".to_string()),
+            },
+            None,
+        )
+        .await;
+
+        assert_eq!(
+            classes,
+            vec![Some(10), Some(20), Some(20), Some(30), Some(0), Some(255)]
+        );
+
+        assert_eq!(result_descriptor.data_type, RasterDataType::U8);
+        assert_eq!(
+            result_descriptor.bands[0].measurement,
+            Measurement::classification(
+                "landcover".to_string(),
+                [(10, "one".to_string()), (20, "low".to_string())]
+                    .into_iter()
+                    .collect()
+            )
+        );
+    }
+
+    #[tokio::test]
+    async fn it_keeps_no_data_without_classes() {
+        let (_, classes) = reclassify(
+            ReclassifyParams {
+                table: ReclassifyTable::Rules {
+                    rules: vec![ReclassifyRule {
+                        input: ReclassifyInput::Range {
+                            min: None,
+                            max: Some(4.),
+                        },
+                        class: 1,
+                        name: None,
+                    }],
+                },
+                default_class: None,
+                no_data_class: None,
+                measurement: None,
+            },
+            None,
+        )
+        .await;
+
+        assert_eq!(classes, vec![Some(1), Some(1), None, None, None, None]);
+    }
+
+    #[tokio::test]
+    async fn it_reads_rules_from_a_data_collection() {
+        let table = DataCollection::from_data(
+            vec![],
+            vec![TimeInterval::default(); 2],
+            [
+                (
+                    "min".to_string(),
+                    FeatureData::NullableFloat(vec![None, Some(4.)]),
+                ),
+                (
+                    "max".to_string(),
+                    FeatureData::NullableFloat(vec![Some(4.), None]),
+                ),
+                ("class".to_string(), FeatureData::Int(vec![1, 2])),
+            ]
+            .into_iter()
+            .collect(),
+            CacheHint::default(),
+        )
+        .unwrap();
+
+        let (_, classes) = reclassify(
+            ReclassifyParams {
+                table: ReclassifyTable::Collection {
+                    value_column: None,
+                    min_column: Some("min".to_string()),
+                    max_column: Some("max".to_string()),
+                    class_column: "class".to_string(),
+                    classes: HashMap::new(),
+                },
+                default_class: None,
+                no_data_class: None,
+                measurement: None,
+            },
+            Some(MockFeatureCollectionSource::single(table).boxed()),
+        )
+        .await;
+
+        assert_eq!(
+            classes,
+            vec![Some(1), Some(1), Some(2), Some(2), Some(2), None]
+        );
+    }
+
+    #[test]
+    fn it_deserializes_rules() {
+        let params: ReclassifyParams = serde_json::from_value(serde_json::json!({
+            "table": {
+                "type": "rules",
+                "rules": [
+                    { "type": "value", "value": 1.0, "class": 1, "name": "water" },
+                    { "type": "range", "min": 2.0, "class": 2 }
+                ]
+            },
+            "defaultClass": 0
+        }))
+        .unwrap();
+
+        assert_eq!(
+            params,
+            ReclassifyParams {
+                table: ReclassifyTable::Rules {
+                    rules: vec![
+                        ReclassifyRule {
+                            input: ReclassifyInput::Value { value: 1. },
+                            class: 1,
+                            name: Some("water".to_string()),
+                        },
+                        ReclassifyRule {
+                            input: ReclassifyInput::Range {
+                                min: Some(2.),
+                                max: None,
+                            },
+                            class: 2,
+                            name: None,
+                        },
+                    ],
+                },
+                default_class: Some(0),
+                no_data_class: None,
+                measurement: None,
+            }
+        );
+    }
+}
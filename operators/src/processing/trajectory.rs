@@ -0,0 +1,629 @@
+use crate::engine::{
+    CanonicOperatorName, ExecutionContext, InitializedSources, InitializedVectorOperator, Operator,
+    OperatorName, QueryContext, QueryProcessor, SingleVectorSource, TypedVectorQueryProcessor,
+    VectorColumnInfo, VectorOperator, VectorQueryProcessor, VectorResultDescriptor,
+    WorkflowOperatorPath,
+};
+use crate::processing::buffer::geometry::EARTH_RADIUS;
+use crate::util::feature_key::FeatureKey;
+use crate::util::Result;
+use async_trait::async_trait;
+use futures::stream::BoxStream;
+use futures::{StreamExt, TryStreamExt};
+use geoengine_datatypes::collections::{
+    BuilderProvider, FeatureCollectionInfos, GeoFeatureCollectionRowBuilder, IntoGeometryIterator,
+    MultiLineStringCollection, MultiPointCollection, VectorDataType,
+};
+use geoengine_datatypes::primitives::{
+    BoundingBox2D, CacheHint, ColumnSelection, Coordinate2D, FeatureDataType, FeatureDataValue,
+    Measurement, MultiLineString, MultiPointAccess, TimeInterval, TimeStep, VectorQueryRectangle,
+};
+use geoengine_datatypes::spatial_reference::{SpatialReference, SpatialReferenceOption};
+use serde::{Deserialize, Serialize};
+use snafu::{ensure, Snafu};
+use std::collections::BTreeMap;
+
+/// The `Trajectory` operator connects points with the same value in `id_column` to tracks
+/// in the order of their time intervals.
+///
+/// A track is split where the time between two consecutive points exceeds `max_gap`.
+/// Depending on the `output`, there is a feature for each segment between two consecutive points
+/// or for each track.
+/// The output contains the id column, the index `track` of the track of each id and the
+/// movement statistics in meters, seconds and degrees clockwise from north.
+///
+/// For a projected spatial reference, its units are assumed to be meters.
+/// Multi-points are represented by the mean of their coordinates.
+/// Points without an id are ignored.
+pub type Trajectory = Operator<TrajectoryParams, SingleVectorSource>;
+
+impl OperatorName for Trajectory {
+    const TYPE_NAME: &'static str = "Trajectory";
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrajectoryParams {
+    pub id_column: String,
+    #[serde(default)]
+    pub max_gap: Option<TimeStep>,
+    #[serde(default)]
+    pub output: TrajectoryOutput,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TrajectoryOutput {
+    /// A line for each pair of consecutive points with its `distance`, `duration`, `speed` and
+    /// `heading`
+    #[default]
+    Segments,
+    /// A line for each track with its `distance`, `duration` and mean `speed`
+    Tracks,
+}
+
+impl TrajectoryOutput {
+    fn columns(self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Self::Segments => &[
+                ("distance", "m"),
+                ("duration", "s"),
+                ("speed", "m/s"),
+                ("heading", "degrees"),
+            ],
+            Self::Tracks => &[("distance", "m"), ("duration", "s"), ("speed", "m/s")],
+        }
+    }
+}
+
+const TRACK_COLUMN: &str = "track";
+
+#[derive(Debug, Snafu)]
+#[snafu(visibility(pub(crate)), context(suffix(false)), module(error))]
+pub enum TrajectoryError {
+    #[snafu(display("The input must be points, found {found}"))]
+    InputMustBePoints { found: String },
+
+    #[snafu(display("The id column `{column}` does not exist"))]
+    IdColumnDoesNotExist { column: String },
+
+    #[snafu(display("The id column must not be named like the output column `{column}`"))]
+    IdColumnNameConflict { column: String },
+
+    #[snafu(display("The maximum gap must be positive"))]
+    InvalidMaxGap,
+}
+
+#[typetag::serde]
+#[async_trait]
+impl VectorOperator for Trajectory {
+    async fn _initialize(
+        self: Box<Self>,
+        path: WorkflowOperatorPath,
+        context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedVectorOperator>> {
+        let name = CanonicOperatorName::from(&self);
+
+        let source = self.sources.initialize_sources(path, context).await?.vector;
+
+        let in_desc = source.result_descriptor();
+
+        ensure!(
+            in_desc.data_type == VectorDataType::MultiPoint,
+            error::InputMustBePoints {
+                found: in_desc.data_type.to_string(),
+            }
+        );
+
+        let Some(id_info) = in_desc.columns.get(&self.params.id_column) else {
+            return Err(TrajectoryError::IdColumnDoesNotExist {
+                column: self.params.id_column.clone(),
+            }
+            .into());
+        };
+
+        let output_columns = self.params.output.columns();
+        ensure!(
+            self.params.id_column != TRACK_COLUMN
+                && output_columns
+                    .iter()
+                    .all(|(column, _)| *column != self.params.id_column),
+            error::IdColumnNameConflict {
+                column: self.params.id_column.clone(),
+            }
+        );
+
+        if let Some(max_gap) = self.params.max_gap {
+            ensure!(max_gap.step > 0, error::InvalidMaxGap);
+        }
+
+        let mut columns = [
+            (self.params.id_column.clone(), id_info.clone()),
+            (
+                TRACK_COLUMN.to_string(),
+                VectorColumnInfo {
+                    data_type: FeatureDataType::Int,
+                    measurement: Measurement::Unitless,
+                },
+            ),
+        ]
+        .into_iter()
+        .collect::<std::collections::HashMap<_, _>>();
+        for (column, unit) in output_columns {
+            columns.insert(
+                (*column).to_string(),
+                VectorColumnInfo {
+                    data_type: FeatureDataType::Float,
+                    measurement: Measurement::continuous(
+                        (*column).to_string(),
+                        Some((*unit).to_string()),
+                    ),
+                },
+            );
+        }
+
+        let result_descriptor = VectorResultDescriptor {
+            data_type: VectorDataType::MultiLineString,
+            spatial_reference: in_desc.spatial_reference,
+            columns,
+            time: in_desc.time,
+            bbox: in_desc.bbox,
+        };
+        let id_type = id_info.data_type;
+
+        Ok(InitializedTrajectory {
+            name,
+            result_descriptor,
+            source,
+            id_type,
+            params: self.params,
+        }
+        .boxed())
+    }
+
+    span_fn!(Trajectory);
+}
+
+pub struct InitializedTrajectory {
+    name: CanonicOperatorName,
+    result_descriptor: VectorResultDescriptor,
+    source: Box<dyn InitializedVectorOperator>,
+    id_type: FeatureDataType,
+    params: TrajectoryParams,
+}
+
+impl InitializedVectorOperator for InitializedTrajectory {
+    fn result_descriptor(&self) -> &VectorResultDescriptor {
+        &self.result_descriptor
+    }
+
+    fn query_processor(&self) -> Result<TypedVectorQueryProcessor> {
+        let source = self
+            .source
+            .query_processor()?
+            .multi_point()
+            .expect("checked in `Trajectory` initialization");
+
+        Ok(TypedVectorQueryProcessor::MultiLineString(
+            TrajectoryProcessor {
+                source,
+                result_descriptor: self.result_descriptor.clone(),
+                id_type: self.id_type,
+                params: self.params.clone(),
+            }
+            .boxed(),
+        ))
+    }
+
+    fn canonic_name(&self) -> CanonicOperatorName {
+        self.name.clone()
+    }
+}
+
+struct TrajectoryProcessor {
+    source: Box<dyn VectorQueryProcessor<VectorType = MultiPointCollection>>,
+    result_descriptor: VectorResultDescriptor,
+    id_type: FeatureDataType,
+    params: TrajectoryParams,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Fix {
+    time: TimeInterval,
+    location: Coordinate2D,
+}
+
+/// The movement between two locations
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Movement {
+    /// The distance in meters
+    distance: f64,
+    /// The heading in degrees clockwise from north
+    heading: f64,
+}
+
+impl Movement {
+    fn between(from: Coordinate2D, to: Coordinate2D, geographic: bool) -> Self {
+        if !geographic {
+            let (dx, dy) = (to.x - from.x, to.y - from.y);
+            return Self {
+                distance: dx.hypot(dy),
+                heading: dx.atan2(dy).to_degrees().rem_euclid(360.),
+            };
+        }
+
+        let (lon1, lat1) = (from.x.to_radians(), from.y.to_radians());
+        let (lon2, lat2) = (to.x.to_radians(), to.y.to_radians());
+        let dlon = lon2 - lon1;
+
+        // the haversine distance and the initial bearing on a sphere
+        let a = ((lat2 - lat1) / 2.).sin().powi(2)
+            + lat1.cos() * lat2.cos() * (dlon / 2.).sin().powi(2);
+        let distance = 2. * EARTH_RADIUS * a.sqrt().min(1.).asin();
+
+        let heading = (dlon.sin() * lat2.cos())
+            .atan2(lat1.cos() * lat2.sin() - lat1.sin() * lat2.cos() * dlon.cos())
+            .to_degrees()
+            .rem_euclid(360.);
+
+        Self { distance, heading }
+    }
+}
+
+impl TrajectoryProcessor {
+    /// Groups the points by id, orders them by time and splits them into tracks at gaps
+    fn tracks(
+        collections: &[MultiPointCollection],
+        id_column: &str,
+        max_gap: Option<TimeStep>,
+    ) -> Result<Vec<(FeatureDataValue, Vec<Vec<Fix>>)>> {
+        let mut groups = BTreeMap::<FeatureKey, (FeatureDataValue, Vec<Fix>)>::new();
+
+        for collection in collections {
+            let ids = collection.data(id_column)?;
+
+            for (feature_index, (geometry, time)) in collection
+                .geometries()
+                .zip(collection.time_intervals())
+                .enumerate()
+            {
+                let id = ids.get_unchecked(feature_index);
+                let key = FeatureKey::from(&id);
+                if key == FeatureKey::Null {
+                    continue;
+                }
+
+                let points = geometry.points();
+                let (x, y) = points
+                    .iter()
+                    .fold((0., 0.), |(x, y), point| (x + point.x, y + point.y));
+                let count = points.len() as f64;
+
+                groups
+                    .entry(key)
+                    .or_insert_with(|| (id, Vec::new()))
+                    .1
+                    .push(Fix {
+                        time: *time,
+                        location: Coordinate2D::new(x / count, y / count),
+                    });
+            }
+        }
+
+        groups
+            .into_values()
+            .map(|(id, mut fixes)| -> Result<_> {
+                fixes.sort_by_key(|fix| (fix.time.start(), fix.time.end()));
+
+                let mut tracks = Vec::<Vec<Fix>>::new();
+                let mut previous: Option<Fix> = None;
+                for fix in fixes {
+                    let is_gap = match (previous, max_gap) {
+                        (Some(previous), Some(max_gap)) => {
+                            (previous.time.start() + max_gap)? < fix.time.start()
+                        }
+                        _ => false,
+                    };
+
+                    match tracks.last_mut() {
+                        Some(track) if !is_gap => track.push(fix),
+                        _ => tracks.push(vec![fix]),
+                    }
+                    previous = Some(fix);
+                }
+
+                Ok((id, tracks))
+            })
+            .collect()
+    }
+
+    fn trajectories(
+        collections: &[MultiPointCollection],
+        params: &TrajectoryParams,
+        id_type: FeatureDataType,
+        spatial_reference: SpatialReferenceOption,
+    ) -> Result<MultiLineStringCollection> {
+        let geographic = spatial_reference == SpatialReference::epsg_4326().into();
+        let cache_hint = collections
+            .iter()
+            .fold(CacheHint::max_duration(), |cache_hint, collection| {
+                cache_hint.merged(&collection.cache_hint)
+            });
+
+        let tracks = Self::tracks(collections, &params.id_column, params.max_gap)?;
+
+        let mut builder = MultiLineStringCollection::builder();
+        builder.add_column(params.id_column.clone(), id_type)?;
+        builder.add_column(TRACK_COLUMN.to_string(), FeatureDataType::Int)?;
+        for (column, _) in params.output.columns() {
+            builder.add_column((*column).to_string(), FeatureDataType::Float)?;
+        }
+        let mut builder = builder.finish_header();
+
+        for (id, tracks) in tracks {
+            for (track_index, track) in tracks.iter().filter(|track| track.len() > 1).enumerate() {
+                let lines: Vec<&[Fix]> = match params.output {
+                    TrajectoryOutput::Segments => track.windows(2).collect(),
+                    TrajectoryOutput::Tracks => vec![track.as_slice()],
+                };
+
+                for line in lines {
+                    let (first, last) = (line[0], line[line.len() - 1]);
+
+                    let distance = line
+                        .windows(2)
+                        .map(|pair| {
+                            Movement::between(pair[0].location, pair[1].location, geographic)
+                                .distance
+                        })
+                        .sum::<f64>();
+                    let duration =
+                        (last.time.start().inner() - first.time.start().inner()) as f64 / 1000.;
+                    let speed = (duration > 0.).then(|| distance / duration);
+
+                    builder.push_geometry(MultiLineString::new(vec![line
+                        .iter()
+                        .map(|fix| fix.location)
+                        .collect()])?);
+                    builder.push_time_interval(TimeInterval::new_unchecked(
+                        first.time.start(),
+                        last.time.end(),
+                    ));
+                    builder.push_data(&params.id_column, id.clone())?;
+                    builder.push_data(
+                        TRACK_COLUMN,
+                        FeatureDataValue::NullableInt(Some(track_index as i64)),
+                    )?;
+                    builder
+                        .push_data("distance", FeatureDataValue::NullableFloat(Some(distance)))?;
+                    builder
+                        .push_data("duration", FeatureDataValue::NullableFloat(Some(duration)))?;
+                    builder.push_data("speed", FeatureDataValue::NullableFloat(speed))?;
+
+                    if params.output == TrajectoryOutput::Segments {
+                        let heading =
+                            Movement::between(first.location, last.location, geographic).heading;
+                        // a segment without movement has no heading
+                        let heading = (distance > 0.).then_some(heading);
+                        builder.push_data("heading", FeatureDataValue::NullableFloat(heading))?;
+                    }
+
+                    builder.finish_row();
+                }
+            }
+        }
+
+        builder.cache_hint(cache_hint);
+
+        Ok(builder.build()?)
+    }
+}
+
+#[async_trait]
+impl QueryProcessor for TrajectoryProcessor {
+    type Output = MultiLineStringCollection;
+    type SpatialBounds = BoundingBox2D;
+    type Selection = ColumnSelection;
+    type ResultDescription = VectorResultDescriptor;
+
+    async fn _query<'a>(
+        &'a self,
+        query: VectorQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<BoxStream<'a, Result<Self::Output>>> {
+        let collections: Vec<MultiPointCollection> =
+            self.source.query(query, ctx).await?.try_collect().await?;
+
+        let params = self.params.clone();
+        let id_type = self.id_type;
+        let spatial_reference = self.result_descriptor.spatial_reference;
+        let trajectories =
+            crate::util::spawn_blocking_with_thread_pool(ctx.thread_pool().clone(), move || {
+                Self::trajectories(&collections, &params, id_type, spatial_reference)
+            })
+            .await??;
+
+        Ok(futures::stream::once(async { Ok(trajectories) }).boxed())
+    }
+
+    fn result_descriptor(&self) -> &VectorResultDescriptor {
+        &self.result_descriptor
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{MockExecutionContext, MockQueryContext};
+    use crate::mock::MockFeatureCollectionSource;
+    use geoengine_datatypes::primitives::{
+        FeatureData, MultiPoint, SpatialResolution, TimeGranularity,
+    };
+    use geoengine_datatypes::spatial_reference::SpatialReferenceAuthority;
+    use geoengine_datatypes::util::test::TestDefault;
+
+    fn animals() -> Vec<MultiPointCollection> {
+        vec![
+            MultiPointCollection::from_data(
+                MultiPoint::many(vec![(0., 0.), (1., 0.), (0., 0.)]).unwrap(),
+                vec![
+                    TimeInterval::new_instant(10_000).unwrap(),
+                    TimeInterval::new_instant(0).unwrap(),
+                    TimeInterval::new_instant(0).unwrap(),
+                ],
+                [(
+                    "animal".to_string(),
+                    FeatureData::NullableText(vec![
+                        Some("fox".to_string()),
+                        Some("owl".to_string()),
+                        Some("fox".to_string()),
+                    ]),
+                )]
+                .into_iter()
+                .collect(),
+                CacheHint::default(),
+            )
+            .unwrap(),
+            MultiPointCollection::from_data(
+                MultiPoint::many(vec![(0., 100.), (1., 50.), (5., 5.)]).unwrap(),
+                vec![
+                    TimeInterval::new_instant(20_000).unwrap(),
+                    TimeInterval::new_instant(3_600_000).unwrap(),
+                    TimeInterval::new_instant(0).unwrap(),
+                ],
+                [(
+                    "animal".to_string(),
+                    FeatureData::NullableText(vec![
+                        Some("fox".to_string()),
+                        Some("owl".to_string()),
+                        None,
+                    ]),
+                )]
+                .into_iter()
+                .collect(),
+                CacheHint::default(),
+            )
+            .unwrap(),
+        ]
+    }
+
+    async fn run(params: TrajectoryParams) -> Result<MultiLineStringCollection> {
+        let operator = Trajectory {
+            params,
+            sources: MockFeatureCollectionSource::with_collections_and_sref(
+                animals(),
+                SpatialReference::new(SpatialReferenceAuthority::Epsg, 32632),
+            )
+            .boxed()
+            .into(),
+        }
+        .boxed()
+        .initialize(
+            WorkflowOperatorPath::initialize_root(),
+            &MockExecutionContext::test_default(),
+        )
+        .await?;
+
+        let processor = operator.query_processor()?.multi_line_string().unwrap();
+
+        let mut result: Vec<MultiLineStringCollection> = processor
+            .query(
+                VectorQueryRectangle {
+                    spatial_bounds: BoundingBox2D::new(
+                        (-1000., -1000.).into(),
+                        (1000., 1000.).into(),
+                    )
+                    .unwrap(),
+                    time_interval: TimeInterval::default(),
+                    spatial_resolution: SpatialResolution::one(),
+                    attributes: ColumnSelection::all(),
+                },
+                &MockQueryContext::test_default(),
+            )
+            .await?
+            .try_collect()
+            .await?;
+
+        assert_eq!(result.len(), 1);
+        Ok(result.remove(0))
+    }
+
+    fn floats(collection: &MultiLineStringCollection, column: &str) -> Vec<Option<f64>> {
+        collection
+            .data(column)
+            .unwrap()
+            .float_options_iter()
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn it_builds_segments() {
+        let result = run(TrajectoryParams {
+            id_column: "animal".to_string(),
+            max_gap: None,
+            output: TrajectoryOutput::Segments,
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(result.len(), 3);
+        assert_eq!(
+            result
+                .data("animal")
+                .unwrap()
+                .strings_iter()
+                .collect::<Vec<_>>(),
+            vec!["fox".to_string(), "fox".to_string(), "owl".to_string()]
+        );
+        assert_eq!(
+            floats(&result, "distance"),
+            vec![Some(0.), Some(100.), Some(50.)]
+        );
+        assert_eq!(
+            floats(&result, "duration"),
+            vec![Some(10.), Some(10.), Some(3600.)]
+        );
+        assert_eq!(
+            floats(&result, "speed"),
+            vec![Some(0.), Some(10.), Some(50. / 3600.)]
+        );
+        assert_eq!(floats(&result, "heading"), vec![None, Some(0.), Some(0.)]);
+        assert_eq!(
+            result.time_intervals(),
+            &[
+                TimeInterval::new_unchecked(0, 10_000),
+                TimeInterval::new_unchecked(10_000, 20_000),
+                TimeInterval::new_unchecked(0, 3_600_000),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn it_splits_tracks_at_gaps() {
+        let result = run(TrajectoryParams {
+            id_column: "animal".to_string(),
+            max_gap: Some(TimeStep {
+                granularity: TimeGranularity::Minutes,
+                step: 1,
+            }),
+            output: TrajectoryOutput::Tracks,
+        })
+        .await
+        .unwrap();
+
+        // the owl has a gap of an hour and no track with more than one point
+        assert_eq!(result.len(), 1);
+        assert_eq!(floats(&result, "distance"), vec![Some(100.)]);
+        assert_eq!(floats(&result, "duration"), vec![Some(20.)]);
+        assert_eq!(floats(&result, "speed"), vec![Some(5.)]);
+        assert_eq!(floats(&result, TRACK_COLUMN), vec![Some(0.)]);
+    }
+
+    #[test]
+    fn it_computes_geographic_movements() {
+        let movement = Movement::between((0., 0.).into(), (1., 0.).into(), true);
+
+        assert!((movement.distance - EARTH_RADIUS.to_radians()).abs() < 1e-6);
+        assert!((movement.heading - 90.).abs() < 1e-9);
+    }
+}
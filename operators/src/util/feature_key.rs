@@ -0,0 +1,40 @@
+use geoengine_datatypes::primitives::{FeatureDataValue, TimeInstance};
+use ordered_float::OrderedFloat;
+
+/// A totally ordered representation of a feature value for grouping
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum FeatureKey {
+    Null,
+    Int(i64),
+    Float(OrderedFloat<f64>),
+    Text(String),
+    Bool(bool),
+    DateTime(TimeInstance),
+}
+
+impl From<&FeatureDataValue> for FeatureKey {
+    fn from(value: &FeatureDataValue) -> Self {
+        match value {
+            FeatureDataValue::Category(v) | FeatureDataValue::NullableCategory(Some(v)) => {
+                Self::Int(i64::from(*v))
+            }
+            FeatureDataValue::Int(v) | FeatureDataValue::NullableInt(Some(v)) => Self::Int(*v),
+            FeatureDataValue::Float(v) | FeatureDataValue::NullableFloat(Some(v)) => {
+                Self::Float(OrderedFloat(*v))
+            }
+            FeatureDataValue::Text(v) | FeatureDataValue::NullableText(Some(v)) => {
+                Self::Text(v.clone())
+            }
+            FeatureDataValue::Bool(v) | FeatureDataValue::NullableBool(Some(v)) => Self::Bool(*v),
+            FeatureDataValue::DateTime(v) | FeatureDataValue::NullableDateTime(Some(v)) => {
+                Self::DateTime(*v)
+            }
+            FeatureDataValue::NullableCategory(None)
+            | FeatureDataValue::NullableInt(None)
+            | FeatureDataValue::NullableFloat(None)
+            | FeatureDataValue::NullableText(None)
+            | FeatureDataValue::NullableBool(None)
+            | FeatureDataValue::NullableDateTime(None) => Self::Null,
+        }
+    }
+}
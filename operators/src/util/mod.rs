@@ -1,4 +1,5 @@
 mod async_util;
+pub mod feature_key;
 pub mod gdal;
 pub mod input;
 pub mod math;
@@ -12,6 +13,7 @@ pub mod stream_zip;
 pub mod string_token;
 pub mod sunpos;
 mod temporary_gdal_thread_local_config_options;
+pub mod vector_stream_to_ogr;
 
 use crate::error::Error;
 use std::collections::HashSet;
@@ -32,7 +32,8 @@ use std::path::PathBuf;
 
 use super::{abortable_query_execution, spawn_blocking};
 
-/// consume a raster stream and write it to a geotiff file, one band for each time step
+/// consume a raster stream and write it to a geotiff file, one band for each time step and selected raster band
+/// The bands are ordered by time and then by raster band, i.e., all raster bands of a time step follow each other.
 /// Note: the entire process is done in memory, and will take 2x the size of the raster
 ///       time series
 #[allow(clippy::too_many_arguments)]
@@ -66,32 +67,40 @@ where
         gdal_tiff_metadata,
     )?;
 
+    let selected_bands = query_rect.attributes.as_vec();
+
     let cache_hint = spawn_blocking(move || {
-        let mut band_idx = 1;
+        let num_bands = selected_bands.len() as isize;
+        let mut time_step = 0;
+        let mut tile_index_in_time_step = 0;
         let mut time = initial_tile_time;
 
         let mut cache_hint = CacheHint::max_duration();
 
         for tile in tiles {
             if tile.time != time {
-                // new time step => next band
+                // new time step => next bands
                 time = tile.time;
-                band_idx += 1;
+                time_step += 1;
+                tile_index_in_time_step = 0;
+            }
 
-                let mut band = dataset.rasterband(band_idx)?;
+            // the tiles of a time step contain all raster bands of a spatial tile before the next spatial tile
+            let raster_band = tile_index_in_time_step % num_bands;
+            let band_idx = time_step * num_bands + raster_band + 1;
 
+            if tile_index_in_time_step < num_bands {
+                let mut band = dataset.rasterband(band_idx)?;
+                set_band_time(&mut band, time)?;
                 band.set_metadata_item(
-                    "start",
-                    &time.start().as_datetime_string_with_millis(),
-                    "time",
-                )?;
-                band.set_metadata_item(
-                    "end",
-                    &time.end().as_datetime_string_with_millis(),
-                    "time",
+                    "band",
+                    &selected_bands[raster_band as usize].to_string(),
+                    "",
                 )?;
             }
 
+            tile_index_in_time_step += 1;
+
             cache_hint.merge_with(&tile.cache_hint);
 
             writer.write_tile_into_band(tile, dataset.rasterband(band_idx)?)?;
@@ -107,6 +116,16 @@ where
     ))
 }
 
+fn set_band_time(band: &mut RasterBand, time: TimeInterval) -> Result<()> {
+    band.set_metadata_item(
+        "start",
+        &time.start().as_datetime_string_with_millis(),
+        "time",
+    )?;
+    band.set_metadata_item("end", &time.end().as_datetime_string_with_millis(), "time")?;
+    Ok(())
+}
+
 fn create_multiband_dataset_and_writer<T>(
     tiles: &[RasterTile2D<T>],
     query_rect: &QueryRectangle<SpatialPartition2D, BandSelection>,
@@ -128,9 +147,11 @@ where
         tile_size_in_pixels: initial_tile_info.tile_size_in_pixels,
         geo_transform: initial_tile_info.global_geo_transform,
     };
+    let num_bands = query_rect.attributes.count() as usize;
     let num_tiles_per_timestep = strat
         .tile_grid_box(query_rect.spatial_bounds)
-        .number_of_elements();
+        .number_of_elements()
+        * num_bands;
     let num_timesteps = tiles.len() / num_tiles_per_timestep;
 
     let x_pixel_size = query_rect.spatial_resolution.x;
@@ -185,7 +206,7 @@ where
         &file_path,
         width as isize,
         height as isize,
-        num_timesteps as isize,
+        (num_timesteps * num_bands) as isize,
         &options,
     )?;
     dataset.set_spatial_ref(&gdal_tiff_metadata.spatial_reference.try_into()?)?;
@@ -1050,7 +1071,7 @@ mod tests {
     use std::ops::Add;
 
     use geoengine_datatypes::primitives::CacheHint;
-    use geoengine_datatypes::primitives::{DateTime, Duration};
+    use geoengine_datatypes::primitives::{DateTime, Duration, TimeInstance};
     use geoengine_datatypes::raster::{Grid, RasterDataType};
     use geoengine_datatypes::{
         primitives::{Coordinate2D, SpatialPartition2D, SpatialResolution, TimeInterval},
@@ -1831,4 +1852,94 @@ mod tests {
 
         drop(ds);
     }
+
+    #[tokio::test]
+    async fn multi_band_multi_time_geotiff() {
+        let ctx = MockQueryContext::test_default();
+        let tiling_specification = TilingSpecification::new(Coordinate2D::default(), [2, 2].into());
+
+        // the value of each pixel encodes its time step and band
+        let data = [
+            (TimeInterval::new_unchecked(0, 10), 0),
+            (TimeInterval::new_unchecked(10, 20), 10),
+        ]
+        .into_iter()
+        .flat_map(|(time, time_offset)| {
+            [(0, 1), (1, 2)].map(|(band, value)| RasterTile2D {
+                time,
+                tile_position: [-1, 0].into(),
+                band,
+                global_geo_transform: TestDefault::test_default(),
+                grid_array: Grid::new([2, 2].into(), vec![time_offset + value; 4])
+                    .unwrap()
+                    .into(),
+                properties: Default::default(),
+                cache_hint: CacheHint::default(),
+            })
+        })
+        .collect::<Vec<RasterTile2D<u8>>>();
+
+        let processor = MockRasterSourceProcessor {
+            result_descriptor: RasterResultDescriptor::with_datatype_and_num_bands(
+                RasterDataType::U8,
+                2,
+            ),
+            data,
+            tiling_specification,
+        }
+        .boxed();
+
+        let (mut bytes, _) = raster_stream_to_multiband_geotiff_bytes(
+            processor,
+            RasterQueryRectangle {
+                spatial_bounds: SpatialPartition2D::new((0., 2.).into(), (2., 0.).into()).unwrap(),
+                time_interval: TimeInterval::new_unchecked(0, 20),
+                spatial_resolution: GeoTransform::test_default().spatial_resolution(),
+                attributes: BandSelection::first_n(2),
+            },
+            ctx,
+            GdalGeoTiffDatasetMetadata {
+                no_data_value: Some(0.),
+                spatial_reference: SpatialReference::epsg_4326(),
+            },
+            GdalGeoTiffOptions {
+                as_cog: false,
+                compression_num_threads: GdalCompressionNumThreads::AllCpus,
+                force_big_tiff: false,
+            },
+            None,
+            Box::pin(futures::future::pending()),
+            tiling_specification,
+        )
+        .await
+        .unwrap();
+
+        let file_path = PathBuf::from(format!("/vsimem/{}/", uuid::Uuid::new_v4()));
+        let _mem_file =
+            gdal::vsi::create_mem_file_from_ref(&file_path, bytes.as_mut_slice()).unwrap();
+        let ds = gdal_open_dataset(&file_path).unwrap();
+
+        // the raster bands of each time step follow each other
+        assert_eq!(ds.raster_count(), 4);
+
+        for (band_idx, (value, raster_band, start)) in
+            (1..).zip([(1, "0", 0), (2, "1", 0), (11, "0", 10), (12, "1", 10)])
+        {
+            let band = ds.rasterband(band_idx).unwrap();
+
+            let pixels = band
+                .read_as::<u8>((0, 0), (2, 2), (2, 2), None)
+                .unwrap()
+                .data;
+            assert_eq!(pixels, vec![value; 4]);
+
+            assert_eq!(band.metadata_item("band", "").unwrap(), raster_band);
+            assert_eq!(
+                band.metadata_item("start", "time").unwrap(),
+                TimeInstance::from_millis_unchecked(start).as_datetime_string_with_millis()
+            );
+        }
+
+        drop(ds);
+    }
 }
@@ -485,9 +485,267 @@ impl PSquareHistogramBucket {
     }
 }
 
+/// Mergeable quantile sketch after Karnin, Lang and Liberty (KLL)
+///
+/// The sketch keeps a hierarchy of compactors. Level `h` stores samples with a weight of `2^h`.
+/// When a level exceeds its capacity, it is sorted and every second element is promoted to the
+/// next level. The capacities shrink geometrically towards the lower levels, so the memory
+/// consumption only depends on the parameter `k` and not on the number of samples.
+///
+/// Two sketches can be merged by concatenating their levels and compacting the result. This
+/// allows building sketches independently (e.g., per tile) and combining them afterwards.
+///
+/// For further details, see
+///
+/// Z. Karnin, K. Lang and E. Liberty, Optimal Quantile Approximation in Streams,
+/// 2016 IEEE 57th Annual Symposium on Foundations of Computer Science (FOCS), 2016, p. 71-78.
+/// <https://arxiv.org/abs/1603.05346>
+///
+/// # Note
+/// The original algorithm uses a random offset for each compaction. To get reproducible results,
+/// this implementation alternates the offset per level instead.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct KllSketch {
+    k: usize,
+    compactors: Vec<Vec<f64>>,
+    offsets: Vec<bool>,
+    size: usize,
+    max_size: usize,
+    sample_count: u64,
+}
+
+impl KllSketch {
+    const CAPACITY_DECAY: f64 = 2.0 / 3.0;
+    const MIN_K: usize = 8;
+    /// Bounds the memory of a single sketch. This corresponds to a rank error of about `3e-6`.
+    const MAX_K: usize = 1 << 20;
+
+    /// Creates a new sketch with the accuracy parameter `k`.
+    /// Larger values of `k` yield more accurate estimates at the cost of memory.
+    ///
+    /// # Panics
+    /// If `k` is smaller than 8 or larger than 2^20.
+    pub fn new(k: usize) -> Self {
+        assert!(k >= Self::MIN_K, "The parameter `k` must be at least 8");
+        assert!(k <= Self::MAX_K, "The parameter `k` must be at most 2^20");
+
+        let mut sketch = Self {
+            k,
+            compactors: Vec::new(),
+            offsets: Vec::new(),
+            size: 0,
+            max_size: 0,
+            sample_count: 0,
+        };
+        sketch.grow();
+        sketch
+    }
+
+    /// Creates a new sketch whose normalized rank error is approximately bounded by `max_rank_error`.
+    ///
+    /// The relation `epsilon ≈ 3.3 / k` follows the empirical error bounds reported for the sketch.
+    ///
+    /// # Errors
+    /// If `max_rank_error` is not within the interval [3.3 / 2^20, 1).
+    pub fn with_max_rank_error(max_rank_error: f64) -> Result<Self, StatisticsError> {
+        if max_rank_error.is_nan() || max_rank_error <= 0.0 || max_rank_error >= 1.0 {
+            return Err(StatisticsError::Initialization {
+                reason: "The maximum rank error must be in the interval (0,1).".to_owned(),
+            });
+        }
+
+        if max_rank_error < 3.3 / Self::MAX_K as f64 {
+            return Err(StatisticsError::Initialization {
+                reason: format!(
+                    "The maximum rank error must be at least {}.",
+                    3.3 / Self::MAX_K as f64
+                ),
+            });
+        }
+
+        let k = (3.3 / max_rank_error).ceil() as usize;
+
+        Ok(Self::new(k.clamp(Self::MIN_K, Self::MAX_K)))
+    }
+
+    /// Returns the accuracy parameter `k`
+    pub fn k(&self) -> usize {
+        self.k
+    }
+
+    /// Returns the number of samples seen so far
+    pub fn sample_count(&self) -> u64 {
+        self.sample_count
+    }
+
+    /// Returns `true` if no valid sample was recorded so far.
+    pub fn is_empty(&self) -> bool {
+        self.sample_count == 0
+    }
+
+    /// Returns the number of samples that are currently retained.
+    pub fn retained(&self) -> usize {
+        self.size
+    }
+
+    /// Updates the sketch with the given sample.
+    ///
+    /// # Note
+    ///
+    /// A `sample` that does not satisfy `f64::is_finite` is silently ignored.
+    pub fn update<T: AsPrimitive<f64>>(&mut self, sample: T) {
+        let value = sample.as_();
+
+        if !value.is_finite() {
+            return;
+        }
+
+        self.compactors[0].push(value);
+        self.size += 1;
+        self.sample_count += 1;
+
+        if self.size >= self.max_size {
+            self.compress();
+        }
+    }
+
+    /// Merges the samples of `other` into this sketch.
+    pub fn merge(&mut self, other: &KllSketch) {
+        while self.compactors.len() < other.compactors.len() {
+            self.grow();
+        }
+
+        for (compactor, other_compactor) in self.compactors.iter_mut().zip(&other.compactors) {
+            compactor.extend_from_slice(other_compactor);
+        }
+
+        self.sample_count += other.sample_count;
+        self.size = self.compactors.iter().map(Vec::len).sum();
+
+        while self.size >= self.max_size {
+            self.compress();
+        }
+    }
+
+    /// Returns the estimate of the given `quantile` or `None` if the sketch is empty.
+    ///
+    /// # Panics
+    /// If the given quantile is not within the interval [0,1].
+    pub fn quantile_estimate(&self, quantile: f64) -> Option<f64> {
+        self.quantile_estimates(&[quantile]).pop().flatten()
+    }
+
+    /// Returns the estimates of the given `quantiles`.
+    /// The estimates are `None` if the sketch is empty.
+    ///
+    /// # Panics
+    /// If any of the given quantiles is not within the interval [0,1].
+    pub fn quantile_estimates(&self, quantiles: &[f64]) -> Vec<Option<f64>> {
+        assert!(
+            quantiles.iter().all(|q| (0.0..=1.0).contains(q)),
+            "The desired quantiles must be in the interval [0,1]"
+        );
+
+        if self.is_empty() {
+            return vec![None; quantiles.len()];
+        }
+
+        let mut weighted_values = self
+            .compactors
+            .iter()
+            .enumerate()
+            .flat_map(|(level, compactor)| compactor.iter().map(move |&v| (v, 1_u64 << level)))
+            .collect::<Vec<_>>();
+
+        weighted_values.sort_unstable_by(|(a, _), (b, _)| a.total_cmp(b));
+
+        let total_weight: u64 = weighted_values.iter().map(|(_, w)| w).sum();
+
+        quantiles
+            .iter()
+            .map(|&quantile| {
+                let target_rank = quantile * total_weight as f64;
+                let mut cumulative_weight = 0;
+
+                for &(value, weight) in &weighted_values {
+                    cumulative_weight += weight;
+                    if cumulative_weight as f64 >= target_rank {
+                        return Some(value);
+                    }
+                }
+
+                weighted_values.last().map(|(v, _)| *v)
+            })
+            .collect()
+    }
+
+    fn capacity(&self, level: usize) -> usize {
+        let depth = self.compactors.len() - level - 1;
+        (Self::CAPACITY_DECAY.powi(depth as i32) * self.k as f64).ceil() as usize + 1
+    }
+
+    fn grow(&mut self) {
+        self.compactors.push(Vec::new());
+        self.offsets.push(false);
+        self.max_size = (0..self.compactors.len())
+            .map(|level| self.capacity(level))
+            .sum();
+    }
+
+    fn compress(&mut self) {
+        for level in 0..self.compactors.len() {
+            if self.compactors[level].len() < self.capacity(level) {
+                continue;
+            }
+
+            if level + 1 >= self.compactors.len() {
+                self.grow();
+            }
+
+            let promoted = self.compact(level);
+            self.compactors[level + 1].extend(promoted);
+
+            self.size = self.compactors.iter().map(Vec::len).sum();
+            if self.size < self.max_size {
+                break;
+            }
+        }
+    }
+
+    /// Sorts the compactor of `level` and removes all but (at most) one of its elements.
+    /// Returns every second of the removed elements.
+    fn compact(&mut self, level: usize) -> Vec<f64> {
+        let offset = usize::from(self.offsets[level]);
+        self.offsets[level] = !self.offsets[level];
+
+        let compactor = &mut self.compactors[level];
+        compactor.sort_unstable_by(f64::total_cmp);
+
+        // keep one element if the number of elements is odd
+        let remainder = if compactor.len() % 2 == 1 {
+            compactor.pop()
+        } else {
+            None
+        };
+
+        let promoted = compactor
+            .iter()
+            .skip(offset)
+            .step_by(2)
+            .copied()
+            .collect::<Vec<_>>();
+
+        compactor.clear();
+        compactor.extend(remainder);
+
+        promoted
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::util::statistics::{PSquareHistogram, PSquareQuantileEstimator};
+    use crate::util::statistics::{KllSketch, PSquareHistogram, PSquareQuantileEstimator};
     use rand::seq::SliceRandom;
 
     #[test]
@@ -735,4 +993,88 @@ mod tests {
 
         assert_eq!(samples + 1, estimator.sample_count());
     }
+
+    #[test]
+    fn test_kll_sketch() {
+        let mut values = (1..=10_000).map(f64::from).collect::<Vec<_>>();
+        values.shuffle(&mut rand::thread_rng());
+
+        let mut sketch = KllSketch::with_max_rank_error(0.01).unwrap();
+
+        for v in &values {
+            sketch.update(*v);
+        }
+
+        assert_eq!(sketch.sample_count(), 10_000);
+        assert!(sketch.retained() < 10_000);
+
+        for (quantile, estimate) in [0.1, 0.5, 0.9]
+            .into_iter()
+            .zip(sketch.quantile_estimates(&[0.1, 0.5, 0.9]))
+        {
+            let estimate = estimate.unwrap();
+            assert!(
+                (estimate - quantile * 10_000.).abs() <= 200.,
+                "estimate {estimate} for quantile {quantile} is off"
+            );
+        }
+    }
+
+    #[test]
+    fn test_kll_sketch_exact_for_small_inputs() {
+        let mut sketch = KllSketch::new(200);
+
+        for v in [5, 1, 4, 2, 3] {
+            sketch.update(v);
+        }
+
+        assert_eq!(sketch.quantile_estimate(0.0), Some(1.0));
+        assert_eq!(sketch.quantile_estimate(0.5), Some(3.0));
+        assert_eq!(sketch.quantile_estimate(1.0), Some(5.0));
+    }
+
+    #[test]
+    fn test_kll_sketch_merge() {
+        let mut left = KllSketch::new(100);
+        let mut right = KllSketch::new(100);
+
+        for v in 0..5_000 {
+            left.update(v);
+        }
+        for v in 5_000..10_000 {
+            right.update(v);
+        }
+
+        left.merge(&right);
+
+        assert_eq!(left.sample_count(), 10_000);
+
+        let median = left.quantile_estimate(0.5).unwrap();
+        assert!((median - 5_000.).abs() <= 500., "median {median} is off");
+    }
+
+    #[test]
+    fn test_kll_sketch_bad_values() {
+        let mut sketch = KllSketch::new(8);
+
+        assert!(sketch.is_empty());
+        assert_eq!(sketch.quantile_estimate(0.5), None);
+
+        sketch.update(f64::NAN);
+        sketch.update(f64::INFINITY);
+
+        assert!(sketch.is_empty());
+
+        assert!(KllSketch::with_max_rank_error(0.0).is_err());
+        assert!(KllSketch::with_max_rank_error(1.0).is_err());
+    }
+
+    #[test]
+    fn test_kll_sketch_rejects_tiny_rank_errors() {
+        assert!(KllSketch::with_max_rank_error(1e-300).is_err());
+        assert!(KllSketch::with_max_rank_error(f64::MIN_POSITIVE).is_err());
+
+        let sketch = KllSketch::with_max_rank_error(3.3 / KllSketch::MAX_K as f64).unwrap();
+        assert_eq!(sketch.k(), KllSketch::MAX_K);
+    }
 }
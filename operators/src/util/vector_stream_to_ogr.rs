@@ -0,0 +1,527 @@
+use crate::engine::{QueryContext, VectorQueryProcessor};
+use crate::source::{
+    OgrSourceColumnSpec, OgrSourceDataset, OgrSourceDatasetTimeType, OgrSourceErrorSpec,
+    OgrSourceTimeFormat,
+};
+use crate::util::Result;
+use futures::future::BoxFuture;
+use futures::StreamExt;
+use gdal::spatial_ref::SpatialRef;
+use gdal::vector::{
+    Feature, FieldValue, LayerAccess, LayerOptions, OGRFieldType, OGRwkbGeometryType,
+};
+use gdal::{Dataset, DriverManager};
+use geoengine_datatypes::collections::{
+    FeatureCollection, FeatureCollectionInfos, IntoGeometryOptionsIterator, VectorDataType,
+};
+use geoengine_datatypes::primitives::{
+    CacheTtlSeconds, FeatureDataType, FeatureDataValue, Geometry, VectorQueryRectangle,
+};
+use geoengine_datatypes::spatial_reference::{SpatialReference, SpatialReferenceOption};
+use geoengine_datatypes::util::arrow::ArrowTyped;
+use std::path::{Path, PathBuf};
+use wkt::ToWkt;
+
+use super::{abortable_query_execution, spawn_blocking};
+
+const TIME_START_COLUMN: &str = "time_start";
+const TIME_END_COLUMN: &str = "time_end";
+
+/// The OGR formats that vector streams can be written to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OgrVectorFormat {
+    GeoPackage,
+    FlatGeobuf,
+    Csv,
+    GeoJson,
+}
+
+impl OgrVectorFormat {
+    fn driver_name(self) -> &'static str {
+        match self {
+            Self::GeoPackage => "GPKG",
+            Self::FlatGeobuf => "FlatGeobuf",
+            Self::Csv => "CSV",
+            Self::GeoJson => "GeoJSON",
+        }
+    }
+
+    pub fn file_extension(self) -> &'static str {
+        match self {
+            Self::GeoPackage => "gpkg",
+            Self::FlatGeobuf => "fgb",
+            Self::Csv => "csv",
+            Self::GeoJson => "geojson",
+        }
+    }
+
+    /// Inserting features one by one into a `GeoPackage` is slow without transactions
+    fn supports_transactions(self) -> bool {
+        match self {
+            Self::GeoPackage => true,
+            Self::FlatGeobuf | Self::Csv | Self::GeoJson => false,
+        }
+    }
+
+    fn layer_options(self) -> Option<&'static [&'static str]> {
+        match self {
+            // otherwise, CSV files would not contain any geometries
+            Self::Csv => Some(&["GEOMETRY=AS_WKT"]),
+            Self::GeoPackage | Self::FlatGeobuf | Self::GeoJson => None,
+        }
+    }
+}
+
+/// Consumes a vector stream and writes it into a single layer of a new OGR dataset at `file_path`.
+///
+/// Returns the loading info for reading the written features with an `OgrSource`.
+/// The validity of the features is stored as epoch milliseconds in additional columns.
+/// Categories are stored as integers, so they become integer columns of the new dataset.
+pub async fn vector_stream_to_ogr<G, C: QueryContext + 'static>(
+    file_path: &Path,
+    processor: Box<dyn VectorQueryProcessor<VectorType = FeatureCollection<G>>>,
+    query_rect: VectorQueryRectangle,
+    mut query_ctx: C,
+    format: OgrVectorFormat,
+    conn_closed: BoxFuture<'_, ()>,
+) -> Result<OgrSourceDataset>
+where
+    G: Geometry + ArrowTyped + 'static,
+    for<'i> FeatureCollection<G>: IntoGeometryOptionsIterator<'i>,
+{
+    let query_abort_trigger = query_ctx.abort_trigger()?;
+
+    let result_descriptor = processor.vector_result_descriptor();
+    let data_type = result_descriptor.data_type;
+    let spatial_reference = result_descriptor.spatial_reference;
+    let mut columns = result_descriptor
+        .columns
+        .iter()
+        .map(|(name, info)| (name.clone(), info.data_type))
+        .collect::<Vec<_>>();
+    columns.sort();
+
+    let file_path = file_path.to_owned();
+    let writer = spawn_blocking(move || {
+        OgrDatasetWriter::create(file_path, format, data_type, spatial_reference, columns)
+    })
+    .await??;
+
+    let execution = async {
+        let stream = processor.vector_query(query_rect, &query_ctx).await?;
+
+        let writer = stream
+            .fold(
+                Ok(writer),
+                |writer: Result<OgrDatasetWriter>, collection| async move {
+                    let mut writer = writer?;
+                    let collection = collection?;
+
+                    spawn_blocking(move || -> Result<OgrDatasetWriter> {
+                        writer.write_collection(&collection)?;
+                        Ok(writer)
+                    })
+                    .await?
+                },
+            )
+            .await?;
+
+        spawn_blocking(move || writer.finish()).await?
+    };
+
+    abortable_query_execution(execution, conn_closed, query_abort_trigger).await
+}
+
+struct OgrDatasetWriter {
+    dataset: Dataset,
+    file_path: PathBuf,
+    layer_name: String,
+    format: OgrVectorFormat,
+    data_type: VectorDataType,
+    columns: Vec<(String, FeatureDataType)>,
+    time_start_column: String,
+    time_end_column: String,
+}
+
+impl OgrDatasetWriter {
+    fn create(
+        file_path: PathBuf,
+        format: OgrVectorFormat,
+        data_type: VectorDataType,
+        spatial_reference: SpatialReferenceOption,
+        columns: Vec<(String, FeatureDataType)>,
+    ) -> Result<Self> {
+        let layer_name = file_path.file_stem().map_or_else(
+            || "features".to_string(),
+            |stem| stem.to_string_lossy().to_string(),
+        );
+
+        let time_start_column = unused_column_name(TIME_START_COLUMN, &columns);
+        let time_end_column = unused_column_name(TIME_END_COLUMN, &columns);
+
+        let srs = Option::<SpatialReference>::from(spatial_reference)
+            .map(SpatialRef::try_from)
+            .transpose()?;
+
+        let driver = DriverManager::get_driver_by_name(format.driver_name())?;
+        let mut dataset = driver.create_vector_only(&file_path)?;
+
+        let layer = dataset.create_layer(LayerOptions {
+            name: &layer_name,
+            srs: srs.as_ref(),
+            ty: ogr_geometry_type(data_type),
+            options: format.layer_options(),
+        })?;
+
+        let mut field_definitions = vec![
+            (time_start_column.as_str(), OGRFieldType::OFTInteger64),
+            (time_end_column.as_str(), OGRFieldType::OFTInteger64),
+        ];
+        for (name, data_type) in &columns {
+            field_definitions.push((name.as_str(), ogr_field_type(*data_type)));
+        }
+        layer.create_defn_fields(&field_definitions)?;
+
+        drop(layer);
+
+        Ok(Self {
+            dataset,
+            file_path,
+            layer_name,
+            format,
+            data_type,
+            columns,
+            time_start_column,
+            time_end_column,
+        })
+    }
+
+    fn write_collection<G>(&mut self, collection: &FeatureCollection<G>) -> Result<()>
+    where
+        G: Geometry + ArrowTyped,
+        for<'i> FeatureCollection<G>: IntoGeometryOptionsIterator<'i>,
+    {
+        if !self.format.supports_transactions() {
+            return write_features(
+                &self.dataset,
+                &self.columns,
+                &self.time_start_column,
+                &self.time_end_column,
+                collection,
+            );
+        }
+
+        let transaction = self.dataset.start_transaction()?;
+        write_features(
+            &transaction,
+            &self.columns,
+            &self.time_start_column,
+            &self.time_end_column,
+            collection,
+        )?;
+        transaction.commit()?;
+
+        Ok(())
+    }
+
+    /// Closes the dataset, s.t. all features are flushed to the file
+    fn finish(self) -> Result<OgrSourceDataset> {
+        drop(self.dataset);
+
+        let mut columns = OgrSourceColumnSpec {
+            format_specifics: None,
+            x: String::new(),
+            y: None,
+            int: vec![],
+            float: vec![],
+            text: vec![],
+            bool: vec![],
+            datetime: vec![],
+            rename: None,
+        };
+
+        for (name, data_type) in self.columns {
+            match data_type {
+                FeatureDataType::Category | FeatureDataType::Int => columns.int.push(name),
+                FeatureDataType::Float => columns.float.push(name),
+                FeatureDataType::Text => columns.text.push(name),
+                FeatureDataType::Bool => columns.bool.push(name),
+                FeatureDataType::DateTime => columns.datetime.push(name),
+            }
+        }
+
+        Ok(OgrSourceDataset {
+            file_name: self.file_path,
+            layer_name: self.layer_name,
+            data_type: Some(self.data_type),
+            time: OgrSourceDatasetTimeType::StartEnd {
+                start_field: self.time_start_column,
+                start_format: OgrSourceTimeFormat::milliseconds(),
+                end_field: self.time_end_column,
+                end_format: OgrSourceTimeFormat::milliseconds(),
+            },
+            default_geometry: None,
+            columns: Some(columns),
+            force_ogr_time_filter: false,
+            force_ogr_spatial_filter: false,
+            on_error: OgrSourceErrorSpec::Abort,
+            sql_query: None,
+            attribute_query: None,
+            cache_ttl: CacheTtlSeconds::default(),
+        })
+    }
+}
+
+fn write_features<G>(
+    dataset: &Dataset,
+    columns: &[(String, FeatureDataType)],
+    time_start_column: &str,
+    time_end_column: &str,
+    collection: &FeatureCollection<G>,
+) -> Result<()>
+where
+    G: Geometry + ArrowTyped,
+    for<'i> FeatureCollection<G>: IntoGeometryOptionsIterator<'i>,
+{
+    let layer = dataset.layer(0)?;
+
+    let column_data = columns
+        .iter()
+        .map(|(name, _)| collection.data(name))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    for (feature_index, (geometry, time)) in collection
+        .geometry_options()
+        .zip(collection.time_intervals())
+        .enumerate()
+    {
+        let mut feature = Feature::new(layer.defn())?;
+
+        if let Some(geometry) = geometry {
+            feature.set_geometry(gdal::vector::Geometry::from_wkt(&geometry.wkt_string())?)?;
+        }
+
+        feature.set_field(
+            time_start_column,
+            &FieldValue::Integer64Value(time.start().inner()),
+        )?;
+        feature.set_field(
+            time_end_column,
+            &FieldValue::Integer64Value(time.end().inner()),
+        )?;
+
+        for ((name, _), data) in columns.iter().zip(&column_data) {
+            // unset fields are null
+            if let Some(value) = ogr_field_value(data.get_unchecked(feature_index)) {
+                feature.set_field(name, &value)?;
+            }
+        }
+
+        feature.create(&layer)?;
+    }
+
+    Ok(())
+}
+
+/// Prefixes `name` with underscores until it does not collide with any column
+fn unused_column_name(name: &str, columns: &[(String, FeatureDataType)]) -> String {
+    let mut name = name.to_string();
+    while columns.iter().any(|(column, _)| *column == name) {
+        name.insert(0, '_');
+    }
+    name
+}
+
+fn ogr_geometry_type(data_type: VectorDataType) -> OGRwkbGeometryType::Type {
+    match data_type {
+        VectorDataType::Data => OGRwkbGeometryType::wkbNone,
+        VectorDataType::MultiPoint => OGRwkbGeometryType::wkbMultiPoint,
+        VectorDataType::MultiLineString => OGRwkbGeometryType::wkbMultiLineString,
+        VectorDataType::MultiPolygon => OGRwkbGeometryType::wkbMultiPolygon,
+    }
+}
+
+fn ogr_field_type(data_type: FeatureDataType) -> OGRFieldType::Type {
+    match data_type {
+        FeatureDataType::Category | FeatureDataType::Bool => OGRFieldType::OFTInteger,
+        FeatureDataType::Int | FeatureDataType::DateTime => OGRFieldType::OFTInteger64,
+        FeatureDataType::Float => OGRFieldType::OFTReal,
+        FeatureDataType::Text => OGRFieldType::OFTString,
+    }
+}
+
+fn ogr_field_value(value: FeatureDataValue) -> Option<FieldValue> {
+    match value {
+        FeatureDataValue::Category(value) | FeatureDataValue::NullableCategory(Some(value)) => {
+            Some(FieldValue::IntegerValue(value.into()))
+        }
+        FeatureDataValue::Int(value) | FeatureDataValue::NullableInt(Some(value)) => {
+            Some(FieldValue::Integer64Value(value))
+        }
+        FeatureDataValue::Float(value) | FeatureDataValue::NullableFloat(Some(value)) => {
+            Some(FieldValue::RealValue(value))
+        }
+        FeatureDataValue::Text(value) | FeatureDataValue::NullableText(Some(value)) => {
+            Some(FieldValue::StringValue(value))
+        }
+        FeatureDataValue::Bool(value) | FeatureDataValue::NullableBool(Some(value)) => {
+            Some(FieldValue::IntegerValue(value.into()))
+        }
+        FeatureDataValue::DateTime(value) | FeatureDataValue::NullableDateTime(Some(value)) => {
+            Some(FieldValue::Integer64Value(value.inner()))
+        }
+        FeatureDataValue::NullableCategory(None)
+        | FeatureDataValue::NullableInt(None)
+        | FeatureDataValue::NullableFloat(None)
+        | FeatureDataValue::NullableText(None)
+        | FeatureDataValue::NullableBool(None)
+        | FeatureDataValue::NullableDateTime(None) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{
+        InitializedVectorOperator, MockExecutionContext, MockQueryContext, StaticMetaData,
+        VectorOperator, VectorResultDescriptor, WorkflowOperatorPath,
+    };
+    use crate::mock::MockFeatureCollectionSource;
+    use crate::source::{OgrSource, OgrSourceParameters};
+    use geoengine_datatypes::collections::MultiPointCollection;
+    use geoengine_datatypes::dataset::{DataId, DatasetId, NamedData};
+    use geoengine_datatypes::primitives::{
+        BoundingBox2D, CacheHint, ColumnSelection, FeatureData, MultiPoint, SpatialResolution,
+        TimeInterval,
+    };
+    use geoengine_datatypes::util::Identifier;
+
+    #[tokio::test]
+    async fn it_writes_and_reads_a_geopackage() {
+        let collection = MultiPointCollection::from_data(
+            MultiPoint::many(vec![(0.0, 0.1), (1.0, 1.1)]).unwrap(),
+            vec![
+                TimeInterval::new(0, 1).unwrap(),
+                TimeInterval::new(1_000, 2_000).unwrap(),
+            ],
+            [
+                (
+                    "int".to_string(),
+                    FeatureData::NullableInt(vec![Some(42), None]),
+                ),
+                (
+                    "text".to_string(),
+                    FeatureData::NullableText(vec![
+                        Some("foo".to_string()),
+                        Some("bar".to_string()),
+                    ]),
+                ),
+            ]
+            .into_iter()
+            .collect(),
+            CacheHint::default(),
+        )
+        .unwrap();
+
+        let processor = MockFeatureCollectionSource::single(collection)
+            .boxed()
+            .initialize(
+                WorkflowOperatorPath::initialize_root(),
+                &MockExecutionContext::test_default(),
+            )
+            .await
+            .unwrap();
+        let result_descriptor = processor.result_descriptor().clone();
+        let processor = processor.query_processor().unwrap().multi_point().unwrap();
+
+        let query_rect = VectorQueryRectangle {
+            spatial_bounds: BoundingBox2D::new((-10., -10.).into(), (10., 10.).into()).unwrap(),
+            time_interval: TimeInterval::default(),
+            spatial_resolution: SpatialResolution::one(),
+            attributes: ColumnSelection::all(),
+        };
+
+        let directory = tempfile::tempdir().unwrap();
+        let file_path = directory.path().join("points.gpkg");
+
+        let loading_info = vector_stream_to_ogr(
+            &file_path,
+            processor,
+            query_rect.clone(),
+            MockQueryContext::test_default(),
+            OgrVectorFormat::GeoPackage,
+            Box::pin(futures::future::pending()),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(loading_info.layer_name, "points");
+        assert_eq!(
+            loading_info.columns.as_ref().map(|c| (&c.int, &c.text)),
+            Some((&vec!["int".to_string()], &vec!["text".to_string()]))
+        );
+
+        let id: DataId = DatasetId::new().into();
+        let name = NamedData::with_system_name("points");
+        let mut exe_ctx = MockExecutionContext::test_default();
+        exe_ctx.add_meta_data::<OgrSourceDataset, VectorResultDescriptor, VectorQueryRectangle>(
+            id,
+            name.clone(),
+            Box::new(StaticMetaData {
+                loading_info,
+                result_descriptor,
+                phantom: Default::default(),
+            }),
+        );
+
+        let source = OgrSource {
+            params: OgrSourceParameters {
+                data: name,
+                attribute_projection: None,
+                attribute_filters: None,
+            },
+        }
+        .boxed()
+        .initialize(WorkflowOperatorPath::initialize_root(), &exe_ctx)
+        .await
+        .unwrap()
+        .query_processor()
+        .unwrap()
+        .multi_point()
+        .unwrap();
+
+        let query_ctx = MockQueryContext::test_default();
+        let collections: Vec<MultiPointCollection> = source
+            .vector_query(query_rect, &query_ctx)
+            .await
+            .unwrap()
+            .map(Result::unwrap)
+            .collect()
+            .await;
+
+        assert_eq!(collections.len(), 1);
+        let collection = &collections[0];
+
+        assert_eq!(collection.len(), 2);
+        assert_eq!(
+            collection.time_intervals(),
+            &[
+                TimeInterval::new(0, 1).unwrap(),
+                TimeInterval::new(1_000, 2_000).unwrap(),
+            ]
+        );
+        assert_eq!(
+            collection.data("int").unwrap().get_unchecked(0),
+            FeatureDataValue::NullableInt(Some(42))
+        );
+        assert_eq!(
+            collection
+                .data("text")
+                .unwrap()
+                .strings_iter()
+                .collect::<Vec<_>>(),
+            vec!["foo".to_string(), "bar".to_string()]
+        );
+        assert!(collection.data("int").unwrap().nulls()[1]);
+    }
+}
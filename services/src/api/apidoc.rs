@@ -1,7 +1,9 @@
 use super::handlers;
 use super::handlers::plots::WrappedPlotOutput;
 use super::handlers::spatial_references::{AxisOrder, SpatialReferenceSpecification};
-use super::handlers::tasks::{TaskAbortOptions, TaskResponse};
+use super::handlers::tasks::{
+    AddRecurringTask, RecurringTaskResponse, TaskAbortOptions, TaskResponse,
+};
 use super::handlers::upload::{UploadFileLayersResponse, UploadFilesResponse};
 use super::handlers::wfs::{CollectionType, GeoJson};
 use super::handlers::workflows::{ProvenanceEntry, RasterStreamWebsocketResultType};
@@ -34,17 +36,25 @@ use crate::api::model::responses::{
 };
 use crate::api::model::services::{
     AddDataset, CreateDataset, DataPath, DatasetDefinition, MetaDataDefinition, MetaDataSuggestion,
-    Provenance, ProvenanceOutput, Provenances, UpdateDataset,
+    Provenance, ProvenanceOutput, Provenances, ReplaceDatasetMetaData, UpdateDataset,
 };
 use crate::api::ogc::{util::OgcBoundingBox, wcs, wfs, wms};
 use crate::contexts::{SessionId, SimpleSession};
 use crate::datasets::listing::{DatasetListing, OrderBy};
-use crate::datasets::storage::{AutoCreateDataset, Dataset};
+use crate::datasets::resumable_upload::{
+    ResumableFileCreation, ResumableFileStatus, ResumableUploadCreation, ResumableUploadStatus,
+};
+use crate::datasets::storage::{AutoCreateDataset, Dataset, DatasetVersion};
 use crate::datasets::upload::{UploadId, Volume, VolumeName};
-use crate::datasets::{DatasetName, RasterDatasetFromWorkflow, RasterDatasetFromWorkflowResult};
+use crate::datasets::{
+    DatasetName, RasterDatasetFromWorkflow, RasterDatasetFromWorkflowResult,
+    RecurringDatasetFromWorkflow, VectorDatasetFormat, VectorDatasetFromWorkflow,
+    VectorDatasetFromWorkflowResult,
+};
 use crate::layers::layer::{
     AddLayer, AddLayerCollection, CollectionItem, Layer, LayerCollection, LayerCollectionListing,
-    LayerListing, Property, ProviderLayerCollectionId, ProviderLayerId,
+    LayerListing, Property, ProviderLayerCollectionId, ProviderLayerId, UpdateLayer,
+    UpdateLayerCollection,
 };
 use crate::layers::listing::{
     LayerCollectionId, ProviderCapabilities, SearchCapabilities, SearchType, SearchTypes,
@@ -55,11 +65,15 @@ use crate::projects::{
     ProjectId, ProjectLayer, ProjectListing, ProjectUpdateToken, ProjectVersion, ProjectVersionId,
     RasterSymbology, STRectangle, StrokeParam, Symbology, TextSymbology, UpdateProject,
 };
-use crate::tasks::{TaskFilter, TaskId, TaskListOptions, TaskStatus, TaskStatusWithId};
+use crate::tasks::{
+    RecurringTaskId, TaskFilter, TaskId, TaskListOptions, TaskPriority, TaskStatus,
+    TaskStatusWithId,
+};
 use crate::util::{
     apidoc::{OpenApiServerInfo, TransformSchemasWithTag},
     server::ServerInfo,
 };
+use crate::workflows::export::{ExportFormat, ExportId, WorkflowExport, WorkflowExportResult};
 use crate::workflows::workflow::{Workflow, WorkflowId};
 use utoipa::openapi::security::{HttpAuthScheme, HttpBuilder, SecurityScheme};
 use utoipa::{Modify, OpenApi};
@@ -77,6 +91,8 @@ use utoipa::{Modify, OpenApi};
         handlers::layers::autocomplete_handler,
         handlers::layers::provider_capabilities_handler,
         handlers::layers::add_layer,
+        handlers::layers::update_layer,
+        handlers::layers::update_collection,
         handlers::layers::add_collection,
         handlers::layers::remove_collection,
         handlers::layers::remove_layer_from_collection,
@@ -91,6 +107,9 @@ use utoipa::{Modify, OpenApi};
         handlers::tasks::abort_handler,
         handlers::tasks::list_handler,
         handlers::tasks::status_handler,
+        handlers::tasks::add_recurring_task_handler,
+        handlers::tasks::list_recurring_tasks_handler,
+        handlers::tasks::remove_recurring_task_handler,
         handlers::wcs::wcs_capabilities_handler,
         handlers::wcs::wcs_describe_coverage_handler,
         handlers::wcs::wcs_get_coverage_handler,
@@ -100,6 +119,9 @@ use utoipa::{Modify, OpenApi};
         handlers::wms::wms_legend_graphic_handler,
         handlers::wms::wms_map_handler,
         handlers::workflows::dataset_from_workflow_handler,
+        handlers::workflows::vector_dataset_from_workflow_handler,
+        handlers::workflows::workflow_export_handler,
+        handlers::workflows::download_workflow_export_handler,
         handlers::workflows::get_workflow_metadata_handler,
         handlers::workflows::get_workflow_provenance_handler,
         handlers::workflows::load_workflow_handler,
@@ -117,6 +139,10 @@ use utoipa::{Modify, OpenApi};
         handlers::datasets::get_loading_info_handler,
         handlers::datasets::update_dataset_symbology_handler,
         handlers::datasets::update_dataset_provenance_handler,
+        handlers::datasets::replace_loading_info_handler,
+        handlers::datasets::list_dataset_versions_handler,
+        handlers::datasets::restore_dataset_version_handler,
+        handlers::datasets::garbage_collect_dataset_versions_handler,
         handlers::spatial_references::get_spatial_reference_specification_handler,
         handlers::plots::get_plot_handler,
         handlers::projects::list_projects_handler,
@@ -128,6 +154,11 @@ use utoipa::{Modify, OpenApi};
         handlers::projects::load_project_version_handler,
         handlers::upload::list_upload_files_handler,
         handlers::upload::list_upload_file_layers_handler,
+        handlers::upload::create_resumable_upload_handler,
+        handlers::upload::resumable_upload_status_handler,
+        handlers::upload::append_to_resumable_upload_handler,
+        handlers::upload::finish_resumable_upload_handler,
+        handlers::upload::abort_resumable_upload_handler,
         handlers::upload::upload_handler
     ),
     components(
@@ -139,6 +170,7 @@ use utoipa::{Modify, OpenApi};
             IdResponse::<LayerId>,
             IdResponse::<LayerCollectionId>,
             IdResponse::<ProjectId>,
+            IdResponse::<RecurringTaskId>,
             DatasetNameResponse,
             UnauthorizedAdminResponse,
             UnauthorizedUserResponse,
@@ -204,6 +236,13 @@ use utoipa::{Modify, OpenApi};
             VectorColumnInfo,
             RasterDatasetFromWorkflow,
             RasterDatasetFromWorkflowResult,
+            VectorDatasetFromWorkflow,
+            VectorDatasetFromWorkflowResult,
+            VectorDatasetFormat,
+            WorkflowExport,
+            WorkflowExportResult,
+            ExportFormat,
+            ExportId,
             RasterQueryRectangle,
             VectorQueryRectangle,
             PlotQueryRectangle,
@@ -211,10 +250,15 @@ use utoipa::{Modify, OpenApi};
 
             TaskAbortOptions,
             TaskFilter,
+            TaskPriority,
             TaskListOptions,
             TaskStatus,
             TaskStatusWithId,
             TaskResponse,
+            AddRecurringTask,
+            RecurringTaskResponse,
+            RecurringTaskId,
+            RecurringDatasetFromWorkflow,
 
             Layer,
             LayerListing,
@@ -224,6 +268,8 @@ use utoipa::{Modify, OpenApi};
             CollectionItem,
             AddLayer,
             AddLayerCollection,
+            UpdateLayer,
+            UpdateLayerCollection,
             SearchCapabilities,
             ProviderCapabilities,
             SearchTypes,
@@ -278,6 +324,10 @@ use utoipa::{Modify, OpenApi};
             CollectionType,
 
             UploadFilesResponse,
+            ResumableUploadCreation,
+            ResumableFileCreation,
+            ResumableUploadStatus,
+            ResumableFileStatus,
             UploadFileLayersResponse,
             CreateDataset,
             UpdateDataset,
@@ -329,6 +379,8 @@ use utoipa::{Modify, OpenApi};
             Volume,
             VolumeName,
             DataPath,
+            ReplaceDatasetMetaData,
+            DatasetVersion,
 
             PlotOutputFormat,
             WrappedPlotOutput,
@@ -3,13 +3,13 @@ use crate::{
         responses::datasets::{errors::*, DatasetNameResponse},
         services::{
             AddDataset, CreateDataset, DataPath, DatasetDefinition, MetaDataDefinition,
-            MetaDataSuggestion, Provenances, UpdateDataset,
+            MetaDataSuggestion, Provenances, ReplaceDatasetMetaData, UpdateDataset,
         },
     },
     contexts::{ApplicationContext, SessionContext},
     datasets::{
         listing::{DatasetListOptions, DatasetProvider},
-        storage::{AutoCreateDataset, DatasetStore, SuggestMetaData},
+        storage::{AutoCreateDataset, DatasetStore, DatasetVersion, SuggestMetaData},
         upload::{AdjustFilePath, Upload, UploadDb, UploadId, UploadRootPath, Volume, VolumeName},
         DatasetName,
     },
@@ -24,27 +24,41 @@ use crate::{
 use actix_web::{web, FromRequest, HttpResponse, HttpResponseBuilder, Responder};
 use gdal::{
     vector::{Layer, LayerAccess, OGRFieldType},
-    Dataset, DatasetOptions,
+    Dataset, DatasetOptions, Metadata,
 };
 use geoengine_datatypes::{
     collections::VectorDataType,
-    primitives::{CacheTtlSeconds, FeatureDataType, Measurement, VectorQueryRectangle},
+    primitives::{
+        CacheTtlSeconds, DateTime, DateTimeParseFormat, FeatureDataType, Measurement,
+        SpatialPartitioned, TimeGranularity, TimeInstance, TimeInterval, TimeStep,
+        VectorQueryRectangle,
+    },
     spatial_reference::{SpatialReference, SpatialReferenceOption},
 };
 use geoengine_operators::{
-    engine::{StaticMetaData, VectorColumnInfo, VectorResultDescriptor},
+    engine::{
+        RasterBandDescriptor, RasterBandDescriptors, StaticMetaData, VectorColumnInfo,
+        VectorResultDescriptor,
+    },
     source::{
+        FileNotFoundHandling, GdalDatasetParameters, GdalLoadingInfoTemporalSlice,
+        GdalMetaDataList, GdalMetaDataRegular, GdalMetaDataStatic, GdalSourceTimePlaceholder,
         OgrSourceColumnSpec, OgrSourceDataset, OgrSourceDatasetTimeType, OgrSourceDurationSpec,
-        OgrSourceErrorSpec, OgrSourceTimeFormat,
+        OgrSourceErrorSpec, OgrSourceTimeFormat, TimeReference,
+    },
+    util::gdal::{
+        gdal_open_dataset, gdal_open_dataset_ex, gdal_parameters_from_dataset,
+        raster_descriptor_from_dataset,
     },
-    util::gdal::{gdal_open_dataset, gdal_open_dataset_ex},
 };
+use serde::Deserialize;
 use snafu::ResultExt;
 use std::{
     collections::HashMap,
     convert::{TryFrom, TryInto},
     path::Path,
 };
+use utoipa::IntoParams;
 
 pub(crate) fn init_dataset_routes<C>(cfg: &mut web::ServiceConfig)
 where
@@ -58,7 +72,17 @@ where
             .service(web::resource("/volumes").route(web::get().to(list_volumes_handler::<C>)))
             .service(
                 web::resource("/{dataset}/loadingInfo")
-                    .route(web::get().to(get_loading_info_handler::<C>)),
+                    .route(web::get().to(get_loading_info_handler::<C>))
+                    .route(web::put().to(replace_loading_info_handler::<C>)),
+            )
+            .service(
+                web::resource("/{dataset}/versions")
+                    .route(web::get().to(list_dataset_versions_handler::<C>))
+                    .route(web::delete().to(garbage_collect_dataset_versions_handler::<C>)),
+            )
+            .service(
+                web::resource("/{dataset}/versions/{version}/restore")
+                    .route(web::post().to(restore_dataset_version_handler::<C>)),
             )
             .service(
                 web::resource("/{dataset}/symbology")
@@ -410,6 +434,235 @@ pub async fn update_dataset_provenance_handler<C: ApplicationContext>(
     Ok(HttpResponse::Ok())
 }
 
+/// Replaces the loading information of a dataset, e.g., to point it to newly uploaded files.
+/// The loading information must be for the source operator of the dataset.
+/// The previous definition is kept as an older version of the dataset.
+#[utoipa::path(
+    tag = "Datasets",
+    put,
+    path = "/dataset/{dataset}/loadingInfo",
+    request_body = ReplaceDatasetMetaData,
+    responses(
+        (status = 200, description = "OK"),
+        (status = 400, description = "Bad request", body = ErrorResponse),
+        (status = 401, response = crate::api::model::responses::UnauthorizedUserResponse)
+    ),
+    params(
+        ("dataset" = DatasetName, description = "Dataset Name"),
+    ),
+    security(
+        ("session_token" = [])
+    )
+)]
+pub async fn replace_loading_info_handler<C: ApplicationContext>(
+    session: C::Session,
+    app_ctx: web::Data<C>,
+    dataset: web::Path<DatasetName>,
+    replacement: web::Json<ReplaceDatasetMetaData>,
+) -> Result<HttpResponseBuilder> {
+    let session_ctx = app_ctx.session_context(session).db();
+
+    let real_dataset = dataset.into_inner();
+
+    let dataset_id = session_ctx
+        .resolve_dataset_name_to_id(&real_dataset)
+        .await?;
+
+    // handle the case where the dataset name is not known
+    let dataset_id = dataset_id.ok_or(error::Error::UnknownDatasetName {
+        dataset_name: real_dataset.to_string(),
+    })?;
+
+    let ReplaceDatasetMetaData {
+        data_path,
+        mut meta_data,
+    } = replacement.into_inner();
+
+    match data_path {
+        DataPath::Upload(upload_id) => {
+            let upload = session_ctx.load_upload(upload_id).await?;
+            adjust_meta_data_path(&mut meta_data, &upload)?;
+        }
+        DataPath::Volume(volume_name) => {
+            let volume_path = get_config_element::<Data>()?
+                .volumes
+                .remove(&volume_name)
+                .ok_or(error::Error::UnknownVolumeName {
+                    volume_name: volume_name.to_string(),
+                })?;
+            let volume = Volume {
+                name: volume_name,
+                path: volume_path,
+            };
+            adjust_meta_data_path(&mut meta_data, &volume)?;
+        }
+    }
+
+    session_ctx
+        .replace_dataset_meta_data(dataset_id, meta_data.into())
+        .await?;
+
+    Ok(HttpResponse::Ok())
+}
+
+/// Lists all versions of a dataset, starting with the most recent one.
+/// The `id` of a version refers to exactly that version, even if the dataset changes afterwards.
+#[utoipa::path(
+    tag = "Datasets",
+    get,
+    path = "/dataset/{dataset}/versions",
+    responses(
+        (status = 200, description = "OK", body = [DatasetVersion],
+            example = json!([
+                {
+                    "id": "2ad4e8e9-e9b6-4c04-9c7c-1b1b8b2bb9c5",
+                    "version": 2,
+                    "changed": "2024-03-01T12:00:00.000Z"
+                },
+                {
+                    "id": "7f0b3a35-4f6a-4b8f-9a9d-9b0e6e1a5b51",
+                    "version": 1,
+                    "changed": "2024-02-01T12:00:00.000Z"
+                }
+            ])
+        ),
+        (status = 400, description = "Bad request", body = ErrorResponse),
+        (status = 401, response = crate::api::model::responses::UnauthorizedUserResponse)
+    ),
+    params(
+        ("dataset" = DatasetName, description = "Dataset Name"),
+    ),
+    security(
+        ("session_token" = [])
+    )
+)]
+pub async fn list_dataset_versions_handler<C: ApplicationContext>(
+    session: C::Session,
+    app_ctx: web::Data<C>,
+    dataset: web::Path<DatasetName>,
+) -> Result<web::Json<Vec<DatasetVersion>>> {
+    let session_ctx = app_ctx.session_context(session).db();
+
+    let real_dataset = dataset.into_inner();
+
+    let dataset_id = session_ctx
+        .resolve_dataset_name_to_id(&real_dataset)
+        .await?;
+
+    // handle the case where the dataset name is not known
+    let dataset_id = dataset_id.ok_or(error::Error::UnknownDatasetName {
+        dataset_name: real_dataset.to_string(),
+    })?;
+
+    let versions = session_ctx.list_dataset_versions(&dataset_id).await?;
+
+    Ok(web::Json(versions))
+}
+
+/// Restores the definition of a dataset from one of its versions.
+/// The restored definition becomes the latest version.
+#[utoipa::path(
+    tag = "Datasets",
+    post,
+    path = "/dataset/{dataset}/versions/{version}/restore",
+    responses(
+        (status = 200, description = "The version that was created by the restore", body = DatasetVersion),
+        (status = 400, description = "Bad request", body = ErrorResponse, examples(
+            ("Referenced an unknown version" = (value = json!({
+                "error": "UnknownDatasetVersion",
+                "message": "Version 3 of dataset 2ad4e8e9-e9b6-4c04-9c7c-1b1b8b2bb9c5 does not exist"
+            })))
+        )),
+        (status = 401, response = crate::api::model::responses::UnauthorizedUserResponse)
+    ),
+    params(
+        ("dataset" = DatasetName, description = "Dataset Name"),
+        ("version" = u32, description = "Version to restore"),
+    ),
+    security(
+        ("session_token" = [])
+    )
+)]
+pub async fn restore_dataset_version_handler<C: ApplicationContext>(
+    session: C::Session,
+    app_ctx: web::Data<C>,
+    path: web::Path<(DatasetName, u32)>,
+) -> Result<web::Json<DatasetVersion>> {
+    let session_ctx = app_ctx.session_context(session).db();
+
+    let (real_dataset, version) = path.into_inner();
+
+    let dataset_id = session_ctx
+        .resolve_dataset_name_to_id(&real_dataset)
+        .await?;
+
+    // handle the case where the dataset name is not known
+    let dataset_id = dataset_id.ok_or(error::Error::UnknownDatasetName {
+        dataset_name: real_dataset.to_string(),
+    })?;
+
+    let restored = session_ctx
+        .restore_dataset_version(dataset_id, version)
+        .await?;
+
+    Ok(web::Json(restored))
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct GarbageCollectDatasetVersionsParams {
+    /// The number of most recent versions to keep
+    keep: u32,
+}
+
+/// Deletes old versions of a dataset. Workflows that are pinned to a deleted version can no longer be loaded.
+#[utoipa::path(
+    tag = "Datasets",
+    delete,
+    path = "/dataset/{dataset}/versions",
+    responses(
+        (status = 200, description = "The number of deleted versions", body = u64),
+        (status = 400, description = "Bad request", body = ErrorResponse, examples(
+            ("Tried to delete all versions" = (value = json!({
+                "error": "MustKeepLatestDatasetVersion",
+                "message": "At least the latest version of a dataset must be kept"
+            })))
+        )),
+        (status = 401, response = crate::api::model::responses::UnauthorizedUserResponse)
+    ),
+    params(
+        ("dataset" = DatasetName, description = "Dataset Name"),
+        GarbageCollectDatasetVersionsParams
+    ),
+    security(
+        ("session_token" = [])
+    )
+)]
+pub async fn garbage_collect_dataset_versions_handler<C: ApplicationContext>(
+    session: C::Session,
+    app_ctx: web::Data<C>,
+    dataset: web::Path<DatasetName>,
+    params: web::Query<GarbageCollectDatasetVersionsParams>,
+) -> Result<web::Json<u64>> {
+    let session_ctx = app_ctx.session_context(session).db();
+
+    let real_dataset = dataset.into_inner();
+
+    let dataset_id = session_ctx
+        .resolve_dataset_name_to_id(&real_dataset)
+        .await?;
+
+    // handle the case where the dataset name is not known
+    let dataset_id = dataset_id.ok_or(error::Error::UnknownDatasetName {
+        dataset_name: real_dataset.to_string(),
+    })?;
+
+    let deleted = session_ctx
+        .garbage_collect_dataset_versions(dataset_id, params.keep)
+        .await?;
+
+    Ok(web::Json(deleted))
+}
+
 /// Creates a new dataset referencing files. Users can reference previously uploaded files. Admins can reference files from a volume.
 #[utoipa::path(
     tag = "Datasets",
@@ -720,9 +973,13 @@ pub async fn auto_create_dataset_handler<C: ApplicationContext>(
 
     let create = create.into_inner();
 
-    let main_file_path = upload.id.root_path()?.join(&create.main_file);
-    let meta_data = auto_detect_meta_data_definition(&main_file_path, &create.layer_name)?;
-    let meta_data = crate::datasets::storage::MetaDataDefinition::OgrMetaData(meta_data);
+    let detected = auto_detect_meta_data_definition(
+        &upload.id.root_path()?,
+        &create.main_file,
+        &upload_file_names(&upload),
+        &create.layer_name,
+    )?;
+    let meta_data = detected.meta_data;
 
     let properties = AddDataset {
         name: None,
@@ -839,25 +1096,28 @@ pub async fn suggest_meta_data_handler<C: ApplicationContext>(
         .or_else(|| suggest_main_file(&upload))
         .ok_or(error::Error::NoMainFileCandidateFound)?;
 
-    let layer_name = suggest.layer_name;
-
-    let main_file_path = path_with_base_path(&upload.id.root_path()?, Path::new(&main_file))?;
-
-    let meta_data = auto_detect_meta_data_definition(&main_file_path, &layer_name)?;
-
-    let layer_name = meta_data.loading_info.layer_name.clone();
-
-    let meta_data = crate::datasets::storage::MetaDataDefinition::OgrMetaData(meta_data);
+    let detected = auto_detect_meta_data_definition(
+        &upload.id.root_path()?,
+        &main_file,
+        &upload_file_names(&upload),
+        &suggest.layer_name,
+    )?;
 
     Ok(web::Json(MetaDataSuggestion {
         main_file,
-        layer_name,
-        meta_data: meta_data.into(),
+        layer_name: detected.layer_name,
+        meta_data: detected.meta_data.into(),
     }))
 }
 
+fn upload_file_names(upload: &Upload) -> Vec<String> {
+    upload.files.iter().map(|file| file.name.clone()).collect()
+}
+
 fn suggest_main_file(upload: &Upload) -> Option<String> {
-    let known_extensions = ["csv", "shp", "json", "geojson", "gpkg", "sqlite"]; // TODO: rasters
+    let known_extensions = [
+        "csv", "shp", "json", "geojson", "gpkg", "sqlite", "tif", "tiff", "nc", "jp2",
+    ];
 
     if upload.files.len() == 1 {
         return Some(upload.files[0].name.clone());
@@ -867,7 +1127,8 @@ fn suggest_main_file(upload: &Upload) -> Option<String> {
     sorted_files.sort_by(|a, b| b.byte_size.cmp(&a.byte_size));
 
     for file in sorted_files {
-        if known_extensions.iter().any(|ext| file.name.ends_with(ext)) {
+        let file_name = file.name.to_lowercase();
+        if known_extensions.iter().any(|ext| file_name.ends_with(ext)) {
             return Some(file.name);
         }
     }
@@ -891,12 +1152,43 @@ fn select_layer_from_dataset<'a>(
     }
 }
 
+/// Detects the meta data of the `main_file` in the `base_path` directory.
+///
+/// For raster files, the `layer_name` selects a subdataset, e.g., a variable of a NetCDF file.
+/// Other raster files in `file_names` that only differ in a date from the main file are
+/// considered as further time steps of the dataset.
 fn auto_detect_meta_data_definition(
+    base_path: &Path,
+    main_file: &str,
+    file_names: &[String],
+    layer_name: &Option<String>,
+) -> Result<DetectedMetaData> {
+    let main_file_path = path_with_base_path(base_path, Path::new(main_file))?;
+
+    let dataset = gdal_open_dataset(&main_file_path).context(error::Operator)?;
+
+    if is_raster_dataset(&dataset) {
+        return auto_detect_raster_meta_data_definition(
+            dataset,
+            &main_file_path,
+            main_file,
+            file_names,
+            layer_name,
+        );
+    }
+
+    let meta_data = auto_detect_vector_meta_data_definition(&main_file_path, layer_name)?;
+
+    Ok(DetectedMetaData {
+        layer_name: meta_data.loading_info.layer_name.clone(),
+        meta_data: crate::datasets::storage::MetaDataDefinition::OgrMetaData(meta_data),
+    })
+}
+
+fn auto_detect_vector_meta_data_definition(
     main_file_path: &Path,
     layer_name: &Option<String>,
 ) -> Result<StaticMetaData<OgrSourceDataset, VectorResultDescriptor, VectorQueryRectangle>> {
-    // TODO: handle Raster datasets as well
-
     let dataset = gdal_open_dataset(main_file_path).context(error::Operator)?;
 
     let layer = select_layer_from_dataset(&dataset, layer_name)?;
@@ -973,6 +1265,349 @@ fn auto_detect_meta_data_definition(
     })
 }
 
+fn is_raster_dataset(dataset: &Dataset) -> bool {
+    dataset.layer_count() == 0
+        && (dataset.raster_count() > 0 || !raster_subdatasets(dataset).is_empty())
+}
+
+/// The names of the subdatasets of a dataset, e.g., `NETCDF:"file.nc":variable`
+fn raster_subdatasets(dataset: &Dataset) -> Vec<String> {
+    dataset
+        .metadata_domain("SUBDATASETS")
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|entry| {
+            let (key, value) = entry.split_once('=')?;
+            key.ends_with("_NAME").then(|| value.to_owned())
+        })
+        .collect()
+}
+
+/// Selects the subdataset whose variable name equals `layer_name` or the first one if no name is given.
+fn select_raster_subdataset(
+    subdatasets: Vec<String>,
+    layer_name: &Option<String>,
+) -> Result<Option<String>> {
+    let Some(layer_name) = layer_name else {
+        return Ok(subdatasets.into_iter().next());
+    };
+
+    if subdatasets.is_empty() {
+        return Ok(None);
+    }
+
+    subdatasets
+        .into_iter()
+        .find(|subdataset| subdataset.rsplit(':').next() == Some(layer_name.as_str()))
+        .map(Some)
+        .ok_or_else(|| error::Error::DatasetInvalidLayerName {
+            layer_name: layer_name.clone(),
+        })
+}
+
+/// Detects the meta data of the first band of a raster file (GeoTIFF, COG, NetCDF, JPEG2000, ...).
+///
+/// Scale and offset are not part of the meta data since the `GdalSource` reads them from the band.
+fn auto_detect_raster_meta_data_definition(
+    main_dataset: Dataset,
+    main_file_path: &Path,
+    main_file: &str,
+    file_names: &[String],
+    layer_name: &Option<String>,
+) -> Result<DetectedMetaData> {
+    let main_file_name = file_name_of(main_file);
+    let main_file_dir = Path::new(main_file).parent();
+
+    let time_series = FileNameDatePattern::detect(main_file_name)
+        .map(|pattern| {
+            let mut files = file_names
+                .iter()
+                .filter(|file_name| Path::new(file_name.as_str()).parent() == main_file_dir)
+                .filter_map(|file_name| {
+                    let file_name = file_name_of(file_name);
+                    Some((pattern.date(file_name)?, file_name))
+                })
+                .collect::<Vec<_>>();
+            files.sort_by_key(|(date, _)| *date);
+            (pattern, files)
+        })
+        .filter(|(_, files)| files.len() > 1);
+
+    let subdataset = select_raster_subdataset(raster_subdatasets(&main_dataset), layer_name)?;
+
+    let (dataset, file_path, layer_name) = if let Some(subdataset) = subdataset {
+        let dataset = gdal_open_dataset(Path::new(&subdataset)).context(error::Operator)?;
+        let variable = subdataset.rsplit(':').next().unwrap_or_default().to_owned();
+        (dataset, subdataset, variable)
+    } else {
+        // a time series is named after the file name without its date
+        let file_stem = match &time_series {
+            Some((pattern, _)) => pattern.name(),
+            None => main_file_path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().to_string())
+                .unwrap_or_default(),
+        };
+        (
+            main_dataset,
+            main_file_path.to_string_lossy().to_string(),
+            file_stem,
+        )
+    };
+
+    let params = gdal_parameters_from_dataset(&dataset, 1, Path::new(&file_path), None, None)
+        .context(error::Operator)?;
+
+    let mut result_descriptor =
+        raster_descriptor_from_dataset(&dataset, 1).context(error::Operator)?;
+    let band_name = dataset
+        .rasterband(1)
+        .context(error::Gdal)?
+        .description()
+        .ok()
+        .filter(|description| !description.is_empty())
+        .unwrap_or_else(|| layer_name.clone());
+    result_descriptor.bands = RasterBandDescriptors::new(vec![RasterBandDescriptor::new(
+        band_name,
+        result_descriptor.bands[0].measurement.clone(),
+    )])
+    .context(error::Operator)?;
+    result_descriptor.bbox = Some(params.spatial_partition());
+
+    let Some((pattern, files)) = time_series else {
+        return Ok(DetectedMetaData {
+            layer_name,
+            meta_data: crate::datasets::storage::MetaDataDefinition::GdalStatic(
+                GdalMetaDataStatic {
+                    time: None,
+                    params,
+                    result_descriptor,
+                    cache_ttl: CacheTtlSeconds::default(),
+                },
+            ),
+        });
+    };
+
+    let times = files
+        .iter()
+        .map(|(date, _)| TimeInstance::from(*date))
+        .collect::<Vec<_>>();
+
+    let meta_data = if let Some(step) = regular_time_step(&files) {
+        let data_time = TimeInterval::new(times[0], (times[times.len() - 1] + step)?)?;
+        result_descriptor.time = Some(data_time);
+
+        crate::datasets::storage::MetaDataDefinition::GdalMetaDataRegular(GdalMetaDataRegular {
+            result_descriptor,
+            params: GdalDatasetParameters {
+                file_path: replace_file_name(&file_path, main_file_path, &pattern.template())
+                    .into(),
+                file_not_found_handling: FileNotFoundHandling::NoData,
+                ..params
+            },
+            time_placeholders: [(
+                RASTER_TIME_PLACEHOLDER.to_owned(),
+                GdalSourceTimePlaceholder {
+                    format: pattern.format,
+                    reference: TimeReference::Start,
+                },
+            )]
+            .into(),
+            data_time,
+            step,
+            cache_ttl: CacheTtlSeconds::default(),
+        })
+    } else {
+        // irregular time steps: each file is valid until the next one starts and the last one as long as its predecessor
+        let last_end = times[times.len() - 1] + (times[times.len() - 1] - times[times.len() - 2]);
+        let ends = times.iter().skip(1).copied().chain([last_end]);
+
+        let slices = files
+            .iter()
+            .zip(times.iter().zip(ends))
+            .map(|((_, file_name), (start, end))| {
+                Ok(GdalLoadingInfoTemporalSlice {
+                    time: TimeInterval::new(*start, end)?,
+                    params: Some(GdalDatasetParameters {
+                        file_path: replace_file_name(&file_path, main_file_path, file_name).into(),
+                        ..params.clone()
+                    }),
+                    cache_ttl: CacheTtlSeconds::default(),
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        result_descriptor.time = Some(TimeInterval::new(times[0], last_end)?);
+
+        crate::datasets::storage::MetaDataDefinition::GdalMetaDataList(GdalMetaDataList {
+            result_descriptor,
+            params: slices,
+        })
+    };
+
+    Ok(DetectedMetaData {
+        layer_name,
+        meta_data,
+    })
+}
+
+/// The last component of a file name that may contain directories
+fn file_name_of(file_name: &str) -> &str {
+    Path::new(file_name)
+        .file_name()
+        .and_then(std::ffi::OsStr::to_str)
+        .unwrap_or(file_name)
+}
+
+/// Replaces the file name of `main_file_path` within `file_path`, which is either the path itself
+/// or a GDAL subdataset name that contains it, e.g., `NETCDF:"/path/file.nc":variable`.
+fn replace_file_name(file_path: &str, main_file_path: &Path, file_name: &str) -> String {
+    file_path.replacen(
+        main_file_path.to_string_lossy().as_ref(),
+        main_file_path
+            .with_file_name(file_name)
+            .to_string_lossy()
+            .as_ref(),
+        1,
+    )
+}
+
+/// The placeholder that replaces the date in the file path of a regular raster time series
+const RASTER_TIME_PLACEHOLDER: &str = "%_START_TIME_%";
+
+/// Date formats that are detected in raster file names, each with the shape of a matching
+/// date where `d` stands for a digit
+const RASTER_FILE_NAME_DATE_FORMATS: [(&str, &str); 3] = [
+    ("%Y-%m-%d", "dddd-dd-dd"),
+    ("%Y_%m_%d", "dddd_dd_dd"),
+    ("%Y%m%d", "dddddddd"),
+];
+
+/// A file name that contains a date, e.g., `ndvi_2014-01-01.tif`
+struct FileNameDatePattern {
+    prefix: String,
+    suffix: String,
+    format: DateTimeParseFormat,
+}
+
+impl FileNameDatePattern {
+    fn detect(file_name: &str) -> Option<Self> {
+        for (format, shape) in RASTER_FILE_NAME_DATE_FORMATS {
+            let format = DateTimeParseFormat::custom(format.to_owned());
+
+            for (start, _) in file_name.char_indices() {
+                let Some(candidate) = file_name.get(start..start + shape.len()) else {
+                    continue;
+                };
+
+                let prefix = &file_name[..start];
+                let suffix = &file_name[start + shape.len()..];
+
+                // do not match parts of longer numbers
+                if !matches_date_shape(candidate, shape)
+                    || prefix.ends_with(|c: char| c.is_ascii_digit())
+                    || suffix.starts_with(|c: char| c.is_ascii_digit())
+                {
+                    continue;
+                }
+
+                if DateTime::parse_from_str(candidate, &format).is_ok() {
+                    return Some(Self {
+                        prefix: prefix.to_owned(),
+                        suffix: suffix.to_owned(),
+                        format,
+                    });
+                }
+            }
+        }
+
+        None
+    }
+
+    fn date(&self, file_name: &str) -> Option<DateTime> {
+        let date = file_name
+            .strip_prefix(&self.prefix)?
+            .strip_suffix(&self.suffix)?;
+
+        DateTime::parse_from_str(date, &self.format).ok()
+    }
+
+    fn template(&self) -> String {
+        format!("{}{RASTER_TIME_PLACEHOLDER}{}", self.prefix, self.suffix)
+    }
+
+    /// The name of the series, i.e., the file stem without the date and its separators
+    fn name(&self) -> String {
+        const SEPARATORS: [char; 3] = ['_', '-', '.'];
+
+        let prefix = self.prefix.trim_end_matches(SEPARATORS);
+        // the suffix is no file name, e.g., `.tif`, so `Path::file_stem` would keep the extension
+        let suffix = self
+            .suffix
+            .rsplit_once('.')
+            .map_or(self.suffix.as_str(), |(stem, _extension)| stem);
+        let suffix = suffix.trim_start_matches(SEPARATORS);
+
+        match (prefix.is_empty(), suffix.is_empty()) {
+            (false, false) => format!("{prefix}_{suffix}"),
+            (false, true) => prefix.to_owned(),
+            _ => suffix.to_owned(),
+        }
+    }
+}
+
+fn matches_date_shape(candidate: &str, shape: &str) -> bool {
+    candidate.len() == shape.len()
+        && candidate.bytes().zip(shape.bytes()).all(|(c, s)| {
+            if s == b'd' {
+                c.is_ascii_digit()
+            } else {
+                c == s
+            }
+        })
+}
+
+/// Returns the step between the sorted `files` if it is the same number of days, months or years for all of them.
+fn regular_time_step(files: &[(DateTime, &str)]) -> Option<TimeStep> {
+    fn common_step(mut steps: impl Iterator<Item = Option<i64>>) -> Option<u32> {
+        let first = steps.next()??;
+        if first <= 0 || !steps.all(|step| step == Some(first)) {
+            return None;
+        }
+        u32::try_from(first).ok()
+    }
+
+    let months = common_step(files.windows(2).map(|pair| {
+        let ((a, _), (b, _)) = (pair[0], pair[1]);
+        (a.day() == b.day()).then(|| {
+            i64::from(b.year() - a.year()) * 12 + i64::from(b.month()) - i64::from(a.month())
+        })
+    }));
+
+    if let Some(months) = months {
+        return Some(if months % 12 == 0 {
+            TimeStep {
+                granularity: TimeGranularity::Years,
+                step: months / 12,
+            }
+        } else {
+            TimeStep {
+                granularity: TimeGranularity::Months,
+                step: months,
+            }
+        });
+    }
+
+    let days = common_step(files.windows(2).map(|pair| {
+        Some((TimeInstance::from(pair[1].0) - TimeInstance::from(pair[0].0)).num_days())
+    }));
+
+    days.map(|days| TimeStep {
+        granularity: TimeGranularity::Days,
+        step: days,
+    })
+}
+
 /// create Gdal dataset with autodetect parameters based on available columns
 fn gdal_autodetect(path: &Path, columns: &[String]) -> Option<GdalAutoDetect> {
     let columns_lower = columns.iter().map(|s| s.to_lowercase()).collect::<Vec<_>>();
@@ -1152,6 +1787,11 @@ fn detect_vector_geometry(layer: &Layer) -> DetectedGeometry {
     }
 }
 
+struct DetectedMetaData {
+    layer_name: String,
+    meta_data: crate::datasets::storage::MetaDataDefinition,
+}
+
 struct GdalAutoDetect {
     dataset: Dataset,
     x: String,
@@ -1338,6 +1978,7 @@ mod tests {
     };
     use geoengine_operators::util::gdal::create_ndvi_meta_data;
     use serde_json::{json, Value};
+    use std::str::FromStr;
     use tokio_postgres::NoTls;
 
     #[ge_context::test]
@@ -1758,7 +2399,7 @@ mod tests {
 
     #[test]
     fn it_auto_detects() {
-        let meta_data = auto_detect_meta_data_definition(
+        let meta_data = auto_detect_vector_meta_data_definition(
             test_data!("vector/data/ne_10m_ports/ne_10m_ports.shp"),
             &None,
         )
@@ -1856,7 +2497,7 @@ mod tests {
 
     #[test]
     fn it_detects_time_json() {
-        let meta_data = auto_detect_meta_data_definition(
+        let meta_data = auto_detect_vector_meta_data_definition(
             test_data!("vector/data/points_with_iso_time.json"),
             &None,
         )
@@ -1935,7 +2576,7 @@ mod tests {
 
     #[test]
     fn it_detects_time_gpkg() {
-        let meta_data = auto_detect_meta_data_definition(
+        let meta_data = auto_detect_vector_meta_data_definition(
             test_data!("vector/data/points_with_time.gpkg"),
             &None,
         )
@@ -2014,9 +2655,11 @@ mod tests {
 
     #[test]
     fn it_detects_time_shp() {
-        let meta_data =
-            auto_detect_meta_data_definition(test_data!("vector/data/points_with_date.shp"), &None)
-                .unwrap();
+        let meta_data = auto_detect_vector_meta_data_definition(
+            test_data!("vector/data/points_with_date.shp"),
+            &None,
+        )
+        .unwrap();
 
         let mut meta_data = crate::datasets::storage::MetaDataDefinition::OgrMetaData(meta_data);
 
@@ -2091,7 +2734,7 @@ mod tests {
 
     #[test]
     fn it_detects_time_start_duration() {
-        let meta_data = auto_detect_meta_data_definition(
+        let meta_data = auto_detect_vector_meta_data_definition(
             test_data!("vector/data/points_with_iso_start_duration.json"),
             &None,
         )
@@ -2163,7 +2806,8 @@ mod tests {
     #[test]
     fn it_detects_csv() {
         let meta_data =
-            auto_detect_meta_data_definition(test_data!("vector/data/lonlat.csv"), &None).unwrap();
+            auto_detect_vector_meta_data_definition(test_data!("vector/data/lonlat.csv"), &None)
+                .unwrap();
 
         let mut meta_data = crate::datasets::storage::MetaDataDefinition::OgrMetaData(meta_data);
 
@@ -2242,6 +2886,184 @@ mod tests {
         );
     }
 
+    #[test]
+    fn it_detects_raster_time_series() {
+        let file_names = [
+            "MOD13A2_M_NDVI_2014-01-01.TIFF",
+            "MOD13A2_M_NDVI_2014-02-01.TIFF",
+            "MOD13A2_M_NDVI_2014-03-01.TIFF",
+            "MOD13A2_M_NDVI_2014-04-01.TIFF",
+            "MOD13A2_M_NDVI_2014-05-01.TIFF",
+            "MOD13A2_M_NDVI_2014-06-01.TIFF",
+        ]
+        .map(ToString::to_string);
+
+        let detected = auto_detect_meta_data_definition(
+            test_data!("raster/modis_ndvi"),
+            "MOD13A2_M_NDVI_2014-03-01.TIFF",
+            &file_names,
+            &None,
+        )
+        .unwrap();
+
+        assert_eq!(detected.layer_name, "MOD13A2_M_NDVI");
+
+        let crate::datasets::storage::MetaDataDefinition::GdalMetaDataRegular(meta_data) =
+            detected.meta_data
+        else {
+            panic!("expected regular meta data");
+        };
+
+        assert_eq!(
+            meta_data.params.file_path,
+            test_data!("raster/modis_ndvi/MOD13A2_M_NDVI_%_START_TIME_%.TIFF")
+        );
+        assert_eq!(
+            meta_data.step,
+            TimeStep {
+                granularity: TimeGranularity::Months,
+                step: 1,
+            }
+        );
+        assert_eq!(
+            meta_data.data_time,
+            TimeInterval::new(
+                TimeInstance::from_str("2014-01-01T00:00:00.000Z").unwrap(),
+                TimeInstance::from_str("2014-07-01T00:00:00.000Z").unwrap(),
+            )
+            .unwrap()
+        );
+        assert_eq!(
+            meta_data.time_placeholders[RASTER_TIME_PLACEHOLDER].format,
+            DateTimeParseFormat::custom("%Y-%m-%d".to_string())
+        );
+        assert_eq!(meta_data.result_descriptor.time, Some(meta_data.data_time));
+        assert_eq!(
+            meta_data.result_descriptor.spatial_reference,
+            SpatialReference::epsg_4326().into()
+        );
+        assert_eq!(
+            meta_data.result_descriptor.resolution,
+            Some(SpatialResolution::new_unchecked(0.1, 0.1))
+        );
+        assert_eq!(meta_data.params.width, 3600);
+        assert_eq!(meta_data.params.height, 1800);
+
+        // without the April file, the time steps are irregular
+        let detected = auto_detect_meta_data_definition(
+            test_data!("raster/modis_ndvi"),
+            "MOD13A2_M_NDVI_2014-01-01.TIFF",
+            &[
+                file_names[0].clone(),
+                file_names[1].clone(),
+                file_names[2].clone(),
+                file_names[4].clone(),
+            ],
+            &None,
+        )
+        .unwrap();
+
+        let crate::datasets::storage::MetaDataDefinition::GdalMetaDataList(meta_data) =
+            detected.meta_data
+        else {
+            panic!("expected list meta data");
+        };
+
+        assert_eq!(meta_data.params.len(), 4);
+        assert_eq!(
+            meta_data.params[3].params.as_ref().unwrap().file_path,
+            test_data!("raster/modis_ndvi/MOD13A2_M_NDVI_2014-05-01.TIFF")
+        );
+        assert_eq!(
+            meta_data.params[2].time,
+            TimeInterval::new(
+                TimeInstance::from_str("2014-03-01T00:00:00.000Z").unwrap(),
+                TimeInstance::from_str("2014-05-01T00:00:00.000Z").unwrap(),
+            )
+            .unwrap()
+        );
+        assert_eq!(
+            meta_data.params[3].time,
+            TimeInterval::new(
+                TimeInstance::from_str("2014-05-01T00:00:00.000Z").unwrap(),
+                TimeInstance::from_str("2014-07-01T00:00:00.000Z").unwrap(),
+            )
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn it_detects_static_rasters() {
+        let detected = auto_detect_meta_data_definition(
+            test_data!("raster/modis_ndvi"),
+            "MOD13A2_M_NDVI_2014-01-01.TIFF",
+            &["MOD13A2_M_NDVI_2014-01-01.TIFF".to_string()],
+            &None,
+        )
+        .unwrap();
+
+        let crate::datasets::storage::MetaDataDefinition::GdalStatic(meta_data) =
+            detected.meta_data
+        else {
+            panic!("expected static meta data");
+        };
+
+        assert_eq!(meta_data.time, None);
+        assert_eq!(
+            meta_data.params.file_path,
+            test_data!("raster/modis_ndvi/MOD13A2_M_NDVI_2014-01-01.TIFF")
+        );
+        assert_eq!(meta_data.params.rasterband_channel, 1);
+        assert_eq!(
+            meta_data.result_descriptor.data_type,
+            geoengine_datatypes::raster::RasterDataType::U8
+        );
+        assert_eq!(meta_data.result_descriptor.bands.len(), 1);
+    }
+
+    #[test]
+    fn it_detects_dates_in_file_names() {
+        let pattern = FileNameDatePattern::detect("S2_20200101_B04.jp2").unwrap();
+        assert_eq!(pattern.template(), "S2_%_START_TIME_%_B04.jp2");
+        assert_eq!(
+            pattern.date("S2_20200111_B04.jp2"),
+            Some(DateTime::new_utc(2020, 1, 11, 0, 0, 0))
+        );
+        assert_eq!(pattern.date("S2_20200111_B08.jp2"), None);
+        assert_eq!(pattern.name(), "S2_B04");
+        assert_eq!(
+            FileNameDatePattern::detect("ndvi_2014-01-01.tif")
+                .unwrap()
+                .name(),
+            "ndvi"
+        );
+
+        assert!(FileNameDatePattern::detect("dem_123456789.tif").is_none());
+        assert!(FileNameDatePattern::detect("dem.tif").is_none());
+    }
+
+    #[test]
+    fn it_replaces_only_the_file_name() {
+        let main_file_path = Path::new("/data/2014-01-01/ndvi_2014-01-01.nc");
+
+        assert_eq!(
+            replace_file_name(
+                "NETCDF:\"/data/2014-01-01/ndvi_2014-01-01.nc\":ndvi",
+                main_file_path,
+                "ndvi_2014-02-01.nc"
+            ),
+            "NETCDF:\"/data/2014-01-01/ndvi_2014-02-01.nc\":ndvi"
+        );
+        assert_eq!(
+            replace_file_name(
+                "/data/2014-01-01/ndvi_2014-01-01.nc",
+                main_file_path,
+                "ndvi_2014-02-01.nc"
+            ),
+            "/data/2014-01-01/ndvi_2014-02-01.nc"
+        );
+    }
+
     #[ge_context::test]
     async fn get_dataset(app_ctx: PostgresContext<NoTls>) -> Result<()> {
         let ctx = app_ctx.default_session_context().await.unwrap();
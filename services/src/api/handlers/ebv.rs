@@ -302,7 +302,7 @@ impl<C: SessionContext> Task<C::TaskContext> for EbvMultiOverviewTask<C> {
 
                         status.error.push(file);
                     }
-                    TaskStatus::Running(_) => {
+                    TaskStatus::Pending { .. } | TaskStatus::Running(_) => {
                         // must not happen, since we used the callback
                         debug!("Ran into task status that must not happend: running/aborted after finish");
                     }
@@ -6,12 +6,13 @@ use crate::error::Error::NotImplemented;
 use crate::error::{Error, Result};
 use crate::layers::layer::{
     AddLayer, AddLayerCollection, CollectionItem, LayerCollection, LayerCollectionListing,
-    ProviderLayerCollectionId,
+    ProviderLayerCollectionId, UpdateLayer, UpdateLayerCollection,
 };
 use crate::layers::listing::{
     LayerCollectionId, LayerCollectionProvider, ProviderCapabilities, SearchParameters,
 };
 use crate::layers::storage::{LayerDb, LayerProviderDb, LayerProviderListingOptions};
+use crate::tasks::TaskPriority;
 use crate::util::config::get_config_element;
 use crate::util::extractors::ValidatedQuery;
 use crate::workflows::registry::WorkflowRegistry;
@@ -76,28 +77,35 @@ where
             ),
     )
     .service(
-        web::scope("/layerDb").service(
-            web::scope("/collections/{collection}")
-                .service(
-                    web::scope("/layers")
-                        .route("", web::post().to(add_layer::<C>))
-                        .service(
-                            web::resource("/{layer}")
-                                .route(web::post().to(add_existing_layer_to_collection::<C>))
-                                .route(web::delete().to(remove_layer_from_collection::<C>)),
-                        ),
-                )
-                .service(
-                    web::scope("/collections")
-                        .route("", web::post().to(add_collection::<C>))
-                        .service(
-                            web::resource("/{sub_collection}")
-                                .route(web::post().to(add_existing_collection_to_collection::<C>))
-                                .route(web::delete().to(remove_collection_from_collection::<C>)),
-                        ),
-                )
-                .route("", web::delete().to(remove_collection::<C>)),
-        ),
+        web::scope("/layerDb")
+            .service(web::resource("/layers/{layer}").route(web::put().to(update_layer::<C>)))
+            .service(
+                web::scope("/collections/{collection}")
+                    .service(
+                        web::scope("/layers")
+                            .route("", web::post().to(add_layer::<C>))
+                            .service(
+                                web::resource("/{layer}")
+                                    .route(web::post().to(add_existing_layer_to_collection::<C>))
+                                    .route(web::delete().to(remove_layer_from_collection::<C>)),
+                            ),
+                    )
+                    .service(
+                        web::scope("/collections")
+                            .route("", web::post().to(add_collection::<C>))
+                            .service(
+                                web::resource("/{sub_collection}")
+                                    .route(
+                                        web::post().to(add_existing_collection_to_collection::<C>),
+                                    )
+                                    .route(
+                                        web::delete().to(remove_collection_from_collection::<C>),
+                                    ),
+                            ),
+                    )
+                    .route("", web::put().to(update_collection::<C>))
+                    .route("", web::delete().to(remove_collection::<C>)),
+            ),
     );
 }
 
@@ -811,6 +819,7 @@ async fn layer_to_dataset<C: ApplicationContext>(
         description: Some(layer.description),
         query: qr.into(),
         as_cog: true,
+        priority: TaskPriority::default(),
     };
 
     let compression_num_threads =
@@ -898,6 +907,68 @@ async fn add_collection<C: ApplicationContext>(
     Ok(web::Json(IdResponse { id }))
 }
 
+/// Update a layer in place, keeping its id, collections and permissions
+#[utoipa::path(
+    tag = "Layers",
+    put,
+    path = "/layerDb/layers/{layer}",
+    params(
+        ("layer" = LayerId, description = "Layer id"),
+    ),
+    request_body = UpdateLayer,
+    responses(
+        (status = 200, description = "OK")
+    ),
+    security(
+        ("session_token" = [])
+    )
+)]
+async fn update_layer<C: ApplicationContext>(
+    session: C::Session,
+    app_ctx: web::Data<C>,
+    layer: web::Path<LayerId>,
+    request: web::Json<UpdateLayer>,
+) -> Result<HttpResponse> {
+    app_ctx
+        .session_context(session)
+        .db()
+        .update_layer(&layer.into_inner().into(), request.into_inner())
+        .await?;
+
+    Ok(HttpResponse::Ok().finish())
+}
+
+/// Update a collection in place, keeping its id, children and permissions
+#[utoipa::path(
+    tag = "Layers",
+    put,
+    path = "/layerDb/collections/{collection}",
+    params(
+        ("collection" = LayerCollectionId, description = "Layer collection id"),
+    ),
+    request_body = UpdateLayerCollection,
+    responses(
+        (status = 200, description = "OK")
+    ),
+    security(
+        ("session_token" = [])
+    )
+)]
+async fn update_collection<C: ApplicationContext>(
+    session: C::Session,
+    app_ctx: web::Data<C>,
+    collection: web::Path<LayerCollectionId>,
+    request: web::Json<UpdateLayerCollection>,
+) -> Result<HttpResponse> {
+    app_ctx
+        .session_context(session)
+        .db()
+        .update_layer_collection(&collection, request.into_inner())
+        .await?;
+
+    Ok(HttpResponse::Ok().finish())
+}
+
 /// Remove a collection
 #[utoipa::path(
     tag = "Layers",
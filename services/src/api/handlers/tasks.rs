@@ -1,6 +1,11 @@
+use crate::api::model::responses::IdResponse;
 use crate::contexts::ApplicationContext;
+use crate::datasets::{
+    list_recurring_datasets_from_workflows, schedule_recurring_dataset_from_workflow_task,
+    RecurringDatasetFromWorkflow,
+};
 use crate::error::Result;
-use crate::tasks::{TaskListOptions, TaskManager, TaskStatusWithId};
+use crate::tasks::{CronSchedule, RecurringTaskId, TaskListOptions, TaskManager, TaskStatusWithId};
 use crate::util::extractors::ValidatedQuery;
 use crate::{contexts::SessionContext, tasks::TaskId};
 use actix_web::{web, FromRequest, HttpResponse, Responder};
@@ -15,6 +20,18 @@ where
     cfg.service(
         web::scope("/tasks")
             .service(web::resource("/list").route(web::get().to(list_handler::<C>)))
+            .service(
+                web::scope("/recurring")
+                    .service(
+                        web::resource("")
+                            .route(web::get().to(list_recurring_tasks_handler::<C>))
+                            .route(web::post().to(add_recurring_task_handler::<C>)),
+                    )
+                    .service(
+                        web::resource("/{recurring_task_id}")
+                            .route(web::delete().to(remove_recurring_task_handler::<C>)),
+                    ),
+            )
             .service(
                 web::scope("/{task_id}")
                     .service(web::resource("/status").route(web::get().to(status_handler::<C>)))
@@ -164,17 +181,149 @@ async fn abort_handler<C: ApplicationContext>(
     Ok(HttpResponse::Accepted().finish())
 }
 
+/// A task that creates a dataset from a workflow every time the schedule is due.
+#[derive(Debug, Clone, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AddRecurringTask {
+    /// A cron-like schedule in UTC with the fields minute, hour, day of month, month and day of week
+    #[schema(value_type = String, example = "0 2 * * *")]
+    pub schedule: CronSchedule,
+    pub task: RecurringDatasetFromWorkflow,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RecurringTaskResponse {
+    pub recurring_task_id: RecurringTaskId,
+    #[schema(value_type = String, example = "0 2 * * *")]
+    pub schedule: CronSchedule,
+    pub task: RecurringDatasetFromWorkflow,
+}
+
+/// Create a recurring task that creates a dataset from a workflow every time its schedule is due.
+///
+/// The tasks run with the session of this request, so they are skipped once the session is no longer valid.
+/// Since every run creates a new dataset, the dataset must not have a name.
+#[utoipa::path(
+    tag = "Tasks",
+    post,
+    path = "/tasks/recurring",
+    request_body(content = AddRecurringTask, example = json!({
+        "schedule": "0 2 * * *",
+        "task": {
+            "type": "vector",
+            "workflowId": "8d2b2e3a-c4e0-4a0e-8c9e-1f4b8c0a6b1e",
+            "params": {
+                "displayName": "nightly points",
+                "description": null,
+                "query": {
+                    "spatialBounds": {"lowerLeftCoordinate": {"x": -10.0, "y": 20.0}, "upperRightCoordinate": {"x": 50.0, "y": 80.0}},
+                    "timeInterval": {"start": 1_388_534_400_000_i64, "end": 1_388_534_401_000_i64},
+                    "spatialResolution": {"x": 0.1, "y": 0.1}
+                }
+            }
+        }
+    })),
+    responses(
+        (status = 200, response = IdResponse::<RecurringTaskId>)
+    ),
+    security(
+        ("session_token" = [])
+    )
+)]
+async fn add_recurring_task_handler<C: ApplicationContext>(
+    session: C::Session,
+    app_ctx: web::Data<C>,
+    recurring_task: web::Json<AddRecurringTask>,
+) -> Result<web::Json<IdResponse<RecurringTaskId>>> {
+    let AddRecurringTask { schedule, task } = recurring_task.into_inner();
+
+    let recurring_task_id = schedule_recurring_dataset_from_workflow_task(
+        app_ctx.get_ref().clone(),
+        session,
+        schedule,
+        task,
+    )
+    .await?;
+
+    Ok(web::Json(IdResponse::from(recurring_task_id)))
+}
+
+/// List the recurring tasks.
+#[utoipa::path(
+    tag = "Tasks",
+    get,
+    path = "/tasks/recurring",
+    responses(
+        (status = 200, description = "The recurring tasks", body = Vec<RecurringTaskResponse>)
+    ),
+    security(
+        ("session_token" = [])
+    )
+)]
+async fn list_recurring_tasks_handler<C: ApplicationContext>(
+    session: C::Session,
+    app_ctx: web::Data<C>,
+) -> Result<web::Json<Vec<RecurringTaskResponse>>> {
+    let ctx = app_ctx.session_context(session);
+
+    let recurring_tasks = list_recurring_datasets_from_workflows(&ctx)
+        .await?
+        .into_iter()
+        .map(|(recurring_task, task)| RecurringTaskResponse {
+            recurring_task_id: recurring_task.recurring_task_id,
+            schedule: recurring_task.schedule,
+            task,
+        })
+        .collect();
+
+    Ok(web::Json(recurring_tasks))
+}
+
+/// Delete a recurring task. Tasks that it already started are not aborted.
+#[utoipa::path(
+    tag = "Tasks",
+    delete,
+    path = "/tasks/recurring/{id}",
+    responses(
+        (status = 200, description = "The recurring task was deleted.")
+    ),
+    params(
+        ("id" = RecurringTaskId, description = "Recurring task id")
+    ),
+    security(
+        ("session_token" = [])
+    )
+)]
+async fn remove_recurring_task_handler<C: ApplicationContext>(
+    session: C::Session,
+    app_ctx: web::Data<C>,
+    recurring_task_id: web::Path<RecurringTaskId>,
+) -> Result<HttpResponse> {
+    app_ctx
+        .session_context(session)
+        .tasks()
+        .remove_recurring_task(recurring_task_id.into_inner())
+        .await?;
+
+    Ok(HttpResponse::Ok().finish())
+}
+
 #[cfg(test)]
 mod tests {
 
     use super::*;
     use crate::contexts::PostgresContext;
+    use crate::datasets::recurring_dataset_from_workflow_factory;
     use crate::ge_context;
     use crate::util::tests::read_body_json;
+    use crate::workflows::{registry::WorkflowRegistry, workflow::Workflow};
     use crate::{
         contexts::SimpleApplicationContext,
         tasks::{
-            util::test::wait_for_task_to_finish, Task, TaskContext, TaskStatus, TaskStatusInfo,
+            util::test::wait_for_task_to_finish, PostgresTaskStore, SimpleTaskManager,
+            SimpleTaskManagerBackend, Task, TaskContext, TaskError, TaskFilter, TaskLimits,
+            TaskStatus, TaskStatusInfo, TaskStore,
         },
         util::tests::send_test_request,
     };
@@ -182,6 +331,9 @@ mod tests {
     use actix_web_httpauth::headers::authorization::Bearer;
     use futures::{channel::oneshot, lock::Mutex};
     use geoengine_datatypes::error::ErrorSource;
+    use geoengine_datatypes::primitives::DateTime;
+    use geoengine_operators::engine::VectorOperator;
+    use geoengine_operators::mock::{MockPointSource, MockPointSourceParams};
     use serde_json::json;
     use std::{pin::Pin, sync::Arc};
     use tokio_postgres::NoTls;
@@ -849,4 +1001,363 @@ mod tests {
             })
         );
     }
+
+    #[tokio::test]
+    async fn it_queues_tasks_beyond_the_concurrency_limit() {
+        let tasks = Arc::new(SimpleTaskManager::new(Arc::new(
+            SimpleTaskManagerBackend::new(TaskLimits {
+                max_concurrent_tasks: 1,
+                ..Default::default()
+            }),
+        )));
+
+        let (task_a, complete_tx_a) = NopTask::new_with_sender();
+        let (task_b, complete_tx_b) = NopTask::new_with_sender();
+
+        let task_id_a = tasks.schedule_task(task_a.boxed(), None).await.unwrap();
+        let task_id_b = tasks.schedule_task(task_b.boxed(), None).await.unwrap();
+
+        assert!(tasks.get_task_status(task_id_a).await.unwrap().is_running());
+        assert_eq!(
+            serde_json::to_value(tasks.get_task_status(task_id_b).await.unwrap()).unwrap(),
+            json!({
+                "status": "pending",
+                "taskType": "nopTask",
+                "description": "No operation",
+            })
+        );
+
+        let pending_tasks = tasks
+            .list_tasks(TaskListOptions {
+                filter: Some(TaskFilter::Pending),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(pending_tasks.len(), 1);
+        assert_eq!(pending_tasks[0].task_id, task_id_b);
+
+        // the pending task starts after the running one finished
+
+        complete_tx_a.send(()).unwrap();
+        wait_for_task_to_finish(tasks.clone(), task_id_a).await;
+
+        geoengine_operators::util::retry::retry(5, 100, 2., None, || {
+            let tasks = tasks.clone();
+            async move {
+                let status = tasks.get_task_status(task_id_b).await.unwrap();
+                status.is_running().then_some(()).ok_or(())
+            }
+        })
+        .await
+        .unwrap();
+
+        complete_tx_b.send(()).unwrap();
+        wait_for_task_to_finish(tasks.clone(), task_id_b).await;
+
+        assert!(tasks
+            .get_task_status(task_id_b)
+            .await
+            .unwrap()
+            .is_completed());
+    }
+
+    #[tokio::test]
+    async fn it_aborts_pending_tasks_without_clean_up() {
+        let tasks = Arc::new(SimpleTaskManager::new(Arc::new(
+            SimpleTaskManagerBackend::new(TaskLimits {
+                max_concurrent_tasks: 1,
+                ..Default::default()
+            }),
+        )));
+
+        let (task_a, _complete_tx_a) = NopTask::new_with_sender();
+        let (task_b, _complete_tx_b) = NopTask::new_with_sender();
+
+        tasks.schedule_task(task_a.boxed(), None).await.unwrap();
+        let task_id_b = tasks.schedule_task(task_b.boxed(), None).await.unwrap();
+
+        tasks.abort_tasks(task_id_b, false).await.unwrap();
+
+        assert_eq!(
+            serde_json::to_value(tasks.get_task_status(task_id_b).await.unwrap()).unwrap(),
+            json!({
+                "status": "aborted",
+                "cleanUp": {"status": "noCleanUp"}
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn it_cancels_recurring_tasks() {
+        let backend = SimpleTaskManagerBackend::default();
+
+        let recurring_task_id = backend
+            .schedule_recurring_task("* * * * *".parse().unwrap(), None, || {
+                NopTask::new_with_sender().0.boxed()
+            })
+            .await;
+
+        backend
+            .cancel_recurring_task(recurring_task_id)
+            .await
+            .unwrap();
+
+        assert!(matches!(
+            backend.cancel_recurring_task(recurring_task_id).await,
+            Err(TaskError::RecurringTaskNotFound { .. })
+        ));
+    }
+
+    #[ge_context::test]
+    async fn it_restores_tasks_after_a_restart(app_ctx: PostgresContext<NoTls>) {
+        let ctx = app_ctx.default_session_context().await.unwrap();
+
+        let tasks = Arc::new(ctx.tasks());
+
+        let (finished_task, complete_tx) = NopTask::new_with_sender();
+        let finished_task_id = tasks
+            .schedule_task(finished_task.boxed(), None)
+            .await
+            .unwrap();
+
+        complete_tx.send(()).unwrap();
+        wait_for_task_to_finish(tasks.clone(), finished_task_id).await;
+
+        let (interrupted_task, _complete_tx) = NopTask::new_with_sender();
+        let interrupted_task_id = tasks
+            .schedule_task(interrupted_task.boxed(), None)
+            .await
+            .unwrap();
+
+        // a new task manager on the same database behaves like one after a restart
+
+        let restarted_tasks = SimpleTaskManager::new(Arc::new(
+            SimpleTaskManagerBackend::new_persistent(
+                Arc::new(PostgresTaskStore::new(ctx.db().conn_pool)),
+                TaskLimits::default(),
+            )
+            .await
+            .unwrap(),
+        ));
+
+        let finished_task_status = serde_json::to_value(
+            restarted_tasks
+                .get_task_status(finished_task_id)
+                .await
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(finished_task_status["status"], json!("completed"));
+        assert_eq!(finished_task_status["taskType"], json!("nopTask"));
+        assert_eq!(finished_task_status["description"], json!("No operation"));
+        assert_eq!(finished_task_status["info"], json!("completed"));
+        assert_eq!(finished_task_status["timeTotal"], json!("00:00:00"));
+
+        assert_eq!(
+            serde_json::to_value(
+                restarted_tasks
+                    .get_task_status(interrupted_task_id)
+                    .await
+                    .unwrap()
+            )
+            .unwrap(),
+            json!({
+                "status": "failed",
+                "error": "The task was interrupted by a shutdown of the server",
+                "cleanUp": {"status": "noCleanUp"}
+            })
+        );
+
+        let task_ids = restarted_tasks
+            .list_tasks(TaskListOptions::default())
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|task| task.task_id)
+            .collect::<Vec<_>>();
+        assert_eq!(task_ids, vec![interrupted_task_id, finished_task_id]);
+    }
+
+    #[ge_context::test]
+    async fn it_deletes_finished_tasks(app_ctx: PostgresContext<NoTls>) {
+        let ctx = app_ctx.default_session_context().await.unwrap();
+
+        let tasks = Arc::new(ctx.tasks());
+
+        let (finished_task, complete_tx) = NopTask::new_with_sender();
+        let finished_task_id = tasks
+            .schedule_task(finished_task.boxed(), None)
+            .await
+            .unwrap();
+
+        complete_tx.send(()).unwrap();
+        wait_for_task_to_finish(tasks.clone(), finished_task_id).await;
+
+        let (running_task, _complete_tx) = NopTask::new_with_sender();
+        let running_task_id = tasks
+            .schedule_task(running_task.boxed(), None)
+            .await
+            .unwrap();
+
+        let store = PostgresTaskStore::new(ctx.db().conn_pool);
+
+        // running tasks are kept, regardless of their age
+        assert_eq!(
+            store.delete_finished_tasks(DateTime::now()).await.unwrap(),
+            1
+        );
+
+        let task_ids = tasks
+            .list_tasks(TaskListOptions::default())
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|task| task.task_id)
+            .collect::<Vec<_>>();
+        assert_eq!(task_ids, vec![running_task_id]);
+    }
+
+    #[ge_context::test]
+    #[allow(clippy::too_many_lines)]
+    async fn it_manages_recurring_tasks(app_ctx: PostgresContext<NoTls>) {
+        let ctx = app_ctx.default_session_context().await.unwrap();
+        let session_id = app_ctx.default_session_id().await;
+
+        let workflow_id = ctx
+            .db()
+            .register_workflow(Workflow {
+                operator: MockPointSource {
+                    params: MockPointSourceParams {
+                        points: vec![(0.0, 0.1).into(), (1.0, 1.1).into()],
+                    },
+                }
+                .boxed()
+                .into(),
+            })
+            .await
+            .unwrap();
+
+        let task = json!({
+            "type": "vector",
+            "workflowId": workflow_id,
+            "params": {
+                "name": null,
+                "displayName": "nightly points",
+                "description": null,
+                "query": {
+                    "spatialBounds": {
+                        "lowerLeftCoordinate": {"x": -10.0, "y": 20.0},
+                        "upperRightCoordinate": {"x": 50.0, "y": 80.0}
+                    },
+                    "timeInterval": {"start": 1_388_534_400_000_i64, "end": 1_388_534_401_000_i64},
+                    "spatialResolution": {"x": 0.1, "y": 0.1}
+                },
+                "format": "geoPackage",
+                "priority": "normal"
+            }
+        });
+
+        let add_recurring_task = |body: serde_json::Value| {
+            actix_web::test::TestRequest::post()
+                .uri("/tasks/recurring")
+                .append_header((header::AUTHORIZATION, Bearer::new(session_id.to_string())))
+                .set_json(body)
+        };
+
+        // every run creates a new dataset, so it cannot have a fixed name
+        let mut named_task = task.clone();
+        named_task["params"]["name"] = json!("nightly_points");
+        let res = send_test_request(
+            add_recurring_task(json!({"schedule": "0 2 * * *", "task": named_task})),
+            app_ctx.clone(),
+        )
+        .await;
+        assert_eq!(res.status(), 400);
+
+        let res = send_test_request(
+            add_recurring_task(json!({"schedule": "0 2 * * 8", "task": task})),
+            app_ctx.clone(),
+        )
+        .await;
+        assert_eq!(res.status(), 400);
+
+        let res = send_test_request(
+            add_recurring_task(json!({"schedule": "0 2 * * *", "task": task})),
+            app_ctx.clone(),
+        )
+        .await;
+        assert_eq!(res.status(), 200);
+        let recurring_task_id = read_body_json(res).await["id"].clone();
+
+        let list_recurring_tasks = || {
+            actix_web::test::TestRequest::get()
+                .uri("/tasks/recurring")
+                .append_header((header::AUTHORIZATION, Bearer::new(session_id.to_string())))
+        };
+
+        let res = send_test_request(list_recurring_tasks(), app_ctx.clone()).await;
+        assert_eq!(res.status(), 200);
+        assert_eq!(
+            read_body_json(res).await,
+            json!([{
+                "recurringTaskId": recurring_task_id,
+                "schedule": "0 2 * * *",
+                "task": task
+            }])
+        );
+
+        // a new task manager on the same database restores the recurring task
+
+        let store = PostgresTaskStore::new(ctx.db().conn_pool);
+        let restarted_tasks = SimpleTaskManagerBackend::new_persistent(
+            Arc::new(PostgresTaskStore::new(ctx.db().conn_pool)),
+            TaskLimits::default(),
+        )
+        .await
+        .unwrap();
+        restarted_tasks
+            .restore_recurring_tasks(|recurring_task| {
+                recurring_dataset_from_workflow_factory(app_ctx.clone(), &recurring_task.definition)
+            })
+            .await
+            .unwrap();
+
+        let restored_recurring_tasks = restarted_tasks.list_recurring_tasks_of_owner(None).await;
+        assert_eq!(
+            restored_recurring_tasks,
+            store.list_recurring_tasks().await.unwrap()
+        );
+        assert_eq!(
+            json!(restored_recurring_tasks[0].recurring_task_id),
+            recurring_task_id
+        );
+
+        // the restored factory creates the task of a run with the session that scheduled it
+        let factory = recurring_dataset_from_workflow_factory(
+            app_ctx.clone(),
+            &restored_recurring_tasks[0].definition,
+        )
+        .unwrap();
+        assert_eq!(factory().await.unwrap().task_type(), "create-dataset");
+
+        let remove_recurring_task = || {
+            actix_web::test::TestRequest::delete()
+                .uri(&format!(
+                    "/tasks/recurring/{}",
+                    recurring_task_id.as_str().unwrap()
+                ))
+                .append_header((header::AUTHORIZATION, Bearer::new(session_id.to_string())))
+        };
+
+        let res = send_test_request(remove_recurring_task(), app_ctx.clone()).await;
+        assert_eq!(res.status(), 200);
+
+        let res = send_test_request(list_recurring_tasks(), app_ctx.clone()).await;
+        assert_eq!(read_body_json(res).await, json!([]));
+        assert!(store.list_recurring_tasks().await.unwrap().is_empty());
+
+        let res = send_test_request(remove_recurring_task(), app_ctx.clone()).await;
+        assert_eq!(res.status(), 400);
+    }
 }
@@ -1,11 +1,16 @@
 use crate::api::model::responses::IdResponse;
-use crate::contexts::{ApplicationContext, SessionContext};
+use crate::contexts::{ApplicationContext, Session, SessionContext};
+use crate::datasets::resumable_upload::{
+    abort_resumable_upload, append_to_resumable_upload, complete_resumable_upload,
+    create_resumable_upload, finish_resumable_upload, resumable_upload_status, ResumableFileStatus,
+    ResumableUploadCreation, ResumableUploadError, ResumableUploadStatus,
+};
 use crate::datasets::upload::{FileId, FileUpload, Upload, UploadDb, UploadId, UploadRootPath};
 use crate::error::Result;
 use crate::error::{self, Error};
 use crate::util::path_with_base_path;
 use actix_multipart::Multipart;
-use actix_web::{web, FromRequest, Responder};
+use actix_web::{web, FromRequest, HttpRequest, HttpResponse, Responder};
 use futures::StreamExt;
 use gdal::vector::LayerAccess;
 use geoengine_datatypes::util::Identifier;
@@ -16,6 +21,9 @@ use std::path::Path;
 use tokio::{fs, io::AsyncWriteExt};
 use utoipa::{ToResponse, ToSchema};
 
+const UPLOAD_OFFSET_HEADER: &str = "Upload-Offset";
+const UPLOAD_CHECKSUM_HEADER: &str = "Upload-Checksum";
+
 pub(crate) fn init_upload_routes<C>(cfg: &mut web::ServiceConfig)
 where
     C: ApplicationContext,
@@ -29,6 +37,23 @@ where
         .service(
             web::resource("/uploads/{upload_id}/files/{file_name}/layers")
                 .route(web::get().to(list_upload_file_layers_handler)),
+        )
+        .service(
+            web::resource("/uploads/resumable")
+                .route(web::post().to(create_resumable_upload_handler::<C>)),
+        )
+        .service(
+            web::resource("/uploads/resumable/{upload_id}")
+                .route(web::get().to(resumable_upload_status_handler::<C>))
+                .route(web::delete().to(abort_resumable_upload_handler::<C>)),
+        )
+        .service(
+            web::resource("/uploads/resumable/{upload_id}/files/{file_name}")
+                .route(web::patch().to(append_to_resumable_upload_handler::<C>)),
+        )
+        .service(
+            web::resource("/uploads/resumable/{upload_id}/finish")
+                .route(web::post().to(finish_resumable_upload_handler::<C>)),
         );
 }
 
@@ -119,6 +144,188 @@ async fn upload_handler<C: ApplicationContext>(
     Ok(web::Json(IdResponse::from(upload_id)))
 }
 
+/// Creates a resumable upload for files that are too large for a single request.
+///
+/// The files are uploaded in chunks and the upload is finished afterwards.
+/// Unfinished uploads expire if they do not progress.
+#[utoipa::path(
+    tag = "Uploads",
+    post,
+    path = "/uploads/resumable",
+    request_body = ResumableUploadCreation,
+    responses(
+        (status = 200, body = ResumableUploadStatus)
+    ),
+    security(
+        ("session_token" = [])
+    )
+)]
+async fn create_resumable_upload_handler<C: ApplicationContext>(
+    session: C::Session,
+    creation: web::Json<ResumableUploadCreation>,
+) -> Result<web::Json<ResumableUploadStatus>> {
+    let status = create_resumable_upload(session.owner(), creation.into_inner()).await?;
+
+    Ok(web::Json(status))
+}
+
+/// Gets the offsets of the files of a resumable upload for resuming it.
+#[utoipa::path(
+    tag = "Uploads",
+    get,
+    path = "/uploads/resumable/{upload_id}",
+    responses(
+        (status = 200, body = ResumableUploadStatus)
+    ),
+    params(
+        ("upload_id" = UploadId, description = "Upload id"),
+    ),
+    security(
+        ("session_token" = [])
+    )
+)]
+async fn resumable_upload_status_handler<C: ApplicationContext>(
+    session: C::Session,
+    upload_id: web::Path<UploadId>,
+) -> Result<web::Json<ResumableUploadStatus>> {
+    let status = resumable_upload_status(upload_id.into_inner(), session.owner()).await?;
+
+    Ok(web::Json(status))
+}
+
+/// Appends the request body to a file of a resumable upload.
+///
+/// The `Upload-Offset` header must contain the current offset of the file.
+/// If the request is interrupted, the received bytes are kept and the upload can be resumed at the new offset.
+/// If the `Upload-Checksum` header contains the hex encoded SHA-256 checksum of the chunk, a partial or corrupted chunk is discarded.
+#[utoipa::path(
+    tag = "Uploads",
+    patch,
+    path = "/uploads/resumable/{upload_id}/files/{file_name}",
+    request_body(content = Vec<u8>, content_type = "application/offset+octet-stream"),
+    responses(
+        (status = 200, body = ResumableFileStatus)
+    ),
+    params(
+        ("upload_id" = UploadId, description = "Upload id"),
+        ("file_name" = String, description = "File name"),
+        ("Upload-Offset" = u64, Header, description = "The offset of the chunk in the file"),
+        ("Upload-Checksum" = Option<String>, Header, description = "The hex encoded SHA-256 checksum of the chunk"),
+    ),
+    security(
+        ("session_token" = [])
+    )
+)]
+async fn append_to_resumable_upload_handler<C: ApplicationContext>(
+    session: C::Session,
+    path: web::Path<(UploadId, String)>,
+    request: HttpRequest,
+    body: web::Payload,
+) -> Result<web::Json<ResumableFileStatus>> {
+    let (upload_id, file_name) = path.into_inner();
+
+    let offset = request
+        .headers()
+        .get(UPLOAD_OFFSET_HEADER)
+        .and_then(|offset| offset.to_str().ok())
+        .and_then(|offset| offset.parse::<u64>().ok())
+        .ok_or(ResumableUploadError::MissingUploadOffset)?;
+
+    let checksum = request
+        .headers()
+        .get(UPLOAD_CHECKSUM_HEADER)
+        .map(|checksum| {
+            checksum
+                .to_str()
+                .map_err(|_| ResumableUploadError::InvalidSha256Checksum {
+                    checksum: String::from_utf8_lossy(checksum.as_bytes()).to_string(),
+                })
+        })
+        .transpose()?;
+
+    let chunk = body.map(|bytes| {
+        bytes.map_err(|error| {
+            Error::from(ResumableUploadError::ResumableUploadPayload {
+                reason: error.to_string(),
+            })
+        })
+    });
+
+    let status = append_to_resumable_upload(
+        upload_id,
+        session.owner(),
+        &file_name,
+        offset,
+        checksum,
+        chunk,
+    )
+    .await?;
+
+    Ok(web::Json(status))
+}
+
+/// Finishes a resumable upload after all of its files were uploaded.
+///
+/// The sizes and checksums of the files are verified.
+/// Afterwards, the upload can be used like any other upload.
+#[utoipa::path(
+    tag = "Uploads",
+    post,
+    path = "/uploads/resumable/{upload_id}/finish",
+    responses(
+        (status = 200, response = crate::api::model::responses::IdResponse::<UploadId>)
+    ),
+    params(
+        ("upload_id" = UploadId, description = "Upload id"),
+    ),
+    security(
+        ("session_token" = [])
+    )
+)]
+async fn finish_resumable_upload_handler<C: ApplicationContext>(
+    session: C::Session,
+    app_ctx: web::Data<C>,
+    upload_id: web::Path<UploadId>,
+) -> Result<web::Json<IdResponse<UploadId>>> {
+    let upload_id = upload_id.into_inner();
+
+    let finished = finish_resumable_upload(upload_id, session.owner()).await?;
+
+    app_ctx
+        .session_context(session)
+        .db()
+        .create_upload(finished.upload.clone())
+        .await?;
+
+    complete_resumable_upload(finished).await?;
+
+    Ok(web::Json(IdResponse::from(upload_id)))
+}
+
+/// Aborts a resumable upload and deletes its files.
+#[utoipa::path(
+    tag = "Uploads",
+    delete,
+    path = "/uploads/resumable/{upload_id}",
+    responses(
+        (status = 200, description = "OK")
+    ),
+    params(
+        ("upload_id" = UploadId, description = "Upload id"),
+    ),
+    security(
+        ("session_token" = [])
+    )
+)]
+async fn abort_resumable_upload_handler<C: ApplicationContext>(
+    session: C::Session,
+    upload_id: web::Path<UploadId>,
+) -> Result<HttpResponse> {
+    abort_resumable_upload(upload_id.into_inner(), session.owner()).await?;
+
+    Ok(HttpResponse::Ok().finish())
+}
+
 #[derive(Deserialize, Serialize, ToSchema, ToResponse)]
 pub struct UploadFilesResponse {
     files: Vec<String>,
@@ -290,4 +497,90 @@ mod tests {
             ]
         );
     }
+
+    #[ge_context::test]
+    async fn it_uploads_resumable_in_chunks(app_ctx: PostgresContext<NoTls>) {
+        let mut test_data = TestDataUploads::default(); // remember created folder and remove them on drop
+
+        let ctx = app_ctx.default_session_context().await.unwrap();
+        let session_id = ctx.session().id();
+
+        let req = test::TestRequest::post()
+            .uri("/uploads/resumable")
+            .append_header((header::AUTHORIZATION, Bearer::new(session_id.to_string())))
+            .set_json(serde_json::json!({
+                "files": [{
+                    "name": "foo.txt",
+                    "byteSize": 6,
+                    "sha256": "c3ab8ff13720e8ad9047dd39466b3c8974e592c2fa383d4a3960714caef0c4f2"
+                }]
+            }));
+        let res = send_test_request(req, app_ctx.clone()).await;
+        assert_eq!(res.status(), 200);
+
+        let status: ResumableUploadStatus = test::read_body_json(res).await;
+        test_data.uploads.push(status.id);
+
+        let req = test::TestRequest::patch()
+            .uri(&format!("/uploads/resumable/{}/files/foo.txt", status.id))
+            .append_header((header::AUTHORIZATION, Bearer::new(session_id.to_string())))
+            .append_header((UPLOAD_OFFSET_HEADER, "0"))
+            .set_payload("foo");
+        let res = send_test_request(req, app_ctx.clone()).await;
+        assert_eq!(res.status(), 200);
+
+        let file: ResumableFileStatus = test::read_body_json(res).await;
+        assert_eq!(file.offset, 3);
+
+        // finishing an incomplete upload fails
+        let req = test::TestRequest::post()
+            .uri(&format!("/uploads/resumable/{}/finish", status.id))
+            .append_header((header::AUTHORIZATION, Bearer::new(session_id.to_string())));
+        let res = send_test_request(req, app_ctx.clone()).await;
+        assert_eq!(res.status(), 400);
+
+        // a chunk without offset is rejected
+        let req = test::TestRequest::patch()
+            .uri(&format!("/uploads/resumable/{}/files/foo.txt", status.id))
+            .append_header((header::AUTHORIZATION, Bearer::new(session_id.to_string())))
+            .set_payload("bar");
+        let res = send_test_request(req, app_ctx.clone()).await;
+        assert_eq!(res.status(), 400);
+
+        // a corrupted chunk is discarded
+        let req = test::TestRequest::patch()
+            .uri(&format!("/uploads/resumable/{}/files/foo.txt", status.id))
+            .append_header((header::AUTHORIZATION, Bearer::new(session_id.to_string())))
+            .append_header((UPLOAD_OFFSET_HEADER, "3"))
+            .append_header((UPLOAD_CHECKSUM_HEADER, "0".repeat(64)))
+            .set_payload("bar");
+        let res = send_test_request(req, app_ctx.clone()).await;
+        assert_eq!(res.status(), 400);
+
+        let req = test::TestRequest::patch()
+            .uri(&format!("/uploads/resumable/{}/files/foo.txt", status.id))
+            .append_header((header::AUTHORIZATION, Bearer::new(session_id.to_string())))
+            .append_header((UPLOAD_OFFSET_HEADER, "3"))
+            .set_payload("bar");
+        let res = send_test_request(req, app_ctx.clone()).await;
+        assert_eq!(res.status(), 200);
+
+        let req = test::TestRequest::post()
+            .uri(&format!("/uploads/resumable/{}/finish", status.id))
+            .append_header((header::AUTHORIZATION, Bearer::new(session_id.to_string())));
+        let res = send_test_request(req, app_ctx.clone()).await;
+        assert_eq!(res.status(), 200);
+
+        let upload: IdResponse<UploadId> = test::read_body_json(res).await;
+        assert_eq!(upload.id, status.id);
+
+        let content = std::fs::read_to_string(upload.id.root_path().unwrap().join("foo.txt"));
+        assert_eq!(content.unwrap(), "foobar");
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/uploads/resumable/{}", status.id))
+            .append_header((header::AUTHORIZATION, Bearer::new(session_id.to_string())));
+        let res = send_test_request(req, app_ctx).await;
+        assert_eq!(res.status(), 400);
+    }
 }
@@ -2,18 +2,27 @@ use crate::api::handlers::tasks::TaskResponse;
 use crate::api::model::datatypes::{BandSelection, DataId, TimeInterval};
 use crate::api::model::responses::IdResponse;
 use crate::api::ogc::util::{parse_bbox, parse_time};
-use crate::contexts::{ApplicationContext, SessionContext};
+use crate::contexts::{ApplicationContext, Session, SessionContext};
 use crate::datasets::listing::{DatasetProvider, Provenance, ProvenanceOutput};
-use crate::datasets::{schedule_raster_dataset_from_workflow_task, RasterDatasetFromWorkflow};
+use crate::datasets::{
+    schedule_raster_dataset_from_workflow_task, schedule_vector_dataset_from_workflow_task,
+    RasterDatasetFromWorkflow, VectorDatasetFromWorkflow,
+};
 use crate::error::Result;
 use crate::layers::storage::LayerProviderDb;
 use crate::util::config::get_config_element;
 use crate::util::parsing::{
     parse_band_selection, parse_spatial_partition, parse_spatial_resolution,
 };
+use crate::workflows::export::{
+    export_archive_path, schedule_workflow_export_task, ExportId, WorkflowExport,
+    WorkflowExportError,
+};
 use crate::workflows::registry::WorkflowRegistry;
 use crate::workflows::workflow::{Workflow, WorkflowId};
 use crate::workflows::{RasterWebsocketStreamHandler, VectorWebsocketStreamHandler};
+use actix_files::NamedFile;
+use actix_web::http::header::{ContentDisposition, DispositionParam, DispositionType};
 use actix_web::{web, FromRequest, HttpRequest, HttpResponse, Responder};
 use futures::future::join_all;
 use geoengine_datatypes::error::{BoxedResultExt, ErrorSource};
@@ -42,6 +51,11 @@ where
         // TODO: rename to plural `workflows`
         web::scope("/workflow")
             .service(web::resource("").route(web::post().to(register_workflow_handler::<C>)))
+            // must be registered before the workflow scope, which would match `export` as an id
+            .service(
+                web::resource("/export/{id}")
+                    .route(web::get().to(download_workflow_export_handler::<C>)),
+            )
             .service(
                 web::scope("/{id}")
                     .service(web::resource("").route(web::get().to(load_workflow_handler::<C>)))
@@ -57,6 +71,10 @@ where
                         web::resource("/allMetadata/zip")
                             .route(web::get().to(get_workflow_all_metadata_zip_handler::<C>)),
                     )
+                    .service(
+                        web::resource("/export")
+                            .route(web::post().to(workflow_export_handler::<C>)),
+                    )
                     .service(
                         web::resource("/rasterStream")
                             .route(web::get().to(raster_stream_websocket::<C>)),
@@ -70,6 +88,10 @@ where
     .service(
         web::resource("datasetFromWorkflow/{id}")
             .route(web::post().to(dataset_from_workflow_handler::<C>)),
+    )
+    .service(
+        web::resource("vectorDatasetFromWorkflow/{id}")
+            .route(web::post().to(vector_dataset_from_workflow_handler::<C>)),
     );
 }
 
@@ -270,11 +292,11 @@ async fn get_workflow_provenance_handler<C: ApplicationContext>(
 
 #[derive(Serialize, ToSchema)]
 pub struct ProvenanceEntry {
-    provenance: Provenance,
+    pub(crate) provenance: Provenance,
     data: Vec<DataId>,
 }
 
-async fn workflow_provenance<C: SessionContext>(
+pub(crate) async fn workflow_provenance<C: SessionContext>(
     workflow: &Workflow,
     ctx: &C,
 ) -> Result<Vec<ProvenanceEntry>> {
@@ -474,6 +496,129 @@ async fn dataset_from_workflow_handler<C: ApplicationContext>(
     Ok(web::Json(TaskResponse::new(task_id)))
 }
 
+/// Create a task for creating a new vector dataset from the result of the workflow given by its `id` and the dataset parameters in the request body.
+/// The features are written to a `GeoPackage` or `FlatGeobuf` file.
+/// Returns the id of the created task
+#[utoipa::path(
+    tag = "Workflows",
+    post,
+    path = "/vectorDatasetFromWorkflow/{id}",
+    request_body = VectorDatasetFromWorkflow,
+    responses(
+        (status = 200, description = "Id of created task", body = TaskResponse,
+            example = json!({"taskId": "7f8a4cfe-76ab-4972-b347-b197e5ef0f3c"})
+        )
+    ),
+    params(
+        ("id" = WorkflowId, description = "Workflow id")
+    ),
+    security(
+        ("session_token" = [])
+    )
+)]
+async fn vector_dataset_from_workflow_handler<C: ApplicationContext>(
+    id: web::Path<WorkflowId>,
+    session: C::Session,
+    app_ctx: web::Data<C>,
+    info: web::Json<VectorDatasetFromWorkflow>,
+) -> Result<web::Json<TaskResponse>> {
+    let ctx = Arc::new(app_ctx.session_context(session));
+
+    let id = id.into_inner();
+    let workflow = ctx.db().load_workflow(&id).await?;
+
+    let task_id = schedule_vector_dataset_from_workflow_task(
+        format!("workflow {id}"),
+        workflow,
+        ctx,
+        info.into_inner(),
+    )
+    .await?;
+
+    Ok(web::Json(TaskResponse::new(task_id)))
+}
+
+/// Create a task for exporting the result of the workflow given by its `id` for the given bounds.
+/// The task creates a ZIP archive with the data, the workflow and its provenance.
+/// Rasters are exported as `GeoTIFF`s or `NetCDF` files, vectors as `GeoPackage`, CSV or `GeoJSON` and plots as JSON or PNG.
+/// Returns the id of the created task
+#[utoipa::path(
+    tag = "Workflows",
+    post,
+    path = "/workflow/{id}/export",
+    request_body = WorkflowExport,
+    responses(
+        (status = 200, description = "Id of created task", body = TaskResponse,
+            example = json!({"taskId": "7f8a4cfe-76ab-4972-b347-b197e5ef0f3c"})
+        )
+    ),
+    params(
+        ("id" = WorkflowId, description = "Workflow id")
+    ),
+    security(
+        ("session_token" = [])
+    )
+)]
+async fn workflow_export_handler<C: ApplicationContext>(
+    id: web::Path<WorkflowId>,
+    session: C::Session,
+    app_ctx: web::Data<C>,
+    request: web::Json<WorkflowExport>,
+) -> Result<web::Json<TaskResponse>> {
+    let ctx = Arc::new(app_ctx.session_context(session));
+
+    let id = id.into_inner();
+    let workflow = ctx.db().load_workflow(&id).await?;
+    let compression_num_threads =
+        get_config_element::<crate::util::config::Gdal>()?.compression_num_threads;
+
+    let task_id = schedule_workflow_export_task(
+        id,
+        workflow,
+        ctx,
+        request.into_inner(),
+        compression_num_threads,
+    )
+    .await?;
+
+    Ok(web::Json(TaskResponse::new(task_id)))
+}
+
+/// Downloads the ZIP archive of a finished workflow export.
+/// The id of the export is part of the result of the export task.
+/// Exports can only be downloaded by the user that created them and expire after a configured time.
+#[utoipa::path(
+    tag = "Workflows",
+    get,
+    path = "/workflow/export/{id}",
+    responses(
+        (status = 200, response = crate::api::model::responses::ZipResponse)
+    ),
+    params(
+        ("id" = ExportId, description = "Export id")
+    ),
+    security(
+        ("session_token" = [])
+    )
+)]
+async fn download_workflow_export_handler<C: ApplicationContext>(
+    id: web::Path<ExportId>,
+    session: C::Session,
+) -> Result<impl Responder> {
+    let export = id.into_inner();
+
+    let archive_path = export_archive_path(export, session.owner()).await?;
+
+    let file = NamedFile::open_async(archive_path)
+        .await
+        .map_err(|_| WorkflowExportError::UnknownExport { export })?;
+
+    Ok(file.set_content_disposition(ContentDisposition {
+        disposition: DispositionType::Attachment,
+        parameters: vec![DispositionParam::Filename(format!("export_{export}.zip"))],
+    }))
+}
+
 /// The query parameters for `raster_stream_websocket`.
 #[derive(Clone, Debug, PartialEq, Deserialize, IntoParams)]
 #[serde(rename_all = "camelCase")]
@@ -674,7 +819,9 @@ mod tests {
     use crate::api::model::responses::ErrorResponse;
     use crate::contexts::{PostgresContext, Session, SimpleApplicationContext};
     use crate::datasets::storage::DatasetStore;
-    use crate::datasets::{DatasetName, RasterDatasetFromWorkflowResult};
+    use crate::datasets::{
+        DatasetName, RasterDatasetFromWorkflowResult, VectorDatasetFromWorkflowResult,
+    };
     use crate::ge_context;
     use crate::tasks::util::test::wait_for_task_to_finish;
     use crate::tasks::{TaskManager, TaskStatus};
@@ -683,21 +830,28 @@ mod tests {
         add_ndvi_to_datasets, check_allowed_http_methods, check_allowed_http_methods2,
         read_body_string, register_ndvi_workflow_helper, send_test_request, TestDataUploads,
     };
+    use crate::workflows::export::WorkflowExportResult;
     use crate::workflows::registry::WorkflowRegistry;
     use actix_web::dev::ServiceResponse;
     use actix_web::{http::header, http::Method, test};
     use actix_web_httpauth::headers::authorization::Bearer;
-    use geoengine_datatypes::collections::MultiPointCollection;
+    use futures::StreamExt;
+    use geoengine_datatypes::collections::{FeatureCollectionInfos, MultiPointCollection};
+    use geoengine_datatypes::dataset::NamedData;
     use geoengine_datatypes::primitives::CacheHint;
     use geoengine_datatypes::primitives::{
-        ContinuousMeasurement, FeatureData, Measurement, MultiPoint, RasterQueryRectangle,
-        SpatialPartition2D, SpatialResolution, TimeInterval,
+        BoundingBox2D, ClassificationMeasurement, ColumnSelection, ContinuousMeasurement,
+        FeatureData, FeatureDataType, Measurement, MultiPoint, RasterQueryRectangle,
+        SpatialPartition2D, SpatialResolution, TimeInterval, VectorQueryRectangle,
+    };
+    use geoengine_datatypes::raster::{
+        GridShape, RasterDataType, RenameBands, TilingSpecification,
     };
-    use geoengine_datatypes::raster::{GridShape, RasterDataType, TilingSpecification};
     use geoengine_datatypes::spatial_reference::SpatialReference;
     use geoengine_operators::engine::{
-        ExecutionContext, MultipleRasterOrSingleVectorSource, PlotOperator, RasterBandDescriptor,
-        RasterBandDescriptors, TypedOperator,
+        ExecutionContext, InitializedVectorOperator, MultipleRasterOrSingleVectorSource,
+        MultipleRasterSources, PlotOperator, RasterBandDescriptor, RasterBandDescriptors,
+        TypedOperator, VectorColumnInfo,
     };
     use geoengine_operators::engine::{RasterOperator, RasterResultDescriptor, VectorOperator};
     use geoengine_operators::mock::{
@@ -705,7 +859,10 @@ mod tests {
         MockRasterSourceParams,
     };
     use geoengine_operators::plot::{Statistics, StatisticsParams};
-    use geoengine_operators::source::{GdalSource, GdalSourceParameters};
+    use geoengine_operators::processing::{RasterStacker, RasterStackerParams};
+    use geoengine_operators::source::{
+        GdalSource, GdalSourceParameters, OgrSource, OgrSourceParameters,
+    };
     use geoengine_operators::util::input::MultiRasterOrVectorOperator::Raster;
     use geoengine_operators::util::raster_stream_to_geotiff::{
         single_timestep_raster_stream_to_geotiff_bytes, GdalGeoTiffDatasetMetadata,
@@ -1114,6 +1271,7 @@ mod tests {
             operator: Statistics {
                 params: StatisticsParams {
                     column_names: vec![],
+                    estimate_quantiles: None,
                 },
                 sources: MultipleRasterOrSingleVectorSource {
                     source: Raster(vec![]),
@@ -1192,6 +1350,60 @@ mod tests {
         );
     }
 
+    #[ge_context::test]
+    async fn it_shows_the_provenance_of_pinned_dataset_versions(app_ctx: PostgresContext<NoTls>) {
+        let ctx = app_ctx.default_session_context().await.unwrap();
+
+        let session_id = app_ctx.default_session_id().await;
+        let (dataset_id, dataset) = add_ndvi_to_datasets(&app_ctx).await;
+
+        let versions = ctx.db().list_dataset_versions(&dataset_id).await.unwrap();
+
+        let workflow = Workflow {
+            operator: TypedOperator::Raster(
+                GdalSource {
+                    params: GdalSourceParameters {
+                        data: NamedData {
+                            name: format!("{}@1", dataset.name),
+                            ..dataset
+                        },
+                    },
+                }
+                .boxed(),
+            ),
+        };
+
+        let id = ctx.db().register_workflow(workflow.clone()).await.unwrap();
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/workflow/{id}/provenance"))
+            .append_header((header::AUTHORIZATION, Bearer::new(session_id.to_string())));
+        let res = send_test_request(req, app_ctx).await;
+
+        let res_status = res.status();
+        let res_body = read_body_string(res).await;
+        assert_eq!(res_status, 200, "{res_body:?}");
+
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&res_body).unwrap(),
+            serde_json::json!([
+                {
+                    "provenance": {
+                        "citation": "Sample Citation",
+                        "license": "Sample License",
+                        "uri": "http://example.org/"
+                    },
+                    "data": [
+                        {
+                            "type": "internal",
+                            "datasetId": versions[0].id.to_string()
+                        }
+                    ]
+                }
+            ])
+        );
+    }
+
     #[ge_context::test]
     async fn it_does_not_register_invalid_workflow(app_ctx: PostgresContext<NoTls>) {
         let ctx = app_ctx.default_session_context().await.unwrap();
@@ -1494,4 +1706,395 @@ mod tests {
             result.as_slice()
         );
     }
+
+    #[ge_context::test]
+    async fn vector_dataset_from_workflow_task_success(app_ctx: PostgresContext<NoTls>) {
+        let ctx = app_ctx.default_session_context().await.unwrap();
+
+        let session_id = app_ctx.default_session_id().await;
+
+        let classification = Measurement::Classification(ClassificationMeasurement {
+            measurement: "land cover".to_string(),
+            classes: [(1, "forest"), (2, "water"), (3, "urban")]
+                .into_iter()
+                .map(|(class, name)| (class, name.to_string()))
+                .collect(),
+        });
+
+        let workflow = Workflow {
+            operator: MockFeatureCollectionSource::with_collections_and_measurements(
+                vec![MultiPointCollection::from_data(
+                    MultiPoint::many(vec![(0.0, 0.1), (1.0, 1.1), (2.0, 2.1)]).unwrap(),
+                    vec![
+                        TimeInterval::new_unchecked(0, 500),
+                        TimeInterval::new_unchecked(500, 1000),
+                        TimeInterval::new_unchecked(5000, 6000),
+                    ],
+                    [
+                        ("foo".to_string(), FeatureData::Float(vec![1.0, 2.0, 3.0])),
+                        ("bar".to_string(), FeatureData::Category(vec![1, 2, 3])),
+                    ]
+                    .into_iter()
+                    .collect(),
+                    CacheHint::default(),
+                )
+                .unwrap()],
+                [("bar".to_string(), classification.clone())]
+                    .into_iter()
+                    .collect(),
+            )
+            .boxed()
+            .into(),
+        };
+
+        let workflow_id = ctx.db().register_workflow(workflow).await.unwrap();
+
+        let req = test::TestRequest::post()
+            .uri(&format!("/vectorDatasetFromWorkflow/{workflow_id}"))
+            .append_header((header::AUTHORIZATION, Bearer::new(session_id.to_string())))
+            .set_json(json!({
+                "displayName": "foo",
+                "description": null,
+                "query": {
+                    "spatialBounds": {
+                        "lowerLeftCoordinate": {"x": -10.0, "y": -10.0},
+                        "upperRightCoordinate": {"x": 10.0, "y": 10.0}
+                    },
+                    "timeInterval": {"start": 0, "end": 1000},
+                    "spatialResolution": {"x": 0.1, "y": 0.1}
+                },
+                "format": "flatGeobuf"
+            }));
+        let res = send_test_request(req, app_ctx.clone()).await;
+
+        assert_eq!(res.status(), 200, "{:?}", res.response());
+
+        let task_response =
+            serde_json::from_str::<TaskResponse>(&read_body_string(res).await).unwrap();
+
+        let tasks = Arc::new(ctx.tasks());
+
+        wait_for_task_to_finish(tasks.clone(), task_response.task_id).await;
+
+        let status = tasks.get_task_status(task_response.task_id).await.unwrap();
+
+        let response = if let TaskStatus::Completed { info, .. } = status {
+            info.as_any_arc()
+                .downcast::<VectorDatasetFromWorkflowResult>()
+                .unwrap()
+                .as_ref()
+                .clone()
+        } else {
+            panic!("Task must be completed");
+        };
+
+        // automatically deletes uploads on drop
+        let _test_uploads = TestDataUploads {
+            uploads: vec![response.upload],
+        };
+
+        let source = OgrSource {
+            params: OgrSourceParameters {
+                data: response.dataset.into(),
+                attribute_projection: None,
+                attribute_filters: None,
+            },
+        }
+        .boxed()
+        .initialize(
+            WorkflowOperatorPath::initialize_root(),
+            &ctx.execution_context().unwrap(),
+        )
+        .await
+        .unwrap();
+
+        // categories are stored as integers, but keep their classes
+        assert_eq!(
+            source.result_descriptor().columns.get("bar").unwrap(),
+            &VectorColumnInfo {
+                data_type: FeatureDataType::Int,
+                measurement: classification,
+            }
+        );
+
+        let processor = source.query_processor().unwrap().multi_point().unwrap();
+
+        let query_ctx = ctx.query_context().unwrap();
+        let collections: Vec<MultiPointCollection> = processor
+            .vector_query(
+                VectorQueryRectangle {
+                    spatial_bounds: BoundingBox2D::new((-180., -90.).into(), (180., 90.).into())
+                        .unwrap(),
+                    time_interval: TimeInterval::default(),
+                    spatial_resolution: SpatialResolution::zero_point_one(),
+                    attributes: ColumnSelection::all(),
+                },
+                &query_ctx,
+            )
+            .await
+            .unwrap()
+            .map(Result::unwrap)
+            .collect()
+            .await;
+
+        // only the features within the query of the task were written
+        assert_eq!(collections.len(), 1);
+        assert_eq!(collections[0].len(), 2);
+        assert_eq!(
+            collections[0]
+                .data("foo")
+                .unwrap()
+                .float_options_iter()
+                .collect::<Vec<_>>(),
+            vec![Some(1.0), Some(2.0)]
+        );
+        assert_eq!(
+            collections[0]
+                .data("bar")
+                .unwrap()
+                .float_options_iter()
+                .collect::<Vec<_>>(),
+            vec![Some(1.0), Some(2.0)]
+        );
+    }
+
+    #[ge_context::test]
+    #[allow(clippy::too_many_lines)]
+    async fn it_exports_a_vector_workflow(app_ctx: PostgresContext<NoTls>) {
+        let ctx = app_ctx.default_session_context().await.unwrap();
+
+        let session_id = app_ctx.default_session_id().await;
+
+        let workflow = Workflow {
+            operator: MockFeatureCollectionSource::single(
+                MultiPointCollection::from_data(
+                    MultiPoint::many(vec![(0.0, 0.1), (1.0, 1.1), (2.0, 2.1)]).unwrap(),
+                    vec![
+                        TimeInterval::new_unchecked(0, 500),
+                        TimeInterval::new_unchecked(500, 1000),
+                        TimeInterval::new_unchecked(5000, 6000),
+                    ],
+                    [("foo".to_string(), FeatureData::Float(vec![1.0, 2.0, 3.0]))]
+                        .into_iter()
+                        .collect(),
+                    CacheHint::default(),
+                )
+                .unwrap(),
+            )
+            .boxed()
+            .into(),
+        };
+
+        let workflow_id = ctx.db().register_workflow(workflow).await.unwrap();
+
+        // vectors cannot be exported as rasters
+        let req = test::TestRequest::post()
+            .uri(&format!("/workflow/{workflow_id}/export"))
+            .append_header((header::AUTHORIZATION, Bearer::new(session_id.to_string())))
+            .set_json(json!({
+                "bounds": {
+                    "spatialReference": "EPSG:4326",
+                    "boundingBox": {
+                        "lowerLeftCoordinate": {"x": -10.0, "y": -10.0},
+                        "upperRightCoordinate": {"x": 10.0, "y": 10.0}
+                    },
+                    "timeInterval": {"start": 0, "end": 1000}
+                },
+                "spatialResolution": {"x": 0.1, "y": 0.1},
+                "format": "geoTiff"
+            }));
+        let res = send_test_request(req, app_ctx.clone()).await;
+
+        ErrorResponse::assert(
+            res,
+            400,
+            "WorkflowExport",
+            "WorkflowExportError: The format GeoTiff cannot be used for exporting a Vector workflow",
+        )
+        .await;
+
+        let req = test::TestRequest::post()
+            .uri(&format!("/workflow/{workflow_id}/export"))
+            .append_header((header::AUTHORIZATION, Bearer::new(session_id.to_string())))
+            .set_json(json!({
+                "bounds": {
+                    "spatialReference": "EPSG:4326",
+                    "boundingBox": {
+                        "lowerLeftCoordinate": {"x": -10.0, "y": -10.0},
+                        "upperRightCoordinate": {"x": 10.0, "y": 10.0}
+                    },
+                    "timeInterval": {"start": 0, "end": 1000}
+                },
+                "spatialResolution": {"x": 0.1, "y": 0.1},
+                "format": "csv"
+            }));
+        let res = send_test_request(req, app_ctx.clone()).await;
+
+        assert_eq!(res.status(), 200, "{:?}", res.response());
+
+        let task_response =
+            serde_json::from_str::<TaskResponse>(&read_body_string(res).await).unwrap();
+
+        let tasks = Arc::new(ctx.tasks());
+
+        wait_for_task_to_finish(tasks.clone(), task_response.task_id).await;
+
+        let status = tasks.get_task_status(task_response.task_id).await.unwrap();
+
+        let export = if let TaskStatus::Completed { info, .. } = status {
+            info.as_any_arc()
+                .downcast::<WorkflowExportResult>()
+                .unwrap()
+                .export
+        } else {
+            panic!("Task must be completed");
+        };
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/workflow/export/{export}"))
+            .append_header((header::AUTHORIZATION, Bearer::new(session_id.to_string())));
+        let res = send_test_request(req, app_ctx.clone()).await;
+
+        assert_eq!(res.status(), 200, "{:?}", res.response());
+
+        let bytes = test::read_body(res).await;
+        let mut archive = ZipArchive::new(Cursor::new(bytes)).unwrap();
+
+        let mut file_names = archive
+            .file_names()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+        file_names.sort();
+        assert_eq!(
+            file_names,
+            vec!["citation.json", "features.csv", "workflow.json"]
+        );
+
+        let mut csv = String::new();
+        archive
+            .by_name("features.csv")
+            .unwrap()
+            .read_to_string(&mut csv)
+            .unwrap();
+
+        // a header and the features within the query
+        assert_eq!(csv.lines().count(), 3);
+        assert!(csv.lines().next().unwrap().contains("foo"));
+        assert!(csv.contains("MULTIPOINT"));
+        assert!(!csv.contains("2.1"));
+
+        std::fs::remove_dir_all(export.root_path().unwrap()).unwrap();
+    }
+
+    #[ge_context::test]
+    async fn it_exports_a_multi_band_raster_workflow(app_ctx: PostgresContext<NoTls>) {
+        let ctx = app_ctx.default_session_context().await.unwrap();
+
+        let session_id = app_ctx.default_session_id().await;
+
+        let (_, dataset) = add_ndvi_to_datasets(&app_ctx).await;
+
+        let ndvi = || {
+            GdalSource {
+                params: GdalSourceParameters {
+                    data: dataset.clone(),
+                },
+            }
+            .boxed()
+        };
+
+        let workflow = Workflow {
+            operator: TypedOperator::Raster(
+                RasterStacker {
+                    params: RasterStackerParams {
+                        rename_bands: RenameBands::Default,
+                    },
+                    sources: MultipleRasterSources {
+                        rasters: vec![ndvi(), ndvi()],
+                    },
+                }
+                .boxed(),
+            ),
+        };
+
+        let workflow_id = ctx.db().register_workflow(workflow).await.unwrap();
+
+        let req = test::TestRequest::post()
+            .uri(&format!("/workflow/{workflow_id}/export"))
+            .append_header((header::AUTHORIZATION, Bearer::new(session_id.to_string())))
+            .set_json(json!({
+                "bounds": {
+                    "spatialReference": "EPSG:4326",
+                    "boundingBox": {
+                        "lowerLeftCoordinate": {"x": -10.0, "y": 20.0},
+                        "upperRightCoordinate": {"x": 50.0, "y": 80.0}
+                    },
+                    // Jan, Feb and Mar 2014
+                    "timeInterval": {"start": 1_388_534_400_000_i64, "end": 1_396_306_800_000_i64}
+                },
+                "spatialResolution": {"x": 1.0, "y": 1.0},
+                "format": "geoTiff"
+            }));
+        let res = send_test_request(req, app_ctx.clone()).await;
+
+        assert_eq!(res.status(), 200, "{:?}", res.response());
+
+        let task_response =
+            serde_json::from_str::<TaskResponse>(&read_body_string(res).await).unwrap();
+
+        let tasks = Arc::new(ctx.tasks());
+
+        wait_for_task_to_finish(tasks.clone(), task_response.task_id).await;
+
+        let status = tasks.get_task_status(task_response.task_id).await.unwrap();
+
+        let export = if let TaskStatus::Completed { info, .. } = status {
+            info.as_any_arc()
+                .downcast::<WorkflowExportResult>()
+                .unwrap()
+                .export
+        } else {
+            panic!("Task must be completed");
+        };
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/workflow/export/{export}"))
+            .append_header((header::AUTHORIZATION, Bearer::new(session_id.to_string())));
+        let res = send_test_request(req, app_ctx.clone()).await;
+
+        assert_eq!(res.status(), 200, "{:?}", res.response());
+
+        let bytes = test::read_body(res).await;
+        let mut archive = ZipArchive::new(Cursor::new(bytes)).unwrap();
+
+        let mut file_names = archive
+            .file_names()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+        file_names.sort();
+        assert_eq!(
+            file_names,
+            vec!["citation.json", "raster.tif", "workflow.json"]
+        );
+
+        let mut geotiff = Vec::new();
+        archive
+            .by_name("raster.tif")
+            .unwrap()
+            .read_to_end(&mut geotiff)
+            .unwrap();
+
+        let mem_file_path =
+            std::path::PathBuf::from(format!("/vsimem/{}.tif", uuid::Uuid::new_v4()));
+        let _mem_file =
+            gdal::vsi::create_mem_file_from_ref(&mem_file_path, geotiff.as_mut_slice()).unwrap();
+        let dataset = gdal::Dataset::open(&mem_file_path).unwrap();
+
+        // both raster bands for each of the three months
+        assert_eq!(dataset.raster_count(), 6);
+
+        drop(dataset);
+
+        std::fs::remove_dir_all(export.root_path().unwrap()).unwrap();
+    }
 }
@@ -136,6 +136,14 @@ pub enum DataPath {
     Upload(UploadId),
 }
 
+/// New meta data for an existing dataset, e.g., pointing to newly uploaded files
+#[derive(Deserialize, Serialize, Debug, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplaceDatasetMetaData {
+    pub data_path: DataPath,
+    pub meta_data: MetaDataDefinition,
+}
+
 #[derive(Deserialize, Serialize, Debug, Clone, ToSchema, Validate)]
 pub struct UpdateDataset {
     pub name: DatasetName,
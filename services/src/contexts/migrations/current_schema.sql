@@ -873,3 +873,50 @@ CREATE TABLE ebv_provider_loading_infos (
         file_name
     ) ON DELETE CASCADE DEFERRABLE
 );
+
+CREATE TYPE "TaskStatus" AS ENUM (
+    'Pending',
+    'Running',
+    'Completed',
+    'Aborted',
+    'Failed'
+);
+
+CREATE TABLE tasks (
+    id uuid PRIMARY KEY,
+    task_type text NOT NULL,
+    description text,
+    status "TaskStatus" NOT NULL,
+    pct_complete double precision NOT NULL,
+    time_started timestamp with time zone,
+    time_total text,
+    info jsonb,
+    error text,
+    clean_up jsonb,
+    created timestamp with time zone NOT NULL DEFAULT clock_timestamp()
+);
+
+CREATE TABLE dataset_versions (
+    id uuid PRIMARY KEY,
+    dataset_id uuid REFERENCES datasets (id) ON DELETE CASCADE NOT NULL,
+    version integer NOT NULL,
+    changed timestamp with time zone NOT NULL,
+    name "DatasetName" NOT NULL,
+    display_name text NOT NULL,
+    description text NOT NULL,
+    tags text [],
+    source_operator text NOT NULL,
+    result_descriptor "ResultDescriptor" NOT NULL,
+    meta_data "MetaDataDefinition" NOT NULL,
+    symbology "Symbology",
+    provenance "Provenance" [],
+    UNIQUE (dataset_id, version)
+);
+
+CREATE TABLE recurring_tasks (
+    id uuid PRIMARY KEY,
+    owner text,
+    schedule text NOT NULL,
+    definition jsonb NOT NULL,
+    created timestamp with time zone NOT NULL DEFAULT clock_timestamp()
+);
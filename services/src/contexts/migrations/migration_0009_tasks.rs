@@ -0,0 +1,51 @@
+use async_trait::async_trait;
+use tokio_postgres::Transaction;
+
+use crate::error::Result;
+
+use super::database_migration::{DatabaseVersion, Migration};
+
+/// This migration adds a table for the statuses of tasks, s.t. they survive restarts
+pub struct Migration0009Tasks;
+
+#[async_trait]
+impl Migration for Migration0009Tasks {
+    fn prev_version(&self) -> Option<DatabaseVersion> {
+        Some("0008_band_names".into())
+    }
+
+    fn version(&self) -> DatabaseVersion {
+        "0009_tasks".into()
+    }
+
+    async fn migrate(&self, tx: &Transaction<'_>) -> Result<()> {
+        tx.batch_execute(
+            r#"
+            CREATE TYPE "TaskStatus" AS ENUM (
+                'Pending',
+                'Running',
+                'Completed',
+                'Aborted',
+                'Failed'
+            );
+
+            CREATE TABLE tasks (
+                id uuid PRIMARY KEY,
+                task_type text NOT NULL,
+                description text,
+                status "TaskStatus" NOT NULL,
+                pct_complete double precision NOT NULL,
+                time_started timestamp with time zone,
+                time_total text,
+                info jsonb,
+                error text,
+                clean_up jsonb,
+                created timestamp with time zone NOT NULL DEFAULT clock_timestamp()
+            );
+            "#,
+        )
+        .await?;
+
+        Ok(())
+    }
+}
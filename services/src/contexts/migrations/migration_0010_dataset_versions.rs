@@ -0,0 +1,77 @@
+use async_trait::async_trait;
+use tokio_postgres::Transaction;
+
+use crate::error::Result;
+
+use super::database_migration::{DatabaseVersion, Migration};
+
+/// This migration adds a table for dataset versions and creates an initial version for every existing dataset
+pub struct Migration0010DatasetVersions;
+
+#[async_trait]
+impl Migration for Migration0010DatasetVersions {
+    fn prev_version(&self) -> Option<DatabaseVersion> {
+        Some("0009_tasks".into())
+    }
+
+    fn version(&self) -> DatabaseVersion {
+        "0010_dataset_versions".into()
+    }
+
+    async fn migrate(&self, tx: &Transaction<'_>) -> Result<()> {
+        tx.batch_execute(
+            r#"
+            CREATE TABLE dataset_versions (
+                id uuid PRIMARY KEY,
+                dataset_id uuid REFERENCES datasets (id) ON DELETE CASCADE NOT NULL,
+                version integer NOT NULL,
+                changed timestamp with time zone NOT NULL,
+                name "DatasetName" NOT NULL,
+                display_name text NOT NULL,
+                description text NOT NULL,
+                tags text [],
+                source_operator text NOT NULL,
+                result_descriptor "ResultDescriptor" NOT NULL,
+                meta_data "MetaDataDefinition" NOT NULL,
+                symbology "Symbology",
+                provenance "Provenance" [],
+                UNIQUE (dataset_id, version)
+            );
+
+            INSERT INTO dataset_versions (
+                id,
+                dataset_id,
+                version,
+                changed,
+                name,
+                display_name,
+                description,
+                tags,
+                source_operator,
+                result_descriptor,
+                meta_data,
+                symbology,
+                provenance
+            )
+            SELECT
+                gen_random_uuid(),
+                id,
+                1,
+                CURRENT_TIMESTAMP,
+                name,
+                display_name,
+                description,
+                tags,
+                source_operator,
+                result_descriptor,
+                meta_data,
+                symbology,
+                provenance
+            FROM datasets;
+            "#,
+        )
+        .await?;
+
+        Ok(())
+    }
+}
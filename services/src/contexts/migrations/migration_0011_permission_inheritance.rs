@@ -0,0 +1,26 @@
+use async_trait::async_trait;
+use tokio_postgres::Transaction;
+
+use crate::error::Result;
+
+use super::database_migration::{DatabaseVersion, Migration};
+
+/// This migration adds a `Write` permission and lets layer collections pass on permissions to their contents
+pub struct Migration0011PermissionInheritance;
+
+#[async_trait]
+impl Migration for Migration0011PermissionInheritance {
+    fn prev_version(&self) -> Option<DatabaseVersion> {
+        Some("0010_dataset_versions".into())
+    }
+
+    fn version(&self) -> DatabaseVersion {
+        "0011_permission_inheritance".into()
+    }
+
+    async fn migrate(&self, _tx: &Transaction<'_>) -> Result<()> {
+        // permissions only exist in Pro, nothing to do here
+
+        Ok(())
+    }
+}
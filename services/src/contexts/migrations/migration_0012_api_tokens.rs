@@ -0,0 +1,26 @@
+use async_trait::async_trait;
+use tokio_postgres::Transaction;
+
+use crate::error::Result;
+
+use super::database_migration::{DatabaseVersion, Migration};
+
+/// This migration adds tables for API tokens of users
+pub struct Migration0012ApiTokens;
+
+#[async_trait]
+impl Migration for Migration0012ApiTokens {
+    fn prev_version(&self) -> Option<DatabaseVersion> {
+        Some("0011_permission_inheritance".into())
+    }
+
+    fn version(&self) -> DatabaseVersion {
+        "0012_api_tokens".into()
+    }
+
+    async fn migrate(&self, _tx: &Transaction<'_>) -> Result<()> {
+        // users only exist in Pro, nothing to do here
+
+        Ok(())
+    }
+}
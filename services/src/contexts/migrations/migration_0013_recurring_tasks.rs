@@ -0,0 +1,37 @@
+use async_trait::async_trait;
+use tokio_postgres::Transaction;
+
+use crate::error::Result;
+
+use super::database_migration::{DatabaseVersion, Migration};
+
+/// This migration adds a table for recurring tasks, s.t. their schedules survive restarts
+pub struct Migration0013RecurringTasks;
+
+#[async_trait]
+impl Migration for Migration0013RecurringTasks {
+    fn prev_version(&self) -> Option<DatabaseVersion> {
+        Some("0012_api_tokens".into())
+    }
+
+    fn version(&self) -> DatabaseVersion {
+        "0013_recurring_tasks".into()
+    }
+
+    async fn migrate(&self, tx: &Transaction<'_>) -> Result<()> {
+        tx.batch_execute(
+            r#"
+            CREATE TABLE recurring_tasks (
+                id uuid PRIMARY KEY,
+                owner text,
+                schedule text NOT NULL,
+                definition jsonb NOT NULL,
+                created timestamp with time zone NOT NULL DEFAULT clock_timestamp()
+            );
+            "#,
+        )
+        .await?;
+
+        Ok(())
+    }
+}
@@ -7,7 +7,11 @@ pub use crate::contexts::migrations::{
     migration_0005_gbif_column_selection::Migration0005GbifColumnSelection,
     migration_0006_ebv_provider::Migration0006EbvProvider,
     migration_0007_owner_role::Migration0007OwnerRole,
-    migration_0008_band_names::Migration0008BandNames,
+    migration_0008_band_names::Migration0008BandNames, migration_0009_tasks::Migration0009Tasks,
+    migration_0010_dataset_versions::Migration0010DatasetVersions,
+    migration_0011_permission_inheritance::Migration0011PermissionInheritance,
+    migration_0012_api_tokens::Migration0012ApiTokens,
+    migration_0013_recurring_tasks::Migration0013RecurringTasks,
 };
 pub use database_migration::{
     initialize_database, migrate_database, DatabaseVersion, Migration, MigrationResult,
@@ -24,6 +28,11 @@ pub mod migration_0005_gbif_column_selection;
 mod migration_0006_ebv_provider;
 pub mod migration_0007_owner_role;
 pub mod migration_0008_band_names;
+pub mod migration_0009_tasks;
+pub mod migration_0010_dataset_versions;
+pub mod migration_0011_permission_inheritance;
+pub mod migration_0012_api_tokens;
+pub mod migration_0013_recurring_tasks;
 
 #[cfg(test)]
 mod schema_info;
@@ -45,6 +54,11 @@ pub fn all_migrations() -> Vec<Box<dyn Migration>> {
         Box::new(Migration0006EbvProvider),
         Box::new(Migration0007OwnerRole),
         Box::new(Migration0008BandNames),
+        Box::new(Migration0009Tasks),
+        Box::new(Migration0010DatasetVersions),
+        Box::new(Migration0011PermissionInheritance),
+        Box::new(Migration0012ApiTokens),
+        Box::new(Migration0013RecurringTasks),
     ]
 }
 
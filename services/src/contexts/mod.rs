@@ -1,6 +1,7 @@
 use crate::datasets::external::netcdfcf::NetCdfCfProviderDb;
-use crate::datasets::storage::DatasetDb;
+use crate::datasets::storage::{DatasetDb, DatasetVersionSelector};
 use crate::datasets::upload::Volume;
+use crate::datasets::DatasetName;
 use crate::error::Result;
 use crate::layers::listing::LayerCollectionProvider;
 use crate::layers::storage::{LayerDb, LayerProviderDb};
@@ -28,7 +29,9 @@ pub use migrations::{
     CurrentSchemaMigration, DatabaseVersion, Migration, Migration0001RasterStacks,
     Migration0002DatasetListingProvider, Migration0003GbifConfig,
     Migration0004DatasetListingProviderPrio, Migration0005GbifColumnSelection,
-    Migration0006EbvProvider, Migration0007OwnerRole, Migration0008BandNames, MigrationResult,
+    Migration0006EbvProvider, Migration0007OwnerRole, Migration0008BandNames, Migration0009Tasks,
+    Migration0010DatasetVersions, Migration0011PermissionInheritance, Migration0012ApiTokens,
+    Migration0013RecurringTasks, MigrationResult,
 };
 pub use postgres::{PostgresContext, PostgresDb, PostgresSessionContext};
 pub use session::{MockableSession, Session, SessionId, SimpleSession};
@@ -263,9 +266,21 @@ where
             return Ok(data_id.into());
         }
 
+        let (name, version) = data.name_and_version();
+
+        let version = version
+            .map(DatasetVersionSelector::from_str)
+            .transpose()
+            .map_err(
+                |source| geoengine_operators::error::Error::CannotResolveDatasetName {
+                    name: data.clone(),
+                    source: Box::new(source),
+                },
+            )?;
+
         let dataset_id = self
             .db
-            .resolve_dataset_name_to_id(&data.into())
+            .resolve_dataset_name_to_id(&DatasetName::new(data.namespace.clone(), name))
             .await
             .map_err(
                 |source| geoengine_operators::error::Error::CannotResolveDatasetName {
@@ -278,6 +293,21 @@ where
         let dataset_id = dataset_id
             .ok_or(geoengine_operators::error::Error::UnknownDatasetName { name: data.clone() })?;
 
+        let Some(version) = version else {
+            return Ok(dataset_id.into());
+        };
+
+        let dataset_id = self
+            .db
+            .resolve_dataset_version(&dataset_id, version)
+            .await
+            .map_err(
+                |source| geoengine_operators::error::Error::CannotResolveDatasetName {
+                    name: data.clone(),
+                    source: Box::new(source),
+                },
+            )?;
+
         Ok(dataset_id.into())
     }
 
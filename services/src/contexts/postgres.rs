@@ -4,13 +4,16 @@ use crate::api::cli::{add_datasets_from_directory, add_providers_from_directory}
 use crate::contexts::{ApplicationContext, QueryContextImpl, SessionId, SimpleSession};
 use crate::contexts::{GeoEngineDb, SessionContext};
 use crate::datasets::upload::{Volume, Volumes};
-use crate::datasets::DatasetName;
+use crate::datasets::{recurring_dataset_from_workflow_factory, DatasetName};
 use crate::error::{self, Error, Result};
 use crate::layers::add_from_directory::{
     add_layer_collections_from_directory, add_layers_from_directory,
 };
 use crate::projects::{ProjectId, STRectangle};
-use crate::tasks::{SimpleTaskManager, SimpleTaskManagerBackend, SimpleTaskManagerContext};
+use crate::tasks::{
+    PostgresTaskStore, SimpleTaskManager, SimpleTaskManagerBackend, SimpleTaskManagerContext,
+    TaskLimits,
+};
 use crate::util::config;
 use crate::util::config::get_config_element;
 use async_trait::async_trait;
@@ -28,6 +31,7 @@ use rayon::ThreadPool;
 use snafu::ensure;
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
 
 // TODO: distinguish user-facing errors from system-facing error messages
 
@@ -81,9 +85,11 @@ where
             Self::load_default_session(pool.get().await?).await?
         };
 
+        let task_manager = Self::create_task_manager(&pool).await?;
+
         Ok(PostgresContext {
             default_session_id: session.id(),
-            task_manager: Default::default(),
+            task_manager: Arc::new(task_manager),
             thread_pool: create_rayon_thread_pool(0),
             exe_ctx_tiling_spec,
             query_ctx_chunk_size,
@@ -117,9 +123,11 @@ where
             Self::load_default_session(pool.get().await?).await?
         };
 
+        let task_manager = Self::create_task_manager(&pool).await?;
+
         let app_ctx = PostgresContext {
             default_session_id: session.id(),
-            task_manager: Default::default(),
+            task_manager: Arc::new(task_manager),
             thread_pool: create_rayon_thread_pool(0),
             exe_ctx_tiling_spec,
             query_ctx_chunk_size,
@@ -144,6 +152,36 @@ where
         Ok(app_ctx)
     }
 
+    /// Schedules the recurring tasks that were persisted before the last shutdown again.
+    pub async fn restore_recurring_tasks(&self) -> Result<()> {
+        self.task_manager
+            .restore_recurring_tasks(|recurring_task| {
+                recurring_dataset_from_workflow_factory(self.clone(), &recurring_task.definition)
+            })
+            .await
+    }
+
+    /// Creates a task manager that persists its tasks, s.t. they survive restarts.
+    /// Finished tasks are deleted after the configured retention time.
+    pub(crate) async fn create_task_manager(
+        pool: &Pool<PostgresConnectionManager<Tls>>,
+    ) -> Result<SimpleTaskManagerBackend> {
+        let config = get_config_element::<config::TaskManager>()?;
+
+        let task_manager = SimpleTaskManagerBackend::new_persistent(
+            Arc::new(PostgresTaskStore::new(pool.clone())),
+            TaskLimits::from(&config),
+        )
+        .await?;
+
+        task_manager.spawn_finished_task_cleanup(
+            Duration::from_secs(config.finished_task_retention_seconds),
+            Duration::from_secs(config.finished_task_cleanup_interval_seconds),
+        );
+
+        Ok(task_manager)
+    }
+
     async fn check_schema_status(
         conn: &PooledConnection<'_, PostgresConnectionManager<Tls>>,
     ) -> Result<DatabaseStatus> {
@@ -354,7 +392,7 @@ where
     type GeoEngineDB = PostgresDb<Tls>;
 
     type TaskContext = SimpleTaskManagerContext;
-    type TaskManager = SimpleTaskManager; // this does not persist across restarts
+    type TaskManager = SimpleTaskManager;
     type QueryContext = QueryContextImpl;
     type ExecutionContext = ExecutionContextImpl<Self::GeoEngineDB>;
 
@@ -457,6 +495,7 @@ impl TryFrom<config::Postgres> for Config {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::api::model::services::UpdateDataset;
     use crate::datasets::external::aruna::ArunaDataProviderDefinition;
     use crate::datasets::external::gbif::{GbifDataProvider, GbifDataProviderDefinition};
     use crate::datasets::external::gfbio_abcd::GfbioAbcdDataProviderDefinition;
@@ -467,7 +506,7 @@ mod tests {
     use crate::datasets::external::pangaea::PangaeaDataProviderDefinition;
     use crate::datasets::listing::{DatasetListOptions, DatasetListing, ProvenanceOutput};
     use crate::datasets::listing::{DatasetProvider, Provenance};
-    use crate::datasets::storage::{DatasetStore, MetaDataDefinition};
+    use crate::datasets::storage::{DatasetStore, DatasetVersionSelector, MetaDataDefinition};
     use crate::datasets::upload::{FileId, UploadId};
     use crate::datasets::upload::{FileUpload, Upload, UploadDb};
     use crate::datasets::{AddDataset, DatasetIdAndName};
@@ -477,6 +516,7 @@ mod tests {
     use crate::layers::layer::{
         AddLayer, AddLayerCollection, CollectionItem, LayerCollection, LayerCollectionListOptions,
         LayerCollectionListing, LayerListing, Property, ProviderLayerCollectionId, ProviderLayerId,
+        UpdateLayer, UpdateLayerCollection,
     };
     use crate::layers::listing::{
         LayerCollectionId, LayerCollectionProvider, SearchParameters, SearchType,
@@ -498,7 +538,7 @@ mod tests {
     use bb8_postgres::tokio_postgres::NoTls;
     use futures::join;
     use geoengine_datatypes::collections::VectorDataType;
-    use geoengine_datatypes::dataset::{DataProviderId, LayerId};
+    use geoengine_datatypes::dataset::{DataId, DataProviderId, LayerId, NamedData};
     use geoengine_datatypes::operations::image::{
         Breakpoint, Colorizer, RasterColorizer, RgbaColor,
     };
@@ -516,10 +556,10 @@ mod tests {
     use geoengine_datatypes::test_data;
     use geoengine_datatypes::util::{NotNanF64, StringPair};
     use geoengine_operators::engine::{
-        MetaData, MetaDataProvider, MultipleRasterOrSingleVectorSource, PlotOperator,
-        PlotResultDescriptor, RasterBandDescriptor, RasterBandDescriptors, RasterResultDescriptor,
-        StaticMetaData, TypedOperator, TypedResultDescriptor, VectorColumnInfo, VectorOperator,
-        VectorResultDescriptor,
+        ExecutionContext, MetaData, MetaDataProvider, MultipleRasterOrSingleVectorSource,
+        PlotOperator, PlotResultDescriptor, RasterBandDescriptor, RasterBandDescriptors,
+        RasterResultDescriptor, StaticMetaData, TypedOperator, TypedResultDescriptor,
+        VectorColumnInfo, VectorOperator, VectorResultDescriptor,
     };
     use geoengine_operators::mock::{
         MockDatasetDataSourceLoadingInfo, MockPointSource, MockPointSourceParams,
@@ -601,6 +641,7 @@ mod tests {
                 operator: Statistics {
                     params: StatisticsParams {
                         column_names: vec![],
+                        estimate_quantiles: None,
                     },
                     sources: MultipleRasterOrSingleVectorSource {
                         source: Raster(vec![]),
@@ -2592,6 +2633,138 @@ mod tests {
         );
     }
 
+    #[allow(clippy::too_many_lines)]
+    #[ge_context::test]
+    async fn it_updates_layers_and_collections(app_ctx: PostgresContext<NoTls>) {
+        let session = app_ctx.default_session().await.unwrap();
+
+        let db = app_ctx.session_context(session).db();
+
+        let root_collection = &db.get_root_layer_collection_id().await.unwrap();
+
+        let collection = db
+            .add_layer_collection(
+                AddLayerCollection {
+                    name: "collection".to_string(),
+                    description: "description".to_string(),
+                    properties: Default::default(),
+                },
+                root_collection,
+            )
+            .await
+            .unwrap();
+
+        let layer = db
+            .add_layer(
+                AddLayer {
+                    name: "layer".to_string(),
+                    description: "description".to_string(),
+                    workflow: Workflow {
+                        operator: TypedOperator::Vector(
+                            MockPointSource {
+                                params: MockPointSourceParams {
+                                    points: vec![Coordinate2D::new(1., 2.); 3],
+                                },
+                            }
+                            .boxed(),
+                        ),
+                    },
+                    symbology: None,
+                    metadata: Default::default(),
+                    properties: Default::default(),
+                },
+                &collection,
+            )
+            .await
+            .unwrap();
+
+        let new_workflow = Workflow {
+            operator: TypedOperator::Vector(
+                MockPointSource {
+                    params: MockPointSourceParams {
+                        points: vec![Coordinate2D::new(3., 4.); 2],
+                    },
+                }
+                .boxed(),
+            ),
+        };
+
+        db.update_layer(
+            &layer,
+            UpdateLayer {
+                name: "updated layer".to_string(),
+                description: "updated description".to_string(),
+                workflow: new_workflow.clone(),
+                symbology: None,
+                metadata: [("foo".to_string(), "bar".to_string())].into(),
+                properties: vec![("key".to_string(), "value".to_string()).into()],
+            },
+        )
+        .await
+        .unwrap();
+
+        let updated_layer = db.load_layer(&layer).await.unwrap();
+
+        assert_eq!(updated_layer.name, "updated layer");
+        assert_eq!(updated_layer.description, "updated description");
+        assert_eq!(
+            crate::workflows::workflow::WorkflowId::from_hash(&updated_layer.workflow),
+            crate::workflows::workflow::WorkflowId::from_hash(&new_workflow)
+        );
+        assert_eq!(
+            updated_layer.metadata,
+            [("foo".to_string(), "bar".to_string())].into()
+        );
+        assert_eq!(
+            updated_layer.properties,
+            vec![("key".to_string(), "value".to_string()).into()]
+        );
+
+        db.update_layer_collection(
+            &collection,
+            UpdateLayerCollection {
+                name: "updated collection".to_string(),
+                description: "updated description".to_string(),
+                properties: vec![("key".to_string(), "value".to_string()).into()],
+            },
+        )
+        .await
+        .unwrap();
+
+        let updated_collection = db
+            .load_layer_collection(
+                &collection,
+                LayerCollectionListOptions {
+                    offset: 0,
+                    limit: 20,
+                },
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(updated_collection.name, "updated collection");
+        assert_eq!(updated_collection.description, "updated description");
+        assert_eq!(
+            updated_collection.properties,
+            vec![("key".to_string(), "value".to_string()).into()]
+        );
+        // the layer is still part of the collection
+        assert_eq!(updated_collection.items.len(), 1);
+
+        // unknown ids cannot be updated
+        assert!(db
+            .update_layer_collection(
+                &LayerCollectionId(uuid::Uuid::new_v4().to_string()),
+                UpdateLayerCollection {
+                    name: "unknown".to_string(),
+                    description: String::new(),
+                    properties: Default::default(),
+                },
+            )
+            .await
+            .is_err());
+    }
+
     #[allow(clippy::too_many_lines)]
     #[ge_context::test]
     async fn it_updates_project_layer_symbology(app_ctx: PostgresContext<NoTls>) {
@@ -2998,6 +3171,233 @@ mod tests {
         );
     }
 
+    #[ge_context::test]
+    #[allow(clippy::too_many_lines)]
+    async fn it_versions_datasets(app_ctx: PostgresContext<NoTls>) {
+        let session = app_ctx.default_session().await.unwrap();
+        let db = app_ctx.session_context(session.clone()).db();
+
+        let loading_info = OgrSourceDataset {
+            file_name: PathBuf::from("test.csv"),
+            layer_name: "test.csv".to_owned(),
+            data_type: Some(VectorDataType::MultiPoint),
+            time: OgrSourceDatasetTimeType::None,
+            default_geometry: None,
+            columns: Some(OgrSourceColumnSpec {
+                format_specifics: Some(FormatSpecifics::Csv {
+                    header: CsvHeader::Auto,
+                }),
+                x: "x".to_owned(),
+                y: None,
+                int: vec![],
+                float: vec![],
+                text: vec![],
+                bool: vec![],
+                datetime: vec![],
+                rename: None,
+            }),
+            force_ogr_time_filter: false,
+            force_ogr_spatial_filter: false,
+            on_error: OgrSourceErrorSpec::Ignore,
+            sql_query: None,
+            attribute_query: None,
+            cache_ttl: CacheTtlSeconds::default(),
+        };
+
+        let replaced_loading_info = OgrSourceDataset {
+            file_name: PathBuf::from("test2.csv"),
+            layer_name: "test2.csv".to_owned(),
+            ..loading_info.clone()
+        };
+
+        let meta_data = |loading_info: OgrSourceDataset| {
+            MetaDataDefinition::OgrMetaData(StaticMetaData::<
+                OgrSourceDataset,
+                VectorResultDescriptor,
+                VectorQueryRectangle,
+            > {
+                loading_info,
+                result_descriptor: VectorResultDescriptor {
+                    data_type: VectorDataType::MultiPoint,
+                    spatial_reference: SpatialReference::epsg_4326().into(),
+                    columns: Default::default(),
+                    time: None,
+                    bbox: None,
+                },
+                phantom: Default::default(),
+            })
+        };
+
+        let DatasetIdAndName {
+            id: dataset_id,
+            name: dataset_name,
+        } = db
+            .add_dataset(
+                AddDataset {
+                    name: Some(DatasetName::new(None, "my_dataset".to_owned())),
+                    display_name: "Ogr Test".to_owned(),
+                    description: "desc".to_owned(),
+                    source_operator: "OgrSource".to_owned(),
+                    symbology: None,
+                    provenance: None,
+                    tags: None,
+                },
+                meta_data(loading_info.clone()),
+            )
+            .await
+            .unwrap();
+
+        let versions = db.list_dataset_versions(&dataset_id).await.unwrap();
+        assert_eq!(versions.len(), 1);
+        assert_eq!(versions[0].version, 1);
+
+        db.update_dataset(
+            dataset_id,
+            UpdateDataset {
+                name: dataset_name.clone(),
+                display_name: "Ogr Test 2".to_owned(),
+                description: "desc".to_owned(),
+                tags: vec![],
+            },
+        )
+        .await
+        .unwrap();
+
+        db.replace_dataset_meta_data(dataset_id, meta_data(replaced_loading_info.clone()))
+            .await
+            .unwrap();
+
+        let versions = db.list_dataset_versions(&dataset_id).await.unwrap();
+        assert_eq!(
+            versions.iter().map(|v| v.version).collect::<Vec<_>>(),
+            vec![3, 2, 1]
+        );
+
+        // the meta data must match the source operator of the dataset
+        assert!(matches!(
+            db.replace_dataset_meta_data(
+                dataset_id,
+                MetaDataDefinition::MockMetaData(StaticMetaData {
+                    loading_info: MockDatasetDataSourceLoadingInfo {
+                        points: vec![Coordinate2D::new(1., 2.)],
+                    },
+                    result_descriptor: VectorResultDescriptor {
+                        data_type: VectorDataType::MultiPoint,
+                        spatial_reference: SpatialReference::epsg_4326().into(),
+                        columns: Default::default(),
+                        time: None,
+                        bbox: None,
+                    },
+                    phantom: Default::default(),
+                }),
+            )
+            .await,
+            Err(crate::error::Error::MetaDataDoesNotMatchSourceOperator { .. })
+        ));
+        assert_eq!(
+            db.list_dataset_versions(&dataset_id).await.unwrap().len(),
+            3
+        );
+
+        let exe_ctx = app_ctx
+            .session_context(session.clone())
+            .execution_context()
+            .unwrap();
+
+        let query = VectorQueryRectangle {
+            spatial_bounds: BoundingBox2D::new_unchecked((-180., -90.).into(), (180., 90.).into()),
+            time_interval: TimeInterval::default(),
+            spatial_resolution: SpatialResolution::zero_point_one(),
+            attributes: ColumnSelection::all(),
+        };
+
+        // a pinned version keeps its definition while the dataset changes
+        let pinned = exe_ctx
+            .resolve_named_data(&NamedData::with_system_name("my_dataset@1"))
+            .await
+            .unwrap();
+        assert_eq!(pinned, DataId::from(versions[2].id));
+
+        let pinned_meta_data: Box<dyn MetaData<OgrSourceDataset, _, _>> =
+            db.meta_data(&pinned).await.unwrap();
+        assert_eq!(
+            pinned_meta_data.loading_info(query.clone()).await.unwrap(),
+            loading_info
+        );
+
+        for name in ["my_dataset", "my_dataset@latest"] {
+            assert_eq!(
+                exe_ctx
+                    .resolve_named_data(&NamedData::with_system_name(name))
+                    .await
+                    .unwrap(),
+                DataId::from(dataset_id)
+            );
+        }
+
+        let latest_meta_data: Box<dyn MetaData<OgrSourceDataset, _, _>> =
+            db.meta_data(&dataset_id.into()).await.unwrap();
+        assert_eq!(
+            latest_meta_data.loading_info(query.clone()).await.unwrap(),
+            replaced_loading_info
+        );
+
+        assert!(exe_ctx
+            .resolve_named_data(&NamedData::with_system_name("my_dataset@0"))
+            .await
+            .is_err());
+        assert!(exe_ctx
+            .resolve_named_data(&NamedData::with_system_name("my_dataset@4"))
+            .await
+            .is_err());
+
+        // restoring creates a new version
+        let restored = db.restore_dataset_version(dataset_id, 1).await.unwrap();
+        assert_eq!(restored.version, 4);
+
+        assert_eq!(
+            db.load_dataset(&dataset_id).await.unwrap().display_name,
+            "Ogr Test"
+        );
+
+        let latest_meta_data: Box<dyn MetaData<OgrSourceDataset, _, _>> =
+            db.meta_data(&dataset_id.into()).await.unwrap();
+        assert_eq!(
+            latest_meta_data.loading_info(query).await.unwrap(),
+            loading_info
+        );
+
+        assert!(db.restore_dataset_version(dataset_id, 42).await.is_err());
+
+        // garbage collection keeps the most recent versions
+        assert!(db
+            .garbage_collect_dataset_versions(dataset_id, 0)
+            .await
+            .is_err());
+        assert_eq!(
+            db.garbage_collect_dataset_versions(dataset_id, 2)
+                .await
+                .unwrap(),
+            2
+        );
+
+        let versions = db.list_dataset_versions(&dataset_id).await.unwrap();
+        assert_eq!(
+            versions.iter().map(|v| v.version).collect::<Vec<_>>(),
+            vec![4, 3]
+        );
+
+        assert!(db
+            .resolve_dataset_version(&dataset_id, DatasetVersionSelector::Version(1))
+            .await
+            .is_err());
+
+        let pinned_meta_data: geoengine_operators::util::Result<
+            Box<dyn MetaData<OgrSourceDataset, VectorResultDescriptor, VectorQueryRectangle>>,
+        > = db.meta_data(&pinned).await;
+        assert!(pinned_meta_data.is_err());
+    }
+
     #[ge_context::test]
     #[allow(clippy::too_many_lines)]
     async fn test_postgres_type_serialization(app_ctx: PostgresContext<NoTls>) {
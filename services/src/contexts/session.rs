@@ -14,6 +14,7 @@ use geoengine_datatypes::util::Identifier;
 use serde::{Deserialize, Serialize};
 use tokio_postgres::NoTls;
 use utoipa::ToSchema;
+use uuid::Uuid;
 
 use super::ApplicationContext;
 
@@ -25,6 +26,9 @@ pub trait Session: Send + Sync + Serialize {
     fn valid_until(&self) -> &DateTime;
     fn project(&self) -> Option<ProjectId>;
     fn view(&self) -> Option<&STRectangle>;
+    /// Identifies the owner of resources that are kept outside of the database, e.g., unfinished uploads.
+    /// This is the user of the session or, if there are no users, the session itself.
+    fn owner(&self) -> Uuid;
 }
 
 pub trait MockableSession: Session {
@@ -79,6 +83,10 @@ impl Session for SimpleSession {
     fn view(&self) -> Option<&STRectangle> {
         self.view.as_ref()
     }
+
+    fn owner(&self) -> Uuid {
+        self.id.0
+    }
 }
 
 impl MockableSession for SimpleSession {
@@ -1,27 +1,38 @@
-use crate::api::model::datatypes::RasterQueryRectangle;
-use crate::contexts::SessionContext;
+use crate::api::handlers::workflows::workflow_provenance;
+use crate::api::model::datatypes::{RasterQueryRectangle, VectorQueryRectangle};
+use crate::contexts::{ApplicationContext, Session, SessionContext, SessionId};
 use crate::datasets::listing::DatasetProvider;
 use crate::datasets::storage::{DatasetDefinition, DatasetStore, MetaDataDefinition};
 use crate::datasets::upload::{UploadId, UploadRootPath};
 use crate::datasets::AddDataset;
 use crate::error;
-use crate::tasks::{Task, TaskId, TaskManager, TaskStatusInfo};
-use crate::workflows::workflow::Workflow;
+use crate::tasks::{
+    CronSchedule, RecurringTask, RecurringTaskFactory, RecurringTaskId, Task, TaskId, TaskManager,
+    TaskPriority, TaskStatusInfo,
+};
+use crate::util::config::get_config_element;
+use crate::workflows::registry::WorkflowRegistry;
+use crate::workflows::workflow::{Workflow, WorkflowId};
+use futures::FutureExt;
 use geoengine_datatypes::error::ErrorSource;
-use geoengine_datatypes::primitives::TimeInterval;
+use geoengine_datatypes::primitives::{FeatureDataType, TimeInterval};
 use geoengine_datatypes::spatial_reference::SpatialReference;
 use geoengine_datatypes::util::Identifier;
-use geoengine_operators::call_on_generic_raster_processor_gdal_types;
 use geoengine_operators::engine::{
-    ExecutionContext, InitializedRasterOperator, RasterResultDescriptor, WorkflowOperatorPath,
+    ExecutionContext, InitializedRasterOperator, InitializedVectorOperator, RasterResultDescriptor,
+    StaticMetaData, VectorColumnInfo, VectorResultDescriptor, WorkflowOperatorPath,
 };
 use geoengine_operators::source::{
-    GdalLoadingInfoTemporalSlice, GdalMetaDataList, GdalMetaDataStatic,
+    GdalLoadingInfoTemporalSlice, GdalMetaDataList, GdalMetaDataStatic, OgrSourceDataset,
 };
 use geoengine_operators::util::raster_stream_to_geotiff::{
     raster_stream_to_geotiff, GdalCompressionNumThreads, GdalGeoTiffDatasetMetadata,
     GdalGeoTiffOptions,
 };
+use geoengine_operators::util::vector_stream_to_ogr::{vector_stream_to_ogr, OgrVectorFormat};
+use geoengine_operators::{
+    call_on_generic_raster_processor_gdal_types, call_on_generic_vector_processor,
+};
 use serde::{Deserialize, Serialize};
 use snafu::{ensure, ResultExt};
 use std::path::PathBuf;
@@ -43,6 +54,9 @@ pub struct RasterDatasetFromWorkflow {
     #[schema(default = default_as_cog)]
     #[serde(default = "default_as_cog")]
     pub as_cog: bool,
+    /// Pending tasks with a higher priority start first
+    #[serde(default)]
+    pub priority: TaskPriority,
 }
 
 /// By default, we set [`RasterDatasetFromWorkflow::as_cog`] to true to produce cloud-optmized `GeoTiff`s.
@@ -172,6 +186,10 @@ impl<C: SessionContext> Task<C::TaskContext> for RasterDatasetFromWorkflowTask<C
         Some(self.upload.to_string())
     }
 
+    fn task_priority(&self) -> TaskPriority {
+        self.info.priority
+    }
+
     fn task_description(&self) -> String {
         format!(
             "Creating dataset {} from {}",
@@ -187,42 +205,75 @@ pub async fn schedule_raster_dataset_from_workflow_task<C: SessionContext>(
     info: RasterDatasetFromWorkflow,
     compression_num_threads: GdalCompressionNumThreads,
 ) -> error::Result<TaskId> {
-    if let Some(dataset_name) = &info.name {
-        let db = ctx.db();
-
-        // try to resolve the dataset name to an id
-        let potential_id_result = db.resolve_dataset_name_to_id(dataset_name).await?;
-
-        // handle the case where the dataset name is already taken
-        if let Some(dataset_id) = potential_id_result {
-            return Err(error::Error::DatasetNameAlreadyExists {
-                dataset_name: dataset_name.to_string(),
-                dataset_id: dataset_id.into(),
-            });
-        }
-    }
+    let task = create_raster_dataset_from_workflow_task(
+        source_name,
+        workflow,
+        ctx.clone(),
+        info,
+        compression_num_threads,
+    )
+    .await?;
 
-    let upload = UploadId::new();
-    let upload_path = upload.root_path()?;
-    fs::create_dir_all(&upload_path)
-        .await
-        .context(crate::error::Io)?;
-    let file_path = upload_path.clone();
+    let task_id = ctx.tasks().schedule_task(task, None).await?;
+
+    Ok(task_id)
+}
+
+async fn create_raster_dataset_from_workflow_task<C: SessionContext>(
+    source_name: String,
+    workflow: Workflow,
+    ctx: Arc<C>,
+    info: RasterDatasetFromWorkflow,
+    compression_num_threads: GdalCompressionNumThreads,
+) -> error::Result<Box<dyn Task<C::TaskContext>>> {
+    check_dataset_name_is_available(info.name.as_ref(), ctx.as_ref()).await?;
 
-    let task = RasterDatasetFromWorkflowTask {
+    let (upload, file_path) = create_upload_directory().await?;
+
+    Ok(RasterDatasetFromWorkflowTask {
         source_name,
         workflow,
-        ctx: ctx.clone(),
+        ctx,
         info,
         upload,
         file_path,
         compression_num_threads,
     }
-    .boxed();
+    .boxed())
+}
 
-    let task_id = ctx.tasks().schedule_task(task, None).await?;
+async fn check_dataset_name_is_available<C: SessionContext>(
+    dataset_name: Option<&DatasetName>,
+    ctx: &C,
+) -> error::Result<()> {
+    let Some(dataset_name) = dataset_name else {
+        return Ok(());
+    };
 
-    Ok(task_id)
+    let db = ctx.db();
+
+    // try to resolve the dataset name to an id
+    let potential_id_result = db.resolve_dataset_name_to_id(dataset_name).await?;
+
+    // handle the case where the dataset name is already taken
+    if let Some(dataset_id) = potential_id_result {
+        return Err(error::Error::DatasetNameAlreadyExists {
+            dataset_name: dataset_name.to_string(),
+            dataset_id: dataset_id.into(),
+        });
+    }
+
+    Ok(())
+}
+
+async fn create_upload_directory() -> error::Result<(UploadId, PathBuf)> {
+    let upload = UploadId::new();
+    let upload_path = upload.root_path()?;
+    fs::create_dir_all(&upload_path)
+        .await
+        .context(crate::error::Io)?;
+
+    Ok((upload, upload_path))
 }
 
 async fn create_dataset<C: SessionContext>(
@@ -295,3 +346,395 @@ async fn create_dataset<C: SessionContext>(
 
     Ok(result)
 }
+
+/// The file format of a vector dataset that is created from a workflow
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum VectorDatasetFormat {
+    #[default]
+    GeoPackage,
+    FlatGeobuf,
+}
+
+impl From<VectorDatasetFormat> for OgrVectorFormat {
+    fn from(format: VectorDatasetFormat) -> Self {
+        match format {
+            VectorDatasetFormat::GeoPackage => Self::GeoPackage,
+            VectorDatasetFormat::FlatGeobuf => Self::FlatGeobuf,
+        }
+    }
+}
+
+/// parameter for the vector dataset from workflow handler (body)
+#[derive(Clone, Debug, Deserialize, Serialize, ToSchema)]
+#[schema(example = json!({"name": "foo", "displayName": "a new dataset", "description": null, "query": {"spatialBounds": {"lowerLeftCoordinate": {"x": -10.0, "y": 20.0}, "upperRightCoordinate": {"x": 50.0, "y": 80.0}}, "timeInterval": {"start": 1_388_534_400_000_i64, "end": 1_388_534_401_000_i64}, "spatialResolution": {"x": 0.1, "y": 0.1}}, "format": "geoPackage"}))]
+#[serde(rename_all = "camelCase")]
+pub struct VectorDatasetFromWorkflow {
+    pub name: Option<DatasetName>,
+    pub display_name: String,
+    pub description: Option<String>,
+    pub query: VectorQueryRectangle,
+    #[serde(default)]
+    pub format: VectorDatasetFormat,
+    /// Pending tasks with a higher priority start first
+    #[serde(default)]
+    pub priority: TaskPriority,
+}
+
+/// response of the vector dataset from workflow handler
+#[derive(Clone, Debug, Deserialize, Serialize, ToSchema)]
+pub struct VectorDatasetFromWorkflowResult {
+    pub dataset: DatasetName,
+    pub upload: UploadId,
+}
+
+impl TaskStatusInfo for VectorDatasetFromWorkflowResult {}
+
+pub struct VectorDatasetFromWorkflowTask<C: SessionContext> {
+    pub source_name: String,
+    pub workflow: Workflow,
+    pub ctx: Arc<C>,
+    pub info: VectorDatasetFromWorkflow,
+    pub upload: UploadId,
+    pub upload_path: PathBuf,
+}
+
+impl<C: SessionContext> VectorDatasetFromWorkflowTask<C> {
+    async fn process(&self) -> error::Result<VectorDatasetFromWorkflowResult> {
+        let operator = self.workflow.operator.clone();
+
+        let operator = operator.get_vector().context(crate::error::Operator)?;
+
+        let execution_context = self.ctx.execution_context()?;
+
+        let workflow_operator_path_root = WorkflowOperatorPath::initialize_root();
+
+        let initialized = operator
+            .initialize(workflow_operator_path_root, &execution_context)
+            .await
+            .context(crate::error::Operator)?;
+
+        let result_descriptor = initialized.result_descriptor().clone();
+
+        let processor = initialized
+            .query_processor()
+            .context(crate::error::Operator)?;
+
+        let query_rect: geoengine_datatypes::primitives::VectorQueryRectangle =
+            self.info.query.into();
+        let query_ctx = self.ctx.query_context()?;
+        let format = OgrVectorFormat::from(self.info.format);
+        let file_path = self
+            .upload_path
+            .join(format!("dataset.{}", format.file_extension()));
+
+        let loading_info = call_on_generic_vector_processor!(processor, p => vector_stream_to_ogr(
+            &file_path,
+            p,
+            query_rect.clone(),
+            query_ctx,
+            format,
+            Box::pin(futures::future::pending()), // datasets shall continue to be built in the background and not cancelled
+        ).await)?;
+
+        // the new dataset is derived from all data of the workflow
+        let provenance = workflow_provenance(&self.workflow, self.ctx.as_ref())
+            .await?
+            .into_iter()
+            .map(|entry| entry.provenance)
+            .collect::<Vec<_>>();
+
+        let dataset = create_vector_dataset(
+            self.info.clone(),
+            loading_info,
+            result_descriptor,
+            query_rect,
+            provenance,
+            self.ctx.as_ref(),
+        )
+        .await?;
+
+        Ok(VectorDatasetFromWorkflowResult {
+            dataset: dataset.name,
+            upload: self.upload,
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl<C: SessionContext> Task<C::TaskContext> for VectorDatasetFromWorkflowTask<C> {
+    async fn run(
+        &self,
+        _ctx: C::TaskContext,
+    ) -> error::Result<Box<dyn crate::tasks::TaskStatusInfo>, Box<dyn ErrorSource>> {
+        let response = self.process().await;
+
+        response
+            .map(TaskStatusInfo::boxed)
+            .map_err(ErrorSource::boxed)
+    }
+
+    async fn cleanup_on_error(
+        &self,
+        _ctx: C::TaskContext,
+    ) -> error::Result<(), Box<dyn ErrorSource>> {
+        fs::remove_dir_all(&self.upload_path)
+            .await
+            .context(crate::error::Io)
+            .map_err(ErrorSource::boxed)?;
+
+        Ok(())
+    }
+
+    fn task_type(&self) -> &'static str {
+        "create-dataset"
+    }
+
+    fn task_unique_id(&self) -> Option<String> {
+        Some(self.upload.to_string())
+    }
+
+    fn task_priority(&self) -> TaskPriority {
+        self.info.priority
+    }
+
+    fn task_description(&self) -> String {
+        format!(
+            "Creating dataset {} from {}",
+            self.info.display_name, self.source_name
+        )
+    }
+}
+
+pub async fn schedule_vector_dataset_from_workflow_task<C: SessionContext>(
+    source_name: String,
+    workflow: Workflow,
+    ctx: Arc<C>,
+    info: VectorDatasetFromWorkflow,
+) -> error::Result<TaskId> {
+    let task =
+        create_vector_dataset_from_workflow_task(source_name, workflow, ctx.clone(), info).await?;
+
+    let task_id = ctx.tasks().schedule_task(task, None).await?;
+
+    Ok(task_id)
+}
+
+async fn create_vector_dataset_from_workflow_task<C: SessionContext>(
+    source_name: String,
+    workflow: Workflow,
+    ctx: Arc<C>,
+    info: VectorDatasetFromWorkflow,
+) -> error::Result<Box<dyn Task<C::TaskContext>>> {
+    check_dataset_name_is_available(info.name.as_ref(), ctx.as_ref()).await?;
+
+    let (upload, upload_path) = create_upload_directory().await?;
+
+    Ok(VectorDatasetFromWorkflowTask {
+        source_name,
+        workflow,
+        ctx,
+        info,
+        upload,
+        upload_path,
+    }
+    .boxed())
+}
+
+/// A dataset that is created from a workflow every time the schedule of its recurring task is due
+#[derive(Clone, Debug, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub enum RecurringDatasetFromWorkflow {
+    #[serde(rename_all = "camelCase")]
+    Raster {
+        workflow_id: WorkflowId,
+        params: RasterDatasetFromWorkflow,
+    },
+    #[serde(rename_all = "camelCase")]
+    Vector {
+        workflow_id: WorkflowId,
+        params: VectorDatasetFromWorkflow,
+    },
+}
+
+impl RecurringDatasetFromWorkflow {
+    fn workflow_id(&self) -> WorkflowId {
+        match self {
+            Self::Raster { workflow_id, .. } | Self::Vector { workflow_id, .. } => *workflow_id,
+        }
+    }
+
+    fn dataset_name(&self) -> Option<&DatasetName> {
+        match self {
+            Self::Raster { params, .. } => params.name.as_ref(),
+            Self::Vector { params, .. } => params.name.as_ref(),
+        }
+    }
+}
+
+/// The definition of a [`RecurringDatasetFromWorkflow`] that is stored with its recurring task
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct StoredRecurringDatasetFromWorkflow {
+    /// The tasks run with the session that scheduled them
+    session_id: SessionId,
+    task: RecurringDatasetFromWorkflow,
+}
+
+/// Schedules the creation of a dataset from a workflow every time the `schedule` is due.
+///
+/// The tasks run with the given `session`, so they are skipped once the session is no longer valid.
+pub async fn schedule_recurring_dataset_from_workflow_task<A: ApplicationContext>(
+    app_ctx: A,
+    session: A::Session,
+    schedule: CronSchedule,
+    task: RecurringDatasetFromWorkflow,
+) -> error::Result<RecurringTaskId> {
+    ensure!(
+        task.dataset_name().is_none(),
+        error::RecurringDatasetMustNotHaveName
+    );
+
+    let ctx = app_ctx.session_context(session.clone());
+
+    // fail early instead of on every run
+    ctx.db().load_workflow(&task.workflow_id()).await?;
+
+    let definition = serde_json::to_value(StoredRecurringDatasetFromWorkflow {
+        session_id: session.id(),
+        task,
+    })?;
+    let factory = recurring_dataset_from_workflow_factory(app_ctx, &definition)?;
+
+    let recurring_task_id = ctx
+        .tasks()
+        .add_recurring_task(schedule, definition, factory)
+        .await?;
+
+    Ok(recurring_task_id)
+}
+
+/// Lists the recurring tasks that create datasets from workflows with their definitions.
+pub async fn list_recurring_datasets_from_workflows<C: SessionContext>(
+    ctx: &C,
+) -> error::Result<Vec<(RecurringTask, RecurringDatasetFromWorkflow)>> {
+    let recurring_tasks = ctx.tasks().list_recurring_tasks().await?;
+
+    // other recurring tasks, e.g., ones that are not persisted, are skipped
+    Ok(recurring_tasks
+        .into_iter()
+        .filter_map(|recurring_task| {
+            let stored = serde_json::from_value::<StoredRecurringDatasetFromWorkflow>(
+                recurring_task.definition.clone(),
+            )
+            .ok()?;
+            Some((recurring_task, stored.task))
+        })
+        .collect())
+}
+
+/// Creates the factory of a recurring task from the `definition` of a [`RecurringDatasetFromWorkflow`].
+pub fn recurring_dataset_from_workflow_factory<A: ApplicationContext>(
+    app_ctx: A,
+    definition: &serde_json::Value,
+) -> error::Result<RecurringTaskFactory<<A::SessionContext as SessionContext>::TaskContext>> {
+    let StoredRecurringDatasetFromWorkflow { session_id, task } =
+        serde_json::from_value(definition.clone())?;
+
+    Ok(Arc::new(move || {
+        let app_ctx = app_ctx.clone();
+        let task = task.clone();
+
+        async move {
+            let session = app_ctx.session_by_id(session_id).await?;
+            let ctx = Arc::new(app_ctx.session_context(session));
+
+            create_recurring_dataset_from_workflow_task(ctx, task).await
+        }
+        .boxed()
+    }))
+}
+
+async fn create_recurring_dataset_from_workflow_task<C: SessionContext>(
+    ctx: Arc<C>,
+    task: RecurringDatasetFromWorkflow,
+) -> error::Result<Box<dyn Task<C::TaskContext>>> {
+    let source_name = format!("workflow {}", task.workflow_id());
+    let workflow = ctx.db().load_workflow(&task.workflow_id()).await?;
+
+    match task {
+        RecurringDatasetFromWorkflow::Raster { params, .. } => {
+            let compression_num_threads =
+                get_config_element::<crate::util::config::Gdal>()?.compression_num_threads;
+
+            create_raster_dataset_from_workflow_task(
+                source_name,
+                workflow,
+                ctx,
+                params,
+                compression_num_threads,
+            )
+            .await
+        }
+        RecurringDatasetFromWorkflow::Vector { params, .. } => {
+            create_vector_dataset_from_workflow_task(source_name, workflow, ctx, params).await
+        }
+    }
+}
+
+async fn create_vector_dataset<C: SessionContext>(
+    info: VectorDatasetFromWorkflow,
+    loading_info: OgrSourceDataset,
+    origin_result_descriptor: VectorResultDescriptor,
+    query_rectangle: geoengine_datatypes::primitives::VectorQueryRectangle,
+    provenance: Vec<crate::datasets::listing::Provenance>,
+    ctx: &C,
+) -> error::Result<DatasetIdAndName> {
+    // categories are written as integer fields and the OGR source reads them back as such,
+    // but their measurement still describes the classes
+    let columns = origin_result_descriptor
+        .columns
+        .into_iter()
+        .map(|(name, info)| {
+            let data_type = match info.data_type {
+                FeatureDataType::Category => FeatureDataType::Int,
+                data_type => data_type,
+            };
+            (
+                name,
+                VectorColumnInfo {
+                    data_type,
+                    measurement: info.measurement,
+                },
+            )
+        })
+        .collect();
+
+    let result_descriptor = VectorResultDescriptor {
+        time: Some(query_rectangle.time_interval),
+        bbox: Some(query_rectangle.spatial_bounds),
+        columns,
+        ..origin_result_descriptor
+    };
+
+    let meta_data = MetaDataDefinition::OgrMetaData(StaticMetaData {
+        loading_info,
+        result_descriptor,
+        phantom: Default::default(),
+    });
+
+    let properties = AddDataset {
+        name: info.name,
+        display_name: info.display_name,
+        description: info.description.unwrap_or_default(),
+        source_operator: "OgrSource".to_owned(),
+        symbology: None,
+        provenance: (!provenance.is_empty()).then_some(provenance),
+        tags: Some(vec!["workflow".to_owned()]),
+    };
+
+    let db = ctx.db();
+    let result = db.add_dataset(properties, meta_data).await?;
+
+    Ok(result)
+}
@@ -1,6 +1,6 @@
 use super::storage::MetaDataDefinition;
 use super::DatasetName;
-use crate::datasets::storage::{validate_tags, Dataset};
+use crate::datasets::storage::{validate_tags, Dataset, DatasetVersion, DatasetVersionSelector};
 use crate::error::Result;
 use crate::projects::Symbology;
 use crate::util::config::{get_config_element, DatasetService};
@@ -87,6 +87,17 @@ pub trait DatasetProvider: Send
 
     async fn resolve_dataset_name_to_id(&self, name: &DatasetName) -> Result<Option<DatasetId>>;
 
+    /// Resolve the `version` of the `dataset` to the id that refers to exactly this version.
+    /// The latest version is resolved to the id of the dataset itself.
+    async fn resolve_dataset_version(
+        &self,
+        dataset: &DatasetId,
+        version: DatasetVersionSelector,
+    ) -> Result<DatasetId>;
+
+    /// List all versions of the `dataset`, starting with the most recent one
+    async fn list_dataset_versions(&self, dataset: &DatasetId) -> Result<Vec<DatasetVersion>>;
+
     async fn dataset_autocomplete_search(
         &self,
         tags: Option<Vec<String>>,
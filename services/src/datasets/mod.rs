@@ -4,12 +4,16 @@ pub mod external; // TODO: move to layers/external
 pub mod listing;
 mod name;
 pub mod postgres;
+pub mod resumable_upload;
 pub mod storage;
 pub mod upload;
 
 pub(crate) use create_from_workflow::{
-    schedule_raster_dataset_from_workflow_task, RasterDatasetFromWorkflow,
-    RasterDatasetFromWorkflowResult,
+    list_recurring_datasets_from_workflows, recurring_dataset_from_workflow_factory,
+    schedule_raster_dataset_from_workflow_task, schedule_recurring_dataset_from_workflow_task,
+    schedule_vector_dataset_from_workflow_task, RasterDatasetFromWorkflow,
+    RasterDatasetFromWorkflowResult, RecurringDatasetFromWorkflow, VectorDatasetFormat,
+    VectorDatasetFromWorkflow, VectorDatasetFromWorkflowResult,
 };
 pub use name::{DatasetIdAndName, DatasetName};
 pub use storage::AddDataset;
@@ -4,7 +4,9 @@ use crate::api::model::services::UpdateDataset;
 use crate::contexts::PostgresDb;
 use crate::datasets::listing::ProvenanceOutput;
 use crate::datasets::listing::{DatasetListOptions, DatasetListing, DatasetProvider};
-use crate::datasets::storage::{Dataset, DatasetDb, DatasetStore, MetaDataDefinition};
+use crate::datasets::storage::{
+    Dataset, DatasetDb, DatasetStore, DatasetVersion, DatasetVersionSelector, MetaDataDefinition,
+};
 use crate::datasets::upload::FileId;
 use crate::datasets::upload::{Upload, UploadDb, UploadId};
 use crate::error::{self, Result};
@@ -26,6 +28,8 @@ use geoengine_operators::engine::{
 use geoengine_operators::mock::MockDatasetDataSourceLoadingInfo;
 use geoengine_operators::source::{GdalLoadingInfo, OgrSourceDataset};
 use postgres_types::{FromSql, ToSql};
+use snafu::ensure;
+use tokio_postgres::Transaction;
 
 impl<Tls> DatasetDb for PostgresDb<Tls>
 where
@@ -59,6 +63,234 @@ where
     Ok(row_option.map(|row| row.get(0)))
 }
 
+/// Stores the current definition of the `dataset` as its next version
+pub async fn create_dataset_version(
+    tx: &Transaction<'_>,
+    dataset: &DatasetId,
+) -> Result<DatasetVersion> {
+    let id = DatasetId::new();
+
+    let stmt = tx
+        .prepare(
+            "
+            INSERT INTO dataset_versions (
+                id,
+                dataset_id,
+                version,
+                changed,
+                name,
+                display_name,
+                description,
+                tags,
+                source_operator,
+                result_descriptor,
+                meta_data,
+                symbology,
+                provenance
+            )
+            SELECT
+                $2,
+                d.id,
+                COALESCE((SELECT MAX(version) FROM dataset_versions WHERE dataset_id = d.id), 0) + 1,
+                CURRENT_TIMESTAMP,
+                d.name,
+                d.display_name,
+                d.description,
+                d.tags,
+                d.source_operator,
+                d.result_descriptor,
+                d.meta_data,
+                d.symbology,
+                d.provenance
+            FROM datasets d
+            WHERE d.id = $1
+            RETURNING version, changed",
+        )
+        .await?;
+
+    let row = tx
+        .query_opt(&stmt, &[dataset, &id])
+        .await?
+        .ok_or(error::Error::UnknownDatasetId)?;
+
+    Ok(DatasetVersion {
+        id,
+        version: row.get::<_, i32>(0) as u32,
+        changed: row.get(1),
+    })
+}
+
+pub async fn list_dataset_versions(
+    tx: &Transaction<'_>,
+    dataset: &DatasetId,
+) -> Result<Vec<DatasetVersion>> {
+    let stmt = tx
+        .prepare(
+            "
+            SELECT id, version, changed
+            FROM dataset_versions
+            WHERE dataset_id = $1
+            ORDER BY version DESC",
+        )
+        .await?;
+
+    let rows = tx.query(&stmt, &[dataset]).await?;
+
+    // every dataset has at least one version
+    ensure!(!rows.is_empty(), error::UnknownDatasetId);
+
+    Ok(rows
+        .iter()
+        .map(|row| DatasetVersion {
+            id: row.get(0),
+            version: row.get::<_, i32>(1) as u32,
+            changed: row.get(2),
+        })
+        .collect())
+}
+
+pub async fn resolve_dataset_version(
+    tx: &Transaction<'_>,
+    dataset: &DatasetId,
+    version: DatasetVersionSelector,
+) -> Result<DatasetId> {
+    let DatasetVersionSelector::Version(version) = version else {
+        return Ok(*dataset);
+    };
+
+    let stmt = tx
+        .prepare("SELECT id FROM dataset_versions WHERE dataset_id = $1 AND version = $2")
+        .await?;
+
+    let row = tx
+        .query_opt(&stmt, &[dataset, &(version as i32)])
+        .await?
+        .ok_or(error::Error::UnknownDatasetVersion {
+            dataset: (*dataset).into(),
+            version,
+        })?;
+
+    Ok(row.get(0))
+}
+
+/// Resolves the id of a dataset version to the id of its dataset. Other ids are returned unchanged.
+pub async fn resolve_dataset_version_id_to_dataset_id(
+    tx: &Transaction<'_>,
+    id: &DatasetId,
+) -> Result<DatasetId> {
+    let stmt = tx
+        .prepare("SELECT dataset_id FROM dataset_versions WHERE id = $1")
+        .await?;
+
+    let row = tx.query_opt(&stmt, &[id]).await?;
+
+    Ok(row.map_or(*id, |row| row.get(0)))
+}
+
+pub async fn replace_dataset_meta_data(
+    tx: &Transaction<'_>,
+    dataset: &DatasetId,
+    meta_data: &MetaDataDefinition,
+) -> Result<()> {
+    let source_operator: String = tx
+        .query_opt(
+            "SELECT source_operator FROM datasets WHERE id = $1;",
+            &[dataset],
+        )
+        .await?
+        .ok_or(error::Error::UnknownDatasetId)?
+        .get(0);
+
+    ensure!(
+        source_operator == meta_data.source_operator_type(),
+        error::MetaDataDoesNotMatchSourceOperator {
+            expected: source_operator,
+            found: meta_data.source_operator_type(),
+        }
+    );
+
+    let typed_meta_data = meta_data.to_typed_metadata();
+
+    let rows_affected = tx
+        .execute(
+            "UPDATE datasets SET result_descriptor = $2, meta_data = $3 WHERE id = $1;",
+            &[
+                dataset,
+                &typed_meta_data.result_descriptor,
+                typed_meta_data.meta_data,
+            ],
+        )
+        .await?;
+
+    ensure!(rows_affected == 1, error::UnknownDatasetId);
+
+    create_dataset_version(tx, dataset).await?;
+
+    Ok(())
+}
+
+pub async fn restore_dataset_version(
+    tx: &Transaction<'_>,
+    dataset: &DatasetId,
+    version: u32,
+) -> Result<DatasetVersion> {
+    let rows_affected = tx
+        .execute(
+            "
+            UPDATE datasets d
+            SET
+                name = v.name,
+                display_name = v.display_name,
+                description = v.description,
+                tags = v.tags,
+                source_operator = v.source_operator,
+                result_descriptor = v.result_descriptor,
+                meta_data = v.meta_data,
+                symbology = v.symbology,
+                provenance = v.provenance
+            FROM dataset_versions v
+            WHERE d.id = $1 AND v.dataset_id = $1 AND v.version = $2;",
+            &[dataset, &(version as i32)],
+        )
+        .await
+        .map_unique_violation("datasets", "name", || error::Error::InvalidDatasetName)?;
+
+    ensure!(
+        rows_affected == 1,
+        error::UnknownDatasetVersion {
+            dataset: *dataset,
+            version,
+        }
+    );
+
+    create_dataset_version(tx, dataset).await
+}
+
+pub async fn garbage_collect_dataset_versions(
+    tx: &Transaction<'_>,
+    dataset: &DatasetId,
+    keep: u32,
+) -> Result<u64> {
+    ensure!(keep > 0, error::MustKeepLatestDatasetVersion);
+
+    let deleted = tx
+        .execute(
+            "
+            DELETE FROM dataset_versions
+            WHERE dataset_id = $1 AND version NOT IN (
+                SELECT version
+                FROM dataset_versions
+                WHERE dataset_id = $1
+                ORDER BY version DESC
+                LIMIT $2
+            );",
+            &[dataset, &i64::from(keep)],
+        )
+        .await?;
+
+    Ok(deleted)
+}
+
 #[allow(clippy::too_many_lines)]
 #[async_trait]
 impl<Tls> DatasetProvider for PostgresDb<Tls>
@@ -232,15 +464,24 @@ where
         let stmt = conn
             .prepare(
                 "
-            SELECT 
-                provenance 
-            FROM 
+            SELECT
+                provenance
+            FROM
                 datasets
             WHERE
-                id = $1;",
+                id = $1
+            UNION ALL
+            SELECT
+                d.provenance
+            FROM
+                dataset_versions v JOIN datasets d
+                    ON (v.dataset_id = d.id)
+            WHERE
+                v.id = $1;",
             )
             .await?;
 
+        // pinned dataset versions have the provenance of their dataset
         let row = conn.query_one(&stmt, &[dataset]).await?;
 
         let provenances: Vec<Provenance> = row.get(0);
@@ -279,6 +520,34 @@ where
         resolve_dataset_name_to_id(&conn, dataset_name).await
     }
 
+    async fn resolve_dataset_version(
+        &self,
+        dataset: &DatasetId,
+        version: DatasetVersionSelector,
+    ) -> Result<DatasetId> {
+        let mut conn = self.conn_pool.get().await?;
+
+        let tx = conn.build_transaction().start().await?;
+
+        let id = resolve_dataset_version(&tx, dataset, version).await?;
+
+        tx.commit().await?;
+
+        Ok(id)
+    }
+
+    async fn list_dataset_versions(&self, dataset: &DatasetId) -> Result<Vec<DatasetVersion>> {
+        let mut conn = self.conn_pool.get().await?;
+
+        let tx = conn.build_transaction().start().await?;
+
+        let versions = list_dataset_versions(&tx, dataset).await?;
+
+        tx.commit().await?;
+
+        Ok(versions)
+    }
+
     async fn dataset_autocomplete_search(
         &self,
         tags: Option<Vec<String>>,
@@ -383,6 +652,13 @@ where
             meta_data
         FROM
             datasets
+        WHERE
+            id = $1
+        UNION ALL
+        SELECT
+            meta_data
+        FROM
+            dataset_versions
         WHERE
             id = $1",
             )
@@ -443,6 +719,13 @@ where
                 meta_data
             FROM
                datasets
+            WHERE
+                id = $1
+            UNION ALL
+            SELECT
+                meta_data
+            FROM
+                dataset_versions
             WHERE
                 id = $1;",
             )
@@ -543,15 +826,19 @@ where
         .await
         .map_unique_violation("datasets", "name", || error::Error::InvalidDatasetName)?;
 
+        create_dataset_version(&tx, &id).await?;
+
         tx.commit().await?;
 
         Ok(DatasetIdAndName { id, name })
     }
 
     async fn update_dataset(&self, dataset: DatasetId, update: UpdateDataset) -> Result<()> {
-        let conn = self.conn_pool.get().await?;
+        let mut conn = self.conn_pool.get().await?;
 
-        conn.execute(
+        let tx = conn.build_transaction().start().await?;
+
+        tx.execute(
             "UPDATE datasets SET name = $2, display_name = $3, description = $4, tags = $5 WHERE id = $1;",
             &[
                 &dataset,
@@ -563,6 +850,10 @@ where
         )
         .await?;
 
+        create_dataset_version(&tx, &dataset).await?;
+
+        tx.commit().await?;
+
         Ok(())
     }
 
@@ -571,14 +862,20 @@ where
         dataset: DatasetId,
         symbology: &Symbology,
     ) -> Result<()> {
-        let conn = self.conn_pool.get().await?;
+        let mut conn = self.conn_pool.get().await?;
 
-        conn.execute(
+        let tx = conn.build_transaction().start().await?;
+
+        tx.execute(
             "UPDATE datasets SET symbology = $2 WHERE id = $1;",
             &[&dataset, &symbology],
         )
         .await?;
 
+        create_dataset_version(&tx, &dataset).await?;
+
+        tx.commit().await?;
+
         Ok(())
     }
 
@@ -587,14 +884,20 @@ where
         dataset: DatasetId,
         provenance: &[Provenance],
     ) -> Result<()> {
-        let conn = self.conn_pool.get().await?;
+        let mut conn = self.conn_pool.get().await?;
 
-        conn.execute(
+        let tx = conn.build_transaction().start().await?;
+
+        tx.execute(
             "UPDATE datasets SET provenance = $2 WHERE id = $1;",
             &[&dataset, &provenance],
         )
         .await?;
 
+        create_dataset_version(&tx, &dataset).await?;
+
+        tx.commit().await?;
+
         Ok(())
     }
 
@@ -607,6 +910,50 @@ where
 
         Ok(())
     }
+
+    async fn replace_dataset_meta_data(
+        &self,
+        dataset: DatasetId,
+        meta_data: MetaDataDefinition,
+    ) -> Result<()> {
+        let mut conn = self.conn_pool.get().await?;
+
+        let tx = conn.build_transaction().start().await?;
+
+        replace_dataset_meta_data(&tx, &dataset, &meta_data).await?;
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    async fn restore_dataset_version(
+        &self,
+        dataset: DatasetId,
+        version: u32,
+    ) -> Result<DatasetVersion> {
+        let mut conn = self.conn_pool.get().await?;
+
+        let tx = conn.build_transaction().start().await?;
+
+        let restored = restore_dataset_version(&tx, &dataset, version).await?;
+
+        tx.commit().await?;
+
+        Ok(restored)
+    }
+
+    async fn garbage_collect_dataset_versions(&self, dataset: DatasetId, keep: u32) -> Result<u64> {
+        let mut conn = self.conn_pool.get().await?;
+
+        let tx = conn.build_transaction().start().await?;
+
+        let deleted = garbage_collect_dataset_versions(&tx, &dataset, keep).await?;
+
+        tx.commit().await?;
+
+        Ok(deleted)
+    }
 }
 
 #[async_trait]
@@ -0,0 +1,932 @@
+//! Resumable uploads transfer large files in several requests.
+//!
+//! An upload is created with the names, sizes and optionally the SHA-256 checksums of its files.
+//! The files are then appended chunk by chunk at their current offsets, which can be queried to resume interrupted transfers.
+//! Chunks with a SHA-256 checksum are discarded if they do not match it.
+//! Finishing the upload verifies the sizes and checksums and turns it into a regular [`Upload`].
+//!
+//! The state of unfinished uploads, including their owner, is stored in a manifest next to their upload directory.
+//! Uploads that do not progress until their expiry are deleted.
+
+use crate::datasets::upload::{FileId, FileUpload, Upload, UploadId, UploadRootPath};
+use crate::error::{self, Result};
+use crate::util::config::{self, get_config_element};
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use futures::{Stream, StreamExt};
+use geoengine_datatypes::util::Identifier;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use snafu::{ensure, ResultExt, Snafu};
+use std::collections::{HashMap, HashSet};
+use std::io::{Read, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock, PoisonError};
+use std::time::Duration;
+use tokio::fs;
+use tokio::io::{AsyncSeekExt, AsyncWriteExt};
+use tokio::sync::OwnedMutexGuard;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+const MANIFEST_EXTENSION: &str = "resumable.json";
+
+#[derive(Debug, Snafu)]
+#[snafu(visibility(pub(crate)))]
+#[snafu(context(suffix(false)))] // disables default `Snafu` suffix
+pub enum ResumableUploadError {
+    #[snafu(display(
+        "The resumable upload {upload_id} does not exist, is finished or has expired"
+    ))]
+    UnknownResumableUpload { upload_id: UploadId },
+    #[snafu(display("The file `{file_name}` is not part of the resumable upload"))]
+    UnknownResumableUploadFile { file_name: String },
+    #[snafu(display("`{file_name}` is not a valid file name"))]
+    InvalidResumableUploadFileName { file_name: String },
+    #[snafu(display("The file `{file_name}` is part of the resumable upload more than once"))]
+    DuplicateResumableUploadFileName { file_name: String },
+    #[snafu(display("`{checksum}` is not a hex encoded SHA-256 checksum"))]
+    InvalidSha256Checksum { checksum: String },
+    #[snafu(display(
+        "The chunk must be appended at offset {offset}, but was sent for offset {requested_offset}"
+    ))]
+    ResumableUploadOffsetMismatch { offset: u64, requested_offset: u64 },
+    #[snafu(display("The file `{file_name}` must not be larger than {byte_size} bytes"))]
+    ResumableUploadExceedsFileSize { file_name: String, byte_size: u64 },
+    #[snafu(display(
+        "The file `{file_name}` is incomplete, only {offset} of {byte_size} bytes were uploaded"
+    ))]
+    ResumableUploadIncomplete {
+        file_name: String,
+        offset: u64,
+        byte_size: u64,
+    },
+    #[snafu(display(
+        "The checksum of the file `{file_name}` does not match, so it must be uploaded again"
+    ))]
+    ResumableUploadChecksumMismatch { file_name: String },
+    #[snafu(display(
+        "The checksum of the chunk at offset {offset} of the file `{file_name}` does not match, so it must be sent again"
+    ))]
+    ResumableUploadChunkChecksumMismatch { file_name: String, offset: u64 },
+    #[snafu(display("The expiration of resumable uploads of {seconds} seconds is too large"))]
+    InvalidResumableUploadExpiration { seconds: u64 },
+    #[snafu(display("The header `Upload-Offset` must contain the offset of the chunk"))]
+    MissingUploadOffset,
+    #[snafu(display("Receiving the chunk failed: {reason}"))]
+    ResumableUploadPayload { reason: String },
+}
+
+/// parameter for creating a resumable upload (body)
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize, ToSchema)]
+#[schema(example = json!({"files": [{"name": "mosaic.tif", "byteSize": 21_474_836_480_u64, "sha256": "9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08"}]}))]
+#[serde(rename_all = "camelCase")]
+pub struct ResumableUploadCreation {
+    pub files: Vec<ResumableFileCreation>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ResumableFileCreation {
+    pub name: String,
+    pub byte_size: u64,
+    /// The hex encoded SHA-256 checksum of the file that is verified when the upload is finished
+    pub sha256: Option<String>,
+}
+
+/// The progress of a resumable upload
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ResumableUploadStatus {
+    pub id: UploadId,
+    pub expires: DateTime<Utc>,
+    pub files: Vec<ResumableFileStatus>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ResumableFileStatus {
+    pub name: String,
+    pub byte_size: u64,
+    /// The number of bytes that were uploaded, i.e., the offset of the next chunk
+    pub offset: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ResumableUploadManifest {
+    /// The [`Session::owner`](crate::contexts::Session::owner) that created the upload
+    owner: Uuid,
+    files: Vec<ResumableFileCreation>,
+    expires: DateTime<Utc>,
+}
+
+impl ResumableUploadManifest {
+    fn file(&self, file_name: &str) -> Result<&ResumableFileCreation> {
+        self.files
+            .iter()
+            .find(|file| file.name == file_name)
+            .ok_or_else(|| {
+                UnknownResumableUploadFile {
+                    file_name: file_name.to_string(),
+                }
+                .build()
+                .into()
+            })
+    }
+}
+
+fn manifest_path(upload_id: UploadId) -> Result<PathBuf> {
+    let root = get_config_element::<config::Upload>()?.path;
+    Ok(root.join(format!("{upload_id}.{MANIFEST_EXTENSION}")))
+}
+
+fn next_expiry() -> Result<DateTime<Utc>> {
+    let expiration = get_config_element::<config::Upload>()?.resumable_upload_expiration_seconds;
+
+    chrono::Duration::from_std(Duration::from_secs(expiration))
+        .ok()
+        .and_then(|expiration| Utc::now().checked_add_signed(expiration))
+        .ok_or_else(|| {
+            InvalidResumableUploadExpiration {
+                seconds: expiration,
+            }
+            .build()
+            .into()
+        })
+}
+
+async fn read_manifest(upload_id: UploadId) -> Result<ResumableUploadManifest> {
+    let manifest = fs::read(manifest_path(upload_id)?)
+        .await
+        .map_err(|_| UnknownResumableUpload { upload_id }.build())?;
+
+    Ok(serde_json::from_slice(&manifest)?)
+}
+
+/// Loads the manifest of an unexpired upload. Uploads of other owners are treated as unknown.
+async fn load_manifest(upload_id: UploadId, owner: Uuid) -> Result<ResumableUploadManifest> {
+    let manifest = read_manifest(upload_id).await?;
+
+    ensure!(
+        manifest.owner == owner && manifest.expires > Utc::now(),
+        UnknownResumableUpload { upload_id }
+    );
+
+    Ok(manifest)
+}
+
+/// Serializes all requests that modify the same upload, s.t. the offsets of its files stay consistent
+async fn lock_upload(upload_id: UploadId) -> OwnedMutexGuard<()> {
+    static LOCKS: OnceLock<Mutex<HashMap<UploadId, Arc<tokio::sync::Mutex<()>>>>> = OnceLock::new();
+
+    let lock = {
+        let mut locks = LOCKS
+            .get_or_init(Default::default)
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+
+        // forget the locks that are neither held nor awaited
+        locks.retain(|_, lock| Arc::strong_count(lock) > 1);
+
+        locks.entry(upload_id).or_default().clone()
+    };
+
+    lock.lock_owned().await
+}
+
+/// Writes the manifest atomically, s.t. concurrent requests never read a partial manifest
+async fn store_manifest(upload_id: UploadId, manifest: &ResumableUploadManifest) -> Result<()> {
+    let path = manifest_path(upload_id)?;
+    let temporary_path = path.with_extension(format!("{}.tmp", uuid::Uuid::new_v4()));
+
+    fs::write(&temporary_path, serde_json::to_vec(manifest)?)
+        .await
+        .context(error::Io)?;
+    fs::rename(&temporary_path, &path)
+        .await
+        .context(error::Io)?;
+
+    Ok(())
+}
+
+async fn status(
+    upload_id: UploadId,
+    manifest: &ResumableUploadManifest,
+) -> Result<ResumableUploadStatus> {
+    let root = upload_id.root_path()?;
+
+    let mut files = Vec::with_capacity(manifest.files.len());
+    for file in &manifest.files {
+        let offset = fs::metadata(root.join(&file.name))
+            .await
+            .context(error::Io)?
+            .len();
+
+        files.push(ResumableFileStatus {
+            name: file.name.clone(),
+            byte_size: file.byte_size,
+            offset,
+        });
+    }
+
+    Ok(ResumableUploadStatus {
+        id: upload_id,
+        expires: manifest.expires,
+        files,
+    })
+}
+
+fn validate_creation(creation: &mut ResumableUploadCreation) -> Result<()> {
+    let mut file_names = HashSet::new();
+
+    for file in &mut creation.files {
+        // the name must not point outside the upload directory
+        let is_plain_file_name = Path::new(&file.name)
+            .file_name()
+            .map_or(false, |file_name| file_name == file.name.as_str());
+        ensure!(
+            is_plain_file_name,
+            InvalidResumableUploadFileName {
+                file_name: file.name.clone()
+            }
+        );
+
+        ensure!(
+            file_names.insert(file.name.clone()),
+            DuplicateResumableUploadFileName {
+                file_name: file.name.clone()
+            }
+        );
+
+        if let Some(checksum) = &mut file.sha256 {
+            ensure!(
+                is_sha256_checksum(checksum),
+                InvalidSha256Checksum {
+                    checksum: checksum.clone()
+                }
+            );
+            checksum.make_ascii_lowercase();
+        }
+    }
+
+    Ok(())
+}
+
+fn is_sha256_checksum(checksum: &str) -> bool {
+    checksum.len() == 64 && checksum.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// Creates a resumable upload with empty files on behalf of the `owner`
+pub async fn create_resumable_upload(
+    owner: Uuid,
+    mut creation: ResumableUploadCreation,
+) -> Result<ResumableUploadStatus> {
+    validate_creation(&mut creation)?;
+
+    let upload_id = UploadId::new();
+    let root = upload_id.root_path()?;
+    fs::create_dir_all(&root).await.context(error::Io)?;
+
+    for file in &creation.files {
+        fs::File::create(root.join(&file.name))
+            .await
+            .context(error::Io)?;
+    }
+
+    let manifest = ResumableUploadManifest {
+        owner,
+        files: creation.files,
+        expires: next_expiry()?,
+    };
+    store_manifest(upload_id, &manifest).await?;
+
+    status(upload_id, &manifest).await
+}
+
+pub async fn resumable_upload_status(
+    upload_id: UploadId,
+    owner: Uuid,
+) -> Result<ResumableUploadStatus> {
+    let manifest = load_manifest(upload_id, owner).await?;
+
+    status(upload_id, &manifest).await
+}
+
+/// Appends a chunk to a file of a resumable upload and extends the expiry of the upload.
+///
+/// The chunk must start at the current offset of the file.
+/// If the transfer of the chunk is interrupted, the received part is kept and the upload can be resumed at the new offset.
+/// If the hex encoded SHA-256 `checksum` of the chunk is given, the file is truncated to the start of the chunk unless the whole chunk matches it.
+pub async fn append_to_resumable_upload<S>(
+    upload_id: UploadId,
+    owner: Uuid,
+    file_name: &str,
+    requested_offset: u64,
+    checksum: Option<&str>,
+    mut chunk: S,
+) -> Result<ResumableFileStatus>
+where
+    S: Stream<Item = Result<Bytes>> + Unpin,
+{
+    if let Some(checksum) = checksum {
+        ensure!(
+            is_sha256_checksum(checksum),
+            InvalidSha256Checksum { checksum }
+        );
+    }
+
+    let _lock = lock_upload(upload_id).await;
+
+    let mut manifest = load_manifest(upload_id, owner).await?;
+    let byte_size = manifest.file(file_name)?.byte_size;
+
+    let mut file = fs::OpenOptions::new()
+        .write(true)
+        .open(upload_id.root_path()?.join(file_name))
+        .await
+        .context(error::Io)?;
+
+    let offset = file.metadata().await.context(error::Io)?.len();
+    ensure!(
+        offset == requested_offset,
+        ResumableUploadOffsetMismatch {
+            offset,
+            requested_offset
+        }
+    );
+
+    file.seek(SeekFrom::Start(offset))
+        .await
+        .context(error::Io)?;
+
+    let mut hasher = Sha256::new();
+    let mut new_offset = offset;
+    let mut result = Ok(());
+    while let Some(bytes) = chunk.next().await {
+        let bytes = match bytes {
+            Ok(bytes) => bytes,
+            Err(error) => {
+                result = Err(error);
+                break;
+            }
+        };
+
+        if new_offset + bytes.len() as u64 > byte_size {
+            result = Err(ResumableUploadExceedsFileSize {
+                file_name,
+                byte_size,
+            }
+            .build()
+            .into());
+            break;
+        }
+
+        if let Err(error) = file.write_all(&bytes).await {
+            result = Err(error::Error::Io { source: error });
+            break;
+        }
+        hasher.update(&bytes);
+        new_offset += bytes.len() as u64;
+    }
+
+    if let Some(checksum) = checksum {
+        // a partial or corrupted chunk cannot be verified, so it must be sent again
+        let is_valid_chunk =
+            result.is_ok() && hex::encode(hasher.finalize()).eq_ignore_ascii_case(checksum);
+
+        if !is_valid_chunk {
+            file.set_len(offset).await.context(error::Io)?;
+            new_offset = offset;
+
+            if result.is_ok() {
+                result = Err(ResumableUploadChunkChecksumMismatch { file_name, offset }
+                    .build()
+                    .into());
+            }
+        }
+    }
+
+    // keep the received part for resuming the upload
+    file.flush().await.context(error::Io)?;
+    file.sync_data().await.context(error::Io)?;
+
+    manifest.expires = next_expiry()?;
+    store_manifest(upload_id, &manifest).await?;
+
+    result?;
+
+    Ok(ResumableFileStatus {
+        name: file_name.to_string(),
+        byte_size,
+        offset: new_offset,
+    })
+}
+
+/// A verified resumable upload. No other request can modify it until it is completed or dropped.
+pub struct FinishedResumableUpload {
+    pub upload: Upload,
+    _lock: OwnedMutexGuard<()>,
+}
+
+/// Verifies that all files are complete and match their checksums.
+///
+/// Returns the upload that must be stored before calling [`complete_resumable_upload`].
+/// Files with wrong checksums are truncated, s.t. they can be uploaded again.
+/// Appending chunks with checksums detects corrupted chunks early and only requires sending them again.
+pub async fn finish_resumable_upload(
+    upload_id: UploadId,
+    owner: Uuid,
+) -> Result<FinishedResumableUpload> {
+    let lock = lock_upload(upload_id).await;
+
+    let manifest = load_manifest(upload_id, owner).await?;
+    let root = upload_id.root_path()?;
+
+    let mut files = Vec::with_capacity(manifest.files.len());
+    for file in manifest.files {
+        let path = root.join(&file.name);
+        let offset = fs::metadata(&path).await.context(error::Io)?.len();
+
+        ensure!(
+            offset == file.byte_size,
+            ResumableUploadIncomplete {
+                file_name: file.name,
+                offset,
+                byte_size: file.byte_size,
+            }
+        );
+
+        if let Some(expected_checksum) = &file.sha256 {
+            let checksum_path = path.clone();
+            let checksum =
+                crate::util::spawn_blocking(move || sha256_of_file(&checksum_path)).await??;
+
+            if checksum != *expected_checksum {
+                fs::File::create(&path).await.context(error::Io)?;
+
+                return Err(ResumableUploadChecksumMismatch {
+                    file_name: file.name,
+                }
+                .build()
+                .into());
+            }
+        }
+
+        files.push(FileUpload {
+            id: FileId::new(),
+            name: file.name,
+            byte_size: file.byte_size,
+        });
+    }
+
+    Ok(FinishedResumableUpload {
+        upload: Upload {
+            id: upload_id,
+            files,
+        },
+        _lock: lock,
+    })
+}
+
+/// Removes the state of a finished resumable upload. Its files are kept.
+pub async fn complete_resumable_upload(finished: FinishedResumableUpload) -> Result<()> {
+    fs::remove_file(manifest_path(finished.upload.id)?)
+        .await
+        .context(error::Io)
+}
+
+/// Deletes an unfinished resumable upload with all of its files
+pub async fn abort_resumable_upload(upload_id: UploadId, owner: Uuid) -> Result<()> {
+    let _lock = lock_upload(upload_id).await;
+
+    load_manifest(upload_id, owner).await?;
+
+    delete_resumable_upload(upload_id).await
+}
+
+async fn delete_resumable_upload(upload_id: UploadId) -> Result<()> {
+    let root = upload_id.root_path()?;
+    if fs::try_exists(&root).await.context(error::Io)? {
+        fs::remove_dir_all(&root).await.context(error::Io)?;
+    }
+
+    fs::remove_file(manifest_path(upload_id)?)
+        .await
+        .context(error::Io)
+}
+
+fn sha256_of_file(path: &Path) -> Result<String> {
+    let mut file = std::fs::File::open(path).context(error::Io)?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0; 1024 * 1024];
+
+    loop {
+        let read = file.read(&mut buffer).context(error::Io)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+
+    Ok(hex::encode(hasher.finalize()))
+}
+
+/// Deletes all resumable uploads whose expiry has passed and returns their number
+pub async fn delete_expired_resumable_uploads() -> Result<usize> {
+    let root = get_config_element::<config::Upload>()?.path;
+    if !fs::try_exists(&root).await.context(error::Io)? {
+        return Ok(0);
+    }
+
+    let mut deleted = 0;
+    let mut entries = fs::read_dir(&root).await.context(error::Io)?;
+    while let Some(entry) = entries.next_entry().await.context(error::Io)? {
+        let file_name = entry.file_name().to_string_lossy().to_string();
+        let Some(upload_id) = file_name
+            .strip_suffix(&format!(".{MANIFEST_EXTENSION}"))
+            .and_then(|upload_id| upload_id.parse::<UploadId>().ok())
+        else {
+            continue;
+        };
+
+        let _lock = lock_upload(upload_id).await;
+
+        // manifests that cannot be read may be of uploads that were just finished or aborted
+        let Ok(manifest) = read_manifest(upload_id).await else {
+            continue;
+        };
+
+        if manifest.expires <= Utc::now() {
+            delete_resumable_upload(upload_id).await?;
+            deleted += 1;
+        }
+    }
+
+    Ok(deleted)
+}
+
+/// Periodically deletes expired resumable uploads in the background
+pub fn spawn_expired_upload_cleanup() -> Result<()> {
+    let interval = get_config_element::<config::Upload>()?.expired_upload_cleanup_interval_seconds;
+
+    crate::util::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(interval));
+
+        loop {
+            interval.tick().await;
+
+            match delete_expired_resumable_uploads().await {
+                Ok(0) => {}
+                Ok(deleted) => log::info!("Deleted {deleted} expired resumable uploads"),
+                Err(error) => log::warn!("Deleting expired resumable uploads failed: {error}"),
+            }
+        }
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::tests::TestDataUploads;
+
+    const OWNER: Uuid = Uuid::from_u128(0x1);
+
+    fn chunk(bytes: &'static [u8]) -> impl Stream<Item = Result<Bytes>> + Unpin {
+        futures::stream::iter([Ok(Bytes::from_static(bytes))])
+    }
+
+    #[tokio::test]
+    async fn it_resumes_and_verifies_uploads() {
+        let mut test_data = TestDataUploads::default();
+
+        let status = create_resumable_upload(
+            OWNER,
+            ResumableUploadCreation {
+                files: vec![ResumableFileCreation {
+                    name: "test.txt".to_string(),
+                    byte_size: 11,
+                    // sha256 of `hello world`
+                    sha256: Some(
+                        "B94D27B9934D3E08A52E52D7DA7DABFAC484EFE37A5380EE9088F7ACE2EFCDE9"
+                            .to_string(),
+                    ),
+                }],
+            },
+        )
+        .await
+        .unwrap();
+        let upload_id = status.id;
+        test_data.uploads.push(upload_id);
+
+        assert_eq!(status.files[0].offset, 0);
+
+        append_to_resumable_upload(upload_id, OWNER, "test.txt", 0, None, chunk(b"hello"))
+            .await
+            .unwrap();
+
+        // chunks must be appended at the current offset
+        assert!(
+            append_to_resumable_upload(upload_id, OWNER, "test.txt", 0, None, chunk(b"hello"))
+                .await
+                .is_err()
+        );
+        assert!(finish_resumable_upload(upload_id, OWNER).await.is_err());
+
+        let status = resumable_upload_status(upload_id, OWNER).await.unwrap();
+        assert_eq!(status.files[0].offset, 5);
+
+        // the file must not exceed its size
+        assert!(append_to_resumable_upload(
+            upload_id,
+            OWNER,
+            "test.txt",
+            5,
+            None,
+            chunk(b" world!")
+        )
+        .await
+        .is_err());
+
+        append_to_resumable_upload(upload_id, OWNER, "test.txt", 5, None, chunk(b" world"))
+            .await
+            .unwrap();
+
+        let finished = finish_resumable_upload(upload_id, OWNER).await.unwrap();
+        let upload = finished.upload.clone();
+        complete_resumable_upload(finished).await.unwrap();
+
+        assert_eq!(upload.files.len(), 1);
+        assert_eq!(upload.files[0].byte_size, 11);
+        assert_eq!(
+            std::fs::read_to_string(upload_id.root_path().unwrap().join("test.txt")).unwrap(),
+            "hello world"
+        );
+        assert!(resumable_upload_status(upload_id, OWNER).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn it_rejects_wrong_checksums_and_invalid_names() {
+        let mut test_data = TestDataUploads::default();
+
+        assert!(create_resumable_upload(
+            OWNER,
+            ResumableUploadCreation {
+                files: vec![ResumableFileCreation {
+                    name: "../test.txt".to_string(),
+                    byte_size: 1,
+                    sha256: None,
+                }],
+            }
+        )
+        .await
+        .is_err());
+
+        let upload_id = create_resumable_upload(
+            OWNER,
+            ResumableUploadCreation {
+                files: vec![ResumableFileCreation {
+                    name: "test.txt".to_string(),
+                    byte_size: 5,
+                    sha256: Some("0".repeat(64)),
+                }],
+            },
+        )
+        .await
+        .unwrap()
+        .id;
+        test_data.uploads.push(upload_id);
+
+        append_to_resumable_upload(upload_id, OWNER, "test.txt", 0, None, chunk(b"hello"))
+            .await
+            .unwrap();
+
+        assert!(finish_resumable_upload(upload_id, OWNER).await.is_err());
+
+        // the file must be uploaded again
+        let status = resumable_upload_status(upload_id, OWNER).await.unwrap();
+        assert_eq!(status.files[0].offset, 0);
+
+        abort_resumable_upload(upload_id, OWNER).await.unwrap();
+        assert!(!upload_id.root_path().unwrap().exists());
+        assert!(resumable_upload_status(upload_id, OWNER).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn it_deletes_expired_uploads() {
+        let mut test_data = TestDataUploads::default();
+
+        let upload_id = create_resumable_upload(
+            OWNER,
+            ResumableUploadCreation {
+                files: vec![ResumableFileCreation {
+                    name: "test.txt".to_string(),
+                    byte_size: 5,
+                    sha256: None,
+                }],
+            },
+        )
+        .await
+        .unwrap()
+        .id;
+        test_data.uploads.push(upload_id);
+
+        let unexpired_upload_id = create_resumable_upload(
+            OWNER,
+            ResumableUploadCreation {
+                files: vec![ResumableFileCreation {
+                    name: "test.txt".to_string(),
+                    byte_size: 5,
+                    sha256: None,
+                }],
+            },
+        )
+        .await
+        .unwrap()
+        .id;
+        test_data.uploads.push(unexpired_upload_id);
+
+        let unreadable_upload_id = UploadId::new();
+        fs::write(manifest_path(unreadable_upload_id).unwrap(), b"{")
+            .await
+            .unwrap();
+
+        let mut manifest = load_manifest(upload_id, OWNER).await.unwrap();
+        manifest.expires = Utc::now() - chrono::Duration::seconds(1);
+        store_manifest(upload_id, &manifest).await.unwrap();
+
+        assert!(delete_expired_resumable_uploads().await.unwrap() >= 1);
+
+        assert!(!upload_id.root_path().unwrap().exists());
+        assert!(!manifest_path(upload_id).unwrap().exists());
+
+        // only uploads that are known to be expired are deleted
+        assert!(resumable_upload_status(unexpired_upload_id, OWNER)
+            .await
+            .is_ok());
+        assert!(manifest_path(unreadable_upload_id).unwrap().exists());
+
+        fs::remove_file(manifest_path(unreadable_upload_id).unwrap())
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn it_restricts_uploads_to_their_owner() {
+        let mut test_data = TestDataUploads::default();
+
+        let other_owner = Uuid::from_u128(0x2);
+
+        let upload_id = create_resumable_upload(
+            OWNER,
+            ResumableUploadCreation {
+                files: vec![ResumableFileCreation {
+                    name: "test.txt".to_string(),
+                    byte_size: 5,
+                    sha256: None,
+                }],
+            },
+        )
+        .await
+        .unwrap()
+        .id;
+        test_data.uploads.push(upload_id);
+
+        assert!(resumable_upload_status(upload_id, other_owner)
+            .await
+            .is_err());
+        assert!(append_to_resumable_upload(
+            upload_id,
+            other_owner,
+            "test.txt",
+            0,
+            None,
+            chunk(b"hello")
+        )
+        .await
+        .is_err());
+
+        append_to_resumable_upload(upload_id, OWNER, "test.txt", 0, None, chunk(b"hello"))
+            .await
+            .unwrap();
+
+        assert!(finish_resumable_upload(upload_id, other_owner)
+            .await
+            .is_err());
+        assert!(abort_resumable_upload(upload_id, other_owner)
+            .await
+            .is_err());
+
+        assert_eq!(
+            resumable_upload_status(upload_id, OWNER)
+                .await
+                .unwrap()
+                .files[0]
+                .offset,
+            5
+        );
+    }
+
+    #[tokio::test]
+    async fn it_discards_corrupted_chunks() {
+        let mut test_data = TestDataUploads::default();
+
+        let upload_id = create_resumable_upload(
+            OWNER,
+            ResumableUploadCreation {
+                files: vec![ResumableFileCreation {
+                    name: "test.txt".to_string(),
+                    byte_size: 11,
+                    sha256: None,
+                }],
+            },
+        )
+        .await
+        .unwrap()
+        .id;
+        test_data.uploads.push(upload_id);
+
+        // sha256 of `hello`
+        let checksum = "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824";
+
+        append_to_resumable_upload(
+            upload_id,
+            OWNER,
+            "test.txt",
+            0,
+            Some(checksum),
+            chunk(b"hello"),
+        )
+        .await
+        .unwrap();
+
+        assert!(matches!(
+            append_to_resumable_upload(
+                upload_id,
+                OWNER,
+                "test.txt",
+                5,
+                Some(checksum),
+                chunk(b" world"),
+            )
+            .await,
+            Err(error::Error::ResumableUpload {
+                source: ResumableUploadError::ResumableUploadChunkChecksumMismatch {
+                    offset: 5,
+                    ..
+                }
+            })
+        ));
+
+        // only the corrupted chunk must be sent again
+        assert_eq!(
+            resumable_upload_status(upload_id, OWNER)
+                .await
+                .unwrap()
+                .files[0]
+                .offset,
+            5
+        );
+        assert_eq!(
+            std::fs::read_to_string(upload_id.root_path().unwrap().join("test.txt")).unwrap(),
+            "hello"
+        );
+    }
+
+    #[tokio::test]
+    async fn it_serializes_concurrent_appends() {
+        let mut test_data = TestDataUploads::default();
+
+        let upload_id = create_resumable_upload(
+            OWNER,
+            ResumableUploadCreation {
+                files: vec![ResumableFileCreation {
+                    name: "test.txt".to_string(),
+                    byte_size: 5,
+                    sha256: None,
+                }],
+            },
+        )
+        .await
+        .unwrap()
+        .id;
+        test_data.uploads.push(upload_id);
+
+        let (first, second) = futures::join!(
+            append_to_resumable_upload(upload_id, OWNER, "test.txt", 0, None, chunk(b"hello")),
+            append_to_resumable_upload(upload_id, OWNER, "test.txt", 0, None, chunk(b"world")),
+        );
+
+        // the second append sees the offset of the first one
+        assert!(first.is_ok() != second.is_ok());
+        assert_eq!(
+            resumable_upload_status(upload_id, OWNER)
+                .await
+                .unwrap()
+                .files[0]
+                .offset,
+            5
+        );
+    }
+}
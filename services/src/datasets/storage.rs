@@ -10,7 +10,7 @@ use crate::error::Result;
 use crate::projects::Symbology;
 use async_trait::async_trait;
 use geoengine_datatypes::dataset::DatasetId;
-use geoengine_datatypes::primitives::VectorQueryRectangle;
+use geoengine_datatypes::primitives::{DateTime, VectorQueryRectangle};
 use geoengine_operators::engine::{MetaData, TypedResultDescriptor};
 use geoengine_operators::source::{GdalMetaDataList, GdalMetadataNetCdfCf};
 use geoengine_operators::{engine::StaticMetaData, source::OgrSourceDataset};
@@ -19,6 +19,7 @@ use geoengine_operators::{mock::MockDatasetDataSourceLoadingInfo, source::GdalMe
 use serde::{Deserialize, Serialize};
 use snafu::ResultExt;
 use std::fmt::Debug;
+use std::str::FromStr;
 use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
 use validator::{Validate, ValidationError};
@@ -300,4 +301,57 @@ pub trait DatasetStore {
     ) -> Result<()>;
 
     async fn delete_dataset(&self, dataset: DatasetId) -> Result<()>;
+
+    /// Replace the meta data of the `dataset`, e.g., to point it to newly uploaded files.
+    /// A new version is created
+    async fn replace_dataset_meta_data(
+        &self,
+        dataset: DatasetId,
+        meta_data: MetaDataDefinition,
+    ) -> Result<()>;
+
+    /// Restore the definition of the `dataset` from the given `version`.
+    /// The restored definition becomes a new version
+    async fn restore_dataset_version(
+        &self,
+        dataset: DatasetId,
+        version: u32,
+    ) -> Result<DatasetVersion>;
+
+    /// Delete all but the `keep` most recent versions of the `dataset` and return the number of deleted versions
+    async fn garbage_collect_dataset_versions(&self, dataset: DatasetId, keep: u32) -> Result<u64>;
+}
+
+/// A snapshot of the definition of a dataset. Every change of a dataset creates a new version.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DatasetVersion {
+    /// The id that refers to exactly this version of the dataset
+    pub id: DatasetId,
+    pub version: u32,
+    pub changed: DateTime,
+}
+
+/// Selects a version of a dataset. Workflows reference a version by suffixing the dataset name, e.g., `name@3` or `name@latest`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DatasetVersionSelector {
+    Latest,
+    Version(u32),
+}
+
+impl FromStr for DatasetVersionSelector {
+    type Err = error::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        if s == "latest" {
+            return Ok(Self::Latest);
+        }
+
+        match s.parse::<u32>() {
+            Ok(version) if version > 0 => Ok(Self::Version(version)),
+            _ => Err(error::Error::InvalidDatasetVersion {
+                version: s.to_string(),
+            }),
+        }
+    }
 }
@@ -85,6 +85,17 @@ pub enum Error {
     InvalidSession,
     #[snafu(display("Invalid admin token"))]
     InvalidAdminToken,
+    #[snafu(display("The API token is invalid, expired or revoked."))]
+    InvalidApiToken,
+    #[snafu(display("API tokens cannot be used to manage API tokens."))]
+    ApiTokenCannotManageApiTokens,
+    #[snafu(display("The expiry date of an API token must be in the future."))]
+    ApiTokenExpiryMustBeInTheFuture,
+    #[cfg(feature = "pro")]
+    #[snafu(display("API token {api_token} does not exist."))]
+    UnknownApiToken {
+        api_token: crate::pro::users::ApiTokenId,
+    },
     #[snafu(display("Header with authorization token not provided."))]
     MissingAuthorizationHeader,
     #[snafu(display("Authentication scheme must be Bearer."))]
@@ -218,6 +229,10 @@ pub enum Error {
         reason: String,
     },
     InvalidUploadFileName,
+    #[snafu(display("Volume '{}' does not exist", volume_name))]
+    UnknownVolumeName {
+        volume_name: String,
+    },
     InvalidDatasetIdNamespace,
     DuplicateDatasetId,
     #[snafu(display("Dataset name '{}' already exists", dataset_name))]
@@ -230,6 +245,29 @@ pub enum Error {
         dataset_name: String,
     },
     InvalidDatasetName,
+    #[snafu(display(
+        "Dataset version '{}' is invalid, it must be a positive number or 'latest'",
+        version
+    ))]
+    InvalidDatasetVersion {
+        version: String,
+    },
+    #[snafu(display("Version {} of dataset {} does not exist", version, dataset))]
+    UnknownDatasetVersion {
+        dataset: DatasetId,
+        version: u32,
+    },
+    #[snafu(display("At least the latest version of a dataset must be kept"))]
+    MustKeepLatestDatasetVersion,
+    #[snafu(display(
+        "The meta data is for the source operator {} but the dataset uses {}",
+        found,
+        expected
+    ))]
+    MetaDataDoesNotMatchSourceOperator {
+        expected: String,
+        found: String,
+    },
     DatasetInvalidLayerName {
         layer_name: String,
     },
@@ -239,6 +277,10 @@ pub enum Error {
         source: gdal::errors::GdalError,
     },
     EmptyDatasetCannotBeImported,
+    #[snafu(display(
+        "Datasets that are created by recurring tasks must not have a name, since each run creates a new dataset"
+    ))]
+    RecurringDatasetMustNotHaveName,
     NoMainFileCandidateFound,
     NoFeatureDataTypeForColumnDataType,
 
@@ -385,6 +427,16 @@ pub enum Error {
         source: crate::api::handlers::workflows::WorkflowApiError,
     },
 
+    #[snafu(context(false), display("ResumableUploadError: {}", source))]
+    ResumableUpload {
+        source: crate::datasets::resumable_upload::ResumableUploadError,
+    },
+
+    #[snafu(context(false), display("WorkflowExportError: {}", source))]
+    WorkflowExport {
+        source: crate::workflows::export::WorkflowExportError,
+    },
+
     SubPathMustNotEscapeBasePath {
         base: PathBuf,
         sub_path: PathBuf,
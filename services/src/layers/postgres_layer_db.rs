@@ -12,7 +12,7 @@ use crate::{
         layer::{
             AddLayer, AddLayerCollection, CollectionItem, Layer, LayerCollection,
             LayerCollectionListOptions, LayerCollectionListing, LayerListing,
-            ProviderLayerCollectionId, ProviderLayerId,
+            ProviderLayerCollectionId, ProviderLayerId, UpdateLayer, UpdateLayerCollection,
         },
         listing::{LayerCollectionId, LayerCollectionProvider},
         storage::{
@@ -23,12 +23,10 @@ use crate::{
     },
 };
 use async_trait::async_trait;
-use bb8_postgres::bb8::PooledConnection;
 use bb8_postgres::tokio_postgres::{
     tls::{MakeTlsConnect, TlsConnect},
     Socket,
 };
-use bb8_postgres::PostgresConnectionManager;
 use geoengine_datatypes::dataset::{DataProviderId, LayerId};
 use geoengine_datatypes::util::HashMapTextTextDbType;
 use snafu::ResultExt;
@@ -204,17 +202,11 @@ pub async fn insert_layer_collection_with_id(
     Ok(collection_id)
 }
 
-pub async fn insert_collection_parent<Tls>(
-    conn: &PooledConnection<'_, PostgresConnectionManager<Tls>>,
+pub async fn insert_collection_parent(
+    trans: &Transaction<'_>,
     collection: &LayerCollectionId,
     parent: &LayerCollectionId,
-) -> Result<()>
-where
-    Tls: MakeTlsConnect<Socket> + Clone + Send + Sync + 'static + std::fmt::Debug,
-    <Tls as MakeTlsConnect<Socket>>::Stream: Send + Sync,
-    <Tls as MakeTlsConnect<Socket>>::TlsConnect: Send,
-    <<Tls as MakeTlsConnect<Socket>>::TlsConnect as TlsConnect<Socket>>::Future: Send,
-{
+) -> Result<()> {
     let collection =
         Uuid::from_str(&collection.0).map_err(|_| crate::error::Error::IdStringMustBeUuid {
             found: collection.0.clone(),
@@ -225,7 +217,7 @@ where
             found: parent.0.clone(),
         })?;
 
-    let stmt = conn
+    let stmt = trans
         .prepare(
             "
         INSERT INTO collection_children (parent, child)
@@ -233,7 +225,7 @@ where
         )
         .await?;
 
-    conn.execute(&stmt, &[&parent, &collection]).await?;
+    trans.execute(&stmt, &[&parent, &collection]).await?;
 
     Ok(())
 }
@@ -357,6 +349,99 @@ pub async fn delete_layer_collection_from_parent(
     Ok(())
 }
 
+pub async fn update_layer(trans: &Transaction<'_>, id: &LayerId, layer: UpdateLayer) -> Result<()> {
+    let layer_id = Uuid::from_str(&id.0).map_err(|_| crate::error::Error::IdStringMustBeUuid {
+        found: id.0.clone(),
+    })?;
+
+    let workflow_id = WorkflowId::from_hash(&layer.workflow);
+
+    let stmt = trans
+        .prepare(
+            "INSERT INTO workflows (id, workflow) VALUES ($1, $2) 
+            ON CONFLICT DO NOTHING;",
+        )
+        .await?;
+
+    trans
+        .execute(
+            &stmt,
+            &[
+                &workflow_id,
+                &serde_json::to_value(&layer.workflow).context(error::SerdeJson)?,
+            ],
+        )
+        .await?;
+
+    let stmt = trans
+        .prepare(
+            "
+            UPDATE layers
+            SET name = $2, description = $3, workflow_id = $4, symbology = $5, properties = $6, metadata = $7
+            WHERE id = $1;",
+        )
+        .await?;
+
+    let num_updated = trans
+        .execute(
+            &stmt,
+            &[
+                &layer_id,
+                &layer.name,
+                &layer.description,
+                &workflow_id,
+                &layer.symbology,
+                &layer.properties,
+                &HashMapTextTextDbType::from(&layer.metadata),
+            ],
+        )
+        .await?;
+
+    if num_updated == 0 {
+        return Err(LayerDbError::NoLayerForGivenId { id: id.clone() }.into());
+    }
+
+    Ok(())
+}
+
+pub async fn update_layer_collection(
+    trans: &Transaction<'_>,
+    id: &LayerCollectionId,
+    collection: UpdateLayerCollection,
+) -> Result<()> {
+    let collection_id =
+        Uuid::from_str(&id.0).map_err(|_| crate::error::Error::IdStringMustBeUuid {
+            found: id.0.clone(),
+        })?;
+
+    let stmt = trans
+        .prepare(
+            "
+            UPDATE layer_collections
+            SET name = $2, description = $3, properties = $4
+            WHERE id = $1;",
+        )
+        .await?;
+
+    let num_updated = trans
+        .execute(
+            &stmt,
+            &[
+                &collection_id,
+                &collection.name,
+                &collection.description,
+                &collection.properties,
+            ],
+        )
+        .await?;
+
+    if num_updated == 0 {
+        return Err(LayerDbError::NoLayerCollectionForGivenId { id: id.clone() }.into());
+    }
+
+    Ok(())
+}
+
 fn create_search_query(full_info: bool) -> String {
     format!("
         WITH RECURSIVE parents AS (
@@ -497,8 +582,12 @@ where
         collection: &LayerCollectionId,
         parent: &LayerCollectionId,
     ) -> Result<()> {
-        let conn = self.conn_pool.get().await?;
-        insert_collection_parent(&conn, collection, parent).await
+        let mut conn = self.conn_pool.get().await?;
+        let transaction = conn.transaction().await?;
+
+        insert_collection_parent(&transaction, collection, parent).await?;
+
+        transaction.commit().await.map_err(Into::into)
     }
 
     async fn remove_layer_collection(&self, collection: &LayerCollectionId) -> Result<()> {
@@ -535,6 +624,28 @@ where
 
         transaction.commit().await.map_err(Into::into)
     }
+
+    async fn update_layer(&self, id: &LayerId, layer: UpdateLayer) -> Result<()> {
+        let mut conn = self.conn_pool.get().await?;
+        let transaction = conn.transaction().await?;
+
+        update_layer(&transaction, id, layer).await?;
+
+        transaction.commit().await.map_err(Into::into)
+    }
+
+    async fn update_layer_collection(
+        &self,
+        id: &LayerCollectionId,
+        collection: UpdateLayerCollection,
+    ) -> Result<()> {
+        let mut conn = self.conn_pool.get().await?;
+        let transaction = conn.transaction().await?;
+
+        update_layer_collection(&transaction, id, collection).await?;
+
+        transaction.commit().await.map_err(Into::into)
+    }
 }
 
 #[async_trait]
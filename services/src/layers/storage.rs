@@ -1,5 +1,5 @@
 use super::external::{DataProvider, TypedDataProviderDefinition};
-use super::layer::{AddLayer, AddLayerCollection};
+use super::layer::{AddLayer, AddLayerCollection, UpdateLayer, UpdateLayerCollection};
 use super::listing::LayerCollectionId;
 use crate::error::Result;
 
@@ -87,7 +87,19 @@ pub trait LayerDb: Send + Sync {
         collection: &LayerCollectionId,
     ) -> Result<()>;
 
-    // TODO: update
+    /// Updates the name, description, workflow, symbology, properties and metadata of the `layer` with the given `id`.
+    ///
+    /// The layer keeps its id and remains in all of its collections.
+    async fn update_layer(&self, id: &LayerId, layer: UpdateLayer) -> Result<()>;
+
+    /// Updates the name, description and properties of the `collection` with the given `id`.
+    ///
+    /// The collection keeps its id, its children and remains in all of its parents.
+    async fn update_layer_collection(
+        &self,
+        id: &LayerCollectionId,
+        collection: UpdateLayerCollection,
+    ) -> Result<()>;
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
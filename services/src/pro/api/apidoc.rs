@@ -4,7 +4,9 @@ use super::model::MlModelId;
 use crate::api::handlers;
 use crate::api::handlers::plots::WrappedPlotOutput;
 use crate::api::handlers::spatial_references::{AxisOrder, SpatialReferenceSpecification};
-use crate::api::handlers::tasks::{TaskAbortOptions, TaskResponse};
+use crate::api::handlers::tasks::{
+    AddRecurringTask, RecurringTaskResponse, TaskAbortOptions, TaskResponse,
+};
 use crate::api::handlers::upload::{UploadFileLayersResponse, UploadFilesResponse};
 use crate::api::handlers::wfs::{CollectionType, GeoJson};
 use crate::api::handlers::workflows::{ProvenanceEntry, RasterStreamWebsocketResultType};
@@ -37,17 +39,25 @@ use crate::api::model::responses::{
 };
 use crate::api::model::services::{
     AddDataset, CreateDataset, DataPath, DatasetDefinition, MetaDataDefinition, MetaDataSuggestion,
-    Provenance, ProvenanceOutput, Provenances, UpdateDataset,
+    Provenance, ProvenanceOutput, Provenances, ReplaceDatasetMetaData, UpdateDataset,
 };
 use crate::api::ogc::{util::OgcBoundingBox, wcs, wfs, wms};
 use crate::contexts::SessionId;
 use crate::datasets::listing::{DatasetListing, OrderBy};
-use crate::datasets::storage::{AutoCreateDataset, Dataset};
+use crate::datasets::resumable_upload::{
+    ResumableFileCreation, ResumableFileStatus, ResumableUploadCreation, ResumableUploadStatus,
+};
+use crate::datasets::storage::{AutoCreateDataset, Dataset, DatasetVersion};
 use crate::datasets::upload::{UploadId, Volume, VolumeName};
-use crate::datasets::{DatasetName, RasterDatasetFromWorkflow, RasterDatasetFromWorkflowResult};
+use crate::datasets::{
+    DatasetName, RasterDatasetFromWorkflow, RasterDatasetFromWorkflowResult,
+    RecurringDatasetFromWorkflow, VectorDatasetFormat, VectorDatasetFromWorkflow,
+    VectorDatasetFromWorkflowResult,
+};
 use crate::layers::layer::{
     AddLayer, AddLayerCollection, CollectionItem, Layer, LayerCollection, LayerCollectionListing,
-    LayerListing, Property, ProviderLayerCollectionId, ProviderLayerId,
+    LayerListing, Property, ProviderLayerCollectionId, ProviderLayerId, UpdateLayer,
+    UpdateLayerCollection,
 };
 use crate::layers::listing::{
     LayerCollectionId, ProviderCapabilities, SearchCapabilities, SearchType, SearchTypes,
@@ -55,11 +65,12 @@ use crate::layers::listing::{
 use crate::pro;
 use crate::pro::api::handlers::users::{Quota, UpdateQuota};
 use crate::pro::permissions::{
-    Permission, PermissionListing, ResourceId, Role, RoleDescription, RoleId,
+    EffectivePermissionListing, Permission, PermissionListing, ResourceId, Role, RoleDescription,
+    RoleId,
 };
 use crate::pro::users::{
-    AuthCodeRequestURL, AuthCodeResponse, UserCredentials, UserId, UserInfo, UserRegistration,
-    UserSession,
+    ApiToken, ApiTokenId, ApiTokenScope, AuthCodeRequestURL, AuthCodeResponse, CreateApiToken,
+    CreatedApiToken, UserCredentials, UserId, UserInfo, UserRegistration, UserSession,
 };
 use crate::projects::{
     ColorParam, CreateProject, DerivedColor, DerivedNumber, LayerUpdate, LayerVisibility,
@@ -67,11 +78,15 @@ use crate::projects::{
     ProjectId, ProjectLayer, ProjectListing, ProjectUpdateToken, ProjectVersion, ProjectVersionId,
     RasterSymbology, STRectangle, StrokeParam, Symbology, TextSymbology, UpdateProject,
 };
-use crate::tasks::{TaskFilter, TaskId, TaskListOptions, TaskStatus, TaskStatusWithId};
+use crate::tasks::{
+    RecurringTaskId, TaskFilter, TaskId, TaskListOptions, TaskPriority, TaskStatus,
+    TaskStatusWithId,
+};
 use crate::util::{
     apidoc::{OpenApiServerInfo, TransformSchemasWithTag},
     server::ServerInfo,
 };
+use crate::workflows::export::{ExportFormat, ExportId, WorkflowExport, WorkflowExportResult};
 use crate::workflows::workflow::{Workflow, WorkflowId};
 use utoipa::openapi::security::{HttpAuthScheme, HttpBuilder, SecurityScheme};
 use utoipa::{Modify, OpenApi};
@@ -89,6 +104,8 @@ use utoipa::{Modify, OpenApi};
         handlers::layers::autocomplete_handler,
         handlers::layers::provider_capabilities_handler,
         handlers::layers::add_layer,
+        handlers::layers::update_layer,
+        handlers::layers::update_collection,
         handlers::layers::add_collection,
         handlers::layers::remove_collection,
         handlers::layers::remove_layer_from_collection,
@@ -99,6 +116,9 @@ use utoipa::{Modify, OpenApi};
         handlers::tasks::abort_handler,
         handlers::tasks::list_handler,
         handlers::tasks::status_handler,
+        handlers::tasks::add_recurring_task_handler,
+        handlers::tasks::list_recurring_tasks_handler,
+        handlers::tasks::remove_recurring_task_handler,
         handlers::wcs::wcs_capabilities_handler,
         handlers::wcs::wcs_describe_coverage_handler,
         handlers::wcs::wcs_get_coverage_handler,
@@ -109,6 +129,9 @@ use utoipa::{Modify, OpenApi};
         handlers::wms::wms_legend_graphic_handler,
         handlers::wms::wms_map_handler,
         handlers::workflows::dataset_from_workflow_handler,
+        handlers::workflows::vector_dataset_from_workflow_handler,
+        handlers::workflows::workflow_export_handler,
+        handlers::workflows::download_workflow_export_handler,
         handlers::workflows::get_workflow_metadata_handler,
         handlers::workflows::get_workflow_provenance_handler,
         handlers::workflows::load_workflow_handler,
@@ -132,6 +155,9 @@ use utoipa::{Modify, OpenApi};
         pro::api::handlers::users::assign_role_handler,
         pro::api::handlers::users::revoke_role_handler,
         pro::api::handlers::users::get_role_descriptions,
+        pro::api::handlers::users::create_api_token_handler,
+        pro::api::handlers::users::list_api_tokens_handler,
+        pro::api::handlers::users::revoke_api_token_handler,
         handlers::datasets::delete_dataset_handler,
         handlers::datasets::list_datasets_handler,
         handlers::datasets::list_volumes_handler,
@@ -143,6 +169,10 @@ use utoipa::{Modify, OpenApi};
         handlers::datasets::get_loading_info_handler,
         handlers::datasets::update_dataset_symbology_handler,
         handlers::datasets::update_dataset_provenance_handler,
+        handlers::datasets::replace_loading_info_handler,
+        handlers::datasets::list_dataset_versions_handler,
+        handlers::datasets::restore_dataset_version_handler,
+        handlers::datasets::garbage_collect_dataset_versions_handler,
         handlers::spatial_references::get_spatial_reference_specification_handler,
         handlers::plots::get_plot_handler,
         handlers::projects::list_projects_handler,
@@ -154,10 +184,16 @@ use utoipa::{Modify, OpenApi};
         handlers::projects::load_project_version_handler,
         handlers::upload::list_upload_files_handler,
         handlers::upload::list_upload_file_layers_handler,
+        handlers::upload::create_resumable_upload_handler,
+        handlers::upload::resumable_upload_status_handler,
+        handlers::upload::append_to_resumable_upload_handler,
+        handlers::upload::finish_resumable_upload_handler,
+        handlers::upload::abort_resumable_upload_handler,
         handlers::upload::upload_handler,
         pro::api::handlers::permissions::add_permission_handler,
         pro::api::handlers::permissions::remove_permission_handler,
-        pro::api::handlers::permissions::get_resource_permissions_handler
+        pro::api::handlers::permissions::get_resource_permissions_handler,
+        pro::api::handlers::permissions::get_effective_resource_permissions_handler
     ),
     components(
         responses(
@@ -168,6 +204,7 @@ use utoipa::{Modify, OpenApi};
             IdResponse::<LayerId>,
             IdResponse::<LayerCollectionId>,
             IdResponse::<ProjectId>,
+            IdResponse::<RecurringTaskId>,
             IdResponse::<RoleId>,
             DatasetNameResponse,
             UnauthorizedAdminResponse,
@@ -245,6 +282,13 @@ use utoipa::{Modify, OpenApi};
             VectorColumnInfo,
             RasterDatasetFromWorkflow,
             RasterDatasetFromWorkflowResult,
+            VectorDatasetFromWorkflow,
+            VectorDatasetFromWorkflowResult,
+            VectorDatasetFormat,
+            WorkflowExport,
+            WorkflowExportResult,
+            ExportFormat,
+            ExportId,
             RasterQueryRectangle,
             VectorQueryRectangle,
             PlotQueryRectangle,
@@ -252,10 +296,15 @@ use utoipa::{Modify, OpenApi};
 
             TaskAbortOptions,
             TaskFilter,
+            TaskPriority,
             TaskListOptions,
             TaskStatus,
             TaskStatusWithId,
             TaskResponse,
+            AddRecurringTask,
+            RecurringTaskResponse,
+            RecurringTaskId,
+            RecurringDatasetFromWorkflow,
 
             Layer,
             LayerListing,
@@ -265,6 +314,8 @@ use utoipa::{Modify, OpenApi};
             CollectionItem,
             AddLayer,
             AddLayerCollection,
+            UpdateLayer,
+            UpdateLayerCollection,
             SearchCapabilities,
             ProviderCapabilities,
             SearchTypes,
@@ -319,6 +370,10 @@ use utoipa::{Modify, OpenApi};
             CollectionType,
 
             UploadFilesResponse,
+            ResumableUploadCreation,
+            ResumableFileCreation,
+            ResumableUploadStatus,
+            ResumableFileStatus,
             UploadFileLayersResponse,
 
             CreateDataset,
@@ -370,6 +425,8 @@ use utoipa::{Modify, OpenApi};
             Volume,
             VolumeName,
             DataPath,
+            ReplaceDatasetMetaData,
+            DatasetVersion,
 
             PlotOutputFormat,
             WrappedPlotOutput,
@@ -393,8 +450,14 @@ use utoipa::{Modify, OpenApi};
             ResourceId,
             Permission,
             PermissionListing,
+            EffectivePermissionListing,
             PermissionListOptions,
             AddRole,
+            ApiToken,
+            ApiTokenId,
+            CreateApiToken,
+            CreatedApiToken,
+            ApiTokenScope,
             RoleDescription,
             Role,
 
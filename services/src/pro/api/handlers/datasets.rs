@@ -2,20 +2,22 @@ use crate::{
     api::{
         handlers::datasets::{
             adjust_meta_data_path, auto_create_dataset_handler, create_upload_dataset,
-            delete_dataset_handler, get_dataset_handler, get_loading_info_handler,
-            list_datasets_handler, list_volumes_handler, suggest_meta_data_handler,
-            update_dataset_handler, update_dataset_provenance_handler,
+            delete_dataset_handler, garbage_collect_dataset_versions_handler, get_dataset_handler,
+            get_loading_info_handler, list_dataset_versions_handler, list_datasets_handler,
+            list_volumes_handler, replace_loading_info_handler, restore_dataset_version_handler,
+            suggest_meta_data_handler, update_dataset_handler, update_dataset_provenance_handler,
             update_dataset_symbology_handler,
         },
         model::{
             responses::datasets::{errors::*, DatasetNameResponse},
-            services::{CreateDataset, DataPath, DatasetDefinition},
+            services::{CreateDataset, DataPath, DatasetDefinition, ReplaceDatasetMetaData},
         },
     },
     contexts::{ApplicationContext, SessionContext},
     datasets::{
         storage::DatasetStore,
         upload::{Volume, VolumeName},
+        DatasetName,
     },
     error::Result,
     pro::{
@@ -24,9 +26,9 @@ use crate::{
     },
     util::config::{get_config_element, Data},
 };
-use actix_web::{web, FromRequest};
+use actix_web::{web, FromRequest, HttpResponseBuilder};
 use geoengine_datatypes::error::BoxedResultExt;
-use snafu::ResultExt;
+use snafu::{ensure, ResultExt};
 
 pub(crate) fn init_dataset_routes<C>(cfg: &mut web::ServiceConfig)
 where
@@ -41,7 +43,17 @@ where
             .service(web::resource("/volumes").route(web::get().to(list_volumes_handler::<C>)))
             .service(
                 web::resource("/{dataset}/loadingInfo")
-                    .route(web::get().to(get_loading_info_handler::<C>)),
+                    .route(web::get().to(get_loading_info_handler::<C>))
+                    .route(web::put().to(replace_pro_loading_info_handler::<C>)),
+            )
+            .service(
+                web::resource("/{dataset}/versions")
+                    .route(web::get().to(list_dataset_versions_handler::<C>))
+                    .route(web::delete().to(garbage_collect_dataset_versions_handler::<C>)),
+            )
+            .service(
+                web::resource("/{dataset}/versions/{version}/restore")
+                    .route(web::post().to(restore_dataset_version_handler::<C>)),
             )
             .service(
                 web::resource("/{dataset}/symbology")
@@ -107,6 +119,8 @@ async fn create_system_dataset<C: ProApplicationContext>(
 where
     <<C as ApplicationContext>::SessionContext as SessionContext>::GeoEngineDB: ProGeoEngineDb,
 {
+    ensure!(session.is_admin(), OnlyAdminsCanCreateDatasetFromVolume);
+
     let volumes = get_config_element::<Data>()
         .context(CannotAccessConfig)?
         .volumes;
@@ -145,10 +159,28 @@ where
     Ok(web::Json(dataset.name.into()))
 }
 
+/// Replaces the loading information of a dataset.
+/// Only admins may point a dataset to files on a volume.
+async fn replace_pro_loading_info_handler<C: ProApplicationContext>(
+    session: C::Session,
+    app_ctx: web::Data<C>,
+    dataset: web::Path<DatasetName>,
+    replacement: web::Json<ReplaceDatasetMetaData>,
+) -> Result<HttpResponseBuilder>
+where
+    <<C as ApplicationContext>::SessionContext as SessionContext>::GeoEngineDB: ProGeoEngineDb,
+{
+    if matches!(replacement.data_path, DataPath::Volume(_)) {
+        ensure!(session.is_admin(), crate::error::AccessDenied);
+    }
+
+    replace_loading_info_handler::<C>(session, app_ctx, dataset, replacement).await
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::api::model::responses::IdResponse;
+    use crate::api::model::responses::{ErrorResponse, IdResponse};
     use crate::datasets::DatasetName;
     use crate::pro::contexts::ProPostgresContext;
     use crate::pro::ge_context;
@@ -157,6 +189,7 @@ mod tests {
         contexts::{Session, SessionContext, SessionId},
         datasets::{
             listing::DatasetProvider,
+            storage::DatasetVersion,
             upload::{UploadId, UploadRootPath, VolumeName},
         },
         pro::{
@@ -416,6 +449,22 @@ mod tests {
             },
         };
 
+        // normal users may not access the volumes
+        let req = actix_web::test::TestRequest::post()
+            .uri("/dataset")
+            .append_header((header::CONTENT_LENGTH, 0))
+            .append_header((header::AUTHORIZATION, Bearer::new(session.id().to_string())))
+            .append_header((header::CONTENT_TYPE, "application/json"))
+            .set_json(create.clone());
+        let res = send_pro_test_request(req, app_ctx.clone()).await;
+        ErrorResponse::assert(
+            res,
+            400,
+            "OnlyAdminsCanCreateDatasetFromVolume",
+            "OnlyAdminsCanCreateDatasetFromVolume",
+        )
+        .await;
+
         // create via admin session
         let admin_session = admin_login(&app_ctx).await;
         let req = actix_web::test::TestRequest::post()
@@ -542,4 +591,85 @@ mod tests {
 
         Ok(())
     }
+
+    #[ge_context::test]
+    async fn it_replaces_loading_info_and_restores_versions(
+        app_ctx: ProPostgresContext<NoTls>,
+    ) -> Result<()> {
+        let mut test_data = TestDataUploads::default(); // remember created folder and remove them on drop
+
+        let session = app_ctx.create_anonymous_session().await.unwrap();
+        let session_id = session.id();
+
+        let upload_id = upload_ne_10m_ports_files(app_ctx.clone(), session_id).await?;
+        test_data.uploads.push(upload_id);
+
+        let dataset_name =
+            construct_dataset_from_upload(app_ctx.clone(), upload_id, session_id).await;
+
+        let req = actix_web::test::TestRequest::get()
+            .uri(&format!("/dataset/{dataset_name}/loadingInfo"))
+            .append_header((header::AUTHORIZATION, Bearer::new(session_id.to_string())));
+        let res = send_pro_test_request(req, app_ctx.clone()).await;
+        assert_eq!(res.status(), 200, "response: {res:?}");
+
+        let meta_data: serde_json::Value = actix_web::test::read_body_json(res).await;
+
+        let req = actix_web::test::TestRequest::put()
+            .uri(&format!("/dataset/{dataset_name}/loadingInfo"))
+            .append_header((header::AUTHORIZATION, Bearer::new(session_id.to_string())))
+            .set_json(json!({
+                "dataPath": {
+                    "upload": upload_id
+                },
+                "metaData": meta_data
+            }));
+        let res = send_pro_test_request(req, app_ctx.clone()).await;
+        assert_eq!(res.status(), 200, "response: {res:?}");
+
+        // only admins may point datasets to volumes
+        let req = actix_web::test::TestRequest::put()
+            .uri(&format!("/dataset/{dataset_name}/loadingInfo"))
+            .append_header((header::AUTHORIZATION, Bearer::new(session_id.to_string())))
+            .set_json(json!({
+                "dataPath": {
+                    "volume": "test_data"
+                },
+                "metaData": meta_data
+            }));
+        let res = send_pro_test_request(req, app_ctx.clone()).await;
+        ErrorResponse::assert(res, 400, "AccessDenied", "AccessDenied").await;
+
+        let req = actix_web::test::TestRequest::get()
+            .uri(&format!("/dataset/{dataset_name}/versions"))
+            .append_header((header::AUTHORIZATION, Bearer::new(session_id.to_string())));
+        let res = send_pro_test_request(req, app_ctx.clone()).await;
+        assert_eq!(res.status(), 200, "response: {res:?}");
+
+        let versions: Vec<DatasetVersion> = actix_web::test::read_body_json(res).await;
+        assert_eq!(
+            versions.iter().map(|v| v.version).collect::<Vec<_>>(),
+            vec![2, 1]
+        );
+
+        let req = actix_web::test::TestRequest::post()
+            .uri(&format!("/dataset/{dataset_name}/versions/1/restore"))
+            .append_header((header::AUTHORIZATION, Bearer::new(session_id.to_string())));
+        let res = send_pro_test_request(req, app_ctx.clone()).await;
+        assert_eq!(res.status(), 200, "response: {res:?}");
+
+        let restored: DatasetVersion = actix_web::test::read_body_json(res).await;
+        assert_eq!(restored.version, 3);
+
+        let req = actix_web::test::TestRequest::delete()
+            .uri(&format!("/dataset/{dataset_name}/versions?keep=1"))
+            .append_header((header::AUTHORIZATION, Bearer::new(session_id.to_string())));
+        let res = send_pro_test_request(req, app_ctx.clone()).await;
+        assert_eq!(res.status(), 200, "response: {res:?}");
+
+        let deleted: u64 = actix_web::test::read_body_json(res).await;
+        assert_eq!(deleted, 2);
+
+        Ok(())
+    }
 }
@@ -3,7 +3,7 @@ use crate::contexts::{ApplicationContext, SessionContext};
 use crate::error::Result;
 use crate::layers::listing::LayerCollectionId;
 use crate::pro::contexts::{ProApplicationContext, ProGeoEngineDb};
-use crate::pro::permissions::{Permission, PermissionListing};
+use crate::pro::permissions::{EffectivePermissionListing, Permission, PermissionListing};
 use crate::pro::permissions::{PermissionDb, ResourceId, RoleId};
 use crate::projects::ProjectId;
 use actix_web::{web, FromRequest, HttpResponse};
@@ -27,6 +27,10 @@ where
             .service(
                 web::resource("/resources/{resource_type}/{resource_id}")
                     .route(web::get().to(get_resource_permissions_handler::<C>)),
+            )
+            .service(
+                web::resource("/resources/{resource_type}/{resource_id}/effective")
+                    .route(web::get().to(get_effective_resource_permissions_handler::<C>)),
             ),
     );
 }
@@ -107,6 +111,45 @@ where
     Ok(web::Json(permissions))
 }
 
+/// Lists the effective permissions for a given resource.
+/// This includes the permissions that are inherited from the layer collections the resource is part of.
+#[utoipa::path(
+    tag = "Permissions",
+    get,
+    path = "/permissions/resources/{resource_type}/{resource_id}/effective",
+    responses(
+        (status = 200, description = "List of effective permissions", body = Vec<EffectivePermissionListing>),
+    ),
+    params(
+        ("resource_type" = String, description = "Resource Type"),
+        ("resource_id" = String, description = "Resource Id"),
+        PermissionListOptions,
+    ),
+    security(
+        ("session_token" = [])
+    )
+)]
+async fn get_effective_resource_permissions_handler<C: ProApplicationContext>(
+    session: C::Session,
+    app_ctx: web::Data<C>,
+    resource_id: web::Path<(String, String)>,
+    options: web::Query<PermissionListOptions>,
+) -> Result<web::Json<Vec<EffectivePermissionListing>>>
+where
+    <<C as ApplicationContext>::SessionContext as SessionContext>::GeoEngineDB: ProGeoEngineDb,
+{
+    let resource_id = ResourceId::try_from(resource_id.into_inner())?;
+    let options = options.into_inner();
+
+    let db = app_ctx.session_context(session).db();
+    let permissions = db
+        .list_effective_permissions(resource_id, options.offset, options.limit)
+        .await
+        .boxed_context(crate::error::PermissionDb)?;
+
+    Ok(web::Json(permissions))
+}
+
 /// Adds a new permission.
 #[utoipa::path(
     tag = "Permissions",
@@ -12,6 +12,7 @@ use crate::pro::users::UserDb;
 use crate::pro::users::UserId;
 use crate::pro::users::UserRegistration;
 use crate::pro::users::UserSession;
+use crate::pro::users::{ApiToken, ApiTokenId, CreateApiToken, CreatedApiToken};
 use crate::pro::users::{AuthCodeRequestURL, AuthCodeResponse, RoleDb, UserCredentials};
 use crate::projects::ProjectId;
 use crate::projects::STRectangle;
@@ -64,6 +65,15 @@ where
         .service(
             web::resource("/user/roles/descriptions")
                 .route(web::get().to(get_role_descriptions::<C>)),
+        )
+        .service(
+            web::resource("/user/apiTokens")
+                .route(web::get().to(list_api_tokens_handler::<C>))
+                .route(web::post().to(create_api_token_handler::<C>)),
+        )
+        .service(
+            web::resource("/user/apiTokens/{api_token}")
+                .route(web::delete().to(revoke_api_token_handler::<C>)),
         );
 }
 
@@ -750,6 +760,110 @@ where
     Ok(web::Json(res))
 }
 
+/// Creates an API token for the current user.
+/// The token is only returned once and can be used as a Bearer token instead of a session id.
+/// API tokens cannot be used to manage API tokens.
+#[utoipa::path(
+    tag = "User",
+    post,
+    path = "/user/apiTokens",
+    request_body = CreateApiToken,
+    responses(
+        (status = 200, description = "The created API token", body = CreatedApiToken,
+            example = json!({
+                "apiToken": {
+                    "id": "3b4a5f5e-1b2d-4b2a-9b5e-2a0c6e6a7d53",
+                    "name": "CI pipeline",
+                    "created": "2024-01-01T00:00:00.000Z",
+                    "validUntil": "2030-01-01T00:00:00.000Z",
+                    "readOnly": true,
+                    "resources": []
+                },
+                "token": "ge_6f1c4d2e8a0b4c3d9e7f5a1b2c3d4e5f6a7b8c9d0e1f2a3b4c5d6e7f8a9b0c1d"
+            })
+        )
+    ),
+    security(
+        ("session_token" = [])
+    )
+)]
+pub(crate) async fn create_api_token_handler<C: ProApplicationContext>(
+    app_ctx: web::Data<C>,
+    session: C::Session,
+    api_token: ValidatedJson<CreateApiToken>,
+) -> Result<web::Json<CreatedApiToken>>
+where
+    <<C as ApplicationContext>::SessionContext as SessionContext>::GeoEngineDB: ProGeoEngineDb,
+{
+    let api_token = app_ctx
+        .session_context(session)
+        .db()
+        .create_api_token(api_token.into_inner())
+        .await?;
+
+    Ok(web::Json(api_token))
+}
+
+/// Lists the API tokens of the current user.
+#[utoipa::path(
+    tag = "User",
+    get,
+    path = "/user/apiTokens",
+    responses(
+        (status = 200, description = "The API tokens of the current user", body = Vec<ApiToken>)
+    ),
+    security(
+        ("session_token" = [])
+    )
+)]
+pub(crate) async fn list_api_tokens_handler<C: ProApplicationContext>(
+    app_ctx: web::Data<C>,
+    session: C::Session,
+) -> Result<web::Json<Vec<ApiToken>>>
+where
+    <<C as ApplicationContext>::SessionContext as SessionContext>::GeoEngineDB: ProGeoEngineDb,
+{
+    let api_tokens = app_ctx
+        .session_context(session)
+        .db()
+        .list_api_tokens()
+        .await?;
+
+    Ok(web::Json(api_tokens))
+}
+
+/// Revokes an API token of the current user.
+#[utoipa::path(
+    tag = "User",
+    delete,
+    path = "/user/apiTokens/{api_token}",
+    responses(
+        (status = 200, description = "The API token was revoked")
+    ),
+    params(
+        ("api_token" = ApiTokenId, description = "API token id")
+    ),
+    security(
+        ("session_token" = [])
+    )
+)]
+pub(crate) async fn revoke_api_token_handler<C: ProApplicationContext>(
+    app_ctx: web::Data<C>,
+    session: C::Session,
+    api_token: web::Path<ApiTokenId>,
+) -> Result<HttpResponse>
+where
+    <<C as ApplicationContext>::SessionContext as SessionContext>::GeoEngineDB: ProGeoEngineDb,
+{
+    app_ctx
+        .session_context(session)
+        .db()
+        .revoke_api_token(api_token.into_inner())
+        .await?;
+
+    Ok(HttpResponse::Ok().finish())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1147,6 +1261,86 @@ mod tests {
         .await;
     }
 
+    #[ge_context::test]
+    async fn it_authenticates_with_api_tokens(app_ctx: ProPostgresContext<NoTls>) {
+        let session = create_session_helper(&app_ctx).await;
+
+        let req = test::TestRequest::post()
+            .uri("/user/apiTokens")
+            .append_header((header::AUTHORIZATION, Bearer::new(session.id().to_string())))
+            .set_json(json!({
+                "name": "CI pipeline",
+                "validUntil": "2100-01-01T00:00:00Z",
+                "readOnly": true
+            }));
+        let res = send_pro_test_request(req, app_ctx.clone()).await;
+
+        assert_eq!(res.status(), 200);
+
+        let created: CreatedApiToken = test::read_body_json(res).await;
+        assert_eq!(created.api_token.name, "CI pipeline");
+        assert!(created.api_token.read_only);
+
+        let req = test::TestRequest::get()
+            .uri("/session")
+            .append_header((header::AUTHORIZATION, Bearer::new(created.token.clone())));
+        let res = send_pro_test_request(req, app_ctx.clone()).await;
+
+        assert_eq!(res.status(), 200);
+
+        let token_session: UserSession = test::read_body_json(res).await;
+        assert_eq!(token_session.user.id, session.user.id);
+        assert!(token_session.is_read_only());
+
+        // API tokens cannot be used to create further API tokens
+        let req = test::TestRequest::post()
+            .uri("/user/apiTokens")
+            .append_header((header::AUTHORIZATION, Bearer::new(created.token.clone())))
+            .set_json(json!({
+                "name": "escalation",
+                "validUntil": "2100-01-01T00:00:00Z"
+            }));
+        let res = send_pro_test_request(req, app_ctx.clone()).await;
+
+        ErrorResponse::assert(
+            res,
+            400,
+            "ApiTokenCannotManageApiTokens",
+            "API tokens cannot be used to manage API tokens.",
+        )
+        .await;
+
+        let req = test::TestRequest::get()
+            .uri("/user/apiTokens")
+            .append_header((header::AUTHORIZATION, Bearer::new(session.id().to_string())));
+        let res = send_pro_test_request(req, app_ctx.clone()).await;
+
+        assert_eq!(res.status(), 200);
+
+        let api_tokens: Vec<ApiToken> = test::read_body_json(res).await;
+        assert_eq!(api_tokens, vec![created.api_token.clone()]);
+
+        let req = test::TestRequest::delete()
+            .uri(&format!("/user/apiTokens/{}", created.api_token.id))
+            .append_header((header::AUTHORIZATION, Bearer::new(session.id().to_string())));
+        let res = send_pro_test_request(req, app_ctx.clone()).await;
+
+        assert_eq!(res.status(), 200);
+
+        let req = test::TestRequest::get()
+            .uri("/session")
+            .append_header((header::AUTHORIZATION, Bearer::new(created.token)));
+        let res = send_pro_test_request(req, app_ctx).await;
+
+        ErrorResponse::assert(
+            res,
+            401,
+            "Unauthorized",
+            "Authorization error: The API token is invalid, expired or revoked.",
+        )
+        .await;
+    }
+
     #[ge_context::test]
     async fn session_view_project(app_ctx: ProPostgresContext<NoTls>) {
         let (session, project) = create_project_helper(&app_ctx).await;
@@ -114,7 +114,7 @@ CREATE TABLE user_uploads (
     PRIMARY KEY (user_id, upload_id)
 );
 
-CREATE TYPE "Permission" AS ENUM ('Read', 'Owner');
+CREATE TYPE "Permission" AS ENUM ('Read', 'Write', 'Owner');
 
 -- TODO: uploads, providers permissions
 
@@ -190,29 +190,161 @@ INNER JOIN permissions AS p ON (
     r.role_id = p.role_id AND p.project_id IS NOT NULL
 );
 
+-- Permissions on layer collections are inherited by their sub-collections and
+-- layers. The `Read` permissions on the root and the unsorted collection only
+-- allow browsing them. They are not inherited, s.t. private contents that are
+-- added to them stay private.
+CREATE FUNCTION is_inheritable_permission(
+    "Permission", uuid
+) RETURNS boolean
+LANGUAGE sql IMMUTABLE
+AS $$
+    SELECT $1 <> 'Read' OR $2 NOT IN (
+        '05102bb3-a855-4a37-8a8a-30026a91fef1',
+        'ffb2dd9e-f5ad-427c-b7f1-c9a0c7a0ae3f'
+    );
+$$;
+
+-- The direct and inherited permissions of the layer collection `$1`.
+-- Only the ancestors of the collection are visited.
+CREATE FUNCTION effective_layer_collection_permissions(
+    uuid
+) RETURNS TABLE (role_id uuid, permission "Permission", inherited_from uuid)
+LANGUAGE sql STABLE
+AS $$
+    WITH RECURSIVE ancestors (id) AS (
+        SELECT $1
+        UNION
+        SELECT c.parent
+        FROM ancestors AS a
+        INNER JOIN collection_children AS c ON (a.id = c.child)
+    )
+    SELECT
+        p.role_id,
+        p.permission,
+        nullif(p.layer_collection_id, $1) AS inherited_from
+    FROM ancestors AS a
+    INNER JOIN permissions AS p ON (a.id = p.layer_collection_id)
+    WHERE
+        p.layer_collection_id = $1
+        OR is_inheritable_permission(p.permission, p.layer_collection_id);
+$$;
+
+-- The direct permissions of the layer `$1` and those inherited from the
+-- collections that contain it.
+CREATE FUNCTION effective_layer_permissions(
+    uuid
+) RETURNS TABLE (role_id uuid, permission "Permission", inherited_from uuid)
+LANGUAGE sql STABLE
+AS $$
+    SELECT
+        p.role_id,
+        p.permission,
+        NULL::uuid AS inherited_from
+    FROM permissions AS p
+    WHERE p.layer_id = $1
+    UNION
+    SELECT
+        p.role_id,
+        p.permission,
+        coalesce(p.inherited_from, c.collection) AS inherited_from
+    FROM collection_layers AS c
+    CROSS JOIN LATERAL effective_layer_collection_permissions(c.collection) AS p
+    WHERE
+        c.layer = $1
+        AND is_inheritable_permission(
+            p.permission, coalesce(p.inherited_from, c.collection)
+        );
+$$;
+
 CREATE VIEW user_permitted_layer_collections
 AS
 SELECT
     r.user_id,
-    p.layer_collection_id,
+    c.id AS layer_collection_id,
     p.permission
-FROM user_roles AS r
-INNER JOIN permissions AS p ON (
-    r.role_id = p.role_id AND p.layer_collection_id IS NOT NULL
-);
+FROM layer_collections AS c
+CROSS JOIN LATERAL effective_layer_collection_permissions(c.id) AS p
+INNER JOIN user_roles AS r ON (p.role_id = r.role_id);
 
 CREATE VIEW user_permitted_layers
 AS
 SELECT
     r.user_id,
-    p.layer_id,
+    l.id AS layer_id,
     p.permission
-FROM user_roles AS r
-INNER JOIN permissions AS p ON (
-    r.role_id = p.role_id AND p.layer_id IS NOT NULL
-);
+FROM layers AS l
+CROSS JOIN LATERAL effective_layer_permissions(l.id) AS p
+INNER JOIN user_roles AS r ON (p.role_id = r.role_id);
 
 CREATE TABLE ml_models (
     id uuid PRIMARY KEY,
     content text NOT NULL
 );
+
+CREATE TABLE api_tokens (
+    id uuid PRIMARY KEY,
+    user_id uuid REFERENCES users (id) ON DELETE CASCADE NOT NULL,
+    name text NOT NULL,
+    -- only the SHA-256 hash of a token is stored
+    token_hash text UNIQUE NOT NULL,
+    read_only boolean NOT NULL,
+    created timestamp with time zone NOT NULL,
+    valid_until timestamp with time zone NOT NULL,
+    UNIQUE (user_id, name)
+);
+
+-- the resources an API token is restricted to
+-- there are no foreign keys, s.t. deleting a resource does not lift the restriction
+CREATE TABLE api_token_resources (
+    api_token_id uuid REFERENCES api_tokens (
+        id
+    ) ON DELETE CASCADE NOT NULL,
+    dataset_id uuid,
+    layer_id uuid,
+    layer_collection_id uuid,
+    project_id uuid,
+    model_id uuid,
+    CHECK (
+        (
+            (dataset_id IS NOT NULL)::integer
+            + (layer_id IS NOT NULL)::integer
+            + (layer_collection_id IS NOT NULL)::integer
+            + (project_id IS NOT NULL)::integer
+            + (model_id IS NOT NULL)::integer
+        ) = 1
+    )
+);
+
+-- Whether the layer collection `$1` is one of the layer collections `$2` or
+-- (transitively) contained in one of them
+CREATE FUNCTION is_layer_collection_in_scope(
+    uuid, uuid[]
+) RETURNS boolean
+LANGUAGE sql STABLE
+AS $$
+    WITH RECURSIVE ancestors (id) AS (
+        SELECT $1
+        UNION
+        SELECT c.parent
+        FROM ancestors AS a
+        INNER JOIN collection_children AS c ON (a.id = c.child)
+    )
+    SELECT EXISTS (SELECT 1 FROM ancestors WHERE id = ANY($2));
+$$;
+
+-- Whether the layer `$1` is one of the layers `$2` or (transitively)
+-- contained in one of the layer collections `$3`
+CREATE FUNCTION is_layer_in_scope(
+    uuid, uuid[], uuid[]
+) RETURNS boolean
+LANGUAGE sql STABLE
+AS $$
+    SELECT $1 = ANY($2) OR EXISTS (
+        SELECT 1
+        FROM collection_layers AS c
+        WHERE
+            c.layer = $1
+            AND is_layer_collection_in_scope(c.collection, $3)
+    );
+$$;
@@ -201,6 +201,7 @@ mod tests {
                 project: None,
                 view: None,
                 roles: vec![RoleId::from_str("b589a590-9c0c-4b55-9aa2-d178a5f42a78").unwrap()],
+                api_token: None,
             },
         );
 
@@ -0,0 +1,108 @@
+use async_trait::async_trait;
+use tokio_postgres::Transaction;
+
+use super::database_migration::{ProMigration, ProMigrationImpl};
+use crate::{contexts::Migration0011PermissionInheritance, error::Result};
+
+#[async_trait]
+impl ProMigration for ProMigrationImpl<Migration0011PermissionInheritance> {
+    async fn pro_migrate(&self, tx: &Transaction<'_>) -> Result<()> {
+        // the new enum value cannot be used before the transaction is committed,
+        // so the functions only refer to `Read` permissions
+        tx.batch_execute(
+            r#"
+            ALTER TYPE "Permission" ADD VALUE 'Write' BEFORE 'Owner';
+
+            -- Permissions on layer collections are inherited by their sub-collections and
+            -- layers. The `Read` permissions on the root and the unsorted collection only
+            -- allow browsing them. They are not inherited, s.t. private contents that are
+            -- added to them stay private.
+            CREATE FUNCTION is_inheritable_permission(
+                "Permission", uuid
+            ) RETURNS boolean
+            LANGUAGE sql IMMUTABLE
+            AS $$
+                SELECT $1 <> 'Read' OR $2 NOT IN (
+                    '05102bb3-a855-4a37-8a8a-30026a91fef1',
+                    'ffb2dd9e-f5ad-427c-b7f1-c9a0c7a0ae3f'
+                );
+            $$;
+
+            -- The direct and inherited permissions of the layer collection `$1`.
+            -- Only the ancestors of the collection are visited.
+            CREATE FUNCTION effective_layer_collection_permissions(
+                uuid
+            ) RETURNS TABLE (role_id uuid, permission "Permission", inherited_from uuid)
+            LANGUAGE sql STABLE
+            AS $$
+                WITH RECURSIVE ancestors (id) AS (
+                    SELECT $1
+                    UNION
+                    SELECT c.parent
+                    FROM ancestors AS a
+                    INNER JOIN collection_children AS c ON (a.id = c.child)
+                )
+                SELECT
+                    p.role_id,
+                    p.permission,
+                    nullif(p.layer_collection_id, $1) AS inherited_from
+                FROM ancestors AS a
+                INNER JOIN permissions AS p ON (a.id = p.layer_collection_id)
+                WHERE
+                    p.layer_collection_id = $1
+                    OR is_inheritable_permission(p.permission, p.layer_collection_id);
+            $$;
+
+            -- The direct permissions of the layer `$1` and those inherited from the
+            -- collections that contain it.
+            CREATE FUNCTION effective_layer_permissions(
+                uuid
+            ) RETURNS TABLE (role_id uuid, permission "Permission", inherited_from uuid)
+            LANGUAGE sql STABLE
+            AS $$
+                SELECT
+                    p.role_id,
+                    p.permission,
+                    NULL::uuid AS inherited_from
+                FROM permissions AS p
+                WHERE p.layer_id = $1
+                UNION
+                SELECT
+                    p.role_id,
+                    p.permission,
+                    coalesce(p.inherited_from, c.collection) AS inherited_from
+                FROM collection_layers AS c
+                CROSS JOIN LATERAL effective_layer_collection_permissions(c.collection) AS p
+                WHERE
+                    c.layer = $1
+                    AND is_inheritable_permission(
+                        p.permission, coalesce(p.inherited_from, c.collection)
+                    );
+            $$;
+
+            CREATE OR REPLACE VIEW user_permitted_layer_collections
+            AS
+            SELECT
+                r.user_id,
+                c.id AS layer_collection_id,
+                p.permission
+            FROM layer_collections AS c
+            CROSS JOIN LATERAL effective_layer_collection_permissions(c.id) AS p
+            INNER JOIN user_roles AS r ON (p.role_id = r.role_id);
+
+            CREATE OR REPLACE VIEW user_permitted_layers
+            AS
+            SELECT
+                r.user_id,
+                l.id AS layer_id,
+                p.permission
+            FROM layers AS l
+            CROSS JOIN LATERAL effective_layer_permissions(l.id) AS p
+            INNER JOIN user_roles AS r ON (p.role_id = r.role_id);
+            "#,
+        )
+        .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,81 @@
+use async_trait::async_trait;
+use tokio_postgres::Transaction;
+
+use super::database_migration::{ProMigration, ProMigrationImpl};
+use crate::{contexts::Migration0012ApiTokens, error::Result};
+
+#[async_trait]
+impl ProMigration for ProMigrationImpl<Migration0012ApiTokens> {
+    async fn pro_migrate(&self, tx: &Transaction<'_>) -> Result<()> {
+        tx.batch_execute(
+            r#"
+            CREATE TABLE api_tokens (
+                id uuid PRIMARY KEY,
+                user_id uuid REFERENCES users (id) ON DELETE CASCADE NOT NULL,
+                name text NOT NULL,
+                token_hash text UNIQUE NOT NULL,
+                read_only boolean NOT NULL,
+                created timestamp with time zone NOT NULL,
+                valid_until timestamp with time zone NOT NULL,
+                UNIQUE (user_id, name)
+            );
+
+            CREATE TABLE api_token_resources (
+                api_token_id uuid REFERENCES api_tokens (
+                    id
+                ) ON DELETE CASCADE NOT NULL,
+                dataset_id uuid,
+                layer_id uuid,
+                layer_collection_id uuid,
+                project_id uuid,
+                model_id uuid,
+                CHECK (
+                    (
+                        (dataset_id IS NOT NULL)::integer
+                        + (layer_id IS NOT NULL)::integer
+                        + (layer_collection_id IS NOT NULL)::integer
+                        + (project_id IS NOT NULL)::integer
+                        + (model_id IS NOT NULL)::integer
+                    ) = 1
+                )
+            );
+
+            -- Whether the layer collection `$1` is one of the layer collections `$2` or
+            -- (transitively) contained in one of them
+            CREATE FUNCTION is_layer_collection_in_scope(
+                uuid, uuid[]
+            ) RETURNS boolean
+            LANGUAGE sql STABLE
+            AS $$
+                WITH RECURSIVE ancestors (id) AS (
+                    SELECT $1
+                    UNION
+                    SELECT c.parent
+                    FROM ancestors AS a
+                    INNER JOIN collection_children AS c ON (a.id = c.child)
+                )
+                SELECT EXISTS (SELECT 1 FROM ancestors WHERE id = ANY($2));
+            $$;
+
+            -- Whether the layer `$1` is one of the layers `$2` or (transitively)
+            -- contained in one of the layer collections `$3`
+            CREATE FUNCTION is_layer_in_scope(
+                uuid, uuid[], uuid[]
+            ) RETURNS boolean
+            LANGUAGE sql STABLE
+            AS $$
+                SELECT $1 = ANY($2) OR EXISTS (
+                    SELECT 1
+                    FROM collection_layers AS c
+                    WHERE
+                        c.layer = $1
+                        AND is_layer_collection_in_scope(c.collection, $3)
+                );
+            $$;
+            "#,
+        )
+        .await?;
+
+        Ok(())
+    }
+}
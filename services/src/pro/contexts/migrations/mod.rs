@@ -3,7 +3,8 @@ use crate::contexts::{
     Migration0000Initial, Migration0001RasterStacks, Migration0002DatasetListingProvider,
     Migration0003GbifConfig, Migration0004DatasetListingProviderPrio,
     Migration0005GbifColumnSelection, Migration0006EbvProvider, Migration0007OwnerRole,
-    Migration0008BandNames,
+    Migration0008BandNames, Migration0009Tasks, Migration0010DatasetVersions,
+    Migration0011PermissionInheritance, Migration0012ApiTokens, Migration0013RecurringTasks,
 };
 use crate::pro::contexts::migrations::database_migration::NoProMigrationImpl;
 
@@ -14,6 +15,8 @@ mod database_migration;
 mod migration_0000_initial;
 mod migration_0004_dataset_listing_provider_prio;
 mod migration_0007_owner_role;
+mod migration_0011_permission_inheritance;
+mod migration_0012_api_tokens;
 
 /// Get all regular and pro migrations. This function wraps all regular migrations into a pro migration.
 pub fn pro_migrations() -> Vec<Box<dyn Migration>>
@@ -33,6 +36,11 @@ where
         Box::new(NoProMigrationImpl::from(Migration0006EbvProvider)),
         Box::new(NoProMigrationImpl::from(Migration0007OwnerRole)),
         Box::new(NoProMigrationImpl::from(Migration0008BandNames)),
+        Box::new(NoProMigrationImpl::from(Migration0009Tasks)),
+        Box::new(NoProMigrationImpl::from(Migration0010DatasetVersions)),
+        Box::new(ProMigrationImpl::from(Migration0011PermissionInheritance)),
+        Box::new(ProMigrationImpl::from(Migration0012ApiTokens)),
+        Box::new(NoProMigrationImpl::from(Migration0013RecurringTasks)),
     ]
 }
 
@@ -23,7 +23,8 @@ pub use postgres::ProPostgresContext;
 use rayon::ThreadPool;
 
 use crate::contexts::{ApplicationContext, GeoEngineDb};
-use crate::datasets::storage::DatasetDb;
+use crate::datasets::storage::{DatasetDb, DatasetVersionSelector};
+use crate::datasets::DatasetName;
 use crate::error::Result;
 use crate::pro::machine_learning::ml_model::MlModelDb;
 
@@ -177,9 +178,21 @@ where
             return Ok(data_id.into());
         }
 
+        let (name, version) = data.name_and_version();
+
+        let version = version
+            .map(DatasetVersionSelector::from_str)
+            .transpose()
+            .map_err(
+                |source| geoengine_operators::error::Error::CannotResolveDatasetName {
+                    name: data.clone(),
+                    source: Box::new(source),
+                },
+            )?;
+
         let dataset_id = self
             .db
-            .resolve_dataset_name_to_id(&data.into())
+            .resolve_dataset_name_to_id(&DatasetName::new(data.namespace.clone(), name))
             .await
             .map_err(
                 |source| geoengine_operators::error::Error::CannotResolveDatasetName {
@@ -192,6 +205,21 @@ where
         let dataset_id = dataset_id
             .ok_or(geoengine_operators::error::Error::UnknownDatasetName { name: data.clone() })?;
 
+        let Some(version) = version else {
+            return Ok(dataset_id.into());
+        };
+
+        let dataset_id = self
+            .db
+            .resolve_dataset_version(&dataset_id, version)
+            .await
+            .map_err(
+                |source| geoengine_operators::error::Error::CannotResolveDatasetName {
+                    name: data.clone(),
+                    source: Box::new(source),
+                },
+            )?;
+
         Ok(dataset_id.into())
     }
 
@@ -7,7 +7,7 @@ use crate::contexts::{
 };
 use crate::contexts::{GeoEngineDb, SessionContext};
 use crate::datasets::upload::{Volume, Volumes};
-use crate::datasets::DatasetName;
+use crate::datasets::{recurring_dataset_from_workflow_factory, DatasetName};
 use crate::error::{self, Error, Result};
 use crate::pro::api::cli::add_datasets_from_directory;
 use crate::pro::layers::add_from_directory::{
@@ -87,6 +87,8 @@ where
 
         Self::create_pro_database(pool.get().await?).await?;
 
+        let task_manager = PostgresContext::create_task_manager(&pool).await?;
+
         let db = ProPostgresDb::new(pool.clone(), UserSession::admin_session());
         let quota = initialize_quota_tracking(
             quota_config.mode,
@@ -96,7 +98,7 @@ where
         );
 
         Ok(ProPostgresContext {
-            task_manager: Default::default(),
+            task_manager: Arc::new(ProTaskManagerBackend::new(task_manager)),
             thread_pool: create_rayon_thread_pool(0),
             exe_ctx_tiling_spec,
             query_ctx_chunk_size,
@@ -122,6 +124,8 @@ where
 
         Self::create_pro_database(pool.get().await?).await?;
 
+        let task_manager = PostgresContext::create_task_manager(&pool).await?;
+
         let db = ProPostgresDb::new(pool.clone(), UserSession::admin_session());
         let quota = initialize_quota_tracking(
             quota_config.mode,
@@ -131,7 +135,7 @@ where
         );
 
         Ok(ProPostgresContext {
-            task_manager: Default::default(),
+            task_manager: Arc::new(ProTaskManagerBackend::new(task_manager)),
             thread_pool: create_rayon_thread_pool(0),
             exe_ctx_tiling_spec: TestDefault::test_default(),
             query_ctx_chunk_size: TestDefault::test_default(),
@@ -146,6 +150,16 @@ where
         })
     }
 
+    /// Schedules the recurring tasks that were persisted before the last shutdown again.
+    pub async fn restore_recurring_tasks(&self) -> Result<()> {
+        self.task_manager
+            .simple_task_manager()
+            .restore_recurring_tasks(|recurring_task| {
+                recurring_dataset_from_workflow_factory(self.clone(), &recurring_task.definition)
+            })
+            .await
+    }
+
     // TODO: check if the datasets exist already and don't output warnings when skipping them
     #[allow(clippy::too_many_arguments, clippy::missing_panics_doc)]
     pub async fn new_with_data(
@@ -167,6 +181,8 @@ where
 
         let created_schema = Self::create_pro_database(pool.get().await?).await?;
 
+        let task_manager = PostgresContext::create_task_manager(&pool).await?;
+
         let db = ProPostgresDb::new(pool.clone(), UserSession::admin_session());
         let quota = initialize_quota_tracking(
             quota_config.mode,
@@ -176,7 +192,7 @@ where
         );
 
         let app_ctx = ProPostgresContext {
-            task_manager: Default::default(),
+            task_manager: Arc::new(ProTaskManagerBackend::new(task_manager)),
             thread_pool: create_rayon_thread_pool(0),
             exe_ctx_tiling_spec,
             query_ctx_chunk_size,
@@ -293,7 +309,7 @@ where
     type GeoEngineDB = ProPostgresDb<Tls>;
 
     type TaskContext = SimpleTaskManagerContext;
-    type TaskManager = ProTaskManager; // this does not persist across restarts
+    type TaskManager = ProTaskManager;
     type QueryContext = QueryContextImpl;
     type ExecutionContext = ExecutionContextImpl<Self::GeoEngineDB>;
 
@@ -515,6 +531,7 @@ mod tests {
     use crate::layers::layer::{
         AddLayer, AddLayerCollection, CollectionItem, LayerCollection, LayerCollectionListOptions,
         LayerCollectionListing, LayerListing, ProviderLayerCollectionId, ProviderLayerId,
+        UpdateLayer, UpdateLayerCollection,
     };
     use crate::layers::listing::{
         LayerCollectionId, LayerCollectionProvider, SearchParameters, SearchType,
@@ -525,9 +542,12 @@ mod tests {
     };
     use crate::pro::ge_context;
     use crate::pro::machine_learning::ml_model::{MlModel, MlModelDb};
-    use crate::pro::permissions::{Permission, PermissionDb, Role, RoleDescription, RoleId};
+    use crate::pro::permissions::{
+        Permission, PermissionDb, ResourceId, Role, RoleDescription, RoleId,
+    };
     use crate::pro::users::{
-        ExternalUserClaims, RoleDb, UserCredentials, UserDb, UserId, UserRegistration,
+        CreateApiToken, ExternalUserClaims, RoleDb, UserCredentials, UserDb, UserId,
+        UserRegistration,
     };
     use crate::pro::util::config::QuotaTrackingMode;
     use crate::pro::util::tests::{admin_login, register_ndvi_workflow_helper};
@@ -763,6 +783,7 @@ mod tests {
                 operator: Statistics {
                     params: StatisticsParams {
                         column_names: vec![],
+                        estimate_quantiles: None,
                     },
                     sources: MultipleRasterOrSingleVectorSource {
                         source: Raster(vec![]),
@@ -4356,6 +4377,716 @@ mod tests {
         );
     }
 
+    #[ge_context::test]
+    async fn it_checks_permissions_when_updating_layers(app_ctx: ProPostgresContext<NoTls>) {
+        let admin_session = admin_login(&app_ctx).await;
+        let session1 = app_ctx.create_anonymous_session().await.unwrap();
+
+        let admin_db = app_ctx.session_context(admin_session.clone()).db();
+        let db1 = app_ctx.session_context(session1.clone()).db();
+
+        let root = admin_db.get_root_layer_collection_id().await.unwrap();
+
+        let collection = admin_db
+            .add_layer_collection(
+                AddLayerCollection {
+                    name: "admin collection".to_string(),
+                    description: String::new(),
+                    properties: Default::default(),
+                },
+                &root,
+            )
+            .await
+            .unwrap();
+
+        let layer = admin_db
+            .add_layer(
+                AddLayer {
+                    name: "admin layer".to_string(),
+                    description: String::new(),
+                    workflow: Workflow {
+                        operator: TypedOperator::Vector(
+                            MockPointSource {
+                                params: MockPointSourceParams {
+                                    points: vec![Coordinate2D::new(1., 2.); 3],
+                                },
+                            }
+                            .boxed(),
+                        ),
+                    },
+                    symbology: None,
+                    metadata: Default::default(),
+                    properties: Default::default(),
+                },
+                &collection,
+            )
+            .await
+            .unwrap();
+
+        admin_db
+            .add_permission(
+                session1.user.id.into(),
+                collection.clone(),
+                Permission::Read,
+            )
+            .await
+            .unwrap();
+        admin_db
+            .add_permission(session1.user.id.into(), layer.clone(), Permission::Read)
+            .await
+            .unwrap();
+
+        let update_collection = UpdateLayerCollection {
+            name: "renamed collection".to_string(),
+            description: String::new(),
+            properties: Default::default(),
+        };
+
+        // reading is not sufficient for updating
+        assert!(db1
+            .update_layer_collection(&collection, update_collection.clone())
+            .await
+            .is_err());
+
+        let update_layer = UpdateLayer {
+            name: "renamed layer".to_string(),
+            description: String::new(),
+            workflow: admin_db.load_layer(&layer).await.unwrap().workflow,
+            symbology: None,
+            metadata: Default::default(),
+            properties: Default::default(),
+        };
+
+        assert!(db1
+            .update_layer(&layer, update_layer.clone())
+            .await
+            .is_err());
+
+        // the owner can update and the ids stay valid for the other user
+        admin_db
+            .update_layer_collection(&collection, update_collection)
+            .await
+            .unwrap();
+        admin_db.update_layer(&layer, update_layer).await.unwrap();
+
+        assert_eq!(db1.load_layer(&layer).await.unwrap().name, "renamed layer");
+        assert_eq!(
+            db1.load_layer_collection(
+                &collection,
+                LayerCollectionListOptions {
+                    offset: 0,
+                    limit: 10,
+                },
+            )
+            .await
+            .unwrap()
+            .name,
+            "renamed collection"
+        );
+    }
+
+    #[allow(clippy::too_many_lines)]
+    #[ge_context::test]
+    async fn it_inherits_layer_collection_permissions(app_ctx: ProPostgresContext<NoTls>) {
+        let admin_session = admin_login(&app_ctx).await;
+        let editor_session = app_ctx.create_anonymous_session().await.unwrap();
+        let reader_session = app_ctx.create_anonymous_session().await.unwrap();
+
+        let admin_db = app_ctx.session_context(admin_session.clone()).db();
+        let editor_db = app_ctx.session_context(editor_session.clone()).db();
+        let reader_db = app_ctx.session_context(reader_session.clone()).db();
+
+        let root = admin_db.get_root_layer_collection_id().await.unwrap();
+
+        let collection = admin_db
+            .add_layer_collection(
+                AddLayerCollection {
+                    name: "shared collection".to_string(),
+                    description: String::new(),
+                    properties: Default::default(),
+                },
+                &root,
+            )
+            .await
+            .unwrap();
+
+        let sub_collection = admin_db
+            .add_layer_collection(
+                AddLayerCollection {
+                    name: "sub collection".to_string(),
+                    description: String::new(),
+                    properties: Default::default(),
+                },
+                &collection,
+            )
+            .await
+            .unwrap();
+
+        let add_layer = AddLayer {
+            name: "admin layer".to_string(),
+            description: String::new(),
+            workflow: Workflow {
+                operator: TypedOperator::Vector(
+                    MockPointSource {
+                        params: MockPointSourceParams {
+                            points: vec![Coordinate2D::new(1., 2.); 3],
+                        },
+                    }
+                    .boxed(),
+                ),
+            },
+            symbology: None,
+            metadata: Default::default(),
+            properties: Default::default(),
+        };
+
+        let layer = admin_db
+            .add_layer(add_layer.clone(), &sub_collection)
+            .await
+            .unwrap();
+
+        admin_db
+            .add_permission(
+                editor_session.user.id.into(),
+                collection.clone(),
+                Permission::Write,
+            )
+            .await
+            .unwrap();
+        admin_db
+            .add_permission(
+                reader_session.user.id.into(),
+                collection.clone(),
+                Permission::Read,
+            )
+            .await
+            .unwrap();
+
+        // write permissions are inherited and imply read permissions
+        assert!(editor_db
+            .has_permission(sub_collection.clone(), Permission::Write)
+            .await
+            .unwrap());
+        assert_eq!(
+            editor_db.load_layer(&layer).await.unwrap().name,
+            "admin layer"
+        );
+
+        editor_db
+            .update_layer_collection(
+                &sub_collection,
+                UpdateLayerCollection {
+                    name: "renamed sub collection".to_string(),
+                    description: String::new(),
+                    properties: Default::default(),
+                },
+            )
+            .await
+            .unwrap();
+        editor_db
+            .update_layer(
+                &layer,
+                UpdateLayer {
+                    name: "renamed layer".to_string(),
+                    description: String::new(),
+                    workflow: add_layer.workflow.clone(),
+                    symbology: None,
+                    metadata: Default::default(),
+                    properties: Default::default(),
+                },
+            )
+            .await
+            .unwrap();
+        editor_db
+            .add_layer(add_layer.clone(), &sub_collection)
+            .await
+            .unwrap();
+
+        let listing = editor_db
+            .load_layer_collection(
+                &sub_collection,
+                LayerCollectionListOptions {
+                    offset: 0,
+                    limit: 10,
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(listing.name, "renamed sub collection");
+        assert_eq!(listing.items.len(), 2);
+
+        // editors can neither delete nor re-share
+        assert!(editor_db
+            .remove_layer_collection(&sub_collection)
+            .await
+            .is_err());
+        assert!(editor_db
+            .add_permission(
+                reader_session.user.id.into(),
+                layer.clone(),
+                Permission::Read
+            )
+            .await
+            .is_err());
+
+        // read permissions are inherited as well
+        assert!(reader_db
+            .load_layer_collection(
+                &sub_collection,
+                LayerCollectionListOptions {
+                    offset: 0,
+                    limit: 10,
+                },
+            )
+            .await
+            .is_ok());
+        assert!(reader_db.load_layer(&layer).await.is_ok());
+        assert!(!reader_db
+            .has_permission(layer.clone(), Permission::Write)
+            .await
+            .unwrap());
+
+        // but the public read permission of the root collection is not
+        let other_session = app_ctx.create_anonymous_session().await.unwrap();
+        let other_db = app_ctx.session_context(other_session).db();
+        assert!(other_db.load_layer(&layer).await.is_err());
+        assert!(!other_db
+            .has_permission(sub_collection.clone(), Permission::Read)
+            .await
+            .unwrap());
+
+        let permissions = admin_db
+            .list_effective_permissions(layer.clone(), 0, 10)
+            .await
+            .unwrap();
+        assert!(permissions.iter().any(|p| {
+            p.role.id == RoleId::from(editor_session.user.id)
+                && p.permission == Permission::Write
+                && p.inherited_from == Some(collection.clone())
+        }));
+        assert!(permissions.iter().any(|p| {
+            p.role.id == Role::admin_role_id()
+                && p.permission == Permission::Owner
+                && p.inherited_from.is_none()
+        }));
+        assert!(permissions.iter().any(|p| {
+            p.role.id == RoleId::from(reader_session.user.id)
+                && p.permission == Permission::Read
+                && p.inherited_from == Some(collection.clone())
+        }));
+        assert!(!permissions
+            .iter()
+            .any(|p| p.role.id == Role::anonymous_role_id()));
+
+        // revoking the permission on the parent revokes the inherited permissions
+        admin_db
+            .remove_permission(
+                editor_session.user.id.into(),
+                collection.clone(),
+                Permission::Write,
+            )
+            .await
+            .unwrap();
+        assert!(editor_db.load_layer(&layer).await.is_err());
+    }
+
+    #[allow(clippy::too_many_lines)]
+    #[ge_context::test]
+    async fn it_only_attaches_owned_collections_and_layers(app_ctx: ProPostgresContext<NoTls>) {
+        let admin_session = admin_login(&app_ctx).await;
+        let owner_session = app_ctx.create_anonymous_session().await.unwrap();
+        let attacker_session = app_ctx.create_anonymous_session().await.unwrap();
+
+        let admin_db = app_ctx.session_context(admin_session.clone()).db();
+        let owner_db = app_ctx.session_context(owner_session.clone()).db();
+        let attacker_db = app_ctx.session_context(attacker_session.clone()).db();
+
+        let root = admin_db.get_root_layer_collection_id().await.unwrap();
+
+        let add_collection = |name: &str| AddLayerCollection {
+            name: name.to_string(),
+            description: String::new(),
+            properties: Default::default(),
+        };
+
+        let shared_collection = admin_db
+            .add_layer_collection(add_collection("shared collection"), &root)
+            .await
+            .unwrap();
+        let private_collection = admin_db
+            .add_layer_collection(add_collection("private collection"), &root)
+            .await
+            .unwrap();
+
+        for session in [&owner_session, &attacker_session] {
+            admin_db
+                .add_permission(
+                    session.user.id.into(),
+                    shared_collection.clone(),
+                    Permission::Write,
+                )
+                .await
+                .unwrap();
+        }
+
+        let owned_collection = owner_db
+            .add_layer_collection(add_collection("owned collection"), &shared_collection)
+            .await
+            .unwrap();
+        let layer = admin_db
+            .add_layer(
+                AddLayer {
+                    name: "private layer".to_string(),
+                    description: String::new(),
+                    workflow: Workflow {
+                        operator: TypedOperator::Vector(
+                            MockPointSource {
+                                params: MockPointSourceParams {
+                                    points: vec![Coordinate2D::new(1., 2.); 3],
+                                },
+                            }
+                            .boxed(),
+                        ),
+                    },
+                    symbology: None,
+                    metadata: Default::default(),
+                    properties: Default::default(),
+                },
+                &private_collection,
+            )
+            .await
+            .unwrap();
+
+        // attaching a foreign collection or layer to a writable collection would grant access to it
+        assert!(attacker_db
+            .add_collection_to_parent(&private_collection, &shared_collection)
+            .await
+            .is_err());
+        assert!(attacker_db
+            .add_layer_to_collection(&layer, &shared_collection)
+            .await
+            .is_err());
+
+        assert!(attacker_db
+            .load_layer_collection(
+                &private_collection,
+                LayerCollectionListOptions {
+                    offset: 0,
+                    limit: 10,
+                },
+            )
+            .await
+            .is_err());
+        assert!(attacker_db.load_layer(&layer).await.is_err());
+
+        let listing = admin_db
+            .load_layer_collection(
+                &shared_collection,
+                LayerCollectionListOptions {
+                    offset: 0,
+                    limit: 10,
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(listing.items.len(), 1);
+
+        // inherited write permission on the child does not suffice either
+        assert!(attacker_db
+            .add_collection_to_parent(&owned_collection, &shared_collection)
+            .await
+            .is_err());
+
+        // owners may attach their own collections to collections they can write
+        owner_db
+            .add_collection_to_parent(&owned_collection, &shared_collection)
+            .await
+            .unwrap();
+        admin_db
+            .add_collection_to_parent(&private_collection, &shared_collection)
+            .await
+            .unwrap();
+        admin_db
+            .add_layer_to_collection(&layer, &shared_collection)
+            .await
+            .unwrap();
+
+        assert!(attacker_db.load_layer(&layer).await.is_ok());
+    }
+
+    #[allow(clippy::too_many_lines)]
+    #[ge_context::test]
+    async fn it_restricts_api_token_scopes(app_ctx: ProPostgresContext<NoTls>) {
+        let admin_session = admin_login(&app_ctx).await;
+        let user_session = app_ctx.create_anonymous_session().await.unwrap();
+
+        let admin_db = app_ctx.session_context(admin_session.clone()).db();
+        let user_db = app_ctx.session_context(user_session.clone()).db();
+
+        let root = admin_db.get_root_layer_collection_id().await.unwrap();
+
+        let add_layer = AddLayer {
+            name: "layer".to_string(),
+            description: String::new(),
+            workflow: Workflow {
+                operator: TypedOperator::Vector(
+                    MockPointSource {
+                        params: MockPointSourceParams {
+                            points: vec![Coordinate2D::new(1., 2.); 3],
+                        },
+                    }
+                    .boxed(),
+                ),
+            },
+            symbology: None,
+            metadata: Default::default(),
+            properties: Default::default(),
+        };
+
+        let mut collections = vec![];
+        let mut layers = vec![];
+        for name in ["in scope", "out of scope"] {
+            let collection = admin_db
+                .add_layer_collection(
+                    AddLayerCollection {
+                        name: name.to_string(),
+                        description: String::new(),
+                        properties: Default::default(),
+                    },
+                    &root,
+                )
+                .await
+                .unwrap();
+            let layer = admin_db
+                .add_layer(add_layer.clone(), &collection)
+                .await
+                .unwrap();
+
+            admin_db
+                .add_permission(
+                    user_session.user.id.into(),
+                    collection.clone(),
+                    Permission::Owner,
+                )
+                .await
+                .unwrap();
+
+            collections.push(collection);
+            layers.push(layer);
+        }
+
+        let created = user_db
+            .create_api_token(CreateApiToken {
+                name: "notebook".to_string(),
+                valid_until: DateTime::now() + Duration::days(1),
+                read_only: true,
+                resources: vec![ResourceId::LayerCollection(collections[0].clone())],
+            })
+            .await
+            .unwrap();
+
+        assert!(matches!(
+            user_db
+                .create_api_token(CreateApiToken {
+                    name: "notebook".to_string(),
+                    valid_until: DateTime::now() + Duration::days(1),
+                    read_only: false,
+                    resources: vec![],
+                })
+                .await,
+            Err(crate::error::Error::Duplicate { .. })
+        ));
+        assert!(matches!(
+            user_db
+                .create_api_token(CreateApiToken {
+                    name: "expired".to_string(),
+                    valid_until: DateTime::now() - Duration::days(1),
+                    read_only: false,
+                    resources: vec![],
+                })
+                .await,
+            Err(crate::error::Error::ApiTokenExpiryMustBeInTheFuture)
+        ));
+
+        let token_session = app_ctx
+            .user_session_by_api_token(&created.token)
+            .await
+            .unwrap();
+        assert_eq!(token_session.user.id, user_session.user.id);
+        assert!(token_session.is_read_only());
+
+        let token_db = app_ctx.session_context(token_session.clone()).db();
+
+        // read-only tokens only grant read permissions on resources in their scope
+        assert!(token_db
+            .has_permission(layers[0].clone(), Permission::Read)
+            .await
+            .unwrap());
+        assert!(!token_db
+            .has_permission(layers[0].clone(), Permission::Write)
+            .await
+            .unwrap());
+        assert!(!token_db
+            .has_permission(layers[1].clone(), Permission::Read)
+            .await
+            .unwrap());
+        assert!(token_db.load_layer(&layers[0]).await.is_ok());
+        assert!(token_db.load_layer(&layers[1]).await.is_err());
+
+        assert!(token_db
+            .create_project(CreateProject {
+                name: "project".to_string(),
+                description: String::new(),
+                bounds: STRectangle::new(
+                    SpatialReferenceOption::Unreferenced,
+                    0.,
+                    0.,
+                    1.,
+                    1.,
+                    0,
+                    1,
+                )
+                .unwrap(),
+                time_step: None,
+            })
+            .await
+            .is_err());
+
+        // token sessions cannot manage tokens
+        assert!(matches!(
+            token_db.revoke_api_token(created.api_token.id).await,
+            Err(crate::error::Error::ApiTokenCannotManageApiTokens)
+        ));
+
+        assert_eq!(
+            user_db.list_api_tokens().await.unwrap(),
+            vec![created.api_token.clone()]
+        );
+
+        user_db
+            .revoke_api_token(created.api_token.id)
+            .await
+            .unwrap();
+
+        assert!(app_ctx
+            .user_session_by_api_token(&created.token)
+            .await
+            .is_err());
+        assert!(user_db.list_api_tokens().await.unwrap().is_empty());
+        assert!(matches!(
+            user_db.revoke_api_token(created.api_token.id).await,
+            Err(crate::error::Error::UnknownApiToken { .. })
+        ));
+    }
+
+    #[allow(clippy::too_many_lines)]
+    #[ge_context::test]
+    async fn it_restricts_api_token_dataset_scopes(app_ctx: ProPostgresContext<NoTls>) {
+        let admin_session = admin_login(&app_ctx).await;
+        let admin_db = app_ctx.session_context(admin_session.clone()).db();
+
+        let descriptor = VectorResultDescriptor {
+            data_type: VectorDataType::Data,
+            spatial_reference: SpatialReferenceOption::Unreferenced,
+            columns: Default::default(),
+            time: None,
+            bbox: None,
+        };
+
+        let meta = StaticMetaData {
+            loading_info: OgrSourceDataset {
+                file_name: Default::default(),
+                layer_name: String::new(),
+                data_type: None,
+                time: Default::default(),
+                default_geometry: None,
+                columns: None,
+                force_ogr_time_filter: false,
+                force_ogr_spatial_filter: false,
+                on_error: OgrSourceErrorSpec::Ignore,
+                sql_query: None,
+                attribute_query: None,
+                cache_ttl: CacheTtlSeconds::default(),
+            },
+            result_descriptor: descriptor.clone(),
+            phantom: Default::default(),
+        };
+
+        let mut datasets = vec![];
+        for name in ["in scope", "out of scope"] {
+            let ds = AddDataset {
+                name: None,
+                display_name: name.to_string(),
+                description: String::new(),
+                source_operator: "OgrSource".to_string(),
+                symbology: None,
+                provenance: None,
+                tags: Some(vec!["scope".to_owned()]),
+            };
+
+            datasets.push(
+                admin_db
+                    .add_dataset(ds, meta.clone().into())
+                    .await
+                    .unwrap()
+                    .id,
+            );
+        }
+
+        let created = admin_db
+            .create_api_token(CreateApiToken {
+                name: "dataset".to_string(),
+                valid_until: DateTime::now() + Duration::days(1),
+                read_only: false,
+                resources: vec![ResourceId::DatasetId(datasets[0])],
+            })
+            .await
+            .unwrap();
+
+        let token_session = app_ctx
+            .user_session_by_api_token(&created.token)
+            .await
+            .unwrap();
+
+        // scoped tokens never grant admin privileges
+        assert!(!token_session.is_admin());
+
+        let token_db = app_ctx.session_context(token_session).db();
+
+        let listing = token_db
+            .list_datasets(DatasetListOptions {
+                filter: None,
+                order: crate::datasets::listing::OrderBy::NameAsc,
+                offset: 0,
+                limit: 10,
+                tags: None,
+            })
+            .await
+            .unwrap();
+        assert_eq!(listing.len(), 1);
+        assert_eq!(listing[0].id, datasets[0]);
+
+        assert!(token_db.load_dataset(&datasets[0]).await.is_ok());
+        assert!(token_db.load_dataset(&datasets[1]).await.is_err());
+        assert!(token_db.load_provenance(&datasets[1]).await.is_err());
+        assert!(token_db.load_loading_info(&datasets[1]).await.is_err());
+
+        let meta: geoengine_operators::util::Result<
+            Box<dyn MetaData<OgrSourceDataset, VectorResultDescriptor, VectorQueryRectangle>>,
+        > = token_db.meta_data(&datasets[0].into()).await;
+        assert!(meta.is_ok());
+
+        let meta: geoengine_operators::util::Result<
+            Box<dyn MetaData<OgrSourceDataset, VectorResultDescriptor, VectorQueryRectangle>>,
+        > = token_db.meta_data(&datasets[1].into()).await;
+        assert!(meta.is_err());
+
+        assert!(matches!(
+            app_ctx.user_session_by_api_token("unknown").await,
+            Err(crate::error::Error::InvalidApiToken)
+        ));
+    }
+
     #[allow(clippy::too_many_lines)]
     #[ge_context::test]
     async fn it_updates_project_layer_symbology(app_ctx: ProPostgresContext<NoTls>) {
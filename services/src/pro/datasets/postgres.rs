@@ -2,14 +2,20 @@ use crate::api::model::services::UpdateDataset;
 use crate::datasets::listing::Provenance;
 use crate::datasets::listing::{DatasetListOptions, DatasetListing, DatasetProvider};
 use crate::datasets::listing::{OrderBy, ProvenanceOutput};
-use crate::datasets::postgres::resolve_dataset_name_to_id;
-use crate::datasets::storage::{Dataset, DatasetDb, DatasetStore, MetaDataDefinition};
+use crate::datasets::postgres::{
+    create_dataset_version, garbage_collect_dataset_versions, list_dataset_versions,
+    replace_dataset_meta_data, resolve_dataset_name_to_id, resolve_dataset_version,
+    resolve_dataset_version_id_to_dataset_id, restore_dataset_version,
+};
+use crate::datasets::storage::{
+    Dataset, DatasetDb, DatasetStore, DatasetVersion, DatasetVersionSelector, MetaDataDefinition,
+};
 use crate::datasets::upload::FileId;
 use crate::datasets::upload::{Upload, UploadDb, UploadId};
 use crate::datasets::{AddDataset, DatasetIdAndName, DatasetName};
 use crate::error::{self, Error, Result};
 use crate::pro::contexts::ProPostgresDb;
-use crate::pro::permissions::postgres_permissiondb::TxPermissionDb;
+use crate::pro::permissions::postgres_permissiondb::{ApiTokenResourceIds, TxPermissionDb};
 use crate::pro::permissions::{Permission, RoleId};
 use crate::projects::Symbology;
 use crate::util::postgres::PostgresErrorExt;
@@ -50,7 +56,9 @@ where
     async fn list_datasets(&self, options: DatasetListOptions) -> Result<Vec<DatasetListing>> {
         let conn = self.conn_pool.get().await?;
 
-        let mut pos = 3;
+        let scope = ApiTokenResourceIds::new(self.session.api_token.as_ref());
+
+        let mut pos = 4;
         let order_sql = if options.order == OrderBy::NameAsc {
             "display_name ASC"
         } else {
@@ -88,6 +96,7 @@ where
                     ON (p.dataset_id = d.id)
             WHERE 
                 p.user_id = $1
+                AND ($4::uuid[] IS NULL OR d.id = ANY($4::uuid[]))
                 {filter_sql}
                 {filter_tags_sql}
             ORDER BY {order_sql}
@@ -105,6 +114,7 @@ where
                         &self.session.user.id,
                         &i64::from(options.limit),
                         &i64::from(options.offset),
+                        &scope.datasets,
                         &format!("%{}%", filter.replace('%', "\\%").replace('_', "\\_")),
                         &filter_tags_list,
                     ],
@@ -118,6 +128,7 @@ where
                         &self.session.user.id,
                         &i64::from(options.limit),
                         &i64::from(options.offset),
+                        &scope.datasets,
                         &format!("%{}%", filter.replace('%', "\\%").replace('_', "\\_")),
                     ],
                 )
@@ -130,6 +141,7 @@ where
                         &self.session.user.id,
                         &i64::from(options.limit),
                         &i64::from(options.offset),
+                        &scope.datasets,
                         &filter_tags_list,
                     ],
                 )
@@ -142,6 +154,7 @@ where
                         &self.session.user.id,
                         &i64::from(options.limit),
                         &i64::from(options.offset),
+                        &scope.datasets,
                     ],
                 )
                 .await?
@@ -186,13 +199,16 @@ where
                     ON (p.dataset_id = d.id)
             WHERE 
                 p.user_id = $1 AND d.id = $2
+                AND ($3::uuid[] IS NULL OR d.id = ANY($3::uuid[]))
             LIMIT 
                 1",
             )
             .await?;
 
+        let scope = ApiTokenResourceIds::new(self.session.api_token.as_ref());
+
         let row = conn
-            .query_opt(&stmt, &[&self.session.user.id, dataset])
+            .query_opt(&stmt, &[&self.session.user.id, dataset, &scope.datasets])
             .await?;
 
         let row = row.ok_or(error::Error::UnknownDatasetId)?;
@@ -216,18 +232,33 @@ where
         let stmt = conn
             .prepare(
                 "
-            SELECT 
-                d.provenance 
-            FROM 
+            SELECT
+                d.provenance
+            FROM
                 user_permitted_datasets p JOIN datasets d
                     ON(p.dataset_id = d.id)
-            WHERE 
-                p.user_id = $1 AND d.id = $2",
+            WHERE
+                p.user_id = $1 AND d.id = $2
+                AND ($3::uuid[] IS NULL OR d.id = ANY($3::uuid[]))
+            UNION ALL
+            SELECT
+                d.provenance
+            FROM
+                user_permitted_datasets p JOIN dataset_versions v
+                    ON (p.dataset_id = v.dataset_id)
+                JOIN datasets d
+                    ON (v.dataset_id = d.id)
+            WHERE
+                p.user_id = $1 AND v.id = $2
+                AND ($3::uuid[] IS NULL OR v.dataset_id = ANY($3::uuid[]))",
             )
             .await?;
 
+        // pinned dataset versions have the provenance of their dataset
+        let scope = ApiTokenResourceIds::new(self.session.api_token.as_ref());
+
         let row = conn
-            .query_opt(&stmt, &[&self.session.user.id, dataset])
+            .query_opt(&stmt, &[&self.session.user.id, dataset, &scope.datasets])
             .await?;
 
         let row = row.ok_or(error::Error::UnknownDatasetId)?;
@@ -250,12 +281,15 @@ where
                 user_permitted_datasets p JOIN datasets d
                     ON(p.dataset_id = d.id)
             WHERE 
-                p.user_id = $1 AND d.id = $2",
+                p.user_id = $1 AND d.id = $2
+                AND ($3::uuid[] IS NULL OR d.id = ANY($3::uuid[]))",
             )
             .await?;
 
+        let scope = ApiTokenResourceIds::new(self.session.api_token.as_ref());
+
         let row = conn
-            .query_one(&stmt, &[&self.session.user.id, dataset])
+            .query_one(&stmt, &[&self.session.user.id, dataset, &scope.datasets])
             .await?;
 
         Ok(row.get(0))
@@ -269,6 +303,38 @@ where
         resolve_dataset_name_to_id(&conn, dataset_name).await
     }
 
+    async fn resolve_dataset_version(
+        &self,
+        dataset: &DatasetId,
+        version: DatasetVersionSelector,
+    ) -> Result<DatasetId> {
+        let mut conn = self.conn_pool.get().await?;
+
+        let tx = conn.build_transaction().start().await?;
+
+        let id = resolve_dataset_version(&tx, dataset, version).await?;
+
+        tx.commit().await?;
+
+        Ok(id)
+    }
+
+    async fn list_dataset_versions(&self, dataset: &DatasetId) -> Result<Vec<DatasetVersion>> {
+        let mut conn = self.conn_pool.get().await?;
+
+        let tx = conn.build_transaction().start().await?;
+
+        self.ensure_permission_in_tx((*dataset).into(), Permission::Read, &tx)
+            .await
+            .boxed_context(crate::error::PermissionDb)?;
+
+        let versions = list_dataset_versions(&tx, dataset).await?;
+
+        tx.commit().await?;
+
+        Ok(versions)
+    }
+
     async fn dataset_autocomplete_search(
         &self,
         tags: Option<Vec<String>>,
@@ -285,12 +351,19 @@ where
             search_string.replace('%', "\\%").replace('_', "\\_")
         );
 
-        let mut query_params: Vec<&(dyn ToSql + Sync)> =
-            vec![&self.session.user.id, &limit, &offset, &search_string];
+        let scope = ApiTokenResourceIds::new(self.session.api_token.as_ref());
+
+        let mut query_params: Vec<&(dyn ToSql + Sync)> = vec![
+            &self.session.user.id,
+            &limit,
+            &offset,
+            &search_string,
+            &scope.datasets,
+        ];
 
         let tags_clause = if let Some(tags) = &tags {
             query_params.push(tags);
-            " AND tags @> $5::text[]".to_string()
+            " AND tags @> $6::text[]".to_string()
         } else {
             String::new()
         };
@@ -305,6 +378,7 @@ where
             WHERE 
                 p.user_id = $1
                 AND display_name ILIKE $4 ESCAPE '\\'
+                AND ($5::uuid[] IS NULL OR d.id = ANY($5::uuid[]))
                 {tags_clause}
             ORDER BY display_name ASC
             LIMIT $2
@@ -374,8 +448,14 @@ where
             }
         })?;
 
+        let dataset_id = resolve_dataset_version_id_to_dataset_id(&tx, &id)
+            .await
+            .map_err(|e| geoengine_operators::error::Error::MetaData {
+                source: Box::new(e),
+            })?;
+
         if !self
-            .has_permission_in_tx(id, Permission::Read, &tx)
+            .has_permission_in_tx(dataset_id, Permission::Read, &tx)
             .await
             .map_err(|e| geoengine_operators::error::Error::MetaData {
                 source: Box::new(e),
@@ -393,15 +473,27 @@ where
             user_permitted_datasets p JOIN datasets d
                 ON (p.dataset_id = d.id)
         WHERE
-            d.id = $1 AND p.user_id = $2",
+            d.id = $1 AND p.user_id = $2
+            AND ($3::uuid[] IS NULL OR d.id = ANY($3::uuid[]))
+        UNION ALL
+        SELECT
+            v.meta_data
+        FROM
+            user_permitted_datasets p JOIN dataset_versions v
+                ON (p.dataset_id = v.dataset_id)
+        WHERE
+            v.id = $1 AND p.user_id = $2
+            AND ($3::uuid[] IS NULL OR v.dataset_id = ANY($3::uuid[]))",
             )
             .await
             .map_err(|e| geoengine_operators::error::Error::MetaData {
                 source: Box::new(e),
             })?;
 
+        let scope = ApiTokenResourceIds::new(self.session.api_token.as_ref());
+
         let row = tx
-            .query_one(&stmt, &[&id, &self.session.user.id])
+            .query_one(&stmt, &[&id, &self.session.user.id, &scope.datasets])
             .await
             .map_err(|e| geoengine_operators::error::Error::MetaData {
                 source: Box::new(e),
@@ -458,8 +550,14 @@ where
             }
         })?;
 
+        let dataset_id = resolve_dataset_version_id_to_dataset_id(&tx, &id)
+            .await
+            .map_err(|e| geoengine_operators::error::Error::MetaData {
+                source: Box::new(e),
+            })?;
+
         if !self
-            .has_permission_in_tx(id, Permission::Read, &tx)
+            .has_permission_in_tx(dataset_id, Permission::Read, &tx)
             .await
             .map_err(|e| geoengine_operators::error::Error::MetaData {
                 source: Box::new(e),
@@ -477,15 +575,27 @@ where
                 user_permitted_datasets p JOIN datasets d
                     ON (p.dataset_id = d.id)
             WHERE
-                d.id = $1 AND p.user_id = $2",
+                d.id = $1 AND p.user_id = $2
+                AND ($3::uuid[] IS NULL OR d.id = ANY($3::uuid[]))
+            UNION ALL
+            SELECT
+                v.meta_data
+            FROM
+                user_permitted_datasets p JOIN dataset_versions v
+                    ON (p.dataset_id = v.dataset_id)
+            WHERE
+                v.id = $1 AND p.user_id = $2
+                AND ($3::uuid[] IS NULL OR v.dataset_id = ANY($3::uuid[]))",
             )
             .await
             .map_err(|e| geoengine_operators::error::Error::MetaData {
                 source: Box::new(e),
             })?;
 
+        let scope = ApiTokenResourceIds::new(self.session.api_token.as_ref());
+
         let row = tx
-            .query_one(&stmt, &[&id, &self.session.user.id])
+            .query_one(&stmt, &[&id, &self.session.user.id, &scope.datasets])
             .await
             .map_err(|e| geoengine_operators::error::Error::MetaData {
                 source: Box::new(e),
@@ -589,6 +699,10 @@ where
 
         let tx = conn.build_transaction().start().await?;
 
+        self.ensure_not_read_only_in_tx(&tx)
+            .await
+            .boxed_context(crate::error::PermissionDb)?;
+
         tx.execute(
             "
                 INSERT INTO datasets (
@@ -638,6 +752,8 @@ where
         )
         .await?;
 
+        create_dataset_version(&tx, &id).await?;
+
         tx.commit().await?;
 
         Ok(DatasetIdAndName { id, name })
@@ -648,7 +764,7 @@ where
 
         let tx = conn.build_transaction().start().await?;
 
-        self.ensure_permission_in_tx(dataset.into(), Permission::Owner, &tx)
+        self.ensure_permission_in_tx(dataset.into(), Permission::Write, &tx)
             .await
             .boxed_context(crate::error::PermissionDb)?;
 
@@ -664,6 +780,8 @@ where
         )
         .await?;
 
+        create_dataset_version(&tx, &dataset).await?;
+
         tx.commit().await?;
 
         Ok(())
@@ -678,7 +796,7 @@ where
 
         let tx = conn.build_transaction().start().await?;
 
-        self.ensure_permission_in_tx(dataset.into(), Permission::Owner, &tx)
+        self.ensure_permission_in_tx(dataset.into(), Permission::Write, &tx)
             .await
             .boxed_context(crate::error::PermissionDb)?;
 
@@ -688,6 +806,8 @@ where
         )
         .await?;
 
+        create_dataset_version(&tx, &dataset).await?;
+
         tx.commit().await?;
 
         Ok(())
@@ -702,7 +822,7 @@ where
 
         let tx = conn.build_transaction().start().await?;
 
-        self.ensure_permission_in_tx(dataset.into(), Permission::Owner, &tx)
+        self.ensure_permission_in_tx(dataset.into(), Permission::Write, &tx)
             .await
             .boxed_context(crate::error::PermissionDb)?;
 
@@ -712,6 +832,8 @@ where
         )
         .await?;
 
+        create_dataset_version(&tx, &dataset).await?;
+
         tx.commit().await?;
 
         Ok(())
@@ -754,6 +876,62 @@ where
 
         Ok(())
     }
+
+    async fn replace_dataset_meta_data(
+        &self,
+        dataset: DatasetId,
+        meta_data: MetaDataDefinition,
+    ) -> Result<()> {
+        let mut conn = self.conn_pool.get().await?;
+
+        let tx = conn.build_transaction().start().await?;
+
+        self.ensure_permission_in_tx(dataset.into(), Permission::Write, &tx)
+            .await
+            .boxed_context(crate::error::PermissionDb)?;
+
+        replace_dataset_meta_data(&tx, &dataset, &meta_data).await?;
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    async fn restore_dataset_version(
+        &self,
+        dataset: DatasetId,
+        version: u32,
+    ) -> Result<DatasetVersion> {
+        let mut conn = self.conn_pool.get().await?;
+
+        let tx = conn.build_transaction().start().await?;
+
+        self.ensure_permission_in_tx(dataset.into(), Permission::Write, &tx)
+            .await
+            .boxed_context(crate::error::PermissionDb)?;
+
+        let restored = restore_dataset_version(&tx, &dataset, version).await?;
+
+        tx.commit().await?;
+
+        Ok(restored)
+    }
+
+    async fn garbage_collect_dataset_versions(&self, dataset: DatasetId, keep: u32) -> Result<u64> {
+        let mut conn = self.conn_pool.get().await?;
+
+        let tx = conn.build_transaction().start().await?;
+
+        self.ensure_permission_in_tx(dataset.into(), Permission::Owner, &tx)
+            .await
+            .boxed_context(crate::error::PermissionDb)?;
+
+        let deleted = garbage_collect_dataset_versions(&tx, &dataset, keep).await?;
+
+        tx.commit().await?;
+
+        Ok(deleted)
+    }
 }
 
 #[async_trait]
@@ -794,6 +972,10 @@ where
         let mut conn = self.conn_pool.get().await?;
         let tx = conn.build_transaction().start().await?;
 
+        self.ensure_not_read_only_in_tx(&tx)
+            .await
+            .boxed_context(crate::error::PermissionDb)?;
+
         let stmt = tx
             .prepare("INSERT INTO uploads (id, files) VALUES ($1, $2)")
             .await?;
@@ -930,6 +1112,70 @@ mod tests {
             .is_empty());
     }
 
+    #[ge_context::test]
+    async fn it_checks_permissions_for_dataset_versions(app_ctx: ProPostgresContext<NoTls>) {
+        let session_a = app_ctx.create_anonymous_session().await.unwrap();
+        let session_b = app_ctx.create_anonymous_session().await.unwrap();
+
+        let db_a = app_ctx.session_context(session_a.clone()).db();
+        let db_b = app_ctx.session_context(session_b.clone()).db();
+
+        add_single_dataset(&db_a, &session_a).await;
+
+        let dataset_id = db_a
+            .resolve_dataset_name_to_id(&DatasetName::new(
+                Some(session_a.user.id.to_string()),
+                "my_dataset",
+            ))
+            .await
+            .unwrap()
+            .unwrap();
+
+        db_a.update_dataset_provenance(dataset_id, &[])
+            .await
+            .unwrap();
+
+        let versions = db_a.list_dataset_versions(&dataset_id).await.unwrap();
+        assert_eq!(
+            versions.iter().map(|v| v.version).collect::<Vec<_>>(),
+            vec![2, 1]
+        );
+
+        // other users cannot access the versions of the dataset
+        assert!(db_b.list_dataset_versions(&dataset_id).await.is_err());
+        assert!(db_b.restore_dataset_version(dataset_id, 1).await.is_err());
+        assert!(db_b
+            .garbage_collect_dataset_versions(dataset_id, 1)
+            .await
+            .is_err());
+
+        let pinned: DataId = versions[1].id.into();
+
+        let meta_data: geoengine_operators::util::Result<
+            Box<dyn MetaData<OgrSourceDataset, VectorResultDescriptor, VectorQueryRectangle>>,
+        > = db_a.meta_data(&pinned).await;
+        assert!(meta_data.is_ok());
+
+        let meta_data: geoengine_operators::util::Result<
+            Box<dyn MetaData<OgrSourceDataset, VectorResultDescriptor, VectorQueryRectangle>>,
+        > = db_b.meta_data(&pinned).await;
+        assert!(meta_data.is_err());
+
+        assert_eq!(
+            db_a.restore_dataset_version(dataset_id, 1)
+                .await
+                .unwrap()
+                .version,
+            3
+        );
+        assert_eq!(
+            db_a.garbage_collect_dataset_versions(dataset_id, 1)
+                .await
+                .unwrap(),
+            2
+        );
+    }
+
     async fn add_single_dataset(db: &ProPostgresDb<NoTls>, session: &UserSession) {
         let loading_info = OgrSourceDataset {
             file_name: PathBuf::from("test.csv"),
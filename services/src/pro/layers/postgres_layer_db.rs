@@ -6,11 +6,12 @@ use crate::layers::listing::{
 };
 use crate::layers::postgres_layer_db::{
     delete_layer_collection, delete_layer_collection_from_parent, delete_layer_from_collection,
-    insert_collection_parent, insert_layer, insert_layer_collection_with_id,
+    insert_collection_parent, insert_layer, insert_layer_collection_with_id, update_layer,
+    update_layer_collection,
 };
 use crate::pro::contexts::ProPostgresDb;
 use crate::pro::datasets::TypedProDataProviderDefinition;
-use crate::pro::permissions::postgres_permissiondb::TxPermissionDb;
+use crate::pro::permissions::postgres_permissiondb::{ApiTokenResourceIds, TxPermissionDb};
 use crate::pro::permissions::{Permission, RoleId};
 use crate::{
     error::Result,
@@ -19,7 +20,7 @@ use crate::{
         layer::{
             AddLayer, AddLayerCollection, CollectionItem, Layer, LayerCollection,
             LayerCollectionListOptions, LayerCollectionListing, LayerListing,
-            ProviderLayerCollectionId, ProviderLayerId,
+            ProviderLayerCollectionId, ProviderLayerId, UpdateLayer, UpdateLayerCollection,
         },
         listing::{LayerCollectionId, LayerCollectionProvider},
         storage::{
@@ -67,7 +68,7 @@ where
         let mut conn = self.conn_pool.get().await?;
         let trans = conn.build_transaction().start().await?;
 
-        self.ensure_permission_in_tx(collection.clone().into(), Permission::Owner, &trans)
+        self.ensure_permission_in_tx(collection.clone().into(), Permission::Write, &trans)
             .await
             .boxed_context(crate::error::PermissionDb)?;
 
@@ -108,7 +109,12 @@ where
         let mut conn = self.conn_pool.get().await?;
         let tx = conn.build_transaction().start().await?;
 
-        self.ensure_permission_in_tx(collection.clone().into(), Permission::Owner, &tx)
+        self.ensure_permission_in_tx(collection.clone().into(), Permission::Write, &tx)
+            .await
+            .boxed_context(crate::error::PermissionDb)?;
+
+        // the layer inherits the permissions of the collection, so only its owner may add it
+        self.ensure_permission_in_tx(layer.clone().into(), Permission::Owner, &tx)
             .await
             .boxed_context(crate::error::PermissionDb)?;
 
@@ -160,7 +166,7 @@ where
         let mut conn = self.conn_pool.get().await?;
         let trans = conn.build_transaction().start().await?;
 
-        self.ensure_permission_in_tx(parent.clone().into(), Permission::Owner, &trans)
+        self.ensure_permission_in_tx(parent.clone().into(), Permission::Write, &trans)
             .await
             .boxed_context(crate::error::PermissionDb)?;
 
@@ -195,8 +201,21 @@ where
         collection: &LayerCollectionId,
         parent: &LayerCollectionId,
     ) -> Result<()> {
-        let conn = self.conn_pool.get().await?;
-        insert_collection_parent(&conn, collection, parent).await
+        let mut conn = self.conn_pool.get().await?;
+        let transaction = conn.build_transaction().start().await?;
+
+        self.ensure_permission_in_tx(parent.clone().into(), Permission::Write, &transaction)
+            .await
+            .boxed_context(crate::error::PermissionDb)?;
+
+        // the child inherits the permissions of its new parent, so only its owner may attach it
+        self.ensure_permission_in_tx(collection.clone().into(), Permission::Owner, &transaction)
+            .await
+            .boxed_context(crate::error::PermissionDb)?;
+
+        insert_collection_parent(&transaction, collection, parent).await?;
+
+        transaction.commit().await.map_err(Into::into)
     }
 
     async fn remove_layer_collection(&self, collection: &LayerCollectionId) -> Result<()> {
@@ -245,6 +264,36 @@ where
 
         transaction.commit().await.map_err(Into::into)
     }
+
+    async fn update_layer(&self, id: &LayerId, layer: UpdateLayer) -> Result<()> {
+        let mut conn = self.conn_pool.get().await?;
+        let transaction = conn.build_transaction().start().await?;
+
+        self.ensure_permission_in_tx(id.clone().into(), Permission::Write, &transaction)
+            .await
+            .boxed_context(crate::error::PermissionDb)?;
+
+        update_layer(&transaction, id, layer).await?;
+
+        transaction.commit().await.map_err(Into::into)
+    }
+
+    async fn update_layer_collection(
+        &self,
+        id: &LayerCollectionId,
+        collection: UpdateLayerCollection,
+    ) -> Result<()> {
+        let mut conn = self.conn_pool.get().await?;
+        let transaction = conn.build_transaction().start().await?;
+
+        self.ensure_permission_in_tx(id.clone().into(), Permission::Write, &transaction)
+            .await
+            .boxed_context(crate::error::PermissionDb)?;
+
+        update_layer_collection(&transaction, id, collection).await?;
+
+        transaction.commit().await.map_err(Into::into)
+    }
 }
 
 fn create_search_query(full_info: bool) -> String {
@@ -261,6 +310,7 @@ fn create_search_query(full_info: bool) -> String {
                 JOIN layer_collections lc ON (u.layer_collection_id = lc.id)
                 JOIN (SELECT DISTINCT child FROM collection_children JOIN parents ON (id = parent)) cc ON (id = cc.child)
             WHERE u.user_id = $4 AND name ILIKE $5
+                AND ($7::uuid[] IS NULL OR is_layer_collection_in_scope(lc.id, $7))
         ) u UNION (
             SELECT 
                 {}
@@ -268,6 +318,7 @@ fn create_search_query(full_info: bool) -> String {
                 JOIN layers uc ON (ul.layer_id = uc.id)
                 JOIN (SELECT DISTINCT layer FROM collection_layers JOIN parents ON (collection = id)) cl ON (id = cl.layer)
             WHERE ul.user_id = $4 AND name ILIKE $5
+                AND ($7::uuid[] IS NULL OR is_layer_in_scope(uc.id, $6, $7))
         )
         ORDER BY {}name ASC
         LIMIT $2 
@@ -338,19 +389,17 @@ where
             }
         })?;
 
+        // the permission was checked above and the user may hold several permissions for the collection
         let stmt = tx
             .prepare(
                 "
         SELECT name, description, properties
-        FROM user_permitted_layer_collections p 
-            JOIN layer_collections c ON (p.layer_collection_id = c.id) 
-        WHERE p.user_id = $1 AND layer_collection_id = $2;",
+        FROM layer_collections
+        WHERE id = $1;",
             )
             .await?;
 
-        let row = tx
-            .query_one(&stmt, &[&self.session.user.id, &collection])
-            .await?;
+        let row = tx.query_one(&stmt, &[&collection]).await?;
 
         let name: String = row.get(0);
         let description: String = row.get(1);
@@ -371,6 +420,7 @@ where
                 JOIN layer_collections lc ON (u.layer_collection_id = lc.id)
                 JOIN collection_children cc ON (layer_collection_id = cc.child)
             WHERE u.user_id = $4 AND cc.parent = $1
+                AND ($6::uuid[] IS NULL OR is_layer_collection_in_scope(lc.id, $6))
         ) u UNION (
             SELECT 
                 concat(id, '') AS id, 
@@ -382,6 +432,7 @@ where
                 JOIN layers uc ON (ul.layer_id = uc.id) 
                 JOIN collection_layers cl ON (layer_id = cl.layer)
             WHERE ul.user_id = $4 AND cl.collection = $1
+                AND ($6::uuid[] IS NULL OR is_layer_in_scope(uc.id, $5, $6))
         )
         ORDER BY is_layer ASC, name ASC
         LIMIT $2 
@@ -390,6 +441,8 @@ where
             )
             .await?;
 
+        let scope = ApiTokenResourceIds::new(self.session.api_token.as_ref());
+
         let rows = tx
             .query(
                 &stmt,
@@ -398,6 +451,8 @@ where
                     &i64::from(options.limit),
                     &i64::from(options.offset),
                     &self.session.user.id,
+                    &scope.layers,
+                    &scope.layer_collections,
                 ],
             )
             .await?;
@@ -465,19 +520,17 @@ where
             }
         })?;
 
+        // the permission was checked above and the user may hold several permissions for the collection
         let stmt = tx
             .prepare(
                 "
         SELECT name, description, properties
-        FROM user_permitted_layer_collections p 
-            JOIN layer_collections c ON (p.layer_collection_id = c.id) 
-        WHERE p.user_id = $1 AND layer_collection_id = $2;",
+        FROM layer_collections
+        WHERE id = $1;",
             )
             .await?;
 
-        let row = tx
-            .query_one(&stmt, &[&self.session.user.id, &collection])
-            .await?;
+        let row = tx.query_one(&stmt, &[&collection]).await?;
 
         let name: String = row.get(0);
         let description: String = row.get(1);
@@ -494,6 +547,8 @@ where
 
         let stmt = tx.prepare(&create_search_query(true)).await?;
 
+        let scope = ApiTokenResourceIds::new(self.session.api_token.as_ref());
+
         let rows = tx
             .query(
                 &stmt,
@@ -503,6 +558,8 @@ where
                     &i64::from(search.offset),
                     &self.session.user.id,
                     &pattern,
+                    &scope.layers,
+                    &scope.layer_collections,
                 ],
             )
             .await?;
@@ -581,6 +638,8 @@ where
 
         let stmt = tx.prepare(&create_search_query(false)).await?;
 
+        let scope = ApiTokenResourceIds::new(self.session.api_token.as_ref());
+
         let rows = tx
             .query(
                 &stmt,
@@ -590,6 +649,8 @@ where
                     &i64::from(search.offset),
                     &self.session.user.id,
                     &pattern,
+                    &scope.layers,
+                    &scope.layer_collections,
                 ],
             )
             .await?;
@@ -56,6 +56,8 @@ impl Role {
 #[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone, Hash, ToSchema, ToSql, FromSql)]
 pub enum Permission {
     Read,
+    /// Allows updating a resource, but neither deleting it nor changing its permissions.
+    Write,
     Owner,
 }
 
@@ -63,6 +65,7 @@ impl std::fmt::Display for Permission {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Permission::Read => write!(f, "Read"),
+            Permission::Write => write!(f, "Write"),
             Permission::Owner => write!(f, "Owner"),
         }
     }
@@ -71,14 +74,15 @@ impl std::fmt::Display for Permission {
 impl Permission {
     /// Return true if this permission includes the given permission.
     pub fn allows(&self, permission: &Permission) -> bool {
-        self == permission || (self == &Permission::Owner)
+        self.implied_permissions().contains(permission)
     }
 
     /// Return the implied permissions for the given permission.
     pub fn implied_permissions(&self) -> Vec<Permission> {
         match self {
             Permission::Read => vec![Permission::Read],
-            Permission::Owner => vec![Permission::Owner, Permission::Read],
+            Permission::Write => vec![Permission::Write, Permission::Read],
+            Permission::Owner => vec![Permission::Owner, Permission::Write, Permission::Read],
         }
     }
 
@@ -86,7 +90,8 @@ impl Permission {
     /// One of the returned permissions must be granted to the user.
     pub fn required_permissions(&self) -> Vec<Permission> {
         match self {
-            Permission::Read => vec![Permission::Owner, Permission::Read],
+            Permission::Read => vec![Permission::Owner, Permission::Write, Permission::Read],
+            Permission::Write => vec![Permission::Owner, Permission::Write],
             Permission::Owner => vec![Permission::Owner],
         }
     }
@@ -174,6 +179,18 @@ pub struct PermissionListing {
     pub permission: Permission,
 }
 
+/// A permission that applies to a resource, either because it was granted on the resource itself
+/// or because it is inherited from a layer collection that (transitively) contains the resource.
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct EffectivePermissionListing {
+    pub resource_id: ResourceId,
+    pub role: Role,
+    pub permission: Permission,
+    /// The layer collection the permission was granted on, if it is inherited.
+    pub inherited_from: Option<LayerCollectionId>,
+}
+
 #[derive(Debug, Snafu)]
 #[snafu(visibility(pub(crate)), context(suffix(PermissionDbError)))]
 pub enum PermissionDbError {
@@ -196,6 +213,8 @@ pub enum PermissionDbError {
     CannotRevokeOwnPermission,
     #[snafu(display("Cannot grant Owner permission, because there can only be one owner."))]
     CannotGrantOwnerPermission,
+    #[snafu(display("Read-only API tokens cannot be used to create resources."))]
+    ReadOnlyApiToken,
     #[snafu(display("Resource Id {resource_id} is not a valid Uuid."))]
     ResourceIdIsNotAValidUuid { resource_id: String },
     #[snafu(display("An unexpected database error occurred."))]
@@ -217,6 +236,8 @@ pub trait PermissionDb {
     ) -> Result<(), PermissionDbError>;
 
     /// Check `permission` for `resource`.
+    /// Permissions on layer collections are inherited by their layers and sub-collections,
+    /// except for the public `Read` permissions of the root and the unsorted collection.
     async fn has_permission<R: Into<ResourceId> + Send + Sync>(
         &self,
         resource: R,
@@ -270,4 +291,13 @@ pub trait PermissionDb {
         offset: u32,
         limit: u32,
     ) -> Result<Vec<PermissionListing>, PermissionDbError>;
+
+    /// list all effective `permission` for `resource`, including those inherited from layer collections.
+    /// Requires `Owner` permission for `resource`.
+    async fn list_effective_permissions<R: Into<ResourceId> + Send + Sync>(
+        &self,
+        resource: R,
+        offset: u32,
+        limit: u32,
+    ) -> Result<Vec<EffectivePermissionListing>, PermissionDbError>;
 }
@@ -1,13 +1,16 @@
 use super::{
-    Bb8PermissionDbError, Permission, PermissionDb, PermissionDbError, PermissionListing,
-    PostgresPermissionDbError, ResourceId, RoleId,
+    Bb8PermissionDbError, EffectivePermissionListing, Permission, PermissionDb, PermissionDbError,
+    PermissionListing, PostgresPermissionDbError, ResourceId, RoleId,
 };
 use crate::error::Result;
+use crate::layers::listing::LayerCollectionId;
 use crate::pro::contexts::ProPostgresDb;
 use crate::pro::permissions::{
     CannotGrantOwnerPermissionPermissionDbError, CannotRevokeOwnPermissionPermissionDbError,
-    MustBeAdminPermissionDbError, PermissionDeniedPermissionDbError, Role,
+    MustBeAdminPermissionDbError, PermissionDeniedPermissionDbError,
+    ReadOnlyApiTokenPermissionDbError, Role,
 };
+use crate::pro::users::ApiTokenScope;
 use async_trait::async_trait;
 use snafu::{ensure, ResultExt};
 use tokio_postgres::{
@@ -18,9 +21,13 @@ use uuid::Uuid;
 
 // TODO: a postgres specific permission db trait that allows re-using connections and transactions
 
-trait ResourceTypeName {
+pub(crate) trait ResourceTypeName {
     fn resource_type_name(&self) -> &'static str;
 
+    /// The relation that contains the direct and inherited permissions of the resource
+    /// whose id is bound to the query `parameter`, e.g., `$1`.
+    fn effective_permissions_relation(&self, parameter: &str) -> String;
+
     fn uuid(&self) -> Result<Uuid, PermissionDbError>;
 }
 
@@ -35,6 +42,19 @@ impl ResourceTypeName for ResourceId {
         }
     }
 
+    fn effective_permissions_relation(&self, parameter: &str) -> String {
+        match self {
+            ResourceId::Layer(_) => format!("effective_layer_permissions({parameter})"),
+            ResourceId::LayerCollection(_) => {
+                format!("effective_layer_collection_permissions({parameter})")
+            }
+            ResourceId::Project(_) | ResourceId::DatasetId(_) | ResourceId::ModelId(_) => format!(
+                "(SELECT role_id, permission, NULL::uuid AS inherited_from FROM permissions WHERE {resource_type} = {parameter})",
+                resource_type = self.resource_type_name()
+            ),
+        }
+    }
+
     fn uuid(&self) -> Result<Uuid, PermissionDbError> {
         match self {
             ResourceId::Layer(id) => {
@@ -54,6 +74,89 @@ impl ResourceTypeName for ResourceId {
     }
 }
 
+/// The ids of the resources an API token restricts a session to, grouped by resource type.
+///
+/// Each list is `None` if the session is not restricted. Thus, they can be bound to
+/// query filters of the form `$n::uuid[] IS NULL OR id = ANY($n)`.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ApiTokenResourceIds {
+    pub datasets: Option<Vec<Uuid>>,
+    pub layers: Option<Vec<Uuid>>,
+    pub layer_collections: Option<Vec<Uuid>>,
+    pub projects: Option<Vec<Uuid>>,
+}
+
+impl ApiTokenResourceIds {
+    pub fn new(api_token: Option<&ApiTokenScope>) -> Self {
+        let Some(api_token) = api_token.filter(|api_token| !api_token.resources.is_empty()) else {
+            return Self::default();
+        };
+
+        let ids = |resource_type: &str| {
+            Some(
+                api_token
+                    .resources
+                    .iter()
+                    .filter(|resource| resource.resource_type_name() == resource_type)
+                    .filter_map(|resource| resource.uuid().ok())
+                    .collect(),
+            )
+        };
+
+        Self {
+            datasets: ids("dataset_id"),
+            layers: ids("layer_id"),
+            layer_collections: ids("layer_collection_id"),
+            projects: ids("project_id"),
+        }
+    }
+}
+
+/// Check whether `resource` is covered by the resources an API token is restricted to.
+/// Layers and layer collections are also covered if they are (transitively) contained in one of the layer collections.
+async fn is_in_api_token_scope(
+    resource: &ResourceId,
+    api_token: &ApiTokenScope,
+    tx: &tokio_postgres::Transaction<'_>,
+) -> Result<bool, PermissionDbError> {
+    if api_token.resources.is_empty() || api_token.resources.contains(resource) {
+        return Ok(true);
+    }
+
+    let scope = ApiTokenResourceIds::new(Some(api_token));
+
+    let row = match resource {
+        ResourceId::Layer(_) => {
+            let stmt = tx
+                .prepare("SELECT is_layer_in_scope($1, $2, $3);")
+                .await
+                .context(PostgresPermissionDbError)?;
+
+            tx.query_one(
+                &stmt,
+                &[&resource.uuid()?, &scope.layers, &scope.layer_collections],
+            )
+            .await
+            .context(PostgresPermissionDbError)?
+        }
+        ResourceId::LayerCollection(_) => {
+            let stmt = tx
+                .prepare("SELECT is_layer_collection_in_scope($1, $2);")
+                .await
+                .context(PostgresPermissionDbError)?;
+
+            tx.query_one(&stmt, &[&resource.uuid()?, &scope.layer_collections])
+                .await
+                .context(PostgresPermissionDbError)?
+        }
+        ResourceId::Project(_) | ResourceId::DatasetId(_) | ResourceId::ModelId(_) => {
+            return Ok(false)
+        }
+    };
+
+    Ok(row.get(0))
+}
+
 /// internal functionality for transactional permission db
 ///
 /// In contrast to the `PermissionDb` this is not to be used by services but only by the `ProPostgresDb` internally.
@@ -90,6 +193,12 @@ pub trait TxPermissionDb {
         tx: &tokio_postgres::Transaction<'_>,
     ) -> Result<(), PermissionDbError>;
 
+    /// Ensure the session may create resources, i.e., it was not authenticated by a read-only API token.
+    async fn ensure_not_read_only_in_tx(
+        &self,
+        tx: &tokio_postgres::Transaction<'_>,
+    ) -> Result<(), PermissionDbError>;
+
     /// Give `permission` to `role` for `resource`.
     /// Requires `Owner` permission for `resource`.
     async fn add_permission_in_tx<R: Into<ResourceId> + Send + Sync>(
@@ -125,6 +234,14 @@ pub trait TxPermissionDb {
         limit: u32,
         tx: &tokio_postgres::Transaction<'_>,
     ) -> Result<Vec<PermissionListing>, PermissionDbError>;
+
+    async fn list_effective_permissions_in_tx<R: Into<ResourceId> + Send + Sync>(
+        &self,
+        resource: R,
+        offset: u32,
+        limit: u32,
+        tx: &tokio_postgres::Transaction<'_>,
+    ) -> Result<Vec<EffectivePermissionListing>, PermissionDbError>;
 }
 
 #[async_trait]
@@ -142,6 +259,8 @@ where
     ) -> Result<(), PermissionDbError> {
         let resource: ResourceId = resource.into();
 
+        self.ensure_not_read_only_in_tx(tx).await?;
+
         let stmt = tx
             .prepare(&format!(
                 "
@@ -174,12 +293,20 @@ where
     ) -> Result<bool, PermissionDbError> {
         let resource: ResourceId = resource.into();
 
+        if let Some(api_token) = &self.session.api_token {
+            if !api_token.allows(&permission)
+                || !is_in_api_token_scope(&resource, api_token, tx).await?
+            {
+                return Ok(false);
+            }
+        }
+
         // TODO: perform join to get all roles of a user instead of using the roles from the session object?
         let stmt = tx
             .prepare(&format!(
                 "
-            SELECT COUNT(*) FROM permissions WHERE role_id = ANY($1) AND permission = ANY($2) AND {resource_type} = $3;",
-                resource_type = resource.resource_type_name()
+            SELECT COUNT(*) FROM {permissions} p WHERE p.role_id = ANY($1) AND p.permission = ANY($2);",
+                permissions = resource.effective_permissions_relation("$3"),
             ))
             .await.context(PostgresPermissionDbError)?;
 
@@ -234,6 +361,18 @@ where
         Ok(())
     }
 
+    async fn ensure_not_read_only_in_tx(
+        &self,
+        _tx: &tokio_postgres::Transaction<'_>,
+    ) -> Result<(), PermissionDbError> {
+        ensure!(
+            !self.session.is_read_only(),
+            ReadOnlyApiTokenPermissionDbError
+        );
+
+        Ok(())
+    }
+
     async fn add_permission_in_tx<R: Into<ResourceId> + Send + Sync>(
         &self,
         role: RoleId,
@@ -372,6 +511,59 @@ where
 
         Ok(permissions)
     }
+
+    async fn list_effective_permissions_in_tx<R: Into<ResourceId> + Send + Sync>(
+        &self,
+        resource: R,
+        offset: u32,
+        limit: u32,
+        tx: &tokio_postgres::Transaction<'_>,
+    ) -> Result<Vec<EffectivePermissionListing>, PermissionDbError> {
+        let resource: ResourceId = resource.into();
+
+        self.ensure_permission_in_tx(resource.clone(), Permission::Owner, tx)
+            .await?;
+
+        let stmt = tx
+            .prepare(&format!(
+                "
+            SELECT 
+                r.id, r.name, p.permission, p.inherited_from
+            FROM 
+                {permissions} p JOIN roles r ON (p.role_id = r.id) 
+            ORDER BY r.name ASC, p.permission DESC
+            OFFSET $2
+            LIMIT $3;",
+                permissions = resource.effective_permissions_relation("$1"),
+            ))
+            .await
+            .context(PostgresPermissionDbError)?;
+
+        let rows = tx
+            .query(
+                &stmt,
+                &[&resource.uuid()?, &(i64::from(offset)), &(i64::from(limit))],
+            )
+            .await
+            .context(PostgresPermissionDbError)?;
+
+        let permissions = rows
+            .into_iter()
+            .map(|row| EffectivePermissionListing {
+                resource_id: resource.clone(),
+                role: Role {
+                    id: row.get(0),
+                    name: row.get(1),
+                },
+                permission: row.get(2),
+                inherited_from: row
+                    .get::<_, Option<Uuid>>(3)
+                    .map(|id| LayerCollectionId(id.to_string())),
+            })
+            .collect();
+
+        Ok(permissions)
+    }
 }
 
 #[async_trait]
@@ -544,4 +736,26 @@ where
 
         Ok(permissions)
     }
+
+    async fn list_effective_permissions<R: Into<ResourceId> + Send + Sync>(
+        &self,
+        resource: R,
+        offset: u32,
+        limit: u32,
+    ) -> Result<Vec<EffectivePermissionListing>, PermissionDbError> {
+        let mut conn = self.conn_pool.get().await.context(Bb8PermissionDbError)?;
+        let tx = conn
+            .build_transaction()
+            .start()
+            .await
+            .context(PostgresPermissionDbError)?;
+
+        let permissions = self
+            .list_effective_permissions_in_tx(resource, offset, limit, &tx)
+            .await?;
+
+        tx.commit().await.context(PostgresPermissionDbError)?;
+
+        Ok(permissions)
+    }
 }
@@ -1,6 +1,6 @@
 use crate::error::Result;
 use crate::pro::contexts::ProPostgresDb;
-use crate::pro::permissions::postgres_permissiondb::TxPermissionDb;
+use crate::pro::permissions::postgres_permissiondb::{ApiTokenResourceIds, TxPermissionDb};
 use crate::pro::permissions::Permission;
 use crate::pro::users::UserId;
 use crate::projects::error::ProjectNotFoundProjectDbError;
@@ -51,6 +51,7 @@ where
         FROM user_permitted_projects u JOIN project_versions p ON (u.project_id = p.project_id)
         WHERE
             u.user_id = $1
+            AND ($4::uuid[] IS NULL OR u.project_id = ANY($4::uuid[]))
             AND p.changed >= ALL (SELECT changed FROM project_versions WHERE project_id = p.project_id)
         ORDER BY p.{}
         LIMIT $2
@@ -66,6 +67,7 @@ where
                     &self.session.user.id,
                     &i64::from(options.limit),
                     &i64::from(options.offset),
+                    &ApiTokenResourceIds::new(self.session.api_token.as_ref()).projects,
                 ],
             )
             .await
@@ -89,6 +91,12 @@ where
             .await
             .context(PostgresProjectDbError)?;
 
+        self.ensure_not_read_only_in_tx(&trans)
+            .await
+            .boxed_context(AccessFailedProjectDbError {
+                project: project.id,
+            })?;
+
         let version_id = insert_project(&trans, &project).await?;
 
         let stmt = trans
@@ -141,7 +149,7 @@ where
             .await
             .context(PostgresProjectDbError)?;
 
-        self.ensure_permission_in_tx(update.id.into(), Permission::Owner, &trans)
+        self.ensure_permission_in_tx(update.id.into(), Permission::Write, &trans)
             .await
             .boxed_context(AccessFailedProjectDbError { project: update.id })?;
 
@@ -178,6 +178,9 @@ pub async fn start_pro_server(static_files_dir: Option<PathBuf>) -> Result<()> {
 
     register_gdal_drivers_from_list(config::get_config_element::<config::Gdal>()?.allowed_drivers);
 
+    crate::datasets::resumable_upload::spawn_expired_upload_cleanup()?;
+    crate::workflows::export::spawn_expired_export_cleanup()?;
+
     start_postgres(
         data_path_config,
         tiling_spec,
@@ -220,6 +223,8 @@ async fn start_postgres(
         )
         .await?;
 
+        ctx.restore_recurring_tasks().await?;
+
         start(
             static_files_dir,
             web_config.bind_address,
@@ -2,6 +2,7 @@ use super::users::UserSession;
 use crate::{
     error,
     tasks::{
+        CronSchedule, RecurringTask, RecurringTaskFactory, RecurringTaskId,
         SimpleTaskManagerBackend, SimpleTaskManagerContext, Task, TaskError, TaskId,
         TaskListOptions, TaskManager, TaskStatus, TaskStatusWithId,
     },
@@ -17,10 +18,39 @@ const ADMIN_ONLY_TASKS: [&str; 3] = [
     crate::api::handlers::ebv::EBV_REMOVE_OVERVIEW_TASK_TYPE,
 ];
 
-#[derive(Default)]
 pub struct ProTaskManagerBackend {
     simple_task_manager: SimpleTaskManagerBackend,
-    task_type_by_id: RwLock<HashMap<TaskId, &'static str>>,
+    task_type_by_id: RwLock<HashMap<TaskId, String>>,
+}
+
+impl ProTaskManagerBackend {
+    pub fn new(simple_task_manager: SimpleTaskManagerBackend) -> Self {
+        Self {
+            simple_task_manager,
+            task_type_by_id: Default::default(),
+        }
+    }
+
+    /// The task manager that runs the tasks of all users
+    pub fn simple_task_manager(&self) -> &SimpleTaskManagerBackend {
+        &self.simple_task_manager
+    }
+
+    /// Returns the type of a task, also for tasks that ran before the last restart.
+    async fn task_type(&self, task_id: TaskId) -> Result<String, TaskError> {
+        if let Some(task_type) = self.task_type_by_id.read().await.get(&task_id) {
+            return Ok(task_type.clone());
+        }
+
+        let task_type = self.simple_task_manager.task_type(task_id).await?;
+
+        self.task_type_by_id
+            .write()
+            .await
+            .insert(task_id, task_type.clone());
+
+        Ok(task_type)
+    }
 }
 
 pub struct ProTaskManager {
@@ -34,10 +64,7 @@ impl ProTaskManager {
     }
 }
 
-fn check_task_type_is_allowed(
-    session: &UserSession,
-    task_type: &'static str,
-) -> Result<(), TaskError> {
+fn check_task_type_is_allowed(session: &UserSession, task_type: &str) -> Result<(), TaskError> {
     if ADMIN_ONLY_TASKS.contains(&task_type) && !session.is_admin() {
         return Err(crate::tasks::TaskError::TaskManagerOperationFailed {
             source: Box::new(error::Error::PermissionDenied),
@@ -62,28 +89,20 @@ impl TaskManager<SimpleTaskManagerContext> for ProTaskManager {
         let task_id = self
             .backend
             .simple_task_manager
-            .schedule_task(task, notify)
+            .schedule_task_for_owner(task, notify, Some(self.session.user.id.to_string()))
             .await?;
 
         self.backend
             .task_type_by_id
             .write()
             .await
-            .insert(task_id, task_type);
+            .insert(task_id, task_type.to_string());
 
         Ok(task_id)
     }
 
     async fn get_task_status(&self, task_id: TaskId) -> Result<TaskStatus, TaskError> {
-        check_task_type_is_allowed(
-            &self.session,
-            self.backend
-                .task_type_by_id
-                .read()
-                .await
-                .get(&task_id)
-                .ok_or(TaskError::TaskNotFound { task_id })?,
-        )?;
+        check_task_type_is_allowed(&self.session, &self.backend.task_type(task_id).await?)?;
 
         // TODO: check permissions for user tasks
 
@@ -99,42 +118,20 @@ impl TaskManager<SimpleTaskManagerContext> for ProTaskManager {
     ) -> Result<Vec<TaskStatusWithId>, TaskError> {
         // TODO: check permissions for user tasks
 
-        let tasks = self
-            .backend
-            .simple_task_manager
-            .list_tasks(TaskListOptions {
-                filter: options.filter,
-                offset: 0,
-                limit: u32::MAX,
-            })
-            .await?;
-
-        let task_types = self.backend.task_type_by_id.read().await;
+        let excluded_task_types: &[&str] = if self.session.is_admin() {
+            &[]
+        } else {
+            &ADMIN_ONLY_TASKS
+        };
 
-        Ok(tasks
-            .iter()
-            .filter(|t| {
-                if let Some(task_type) = task_types.get(&t.task_id) {
-                    return check_task_type_is_allowed(&self.session, task_type).is_ok();
-                }
-                false
-            })
-            .skip(options.offset as usize)
-            .take(options.limit as usize)
-            .cloned()
-            .collect::<Vec<_>>())
+        self.backend
+            .simple_task_manager
+            .list_tasks_excluding_types(options, excluded_task_types)
+            .await
     }
 
     async fn abort_tasks(&self, task_id: TaskId, force: bool) -> Result<(), TaskError> {
-        check_task_type_is_allowed(
-            &self.session,
-            self.backend
-                .task_type_by_id
-                .read()
-                .await
-                .get(&task_id)
-                .ok_or(TaskError::TaskNotFound { task_id })?,
-        )?;
+        check_task_type_is_allowed(&self.session, &self.backend.task_type(task_id).await?)?;
 
         // TODO: check permissions for user tasks
 
@@ -143,6 +140,44 @@ impl TaskManager<SimpleTaskManagerContext> for ProTaskManager {
             .abort_tasks(task_id, force)
             .await
     }
+
+    async fn add_recurring_task(
+        &self,
+        schedule: CronSchedule,
+        definition: serde_json::Value,
+        factory: RecurringTaskFactory<SimpleTaskManagerContext>,
+    ) -> Result<RecurringTaskId, TaskError> {
+        self.backend
+            .simple_task_manager
+            .schedule_persistent_recurring_task(
+                schedule,
+                Some(self.session.user.id.to_string()),
+                definition,
+                factory,
+            )
+            .await
+    }
+
+    async fn list_recurring_tasks(&self) -> Result<Vec<RecurringTask>, TaskError> {
+        Ok(self
+            .backend
+            .simple_task_manager
+            .list_recurring_tasks_of_owner(Some(&self.session.user.id.to_string()))
+            .await)
+    }
+
+    async fn remove_recurring_task(
+        &self,
+        recurring_task_id: RecurringTaskId,
+    ) -> Result<(), TaskError> {
+        self.backend
+            .simple_task_manager
+            .cancel_recurring_task_of_owner(
+                recurring_task_id,
+                Some(&self.session.user.id.to_string()),
+            )
+            .await
+    }
 }
 
 #[cfg(test)]
@@ -150,11 +185,178 @@ mod tests {
 
     use super::*;
     use crate::{
-        contexts::{ApplicationContext, SessionContext},
+        contexts::{ApplicationContext, MockableSession, SessionContext},
         pro::{contexts::ProPostgresContext, ge_context, users::UserAuth},
+        tasks::{TaskLimits, TaskStatusInfo},
     };
+    use geoengine_datatypes::error::ErrorSource;
     use tokio_postgres::NoTls;
 
+    struct SubTask;
+
+    #[async_trait::async_trait]
+    impl Task<SimpleTaskManagerContext> for SubTask {
+        async fn run(
+            &self,
+            _ctx: SimpleTaskManagerContext,
+        ) -> Result<Box<dyn TaskStatusInfo>, Box<dyn ErrorSource>> {
+            Ok(().boxed())
+        }
+
+        async fn cleanup_on_error(
+            &self,
+            _ctx: SimpleTaskManagerContext,
+        ) -> Result<(), Box<dyn ErrorSource>> {
+            Ok(())
+        }
+
+        fn task_type(&self) -> &'static str {
+            "subTask"
+        }
+
+        fn task_description(&self) -> String {
+            String::new()
+        }
+    }
+
+    struct ParentTask {
+        task_manager: Arc<ProTaskManager>,
+    }
+
+    #[async_trait::async_trait]
+    impl Task<SimpleTaskManagerContext> for ParentTask {
+        async fn run(
+            &self,
+            _ctx: SimpleTaskManagerContext,
+        ) -> Result<Box<dyn TaskStatusInfo>, Box<dyn ErrorSource>> {
+            let (notify, subtask_finished) = oneshot::channel();
+
+            self.task_manager
+                .schedule_task(SubTask.boxed(), Some(notify))
+                .await
+                .map_err(ErrorSource::boxed)?;
+
+            // the subtask must start while its parent occupies the only slot of the user
+            let _status = subtask_finished.await;
+
+            Ok(().boxed())
+        }
+
+        async fn cleanup_on_error(
+            &self,
+            _ctx: SimpleTaskManagerContext,
+        ) -> Result<(), Box<dyn ErrorSource>> {
+            Ok(())
+        }
+
+        fn task_type(&self) -> &'static str {
+            "parentTask"
+        }
+
+        fn task_description(&self) -> String {
+            String::new()
+        }
+    }
+
+    #[tokio::test]
+    async fn it_does_not_limit_subtasks_of_waiting_parents() {
+        let backend = Arc::new(ProTaskManagerBackend::new(SimpleTaskManagerBackend::new(
+            TaskLimits {
+                max_concurrent_tasks: 1,
+                max_concurrent_tasks_per_owner: 1,
+                ..Default::default()
+            },
+        )));
+        let task_manager = Arc::new(ProTaskManager::new(backend, UserSession::mock()));
+
+        let (notify, parent_finished) = oneshot::channel();
+        task_manager
+            .schedule_task(
+                ParentTask {
+                    task_manager: task_manager.clone(),
+                }
+                .boxed(),
+                Some(notify),
+            )
+            .await
+            .unwrap();
+
+        let status = tokio::time::timeout(std::time::Duration::from_secs(5), parent_finished)
+            .await
+            .expect("the parent task should not wait for its subtask forever")
+            .unwrap();
+        assert!(status.is_completed());
+    }
+
+    struct AdminOnlyTask;
+
+    #[async_trait::async_trait]
+    impl Task<SimpleTaskManagerContext> for AdminOnlyTask {
+        async fn run(
+            &self,
+            _ctx: SimpleTaskManagerContext,
+        ) -> Result<Box<dyn TaskStatusInfo>, Box<dyn ErrorSource>> {
+            Ok(().boxed())
+        }
+
+        async fn cleanup_on_error(
+            &self,
+            _ctx: SimpleTaskManagerContext,
+        ) -> Result<(), Box<dyn ErrorSource>> {
+            Ok(())
+        }
+
+        fn task_type(&self) -> &'static str {
+            crate::api::handlers::ebv::EBV_OVERVIEW_TASK_TYPE
+        }
+
+        fn task_description(&self) -> String {
+            String::new()
+        }
+    }
+
+    #[ge_context::test]
+    async fn it_lists_admin_only_tasks_only_to_admins(app_ctx: ProPostgresContext<NoTls>) {
+        async fn list(tasks: &ProTaskManager, offset: u32, limit: u32) -> Vec<TaskId> {
+            tasks
+                .list_tasks(TaskListOptions {
+                    filter: None,
+                    offset,
+                    limit,
+                })
+                .await
+                .unwrap()
+                .into_iter()
+                .map(|task| task.task_id)
+                .collect()
+        }
+
+        let admin_tasks = app_ctx
+            .session_context(UserSession::admin_session())
+            .tasks();
+
+        let session = app_ctx.create_anonymous_session().await.unwrap();
+        let user_tasks = app_ctx.session_context(session).tasks();
+
+        let user_task_id = user_tasks
+            .schedule_task(SubTask.boxed(), None)
+            .await
+            .unwrap();
+        let admin_task_id = admin_tasks
+            .schedule_task(AdminOnlyTask.boxed(), None)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            list(&admin_tasks, 0, 10).await,
+            vec![admin_task_id, user_task_id]
+        );
+
+        // the offset and limit apply to the tasks that the user is allowed to see
+        assert_eq!(list(&user_tasks, 0, 1).await, vec![user_task_id]);
+        assert!(list(&user_tasks, 1, 1).await.is_empty());
+    }
+
     #[ge_context::test]
     async fn it_lists(app_ctx: ProPostgresContext<NoTls>) {
         let session = app_ctx.create_anonymous_session().await.unwrap();
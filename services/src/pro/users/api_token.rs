@@ -0,0 +1,127 @@
+use crate::identifier;
+use crate::pro::permissions::{Permission, ResourceId};
+use geoengine_datatypes::primitives::DateTime;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use utoipa::ToSchema;
+use validator::Validate;
+
+identifier!(ApiTokenId);
+
+/// Prefix of all API tokens. It distinguishes them from session ids in the `Authorization` header.
+pub const API_TOKEN_PREFIX: &str = "ge_";
+
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone, ToSchema, Validate)]
+#[serde(rename_all = "camelCase")]
+#[schema(example = json!({
+    "name": "CI pipeline",
+    "validUntil": "2030-01-01T00:00:00Z",
+    "readOnly": true,
+    "resources": [
+        {
+            "type": "DatasetId",
+            "id": "8b3f2f53-dcd7-4d6d-9c2c-6a3b5a9ef5d1"
+        }
+    ]
+}))]
+pub struct CreateApiToken {
+    #[validate(length(min = 1))]
+    pub name: String,
+    pub valid_until: DateTime,
+    /// A read-only token only grants `Read` permissions and cannot create resources.
+    #[serde(default)]
+    pub read_only: bool,
+    /// Restricts the token to these resources and the contents of these layer collections.
+    /// An empty list grants access to all resources of the user.
+    #[serde(default)]
+    pub resources: Vec<ResourceId>,
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiToken {
+    pub id: ApiTokenId,
+    pub name: String,
+    pub created: DateTime,
+    pub valid_until: DateTime,
+    pub read_only: bool,
+    pub resources: Vec<ResourceId>,
+}
+
+/// A newly created API token. The `token` is only returned once and cannot be retrieved later.
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreatedApiToken {
+    pub api_token: ApiToken,
+    pub token: String,
+}
+
+/// The restrictions of a session that was authenticated by an API token
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiTokenScope {
+    pub id: ApiTokenId,
+    pub read_only: bool,
+    pub resources: Vec<ResourceId>,
+}
+
+impl ApiTokenScope {
+    /// Return true if the token may be used for actions that require `permission`.
+    pub fn allows(&self, permission: &Permission) -> bool {
+        !self.read_only || permission == &Permission::Read
+    }
+}
+
+/// Generate a new random API token.
+pub fn generate_api_token() -> String {
+    let mut secret = [0; 32];
+    rand::thread_rng().fill_bytes(&mut secret);
+
+    format!("{API_TOKEN_PREFIX}{}", hex::encode(secret))
+}
+
+/// Hash an API token for storing and looking it up in the database.
+/// The tokens are random, so there is no need for salting or slow hashing.
+pub fn hash_api_token(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_generates_distinct_tokens() {
+        let token = generate_api_token();
+
+        assert!(token.starts_with(API_TOKEN_PREFIX));
+        assert_eq!(token.len(), API_TOKEN_PREFIX.len() + 64);
+        assert_ne!(token, generate_api_token());
+    }
+
+    #[test]
+    fn it_hashes_tokens() {
+        let token = generate_api_token();
+
+        assert_eq!(hash_api_token(&token), hash_api_token(&token));
+        assert_ne!(hash_api_token(&token), token);
+        assert_ne!(
+            hash_api_token(&token),
+            hash_api_token(&generate_api_token())
+        );
+    }
+
+    #[test]
+    fn it_restricts_read_only_tokens() {
+        let scope = ApiTokenScope {
+            id: ApiTokenId::from_u128(1),
+            read_only: true,
+            resources: vec![],
+        };
+
+        assert!(scope.allows(&Permission::Read));
+        assert!(!scope.allows(&Permission::Write));
+        assert!(!scope.allows(&Permission::Owner));
+    }
+}
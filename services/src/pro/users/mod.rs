@@ -1,9 +1,13 @@
+mod api_token;
 mod oidc;
 mod postgres_userdb;
 mod session;
 mod user;
 mod userdb;
 
+pub use api_token::{
+    ApiToken, ApiTokenId, ApiTokenScope, CreateApiToken, CreatedApiToken, API_TOKEN_PREFIX,
+};
 pub(crate) use oidc::OidcError;
 pub(super) use oidc::{AuthCodeRequestURL, AuthCodeResponse, OidcDisabled, OidcRequestDb};
 #[cfg(test)]
@@ -1,14 +1,17 @@
 use crate::contexts::SessionId;
 use crate::error::Result;
+use crate::layers::listing::LayerCollectionId;
 use crate::pro::contexts::ProPostgresDb;
-use crate::pro::permissions::postgres_permissiondb::TxPermissionDb;
-use crate::pro::permissions::{Role, RoleDescription, RoleId};
+use crate::pro::permissions::postgres_permissiondb::{ResourceTypeName, TxPermissionDb};
+use crate::pro::permissions::{ResourceId, Role, RoleDescription, RoleId};
+use crate::pro::users::api_token::{generate_api_token, hash_api_token};
 use crate::pro::users::oidc::ExternalUserClaims;
 use crate::pro::users::userdb::{
     CannotRevokeRoleThatIsNotAssignedRoleDbError, RoleIdDoesNotExistRoleDbError,
 };
 use crate::pro::users::{
-    User, UserCredentials, UserDb, UserId, UserInfo, UserRegistration, UserSession,
+    ApiToken, ApiTokenId, ApiTokenScope, CreateApiToken, CreatedApiToken, User, UserCredentials,
+    UserDb, UserId, UserInfo, UserRegistration, UserSession,
 };
 use crate::projects::{ProjectId, STRectangle};
 use crate::util::postgres::PostgresErrorExt;
@@ -19,9 +22,14 @@ use async_trait::async_trait;
 use bb8_postgres::{
     tokio_postgres::tls::MakeTlsConnect, tokio_postgres::tls::TlsConnect, tokio_postgres::Socket,
 };
-use geoengine_datatypes::primitives::Duration;
+use geoengine_datatypes::dataset::{DatasetId, LayerId};
+use geoengine_datatypes::error::BoxedResultExt;
+use geoengine_datatypes::primitives::{DateTime, Duration};
+use geoengine_datatypes::pro::MlModelId;
 use pwhash::bcrypt;
 use snafu::{ensure, ResultExt};
+use std::collections::HashMap;
+use tokio_postgres::Transaction;
 use uuid::Uuid;
 
 use super::userdb::{
@@ -177,6 +185,7 @@ where
             project: None,
             view: None,
             roles: vec![user_id.into(), Role::anonymous_role_id()],
+            api_token: None,
         })
     }
 
@@ -260,6 +269,7 @@ where
                 project: None,
                 view: None,
                 roles,
+                api_token: None,
             })
         } else {
             Err(error::Error::LoginFailed)
@@ -394,6 +404,7 @@ where
             project: None,
             view: None,
             roles,
+            api_token: None,
         })
     }
 
@@ -437,6 +448,7 @@ where
             project: row.get::<usize, Option<Uuid>>(5).map(ProjectId),
             view: row.get(6),
             roles: vec![],
+            api_token: None,
         };
 
         let stmt = tx
@@ -453,6 +465,110 @@ where
 
         Ok(session)
     }
+
+    async fn user_session_by_api_token(&self, token: &str) -> Result<UserSession> {
+        let mut conn = self.pool.get().await?;
+
+        let tx = conn.build_transaction().start().await?;
+
+        let stmt = tx
+            .prepare(
+                "
+            SELECT 
+                t.id,
+                t.read_only,
+                t.created,
+                t.valid_until,
+                u.id,   
+                u.email,
+                u.real_name
+            FROM 
+                api_tokens t JOIN users u ON (t.user_id = u.id)
+            WHERE t.token_hash = $1 AND CURRENT_TIMESTAMP < t.valid_until;",
+            )
+            .await?;
+
+        let row = tx
+            .query_opt(&stmt, &[&hash_api_token(token)])
+            .await?
+            .ok_or(error::Error::InvalidApiToken)?;
+
+        let api_token_id: ApiTokenId = row.get(0);
+
+        let mut session = UserSession {
+            // an API token has no session in the database, so its id identifies the session
+            id: SessionId(api_token_id.0),
+            user: UserInfo {
+                id: row.get(4),
+                email: row.get(5),
+                real_name: row.get(6),
+            },
+            created: row.get(2),
+            valid_until: row.get(3),
+            project: None,
+            view: None,
+            roles: vec![],
+            api_token: Some(ApiTokenScope {
+                id: api_token_id,
+                read_only: row.get(1),
+                resources: load_api_token_resources(&tx, &[api_token_id])
+                    .await?
+                    .remove(&api_token_id)
+                    .unwrap_or_default(),
+            }),
+        };
+
+        let stmt = tx
+            .prepare(
+                "
+            SELECT role_id FROM user_roles WHERE user_id = $1;
+            ",
+            )
+            .await?;
+
+        let rows = tx.query(&stmt, &[&session.user.id]).await?;
+
+        session.roles = rows.into_iter().map(|row| row.get(0)).collect();
+
+        Ok(session)
+    }
+}
+
+/// Load the resources the given API tokens are restricted to.
+async fn load_api_token_resources(
+    tx: &Transaction<'_>,
+    api_tokens: &[ApiTokenId],
+) -> Result<HashMap<ApiTokenId, Vec<ResourceId>>> {
+    let stmt = tx
+        .prepare(
+            "
+        SELECT 
+            api_token_id, dataset_id, layer_id, layer_collection_id, project_id, model_id
+        FROM api_token_resources
+        WHERE api_token_id = ANY($1);",
+        )
+        .await?;
+
+    let rows = tx.query(&stmt, &[&api_tokens]).await?;
+
+    let mut resources: HashMap<ApiTokenId, Vec<ResourceId>> = HashMap::new();
+    for row in rows {
+        let resource = if let Some(dataset_id) = row.get::<_, Option<Uuid>>(1) {
+            ResourceId::DatasetId(DatasetId(dataset_id))
+        } else if let Some(layer_id) = row.get::<_, Option<Uuid>>(2) {
+            ResourceId::Layer(LayerId(layer_id.to_string()))
+        } else if let Some(layer_collection_id) = row.get::<_, Option<Uuid>>(3) {
+            ResourceId::LayerCollection(LayerCollectionId(layer_collection_id.to_string()))
+        } else if let Some(project_id) = row.get::<_, Option<Uuid>>(4) {
+            ResourceId::Project(ProjectId(project_id))
+        } else {
+            ResourceId::ModelId(MlModelId(row.get(5)))
+        };
+
+        resources.entry(row.get(0)).or_default().push(resource);
+    }
+
+    Ok(resources)
 }
 
 #[async_trait]
@@ -636,6 +752,142 @@ where
 
         Ok(())
     }
+
+    async fn create_api_token(&self, api_token: CreateApiToken) -> Result<CreatedApiToken> {
+        ensure!(
+            self.session.api_token.is_none(),
+            error::ApiTokenCannotManageApiTokens
+        );
+        ensure!(
+            api_token.valid_until > DateTime::now(),
+            error::ApiTokenExpiryMustBeInTheFuture
+        );
+
+        let mut conn = self.conn_pool.get().await?;
+        let tx = conn.build_transaction().start().await?;
+
+        let id = ApiTokenId::new();
+        let token = generate_api_token();
+
+        let stmt = tx
+            .prepare(
+                "
+            INSERT INTO api_tokens (
+                id, user_id, name, token_hash, read_only, created, valid_until
+            )
+            VALUES ($1, $2, $3, $4, $5, CURRENT_TIMESTAMP, $6)
+            RETURNING created;",
+            )
+            .await?;
+
+        let row = tx
+            .query_one(
+                &stmt,
+                &[
+                    &id,
+                    &self.session.user.id,
+                    &api_token.name,
+                    &hash_api_token(&token),
+                    &api_token.read_only,
+                    &api_token.valid_until,
+                ],
+            )
+            .await
+            .map_unique_violation("api_tokens", "user_id_name", || error::Error::Duplicate {
+                reason: format!("API token with name {} already exists", api_token.name),
+            })?;
+
+        for resource in &api_token.resources {
+            let stmt = tx
+                .prepare(&format!(
+                    "INSERT INTO api_token_resources (api_token_id, {resource_type}) VALUES ($1, $2);",
+                    resource_type = resource.resource_type_name()
+                ))
+                .await?;
+
+            tx.execute(
+                &stmt,
+                &[&id, &resource.uuid().boxed_context(error::PermissionDb)?],
+            )
+            .await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(CreatedApiToken {
+            api_token: ApiToken {
+                id,
+                name: api_token.name,
+                created: row.get(0),
+                valid_until: api_token.valid_until,
+                read_only: api_token.read_only,
+                resources: api_token.resources,
+            },
+            token,
+        })
+    }
+
+    async fn list_api_tokens(&self) -> Result<Vec<ApiToken>> {
+        ensure!(
+            self.session.api_token.is_none(),
+            error::ApiTokenCannotManageApiTokens
+        );
+
+        let mut conn = self.conn_pool.get().await?;
+        let tx = conn.build_transaction().start().await?;
+
+        let stmt = tx
+            .prepare(
+                "
+            SELECT id, name, created, valid_until, read_only
+            FROM api_tokens
+            WHERE user_id = $1
+            ORDER BY created DESC, name ASC;",
+            )
+            .await?;
+
+        let rows = tx.query(&stmt, &[&self.session.user.id]).await?;
+
+        let ids: Vec<ApiTokenId> = rows.iter().map(|row| row.get(0)).collect();
+        let mut resources = load_api_token_resources(&tx, &ids).await?;
+
+        tx.commit().await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                let id = row.get(0);
+                ApiToken {
+                    id,
+                    name: row.get(1),
+                    created: row.get(2),
+                    valid_until: row.get(3),
+                    read_only: row.get(4),
+                    resources: resources.remove(&id).unwrap_or_default(),
+                }
+            })
+            .collect())
+    }
+
+    async fn revoke_api_token(&self, api_token: ApiTokenId) -> Result<()> {
+        ensure!(
+            self.session.api_token.is_none(),
+            error::ApiTokenCannotManageApiTokens
+        );
+
+        let conn = self.conn_pool.get().await?;
+        let stmt = conn
+            .prepare("DELETE FROM api_tokens WHERE id = $1 AND user_id = $2;")
+            .await?;
+
+        let deleted = conn
+            .execute(&stmt, &[&api_token, &self.session.user.id])
+            .await?;
+
+        ensure!(deleted > 0, error::UnknownApiToken { api_token });
+
+        Ok(())
+    }
 }
 
 #[async_trait]
@@ -3,11 +3,13 @@ use crate::contexts::{ApplicationContext, MockableSession, Session, SessionId};
 use crate::error;
 use crate::pro::contexts::ProPostgresContext;
 use crate::pro::permissions::{Role, RoleId};
-use crate::pro::users::UserId;
+use crate::pro::users::{ApiTokenScope, UserAuth, UserId, API_TOKEN_PREFIX};
 use crate::projects::{ProjectId, STRectangle};
 use crate::util::Identifier;
 use actix_http::Payload;
+use actix_web::http::header;
 use actix_web::{web, FromRequest, HttpRequest};
+use actix_web_httpauth::headers::authorization::{Bearer, Scheme};
 use bb8_postgres::tokio_postgres::NoTls;
 use futures::future::err;
 use futures_util::future::LocalBoxFuture;
@@ -15,6 +17,7 @@ use futures_util::FutureExt;
 use geoengine_datatypes::primitives::DateTime;
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
+use uuid::Uuid;
 
 #[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone, ToSchema)]
 #[serde(rename_all = "camelCase")]
@@ -34,6 +37,9 @@ pub struct UserSession {
     pub project: Option<ProjectId>,
     pub view: Option<STRectangle>,
     pub roles: Vec<RoleId>, // a user has a default role (= its user id) and other additonal roles
+    /// The restrictions of the session if it was authenticated by an API token
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_token: Option<ApiTokenScope>,
 }
 
 impl UserSession {
@@ -52,11 +58,23 @@ impl UserSession {
             project: None,
             view: None,
             roles: vec![role],
+            api_token: None,
         }
     }
 
+    /// Read-only and resource-scoped API tokens do not grant admin privileges.
     pub fn is_admin(&self) -> bool {
         self.roles.contains(&Role::admin_role_id())
+            && self.api_token.as_ref().map_or(true, |api_token| {
+                !api_token.read_only && api_token.resources.is_empty()
+            })
+    }
+
+    /// Return true if the session was authenticated by a read-only API token.
+    pub fn is_read_only(&self) -> bool {
+        self.api_token
+            .as_ref()
+            .map_or(false, |api_token| api_token.read_only)
     }
 }
 
@@ -75,6 +93,7 @@ impl MockableSession for UserSession {
             project: None,
             view: None,
             roles: vec![user_id.into(), Role::registered_user_role_id()],
+            api_token: None,
         }
     }
 }
@@ -99,6 +118,10 @@ impl Session for UserSession {
     fn view(&self) -> Option<&STRectangle> {
         self.view.as_ref()
     }
+
+    fn owner(&self) -> Uuid {
+        self.user.id.0
+    }
 }
 
 impl FromRequest for UserSession {
@@ -106,21 +129,46 @@ impl FromRequest for UserSession {
     type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
 
     fn from_request(req: &HttpRequest, _payload: &mut Payload) -> Self::Future {
-        let token = match get_token(req) {
-            Ok(token) => token,
-            Err(error) => return Box::pin(err(error)),
-        };
-
         let pg_ctx = req
             .app_data::<web::Data<ProPostgresContext<NoTls>>>()
             .expect(
             "Application context should be present because it is set during server initialization.",
         );
         let pg_ctx = pg_ctx.get_ref().clone();
+
+        if let Some(api_token) = get_api_token(req) {
+            return async move {
+                pg_ctx
+                    .user_session_by_api_token(&api_token)
+                    .await
+                    .map_err(|error| error::Error::Unauthorized {
+                        source: Box::new(error),
+                    })
+            }
+            .boxed_local();
+        }
+
+        let token = match get_token(req) {
+            Ok(token) => token,
+            Err(error) => return Box::pin(err(error)),
+        };
+
         return async move { pg_ctx.session_by_id(token).await.map_err(Into::into) }.boxed_local();
     }
 }
 
+/// Extract an API token from the `Authorization` header.
+/// Returns `None` if the bearer token is not an API token, e.g., a session id.
+fn get_api_token(req: &HttpRequest) -> Option<String> {
+    let header = req.headers().get(header::AUTHORIZATION)?;
+    let scheme = Bearer::parse(header).ok()?;
+
+    scheme
+        .token()
+        .starts_with(API_TOKEN_PREFIX)
+        .then(|| scheme.token().to_string())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -153,6 +201,7 @@ mod tests {
             created: DateTime::from_str("2020-01-01T00:00:00Z").unwrap(),
             valid_until: DateTime::from_str("2021-01-01T00:00:00Z").unwrap(),
             roles: vec![RoleId::from_str("da3825dd-6240-460d-a324-02bd06704aaa").unwrap()],
+            api_token: None,
         };
 
         assert_eq!(
@@ -2,7 +2,10 @@ use crate::contexts::SessionId;
 use crate::error::Result;
 use crate::pro::permissions::{RoleDescription, RoleId};
 use crate::pro::users::oidc::ExternalUserClaims;
-use crate::pro::users::{UserCredentials, UserId, UserRegistration, UserSession};
+use crate::pro::users::{
+    ApiToken, ApiTokenId, CreateApiToken, CreatedApiToken, UserCredentials, UserId,
+    UserRegistration, UserSession,
+};
 use crate::projects::{ProjectId, STRectangle};
 use async_trait::async_trait;
 use geoengine_datatypes::primitives::Duration;
@@ -54,6 +57,14 @@ pub trait UserAuth {
     /// This call fails if the session is invalid.
     ///
     async fn user_session_by_id(&self, session: SessionId) -> Result<UserSession>;
+
+    /// Get session for an API token
+    ///
+    /// # Errors
+    ///
+    /// This call fails if the token is invalid, expired or revoked.
+    ///
+    async fn user_session_by_api_token(&self, token: &str) -> Result<UserSession>;
 }
 
 #[async_trait]
@@ -149,6 +160,30 @@ pub trait UserDb: Send + Sync {
         user: &UserId,
         new_available_quota: i64,
     ) -> Result<()>;
+
+    /// Creates a new API token for the current user. Only its hash is stored.
+    ///
+    /// # Errors
+    ///
+    /// This call fails if the session was authenticated by an API token, the name is already taken or the expiry date is not in the future
+    ///
+    async fn create_api_token(&self, api_token: CreateApiToken) -> Result<CreatedApiToken>;
+
+    /// Lists the API tokens of the current user
+    ///
+    /// # Errors
+    ///
+    /// This call fails if the session was authenticated by an API token
+    ///
+    async fn list_api_tokens(&self) -> Result<Vec<ApiToken>>;
+
+    /// Revokes an API token of the current user
+    ///
+    /// # Errors
+    ///
+    /// This call fails if the session was authenticated by an API token or the token is unknown
+    ///
+    async fn revoke_api_token(&self, api_token: ApiTokenId) -> Result<()>;
 }
 
 #[derive(Debug, Snafu)]
@@ -82,6 +82,7 @@ pub fn create_random_user_session_helper() -> UserSession {
         project: None,
         view: None,
         roles: vec![user_id.into(), Role::registered_user_role_id()],
+        api_token: None,
     }
 }
 
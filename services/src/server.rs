@@ -44,6 +44,9 @@ pub async fn start_server(static_files_dir: Option<PathBuf>) -> Result<()> {
 
     register_gdal_drivers_from_list(config::get_config_element::<config::Gdal>()?.allowed_drivers);
 
+    crate::datasets::resumable_upload::spawn_expired_upload_cleanup()?;
+    crate::workflows::export::spawn_expired_export_cleanup()?;
+
     let db_config = config::get_config_element::<config::Postgres>()?;
 
     let ctx = PostgresContext::new_with_data(
@@ -58,6 +61,8 @@ pub async fn start_server(static_files_dir: Option<PathBuf>) -> Result<()> {
     )
     .await?;
 
+    ctx.restore_recurring_tasks().await?;
+
     start(
         static_files_dir,
         web_config.bind_address,
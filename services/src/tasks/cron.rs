@@ -0,0 +1,276 @@
+use super::TaskError;
+use chrono::{DateTime, Datelike, Duration, DurationRound, Timelike, Utc};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+/// A cron-like schedule in UTC with the fields minute, hour, day of month, month and day of week.
+///
+/// Each field is `*`, a value, a range `a-b` or a comma-separated list of them.
+/// Steps like `*/15` or `1-10/3` select every n-th value.
+/// Days of week range from `0` (Sunday) to `7` (Sunday again).
+/// As in cron, if both days of month and days of week are restricted, either of them must match.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct CronSchedule {
+    expression: String,
+    minutes: u64,
+    hours: u64,
+    days_of_month: u64,
+    months: u64,
+    days_of_week: u64,
+    days_of_month_restricted: bool,
+    days_of_week_restricted: bool,
+}
+
+/// Schedules that do not match within this many years are considered to never match, e.g., `0 0 30 2 *`
+const MAX_YEARS_TO_SEARCH: i32 = 5;
+
+impl CronSchedule {
+    /// The first time after `time` that matches the schedule
+    pub fn next_after(&self, time: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let mut next = time.duration_trunc(Duration::minutes(1)).ok()? + Duration::minutes(1);
+        let last_year = time.year() + MAX_YEARS_TO_SEARCH;
+
+        while next.year() <= last_year {
+            if !contains(self.months, next.month()) {
+                next = start_of_next_month(next)?;
+                continue;
+            }
+
+            if !self.matches_day(next) {
+                next = next.duration_trunc(Duration::days(1)).ok()? + Duration::days(1);
+                continue;
+            }
+
+            if !contains(self.hours, next.hour()) {
+                next = next.duration_trunc(Duration::hours(1)).ok()? + Duration::hours(1);
+                continue;
+            }
+
+            if !contains(self.minutes, next.minute()) {
+                next += Duration::minutes(1);
+                continue;
+            }
+
+            return Some(next);
+        }
+
+        None
+    }
+
+    fn matches_day(&self, time: DateTime<Utc>) -> bool {
+        let day_of_month = contains(self.days_of_month, time.day());
+        let day_of_week = contains(self.days_of_week, time.weekday().num_days_from_sunday());
+
+        match (self.days_of_month_restricted, self.days_of_week_restricted) {
+            (true, true) => day_of_month || day_of_week,
+            (true, false) => day_of_month,
+            (false, true) => day_of_week,
+            (false, false) => true,
+        }
+    }
+}
+
+fn contains(set: u64, value: u32) -> bool {
+    set & (1 << value) != 0
+}
+
+fn start_of_next_month(time: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    let (year, month) = if time.month() == 12 {
+        (time.year() + 1, 1)
+    } else {
+        (time.year(), time.month() + 1)
+    };
+
+    time.with_day(1)?
+        .with_hour(0)?
+        .with_minute(0)?
+        .with_month(month)?
+        .with_year(year)
+}
+
+/// Parses a field into a bit set of its values.
+/// Returns whether the field is restricted, i.e., it is not `*`.
+fn parse_field(field: &str, min: u32, max: u32) -> Result<(u64, bool), String> {
+    let mut set = 0;
+
+    for item in field.split(',') {
+        let (range, step) = match item.split_once('/') {
+            Some((range, step)) => (
+                range,
+                step.parse::<u32>()
+                    .ok()
+                    .filter(|step| *step > 0)
+                    .ok_or_else(|| format!("invalid step `{step}`"))?,
+            ),
+            None => (item, 1),
+        };
+
+        let parse_value = |value: &str| {
+            value
+                .parse::<u32>()
+                .ok()
+                .filter(|value| (min..=max).contains(value))
+                .ok_or_else(|| format!("`{value}` is not a value between {min} and {max}"))
+        };
+
+        let (start, end) = match range.split_once('-') {
+            _ if range == "*" => (min, max),
+            Some((start, end)) => (parse_value(start)?, parse_value(end)?),
+            // a single value with a step ranges to the maximum
+            None if step > 1 => (parse_value(range)?, max),
+            None => {
+                let value = parse_value(range)?;
+                (value, value)
+            }
+        };
+
+        if start > end {
+            return Err(format!("the range `{range}` is empty"));
+        }
+
+        for value in (start..=end).step_by(step as usize) {
+            set |= 1 << value;
+        }
+    }
+
+    Ok((set, field != "*"))
+}
+
+impl FromStr for CronSchedule {
+    type Err = TaskError;
+
+    fn from_str(expression: &str) -> Result<Self, Self::Err> {
+        let invalid = |reason: String| TaskError::InvalidCronSchedule {
+            schedule: expression.to_string(),
+            reason,
+        };
+
+        let fields = expression.split_whitespace().collect::<Vec<_>>();
+        let [minutes, hours, days_of_month, months, days_of_week] = fields[..] else {
+            return Err(invalid(format!(
+                "expected 5 fields, but got {}",
+                fields.len()
+            )));
+        };
+
+        let (minutes, _) = parse_field(minutes, 0, 59).map_err(invalid)?;
+        let (hours, _) = parse_field(hours, 0, 23).map_err(invalid)?;
+        let (days_of_month, days_of_month_restricted) =
+            parse_field(days_of_month, 1, 31).map_err(invalid)?;
+        let (months, _) = parse_field(months, 1, 12).map_err(invalid)?;
+        let (mut days_of_week, days_of_week_restricted) =
+            parse_field(days_of_week, 0, 7).map_err(invalid)?;
+
+        // both 0 and 7 are Sunday
+        if contains(days_of_week, 7) {
+            days_of_week |= 1;
+        }
+
+        Ok(Self {
+            expression: fields.join(" "),
+            minutes,
+            hours,
+            days_of_month,
+            months,
+            days_of_week,
+            days_of_month_restricted,
+            days_of_week_restricted,
+        })
+    }
+}
+
+impl TryFrom<String> for CronSchedule {
+    type Error = TaskError;
+
+    fn try_from(expression: String) -> Result<Self, Self::Error> {
+        expression.parse()
+    }
+}
+
+impl From<CronSchedule> for String {
+    fn from(schedule: CronSchedule) -> Self {
+        schedule.expression
+    }
+}
+
+impl std::fmt::Display for CronSchedule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.expression)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn time(year: i32, month: u32, day: u32, hour: u32, minute: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(year, month, day, hour, minute, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn it_finds_the_next_time() {
+        let nightly: CronSchedule = "30 2 * * *".parse().unwrap();
+        assert_eq!(
+            nightly.next_after(time(2024, 1, 31, 3, 0)),
+            Some(time(2024, 2, 1, 2, 30))
+        );
+        assert_eq!(
+            nightly.next_after(time(2024, 1, 31, 2, 29)),
+            Some(time(2024, 1, 31, 2, 30))
+        );
+        // strictly after the given time
+        assert_eq!(
+            nightly.next_after(time(2024, 1, 31, 2, 30)),
+            Some(time(2024, 2, 1, 2, 30))
+        );
+
+        let quarter_hourly: CronSchedule = "*/15 * * * *".parse().unwrap();
+        assert_eq!(
+            quarter_hourly.next_after(time(2024, 12, 31, 23, 50)),
+            Some(time(2025, 1, 1, 0, 0))
+        );
+
+        // Sundays or the first of a month
+        let weekly: CronSchedule = "0 0 1 * 7".parse().unwrap();
+        assert_eq!(
+            weekly.next_after(time(2024, 5, 27, 12, 0)),
+            Some(time(2024, 6, 1, 0, 0))
+        );
+        assert_eq!(
+            weekly.next_after(time(2024, 6, 1, 12, 0)),
+            Some(time(2024, 6, 2, 0, 0))
+        );
+
+        let leap_day: CronSchedule = "0 12 29 2 *".parse().unwrap();
+        assert_eq!(
+            leap_day.next_after(time(2024, 3, 1, 0, 0)),
+            Some(time(2028, 2, 29, 12, 0))
+        );
+
+        let never: CronSchedule = "0 0 30 2 *".parse().unwrap();
+        assert_eq!(never.next_after(time(2024, 1, 1, 0, 0)), None);
+    }
+
+    #[test]
+    fn it_parses_and_serializes() {
+        let schedule: CronSchedule = "0,30  8-18/2 * 1-7 1-5".parse().unwrap();
+        assert_eq!(schedule.to_string(), "0,30 8-18/2 * 1-7 1-5");
+        assert_eq!(
+            schedule.next_after(time(2024, 6, 28, 18, 30)),
+            Some(time(2024, 7, 1, 8, 0))
+        );
+        assert_eq!(
+            serde_json::to_value(&schedule).unwrap(),
+            serde_json::json!("0,30 8-18/2 * 1-7 1-5")
+        );
+
+        assert!("* * * *".parse::<CronSchedule>().is_err());
+        assert!("60 * * * *".parse::<CronSchedule>().is_err());
+        assert!("*/0 * * * *".parse::<CronSchedule>().is_err());
+        assert!("5-1 * * * *".parse::<CronSchedule>().is_err());
+        assert!(serde_json::from_str::<CronSchedule>("\"0 0 0 * *\"").is_err());
+    }
+}
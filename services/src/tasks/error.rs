@@ -1,6 +1,6 @@
 use snafu::Snafu;
 
-use super::TaskId;
+use super::{RecurringTaskId, TaskId};
 
 #[derive(Debug, Snafu)]
 #[snafu(visibility(pub(crate)))]
@@ -24,6 +24,12 @@ pub enum TaskError {
         task_unique_id: String,
     },
 
+    #[snafu(display("Recurring task not found with id: {recurring_task_id}"))]
+    RecurringTaskNotFound { recurring_task_id: RecurringTaskId },
+
+    #[snafu(display("Invalid cron schedule `{schedule}`: {reason}"))]
+    InvalidCronSchedule { schedule: String, reason: String },
+
     TaskManagerOperationFailed {
         source: Box<dyn std::error::Error + Send + Sync>,
     },
@@ -1,41 +1,202 @@
+use super::queue::{TaskAdmission, TaskQueue, TaskSlotKey};
 use super::{
-    RunningTaskStatusInfo, Task, TaskCleanUpStatus, TaskContext, TaskError, TaskFilter, TaskId,
-    TaskListOptions, TaskManager, TaskStatus, TaskStatusInfo, TaskStatusWithId,
+    CronSchedule, RecurringTask, RecurringTaskFactory, RecurringTaskId, RunningTaskStatusInfo,
+    Task, TaskCleanUpStatus, TaskContext, TaskError, TaskFilter, TaskId, TaskLimits,
+    TaskListOptions, TaskManager, TaskStatus, TaskStatusInfo, TaskStatusWithId, TaskStore,
 };
 use crate::{contexts::Db, error::Result};
 use futures::channel::oneshot;
-use futures::StreamExt;
-use geoengine_datatypes::{error::ErrorSource, util::Identifier};
+use futures::{FutureExt, StreamExt};
+use geoengine_datatypes::{
+    error::ErrorSource,
+    primitives::{self, DateTime},
+    util::Identifier,
+};
 use log::warn;
 use std::{
     collections::{HashMap, HashSet, VecDeque},
-    sync::Arc,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
 };
 use tokio::{
-    sync::{RwLock, RwLockWriteGuard},
+    sync::{Mutex, RwLock, RwLockWriteGuard},
     task::JoinHandle,
 };
 
 type SharedTask = Arc<Box<dyn Task<SimpleTaskManagerContext>>>;
 
+/// The progress of a task is written to the store at most once within this interval.
+/// Changes of the status, e.g., from running to completed, are always written.
+const STORED_PROGRESS_INTERVAL: Duration = Duration::from_secs(1);
+
+tokio::task_local! {
+    /// The task that is currently running, s.t. the tasks it schedules are known to be its subtasks
+    static RUNNING_TASK: TaskId;
+}
+
 /// An in-memory implementation of the [`TaskManager`] trait.
+///
+/// If it has a [`TaskStore`], all status updates are written through to the store.
+/// Then, the statuses of finished tasks are still available after a restart.
 #[derive(Default, Clone)]
 pub struct SimpleTaskManagerBackend {
     tasks_by_id: Db<HashMap<TaskId, TaskHandle>>,
     unique_tasks: Db<HashSet<(&'static str, String)>>,
     // these two lists won't be cleaned-up
-    status_by_id: Db<HashMap<TaskId, Db<TaskStatus>>>,
+    status_by_id: Db<HashMap<TaskId, SharedTaskStatus>>,
     status_list: Db<VecDeque<TaskUpdateStatusWithTaskId>>,
+    store: Option<Arc<dyn TaskStore>>,
+    queue: TaskQueue,
+    recurring_tasks: Db<HashMap<RecurringTaskId, RecurringTaskHandle>>,
 }
 
 struct TaskHandle {
     task: SharedTask,
     handle: Option<JoinHandle<()>>,
-    status: Db<TaskStatus>,
+    status: SharedTaskStatus,
     unique_key: Option<(&'static str, String)>,
 }
 
+struct RecurringTaskHandle {
+    recurring_task: RecurringTask,
+    handle: JoinHandle<()>,
+}
+
 impl SimpleTaskManagerBackend {
+    /// Creates a task manager that only runs as many tasks at once as the `limits` allow.
+    /// Further tasks are pending until running tasks finish.
+    pub fn new(limits: TaskLimits) -> Self {
+        Self {
+            queue: TaskQueue::new(limits),
+            ..Default::default()
+        }
+    }
+
+    /// Creates a task manager that persists the statuses of its tasks in the `store`.
+    ///
+    /// Tasks that did not finish before the last shutdown are marked as failed.
+    /// They cannot be re-queued, since tasks are not serializable.
+    pub async fn new_persistent(store: Arc<dyn TaskStore>, limits: TaskLimits) -> Result<Self> {
+        let interrupted_tasks = store.fail_interrupted_tasks().await?;
+        if interrupted_tasks > 0 {
+            warn!("Marked {interrupted_tasks} tasks as failed that were interrupted by a shutdown");
+        }
+
+        Ok(Self {
+            store: Some(store),
+            ..Self::new(limits)
+        })
+    }
+
+    /// Returns the type of a task, also for tasks that ran before the last restart.
+    pub async fn task_type(&self, task_id: TaskId) -> Result<String, TaskError> {
+        if let Some(task_handle) = self.tasks_by_id.read().await.get(&task_id) {
+            return Ok(task_handle.task.task_type().to_string());
+        }
+
+        let Some(store) = &self.store else {
+            return Err(TaskError::TaskNotFound { task_id });
+        };
+
+        store
+            .load_task_type(task_id)
+            .await
+            .map_err(store_error)?
+            .ok_or(TaskError::TaskNotFound { task_id })
+    }
+
+    /// Periodically deletes the stored statuses of finished tasks that were scheduled more than `retention` ago.
+    pub fn spawn_finished_task_cleanup(&self, retention: Duration, interval: Duration) {
+        let Some(store) = self.store.clone() else {
+            return;
+        };
+
+        let retention = primitives::Duration::milliseconds(
+            i64::try_from(retention.as_millis()).unwrap_or(i64::MAX),
+        );
+
+        crate::util::spawn(async move {
+            let mut interval = tokio::time::interval(interval);
+
+            loop {
+                interval.tick().await;
+
+                let scheduled_before = DateTime::now() - retention;
+
+                match store.delete_finished_tasks(scheduled_before).await {
+                    Ok(0) => {}
+                    Ok(deleted) => log::info!("Deleted {deleted} finished tasks"),
+                    Err(error) => warn!("Deleting finished tasks failed: {error}"),
+                }
+            }
+        });
+    }
+
+    /// Lists the tasks like [`TaskManager::list_tasks`], but skips tasks of the `excluded_task_types`.
+    pub async fn list_tasks_excluding_types(
+        &self,
+        options: TaskListOptions,
+        excluded_task_types: &[&str],
+    ) -> Result<Vec<TaskStatusWithId>, TaskError> {
+        if let Some(store) = &self.store {
+            let stored_tasks = store
+                .list_task_statuses(&options, excluded_task_types)
+                .await
+                .map_err(store_error)?;
+
+            let status_by_id = self.status_by_id.read().await;
+
+            let mut result = Vec::with_capacity(stored_tasks.len());
+            for TaskStatusWithId { task_id, status } in stored_tasks {
+                // the in-memory status of a task of this run also contains the time estimation
+                let status = match status_by_id.get(&task_id) {
+                    Some(task_status) => task_status.get().await,
+                    None => status,
+                };
+
+                result.push(TaskStatusWithId { task_id, status });
+            }
+
+            return Ok(result);
+        }
+
+        let lock = self.status_list.read().await;
+
+        let stream = futures::stream::iter(
+            lock.iter()
+                .filter(|task| !excluded_task_types.contains(&task.task_type.as_str())),
+        );
+
+        let result: Vec<TaskStatusWithId> = stream
+            .filter_map(|task_status_with_id| async {
+                let task_status = task_status_with_id.status.get().await;
+
+                match (options.filter, &task_status) {
+                    (None, _)
+                    | (Some(TaskFilter::Pending), &TaskStatus::Pending { .. })
+                    | (Some(TaskFilter::Running), &TaskStatus::Running(_))
+                    | (Some(TaskFilter::Completed), &TaskStatus::Completed { .. })
+                    | (Some(TaskFilter::Aborted), &TaskStatus::Aborted { .. })
+                    | (Some(TaskFilter::Failed), &TaskStatus::Failed { .. }) => {
+                        Some(TaskStatusWithId {
+                            task_id: task_status_with_id.task_id,
+                            status: task_status,
+                        })
+                    }
+                    _ => None,
+                }
+            })
+            .skip(options.offset as usize)
+            .take(options.limit as usize)
+            .collect()
+            .await;
+
+        Ok(result)
+    }
+
     async fn write_lock_all(&self) -> WriteLockAll {
         let (tasks_by_id, status_by_id, task_list, unique_tasks) = tokio::join!(
             self.tasks_by_id.write(),
@@ -61,15 +222,15 @@ impl SimpleTaskManagerBackend {
     }
 }
 
-#[derive(Debug)]
 struct TaskUpdateStatusWithTaskId {
     pub task_id: TaskId,
-    pub status: Db<TaskStatus>,
+    pub task_type: String,
+    pub status: SharedTaskStatus,
 }
 
 struct WriteLockAll<'a> {
     pub tasks_by_id: RwLockWriteGuard<'a, HashMap<TaskId, TaskHandle>>,
-    pub status_by_id: RwLockWriteGuard<'a, HashMap<TaskId, Db<TaskStatus>>>,
+    pub status_by_id: RwLockWriteGuard<'a, HashMap<TaskId, SharedTaskStatus>>,
     pub status_list: RwLockWriteGuard<'a, VecDeque<TaskUpdateStatusWithTaskId>>,
     pub unique_tasks: RwLockWriteGuard<'a, HashSet<(&'static str, String)>>,
 }
@@ -79,12 +240,16 @@ struct WriteLockForUpdate<'a> {
     pub unique_tasks: RwLockWriteGuard<'a, HashSet<(&'static str, String)>>,
 }
 
-#[async_trait::async_trait]
-impl TaskManager<SimpleTaskManagerContext> for SimpleTaskManagerBackend {
-    async fn schedule_task(
+impl SimpleTaskManagerBackend {
+    /// Schedules a task on behalf of an `owner`, s.t. the per-owner limit applies to it.
+    ///
+    /// Subtasks, i.e., tasks that are scheduled by running tasks, start immediately.
+    /// They run within the slot of their parent, which usually waits for them.
+    pub async fn schedule_task_for_owner(
         &self,
         task: Box<dyn Task<SimpleTaskManagerContext>>,
         notify: Option<oneshot::Sender<TaskStatus>>,
+        owner: Option<String>,
     ) -> Result<TaskId, TaskError> {
         let task_id = TaskId::new();
 
@@ -105,15 +270,51 @@ impl TaskManager<SimpleTaskManagerContext> for SimpleTaskManagerBackend {
             }
         }
 
-        let task_type = task.task_type();
+        let task_type = task.task_type().to_string();
         let description = Some(task.task_description());
 
+        // counting subtasks against the limits would deadlock parents that wait for them
+        let is_subtask = RUNNING_TASK.try_with(|_| ()).is_ok();
+        let admission = (!is_subtask).then(|| {
+            self.queue.enqueue(
+                TaskSlotKey {
+                    owner,
+                    task_type: task_type.clone(),
+                },
+                task.task_priority(),
+            )
+        });
+
+        let status = if matches!(admission, Some(TaskAdmission::Pending(_))) {
+            TaskStatus::Pending {
+                task_type: task_type.clone(),
+                description: description.clone(),
+            }
+        } else {
+            TaskStatus::Running(RunningTaskStatusInfo::new(
+                task_type.clone(),
+                description.clone(),
+                0.,
+                ().boxed(),
+            ))
+        };
+
+        if let Some(store) = &self.store {
+            if let Err(error) = store
+                .insert_task(task_id, &task_type, description.as_deref(), &status)
+                .await
+            {
+                if let Some(task_unique_id) = &task_unique_key {
+                    lock.unique_tasks.remove(task_unique_id);
+                }
+                return Err(store_error(error));
+            }
+        }
+
         let mut task_handle = TaskHandle {
             task: Arc::new(task),
             handle: None,
-            status: Arc::new(RwLock::new(TaskStatus::Running(
-                RunningTaskStatusInfo::new(task_type, description, 0., ().boxed()),
-            ))),
+            status: SharedTaskStatus::new(task_id, status, self.store.clone()),
             unique_key: task_unique_key,
         };
 
@@ -127,6 +328,7 @@ impl TaskManager<SimpleTaskManagerContext> for SimpleTaskManagerBackend {
             task_id,
             task,
             task_ctx,
+            admission,
             notify,
         );
 
@@ -136,6 +338,7 @@ impl TaskManager<SimpleTaskManagerContext> for SimpleTaskManagerBackend {
             .insert(task_id, task_handle.status.clone());
         lock.status_list.push_front(TaskUpdateStatusWithTaskId {
             task_id,
+            task_type,
             status: task_handle.status.clone(),
         });
 
@@ -148,50 +351,228 @@ impl TaskManager<SimpleTaskManagerContext> for SimpleTaskManagerBackend {
         Ok(task_id)
     }
 
-    async fn get_task_status(&self, task_id: TaskId) -> Result<TaskStatus, TaskError> {
-        let task_status_map = self.status_by_id.read().await;
-        let task_status = task_status_map
-            .get(&task_id)
-            .ok_or(TaskError::TaskNotFound { task_id })?
+    /// Schedules a new task, created by `create_task`, every time the `schedule` is due.
+    ///
+    /// Recurring tasks that are scheduled this way are not persisted, so they have to be registered again after a restart.
+    pub async fn schedule_recurring_task<F>(
+        &self,
+        schedule: CronSchedule,
+        owner: Option<String>,
+        create_task: F,
+    ) -> RecurringTaskId
+    where
+        F: Fn() -> Box<dyn Task<SimpleTaskManagerContext>> + Send + Sync + 'static,
+    {
+        let recurring_task = RecurringTask {
+            recurring_task_id: RecurringTaskId::new(),
+            owner,
+            schedule,
+            definition: serde_json::Value::Null,
+        };
+        let recurring_task_id = recurring_task.recurring_task_id;
+
+        self.spawn_recurring_task(
+            recurring_task,
+            Arc::new(move || futures::future::ok::<_, crate::error::Error>(create_task()).boxed()),
+        )
+        .await;
+
+        recurring_task_id
+    }
+
+    /// Schedules a new task, created by the `factory`, every time the `schedule` is due on behalf of an `owner`.
+    ///
+    /// The recurring task is written to the store, s.t. [`Self::restore_recurring_tasks`] can schedule it again after a restart.
+    pub async fn schedule_persistent_recurring_task(
+        &self,
+        schedule: CronSchedule,
+        owner: Option<String>,
+        definition: serde_json::Value,
+        factory: RecurringTaskFactory<SimpleTaskManagerContext>,
+    ) -> Result<RecurringTaskId, TaskError> {
+        let recurring_task = RecurringTask {
+            recurring_task_id: RecurringTaskId::new(),
+            owner,
+            schedule,
+            definition,
+        };
+        let recurring_task_id = recurring_task.recurring_task_id;
+
+        if let Some(store) = &self.store {
+            store
+                .insert_recurring_task(&recurring_task)
+                .await
+                .map_err(store_error)?;
+        }
+
+        self.spawn_recurring_task(recurring_task, factory).await;
+
+        Ok(recurring_task_id)
+    }
+
+    /// Schedules the recurring tasks of the store again, e.g., after a restart.
+    ///
+    /// The `create_factory` re-creates the factory of a recurring task from its definition.
+    /// Recurring tasks whose factory cannot be re-created are skipped.
+    pub async fn restore_recurring_tasks<F>(&self, create_factory: F) -> Result<()>
+    where
+        F: Fn(&RecurringTask) -> Result<RecurringTaskFactory<SimpleTaskManagerContext>>,
+    {
+        let Some(store) = &self.store else {
+            return Ok(());
+        };
+
+        for recurring_task in store.list_recurring_tasks().await? {
+            match create_factory(&recurring_task) {
+                Ok(factory) => self.spawn_recurring_task(recurring_task, factory).await,
+                Err(error) => warn!(
+                    "failed to restore recurring task {}: {error}",
+                    recurring_task.recurring_task_id
+                ),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Lists the recurring tasks of the `owner` in no particular order. Without an `owner`, all recurring tasks are listed.
+    pub async fn list_recurring_tasks_of_owner(&self, owner: Option<&str>) -> Vec<RecurringTask> {
+        self.recurring_tasks
             .read()
             .await
-            .clone();
+            .values()
+            .filter(|handle| is_owned_by(&handle.recurring_task, owner))
+            .map(|handle| handle.recurring_task.clone())
+            .collect()
+    }
 
-        Ok(task_status)
+    /// Stops scheduling new tasks for a recurring task. Already running tasks are not aborted.
+    pub async fn cancel_recurring_task(
+        &self,
+        recurring_task_id: RecurringTaskId,
+    ) -> Result<(), TaskError> {
+        self.cancel_recurring_task_of_owner(recurring_task_id, None)
+            .await
     }
 
-    async fn list_tasks(
+    /// Cancels a recurring task like [`Self::cancel_recurring_task`], but only if it belongs to the `owner`.
+    pub async fn cancel_recurring_task_of_owner(
         &self,
-        options: TaskListOptions,
-    ) -> Result<Vec<TaskStatusWithId>, TaskError> {
-        let lock = self.status_list.read().await;
+        recurring_task_id: RecurringTaskId,
+        owner: Option<&str>,
+    ) -> Result<(), TaskError> {
+        let mut recurring_tasks = self.recurring_tasks.write().await;
+
+        let is_owned = recurring_tasks
+            .get(&recurring_task_id)
+            .is_some_and(|handle| is_owned_by(&handle.recurring_task, owner));
+        if !is_owned {
+            return Err(TaskError::RecurringTaskNotFound { recurring_task_id });
+        }
 
-        let stream = futures::stream::iter(lock.iter());
+        if let Some(store) = &self.store {
+            store
+                .delete_recurring_task(recurring_task_id)
+                .await
+                .map_err(store_error)?;
+        }
 
-        let result: Vec<TaskStatusWithId> = stream
-            .filter_map(|task_status_with_id| async {
-                let task_status = task_status_with_id.status.read().await;
+        if let Some(handle) = recurring_tasks.remove(&recurring_task_id) {
+            handle.handle.abort();
+        }
 
-                match (options.filter, &*task_status) {
-                    (None, _)
-                    | (Some(TaskFilter::Running), &TaskStatus::Running(_))
-                    | (Some(TaskFilter::Completed), &TaskStatus::Completed { .. })
-                    | (Some(TaskFilter::Aborted), &TaskStatus::Aborted { .. })
-                    | (Some(TaskFilter::Failed), &TaskStatus::Failed { .. }) => {
-                        Some(TaskStatusWithId {
-                            task_id: task_status_with_id.task_id,
-                            status: task_status.clone(),
-                        })
+        Ok(())
+    }
+
+    async fn spawn_recurring_task(
+        &self,
+        recurring_task: RecurringTask,
+        factory: RecurringTaskFactory<SimpleTaskManagerContext>,
+    ) {
+        let RecurringTask {
+            recurring_task_id,
+            owner,
+            schedule,
+            ..
+        } = recurring_task.clone();
+        let task_manager = self.clone();
+
+        let handle = crate::util::spawn(async move {
+            loop {
+                let now = chrono::Utc::now();
+                let Some(next) = schedule.next_after(now) else {
+                    warn!("recurring task {recurring_task_id} with schedule `{schedule}` is never due again");
+                    return;
+                };
+
+                tokio::time::sleep((next - now).to_std().unwrap_or_default()).await;
+
+                // e.g., fails if the workflow of the task was deleted in the meantime
+                let task = match factory().await {
+                    Ok(task) => task,
+                    Err(error) => {
+                        warn!("failed to create the task of recurring task {recurring_task_id}: {error}");
+                        continue;
                     }
-                    _ => None,
+                };
+
+                // e.g., fails for unique tasks if the previous run did not finish yet
+                if let Err(error) = task_manager
+                    .schedule_task_for_owner(task, None, owner.clone())
+                    .await
+                {
+                    warn!("failed to schedule recurring task {recurring_task_id}: {error}");
                 }
-            })
-            .skip(options.offset as usize)
-            .take(options.limit as usize)
-            .collect()
-            .await;
+            }
+        });
 
-        Ok(result)
+        self.recurring_tasks.write().await.insert(
+            recurring_task_id,
+            RecurringTaskHandle {
+                recurring_task,
+                handle,
+            },
+        );
+    }
+}
+
+/// Whether the recurring task belongs to the `owner`. Without an `owner`, all recurring tasks match.
+fn is_owned_by(recurring_task: &RecurringTask, owner: Option<&str>) -> bool {
+    owner.is_none() || recurring_task.owner.as_deref() == owner
+}
+
+#[async_trait::async_trait]
+impl TaskManager<SimpleTaskManagerContext> for SimpleTaskManagerBackend {
+    async fn schedule_task(
+        &self,
+        task: Box<dyn Task<SimpleTaskManagerContext>>,
+        notify: Option<oneshot::Sender<TaskStatus>>,
+    ) -> Result<TaskId, TaskError> {
+        self.schedule_task_for_owner(task, notify, None).await
+    }
+
+    async fn get_task_status(&self, task_id: TaskId) -> Result<TaskStatus, TaskError> {
+        if let Some(task_status) = self.status_by_id.read().await.get(&task_id) {
+            return Ok(task_status.get().await);
+        }
+
+        // tasks of previous runs are only in the store
+        let Some(store) = &self.store else {
+            return Err(TaskError::TaskNotFound { task_id });
+        };
+
+        store
+            .load_task_status(task_id)
+            .await
+            .map_err(store_error)?
+            .ok_or(TaskError::TaskNotFound { task_id })
+    }
+
+    async fn list_tasks(
+        &self,
+        options: TaskListOptions,
+    ) -> Result<Vec<TaskStatusWithId>, TaskError> {
+        self.list_tasks_excluding_types(options, &[]).await
     }
 
     async fn abort_tasks(&self, task_id: TaskId, force: bool) -> Result<(), TaskError> {
@@ -202,20 +583,19 @@ impl TaskManager<SimpleTaskManagerContext> for SimpleTaskManagerBackend {
             .remove(&task_id)
             .ok_or(TaskError::TaskNotFound { task_id })?;
 
-        let task_status_lock = task_handle.status.read().await;
+        let task_status = task_handle.status.get().await;
 
-        if task_status_lock.is_finished() {
+        if task_status.is_finished() {
             return Err(TaskError::TaskAlreadyFinished { task_id });
-        } else if !force && task_status_lock.has_aborted() {
-            drop(task_status_lock);
-
+        } else if !force && task_status.has_aborted() {
             // put clean-up handle back
             write_lock.tasks_by_id.insert(task_id, task_handle);
 
             return Err(TaskError::TaskAlreadyAborted { task_id });
         }
 
-        drop(task_status_lock); // prevent deadlocks on the status lock
+        // a pending task did not start, so there is nothing to clean up
+        let task_was_pending = task_status.is_pending();
 
         let task_finished_before_being_aborted = if let Some(handle) = task_handle.handle.take() {
             handle.abort();
@@ -227,7 +607,7 @@ impl TaskManager<SimpleTaskManagerContext> for SimpleTaskManagerBackend {
 
         let subtask_ids = task_handle.task.subtasks().await;
 
-        if force || task_finished_before_being_aborted {
+        if force || task_was_pending || task_finished_before_being_aborted {
             set_status_to_no_clean_up(&task_handle.status).await;
 
             remove_unique_key(&task_handle, &mut write_lock.unique_tasks);
@@ -249,6 +629,27 @@ impl TaskManager<SimpleTaskManagerContext> for SimpleTaskManagerBackend {
 
         Ok(())
     }
+
+    async fn add_recurring_task(
+        &self,
+        schedule: CronSchedule,
+        definition: serde_json::Value,
+        factory: RecurringTaskFactory<SimpleTaskManagerContext>,
+    ) -> Result<RecurringTaskId, TaskError> {
+        self.schedule_persistent_recurring_task(schedule, None, definition, factory)
+            .await
+    }
+
+    async fn list_recurring_tasks(&self) -> Result<Vec<RecurringTask>, TaskError> {
+        Ok(self.list_recurring_tasks_of_owner(None).await)
+    }
+
+    async fn remove_recurring_task(
+        &self,
+        recurring_task_id: RecurringTaskId,
+    ) -> Result<(), TaskError> {
+        self.cancel_recurring_task(recurring_task_id).await
+    }
 }
 
 async fn abort_subtasks(
@@ -274,10 +675,28 @@ fn run_task(
     task_id: TaskId,
     task: SharedTask,
     task_ctx: SimpleTaskManagerContext,
+    admission: Option<TaskAdmission>,
     notify: Option<oneshot::Sender<TaskStatus>>,
 ) -> JoinHandle<()> {
     crate::util::spawn(async move {
-        let result = task.run(task_ctx.clone()).await;
+        // the slot is released when the task finished and then starts pending tasks
+        let _slot = match admission {
+            None => None, /* subtasks use the slot of their parent */
+            Some(TaskAdmission::Started(slot)) => Some(slot),
+            Some(TaskAdmission::Pending(started)) => {
+                let Ok(slot) = started.await else {
+                    return; /* never happens, since the queue keeps pending tasks until they start */
+                };
+
+                task_ctx.status.update(set_status_to_running).await;
+
+                Some(slot)
+            }
+        };
+
+        let result = RUNNING_TASK
+            .scope(task_id, task.run(task_ctx.clone()))
+            .await;
 
         let mut update_lock = task_manager.write_lock_for_update().await;
 
@@ -289,24 +708,31 @@ fn run_task(
 
         match result {
             Ok(status) => {
-                let mut task_status_lock = task_handle.status.write().await;
-                let completed_task_status = task_status_lock.completed(Arc::from(status));
-                *task_status_lock = completed_task_status;
+                let info: Arc<dyn TaskStatusInfo> = Arc::from(status);
+                task_handle
+                    .status
+                    .update(|task_status| Some(task_status.completed(info)))
+                    .await;
 
                 remove_unique_key(&task_handle, &mut update_lock.unique_tasks);
             }
             Err(err) => {
                 let err = Arc::from(err);
 
-                *task_handle.status.write().await = TaskStatus::failed(
-                    Arc::clone(&err),
-                    TaskCleanUpStatus::Running(RunningTaskStatusInfo::new(
-                        "",
-                        None,
-                        0.,
-                        ().boxed(),
-                    )),
-                );
+                task_handle
+                    .status
+                    .update(|_| {
+                        Some(TaskStatus::failed(
+                            err,
+                            TaskCleanUpStatus::Running(RunningTaskStatusInfo::new(
+                                String::new(),
+                                None,
+                                0.,
+                                ().boxed(),
+                            )),
+                        ))
+                    })
+                    .await;
 
                 clean_up_phase(task_manager.clone(), task_handle, &mut update_lock, task_id);
             }
@@ -316,9 +742,7 @@ fn run_task(
         if let Some(notify) = notify {
             // we can ignore the returned error because this means
             // that the receiver has already been dropped
-            notify
-                .send(task_status.read().await.clone())
-                .unwrap_or_default();
+            notify.send(task_status.get().await).unwrap_or_default();
         }
     })
 }
@@ -332,52 +756,89 @@ fn remove_unique_key(
     }
 }
 
-async fn set_status_to_aborting(task_status: &Db<TaskStatus>) {
-    let mut task_status_lock = task_status.write().await;
-    *task_status_lock = TaskStatus::aborted(TaskCleanUpStatus::Running(
-        RunningTaskStatusInfo::new("", None, 0., ().boxed()),
-    ));
+fn set_status_to_running(task_status: &TaskStatus) -> Option<TaskStatus> {
+    match task_status {
+        TaskStatus::Pending {
+            task_type,
+            description,
+        } => Some(TaskStatus::Running(RunningTaskStatusInfo::new(
+            task_type.clone(),
+            description.clone(),
+            0.,
+            ().boxed(),
+        ))),
+        _ => None, // must not happen, ignore
+    }
 }
 
-async fn set_status_to_clean_up_completed(task_status: &Db<TaskStatus>) {
-    let mut task_status_lock = task_status.write().await;
+async fn set_status_to_aborting(task_status: &SharedTaskStatus) {
+    task_status
+        .update(|_| {
+            Some(TaskStatus::aborted(TaskCleanUpStatus::Running(
+                RunningTaskStatusInfo::new(String::new(), None, 0., ().boxed()),
+            )))
+        })
+        .await;
+}
 
+async fn set_status_to_clean_up_completed(task_status: &SharedTaskStatus) {
     let task_clean_up_status = TaskCleanUpStatus::Completed {
         info: Arc::new(().boxed()),
     };
 
-    *task_status_lock = match &*task_status_lock {
-        TaskStatus::Running(_) | TaskStatus::Completed { .. } => return, // must not happen, ignore
-        TaskStatus::Aborted { .. } => TaskStatus::aborted(task_clean_up_status),
-        TaskStatus::Failed { error, .. } => TaskStatus::failed(error.clone(), task_clean_up_status),
-    };
+    task_status
+        .update(|task_status| match task_status {
+            TaskStatus::Pending { .. } | TaskStatus::Running(_) | TaskStatus::Completed { .. } => {
+                None // must not happen, ignore
+            }
+            TaskStatus::Aborted { .. } => Some(TaskStatus::aborted(task_clean_up_status)),
+            TaskStatus::Failed { error, .. } => {
+                Some(TaskStatus::failed(error.clone(), task_clean_up_status))
+            }
+        })
+        .await;
 }
 
-async fn set_status_to_no_clean_up(task_status: &Db<TaskStatus>) {
-    let mut task_status_lock = task_status.write().await;
-
+async fn set_status_to_no_clean_up(task_status: &SharedTaskStatus) {
     let task_clean_up_status = TaskCleanUpStatus::NoCleanUp;
 
-    *task_status_lock = match &*task_status_lock {
-        TaskStatus::Completed { .. } => return, // must not happen, ignore
-        TaskStatus::Running(_) | TaskStatus::Aborted { .. } => {
-            TaskStatus::aborted(task_clean_up_status)
-        }
-        TaskStatus::Failed { error, .. } => TaskStatus::failed(error.clone(), task_clean_up_status),
-    }
+    task_status
+        .update(|task_status| match task_status {
+            TaskStatus::Completed { .. } => None, // must not happen, ignore
+            TaskStatus::Pending { .. } | TaskStatus::Running(_) | TaskStatus::Aborted { .. } => {
+                Some(TaskStatus::aborted(task_clean_up_status))
+            }
+            TaskStatus::Failed { error, .. } => {
+                Some(TaskStatus::failed(error.clone(), task_clean_up_status))
+            }
+        })
+        .await;
 }
 
-async fn set_status_to_clean_up_failed(task_status: &Db<TaskStatus>, error: Box<dyn ErrorSource>) {
-    let mut task_status_lock = task_status.write().await;
-
+async fn set_status_to_clean_up_failed(
+    task_status: &SharedTaskStatus,
+    error: Box<dyn ErrorSource>,
+) {
     let task_clean_up_status = TaskCleanUpStatus::Failed {
         error: Arc::from(error),
     };
 
-    *task_status_lock = match &*task_status_lock {
-        TaskStatus::Running(_) | TaskStatus::Completed { .. } => return, // must not happen, ignore
-        TaskStatus::Aborted { .. } => TaskStatus::aborted(task_clean_up_status),
-        TaskStatus::Failed { error, .. } => TaskStatus::failed(error.clone(), task_clean_up_status),
+    task_status
+        .update(|task_status| match task_status {
+            TaskStatus::Pending { .. } | TaskStatus::Running(_) | TaskStatus::Completed { .. } => {
+                None // must not happen, ignore
+            }
+            TaskStatus::Aborted { .. } => Some(TaskStatus::aborted(task_clean_up_status)),
+            TaskStatus::Failed { error, .. } => {
+                Some(TaskStatus::failed(error.clone(), task_clean_up_status))
+            }
+        })
+        .await;
+}
+
+fn store_error(error: crate::error::Error) -> TaskError {
+    TaskError::TaskManagerOperationFailed {
+        source: Box::new(error),
     }
 }
 
@@ -414,34 +875,139 @@ fn clean_up_phase(
     write_lock.tasks_by_id.insert(task_id, task_handle);
 }
 
+/// The status of a task that is shared between the task manager and the task context.
 #[derive(Clone)]
-pub struct SimpleTaskManagerContext {
+struct SharedTaskStatus {
+    task_id: TaskId,
     status: Db<TaskStatus>,
+    /// The number of updates of the status, s.t. outdated statuses are not written to the store
+    version: Arc<AtomicU64>,
+    store: Option<Arc<dyn TaskStore>>,
+    stored: Arc<Mutex<StoredTaskStatusVersion>>,
 }
 
-#[async_trait::async_trait]
-impl TaskContext for SimpleTaskManagerContext {
-    async fn set_completion(&self, pct_complete: f64, status: Box<dyn TaskStatusInfo>) {
-        let mut task_status = self.status.write().await;
+/// The version of the status that was written to the store last
+#[derive(Default)]
+struct StoredTaskStatusVersion {
+    version: u64,
+    time: Option<Instant>,
+}
 
-        *task_status = match &*task_status {
-            TaskStatus::Running(current_info) => {
-                TaskStatus::Running(current_info.update(pct_complete, status))
+impl SharedTaskStatus {
+    fn new(task_id: TaskId, status: TaskStatus, store: Option<Arc<dyn TaskStore>>) -> Self {
+        Self {
+            task_id,
+            status: Arc::new(RwLock::new(status)),
+            version: Default::default(),
+            store,
+            stored: Default::default(),
+        }
+    }
+
+    async fn get(&self) -> TaskStatus {
+        self.status.read().await.clone()
+    }
+
+    /// Replaces the status if `update` returns a new one and writes it through to the store.
+    async fn update<F>(&self, update: F)
+    where
+        F: FnOnce(&TaskStatus) -> Option<TaskStatus> + Send,
+    {
+        self.update_and_store(update, false).await;
+    }
+
+    /// Replaces the status like [`Self::update`], but writes it to the store at most once per [`STORED_PROGRESS_INTERVAL`].
+    async fn update_progress<F>(&self, update: F)
+    where
+        F: FnOnce(&TaskStatus) -> Option<TaskStatus> + Send,
+    {
+        self.update_and_store(update, true).await;
+    }
+
+    async fn update_and_store<F>(&self, update: F, is_progress: bool)
+    where
+        F: FnOnce(&TaskStatus) -> Option<TaskStatus> + Send,
+    {
+        let (version, task_status) = {
+            let mut task_status = self.status.write().await;
+
+            let Some(new_task_status) = update(&task_status) else {
+                return;
+            };
+            *task_status = new_task_status;
+
+            if self.store.is_none() {
+                return;
             }
-            TaskStatus::Aborted {
-                clean_up: TaskCleanUpStatus::Running(current_info),
-            } => TaskStatus::aborted(TaskCleanUpStatus::Running(
-                current_info.update(pct_complete, status),
-            )),
-            TaskStatus::Failed {
-                error,
-                clean_up: TaskCleanUpStatus::Running(current_info),
-            } => TaskStatus::failed(
-                error.clone(),
-                TaskCleanUpStatus::Running(current_info.update(pct_complete, status)),
-            ),
-            _ => return, // already completed, aborted or failed, so we ignore the status update
+
+            let version = self.version.fetch_add(1, Ordering::SeqCst) + 1;
+
+            (version, task_status.clone())
+        };
+
+        let Some(store) = &self.store else {
+            return;
         };
+
+        // the status lock is released while storing, so newer statuses may have been stored in the meantime
+        let mut stored = self.stored.lock().await;
+
+        if stored.version >= version {
+            return;
+        }
+
+        if is_progress
+            && stored
+                .time
+                .is_some_and(|time| time.elapsed() < STORED_PROGRESS_INTERVAL)
+        {
+            return;
+        }
+
+        match store.update_task_status(self.task_id, &task_status).await {
+            Ok(()) => {
+                stored.version = version;
+                stored.time = Some(Instant::now());
+            }
+            Err(error) => {
+                warn!(
+                    "failed to store the status of task {}: {error:?}",
+                    self.task_id
+                );
+            }
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct SimpleTaskManagerContext {
+    status: SharedTaskStatus,
+}
+
+#[async_trait::async_trait]
+impl TaskContext for SimpleTaskManagerContext {
+    async fn set_completion(&self, pct_complete: f64, status: Box<dyn TaskStatusInfo>) {
+        self.status
+            .update_progress(|task_status| match task_status {
+                TaskStatus::Running(current_info) => Some(TaskStatus::Running(
+                    current_info.update(pct_complete, status),
+                )),
+                TaskStatus::Aborted {
+                    clean_up: TaskCleanUpStatus::Running(current_info),
+                } => Some(TaskStatus::aborted(TaskCleanUpStatus::Running(
+                    current_info.update(pct_complete, status),
+                ))),
+                TaskStatus::Failed {
+                    error,
+                    clean_up: TaskCleanUpStatus::Running(current_info),
+                } => Some(TaskStatus::failed(
+                    error.clone(),
+                    TaskCleanUpStatus::Running(current_info.update(pct_complete, status)),
+                )),
+                // still pending, already completed, aborted or failed, so we ignore the status update
+                _ => None,
+            })
+            .await;
     }
 }
 
@@ -479,4 +1045,26 @@ impl TaskManager<SimpleTaskManagerContext> for SimpleTaskManager {
     async fn abort_tasks(&self, task_id: TaskId, force: bool) -> Result<(), TaskError> {
         self.backend.abort_tasks(task_id, force).await
     }
+
+    async fn add_recurring_task(
+        &self,
+        schedule: CronSchedule,
+        definition: serde_json::Value,
+        factory: RecurringTaskFactory<SimpleTaskManagerContext>,
+    ) -> Result<RecurringTaskId, TaskError> {
+        self.backend
+            .add_recurring_task(schedule, definition, factory)
+            .await
+    }
+
+    async fn list_recurring_tasks(&self) -> Result<Vec<RecurringTask>, TaskError> {
+        self.backend.list_recurring_tasks().await
+    }
+
+    async fn remove_recurring_task(
+        &self,
+        recurring_task_id: RecurringTaskId,
+    ) -> Result<(), TaskError> {
+        self.backend.remove_recurring_task(recurring_task_id).await
+    }
 }
@@ -1,16 +1,23 @@
+mod cron;
 mod error;
 mod in_memory;
+mod postgres;
+mod queue;
 mod time_estimation;
 pub mod util;
 
 use self::time_estimation::TimeEstimation;
 use crate::identifier;
 use crate::{error::Result, util::config::get_config_element};
+pub use cron::CronSchedule;
 pub use error::TaskError;
 use futures::channel::oneshot;
+use futures::future::BoxFuture;
 use geoengine_datatypes::primitives::DateTime;
 use geoengine_datatypes::{error::ErrorSource, util::AsAnyArc};
 pub use in_memory::{SimpleTaskManager, SimpleTaskManagerBackend, SimpleTaskManagerContext};
+pub use postgres::PostgresTaskStore;
+pub use queue::{TaskLimits, TaskPriority};
 use serde::{Deserialize, Serialize, Serializer};
 use std::borrow::Cow;
 use std::{fmt, sync::Arc};
@@ -42,10 +49,94 @@ pub trait TaskManager<C: TaskContext>: Send + Sync {
     ///  - `force`: If `true`, the task will be aborted without calling clean-up functions.
     ///
     async fn abort_tasks(&self, task_id: TaskId, force: bool) -> Result<(), TaskError>;
+
+    /// Schedules a task, created by the `factory`, every time the `schedule` is due.
+    ///
+    /// The `definition` is persisted, s.t. the recurring task can be restored after a restart.
+    async fn add_recurring_task(
+        &self,
+        schedule: CronSchedule,
+        definition: serde_json::Value,
+        factory: RecurringTaskFactory<C>,
+    ) -> Result<RecurringTaskId, TaskError>;
+
+    async fn list_recurring_tasks(&self) -> Result<Vec<RecurringTask>, TaskError>;
+
+    /// Stops scheduling new tasks for a recurring task. Already running tasks are not aborted.
+    async fn remove_recurring_task(
+        &self,
+        recurring_task_id: RecurringTaskId,
+    ) -> Result<(), TaskError>;
 }
 
 identifier!(TaskId);
 
+identifier!(RecurringTaskId);
+
+/// Creates the task of a recurring task every time its schedule is due.
+pub type RecurringTaskFactory<C> =
+    Arc<dyn Fn() -> BoxFuture<'static, Result<Box<dyn Task<C>>>> + Send + Sync>;
+
+/// A task that is scheduled every time its `schedule` is due.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RecurringTask {
+    pub recurring_task_id: RecurringTaskId,
+    pub owner: Option<String>,
+    pub schedule: CronSchedule,
+    /// The serialized form of the task, s.t. its factory can be re-created after a restart
+    pub definition: serde_json::Value,
+}
+
+/// A persistent storage for the statuses of tasks.
+///
+/// It allows a [`TaskManager`] to report the statuses of tasks after a restart of the server.
+#[async_trait::async_trait]
+pub trait TaskStore: Send + Sync {
+    /// Stores a newly scheduled task with its initial status.
+    async fn insert_task(
+        &self,
+        task_id: TaskId,
+        task_type: &str,
+        description: Option<&str>,
+        status: &TaskStatus,
+    ) -> Result<()>;
+
+    /// Replaces the status of a stored task.
+    async fn update_task_status(&self, task_id: TaskId, status: &TaskStatus) -> Result<()>;
+
+    async fn load_task_status(&self, task_id: TaskId) -> Result<Option<TaskStatus>>;
+
+    async fn load_task_type(&self, task_id: TaskId) -> Result<Option<String>>;
+
+    /// Lists the stored tasks, the most recently scheduled first.
+    ///
+    /// Tasks of the `excluded_task_types` are skipped before applying the offset and limit.
+    async fn list_task_statuses(
+        &self,
+        options: &TaskListOptions,
+        excluded_task_types: &[&str],
+    ) -> Result<Vec<TaskStatusWithId>>;
+
+    /// Marks all pending and running tasks as failed and returns their number.
+    ///
+    /// This must be called on startup, since the execution of these tasks was interrupted.
+    async fn fail_interrupted_tasks(&self) -> Result<u64>;
+
+    /// Deletes finished tasks that were scheduled before `scheduled_before` and returns their number.
+    ///
+    /// Tasks whose clean-up is still running are kept.
+    async fn delete_finished_tasks(&self, scheduled_before: DateTime) -> Result<u64>;
+
+    async fn insert_recurring_task(&self, recurring_task: &RecurringTask) -> Result<()>;
+
+    /// Lists all stored recurring tasks, the oldest first.
+    async fn list_recurring_tasks(&self) -> Result<Vec<RecurringTask>>;
+
+    /// Deletes a recurring task and returns whether it existed.
+    async fn delete_recurring_task(&self, recurring_task_id: RecurringTaskId) -> Result<bool>;
+}
+
 /// A task that can run asynchronously and reports its status.
 #[async_trait::async_trait]
 pub trait Task<C: TaskContext>: Send + Sync {
@@ -69,6 +160,11 @@ pub trait Task<C: TaskContext>: Send + Sync {
 
     fn task_description(&self) -> String;
 
+    /// Pending tasks with a higher priority start first.
+    fn task_priority(&self) -> TaskPriority {
+        TaskPriority::Normal
+    }
+
     /// Return subtasks of this tasks.
     ///
     /// For instance, they will get aborted when this tasks gets aborted.
@@ -93,11 +189,16 @@ pub trait TaskContext: Send + Sync {
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase", tag = "status")]
 pub enum TaskStatus {
-    // Pending, // TODO: at some point, don't just run things
+    /// The task waits for a free slot, since the maximum number of concurrent tasks is reached.
+    #[serde(rename_all = "camelCase")]
+    Pending {
+        task_type: String,
+        description: Option<String>,
+    },
     Running(Arc<RunningTaskStatusInfo>),
     #[serde(rename_all = "camelCase")]
     Completed {
-        task_type: &'static str,
+        task_type: String,
         description: Option<String>,
         info: Arc<dyn TaskStatusInfo>,
         time_total: String,
@@ -128,6 +229,19 @@ impl<'a> ToSchema<'a> for TaskStatus {
         (
             "TaskStatus",
             OneOfBuilder::new()
+                .item(
+                    ObjectBuilder::new()
+                        .property(
+                            "status",
+                            ObjectBuilder::new()
+                                .schema_type(SchemaType::String)
+                                .enum_values::<[&str; 1], &str>(Some(["pending"])),
+                        )
+                        .required("status")
+                        .property("taskType", Object::with_type(SchemaType::String))
+                        .required("taskType")
+                        .property("description", Object::with_type(SchemaType::String)),
+                )
                 .item(
                     ObjectBuilder::new()
                         .property(
@@ -235,7 +349,7 @@ impl TaskStatus {
     #[allow(clippy::missing_panics_doc)]
     pub fn completed(&self, info: Arc<dyn TaskStatusInfo>) -> Self {
         Self::Completed {
-            task_type: self.task_type(),
+            task_type: self.task_type().to_string(),
             description: self.description(),
             info,
             time_total: self.time_total(),
@@ -253,6 +367,10 @@ impl TaskStatus {
         Self::Failed { error, clean_up }
     }
 
+    pub fn is_pending(&self) -> bool {
+        matches!(self, TaskStatus::Pending { .. })
+    }
+
     pub fn is_running(&self) -> bool {
         matches!(self, TaskStatus::Running(_))
     }
@@ -304,17 +422,21 @@ impl TaskStatus {
         }
     }
 
-    fn task_type(&self) -> &'static str {
+    fn task_type(&self) -> &str {
         match self {
-            TaskStatus::Completed { task_type, .. } => task_type,
-            TaskStatus::Running(info) => info.task_type,
+            TaskStatus::Pending { task_type, .. } | TaskStatus::Completed { task_type, .. } => {
+                task_type
+            }
+            TaskStatus::Running(info) => &info.task_type,
             _ => "",
         }
     }
 
     fn description(&self) -> Option<String> {
         match self {
-            TaskStatus::Completed { description, .. } => description.clone(),
+            TaskStatus::Pending { description, .. } | TaskStatus::Completed { description, .. } => {
+                description.clone()
+            }
             TaskStatus::Running(info) => info.description.clone(),
             _ => None,
         }
@@ -324,7 +446,7 @@ impl TaskStatus {
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct RunningTaskStatusInfo {
-    task_type: &'static str,
+    task_type: String,
     description: Option<String>,
     #[serde(serialize_with = "serialize_as_pct")]
     pct_complete: f64,
@@ -335,7 +457,7 @@ pub struct RunningTaskStatusInfo {
 
 impl RunningTaskStatusInfo {
     pub fn new(
-        task_type: &'static str,
+        task_type: String,
         description: Option<String>,
         pct_complete: f64,
         info: Box<dyn TaskStatusInfo>,
@@ -351,6 +473,29 @@ impl RunningTaskStatusInfo {
         })
     }
 
+    /// Restores the info of a task that was started at `time_started`, e.g., from a [`TaskStore`].
+    pub(crate) fn restore(
+        task_type: String,
+        description: Option<String>,
+        pct_complete: f64,
+        time_started: DateTime,
+        info: Box<dyn TaskStatusInfo>,
+    ) -> Arc<Self> {
+        let pct_complete = pct_complete.clamp(0., 1.);
+
+        let mut time_estimate = TimeEstimation::started_at(time_started);
+        time_estimate.update_now(pct_complete);
+
+        Arc::new(RunningTaskStatusInfo {
+            task_type,
+            description,
+            pct_complete,
+            time_started,
+            estimated_time_remaining: time_estimate,
+            info,
+        })
+    }
+
     pub fn update(&self, pct_complete: f64, info: Box<dyn TaskStatusInfo>) -> Arc<Self> {
         let pct_complete = pct_complete.clamp(0., 1.);
 
@@ -358,7 +503,7 @@ impl RunningTaskStatusInfo {
         time_estimate.update_now(pct_complete);
 
         Arc::new(RunningTaskStatusInfo {
-            task_type: self.task_type,
+            task_type: self.task_type.clone(),
             description: self.description.clone(),
             pct_complete,
             time_started: self.time_started,
@@ -402,6 +547,7 @@ erased_serde::serialize_trait_object!(TaskStatusInfo);
 
 impl TaskStatusInfo for () {}
 impl TaskStatusInfo for String {}
+impl TaskStatusInfo for serde_json::Value {}
 
 #[derive(Debug, Serialize, Deserialize, Clone, ToSchema, IntoParams, Validate)]
 pub struct TaskListOptions {
@@ -448,6 +594,7 @@ fn task_list_limit_default() -> u32 {
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub enum TaskFilter {
+    Pending,
     Running,
     Aborted,
     Failed,
@@ -0,0 +1,522 @@
+use super::{
+    RecurringTask, RecurringTaskId, RunningTaskStatusInfo, TaskCleanUpStatus, TaskFilter, TaskId,
+    TaskListOptions, TaskStatus, TaskStatusInfo, TaskStatusWithId, TaskStore,
+};
+use crate::error::Result;
+use async_trait::async_trait;
+use bb8_postgres::{
+    bb8::Pool,
+    tokio_postgres::{
+        tls::{MakeTlsConnect, TlsConnect},
+        Row, Socket,
+    },
+    PostgresConnectionManager,
+};
+use geoengine_datatypes::primitives::DateTime;
+use postgres_types::{FromSql, ToSql};
+use serde::Deserialize;
+use snafu::Snafu;
+use std::sync::Arc;
+
+/// A [`TaskStore`] that keeps the task statuses in the `tasks` table.
+pub struct PostgresTaskStore<Tls>
+where
+    Tls: MakeTlsConnect<Socket> + Clone + Send + Sync + 'static + std::fmt::Debug,
+    <Tls as MakeTlsConnect<Socket>>::Stream: Send + Sync,
+    <Tls as MakeTlsConnect<Socket>>::TlsConnect: Send,
+    <<Tls as MakeTlsConnect<Socket>>::TlsConnect as TlsConnect<Socket>>::Future: Send,
+{
+    conn_pool: Pool<PostgresConnectionManager<Tls>>,
+}
+
+impl<Tls> PostgresTaskStore<Tls>
+where
+    Tls: MakeTlsConnect<Socket> + Clone + Send + Sync + 'static + std::fmt::Debug,
+    <Tls as MakeTlsConnect<Socket>>::Stream: Send + Sync,
+    <Tls as MakeTlsConnect<Socket>>::TlsConnect: Send,
+    <<Tls as MakeTlsConnect<Socket>>::TlsConnect as TlsConnect<Socket>>::Future: Send,
+{
+    pub fn new(conn_pool: Pool<PostgresConnectionManager<Tls>>) -> Self {
+        Self { conn_pool }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ToSql, FromSql)]
+#[postgres(name = "TaskStatus")]
+enum TaskStatusDbType {
+    Pending,
+    Running,
+    Completed,
+    Aborted,
+    Failed,
+}
+
+impl From<TaskFilter> for TaskStatusDbType {
+    fn from(filter: TaskFilter) -> Self {
+        match filter {
+            TaskFilter::Pending => Self::Pending,
+            TaskFilter::Running => Self::Running,
+            TaskFilter::Completed => Self::Completed,
+            TaskFilter::Aborted => Self::Aborted,
+            TaskFilter::Failed => Self::Failed,
+        }
+    }
+}
+
+/// The status columns of the `tasks` table
+struct TaskStatusColumns {
+    status: TaskStatusDbType,
+    pct_complete: f64,
+    time_started: Option<DateTime>,
+    time_total: Option<String>,
+    info: Option<serde_json::Value>,
+    error: Option<String>,
+    clean_up: Option<serde_json::Value>,
+}
+
+impl TryFrom<&TaskStatus> for TaskStatusColumns {
+    type Error = crate::error::Error;
+
+    fn try_from(task_status: &TaskStatus) -> Result<Self> {
+        let mut columns = Self {
+            status: TaskStatusDbType::Pending,
+            pct_complete: 0.,
+            time_started: None,
+            time_total: None,
+            info: None,
+            error: None,
+            clean_up: None,
+        };
+
+        match task_status {
+            TaskStatus::Pending { .. } => {}
+            TaskStatus::Running(info) => {
+                columns.status = TaskStatusDbType::Running;
+                columns.pct_complete = info.pct_complete;
+                columns.time_started = Some(info.time_started);
+                columns.info = Some(serde_json::to_value(&*info.info)?);
+            }
+            TaskStatus::Completed {
+                info,
+                time_total,
+                time_started,
+                ..
+            } => {
+                columns.status = TaskStatusDbType::Completed;
+                columns.pct_complete = 1.;
+                columns.time_started = Some(*time_started);
+                columns.time_total = Some(time_total.clone());
+                columns.info = Some(serde_json::to_value(&**info)?);
+            }
+            TaskStatus::Aborted { clean_up } => {
+                columns.status = TaskStatusDbType::Aborted;
+                columns.clean_up = Some(serde_json::to_value(clean_up)?);
+            }
+            TaskStatus::Failed { error, clean_up } => {
+                columns.status = TaskStatusDbType::Failed;
+                columns.error = Some(error.to_string());
+                columns.clean_up = Some(serde_json::to_value(clean_up)?);
+            }
+        }
+
+        Ok(columns)
+    }
+}
+
+/// The error of a task that is restored from the store
+#[derive(Debug, Snafu)]
+#[snafu(display("{message}"))]
+struct StoredTaskError {
+    message: String,
+}
+
+/// The serialized form of a [`TaskCleanUpStatus`]
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase", tag = "status")]
+enum StoredTaskCleanUpStatus {
+    NoCleanUp,
+    #[serde(rename_all = "camelCase")]
+    Running {
+        time_started: DateTime,
+    },
+    Completed {
+        info: serde_json::Value,
+    },
+    Aborted {
+        info: serde_json::Value,
+    },
+    Failed {
+        error: String,
+    },
+}
+
+impl From<StoredTaskCleanUpStatus> for TaskCleanUpStatus {
+    fn from(clean_up: StoredTaskCleanUpStatus) -> Self {
+        match clean_up {
+            StoredTaskCleanUpStatus::NoCleanUp => Self::NoCleanUp,
+            StoredTaskCleanUpStatus::Running { time_started } => Self::Running(
+                RunningTaskStatusInfo::restore(String::new(), None, 0., time_started, ().boxed()),
+            ),
+            StoredTaskCleanUpStatus::Completed { info } => Self::Completed {
+                info: Arc::new(info.boxed()),
+            },
+            StoredTaskCleanUpStatus::Aborted { info } => Self::Aborted {
+                info: Arc::new(info.boxed()),
+            },
+            StoredTaskCleanUpStatus::Failed { error } => Self::Failed {
+                error: Arc::new(StoredTaskError { message: error }),
+            },
+        }
+    }
+}
+
+const TASK_STATUS_COLUMNS: &str = "
+    task_type,
+    description,
+    status,
+    pct_complete,
+    time_started,
+    time_total,
+    info,
+    error,
+    clean_up";
+
+fn task_status_from_row(row: &Row) -> Result<TaskStatus> {
+    let task_type: String = row.get("task_type");
+    let description: Option<String> = row.get("description");
+    let time_started = row
+        .get::<_, Option<DateTime>>("time_started")
+        .unwrap_or_else(DateTime::now);
+    let info = row
+        .get::<_, Option<serde_json::Value>>("info")
+        .map_or_else(|| ().boxed(), TaskStatusInfo::boxed);
+    let clean_up: TaskCleanUpStatus = row
+        .get::<_, Option<serde_json::Value>>("clean_up")
+        .map(serde_json::from_value::<StoredTaskCleanUpStatus>)
+        .transpose()?
+        .map_or(TaskCleanUpStatus::NoCleanUp, Into::into);
+
+    Ok(match row.get::<_, TaskStatusDbType>("status") {
+        TaskStatusDbType::Pending => TaskStatus::Pending {
+            task_type,
+            description,
+        },
+        TaskStatusDbType::Running => TaskStatus::Running(RunningTaskStatusInfo::restore(
+            task_type,
+            description,
+            row.get("pct_complete"),
+            time_started,
+            info,
+        )),
+        TaskStatusDbType::Completed => TaskStatus::Completed {
+            task_type,
+            description,
+            info: Arc::from(info),
+            time_total: row
+                .get::<_, Option<String>>("time_total")
+                .unwrap_or_default(),
+            time_started,
+        },
+        TaskStatusDbType::Aborted => TaskStatus::aborted(clean_up),
+        TaskStatusDbType::Failed => TaskStatus::failed(
+            Arc::new(StoredTaskError {
+                message: row.get::<_, Option<String>>("error").unwrap_or_default(),
+            }),
+            clean_up,
+        ),
+    })
+}
+
+#[async_trait]
+impl<Tls> TaskStore for PostgresTaskStore<Tls>
+where
+    Tls: MakeTlsConnect<Socket> + Clone + Send + Sync + 'static + std::fmt::Debug,
+    <Tls as MakeTlsConnect<Socket>>::Stream: Send + Sync,
+    <Tls as MakeTlsConnect<Socket>>::TlsConnect: Send,
+    <<Tls as MakeTlsConnect<Socket>>::TlsConnect as TlsConnect<Socket>>::Future: Send,
+{
+    async fn insert_task(
+        &self,
+        task_id: TaskId,
+        task_type: &str,
+        description: Option<&str>,
+        status: &TaskStatus,
+    ) -> Result<()> {
+        let columns = TaskStatusColumns::try_from(status)?;
+
+        let conn = self.conn_pool.get().await?;
+
+        let stmt = conn
+            .prepare(
+                "
+                INSERT INTO tasks (
+                    id,
+                    task_type,
+                    description,
+                    status,
+                    pct_complete,
+                    time_started,
+                    time_total,
+                    info,
+                    error,
+                    clean_up
+                ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10);",
+            )
+            .await?;
+
+        conn.execute(
+            &stmt,
+            &[
+                &task_id,
+                &task_type,
+                &description,
+                &columns.status,
+                &columns.pct_complete,
+                &columns.time_started,
+                &columns.time_total,
+                &columns.info,
+                &columns.error,
+                &columns.clean_up,
+            ],
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn update_task_status(&self, task_id: TaskId, status: &TaskStatus) -> Result<()> {
+        let columns = TaskStatusColumns::try_from(status)?;
+
+        let conn = self.conn_pool.get().await?;
+
+        let stmt = conn
+            .prepare(
+                "
+                UPDATE tasks SET
+                    status = $2,
+                    pct_complete = $3,
+                    time_started = $4,
+                    time_total = $5,
+                    info = $6,
+                    error = $7,
+                    clean_up = $8
+                WHERE id = $1;",
+            )
+            .await?;
+
+        conn.execute(
+            &stmt,
+            &[
+                &task_id,
+                &columns.status,
+                &columns.pct_complete,
+                &columns.time_started,
+                &columns.time_total,
+                &columns.info,
+                &columns.error,
+                &columns.clean_up,
+            ],
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn load_task_status(&self, task_id: TaskId) -> Result<Option<TaskStatus>> {
+        let conn = self.conn_pool.get().await?;
+
+        let stmt = conn
+            .prepare(&format!(
+                "SELECT {TASK_STATUS_COLUMNS} FROM tasks WHERE id = $1;"
+            ))
+            .await?;
+
+        conn.query_opt(&stmt, &[&task_id])
+            .await?
+            .as_ref()
+            .map(task_status_from_row)
+            .transpose()
+    }
+
+    async fn load_task_type(&self, task_id: TaskId) -> Result<Option<String>> {
+        let conn = self.conn_pool.get().await?;
+
+        let stmt = conn
+            .prepare("SELECT task_type FROM tasks WHERE id = $1;")
+            .await?;
+
+        let row = conn.query_opt(&stmt, &[&task_id]).await?;
+
+        Ok(row.map(|row| row.get(0)))
+    }
+
+    async fn list_task_statuses(
+        &self,
+        options: &TaskListOptions,
+        excluded_task_types: &[&str],
+    ) -> Result<Vec<TaskStatusWithId>> {
+        let conn = self.conn_pool.get().await?;
+
+        let stmt = conn
+            .prepare(&format!(
+                r#"
+                SELECT id, {TASK_STATUS_COLUMNS}
+                FROM tasks
+                WHERE
+                    ($1::"TaskStatus" IS NULL OR status = $1) AND
+                    NOT task_type = ANY($4::text[])
+                ORDER BY created DESC
+                OFFSET $2
+                LIMIT $3;"#
+            ))
+            .await?;
+
+        let rows = conn
+            .query(
+                &stmt,
+                &[
+                    &options.filter.map(TaskStatusDbType::from),
+                    &i64::from(options.offset),
+                    &i64::from(options.limit),
+                    &excluded_task_types,
+                ],
+            )
+            .await?;
+
+        rows.iter()
+            .map(|row| -> Result<TaskStatusWithId> {
+                Ok(TaskStatusWithId {
+                    task_id: row.get("id"),
+                    status: task_status_from_row(row)?,
+                })
+            })
+            .collect()
+    }
+
+    async fn fail_interrupted_tasks(&self) -> Result<u64> {
+        let interrupted = || StoredTaskError {
+            message: "The task was interrupted by a shutdown of the server".to_string(),
+        };
+
+        let no_clean_up = serde_json::to_value(TaskCleanUpStatus::NoCleanUp)?;
+        let failed_clean_up = serde_json::to_value(TaskCleanUpStatus::Failed {
+            error: Arc::new(interrupted()),
+        })?;
+
+        let mut conn = self.conn_pool.get().await?;
+        let tx = conn.build_transaction().start().await?;
+
+        // interrupted tasks cannot be cleaned up, since they are not serializable
+        let interrupted_tasks = tx
+            .execute(
+                r#"
+                UPDATE tasks SET
+                    status = 'Failed',
+                    error = $1,
+                    clean_up = $2
+                WHERE status IN ('Pending', 'Running');"#,
+                &[&interrupted().to_string(), &no_clean_up],
+            )
+            .await?;
+
+        tx.execute(
+            r#"
+            UPDATE tasks SET clean_up = $1
+            WHERE
+                status IN ('Aborted', 'Failed') AND
+                clean_up ->> 'status' = 'running';"#,
+            &[&failed_clean_up],
+        )
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(interrupted_tasks)
+    }
+
+    async fn delete_finished_tasks(&self, scheduled_before: DateTime) -> Result<u64> {
+        let conn = self.conn_pool.get().await?;
+
+        let stmt = conn
+            .prepare(
+                r#"
+                DELETE FROM tasks
+                WHERE
+                    created < $1 AND
+                    status IN ('Completed', 'Aborted', 'Failed') AND
+                    (clean_up IS NULL OR clean_up ->> 'status' <> 'running');"#,
+            )
+            .await?;
+
+        let deleted_tasks = conn.execute(&stmt, &[&scheduled_before]).await?;
+
+        Ok(deleted_tasks)
+    }
+
+    async fn insert_recurring_task(&self, recurring_task: &RecurringTask) -> Result<()> {
+        let conn = self.conn_pool.get().await?;
+
+        let stmt = conn
+            .prepare(
+                "
+                INSERT INTO recurring_tasks (
+                    id,
+                    owner,
+                    schedule,
+                    definition
+                ) VALUES ($1, $2, $3, $4);",
+            )
+            .await?;
+
+        conn.execute(
+            &stmt,
+            &[
+                &recurring_task.recurring_task_id,
+                &recurring_task.owner,
+                &recurring_task.schedule.to_string(),
+                &recurring_task.definition,
+            ],
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn list_recurring_tasks(&self) -> Result<Vec<RecurringTask>> {
+        let conn = self.conn_pool.get().await?;
+
+        let stmt = conn
+            .prepare(
+                "
+                SELECT id, owner, schedule, definition
+                FROM recurring_tasks
+                ORDER BY created;",
+            )
+            .await?;
+
+        let rows = conn.query(&stmt, &[]).await?;
+
+        rows.iter()
+            .map(|row| -> Result<RecurringTask> {
+                Ok(RecurringTask {
+                    recurring_task_id: row.get("id"),
+                    owner: row.get("owner"),
+                    schedule: row.get::<_, String>("schedule").parse()?,
+                    definition: row.get("definition"),
+                })
+            })
+            .collect()
+    }
+
+    async fn delete_recurring_task(&self, recurring_task_id: RecurringTaskId) -> Result<bool> {
+        let conn = self.conn_pool.get().await?;
+
+        let stmt = conn
+            .prepare("DELETE FROM recurring_tasks WHERE id = $1;")
+            .await?;
+
+        let deleted = conn.execute(&stmt, &[&recurring_task_id]).await?;
+
+        Ok(deleted > 0)
+    }
+}
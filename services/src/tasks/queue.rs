@@ -0,0 +1,345 @@
+use futures::channel::oneshot;
+use serde::{Deserialize, Serialize};
+use std::{
+    cmp::Reverse,
+    collections::{BTreeMap, HashMap},
+    sync::{Arc, Mutex},
+};
+use utoipa::ToSchema;
+
+/// The priority of a task. Pending tasks with a higher priority start first.
+#[derive(
+    Debug,
+    Default,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+    ToSchema,
+)]
+#[serde(rename_all = "camelCase")]
+pub enum TaskPriority {
+    Low,
+    #[default]
+    Normal,
+    High,
+}
+
+/// Limits for the number of concurrently running tasks. A limit of `0` means that there is no limit.
+#[derive(Debug, Default, Clone)]
+pub struct TaskLimits {
+    pub max_concurrent_tasks: usize,
+    pub max_concurrent_tasks_per_owner: usize,
+    pub max_concurrent_tasks_per_type: HashMap<String, usize>,
+}
+
+impl From<&crate::util::config::TaskManager> for TaskLimits {
+    fn from(config: &crate::util::config::TaskManager) -> Self {
+        Self {
+            max_concurrent_tasks: config.max_concurrent_tasks,
+            max_concurrent_tasks_per_owner: config.max_concurrent_tasks_per_user,
+            max_concurrent_tasks_per_type: config.max_concurrent_tasks_per_type.clone(),
+        }
+    }
+}
+
+/// Identifies which limits apply to a task
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TaskSlotKey {
+    pub owner: Option<String>,
+    pub task_type: String,
+}
+
+/// A queue that admits tasks as long as the [`TaskLimits`] allow it.
+///
+/// Further tasks are pending and start in the order of their priority when slots are released.
+/// Tasks with the same priority start in the order they were enqueued.
+#[derive(Debug, Clone, Default)]
+pub struct TaskQueue {
+    limits: Arc<TaskLimits>,
+    state: Arc<Mutex<TaskQueueState>>,
+}
+
+#[derive(Debug, Default)]
+struct TaskQueueState {
+    running: usize,
+    running_by_owner: HashMap<String, usize>,
+    running_by_type: HashMap<String, usize>,
+    pending: BTreeMap<(Reverse<TaskPriority>, u64), PendingTask>,
+    next_sequence_number: u64,
+}
+
+#[derive(Debug)]
+struct PendingTask {
+    key: TaskSlotKey,
+    start: oneshot::Sender<TaskSlot>,
+}
+
+/// The result of enqueueing a task
+pub enum TaskAdmission {
+    Started(TaskSlot),
+    /// The slot is sent when the task may start
+    Pending(oneshot::Receiver<TaskSlot>),
+}
+
+/// A slot of a running task. It is released when it is dropped.
+#[derive(Debug)]
+pub struct TaskSlot {
+    queue: TaskQueue,
+    key: TaskSlotKey,
+}
+
+impl Drop for TaskSlot {
+    fn drop(&mut self) {
+        self.queue.release(&self.key);
+    }
+}
+
+impl TaskQueue {
+    pub fn new(limits: TaskLimits) -> Self {
+        Self {
+            limits: Arc::new(limits),
+            state: Default::default(),
+        }
+    }
+
+    pub fn enqueue(&self, key: TaskSlotKey, priority: TaskPriority) -> TaskAdmission {
+        let mut state = self.state.lock().expect("the lock should not be poisoned");
+
+        // the pending tasks cannot start, since the queue is dispatched after each release,
+        // so a new task may only overtake them if their limits are reached
+        if state.can_start(&self.limits, &key) {
+            state.acquire(&key);
+            return TaskAdmission::Started(TaskSlot {
+                queue: self.clone(),
+                key,
+            });
+        }
+
+        let (start, started) = oneshot::channel();
+        let sequence_number = state.next_sequence_number;
+        state.next_sequence_number += 1;
+        state.pending.insert(
+            (Reverse(priority), sequence_number),
+            PendingTask { key, start },
+        );
+
+        TaskAdmission::Pending(started)
+    }
+
+    fn release(&self, key: &TaskSlotKey) {
+        let started_tasks = {
+            let mut state = self.state.lock().expect("the lock should not be poisoned");
+            state.release(key);
+            state.dispatch(self)
+        };
+
+        // send outside of the lock, since failing to send drops and thus releases the slot
+        for (start, slot) in started_tasks {
+            start.send(slot).unwrap_or_default();
+        }
+    }
+}
+
+impl TaskQueueState {
+    fn can_start(&self, limits: &TaskLimits, key: &TaskSlotKey) -> bool {
+        let below_limit = |running: usize, limit: usize| limit == 0 || running < limit;
+
+        let running_of_owner = key
+            .owner
+            .as_ref()
+            .and_then(|owner| self.running_by_owner.get(owner))
+            .copied()
+            .unwrap_or_default();
+        let running_of_type = self
+            .running_by_type
+            .get(&key.task_type)
+            .copied()
+            .unwrap_or_default();
+        let type_limit = limits
+            .max_concurrent_tasks_per_type
+            .get(&key.task_type)
+            .copied()
+            .unwrap_or_default();
+
+        below_limit(self.running, limits.max_concurrent_tasks)
+            && (key.owner.is_none()
+                || below_limit(running_of_owner, limits.max_concurrent_tasks_per_owner))
+            && below_limit(running_of_type, type_limit)
+    }
+
+    fn acquire(&mut self, key: &TaskSlotKey) {
+        self.running += 1;
+        if let Some(owner) = &key.owner {
+            *self.running_by_owner.entry(owner.clone()).or_default() += 1;
+        }
+        *self
+            .running_by_type
+            .entry(key.task_type.clone())
+            .or_default() += 1;
+    }
+
+    fn release(&mut self, key: &TaskSlotKey) {
+        self.running = self.running.saturating_sub(1);
+
+        if let Some(owner) = &key.owner {
+            decrement_count(&mut self.running_by_owner, owner);
+        }
+        decrement_count(&mut self.running_by_type, &key.task_type);
+    }
+
+    /// Acquires slots for all pending tasks that may start now, in the order of their priority.
+    fn dispatch(&mut self, queue: &TaskQueue) -> Vec<(oneshot::Sender<TaskSlot>, TaskSlot)> {
+        // aborted tasks do not wait for their start anymore
+        self.pending
+            .retain(|_, pending_task| !pending_task.start.is_canceled());
+
+        let startable_tasks = self
+            .pending
+            .iter()
+            .filter(|(_, pending_task)| self.can_start(&queue.limits, &pending_task.key))
+            .map(|(order, _)| *order)
+            .collect::<Vec<_>>();
+
+        let mut started_tasks = Vec::new();
+        for order in startable_tasks {
+            // starting a task may exhaust the limits of the following ones
+            let can_start = self.pending.get(&order).map_or(false, |pending_task| {
+                self.can_start(&queue.limits, &pending_task.key)
+            });
+            if !can_start {
+                continue;
+            }
+
+            let Some(PendingTask { key, start }) = self.pending.remove(&order) else {
+                continue;
+            };
+
+            self.acquire(&key);
+            started_tasks.push((
+                start,
+                TaskSlot {
+                    queue: queue.clone(),
+                    key,
+                },
+            ));
+        }
+
+        started_tasks
+    }
+}
+
+fn decrement_count(counts: &mut HashMap<String, usize>, key: &str) {
+    if let Some(count) = counts.get_mut(key) {
+        *count = count.saturating_sub(1);
+        if *count == 0 {
+            counts.remove(key);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(owner: &str, task_type: &str) -> TaskSlotKey {
+        TaskSlotKey {
+            owner: Some(owner.to_string()),
+            task_type: task_type.to_string(),
+        }
+    }
+
+    fn started(admission: TaskAdmission) -> TaskSlot {
+        match admission {
+            TaskAdmission::Started(slot) => slot,
+            TaskAdmission::Pending(_) => panic!("the task should have started"),
+        }
+    }
+
+    fn pending(admission: TaskAdmission) -> oneshot::Receiver<TaskSlot> {
+        match admission {
+            TaskAdmission::Started(_) => panic!("the task should be pending"),
+            TaskAdmission::Pending(started) => started,
+        }
+    }
+
+    #[test]
+    fn it_starts_pending_tasks_by_priority() {
+        let queue = TaskQueue::new(TaskLimits {
+            max_concurrent_tasks: 1,
+            ..Default::default()
+        });
+
+        let running = started(queue.enqueue(key("a", "t"), TaskPriority::Normal));
+        let mut low = pending(queue.enqueue(key("a", "t"), TaskPriority::Low));
+        let mut high = pending(queue.enqueue(key("b", "t"), TaskPriority::High));
+        let mut normal = pending(queue.enqueue(key("c", "t"), TaskPriority::Normal));
+
+        drop(running);
+        let high = high.try_recv().unwrap().unwrap();
+        assert!(normal.try_recv().unwrap().is_none());
+        assert!(low.try_recv().unwrap().is_none());
+
+        drop(high);
+        let normal = normal.try_recv().unwrap().unwrap();
+        assert!(low.try_recv().unwrap().is_none());
+
+        drop(normal);
+        assert!(low.try_recv().unwrap().is_some());
+    }
+
+    #[test]
+    fn it_limits_tasks_per_owner_and_type() {
+        let queue = TaskQueue::new(TaskLimits {
+            max_concurrent_tasks: 0,
+            max_concurrent_tasks_per_owner: 2,
+            max_concurrent_tasks_per_type: [("export".to_string(), 1)].into(),
+        });
+
+        let _a1 = started(queue.enqueue(key("a", "t"), TaskPriority::Normal));
+        let a2 = started(queue.enqueue(key("a", "t"), TaskPriority::Normal));
+        let mut a3 = pending(queue.enqueue(key("a", "t"), TaskPriority::High));
+
+        // other owners are not affected
+        let export = started(queue.enqueue(key("b", "export"), TaskPriority::Normal));
+        let mut export2 = pending(queue.enqueue(key("c", "export"), TaskPriority::Normal));
+
+        // tasks without owners are only affected by the global and the type limits
+        let _system = started(queue.enqueue(
+            TaskSlotKey {
+                owner: None,
+                task_type: "t".to_string(),
+            },
+            TaskPriority::Normal,
+        ));
+
+        drop(export);
+        assert!(export2.try_recv().unwrap().is_some());
+        assert!(a3.try_recv().unwrap().is_none());
+
+        drop(a2);
+        assert!(a3.try_recv().unwrap().is_some());
+    }
+
+    #[test]
+    fn it_skips_aborted_pending_tasks() {
+        let queue = TaskQueue::new(TaskLimits {
+            max_concurrent_tasks: 1,
+            ..Default::default()
+        });
+
+        let running = started(queue.enqueue(key("a", "t"), TaskPriority::Normal));
+        let aborted = pending(queue.enqueue(key("a", "t"), TaskPriority::Normal));
+        let mut waiting = pending(queue.enqueue(key("a", "t"), TaskPriority::Normal));
+
+        drop(aborted);
+        drop(running);
+
+        assert!(waiting.try_recv().unwrap().is_some());
+    }
+}
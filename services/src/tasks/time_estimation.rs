@@ -13,8 +13,12 @@ pub struct TimeEstimation {
 
 impl TimeEstimation {
     pub fn new() -> Self {
+        Self::started_at(DateTime::now())
+    }
+
+    pub fn started_at(initial_time: DateTime) -> Self {
         Self {
-            initial_time: DateTime::now(),
+            initial_time,
             estimate_seconds_per_pct: NumberStatistics::default(),
             time_estimate_seconds: (None, None),
         }
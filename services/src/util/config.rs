@@ -228,6 +228,18 @@ impl ConfigElement for LayerService {
 pub struct TaskManager {
     pub list_limit: u32,
     pub list_default_limit: u32,
+    /// The maximum number of tasks that run at once. Further tasks are pending.
+    /// `0` means that there is no limit.
+    pub max_concurrent_tasks: usize,
+    /// The maximum number of tasks of a single user that run at once.
+    /// `0` means that there is no limit.
+    pub max_concurrent_tasks_per_user: usize,
+    /// The maximum number of tasks of a task type that run at once.
+    #[serde(default)]
+    pub max_concurrent_tasks_per_type: HashMap<String, usize>,
+    /// The stored statuses of finished tasks are deleted after this time
+    pub finished_task_retention_seconds: u64,
+    pub finished_task_cleanup_interval_seconds: u64,
 }
 
 impl ConfigElement for TaskManager {
@@ -237,12 +249,27 @@ impl ConfigElement for TaskManager {
 #[derive(Debug, Deserialize)]
 pub struct Upload {
     pub path: PathBuf,
+    /// Resumable uploads expire if they are not continued within this time
+    pub resumable_upload_expiration_seconds: u64,
+    pub expired_upload_cleanup_interval_seconds: u64,
 }
 
 impl ConfigElement for Upload {
     const KEY: &'static str = "upload";
 }
 
+#[derive(Debug, Deserialize)]
+pub struct Export {
+    pub path: PathBuf,
+    /// Exports are deleted after this time
+    pub export_expiration_seconds: u64,
+    pub expired_export_cleanup_interval_seconds: u64,
+}
+
+impl ConfigElement for Export {
+    const KEY: &'static str = "export";
+}
+
 #[derive(Debug, Deserialize)]
 pub struct Logging {
     pub log_spec: String,
@@ -0,0 +1,882 @@
+use crate::api::handlers::workflows::workflow_provenance;
+use crate::api::model::datatypes::SpatialResolution;
+use crate::contexts::{Session, SessionContext};
+use crate::error::{self, Result};
+use crate::identifier;
+use crate::projects::STRectangle;
+use crate::tasks::{Task, TaskId, TaskManager, TaskPriority, TaskStatusInfo};
+use crate::util::config::{self, get_config_element};
+use crate::workflows::workflow::{Workflow, WorkflowId};
+use chrono::Utc;
+use gdal::raster::RasterCreationOption;
+use gdal::{Dataset, DriverManager, Metadata};
+use geoengine_datatypes::error::{BoxedResultExt, ErrorSource};
+use geoengine_datatypes::operations::reproject::reproject_query;
+use geoengine_datatypes::primitives::{
+    AxisAlignedRectangle, BandSelection, ColumnSelection, DateTime, RasterQueryRectangle,
+    SpatialPartition2D, TimeInstance, VectorQueryRectangle,
+};
+use geoengine_datatypes::spatial_reference::SpatialReference;
+use geoengine_operators::engine::{
+    CanonicOperatorName, ExecutionContext, InitializedRasterOperator, InitializedVectorOperator,
+    PlotOperator, RasterOperator, SingleRasterOrVectorSource, TypedOperator,
+    TypedPlotQueryProcessor, VectorOperator, WorkflowOperatorPath,
+};
+use geoengine_operators::processing::{
+    InitializedRasterReprojection, InitializedVectorReprojection, Reprojection, ReprojectionParams,
+};
+use geoengine_operators::util::input::RasterOrVectorOperator;
+use geoengine_operators::util::raster_stream_to_geotiff::{
+    raster_stream_to_multiband_geotiff_bytes, GdalCompressionNumThreads,
+    GdalGeoTiffDatasetMetadata, GdalGeoTiffOptions,
+};
+use geoengine_operators::util::vector_stream_to_ogr::{vector_stream_to_ogr, OgrVectorFormat};
+use geoengine_operators::{
+    call_on_generic_raster_processor_gdal_types, call_on_generic_vector_processor,
+};
+use serde::{Deserialize, Serialize};
+use snafu::{ensure, ResultExt, Snafu};
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::fs;
+use utoipa::ToSchema;
+use uuid::Uuid;
+use zip::write::FileOptions;
+use zip::ZipWriter;
+
+identifier!(ExportId);
+
+/// The name of the archive inside the directory of an export
+const ARCHIVE_FILE_NAME: &str = "export.zip";
+
+/// The name of the directory that holds the exported data until it is added to the archive
+const DATA_DIRECTORY_NAME: &str = "data";
+
+/// The name of the file inside the directory of an export that records its owner and expiry
+const MANIFEST_FILE_NAME: &str = "export.json";
+
+impl ExportId {
+    pub fn root_path(self) -> Result<PathBuf> {
+        let root = get_config_element::<config::Export>()?.path;
+        Ok(root.join(self.to_string()))
+    }
+
+    /// The path of the finished archive
+    pub fn archive_path(self) -> Result<PathBuf> {
+        Ok(self.root_path()?.join(ARCHIVE_FILE_NAME))
+    }
+
+    fn manifest_path(self) -> Result<PathBuf> {
+        Ok(self.root_path()?.join(MANIFEST_FILE_NAME))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ExportManifest {
+    /// The [`Session::owner`] that created the export
+    owner: Uuid,
+    expires: chrono::DateTime<Utc>,
+}
+
+impl ExportManifest {
+    /// Creates a manifest that expires after the configured time from now
+    fn new(owner: Uuid) -> Result<Self> {
+        let expiration = get_config_element::<config::Export>()?.export_expiration_seconds;
+
+        let expires = chrono::Duration::from_std(std::time::Duration::from_secs(expiration))
+            .ok()
+            .and_then(|expiration| Utc::now().checked_add_signed(expiration))
+            .ok_or(WorkflowExportError::InvalidExportExpiration {
+                seconds: expiration,
+            })?;
+
+        Ok(Self { owner, expires })
+    }
+}
+
+async fn read_manifest(export: ExportId) -> Result<ExportManifest> {
+    let manifest = fs::read(export.manifest_path()?)
+        .await
+        .map_err(|_| UnknownExport { export }.build())?;
+
+    Ok(serde_json::from_slice(&manifest)?)
+}
+
+/// Writes the manifest atomically, s.t. the cleanup never reads a partial manifest
+async fn store_manifest(export: ExportId, manifest: &ExportManifest) -> Result<()> {
+    let path = export.manifest_path()?;
+    let temporary_path = path.with_extension(format!("{}.tmp", Uuid::new_v4()));
+
+    fs::write(&temporary_path, serde_json::to_vec(manifest)?)
+        .await
+        .context(error::Io)?;
+    fs::rename(&temporary_path, &path)
+        .await
+        .context(error::Io)?;
+
+    Ok(())
+}
+
+/// Returns the path of the archive of an unexpired export, which only exists once the export is finished.
+/// Exports of other owners are treated as unknown.
+pub async fn export_archive_path(export: ExportId, owner: Uuid) -> Result<PathBuf> {
+    let manifest = read_manifest(export).await?;
+
+    ensure!(
+        manifest.owner == owner && manifest.expires > Utc::now(),
+        UnknownExport { export }
+    );
+
+    export.archive_path()
+}
+
+/// The file format of the exported data
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum ExportFormat {
+    /// A `GeoTIFF` with a band per raster band and time step, ordered by time
+    GeoTiff,
+    /// One CF-compliant `NetCDF` file per raster band with a time dimension
+    NetCdf,
+    GeoPackage,
+    /// A CSV file with the geometries as WKT
+    Csv,
+    GeoJson,
+    /// The plot data as JSON, e.g., a Vega chart
+    Json,
+    Png,
+}
+
+impl ExportFormat {
+    fn output_type(self) -> &'static str {
+        match self {
+            Self::GeoTiff | Self::NetCdf => "Raster",
+            Self::GeoPackage | Self::Csv | Self::GeoJson => "Vector",
+            Self::Json | Self::Png => "Plot",
+        }
+    }
+}
+
+/// parameter for the workflow export handler (body)
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize, ToSchema)]
+#[schema(example = json!({"bounds": {"spatialReference": "EPSG:4326", "boundingBox": {"lowerLeftCoordinate": {"x": -10.0, "y": 20.0}, "upperRightCoordinate": {"x": 50.0, "y": 80.0}}, "timeInterval": {"start": 1_388_534_400_000_i64, "end": 1_388_534_401_000_i64}}, "spatialResolution": {"x": 0.1, "y": 0.1}, "format": "geoTiff"}))]
+#[serde(rename_all = "camelCase")]
+pub struct WorkflowExport {
+    /// The bounds of the export. If no spatial reference is given, the one of the workflow is used.
+    pub bounds: STRectangle,
+    pub spatial_resolution: SpatialResolution,
+    /// Defaults to `GeoTiff` for rasters, `GeoPackage` for vectors and the output format of plots
+    pub format: Option<ExportFormat>,
+    /// Pending tasks with a higher priority start first
+    #[serde(default)]
+    pub priority: TaskPriority,
+}
+
+/// response of the workflow export task
+#[derive(Clone, Debug, Deserialize, Serialize, ToSchema)]
+pub struct WorkflowExportResult {
+    pub export: ExportId,
+}
+
+impl TaskStatusInfo for WorkflowExportResult {}
+
+#[derive(Debug, Snafu)]
+#[snafu(visibility(pub(crate)))]
+#[snafu(context(suffix(false)))] // disables default `Snafu` suffix
+pub enum WorkflowExportError {
+    #[snafu(display(
+        "The format {format:?} cannot be used for exporting a {output_type} workflow"
+    ))]
+    UnsupportedExportFormat {
+        format: ExportFormat,
+        output_type: &'static str,
+    },
+    #[snafu(display("The export {export} does not exist or is not finished yet"))]
+    UnknownExport { export: ExportId },
+    #[snafu(display("Adding the file `{file_name}` to the export archive failed: {source}"))]
+    CannotAddFileToExportArchive {
+        file_name: String,
+        source: Box<dyn ErrorSource>,
+    },
+    #[snafu(display("The expiration of exports of {seconds} seconds is too large"))]
+    InvalidExportExpiration { seconds: u64 },
+    #[snafu(display("Finishing the export archive failed: {source}"))]
+    CannotFinishExportArchive { source: zip::result::ZipError },
+}
+
+pub struct WorkflowExportTask<C: SessionContext> {
+    pub owner: Uuid,
+    pub workflow_id: WorkflowId,
+    pub workflow: Workflow,
+    pub ctx: Arc<C>,
+    pub request: WorkflowExport,
+    pub export: ExportId,
+    pub export_path: PathBuf,
+    pub compression_num_threads: GdalCompressionNumThreads,
+}
+
+impl<C: SessionContext> WorkflowExportTask<C> {
+    async fn process(&self) -> Result<WorkflowExportResult> {
+        let data_path = self.export_path.join(DATA_DIRECTORY_NAME);
+        fs::create_dir_all(&data_path).await.context(error::Io)?;
+
+        let data_files = match self.workflow.operator.clone() {
+            TypedOperator::Raster(operator) => self.export_raster(operator, &data_path).await?,
+            TypedOperator::Vector(operator) => self.export_vector(operator, &data_path).await?,
+            TypedOperator::Plot(operator) => self.export_plot(operator, &data_path).await?,
+        };
+
+        let provenance = workflow_provenance(&self.workflow, self.ctx.as_ref()).await?;
+
+        let workflow = serde_json::to_vec_pretty(&self.workflow)?;
+        let citation = serde_json::to_vec_pretty(&provenance)?;
+        let archive_path = self.export.archive_path()?;
+
+        // the archive must not be downloaded before it is complete
+        let partial_archive_path = archive_path.with_extension("zip.part");
+        let writer_archive_path = partial_archive_path.clone();
+        crate::util::spawn_blocking(move || {
+            write_archive(&writer_archive_path, &workflow, &citation, &data_files)
+        })
+        .await??;
+        fs::rename(&partial_archive_path, &archive_path)
+            .await
+            .context(error::Io)?;
+
+        fs::remove_dir_all(&data_path).await.context(error::Io)?;
+
+        // the archive can be downloaded for the whole expiration time, regardless of how long the export took
+        store_manifest(self.export, &ExportManifest::new(self.owner)?).await?;
+
+        Ok(WorkflowExportResult {
+            export: self.export,
+        })
+    }
+
+    async fn export_raster(
+        &self,
+        operator: Box<dyn RasterOperator>,
+        data_path: &Path,
+    ) -> Result<Vec<PathBuf>> {
+        let format = self.request.format.unwrap_or(ExportFormat::GeoTiff);
+
+        let execution_context = self.ctx.execution_context()?;
+        let tiling_specification = execution_context.tiling_specification();
+
+        let initialized = operator
+            .clone()
+            .initialize(WorkflowOperatorPath::initialize_root(), &execution_context)
+            .await
+            .context(error::Operator)?;
+
+        let workflow_spatial_ref: Option<SpatialReference> =
+            initialized.result_descriptor().spatial_reference.into();
+        let workflow_spatial_ref =
+            workflow_spatial_ref.ok_or(error::Error::InvalidSpatialReference)?;
+        let request_spatial_ref = self.request_spatial_reference(workflow_spatial_ref);
+
+        let initialized: Box<dyn InitializedRasterOperator> =
+            if request_spatial_ref == workflow_spatial_ref {
+                initialized
+            } else {
+                let params = ReprojectionParams {
+                    target_spatial_reference: request_spatial_ref,
+                };
+
+                let reprojection = Reprojection {
+                    params,
+                    sources: SingleRasterOrVectorSource {
+                        source: RasterOrVectorOperator::Raster(operator),
+                    },
+                };
+
+                Box::new(
+                    InitializedRasterReprojection::try_new_with_input(
+                        CanonicOperatorName::from(&reprojection),
+                        params,
+                        initialized,
+                        tiling_specification,
+                    )
+                    .context(error::Operator)?,
+                )
+            };
+
+        let bounding_box = self.request.bounds.bounding_box;
+        let spatial_bounds =
+            SpatialPartition2D::new(bounding_box.upper_left(), bounding_box.lower_right())?;
+
+        let num_bands = initialized.result_descriptor().bands.len();
+
+        let query_rect = |attributes| RasterQueryRectangle {
+            spatial_bounds,
+            time_interval: self.request.bounds.time_interval,
+            spatial_resolution: self.request.spatial_resolution.into(),
+            attributes,
+        };
+
+        match format {
+            ExportFormat::GeoTiff => {
+                let geotiff = self
+                    .raster_to_geotiff(
+                        initialized.as_ref(),
+                        query_rect(BandSelection::first_n(num_bands as u32)),
+                        request_spatial_ref,
+                    )
+                    .await?;
+
+                let file_path = data_path.join("raster.tif");
+                fs::write(&file_path, geotiff).await.context(error::Io)?;
+
+                Ok(vec![file_path])
+            }
+            ExportFormat::NetCdf => {
+                let mut data_files = Vec::with_capacity(num_bands);
+
+                // the bands of the `GeoTIFF` become the time dimension, so each raster band is a separate variable
+                for (band_index, band) in initialized.result_descriptor().bands.iter().enumerate() {
+                    let geotiff = self
+                        .raster_to_geotiff(
+                            initialized.as_ref(),
+                            query_rect(BandSelection::new_single(band_index as u32)),
+                            request_spatial_ref,
+                        )
+                        .await?;
+
+                    let variable_name = sanitize_file_name(&band.name);
+                    let file_path = data_path.join(format!("{band_index}_{variable_name}.nc"));
+                    let netcdf_path = file_path.clone();
+                    crate::util::spawn_blocking(move || {
+                        geotiff_to_netcdf(geotiff, &netcdf_path, &variable_name)
+                    })
+                    .await??;
+
+                    data_files.push(file_path);
+                }
+
+                Ok(data_files)
+            }
+            _ => Err(WorkflowExportError::UnsupportedExportFormat {
+                format,
+                output_type: "Raster",
+            }
+            .into()),
+        }
+    }
+
+    /// Writes the selected bands of the raster into a `GeoTIFF` with a band per raster band and time step
+    async fn raster_to_geotiff(
+        &self,
+        initialized: &dyn InitializedRasterOperator,
+        query_rect: RasterQueryRectangle,
+        spatial_reference: SpatialReference,
+    ) -> Result<Vec<u8>> {
+        let tiling_specification = self.ctx.execution_context()?.tiling_specification();
+        let processor = initialized.query_processor().context(error::Operator)?;
+        let query_ctx = self.ctx.query_context()?;
+
+        let (geotiff, _cache_hint) = call_on_generic_raster_processor_gdal_types!(processor, p =>
+            raster_stream_to_multiband_geotiff_bytes(
+                p,
+                query_rect,
+                query_ctx,
+                GdalGeoTiffDatasetMetadata {
+                    no_data_value: None,
+                    spatial_reference,
+                },
+                GdalGeoTiffOptions {
+                    compression_num_threads: self.compression_num_threads,
+                    as_cog: false,
+                    force_big_tiff: false,
+                },
+                None,
+                Box::pin(futures::future::pending()), // exports shall continue in the background and not be cancelled
+                tiling_specification,
+            ).await)?
+        .map_err(error::Error::from)?;
+
+        Ok(geotiff)
+    }
+
+    async fn export_vector(
+        &self,
+        operator: Box<dyn VectorOperator>,
+        data_path: &Path,
+    ) -> Result<Vec<PathBuf>> {
+        let format = match self.request.format.unwrap_or(ExportFormat::GeoPackage) {
+            ExportFormat::GeoPackage => OgrVectorFormat::GeoPackage,
+            ExportFormat::Csv => OgrVectorFormat::Csv,
+            ExportFormat::GeoJson => OgrVectorFormat::GeoJson,
+            format => {
+                return Err(WorkflowExportError::UnsupportedExportFormat {
+                    format,
+                    output_type: "Vector",
+                }
+                .into())
+            }
+        };
+
+        let execution_context = self.ctx.execution_context()?;
+
+        let initialized = operator
+            .clone()
+            .initialize(WorkflowOperatorPath::initialize_root(), &execution_context)
+            .await
+            .context(error::Operator)?;
+
+        let workflow_spatial_ref: Option<SpatialReference> =
+            initialized.result_descriptor().spatial_reference.into();
+        let workflow_spatial_ref =
+            workflow_spatial_ref.ok_or(error::Error::InvalidSpatialReference)?;
+        let request_spatial_ref = self.request_spatial_reference(workflow_spatial_ref);
+
+        let initialized: Box<dyn InitializedVectorOperator> =
+            if request_spatial_ref == workflow_spatial_ref {
+                initialized
+            } else {
+                let params = ReprojectionParams {
+                    target_spatial_reference: request_spatial_ref,
+                };
+
+                let reprojection = Reprojection {
+                    params,
+                    sources: SingleRasterOrVectorSource {
+                        source: RasterOrVectorOperator::Vector(operator),
+                    },
+                };
+
+                Box::new(
+                    InitializedVectorReprojection::try_new_with_input(
+                        CanonicOperatorName::from(&reprojection),
+                        params,
+                        initialized,
+                    )
+                    .context(error::Operator)?,
+                )
+            };
+
+        let processor = initialized.query_processor().context(error::Operator)?;
+
+        let query_rect = VectorQueryRectangle {
+            spatial_bounds: self.request.bounds.bounding_box,
+            time_interval: self.request.bounds.time_interval,
+            spatial_resolution: self.request.spatial_resolution.into(),
+            attributes: ColumnSelection::all(),
+        };
+        let query_ctx = self.ctx.query_context()?;
+
+        let file_path = data_path.join(format!("features.{}", format.file_extension()));
+
+        call_on_generic_vector_processor!(processor, p => vector_stream_to_ogr(
+            &file_path,
+            p,
+            query_rect,
+            query_ctx,
+            format,
+            Box::pin(futures::future::pending()), // exports shall continue in the background and not be cancelled
+        ).await)?;
+
+        Ok(vec![file_path])
+    }
+
+    async fn export_plot(
+        &self,
+        operator: Box<dyn PlotOperator>,
+        data_path: &Path,
+    ) -> Result<Vec<PathBuf>> {
+        let execution_context = self.ctx.execution_context()?;
+
+        let initialized = operator
+            .initialize(WorkflowOperatorPath::initialize_root(), &execution_context)
+            .await
+            .context(error::Operator)?;
+
+        let workflow_spatial_ref: Option<SpatialReference> =
+            initialized.result_descriptor().spatial_reference.into();
+        let workflow_spatial_ref =
+            workflow_spatial_ref.ok_or(error::Error::InvalidSpatialReference)?;
+        let request_spatial_ref = self.request_spatial_reference(workflow_spatial_ref);
+
+        let query_rect = VectorQueryRectangle {
+            spatial_bounds: self.request.bounds.bounding_box,
+            time_interval: self.request.bounds.time_interval,
+            spatial_resolution: self.request.spatial_resolution.into(),
+            attributes: ColumnSelection::all(),
+        };
+
+        // plots cannot be reprojected, so the query is reprojected instead
+        let query_rect = if request_spatial_ref == workflow_spatial_ref {
+            Some(query_rect)
+        } else {
+            reproject_query(query_rect, workflow_spatial_ref, request_spatial_ref)
+                .map_err(From::from)
+                .context(error::Operator)?
+        };
+
+        let Some(query_rect) = query_rect else {
+            return Err(error::Error::UnresolvableQueryBoundingBox2DInSrs {
+                query_bbox: self.request.bounds.bounding_box.into(),
+                query_srs: workflow_spatial_ref.into(),
+            });
+        };
+
+        let processor = initialized.query_processor().context(error::Operator)?;
+        let query_ctx = self.ctx.query_context()?;
+
+        let (file_name, data) = match (processor, self.request.format) {
+            (TypedPlotQueryProcessor::JsonPlain(processor), None | Some(ExportFormat::Json)) => {
+                let json = processor
+                    .plot_query(query_rect.into(), &query_ctx)
+                    .await
+                    .context(error::Operator)?;
+
+                ("plot.json", serde_json::to_vec_pretty(&json)?)
+            }
+            (TypedPlotQueryProcessor::JsonVega(processor), None | Some(ExportFormat::Json)) => {
+                let chart = processor
+                    .plot_query(query_rect.into(), &query_ctx)
+                    .await
+                    .context(error::Operator)?;
+
+                ("plot.json", serde_json::to_vec_pretty(&chart)?)
+            }
+            (TypedPlotQueryProcessor::ImagePng(processor), None | Some(ExportFormat::Png)) => {
+                let png_bytes = processor
+                    .plot_query(query_rect.into(), &query_ctx)
+                    .await
+                    .context(error::Operator)?;
+
+                ("plot.png", png_bytes)
+            }
+            (_, Some(format)) => {
+                return Err(WorkflowExportError::UnsupportedExportFormat {
+                    format,
+                    output_type: "Plot",
+                }
+                .into())
+            }
+        };
+
+        let file_path = data_path.join(file_name);
+        fs::write(&file_path, data).await.context(error::Io)?;
+
+        Ok(vec![file_path])
+    }
+
+    fn request_spatial_reference(
+        &self,
+        workflow_spatial_ref: SpatialReference,
+    ) -> SpatialReference {
+        Option::<SpatialReference>::from(self.request.bounds.spatial_reference)
+            .unwrap_or(workflow_spatial_ref)
+    }
+}
+
+#[async_trait::async_trait]
+impl<C: SessionContext> Task<C::TaskContext> for WorkflowExportTask<C> {
+    async fn run(
+        &self,
+        _ctx: C::TaskContext,
+    ) -> Result<Box<dyn crate::tasks::TaskStatusInfo>, Box<dyn ErrorSource>> {
+        let response = self.process().await;
+
+        response
+            .map(TaskStatusInfo::boxed)
+            .map_err(ErrorSource::boxed)
+    }
+
+    async fn cleanup_on_error(&self, _ctx: C::TaskContext) -> Result<(), Box<dyn ErrorSource>> {
+        fs::remove_dir_all(&self.export_path)
+            .await
+            .context(error::Io)
+            .map_err(ErrorSource::boxed)?;
+
+        Ok(())
+    }
+
+    fn task_type(&self) -> &'static str {
+        "workflow-export"
+    }
+
+    fn task_unique_id(&self) -> Option<String> {
+        Some(self.export.to_string())
+    }
+
+    fn task_priority(&self) -> TaskPriority {
+        self.request.priority
+    }
+
+    fn task_description(&self) -> String {
+        format!("Exporting workflow {}", self.workflow_id)
+    }
+}
+
+pub async fn schedule_workflow_export_task<C: SessionContext>(
+    workflow_id: WorkflowId,
+    workflow: Workflow,
+    ctx: Arc<C>,
+    request: WorkflowExport,
+    compression_num_threads: GdalCompressionNumThreads,
+) -> Result<TaskId> {
+    // fail early instead of failing the task
+    if let Some(format) = request.format {
+        let output_type = match workflow.operator {
+            TypedOperator::Raster(_) => "Raster",
+            TypedOperator::Vector(_) => "Vector",
+            TypedOperator::Plot(_) => "Plot",
+        };
+
+        ensure!(
+            format.output_type() == output_type,
+            UnsupportedExportFormat {
+                format,
+                output_type
+            }
+        );
+    }
+
+    let owner = ctx.session().owner();
+
+    let export = ExportId::new();
+    let export_path = export.root_path()?;
+    fs::create_dir_all(&export_path).await.context(error::Io)?;
+
+    // unfinished exports also expire, e.g., if they were interrupted by a restart
+    store_manifest(export, &ExportManifest::new(owner)?).await?;
+
+    let task = WorkflowExportTask {
+        owner,
+        workflow_id,
+        workflow,
+        ctx: ctx.clone(),
+        request,
+        export,
+        export_path,
+        compression_num_threads,
+    }
+    .boxed();
+
+    let task_id = ctx.tasks().schedule_task(task, None).await?;
+
+    Ok(task_id)
+}
+
+/// Deletes all exports whose expiry has passed and returns their number
+pub async fn delete_expired_exports() -> Result<usize> {
+    let root = get_config_element::<config::Export>()?.path;
+    if !fs::try_exists(&root).await.context(error::Io)? {
+        return Ok(0);
+    }
+
+    let mut deleted = 0;
+    let mut entries = fs::read_dir(&root).await.context(error::Io)?;
+    while let Some(entry) = entries.next_entry().await.context(error::Io)? {
+        let Ok(export) = entry.file_name().to_string_lossy().parse::<ExportId>() else {
+            continue;
+        };
+
+        // manifests that cannot be read may be of exports that were just scheduled
+        let Ok(manifest) = read_manifest(export).await else {
+            continue;
+        };
+
+        if manifest.expires <= Utc::now() {
+            fs::remove_dir_all(entry.path()).await.context(error::Io)?;
+            deleted += 1;
+        }
+    }
+
+    Ok(deleted)
+}
+
+/// Periodically deletes expired exports in the background
+pub fn spawn_expired_export_cleanup() -> Result<()> {
+    let interval = get_config_element::<config::Export>()?.expired_export_cleanup_interval_seconds;
+
+    crate::util::spawn(async move {
+        let mut interval = tokio::time::interval(std::time::Duration::from_secs(interval));
+
+        loop {
+            interval.tick().await;
+
+            match delete_expired_exports().await {
+                Ok(0) => {}
+                Ok(deleted) => log::info!("Deleted {deleted} expired exports"),
+                Err(error) => log::warn!("Deleting expired exports failed: {error}"),
+            }
+        }
+    });
+
+    Ok(())
+}
+
+/// Writes the workflow, its provenance and the exported data into a ZIP archive
+fn write_archive(
+    archive_path: &Path,
+    workflow: &[u8],
+    citation: &[u8],
+    data_files: &[PathBuf],
+) -> Result<()> {
+    let archive = File::create(archive_path).context(error::Io)?;
+
+    let zip_options = FileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+    let mut zip_writer = ZipWriter::new(archive);
+
+    for (file_name, content) in [("workflow.json", workflow), ("citation.json", citation)] {
+        zip_writer
+            .start_file(file_name, zip_options)
+            .boxed_context(CannotAddFileToExportArchive { file_name })?;
+        zip_writer
+            .write_all(content)
+            .boxed_context(CannotAddFileToExportArchive { file_name })?;
+    }
+
+    // rasters may exceed the size limits of regular ZIP archives
+    let data_options = zip_options.large_file(true);
+
+    for data_file in data_files {
+        let file_name = data_file
+            .file_name()
+            .map(|file_name| file_name.to_string_lossy().to_string())
+            .ok_or(error::Error::PathIsNotAFile)?;
+
+        zip_writer
+            .start_file(file_name.as_str(), data_options)
+            .boxed_context(CannotAddFileToExportArchive {
+                file_name: file_name.as_str(),
+            })?;
+
+        let mut data = File::open(data_file).context(error::Io)?;
+        std::io::copy(&mut data, &mut zip_writer).boxed_context(CannotAddFileToExportArchive {
+            file_name: file_name.as_str(),
+        })?;
+    }
+
+    zip_writer.finish().context(CannotFinishExportArchive)?;
+
+    Ok(())
+}
+
+/// Converts a `GeoTIFF` with a band per time step into a CF-compliant `NetCDF` file with a time dimension.
+///
+/// The time of each band is taken from the metadata that `raster_stream_to_multiband_geotiff_bytes` writes.
+/// If any band has no time, the bands are written as separate variables without a time dimension.
+fn geotiff_to_netcdf(mut geotiff: Vec<u8>, file_path: &Path, variable_name: &str) -> Result<()> {
+    let mem_file_path = PathBuf::from(format!("/vsimem/{}.tif", uuid::Uuid::new_v4()));
+    let _mem_file = gdal::vsi::create_mem_file_from_ref(&mem_file_path, geotiff.as_mut_slice())?;
+    let geotiff_dataset = Dataset::open(&mem_file_path)?;
+
+    let time_steps = (1..=geotiff_dataset.raster_count())
+        .map(|band_index| {
+            let band = geotiff_dataset.rasterband(band_index).ok()?;
+            let start = band.metadata_item("start", "time")?;
+            let start = DateTime::parse_from_rfc3339(&start).ok()?;
+            Some(TimeInstance::from(start).inner())
+        })
+        .collect::<Option<Vec<_>>>();
+
+    // the metadata for the netCDF driver is set on an in-memory copy to leave the `GeoTIFF` untouched
+    let mem_driver = DriverManager::get_driver_by_name("MEM")?;
+    let mut dataset = geotiff_dataset.create_copy(&mem_driver, "", &[])?;
+    drop(geotiff_dataset);
+
+    if let Some(time_steps) = time_steps {
+        let time_values = time_steps
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(",");
+
+        dataset.set_metadata_item("NETCDF_DIM_EXTRA", "{time}", "")?;
+        // type 10 is a 64-bit integer
+        dataset.set_metadata_item(
+            "NETCDF_DIM_time_DEF",
+            &format!("{{{},10}}", time_steps.len()),
+            "",
+        )?;
+        dataset.set_metadata_item("NETCDF_DIM_time_VALUES", &format!("{{{time_values}}}"), "")?;
+        dataset.set_metadata_item("time#standard_name", "time", "")?;
+        dataset.set_metadata_item("time#axis", "T", "")?;
+        dataset.set_metadata_item("time#calendar", "standard", "")?;
+        dataset.set_metadata_item("time#units", "milliseconds since 1970-01-01 00:00:00", "")?;
+
+        for (band_index, time_step) in (1..).zip(&time_steps) {
+            let mut band = dataset.rasterband(band_index)?;
+            band.set_metadata_item("NETCDF_VARNAME", variable_name, "")?;
+            band.set_metadata_item("NETCDF_DIM_time", &time_step.to_string(), "")?;
+        }
+    }
+
+    let netcdf_driver = DriverManager::get_driver_by_name("netCDF")?;
+    dataset.create_copy(
+        &netcdf_driver,
+        file_path,
+        &[
+            RasterCreationOption {
+                key: "FORMAT",
+                value: "NC4",
+            },
+            RasterCreationOption {
+                key: "COMPRESS",
+                value: "DEFLATE",
+            },
+        ],
+    )?;
+
+    Ok(())
+}
+
+/// Replaces all characters that may not be safe in file or variable names
+fn sanitize_file_name(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn it_restricts_exports_to_their_owner_until_they_expire() {
+        let owner = Uuid::new_v4();
+        let export = ExportId::new();
+        let root = export.root_path().unwrap();
+
+        fs::create_dir_all(&root).await.unwrap();
+        store_manifest(export, &ExportManifest::new(owner).unwrap())
+            .await
+            .unwrap();
+
+        assert_eq!(
+            export_archive_path(export, owner).await.unwrap(),
+            export.archive_path().unwrap()
+        );
+        assert!(export_archive_path(export, Uuid::new_v4()).await.is_err());
+
+        delete_expired_exports().await.unwrap();
+        assert!(root.exists());
+
+        store_manifest(
+            export,
+            &ExportManifest {
+                owner,
+                expires: Utc::now() - chrono::Duration::seconds(1),
+            },
+        )
+        .await
+        .unwrap();
+
+        assert!(export_archive_path(export, owner).await.is_err());
+
+        delete_expired_exports().await.unwrap();
+        assert!(!root.exists());
+    }
+}
@@ -1,3 +1,4 @@
+pub mod export;
 mod postgres_workflow_registry;
 mod raster_stream;
 pub mod registry;